@@ -0,0 +1,101 @@
+//! Command recording micro-benchmark.
+//!
+//! Records the same stream of dynamic state commands three ways:
+//!
+//! * through `Device::cmd_*`, which looks each function up through the
+//!   device's shared loader,
+//! * through a `DeviceCommands` table copied out of the device, and
+//! * through `CommandBuffer`, which stores such a table inline.
+//!
+//! The last two should cost the same and no more than the first. Run with
+//! `cargo bench --bench recording`, and again with `--features validation` to
+//! include the cost of command buffer state tracking.
+//!
+//! Requires a Vulkan implementation; exits without measuring anything if
+//! none is available.
+
+extern crate voodoo;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::time::{Duration, Instant};
+use voodoo::{CommandPool, CommandPoolCreateFlags, CommandBuffer, CommandBufferLevel,
+    CommandBufferUsageFlags, CommandBufferResetFlags, Device, Viewport, Rect2d, Offset2d,
+    Extent2d};
+
+
+/// Number of viewport/scissor command pairs recorded per round.
+const COMMANDS_PER_ROUND: u32 = 50_000;
+
+/// Number of rounds measured for each method.
+const ROUNDS: u32 = 20;
+
+
+/// Records one round with `record`, returning the time spent recording.
+fn round<F>(command_buffer: &CommandBuffer, record: F) -> Duration
+        where F: Fn(&CommandBuffer) {
+    command_buffer.reset(CommandBufferResetFlags::RELEASE_RESOURCES).unwrap();
+    command_buffer.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT).unwrap();
+    let start = Instant::now();
+    for _ in 0..COMMANDS_PER_ROUND {
+        record(command_buffer);
+    }
+    let elapsed = start.elapsed();
+    command_buffer.end().unwrap();
+    elapsed
+}
+
+/// Runs `ROUNDS` rounds (after a warm-up round) and prints the fastest, in
+/// nanoseconds per command.
+fn bench<F>(name: &str, command_buffer: &CommandBuffer, record: F)
+        where F: Fn(&CommandBuffer) {
+    round(command_buffer, &record);
+    let best = (0..ROUNDS).map(|_| round(command_buffer, &record)).min().unwrap();
+    let nanos = best.as_secs() as f64 * 1e9 + best.subsec_nanos() as f64;
+    println!("{:<20} {:>8.2} ns/command", name, nanos / (COMMANDS_PER_ROUND * 2) as f64);
+}
+
+fn main() {
+    let (device, family_index): (Device, u32) = match common::device() {
+        Some(d) => d,
+        None => {
+            println!("No Vulkan implementation available; skipping.");
+            return;
+        },
+    };
+    let command_pool = CommandPool::builder()
+        .flags(CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+        .queue_family_index(family_index)
+        .build(device.clone()).unwrap();
+    let command_buffer = command_pool.allocate_command_buffer(CommandBufferLevel::Primary).unwrap();
+
+    let viewports = [Viewport::builder()
+        .x(0.0)
+        .y(0.0)
+        .width(1024.0)
+        .height(768.0)
+        .min_depth(0.0)
+        .max_depth(1.0)
+        .build()];
+    let scissors = [Rect2d::builder()
+        .offset(Offset2d::builder().x(0).y(0).build())
+        .extent(Extent2d::builder().width(1024).height(768).build())
+        .build()];
+
+    bench("Device::cmd_*", &command_buffer, |cb| unsafe {
+        device.cmd_set_viewport(cb.handle(), 0, &viewports);
+        device.cmd_set_scissor(cb.handle(), 0, &scissors);
+    });
+
+    let commands = device.commands();
+    bench("DeviceCommands", &command_buffer, |cb| unsafe {
+        commands.cmd_set_viewport(cb.handle(), 0, &viewports);
+        commands.cmd_set_scissor(cb.handle(), 0, &scissors);
+    });
+
+    bench("CommandBuffer", &command_buffer, |cb| {
+        cb.set_viewport(0, &viewports);
+        cb.set_scissor(0, &scissors);
+    });
+}
@@ -0,0 +1,165 @@
+//! Doubles the contents of a storage buffer with a compute shader and reads
+//! the result back, without creating a window or surface.
+
+extern crate voodoo;
+
+use std::mem;
+use std::ffi::CString;
+use voodoo::shaderc::ShaderKind;
+use voodoo::{Result as VdResult, Instance, ApplicationInfo, Loader, PhysicalDevice, Device,
+    DeviceQueueCreateInfo, QueueFlags, CommandPool, ShaderModule, GlslOptions, PipelineLayout,
+    ShaderStage, ShaderStageFlags, ComputePipeline, Buffer, BufferUsageFlags, SharingMode,
+    DeviceMemory, MemoryPropertyFlags, MemoryMapFlags, DescriptorSetLayout,
+    DescriptorSetLayoutBinding, DescriptorType, DescriptorPool, PipelineBindPoint,
+    BufferMemoryBarrier, AccessFlags, PipelineStageFlags, DependencyFlags, one_time_commands};
+
+
+static DOUBLE_SHADER: &str = r#"
+    #version 450
+
+    layout(local_size_x = 64) in;
+
+    layout(set = 0, binding = 0) buffer Values {
+        uint values[];
+    };
+
+    void main() {
+        uint idx = gl_GlobalInvocationID.x;
+        if (idx < values.length()) {
+            values[idx] *= 2;
+        }
+    }
+"#;
+
+const LOCAL_SIZE: u32 = 64;
+const VALUE_COUNT: u32 = 1000;
+
+
+fn init_instance() -> VdResult<Instance> {
+    let app_name = CString::new("Compute Double")?;
+
+    let app_info = ApplicationInfo::builder()
+        .application_name(&app_name)
+        .application_version((1, 0, 0))
+        .api_version((1, 0, 0))
+        .build();
+
+    let loader = Loader::new()?;
+
+    Instance::builder()
+        .application_info(&app_info)
+        .build(loader)
+}
+
+/// Returns the first physical device with a compute queue family, along with
+/// the index of that family.
+fn choose_physical_device(instance: &Instance) -> VdResult<(PhysicalDevice, u32)> {
+    for device in instance.physical_devices()? {
+        let queue_families = device.queue_family_properties()?;
+        for (idx, family) in queue_families.iter().enumerate() {
+            if family.queue_flags().contains(QueueFlags::COMPUTE) {
+                return Ok((device, idx as u32));
+            }
+        }
+    }
+    panic!("Failed to find a device with a compute queue.");
+}
+
+fn main() {
+    let instance = init_instance().unwrap();
+    let (physical_device, queue_family_index) = choose_physical_device(&instance).unwrap();
+
+    let queue_priorities = [1.0];
+    let queue_create_infos = [DeviceQueueCreateInfo::builder()
+        .queue_family_index(queue_family_index)
+        .queue_priorities(&queue_priorities)
+        .build()];
+    let device = Device::builder()
+        .queue_create_infos(&queue_create_infos)
+        .build(physical_device)
+        .unwrap();
+    let queue = device.queue(0).unwrap().clone();
+
+    // Storage buffer, mapped and filled with `0..VALUE_COUNT`:
+    let buffer_bytes = (VALUE_COUNT as usize * mem::size_of::<u32>()) as u64;
+    let buffer = Buffer::builder()
+        .size(buffer_bytes)
+        .usage(BufferUsageFlags::STORAGE_BUFFER)
+        .sharing_mode(SharingMode::Exclusive)
+        .build(device.clone())
+        .unwrap();
+    let (memory, _) = DeviceMemory::allocate_and_bind_buffers(device.clone(), &[&buffer],
+        MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT).unwrap();
+
+    let input: Vec<u32> = (0..VALUE_COUNT).collect();
+    let mut data = unsafe { memory.map(0, buffer_bytes, MemoryMapFlags::empty()).unwrap() };
+    data.copy_from_slice(&input);
+    memory.unmap(data);
+
+    // Descriptors:
+    let bindings = [DescriptorSetLayoutBinding::builder()
+        .binding(0)
+        .descriptor_type(DescriptorType::StorageBuffer)
+        .descriptor_count(1)
+        .stage_flags(ShaderStageFlags::COMPUTE)
+        .build()];
+    let set_layout = DescriptorSetLayout::builder()
+        .bindings(&bindings)
+        .build(device.clone())
+        .unwrap();
+    let descriptor_pool = DescriptorPool::builder()
+        .for_layouts(&[(&set_layout, 1)])
+        .build(device.clone())
+        .unwrap();
+    let descriptor_set = descriptor_pool.allocate_descriptor_set(&set_layout).unwrap();
+    descriptor_set.write_buffer(0, &buffer, 0, None, DescriptorType::StorageBuffer);
+
+    // Pipeline:
+    let shader_module = ShaderModule::from_glsl(device.clone(), DOUBLE_SHADER,
+        ShaderKind::Compute, "main", &GlslOptions::default()).unwrap();
+    let set_layouts = [set_layout.handle()];
+    let pipeline_layout = PipelineLayout::builder()
+        .set_layouts(&set_layouts)
+        .build(device.clone())
+        .unwrap();
+    let stage = ShaderStage::builder()
+        .stage(ShaderStageFlags::COMPUTE)
+        .module(&shader_module)
+        .build()
+        .unwrap();
+    let pipeline = ComputePipeline::builder()
+        .stage(&stage)
+        .layout(&pipeline_layout)
+        .build(device.clone())
+        .unwrap();
+
+    let command_pool = CommandPool::builder()
+        .queue_family_index(queue_family_index)
+        .build(device.clone())
+        .unwrap();
+
+    one_time_commands(&queue, &command_pool, |command_buffer| {
+        pipeline.bind(command_buffer);
+        command_buffer.bind_descriptor_sets(PipelineBindPoint::Compute, &pipeline_layout, 0,
+            &[&descriptor_set], &[]);
+        command_buffer.dispatch((VALUE_COUNT + LOCAL_SIZE - 1) / LOCAL_SIZE, 1, 1);
+
+        // Make the shader writes visible to the host:
+        let barrier = BufferMemoryBarrier::builder()
+            .src_access_mask(AccessFlags::SHADER_WRITE)
+            .dst_access_mask(AccessFlags::HOST_READ)
+            .buffer(&buffer)
+            .build();
+        command_buffer.pipeline_barrier(PipelineStageFlags::COMPUTE_SHADER,
+            PipelineStageFlags::HOST, DependencyFlags::empty(), &[], &[barrier], &[]);
+        Ok(())
+    }).unwrap();
+
+    let data = unsafe { memory.map::<u32>(0, buffer_bytes, MemoryMapFlags::empty()).unwrap() };
+    for (idx, (&result, &value)) in data.iter().zip(input.iter()).enumerate() {
+        assert_eq!(result, value * 2, "value {} was not doubled", idx);
+    }
+    memory.unmap(data);
+
+    println!("Doubled {} values.", VALUE_COUNT);
+}
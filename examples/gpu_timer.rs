@@ -0,0 +1,122 @@
+//! Times an empty compute dispatch without creating a window or surface.
+
+extern crate voodoo;
+
+use std::ffi::CString;
+use voodoo::{Result as VdResult, Instance, ApplicationInfo, Loader, PhysicalDevice, Device,
+    DeviceQueueCreateInfo, QueueFlags, CommandPool, CommandBufferLevel, CommandBufferUsageFlags,
+    CommandPoolCreateFlags, ShaderModule, PipelineLayout, ShaderStage,
+    ShaderStageFlags, ComputePipeline, PipelineStageFlags,
+    GpuTimer};
+
+
+/// `void main() {}` with a local size of 1 x 1 x 1.
+static EMPTY_COMPUTE_SPIRV: [u32; 35] = [
+    0x07230203, 0x00010000, 0x00000000, 0x00000005, 0x00000000,
+    // OpCapability Shader
+    0x00020011, 0x00000001,
+    // OpMemoryModel Logical GLSL450
+    0x0003000e, 0x00000000, 0x00000001,
+    // OpEntryPoint GLCompute %1 "main"
+    0x0005000f, 0x00000005, 0x00000001, 0x6e69616d, 0x00000000,
+    // OpExecutionMode %1 LocalSize 1 1 1
+    0x00060010, 0x00000001, 0x00000011, 0x00000001, 0x00000001, 0x00000001,
+    // %2 = OpTypeVoid
+    0x00020013, 0x00000002,
+    // %3 = OpTypeFunction %2
+    0x00030021, 0x00000003, 0x00000002,
+    // %1 = OpFunction %2 None %3
+    0x00050036, 0x00000002, 0x00000001, 0x00000000, 0x00000003,
+    // %4 = OpLabel
+    0x000200f8, 0x00000004,
+    // OpReturn
+    0x000100fd,
+    // OpFunctionEnd
+    0x00010038,
+];
+
+
+fn init_instance() -> VdResult<Instance> {
+    let app_name = CString::new("GPU Timer")?;
+
+    let app_info = ApplicationInfo::builder()
+        .application_name(&app_name)
+        .application_version((1, 0, 0))
+        .api_version((1, 0, 0))
+        .build();
+
+    let loader = Loader::new()?;
+
+    Instance::builder()
+        .application_info(&app_info)
+        .build(loader)
+}
+
+/// Returns the first physical device with a compute queue family which
+/// supports timestamps, along with the index of that family.
+fn choose_physical_device(instance: &Instance) -> VdResult<(PhysicalDevice, u32)> {
+    for device in instance.physical_devices()? {
+        let queue_families = device.queue_family_properties()?;
+        for (idx, family) in queue_families.iter().enumerate() {
+            if family.queue_flags().contains(QueueFlags::COMPUTE) &&
+                    family.timestamp_valid_bits() > 0 {
+                return Ok((device, idx as u32));
+            }
+        }
+    }
+    panic!("Failed to find a device with a compute queue supporting timestamps.");
+}
+
+fn main() {
+    let instance = init_instance().unwrap();
+    let (physical_device, queue_family_index) = choose_physical_device(&instance).unwrap();
+
+    let queue_priorities = [1.0];
+    let queue_create_infos = [DeviceQueueCreateInfo::builder()
+        .queue_family_index(queue_family_index)
+        .queue_priorities(&queue_priorities)
+        .build()];
+    let device = Device::builder()
+        .queue_create_infos(&queue_create_infos)
+        .build(physical_device)
+        .unwrap();
+    let queue = device.queue(0).unwrap().clone();
+
+    let shader_module = ShaderModule::new(device.clone(), &EMPTY_COMPUTE_SPIRV).unwrap();
+    let pipeline_layout = PipelineLayout::builder().build(device.clone()).unwrap();
+    let stage = ShaderStage::builder()
+        .stage(ShaderStageFlags::COMPUTE)
+        .module(&shader_module)
+        .build()
+        .unwrap();
+    let pipeline = ComputePipeline::builder()
+        .stage(&stage)
+        .layout(&pipeline_layout)
+        .build(device.clone())
+        .unwrap();
+
+    let command_pool = CommandPool::builder()
+        .queue_family_index(queue_family_index)
+        .flags(CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+        .build(device.clone())
+        .unwrap();
+    let command_buffer = command_pool.allocate_command_buffer(CommandBufferLevel::Primary).unwrap();
+
+    let timer = GpuTimer::new(device.clone(), queue_family_index).unwrap();
+
+    command_buffer.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT).unwrap();
+    timer.begin(&command_buffer, PipelineStageFlags::TOP_OF_PIPE);
+    pipeline.bind(&command_buffer);
+    command_buffer.dispatch(1024, 1, 1);
+    timer.end(&command_buffer, PipelineStageFlags::BOTTOM_OF_PIPE);
+    command_buffer.end().unwrap();
+
+    queue.submit_one(&[], command_buffer.handle(), &[], None).unwrap();
+    queue.wait_idle();
+
+    match timer.elapsed(&device).unwrap() {
+        Some(elapsed) => println!("Dispatch took {} ns.",
+            elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64),
+        None => println!("Timestamps unavailable."),
+    }
+}
@@ -2,7 +2,6 @@
 
 #[macro_use]
 extern crate voodoo as vd;
-extern crate voodoo_winit;
 extern crate cgmath;
 extern crate image;
 extern crate smallvec;
@@ -13,24 +12,25 @@ use std::mem;
 use std::time;
 use std::path::Path;
 use std::hash::{Hash, Hasher};
-use std::collections::{HashMap, BTreeSet};
-use std::ffi::{CStr, CString};
+use std::collections::HashMap;
+use std::ffi::CString;
 use std::cmp;
 use smallvec::SmallVec;
 use cgmath::{Matrix3, Matrix4};
 use ordered_float::OrderedFloat;
 use vd::{vks, util, Result as VdResult, Instance, Device, SurfaceKhr,
-    SwapchainKhr, ImageView, PipelineLayout, RenderPass, GraphicsPipeline, Framebuffer,
+    SwapchainKhr, AcquireResult, ImageView, PipelineLayout, RenderPass, GraphicsPipeline,
+    Framebuffer,
     CommandPool, Semaphore, Buffer, DeviceMemory, DescriptorSetLayout, DescriptorPool, Image,
     Sampler, Loader, SwapchainSupportDetails, PhysicalDevice, PhysicalDeviceFeatures, ShaderModule,
-    QueueFlags, Format, ApplicationInfo, DeviceQueueCreateInfo, SurfaceFormatKhr, ColorSpaceKhr,
+    QueueFamilySelection, Format, ApplicationInfo, ColorSpaceKhr,
     PresentModeKhr, SurfaceCapabilitiesKhr, Extent2d, ImageUsageFlags, CompositeAlphaFlagsKhr,
     SharingMode, ImageViewType, ComponentMapping, ImageSubresourceRange, ImageAspectFlags,
     ImageTiling, FormatFeatureFlags, AttachmentDescription, SampleCountFlags, AttachmentLoadOp,
-    AttachmentStoreOp, ImageLayout, AttachmentReference, SubpassDescription, PipelineBindPoint,
+    AttachmentStoreOp, ImageLayout, SubpassBuilder, PipelineBindPoint,
     SubpassDependency, PipelineStageFlags, AccessFlags, DescriptorSetLayoutBinding, DescriptorType,
-    ShaderStageFlags, DescriptorPoolSize, DescriptorSet, DescriptorBufferInfo, DescriptorImageInfo,
-    WriteDescriptorSet, PipelineShaderStageCreateInfo, PipelineVertexInputStateCreateInfo,
+    ShaderStageFlags, DescriptorPoolSize, DescriptorSet,
+    ShaderStage, VertexLayout,
     PipelineInputAssemblyStateCreateInfo, PrimitiveTopology, Viewport, Rect2d, Offset2d,
     PipelineViewportStateCreateInfo, PipelineRasterizationStateCreateInfo, PolygonMode,
     CullModeFlags, FrontFace, PipelineMultisampleStateCreateInfo, StencilOpState, StencilOp,
@@ -41,19 +41,15 @@ use vd::{vks, util, Result as VdResult, Instance, Device, SurfaceKhr,
     BufferUsageFlags, MemoryPropertyFlags, MemoryMapFlags, ImageType, Filter, SamplerMipmapMode,
     SamplerAddressMode, BorderColor, CommandBufferHandle, ClearValue,
     ClearColorValue, RenderPassBeginInfo, SubpassContents, IndexType, SemaphoreCreateFlags,
-    CallResult, PresentInfoKhr, ErrorKind, VertexInputBindingDescription, VertexInputRate,
+    CallResult, PresentInfoKhr, PresentResult, ErrorKind,
     VertexInputAttributeDescription};
-use voodoo_winit::winit::{EventsLoop, WindowBuilder, Window, Event, WindowEvent};
+use vd::winit::{EventsLoop, WindowBuilder, Window, Event, WindowEvent};
 
 #[cfg(debug_assertions)]
 pub const ENABLE_VALIDATION_LAYERS: bool = true;
 #[cfg(not(debug_assertions))]
 pub const ENABLE_VALIDATION_LAYERS: bool = false;
 
-pub static VALIDATION_LAYER_NAMES: &[&str] = &[
-    "VK_LAYER_LUNARG_standard_validation"
-];
-
 static REQUIRED_DEVICE_EXTENSIONS: &[&str] = &[
     "VK_KHR_swapchain",
 ];
@@ -90,17 +86,9 @@ pub struct Vertex {
     pub tex_coord: [f32; 2],
 }
 
-impl Vertex {
-    pub fn binding_description() -> VertexInputBindingDescription {
-        VertexInputBindingDescription::builder()
-            .binding(0)
-            .stride(mem::size_of::<Vertex>() as u32)
-            .input_rate(VertexInputRate::Vertex)
-            .build()
-    }
-
-    pub fn attribute_descriptions() -> [VertexInputAttributeDescription; 3] {
-        [
+impl VertexLayout for Vertex {
+    fn attributes() -> Vec<VertexInputAttributeDescription> {
+        vec![
             VertexInputAttributeDescription::builder()
                 .binding(0)
                 .location(0)
@@ -180,29 +168,11 @@ fn init_window() -> (Window, EventsLoop) {
     (window, events_loop)
 }
 
-/// Returns the list of layer names to be enabled.
-///
-/// Enable additional layers by adding names to the returned list.
-fn enabled_layer_names<'ln>(loader: &Loader) -> SmallVec<[&'ln str; 16]> {
-    if ENABLE_VALIDATION_LAYERS {
-        if loader.verify_layer_support(VALIDATION_LAYER_NAMES).unwrap() {
-            VALIDATION_LAYER_NAMES.iter().map(|&lyr_name| lyr_name).collect()
-        } else {
-            println!("WARNING: One or more validation layers cannot be loaded. Debug report \
-            generation will be unavailable. Please install the LunarG Vulkan SDK from \
-            `https://vulkan.lunarg.com/` to enable validation layers.");
-            SmallVec::new()
-        }
-    } else {
-        SmallVec::new()
-    }
-}
-
 /// Initializes and returns a new loader and instance.
 ///
 /// If `ENABLE_VALIDATION_LAYERS` is `true`, validation layers will be loaded (if available)
-/// and debug reports will print to stdout. If the LunarG SDK is not installed
-/// on your system, a warning will be printed to that effect.
+/// and debug reports will print to stdout. If the Vulkan SDK is not installed
+/// on your system, a warning will be logged to that effect.
 fn init_instance() -> VdResult<Instance> {
     let app_name = CString::new("Hello Rustaceans!")?;
     let eng_name = CString::new("Engine")?;
@@ -219,9 +189,8 @@ fn init_instance() -> VdResult<Instance> {
 
     Instance::builder()
         .application_info(&app_info)
-        .enabled_layer_names(enabled_layer_names(&loader).as_slice())
         .enabled_extensions(&loader.enumerate_instance_extension_properties()?)
-        .print_debug_report(ENABLE_VALIDATION_LAYERS)
+        .validation(ENABLE_VALIDATION_LAYERS)
         .build(loader)
 }
 
@@ -230,29 +199,11 @@ fn init_instance() -> VdResult<Instance> {
 /// graphics and presentation on `physical_device`.
 fn find_queue_families(physical_device: &PhysicalDevice, surface: &SurfaceKhr)
         -> VdResult<QueueFamilyIndices> {
-    let queue_families = physical_device.queue_family_properties()?;
-    let mut graphics_family_idx = None;
-    let mut present_family_idx = None;
-
-    let mut i = 0u32;
-    for queue_family in &queue_families {
-        if queue_family.queue_count() > 0 && queue_family.queue_flags()
-                .contains(QueueFlags::GRAPHICS) {
-            graphics_family_idx = Some(i);
-        }
-
-        let presentation_support = physical_device.surface_support_khr(i as u32, surface)?;
-        if queue_family.queue_count() > 0 && presentation_support {
-            present_family_idx = Some(i);
-        }
-
-        if let (Some(gf_idx), Some(pf_idx)) = (graphics_family_idx, present_family_idx) {
-            return Ok(QueueFamilyIndices::new(gf_idx, pf_idx));
-        }
-
-        i += 1;
+    let selection = physical_device.find_queue_families(Some(surface))?;
+    match (selection.graphics, selection.present) {
+        (Some(gf_idx), Some(pf_idx)) => Ok(QueueFamilyIndices::new(gf_idx, pf_idx)),
+        _ => Err("unable to find graphics and/or presentation queue family support".into()),
     }
-    Err("unable to find graphics and/or presentation queue family support".into())
 }
 
 /// Returns true if the specified physical device has the required features,
@@ -273,10 +224,12 @@ fn device_is_suitable(physical_device: &PhysicalDevice, surface: &SurfaceKhr)
             !swap_chain_details.present_modes.is_empty()
     }
 
-    let queue_family_indices = find_queue_families(&physical_device, surface)?;
+    let queue_families = physical_device.find_queue_families(Some(surface))?;
 
     Ok(extensions_supported &&
         swap_chain_adequate &&
+        queue_families.graphics.is_some() &&
+        queue_families.present.is_some() &&
         device_features.sampler_anisotropy())
 }
 
@@ -299,65 +252,24 @@ fn choose_physical_device(instance: &Instance, surface: &SurfaceKhr)
 }
 
 fn create_device(surface: &SurfaceKhr, physical_device: PhysicalDevice) -> VdResult<Device> {
-    let queue_family_indices = find_queue_families(&physical_device, surface)?;
-    let unique_queue_family_idxs: BTreeSet<u32> = [queue_family_indices.graphics_family_idx,
-        queue_family_indices.present_family_idx].iter().map(|&i| i).collect();
-
-    let queue_priorities = [1.0];
-    let queue_create_infos: SmallVec<[_; 2]> = unique_queue_family_idxs.iter().map(|&idx| {
-        DeviceQueueCreateInfo::builder()
-            .queue_family_index(idx)
-            .queue_priorities(&queue_priorities)
-            .build()
-    }).collect();
+    // Only graphics and presentation queues are used:
+    let queue_families = QueueFamilySelection {
+        dedicated_transfer: None,
+        async_compute: None,
+        .. physical_device.find_queue_families(Some(surface))?
+    };
 
     let features = PhysicalDeviceFeatures::builder()
         .sampler_anisotropy(true)
         .build();
 
     Device::builder()
-        .queue_create_infos(&queue_create_infos)
+        .queue_families(&queue_families)
         .enabled_extension_names(REQUIRED_DEVICE_EXTENSIONS)
         .enabled_features(&features)
         .build(physical_device)
 }
 
-fn choose_swap_surface_format(available_formats: &[SurfaceFormatKhr])
-        -> SurfaceFormatKhr {
-    if available_formats.len() == 1 && available_formats[0].format() == Format::Undefined {
-        return SurfaceFormatKhr::builder()
-            .format(Format::B8G8R8A8Unorm)
-            .color_space(ColorSpaceKhr::SrgbNonlinearKhr)
-            .build();
-    }
-    for available_format in available_formats {
-        if available_format.format() == Format::B8G8R8A8Unorm &&
-                available_format.color_space() == ColorSpaceKhr::SrgbNonlinearKhr {
-            return SurfaceFormatKhr::builder()
-                .format(Format::B8G8R8A8Unorm)
-                .color_space(ColorSpaceKhr::SrgbNonlinearKhr)
-                .build();
-        }
-    }
-    SurfaceFormatKhr::builder()
-        .format(available_formats[0].format())
-        .color_space(available_formats[0].color_space())
-        .build()
-}
-
-fn choose_swap_present_mode(available_present_modes: &[PresentModeKhr])
-        -> PresentModeKhr {
-    let mut best_mode = PresentModeKhr::MailboxKhr;
-    for &available_present_mode in available_present_modes {
-        if available_present_mode == PresentModeKhr::FifoKhr {
-            return available_present_mode;
-        } else if available_present_mode == PresentModeKhr::ImmediateKhr {
-            best_mode = available_present_mode;
-        }
-    }
-    best_mode
-}
-
 fn choose_swap_extent(capabilities: &SurfaceCapabilitiesKhr,
         window_size: Option<Extent2d>) -> Extent2d {
     if capabilities.current_extent().width() != u32::max_value() {
@@ -380,8 +292,10 @@ fn create_swapchain(surface: SurfaceKhr, device: Device, window_size: Option<Ext
         old_swapchain: Option<&SwapchainKhr>) -> VdResult<SwapchainKhr> {
     let queue_family_indices = find_queue_families(device.physical_device(), &surface)?;
     let swapchain_details = SwapchainSupportDetails::new(&surface, device.physical_device())?;
-    let surface_format = choose_swap_surface_format(&swapchain_details.formats);
-    let present_mode = choose_swap_present_mode(&swapchain_details.present_modes);
+    let surface_format = vd::choose_surface_format(&swapchain_details.formats,
+        &[(Format::B8G8R8A8Unorm, ColorSpaceKhr::SrgbNonlinearKhr)]);
+    let present_mode = vd::choose_present_mode(&swapchain_details.present_modes,
+        &[PresentModeKhr::MailboxKhr]);
     let extent = choose_swap_extent(&swapchain_details.capabilities, window_size);
 
     let mut image_count = swapchain_details.capabilities.min_image_count() + 1;
@@ -424,7 +338,7 @@ pub fn create_image_views(swapchain: &SwapchainKhr) -> VdResult<Vec<ImageView>>
         ImageView::builder()
             .image(image)
             .view_type(ImageViewType::Type2d)
-            .format(swapchain.image_format())
+            .format(image.format())
             .components(ComponentMapping::default())
             .subresource_range(ImageSubresourceRange::builder()
                 .aspect_mask(ImageAspectFlags::COLOR)
@@ -434,7 +348,7 @@ pub fn create_image_views(swapchain: &SwapchainKhr) -> VdResult<Vec<ImageView>>
                 .layer_count(1)
                 .build()
             )
-            .build(swapchain.device().clone(), Some(swapchain.clone()))
+            .build(image.device().clone(), image.swapchain().cloned())
 
     }).collect::<Result<Vec<_>, _>>()
 }
@@ -466,7 +380,8 @@ fn create_render_pass(device: Device, swapchain_image_format: Format)
         -> VdResult<RenderPass> {
     let depth_image_format = find_depth_format(&device)?;
 
-    let color_attachment = AttachmentDescription::builder()
+    let mut builder = RenderPass::builder();
+    let color = builder.attachment(AttachmentDescription::builder()
         .format(swapchain_image_format)
         .samples(SampleCountFlags::COUNT_1)
         .load_op(AttachmentLoadOp::Clear)
@@ -475,9 +390,8 @@ fn create_render_pass(device: Device, swapchain_image_format: Format)
         .stencil_store_op(AttachmentStoreOp::DontCare)
         .initial_layout(ImageLayout::Undefined)
         .final_layout(ImageLayout::PresentSrcKhr)
-        .build();
-
-    let depth_attachment = AttachmentDescription::builder()
+        .build());
+    let depth = builder.attachment(AttachmentDescription::builder()
         .format(depth_image_format)
         .samples(SampleCountFlags::COUNT_1)
         .load_op(AttachmentLoadOp::Clear)
@@ -486,39 +400,14 @@ fn create_render_pass(device: Device, swapchain_image_format: Format)
         .stencil_store_op(AttachmentStoreOp::DontCare)
         .initial_layout(ImageLayout::Undefined)
         .final_layout(ImageLayout::DepthStencilAttachmentOptimal)
-        .build();
+        .build());
 
-    let color_attachment_ref = AttachmentReference::builder()
-        .attachment(0)
-        .layout(ImageLayout::ColorAttachmentOptimal)
-        .build();
-
-    let depth_attachment_ref = AttachmentReference::builder()
-        .attachment(1)
-        .layout(ImageLayout::DepthStencilAttachmentOptimal)
-        .build();
+    let subpass = builder.subpass(SubpassBuilder::new()
+        .color_attachment(color, ImageLayout::ColorAttachmentOptimal)
+        .depth_stencil_attachment(depth, ImageLayout::DepthStencilAttachmentOptimal));
 
-    let color_attachments = [color_attachment_ref];
-
-    let subpass = SubpassDescription::builder()
-        .pipeline_bind_point(PipelineBindPoint::Graphics)
-        .color_attachments(&color_attachments[..])
-        .depth_stencil_attachment(&depth_attachment_ref)
-        .build();
-
-    let dependency = SubpassDependency::builder()
-        .src_subpass(vd::SUBPASS_EXTERNAL)
-        .dst_subpass(0)
-        .src_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
-        .dst_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
-        .dst_access_mask(AccessFlags::COLOR_ATTACHMENT_READ |
-            AccessFlags::COLOR_ATTACHMENT_WRITE)
-        .build();
-
-    RenderPass::builder()
-        .attachments(&[color_attachment, depth_attachment])
-        .subpasses(&[subpass])
-        .dependencies(&[dependency])
+    builder.dependency(SubpassDependency::external_color_to(subpass))
+        .dependency(SubpassDependency::external_depth_to(subpass))
         .build(device)
 }
 
@@ -565,41 +454,17 @@ fn create_descriptor_pool(device: Device) -> VdResult<DescriptorPool> {
 fn create_descriptor_sets(layout: &DescriptorSetLayout,
         pool: &DescriptorPool, uniform_buffer: &Buffer, texture_image_view: &ImageView,
         texture_sampler: &Sampler) -> VdResult<SmallVec<[DescriptorSet; 8]>> {
-    let descriptor_sets = pool.allocate_descriptor_sets(&[layout.handle()])?;
-
-    let buffer_info = DescriptorBufferInfo::builder()
-        .buffer(uniform_buffer)
-        .offset(0)
-        .range(mem::size_of::<UniformBufferObject>() as u64)
-        .build();
-
-    let image_info = DescriptorImageInfo::builder()
-        .sampler(texture_sampler)
-        .image_view(texture_image_view)
-        .image_layout(ImageLayout::ShaderReadOnlyOptimal)
-        .build();
-
-    let descriptor_writes = [
-        WriteDescriptorSet::builder()
-            .dst_set(&descriptor_sets[0])
-            .dst_binding(0)
-            .dst_array_element(0)
-            .descriptor_count(1)
-            .descriptor_type(DescriptorType::UniformBuffer)
-            .buffer_info(&buffer_info)
-            .build(),
-        WriteDescriptorSet::builder()
-            .dst_set(&descriptor_sets[0])
-            .dst_binding(1)
-            .dst_array_element(0)
-            .descriptor_count(1)
-            .descriptor_type(DescriptorType::CombinedImageSampler)
-            .image_info(&image_info)
-            .build(),
-    ];
+    let descriptor_set = pool.allocate_descriptor_set(layout)?;
 
-    pool.update_descriptor_sets(&descriptor_writes, &[]);
+    descriptor_set.writes()
+        .buffer(0, uniform_buffer, 0, Some(mem::size_of::<UniformBufferObject>() as u64),
+            DescriptorType::UniformBuffer)
+        .image(1, Some(texture_sampler.handle()), Some(texture_image_view.handle()),
+            ImageLayout::ShaderReadOnlyOptimal, DescriptorType::CombinedImageSampler)
+        .update();
 
+    let mut descriptor_sets = SmallVec::new();
+    descriptor_sets.push(descriptor_set);
     Ok(descriptor_sets)
 }
 
@@ -621,28 +486,16 @@ fn create_graphics_pipeline(device: Device, pipeline_layout: &PipelineLayout,
     let vert_shader_module = ShaderModule::new(device.clone(), vert_shader_code)?;
     let frag_shader_module = ShaderModule::new(device.clone(), frag_shader_code)?;
 
-    let fn_name = CStr::from_bytes_with_nul(b"main\0").unwrap();
-
-    let vert_shader_stage_info = PipelineShaderStageCreateInfo::builder()
+    let vert_shader_stage = ShaderStage::builder()
         .stage(ShaderStageFlags::VERTEX)
-
         .module(&vert_shader_module)
-        .name(fn_name)
-        .build();
+        .build()?;
 
-    let frag_shader_stage_info = PipelineShaderStageCreateInfo::builder()
+    let frag_shader_stage = ShaderStage::builder()
         .stage(ShaderStageFlags::FRAGMENT)
         .module(&frag_shader_module)
-        .name(fn_name)
-        .build();
-
-    let binding_descriptions = [Vertex::binding_description()];
-    let attribute_descriptions = Vertex::attribute_descriptions();
+        .build()?;
 
-    let vertex_input_info = PipelineVertexInputStateCreateInfo::builder()
-        .vertex_binding_descriptions(&binding_descriptions[..])
-        .vertex_attribute_descriptions(&attribute_descriptions[..])
-        .build();
 
     let input_assembly = PipelineInputAssemblyStateCreateInfo::builder()
         .topology(PrimitiveTopology::TriangleList)
@@ -735,11 +588,11 @@ fn create_graphics_pipeline(device: Device, pipeline_layout: &PipelineLayout,
         .blend_constants([0.0f32; 4])
         .build();
 
-    let shader_stages = &[vert_shader_stage_info, frag_shader_stage_info];
+    let shader_stages = [vert_shader_stage, frag_shader_stage];
 
     GraphicsPipeline::builder()
-        .stages(shader_stages)
-        .vertex_input_state(&vertex_input_info)
+        .shader_stages(&shader_stages)
+        .vertex_layout::<Vertex>()
         .input_assembly_state(&input_assembly)
         .viewport_state(&viewport_state)
         .rasterization_state(&rasterizer)
@@ -798,45 +651,20 @@ fn end_single_time_commands(device: &Device, command_buffer: CommandBuffer) -> V
     Ok(())
 }
 
-fn has_stencil_component(format: Format) -> bool {
-    format == Format::D32SfloatS8Uint || format == Format::D24UnormS8Uint
-}
-
 fn transition_image_layout(device: &Device, command_pool: &CommandPool, image: &Image,
         format: Format, old_layout: ImageLayout, new_layout: ImageLayout)
          -> VdResult<()> {
     let command_buffer = begin_single_time_commands(command_pool)?;
 
-    let subresource_range = ImageSubresourceRange::builder()
-        .aspect_mask(ImageAspectFlags::COLOR)
-        .base_mip_level(0)
-        .level_count(1)
-        .base_array_layer(0)
-        .layer_count(1)
-        .build();
+    let subresource_range = ImageSubresourceRange::format_full(format);
 
     let mut barrier = ImageMemoryBarrier::builder()
-        .src_access_mask(AccessFlags::empty())
-        .dst_access_mask(AccessFlags::empty())
         .old_layout(old_layout)
         .new_layout(new_layout)
-        .src_queue_family_index(vd::QUEUE_FAMILY_IGNORED)
-        .dst_queue_family_index(vd::QUEUE_FAMILY_IGNORED)
         .image(image)
         .subresource_range(subresource_range)
         .build();
 
-    if new_layout == ImageLayout::DepthStencilAttachmentOptimal {
-        barrier.subresource_range_mut().set_aspect_mask(ImageAspectFlags::DEPTH);
-        if has_stencil_component(format) {
-            let aspect_mask = barrier.subresource_range().aspect_mask() |
-                ImageAspectFlags::STENCIL;
-            barrier.subresource_range_mut().set_aspect_mask(aspect_mask);
-        }
-    } else {
-        barrier.subresource_range_mut().set_aspect_mask(ImageAspectFlags::COLOR);
-    }
-
     let source_stage: PipelineStageFlags;
     let destination_stage: PipelineStageFlags;
 
@@ -1234,8 +1062,7 @@ pub fn create_command_buffers(device: &Device, command_pool: &CommandPool,
     let command_buffers = command_pool.allocate_command_buffers(CommandBufferLevel::Primary,
             swapchain_framebuffers.len() as u32)?;
 
-    let vertex_buffers = [vertex_buffer];
-    let offsets = [0];
+    let vertex_buffers = [(vertex_buffer, 0)];
     let descriptor_sets = [&descriptor_set];
 
     for (cmd_buf, swapchain_framebuffer) in command_buffers.iter()
@@ -1262,7 +1089,7 @@ pub fn create_command_buffers(device: &Device, command_pool: &CommandPool,
 
         cmd_buf.begin_render_pass(&render_pass_info, SubpassContents::Inline);
         cmd_buf.bind_pipeline(PipelineBindPoint::Graphics, &graphics_pipeline);
-        cmd_buf.bind_vertex_buffers(0, &vertex_buffers, &offsets);
+        cmd_buf.bind_vertex_buffers(0, &vertex_buffers);
         cmd_buf.bind_index_buffer(index_buffer, 0, IndexType::Uint32);
         cmd_buf.bind_descriptor_sets(PipelineBindPoint::Graphics, pipeline_layout,
             0, &descriptor_sets, &[]);
@@ -1275,6 +1102,14 @@ pub fn create_command_buffers(device: &Device, command_pool: &CommandPool,
     Ok(command_buffers)
 }
 
+/// Returns `true` if `err` reports that the swapchain is out of date.
+fn is_out_of_date(err: &vd::Error) -> bool {
+    match err.kind {
+        ErrorKind::ApiCall(CallResult::ErrorOutOfDateKhr, _) => true,
+        _ => false,
+    }
+}
+
 
 struct SwapchainComponents {
     image_views: Vec<ImageView>,
@@ -1325,7 +1160,7 @@ impl App {
     pub fn new() -> VdResult<App> {
         let instance = init_instance()?;
         let (window, events_loop) = init_window();
-        let surface = voodoo_winit::create_surface(instance.clone(), &window)?;
+        let surface = vd::winit_support::create_surface(&instance, &window)?;
         let physical_device = choose_physical_device(&instance, &surface)?;
         let device = create_device(&surface, physical_device)?;
         let swapchain = create_swapchain(surface.clone(), device.clone(), None, None)?;
@@ -1502,21 +1337,11 @@ impl App {
         let acquire_result = self.swapchain.as_ref().unwrap().acquire_next_image_khr(
             u64::max_value(), Some(&self.image_available_semaphore), None);
         let image_index = match acquire_result {
-            Ok(idx) => idx,
-            Err(res) => {
-                if let ErrorKind::ApiCall(call_res, _fn_name) = res.kind {
-                    if call_res == CallResult::ErrorOutOfDateKhr {
-                        let dims = self.window.get_inner_size().unwrap();
-                        self.recreate_swapchain(Extent2d::builder()
-                            .height(dims.0).width(dims.1).build())?;
-                        return Ok(());
-                    } else {
-                        panic!("Unable to present swap chain image");
-                    }
-                } else {
-                    panic!("Unable to present swap chain image");
-                }
-            }
+            Ok(AcquireResult::Normal(idx)) => idx,
+            // Still presentable, the swapchain is recreated after presenting:
+            Ok(AcquireResult::Suboptimal(idx)) => idx,
+            Err(ref err) if is_out_of_date(err) => return self.recreate_swapchain_for_window(),
+            Err(_) => panic!("Unable to acquire swap chain image"),
         };
 
         let wait_semaphores = [self.image_available_semaphore.handle()];
@@ -1544,10 +1369,20 @@ impl App {
             .image_indices(&image_indices)
             .build();
 
-        queue.present_khr(&present_info)?;
+        let present_result = queue.present_khr(&present_info);
         queue.wait_idle();
 
-        Ok(())
+        match present_result {
+            Ok(PresentResult::Normal) => Ok(()),
+            Ok(PresentResult::Suboptimal) => self.recreate_swapchain_for_window(),
+            Err(ref err) if is_out_of_date(err) => self.recreate_swapchain_for_window(),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn recreate_swapchain_for_window(&mut self) -> VdResult<()> {
+        let dims = self.window.get_inner_size().unwrap();
+        self.recreate_swapchain(Extent2d::builder().width(dims.0).height(dims.1).build())
     }
 
     fn main_loop(&mut self) -> VdResult<()> {
@@ -0,0 +1,224 @@
+//! Records one secondary command buffer per object on worker threads and
+//! executes them from a single primary render pass, rendering offscreen.
+//!
+//! Each "object" here is simply a rectangle of the framebuffer cleared to
+//! its own color.
+
+extern crate voodoo;
+
+use std::ffi::CString;
+use std::thread;
+use voodoo::{Result as VdResult, Instance, ApplicationInfo, Loader, PhysicalDevice, Device,
+    DeviceQueueCreateInfo, QueueFlags, CommandPool, CommandBuffer, CommandBufferRecorder,
+    CommandBufferLevel,
+    CommandBufferUsageFlags, CommandBufferInheritanceInfo, Image, ImageView, ImageType,
+    ImageViewType, ImageTiling, ImageUsageFlags, ImageLayout, ImageAspectFlags,
+    ImageSubresourceRange, ComponentMapping, SharingMode, SampleCountFlags, Format, Extent2d,
+    Extent3d, Offset2d, Rect2d, MemoryPropertyFlags, DeviceMemory, AttachmentDescription,
+    AttachmentLoadOp, AttachmentStoreOp, SubpassBuilder, RenderPass, Framebuffer, RenderPassBeginInfo, SubpassContents, ClearValue,
+    ClearColorValue, ClearAttachment, ClearRect};
+
+
+const EXTENT: u32 = 256;
+const OBJECT_COUNT: u32 = 4;
+
+
+fn init_instance() -> VdResult<Instance> {
+    let app_name = CString::new("Secondary Command Buffers")?;
+
+    let app_info = ApplicationInfo::builder()
+        .application_name(&app_name)
+        .application_version((1, 0, 0))
+        .api_version((1, 0, 0))
+        .build();
+
+    let loader = Loader::new()?;
+
+    Instance::builder()
+        .application_info(&app_info)
+        .build(loader)
+}
+
+/// Returns the first physical device with a graphics queue family, along
+/// with the index of that family.
+fn choose_physical_device(instance: &Instance) -> VdResult<(PhysicalDevice, u32)> {
+    for device in instance.physical_devices()? {
+        let queue_families = device.queue_family_properties()?;
+        for (idx, family) in queue_families.iter().enumerate() {
+            if family.queue_flags().contains(QueueFlags::GRAPHICS) {
+                return Ok((device, idx as u32));
+            }
+        }
+    }
+    panic!("Failed to find a device with a graphics queue.");
+}
+
+fn create_color_target(device: &Device) -> VdResult<(Image, DeviceMemory, ImageView)> {
+    let image = Image::builder()
+        .image_type(ImageType::Type2d)
+        .format(Format::R8G8B8A8Unorm)
+        .extent(Extent3d::builder().width(EXTENT).height(EXTENT).depth(1).build())
+        .mip_levels(1)
+        .array_layers(1)
+        .samples(SampleCountFlags::COUNT_1)
+        .tiling(ImageTiling::Optimal)
+        .usage(ImageUsageFlags::COLOR_ATTACHMENT)
+        .sharing_mode(SharingMode::Exclusive)
+        .initial_layout(ImageLayout::Undefined)
+        .build(device.clone())?;
+
+    let memory_requirements = image.memory_requirements().clone();
+    let memory_type_index = device.memory_type_index(memory_requirements.memory_type_bits(),
+        MemoryPropertyFlags::DEVICE_LOCAL)?;
+    let memory = DeviceMemory::new(device.clone(), memory_requirements.size(),
+        memory_type_index)?;
+    unsafe { image.bind_memory(&memory, 0)?; }
+
+    let view = ImageView::builder()
+        .image(image.handle())
+        .view_type(ImageViewType::Type2d)
+        .format(Format::R8G8B8A8Unorm)
+        .components(ComponentMapping::default())
+        .subresource_range(ImageSubresourceRange::builder()
+            .aspect_mask(ImageAspectFlags::COLOR)
+            .base_mip_level(0)
+            .level_count(1)
+            .base_array_layer(0)
+            .layer_count(1)
+            .build())
+        .build(device.clone(), None)?;
+
+    Ok((image, memory, view))
+}
+
+fn create_render_pass(device: &Device) -> VdResult<RenderPass> {
+    let mut builder = RenderPass::builder();
+    let color = builder.attachment(AttachmentDescription::builder()
+        .format(Format::R8G8B8A8Unorm)
+        .samples(SampleCountFlags::COUNT_1)
+        .load_op(AttachmentLoadOp::Clear)
+        .store_op(AttachmentStoreOp::Store)
+        .stencil_load_op(AttachmentLoadOp::DontCare)
+        .stencil_store_op(AttachmentStoreOp::DontCare)
+        .initial_layout(ImageLayout::Undefined)
+        .final_layout(ImageLayout::ColorAttachmentOptimal)
+        .build());
+
+    builder.subpass(SubpassBuilder::new()
+        .color_attachment(color, ImageLayout::ColorAttachmentOptimal));
+
+    builder.build(device.clone())
+}
+
+/// Records into `command_buffer`, a secondary command buffer, commands which
+/// clear the rectangle belonging to object `index` within subpass 0 of
+/// `render_pass`.
+fn record_object(command_buffer: &CommandBuffer, render_pass: &RenderPass,
+        framebuffer: &Framebuffer, index: u32) -> VdResult<()> {
+    let inheritance_info = CommandBufferInheritanceInfo::builder()
+        .render_pass(render_pass)
+        .subpass(0)
+        .framebuffer(framebuffer)
+        .build();
+    command_buffer.begin_secondary(CommandBufferUsageFlags::ONE_TIME_SUBMIT |
+        CommandBufferUsageFlags::RENDER_PASS_CONTINUE, &inheritance_info)?;
+
+    let shade = (index + 1) as f32 / OBJECT_COUNT as f32;
+    let attachment = ClearAttachment::builder()
+        .aspect_mask(ImageAspectFlags::COLOR)
+        .color_attachment(0)
+        .clear_value(ClearValue { color: ClearColorValue { float32: [shade, 0.0, 1.0 - shade, 1.0] } })
+        .build();
+    let width = EXTENT / OBJECT_COUNT;
+    let rect = ClearRect::builder()
+        .rect(Rect2d::builder()
+            .offset(Offset2d::builder().x((index * width) as i32).y(0).build())
+            .extent(Extent2d::builder().width(width).height(EXTENT).build())
+            .build())
+        .base_array_layer(0)
+        .layer_count(1)
+        .build();
+    command_buffer.clear_attachments(&[attachment], &[rect]);
+
+    command_buffer.end()?;
+    Ok(())
+}
+
+fn main() {
+    let instance = init_instance().unwrap();
+    let (physical_device, queue_family_index) = choose_physical_device(&instance).unwrap();
+
+    let queue_priorities = [1.0];
+    let queue_create_infos = [DeviceQueueCreateInfo::builder()
+        .queue_family_index(queue_family_index)
+        .queue_priorities(&queue_priorities)
+        .build()];
+    let device = Device::builder()
+        .queue_create_infos(&queue_create_infos)
+        .build(physical_device)
+        .unwrap();
+    let queue = device.queue(0).unwrap().clone();
+
+    let (_image, _memory, view) = create_color_target(&device).unwrap();
+    let render_pass = create_render_pass(&device).unwrap();
+    let framebuffer = Framebuffer::builder()
+        .render_pass(&render_pass)
+        .attachments(&[&view])
+        .width(EXTENT)
+        .height(EXTENT)
+        .layers(1)
+        .build(device.clone())
+        .unwrap();
+
+    // Command pools are externally synchronized so each worker gets its own,
+    // owned solely by its command buffer so that the pair may be recorded on,
+    // and returned from, the worker thread.
+    let workers: Vec<_> = (0..OBJECT_COUNT).map(|index| {
+        let command_pool = CommandPool::builder()
+            .queue_family_index(queue_family_index)
+            .build(device.clone())
+            .unwrap();
+        let command_buffer = command_pool.allocate_secondary_command_buffer().unwrap();
+        drop(command_pool);
+        let mut recorder = CommandBufferRecorder::new(command_buffer).unwrap();
+        let render_pass = render_pass.clone();
+        let framebuffer = framebuffer.clone();
+        thread::spawn(move || {
+            recorder.record(|cb| record_object(cb, &render_pass, &framebuffer, index)).unwrap();
+            recorder
+        })
+    }).collect();
+    let recorded: Vec<CommandBuffer> = workers.into_iter()
+        .map(|worker| worker.join().unwrap().into_inner())
+        .collect();
+    let secondaries: Vec<&CommandBuffer> = recorded.iter().collect();
+
+    let command_pool = CommandPool::builder()
+        .queue_family_index(queue_family_index)
+        .build(device.clone())
+        .unwrap();
+    let primary = command_pool.allocate_command_buffer(CommandBufferLevel::Primary).unwrap();
+
+    let clear_values = [ClearValue { color: ClearColorValue { float32: [0.0, 0.0, 0.0, 1.0] } }];
+    let render_pass_info = RenderPassBeginInfo::builder()
+        .render_pass(&render_pass)
+        .framebuffer(&framebuffer)
+        .render_area(Rect2d::builder()
+            .offset(Offset2d::builder().x(0).y(0).build())
+            .extent(Extent2d::builder().width(EXTENT).height(EXTENT).build())
+            .build())
+        .clear_values(&clear_values)
+        .build();
+
+    primary.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT).unwrap();
+    primary.begin_render_pass(&render_pass_info, SubpassContents::SecondaryCommandBuffers);
+    primary.execute_commands(&secondaries);
+    primary.end_render_pass();
+    primary.end().unwrap();
+
+    queue.submit_one(&[], primary.handle(), &[], None).unwrap();
+    queue.wait_idle();
+
+    println!("Executed {} secondary command buffers recorded on worker threads.",
+        secondaries.len());
+}
@@ -0,0 +1,811 @@
+//! Device memory sub-allocation.
+//!
+//! `LinearAllocator` carves many resources out of a few large
+//! `DeviceMemory` blocks, staying well clear of `maxMemoryAllocationCount`.
+//! Memory is handed out in order and is only reclaimed all at once, making it
+//! well suited to resources sharing a common lifetime (per-level or
+//! per-frame data, for example).
+//!
+//! `Allocator` manages blocks for every memory type, selected per request by
+//! memory properties, and frees suballocations individually according to an
+//! `AllocationStrategy`.
+
+use ::{VdResult, Device, DeviceMemory, DeviceMemoryHandle, MemoryRequirements,
+    MemoryPropertyFlags, DeviceSize};
+use ::mem_util::align_up;
+use smallvec::SmallVec;
+
+
+/// The tiling arrangement of a resource, as far as `bufferImageGranularity`
+/// is concerned.
+///
+/// Linear and optimal resources placed in the same memory object must be
+/// separated by at least `bufferImageGranularity` bytes ("pages").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// A buffer or an image created with `ImageTiling::Linear`.
+    Linear,
+    /// An image created with `ImageTiling::Optimal`.
+    Optimal,
+}
+
+
+/// The intended use of a memory allocation, used to choose a memory type
+/// with `Device::memory_type_index_for_usage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryUsage {
+    /// Accessed only by the device. Prefers `DEVICE_LOCAL` memory.
+    GpuOnly,
+    /// Written by the host and read by the device, such as uniform or
+    /// staging data. Prefers `DEVICE_LOCAL | HOST_VISIBLE | HOST_COHERENT`
+    /// memory, then `HOST_VISIBLE | HOST_COHERENT`, then `HOST_VISIBLE`.
+    CpuToGpu,
+    /// Written by the device and read by the host, such as readback data.
+    /// Prefers `HOST_VISIBLE | HOST_CACHED` memory, then `HOST_VISIBLE`.
+    GpuToCpu,
+    /// Accessed mostly by the host. Prefers `HOST_VISIBLE | HOST_COHERENT`
+    /// memory, then `HOST_VISIBLE`.
+    CpuOnly,
+}
+
+impl MemoryUsage {
+    /// Returns the property combinations suited to this usage, in order of
+    /// preference.
+    pub(crate) fn preferred_properties(&self) -> SmallVec<[MemoryPropertyFlags; 4]> {
+        let visible = MemoryPropertyFlags::HOST_VISIBLE;
+        let coherent = MemoryPropertyFlags::HOST_COHERENT;
+        let mut props = SmallVec::new();
+        match *self {
+            MemoryUsage::GpuOnly => {
+                props.push(MemoryPropertyFlags::DEVICE_LOCAL);
+                props.push(MemoryPropertyFlags::empty());
+            },
+            MemoryUsage::CpuToGpu => {
+                props.push(MemoryPropertyFlags::DEVICE_LOCAL | visible | coherent);
+                props.push(visible | coherent);
+                props.push(visible);
+            },
+            MemoryUsage::GpuToCpu => {
+                props.push(visible | MemoryPropertyFlags::HOST_CACHED);
+                props.push(visible);
+            },
+            MemoryUsage::CpuOnly => {
+                props.push(visible | coherent);
+                props.push(visible);
+            },
+        }
+        props
+    }
+}
+
+
+/// A region of a memory block handed out by an allocator.
+///
+/// A suballocation does not keep its block alive. The allocator it came from
+/// must outlive any resource bound to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suballocation {
+    memory: DeviceMemoryHandle,
+    offset: DeviceSize,
+    size: DeviceSize,
+}
+
+impl Suballocation {
+    /// Returns the handle of the memory object containing this region.
+    pub fn memory(&self) -> DeviceMemoryHandle {
+        self.memory
+    }
+
+    /// Returns the offset of this region within its memory object, suitable
+    /// for passing to `bind_buffer_memory` or `bind_image_memory`.
+    pub fn offset(&self) -> DeviceSize {
+        self.offset
+    }
+
+    /// Returns the size of this region in bytes.
+    pub fn size(&self) -> DeviceSize {
+        self.size
+    }
+}
+
+
+/// Returns the offset at which a resource of `kind` with the given
+/// `alignment` may be placed in a block where `used` bytes have been handed
+/// out, the last of which went to a resource of `prev_kind`.
+fn placement_offset(used: DeviceSize, prev_kind: Option<ResourceKind>, kind: ResourceKind,
+        alignment: DeviceSize, granularity: DeviceSize) -> DeviceSize {
+    let offset = align_up(used, alignment);
+    match prev_kind {
+        // The previous resource ends at `used`. Starting on a fresh page
+        // guarantees the two never share one.
+        Some(prev) if prev != kind => align_up(offset, granularity),
+        _ => offset,
+    }
+}
+
+
+#[derive(Debug)]
+struct Block {
+    memory: DeviceMemory,
+    size: DeviceSize,
+    used: DeviceSize,
+    last_kind: Option<ResourceKind>,
+}
+
+impl Block {
+    /// Returns the offset at which the described resource would fit, if it
+    /// does.
+    fn fit(&self, reqs: &MemoryRequirements, kind: ResourceKind, granularity: DeviceSize)
+            -> Option<DeviceSize> {
+        let offset = placement_offset(self.used, self.last_kind, kind, reqs.alignment(),
+            granularity);
+        if offset.checked_add(reqs.size()).map(|end| end <= self.size).unwrap_or(false) {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+}
+
+
+/// A linear (bump) allocator handing out regions of large device memory
+/// blocks of a single memory type.
+///
+/// Allocation is a matter of advancing an offset within the current block.
+/// When the current block is full another is allocated (resources larger than
+/// the block size get a block of their own). Individual suballocations can
+/// not be freed; `reset` reclaims all of them at once.
+///
+///
+/// ### Destruction
+///
+/// Dropping this `LinearAllocator` frees every block it has allocated.
+/// Resources bound to its memory must be destroyed first.
+///
+#[derive(Debug)]
+pub struct LinearAllocator {
+    device: Device,
+    memory_type_index: u32,
+    block_size: DeviceSize,
+    buffer_image_granularity: DeviceSize,
+    blocks: Vec<Block>,
+    current: usize,
+}
+
+impl LinearAllocator {
+    /// Returns a new `LinearAllocator` which allocates blocks of
+    /// `block_size` bytes from the memory type at `memory_type_index`.
+    ///
+    /// No memory is allocated until the first call to `allocate`.
+    pub fn new(device: Device, memory_type_index: u32, block_size: DeviceSize)
+            -> VdResult<LinearAllocator> {
+        let memory_type_count = device.physical_device().memory_properties().memory_type_count();
+        if memory_type_index >= memory_type_count {
+            return Err(format!("LinearAllocator::new: invalid memory type index: {} \
+                (memory type count: {})", memory_type_index, memory_type_count).into());
+        }
+        if block_size == 0 {
+            return Err("LinearAllocator::new: block size must be non-zero".into());
+        }
+        let buffer_image_granularity = device.limits().buffer_image_granularity();
+
+        Ok(LinearAllocator {
+            device,
+            memory_type_index,
+            block_size,
+            buffer_image_granularity,
+            blocks: Vec::new(),
+            current: 0,
+        })
+    }
+
+    /// Returns a new `LinearAllocator` using the first memory type which has
+    /// all of `properties`.
+    pub fn with_properties(device: Device, properties: MemoryPropertyFlags,
+            block_size: DeviceSize) -> VdResult<LinearAllocator> {
+        let memory_type_index = device.memory_type_index(!0, properties)?;
+        LinearAllocator::new(device, memory_type_index, block_size)
+    }
+
+    /// Hands out a region satisfying the size and alignment of `reqs` for a
+    /// resource of `kind`, allocating a new block if required.
+    ///
+    /// Returns an error if `reqs` does not permit this allocator's memory
+    /// type.
+    pub fn allocate(&mut self, reqs: &MemoryRequirements, kind: ResourceKind)
+            -> VdResult<Suballocation> {
+        if reqs.memory_type_bits() & (1 << self.memory_type_index) == 0 {
+            return Err(format!("LinearAllocator::allocate: memory type {} is not permitted by \
+                the requirements (memory type bits: {:#b})", self.memory_type_index,
+                reqs.memory_type_bits()).into());
+        }
+
+        // Try the current block and any already allocated (reclaimed) ones.
+        while self.current < self.blocks.len() {
+            let granularity = self.buffer_image_granularity;
+            let block = &mut self.blocks[self.current];
+            if let Some(offset) = block.fit(reqs, kind, granularity) {
+                block.used = offset + reqs.size();
+                block.last_kind = Some(kind);
+                return Ok(Suballocation { memory: block.memory.handle(), offset,
+                    size: reqs.size() });
+            }
+            self.current += 1;
+        }
+
+        let size = if reqs.size() > self.block_size { reqs.size() } else { self.block_size };
+        let memory = DeviceMemory::new(self.device.clone(), size, self.memory_type_index)?;
+        let suballocation = Suballocation { memory: memory.handle(), offset: 0,
+            size: reqs.size() };
+        self.blocks.push(Block { memory, size, used: reqs.size(), last_kind: Some(kind) });
+        self.current = self.blocks.len() - 1;
+        Ok(suballocation)
+    }
+
+    /// Reclaims every suballocation at once. Blocks are kept and reused by
+    /// subsequent allocations.
+    ///
+    /// ## Safety
+    ///
+    /// No resource bound to memory handed out by this allocator may be in
+    /// use by the device, nor be used again afterwards.
+    pub unsafe fn reset(&mut self) {
+        for block in self.blocks.iter_mut() {
+            block.used = 0;
+            block.last_kind = None;
+        }
+        self.current = 0;
+    }
+
+    /// Returns the index of the memory type blocks are allocated from.
+    pub fn memory_type_index(&self) -> u32 {
+        self.memory_type_index
+    }
+
+    /// Returns the default block size.
+    pub fn block_size(&self) -> DeviceSize {
+        self.block_size
+    }
+
+    /// Returns the number of blocks currently allocated.
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns the total number of bytes allocated from the device.
+    pub fn allocated_size(&self) -> DeviceSize {
+        self.blocks.iter().map(|b| b.size).sum()
+    }
+
+    /// Returns a reference to the associated device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+}
+
+
+/// The way an `Allocator` places suballocations within its blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationStrategy {
+    /// Regions are handed out in order. Freed regions are only reclaimed
+    /// once every region in their block has been freed. Suited to transient
+    /// resources.
+    Linear,
+    /// Regions are placed in the first free range large enough to hold them.
+    /// Freed regions are merged with adjacent free ranges and reused. Suited
+    /// to long-lived resources.
+    FreeList,
+}
+
+
+/// Memory usage statistics for an `Allocator`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocatorStats {
+    allocated_bytes: DeviceSize,
+    used_bytes: DeviceSize,
+    block_count: usize,
+    dedicated_count: usize,
+}
+
+impl AllocatorStats {
+    /// Returns the total number of bytes allocated from the device.
+    pub fn allocated_bytes(&self) -> DeviceSize {
+        self.allocated_bytes
+    }
+
+    /// Returns the number of bytes in suballocations which have not been
+    /// freed.
+    pub fn used_bytes(&self) -> DeviceSize {
+        self.used_bytes
+    }
+
+    /// Returns the number of memory objects allocated from the device,
+    /// including dedicated allocations.
+    pub fn block_count(&self) -> usize {
+        self.block_count
+    }
+
+    /// Returns the number of dedicated allocations, made for requests larger
+    /// than the block size.
+    pub fn dedicated_count(&self) -> usize {
+        self.dedicated_count
+    }
+}
+
+
+#[derive(Debug)]
+struct PoolBlock {
+    memory: DeviceMemory,
+    size: DeviceSize,
+    dedicated: bool,
+    /// Free ranges as `(offset, size)`, sorted by offset (`FreeList` only).
+    free: Vec<(DeviceSize, DeviceSize)>,
+    /// The end of the last region handed out (`Linear` only).
+    bump: DeviceSize,
+    used: DeviceSize,
+    live_count: usize,
+}
+
+impl PoolBlock {
+    fn new(memory: DeviceMemory, size: DeviceSize, dedicated: bool) -> PoolBlock {
+        PoolBlock { memory, size, dedicated, free: vec![(0, size)], bump: 0, used: 0,
+            live_count: 0 }
+    }
+
+    /// Reserves a region for `reqs`, returning its offset, if one fits.
+    fn reserve(&mut self, reqs: &MemoryRequirements, strategy: AllocationStrategy)
+            -> Option<DeviceSize> {
+        let size = reqs.size();
+        let offset = match strategy {
+            AllocationStrategy::Linear => {
+                let offset = align_up(self.bump, reqs.alignment());
+                if !offset.checked_add(size).map(|end| end <= self.size).unwrap_or(false) {
+                    return None;
+                }
+                self.bump = offset + size;
+                offset
+            },
+            AllocationStrategy::FreeList => {
+                let fit = self.free.iter().enumerate().filter_map(|(idx, &(start, len))| {
+                    let offset = align_up(start, reqs.alignment());
+                    offset.checked_add(size).and_then(|end| {
+                        if end <= start + len { Some((idx, offset)) } else { None }
+                    })
+                }).next();
+                let (idx, offset) = fit?;
+                let (start, len) = self.free[idx];
+                let tail = (offset + size, start + len - (offset + size));
+                if offset > start {
+                    self.free[idx] = (start, offset - start);
+                    if tail.1 != 0 { self.free.insert(idx + 1, tail); }
+                } else if tail.1 != 0 {
+                    self.free[idx] = tail;
+                } else {
+                    self.free.remove(idx);
+                }
+                offset
+            },
+        };
+        self.used += size;
+        self.live_count += 1;
+        Some(offset)
+    }
+
+    /// Returns the region at `offset` to the block.
+    fn release(&mut self, offset: DeviceSize, size: DeviceSize, strategy: AllocationStrategy) {
+        self.used -= size;
+        self.live_count -= 1;
+        match strategy {
+            AllocationStrategy::Linear => if self.live_count == 0 { self.bump = 0; },
+            AllocationStrategy::FreeList => {
+                let idx = self.free.iter().position(|&(o, _)| o > offset)
+                    .unwrap_or(self.free.len());
+                self.free.insert(idx, (offset, size));
+                if idx + 1 < self.free.len() && offset + size == self.free[idx + 1].0 {
+                    self.free[idx].1 += self.free[idx + 1].1;
+                    self.free.remove(idx + 1);
+                }
+                if idx > 0 && self.free[idx - 1].0 + self.free[idx - 1].1 == offset {
+                    self.free[idx - 1].1 += self.free[idx].1;
+                    self.free.remove(idx);
+                }
+            },
+        }
+    }
+}
+
+
+/// The blocks of a single memory type holding resources of one kind.
+///
+/// Linear and optimal resources are kept in separate pools so that
+/// `bufferImageGranularity` never needs to be considered.
+#[derive(Debug)]
+struct Pool {
+    memory_type_index: u32,
+    kind: ResourceKind,
+    blocks: Vec<PoolBlock>,
+}
+
+
+/// A general purpose allocator handing out regions of large device memory
+/// blocks of any memory type.
+///
+/// Each request selects a memory type from its requirements and the
+/// requested memory properties. Blocks of `block_size` bytes are allocated
+/// for each memory type as needed. Requests larger than the block size are
+/// given a dedicated allocation, freed as soon as its suballocation is.
+///
+/// ```text
+/// let mut allocator = Allocator::new(device.clone(), AllocationStrategy::FreeList,
+///     64 * 1024 * 1024);
+/// let region = allocator.allocate(buffer.memory_requirements(),
+///     MemoryPropertyFlags::DEVICE_LOCAL, ResourceKind::Linear)?;
+/// device.bind_buffer_memory(buffer.handle(), region.memory(), region.offset())?;
+/// ```
+///
+///
+/// ### Destruction
+///
+/// Dropping this `Allocator` frees every block it has allocated. Resources
+/// bound to its memory must be destroyed first.
+///
+#[derive(Debug)]
+pub struct Allocator {
+    device: Device,
+    strategy: AllocationStrategy,
+    block_size: DeviceSize,
+    pools: Vec<Pool>,
+}
+
+impl Allocator {
+    /// Returns a new `Allocator` which places suballocations according to
+    /// `strategy` within blocks of `block_size` bytes.
+    ///
+    /// No memory is allocated until the first call to `allocate`.
+    pub fn new(device: Device, strategy: AllocationStrategy, block_size: DeviceSize)
+            -> VdResult<Allocator> {
+        if block_size == 0 {
+            return Err("Allocator::new: block size must be non-zero".into());
+        }
+        Ok(Allocator { device, strategy, block_size, pools: Vec::new() })
+    }
+
+    /// Hands out a region satisfying `reqs` for a resource of `kind` from a
+    /// memory type which has all of `properties`, allocating a new block if
+    /// required.
+    pub fn allocate(&mut self, reqs: &MemoryRequirements, properties: MemoryPropertyFlags,
+            kind: ResourceKind) -> VdResult<Suballocation> {
+        let memory_type_index = self.device.memory_type_index(reqs.memory_type_bits(),
+            properties)?;
+        let strategy = self.strategy;
+        let pool_idx = match self.pools.iter()
+                .position(|p| p.memory_type_index == memory_type_index && p.kind == kind) {
+            Some(idx) => idx,
+            None => {
+                self.pools.push(Pool { memory_type_index, kind, blocks: Vec::new() });
+                self.pools.len() - 1
+            },
+        };
+        let pool = &mut self.pools[pool_idx];
+
+        if reqs.size() <= self.block_size {
+            for block in pool.blocks.iter_mut().filter(|b| !b.dedicated) {
+                if let Some(offset) = block.reserve(reqs, strategy) {
+                    return Ok(Suballocation { memory: block.memory.handle(), offset,
+                        size: reqs.size() });
+                }
+            }
+        }
+
+        let dedicated = reqs.size() > self.block_size;
+        let size = if dedicated { reqs.size() } else { self.block_size };
+        let memory = DeviceMemory::new(self.device.clone(), size, memory_type_index)?;
+        let mut block = PoolBlock::new(memory, size, dedicated);
+        let offset = block.reserve(reqs, strategy)
+            .expect("Allocator::allocate: request does not fit in a new block");
+        let suballocation = Suballocation { memory: block.memory.handle(), offset,
+            size: reqs.size() };
+        pool.blocks.push(block);
+        Ok(suballocation)
+    }
+
+    /// Returns `suballocation` to the block it was handed out from. Dedicated
+    /// allocations are freed immediately.
+    ///
+    /// Returns an error if `suballocation` was not handed out by this
+    /// allocator.
+    ///
+    /// ## Safety
+    ///
+    /// No resource bound to `suballocation` may be in use by the device, nor
+    /// be used again afterwards. `suballocation` must not already have been
+    /// freed.
+    pub unsafe fn free(&mut self, suballocation: Suballocation) -> VdResult<()> {
+        let strategy = self.strategy;
+        for pool in self.pools.iter_mut() {
+            let block_idx = match pool.blocks.iter()
+                    .position(|b| b.memory.handle() == suballocation.memory) {
+                Some(idx) => idx,
+                None => continue,
+            };
+            {
+                let block = &mut pool.blocks[block_idx];
+                let in_bounds = suballocation.offset.checked_add(suballocation.size)
+                    .map(|end| end <= block.size).unwrap_or(false);
+                if !in_bounds || block.live_count == 0 {
+                    return Err(format!("Allocator::free: invalid suballocation: {:?}",
+                        suballocation).into());
+                }
+                block.release(suballocation.offset, suballocation.size, strategy);
+            }
+            if pool.blocks[block_idx].dedicated {
+                pool.blocks.remove(block_idx);
+            }
+            return Ok(());
+        }
+        Err(format!("Allocator::free: the suballocation was not allocated by this allocator: \
+            {:?}", suballocation).into())
+    }
+
+    /// Returns the allocation strategy.
+    pub fn strategy(&self) -> AllocationStrategy {
+        self.strategy
+    }
+
+    /// Returns the block size.
+    pub fn block_size(&self) -> DeviceSize {
+        self.block_size
+    }
+
+    /// Returns current memory usage statistics.
+    pub fn stats(&self) -> AllocatorStats {
+        let mut stats = AllocatorStats::default();
+        for block in self.pools.iter().flat_map(|p| p.blocks.iter()) {
+            stats.allocated_bytes += block.size;
+            stats.used_bytes += block.used;
+            stats.block_count += 1;
+            if block.dedicated { stats.dedicated_count += 1; }
+        }
+        stats
+    }
+
+    /// Returns a reference to the associated device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use loader::null;
+    use ::{MemoryRequirements, DeviceSize, DeviceMemory, MemoryPropertyFlags};
+    use super::{ResourceKind, LinearAllocator, placement_offset, Allocator, AllocationStrategy,
+        AllocatorStats, PoolBlock};
+
+    fn reqs(size: DeviceSize, alignment: DeviceSize) -> MemoryRequirements {
+        MemoryRequirements::builder()
+            .size(size)
+            .alignment(alignment)
+            .memory_type_bits(1)
+            .build()
+    }
+
+    fn pool_block(size: DeviceSize) -> PoolBlock {
+        PoolBlock::new(DeviceMemory::new(null::device(), size, 0).unwrap(), size, false)
+    }
+
+    fn stats(allocated_bytes: DeviceSize, used_bytes: DeviceSize, block_count: usize,
+            dedicated_count: usize) -> AllocatorStats {
+        AllocatorStats { allocated_bytes, used_bytes, block_count, dedicated_count }
+    }
+
+    #[test]
+    fn placement_offset_separates_linear_and_optimal_pages() {
+        assert_eq!(placement_offset(0, None, ResourceKind::Optimal, 256, 1024), 0);
+        assert_eq!(placement_offset(100, Some(ResourceKind::Linear), ResourceKind::Linear,
+            64, 1024), 128);
+        // A change of kind moves the resource onto the next page.
+        assert_eq!(placement_offset(100, Some(ResourceKind::Linear), ResourceKind::Optimal,
+            64, 1024), 1024);
+        assert_eq!(placement_offset(2048, Some(ResourceKind::Optimal), ResourceKind::Linear,
+            64, 1024), 2048);
+        // The first resource in a block has no neighbor.
+        assert_eq!(placement_offset(100, None, ResourceKind::Optimal, 64, 1024), 128);
+    }
+
+    #[test]
+    fn linear_allocator_fills_blocks_in_order() {
+        let device = null::device();
+        let mut allocator = LinearAllocator::new(device, 0, 4096).unwrap();
+        assert_eq!(allocator.block_count(), 0);
+
+        let a = allocator.allocate(&reqs(1000, 256), ResourceKind::Linear).unwrap();
+        let b = allocator.allocate(&reqs(1000, 256), ResourceKind::Linear).unwrap();
+        assert_eq!((a.offset(), b.offset()), (0, 1024));
+        assert_eq!(a.memory(), b.memory());
+        let c = allocator.allocate(&reqs(100, 16), ResourceKind::Optimal).unwrap();
+        assert_eq!(c.offset(), null::BUFFER_IMAGE_GRANULARITY * 3);
+        assert_eq!(allocator.block_count(), 1);
+
+        // Too large for the remainder of the first block.
+        let d = allocator.allocate(&reqs(2000, 256), ResourceKind::Optimal).unwrap();
+        assert_eq!(d.offset(), 0);
+        assert!(d.memory() != a.memory());
+        // Larger than the block size.
+        let e = allocator.allocate(&reqs(10000, 256), ResourceKind::Linear).unwrap();
+        assert_eq!(allocator.block_count(), 3);
+        assert_eq!(allocator.allocated_size(), 4096 * 2 + 10000);
+        assert_eq!(e.size(), 10000);
+
+        // Blocks are reused from the first after a reset.
+        unsafe { allocator.reset(); }
+        let f = allocator.allocate(&reqs(3000, 256), ResourceKind::Optimal).unwrap();
+        assert_eq!((f.memory(), f.offset()), (a.memory(), 0));
+        let g = allocator.allocate(&reqs(3000, 256), ResourceKind::Optimal).unwrap();
+        assert_eq!((g.memory(), g.offset()), (d.memory(), 0));
+        assert_eq!(allocator.block_count(), 3);
+    }
+
+    #[test]
+    fn linear_allocator_rejects_invalid_arguments() {
+        let device = null::device();
+        assert!(LinearAllocator::new(device.clone(), 3, 4096).is_err());
+        assert!(LinearAllocator::new(device.clone(), 0, 0).is_err());
+        let mut allocator = LinearAllocator::new(device, 0, 4096).unwrap();
+        let reqs = MemoryRequirements::builder().size(64).alignment(64).memory_type_bits(2).build();
+        assert!(allocator.allocate(&reqs, ResourceKind::Linear).is_err());
+        assert_eq!(allocator.block_count(), 0);
+    }
+
+    #[test]
+    fn pool_block_merges_freed_ranges() {
+        let free_list = AllocationStrategy::FreeList;
+        let mut block = pool_block(4096);
+        let offsets: Vec<_> = (0..3).map(|_| block.reserve(&reqs(1024, 256), free_list).unwrap())
+            .collect();
+        assert_eq!(offsets, [0, 1024, 2048]);
+        assert_eq!(block.free, [(3072, 1024)]);
+
+        block.release(0, 1024, free_list);
+        assert_eq!(block.free, [(0, 1024), (3072, 1024)]);
+        // Merged with the following range:
+        block.release(2048, 1024, free_list);
+        assert_eq!(block.free, [(0, 1024), (2048, 2048)]);
+        // Merged with both neighbors:
+        block.release(1024, 1024, free_list);
+        assert_eq!(block.free, [(0, 4096)]);
+        assert_eq!((block.used, block.live_count), (0, 0));
+
+        for _ in 0..3 { block.reserve(&reqs(1024, 256), free_list).unwrap(); }
+        block.release(0, 1024, free_list);
+        // Merged with the preceding range:
+        block.release(1024, 1024, free_list);
+        assert_eq!(block.free, [(0, 2048), (3072, 1024)]);
+        assert_eq!((block.used, block.live_count), (1024, 1));
+    }
+
+    #[test]
+    fn pool_block_aligns_regions() {
+        let free_list = AllocationStrategy::FreeList;
+        let mut block = pool_block(4096);
+        assert_eq!(block.reserve(&reqs(100, 1), free_list), Some(0));
+        assert_eq!(block.reserve(&reqs(100, 256), free_list), Some(256));
+        assert_eq!(block.free, [(100, 156), (356, 3740)]);
+        // The first gap left by alignment which fits is used:
+        assert_eq!(block.reserve(&reqs(64, 64), free_list), Some(128));
+        assert_eq!(block.free, [(100, 28), (192, 64), (356, 3740)]);
+        assert_eq!(block.reserve(&reqs(4096, 1), free_list), None);
+
+        let linear = AllocationStrategy::Linear;
+        let mut block = pool_block(4096);
+        assert_eq!(block.reserve(&reqs(100, 1), linear), Some(0));
+        assert_eq!(block.reserve(&reqs(100, 256), linear), Some(256));
+        assert_eq!(block.reserve(&reqs(64, 64), linear), Some(384));
+        assert_eq!(block.reserve(&reqs(3648, 1), linear), Some(448));
+        assert_eq!(block.reserve(&reqs(1, 1), linear), None);
+    }
+
+    #[test]
+    fn allocator_reuses_freed_regions() {
+        let props = MemoryPropertyFlags::DEVICE_LOCAL;
+        let mut allocator = Allocator::new(null::device(), AllocationStrategy::FreeList, 4096)
+            .unwrap();
+        assert_eq!(allocator.stats(), stats(0, 0, 0, 0));
+
+        let a = allocator.allocate(&reqs(1024, 256), props, ResourceKind::Linear).unwrap();
+        let b = allocator.allocate(&reqs(1024, 256), props, ResourceKind::Linear).unwrap();
+        assert_eq!((a.offset(), b.offset()), (0, 1024));
+        assert_eq!(a.memory(), b.memory());
+        assert_eq!(allocator.stats(), stats(4096, 2048, 1, 0));
+
+        unsafe { allocator.free(a).unwrap(); }
+        assert_eq!(allocator.stats(), stats(4096, 1024, 1, 0));
+        let c = allocator.allocate(&reqs(512, 256), props, ResourceKind::Linear).unwrap();
+        assert_eq!((c.memory(), c.offset()), (a.memory(), 0));
+        assert_eq!(allocator.stats(), stats(4096, 1536, 1, 0));
+
+        // Resources of another kind are kept in a separate block:
+        let d = allocator.allocate(&reqs(512, 256), props, ResourceKind::Optimal).unwrap();
+        assert!(d.memory() != a.memory());
+        assert_eq!(allocator.stats(), stats(8192, 2048, 2, 0));
+
+        unsafe {
+            allocator.free(b).unwrap();
+            allocator.free(c).unwrap();
+            allocator.free(d).unwrap();
+        }
+        // Blocks are kept once empty:
+        assert_eq!(allocator.stats(), stats(8192, 0, 2, 0));
+        let e = allocator.allocate(&reqs(4096, 256), props, ResourceKind::Linear).unwrap();
+        assert_eq!((e.memory(), e.offset()), (a.memory(), 0));
+        assert_eq!(allocator.stats(), stats(8192, 4096, 2, 0));
+    }
+
+    #[test]
+    fn allocator_dedicates_large_allocations() {
+        let props = MemoryPropertyFlags::DEVICE_LOCAL;
+        let mut allocator = Allocator::new(null::device(), AllocationStrategy::FreeList, 4096)
+            .unwrap();
+        let a = allocator.allocate(&reqs(10000, 256), props, ResourceKind::Linear).unwrap();
+        assert_eq!((a.offset(), a.size()), (0, 10000));
+        assert_eq!(allocator.stats(), stats(10000, 10000, 1, 1));
+
+        // Dedicated blocks are not shared:
+        let b = allocator.allocate(&reqs(1024, 256), props, ResourceKind::Linear).unwrap();
+        assert!(b.memory() != a.memory());
+        assert_eq!(allocator.stats(), stats(14096, 11024, 2, 1));
+
+        // And are freed along with their suballocation:
+        unsafe { allocator.free(a).unwrap(); }
+        assert_eq!(allocator.stats(), stats(4096, 1024, 1, 0));
+        unsafe { assert!(allocator.free(a).is_err()); }
+    }
+
+    #[test]
+    fn linear_strategy_reclaims_empty_blocks() {
+        let props = MemoryPropertyFlags::DEVICE_LOCAL;
+        let mut allocator = Allocator::new(null::device(), AllocationStrategy::Linear, 4096)
+            .unwrap();
+        let a = allocator.allocate(&reqs(1024, 256), props, ResourceKind::Linear).unwrap();
+        let b = allocator.allocate(&reqs(1024, 256), props, ResourceKind::Linear).unwrap();
+        unsafe { allocator.free(a).unwrap(); }
+        assert_eq!(allocator.stats(), stats(4096, 1024, 1, 0));
+
+        // Freed regions are not reused while the block holds others:
+        let c = allocator.allocate(&reqs(1024, 256), props, ResourceKind::Linear).unwrap();
+        assert_eq!((c.memory(), c.offset()), (a.memory(), 2048));
+        let d = allocator.allocate(&reqs(2048, 256), props, ResourceKind::Linear).unwrap();
+        assert!(d.memory() != a.memory());
+        assert_eq!(allocator.stats(), stats(8192, 4096, 2, 0));
+
+        // The block is reclaimed once all of its regions are freed:
+        unsafe {
+            allocator.free(b).unwrap();
+            allocator.free(c).unwrap();
+        }
+        assert_eq!(allocator.stats(), stats(8192, 2048, 2, 0));
+        let e = allocator.allocate(&reqs(4096, 256), props, ResourceKind::Linear).unwrap();
+        assert_eq!((e.memory(), e.offset()), (a.memory(), 0));
+        assert_eq!(allocator.stats(), stats(8192, 6144, 2, 0));
+    }
+
+    #[test]
+    fn allocator_rejects_invalid_arguments() {
+        let device = null::device();
+        assert!(Allocator::new(device.clone(), AllocationStrategy::FreeList, 0).is_err());
+        let mut allocator = Allocator::new(device, AllocationStrategy::FreeList, 4096).unwrap();
+        // No memory type permitted by the requirements has the properties:
+        assert!(allocator.allocate(&reqs(64, 64), MemoryPropertyFlags::HOST_VISIBLE,
+            ResourceKind::Linear).is_err());
+        assert_eq!(allocator.stats(), stats(0, 0, 0, 0));
+
+        let a = allocator.allocate(&reqs(64, 64), MemoryPropertyFlags::DEVICE_LOCAL,
+            ResourceKind::Linear).unwrap();
+        unsafe {
+            allocator.free(a).unwrap();
+            // Already freed:
+            assert!(allocator.free(a).is_err());
+        }
+    }
+}
@@ -0,0 +1,471 @@
+//! Host memory allocation callbacks.
+//!
+//! Vulkan lets applications supply the allocator used for host memory
+//! allocated by the implementation on their behalf. `AllocationCallbacks`
+//! wraps a Rust type implementing `HostAllocator` in the `extern "system"`
+//! trampolines and `VkAllocationCallbacks` structure the API expects.
+//!
+//! ### Lifetime
+//!
+//! Objects must be destroyed using callbacks compatible with those used to
+//! create them, and the callbacks must remain valid until then. The
+//! simplest way to guarantee both is to hand an `AllocationCallbacks` to
+//! `DeviceBuilder::allocation_callbacks`: the device then owns it, uses it
+//! for itself and for every object created or destroyed through it without
+//! an explicit allocator, and, because every wrapper type holds a clone of
+//! its `Device`, keeps it alive until the last such object is destroyed.
+//!
+//! Pointers returned by `AllocationCallbacks::as_raw` are only valid for as
+//! long as the `AllocationCallbacks` they came from is neither moved nor
+//! dropped.
+
+use std::fmt;
+use std::ptr;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::alloc::{self, Layout};
+use libc::c_void;
+use num_traits::FromPrimitive;
+use vks;
+use ::{SystemAllocationScope, InternalAllocationType};
+
+
+/// An allocator for host memory requested by a Vulkan implementation.
+///
+/// Implementations work in terms of `Layout`s; `AllocationCallbacks`
+/// records the layout of each allocation alongside it so that it can be
+/// provided again to `reallocate` and `free`, which the API does not do.
+///
+/// Panics are caught before they reach the implementation: a panicking
+/// `allocate` or `reallocate` is reported as an allocation failure and a
+/// panicking `free` leaks the allocation.
+pub unsafe trait HostAllocator: Send + Sync + 'static {
+    /// Allocates a block of memory described by `layout`, returning null on
+    /// failure.
+    fn allocate(&self, layout: Layout, scope: SystemAllocationScope) -> *mut u8;
+
+    /// Frees a block previously returned by `allocate` or `reallocate` with
+    /// the same `layout`.
+    fn free(&self, ptr: *mut u8, layout: Layout);
+
+    /// Resizes a block previously returned by `allocate` or `reallocate`
+    /// with `layout` to `new_size` bytes, preserving its contents up to the
+    /// lesser of the two sizes. Returns null, leaving the original block
+    /// untouched, on failure.
+    ///
+    /// Defaults to allocating a new block, copying, and freeing the
+    /// original.
+    fn reallocate(&self, ptr: *mut u8, layout: Layout, new_size: usize,
+            scope: SystemAllocationScope) -> *mut u8 {
+        let new_layout = match Layout::from_size_align(new_size, layout.align()) {
+            Ok(l) => l,
+            Err(_) => return ptr::null_mut(),
+        };
+        let new_ptr = self.allocate(new_layout, scope);
+        if !new_ptr.is_null() {
+            unsafe { ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size)); }
+            self.free(ptr, layout);
+        }
+        new_ptr
+    }
+
+    /// Called when the implementation allocates memory for its own use
+    /// (such as executable memory) without calling `allocate`.
+    fn internal_allocation(&self, _size: usize, _allocation_type: InternalAllocationType,
+            _scope: SystemAllocationScope) {}
+
+    /// Called when memory reported by `internal_allocation` is freed.
+    fn internal_free(&self, _size: usize, _allocation_type: InternalAllocationType,
+            _scope: SystemAllocationScope) {}
+}
+
+
+/// The layout of an allocation, stored immediately before the block handed
+/// to the implementation.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Header {
+    size: usize,
+    align: usize,
+}
+
+/// Returns the offset of the user block from the start of the underlying
+/// allocation for a block aligned to `align`.
+fn header_offset(align: usize) -> usize {
+    let header_size = mem::size_of::<Header>();
+    (header_size + align - 1) / align * align
+}
+
+/// Returns the layout of the underlying allocation for a user block of
+/// `size` bytes aligned to `align`.
+fn outer_layout(size: usize, align: usize) -> Option<Layout> {
+    let align = align.max(mem::align_of::<Header>());
+    let total = header_offset(align).checked_add(size)?;
+    Layout::from_size_align(total, align).ok()
+}
+
+unsafe fn read_header(block: *mut c_void) -> Header {
+    ptr::read_unaligned((block as *mut u8).offset(-(mem::size_of::<Header>() as isize))
+        as *const Header)
+}
+
+unsafe fn alloc_block<A: HostAllocator>(allocator: &A, size: usize, align: usize,
+        scope: SystemAllocationScope) -> *mut c_void {
+    if size == 0 || align == 0 || !align.is_power_of_two() { return ptr::null_mut(); }
+    let layout = match outer_layout(size, align) {
+        Some(l) => l,
+        None => return ptr::null_mut(),
+    };
+    let base = allocator.allocate(layout, scope);
+    if base.is_null() { return ptr::null_mut(); }
+    let block = base.offset(header_offset(layout.align()) as isize);
+    ptr::write_unaligned(block.offset(-(mem::size_of::<Header>() as isize)) as *mut Header,
+        Header { size, align });
+    block as *mut c_void
+}
+
+unsafe fn free_block<A: HostAllocator>(allocator: &A, block: *mut c_void) {
+    if block.is_null() { return; }
+    let header = read_header(block);
+    let layout = outer_layout(header.size, header.align)
+        .expect("AllocationCallbacks: corrupt allocation header");
+    let base = (block as *mut u8).offset(-(header_offset(layout.align()) as isize));
+    allocator.free(base, layout);
+}
+
+fn scope_from_raw(scope: vks::VkSystemAllocationScope) -> SystemAllocationScope {
+    SystemAllocationScope::from_i32(scope as i32).unwrap_or(SystemAllocationScope::Object)
+}
+
+fn allocation_type_from_raw(allocation_type: vks::VkInternalAllocationType)
+        -> InternalAllocationType {
+    InternalAllocationType::from_i32(allocation_type as i32)
+        .unwrap_or(InternalAllocationType::Executable)
+}
+
+unsafe extern "system" fn allocation_fn<A: HostAllocator>(user_data: *mut c_void, size: usize,
+        alignment: usize, scope: vks::VkSystemAllocationScope) -> *mut c_void {
+    let allocator = &*(user_data as *const A);
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        alloc_block(allocator, size, alignment, scope_from_raw(scope))
+    })).unwrap_or(ptr::null_mut())
+}
+
+unsafe extern "system" fn reallocation_fn<A: HostAllocator>(user_data: *mut c_void,
+        original: *mut c_void, size: usize, alignment: usize,
+        scope: vks::VkSystemAllocationScope) -> *mut c_void {
+    let allocator = &*(user_data as *const A);
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        if original.is_null() {
+            return alloc_block(allocator, size, alignment, scope_from_raw(scope));
+        }
+        if size == 0 {
+            free_block(allocator, original);
+            return ptr::null_mut();
+        }
+        let header = read_header(original);
+        let old_layout = match outer_layout(header.size, header.align) {
+            Some(l) => l,
+            None => return ptr::null_mut(),
+        };
+        // The alignment must match the original allocation's:
+        if alignment != header.align { return ptr::null_mut(); }
+        let new_layout = match outer_layout(size, alignment) {
+            Some(l) => l,
+            None => return ptr::null_mut(),
+        };
+        let offset = header_offset(old_layout.align());
+        let base = (original as *mut u8).offset(-(offset as isize));
+        let new_base = allocator.reallocate(base, old_layout, new_layout.size(),
+            scope_from_raw(scope));
+        if new_base.is_null() { return ptr::null_mut(); }
+        let block = new_base.offset(offset as isize);
+        ptr::write_unaligned(block.offset(-(mem::size_of::<Header>() as isize)) as *mut Header,
+            Header { size, align: alignment });
+        block as *mut c_void
+    })).unwrap_or(ptr::null_mut())
+}
+
+unsafe extern "system" fn free_fn<A: HostAllocator>(user_data: *mut c_void,
+        memory: *mut c_void) {
+    let allocator = &*(user_data as *const A);
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| free_block(allocator, memory)));
+}
+
+unsafe extern "system" fn internal_allocation_fn<A: HostAllocator>(user_data: *mut c_void,
+        size: usize, allocation_type: vks::VkInternalAllocationType,
+        scope: vks::VkSystemAllocationScope) {
+    let allocator = &*(user_data as *const A);
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        allocator.internal_allocation(size, allocation_type_from_raw(allocation_type),
+            scope_from_raw(scope))
+    }));
+}
+
+unsafe extern "system" fn internal_free_fn<A: HostAllocator>(user_data: *mut c_void,
+        size: usize, allocation_type: vks::VkInternalAllocationType,
+        scope: vks::VkSystemAllocationScope) {
+    let allocator = &*(user_data as *const A);
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        allocator.internal_free(size, allocation_type_from_raw(allocation_type),
+            scope_from_raw(scope))
+    }));
+}
+
+
+/// A `VkAllocationCallbacks` structure backed by a `HostAllocator`.
+///
+/// See the module documentation for lifetime requirements.
+#[derive(Clone)]
+pub struct AllocationCallbacks {
+    raw: vks::VkAllocationCallbacks,
+    _allocator: Arc<HostAllocator>,
+}
+
+impl AllocationCallbacks {
+    /// Returns new callbacks forwarding to `allocator`.
+    pub fn new<A: HostAllocator>(allocator: Arc<A>) -> AllocationCallbacks {
+        let raw = vks::VkAllocationCallbacks {
+            pUserData: &*allocator as *const A as *mut c_void,
+            pfnAllocation: Some(allocation_fn::<A>),
+            pfnReallocation: Some(reallocation_fn::<A>),
+            pfnFree: Some(free_fn::<A>),
+            pfnInternalAllocation: Some(internal_allocation_fn::<A>),
+            pfnInternalFree: Some(internal_free_fn::<A>),
+        };
+        AllocationCallbacks { raw, _allocator: allocator }
+    }
+
+    /// Returns a pointer to the raw structure, suitable for passing as the
+    /// `allocator` argument of `Device` and `Instance` functions.
+    ///
+    /// Only valid while `self` is neither moved nor dropped.
+    pub fn as_raw(&self) -> *const vks::VkAllocationCallbacks {
+        &self.raw
+    }
+}
+
+impl fmt::Debug for AllocationCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AllocationCallbacks")
+            .field("user_data", &self.raw.pUserData)
+            .finish()
+    }
+}
+
+unsafe impl Send for AllocationCallbacks {}
+unsafe impl Sync for AllocationCallbacks {}
+
+
+/// A `HostAllocator` using the global allocator which keeps count of
+/// allocations and bytes outstanding, useful for detecting leaks.
+#[derive(Debug, Default)]
+pub struct CountingHostAllocator {
+    allocations: AtomicUsize,
+    frees: AtomicUsize,
+    bytes: AtomicUsize,
+    internal_bytes: AtomicUsize,
+}
+
+impl CountingHostAllocator {
+    /// Returns a new counting allocator.
+    pub fn new() -> CountingHostAllocator {
+        CountingHostAllocator::default()
+    }
+
+    /// Returns the total number of allocations made, including
+    /// reallocations.
+    pub fn allocation_count(&self) -> usize {
+        self.allocations.load(Ordering::SeqCst)
+    }
+
+    /// Returns the total number of allocations freed, including blocks
+    /// replaced by reallocation.
+    pub fn free_count(&self) -> usize {
+        self.frees.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of allocations not yet freed.
+    pub fn live_allocations(&self) -> usize {
+        self.allocation_count().saturating_sub(self.free_count())
+    }
+
+    /// Returns the number of bytes currently allocated, including the
+    /// space used to record each allocation's layout.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bytes.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of bytes reported as allocated internally by the
+    /// implementation and not yet freed.
+    pub fn internal_bytes(&self) -> usize {
+        self.internal_bytes.load(Ordering::SeqCst)
+    }
+}
+
+unsafe impl HostAllocator for CountingHostAllocator {
+    fn allocate(&self, layout: Layout, _scope: SystemAllocationScope) -> *mut u8 {
+        let ptr = unsafe { alloc::alloc(layout) };
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::SeqCst);
+            self.bytes.fetch_add(layout.size(), Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    fn free(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { alloc::dealloc(ptr, layout); }
+        self.frees.fetch_add(1, Ordering::SeqCst);
+        self.bytes.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+
+    fn internal_allocation(&self, size: usize, _allocation_type: InternalAllocationType,
+            _scope: SystemAllocationScope) {
+        self.internal_bytes.fetch_add(size, Ordering::SeqCst);
+    }
+
+    fn internal_free(&self, size: usize, _allocation_type: InternalAllocationType,
+            _scope: SystemAllocationScope) {
+        self.internal_bytes.fetch_sub(size, Ordering::SeqCst);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+    use std::ptr;
+    use std::sync::Arc;
+    use std::alloc::Layout;
+    use vks;
+    use ::SystemAllocationScope;
+    use super::{AllocationCallbacks, CountingHostAllocator, HostAllocator, Header,
+        header_offset, outer_layout};
+
+    const SCOPE: vks::VkSystemAllocationScope = vks::VK_SYSTEM_ALLOCATION_SCOPE_OBJECT;
+
+    fn counting_callbacks() -> (Arc<CountingHostAllocator>, AllocationCallbacks) {
+        let allocator = Arc::new(CountingHostAllocator::new());
+        let callbacks = AllocationCallbacks::new(allocator.clone());
+        (allocator, callbacks)
+    }
+
+    #[test]
+    fn header_precedes_aligned_block() {
+        let header_size = mem::size_of::<Header>();
+        assert_eq!(header_offset(1), header_size);
+        assert_eq!(header_offset(header_size), header_size);
+        assert_eq!(header_offset(256), 256);
+
+        let layout = outer_layout(10, 1).unwrap();
+        assert_eq!(layout.align(), mem::align_of::<Header>());
+        assert_eq!(layout.size(), header_size + 10);
+        let layout = outer_layout(10, 64).unwrap();
+        assert_eq!((layout.size(), layout.align()), (64 + 10, 64));
+        assert!(outer_layout(usize::max_value(), 8).is_none());
+    }
+
+    #[test]
+    fn allocations_are_aligned_and_balanced() {
+        let (allocator, callbacks) = counting_callbacks();
+        let raw = &callbacks.raw;
+        let allocation_fn = raw.pfnAllocation.unwrap();
+        let reallocation_fn = raw.pfnReallocation.unwrap();
+        let free_fn = raw.pfnFree.unwrap();
+
+        unsafe {
+            let blocks: Vec<_> = [1, 8, 64, 4096].iter().map(|&align| {
+                let block = allocation_fn(raw.pUserData, 100, align, SCOPE);
+                assert!(!block.is_null());
+                assert_eq!(block as usize % align, 0);
+                block
+            }).collect();
+            assert_eq!(allocator.live_allocations(), 4);
+            assert!(allocator.allocated_bytes() >= 400);
+
+            // Contents up to the lesser size survive reallocation.
+            let block = blocks[2] as *mut u8;
+            for i in 0..100 { *block.offset(i) = i as u8; }
+            let grown = reallocation_fn(raw.pUserData, block as *mut _, 1000, 64, SCOPE);
+            assert!(!grown.is_null());
+            assert_eq!(grown as usize % 64, 0);
+            for i in 0..100 { assert_eq!(*(grown as *mut u8).offset(i), i as u8); }
+            let shrunk = reallocation_fn(raw.pUserData, grown, 10, 64, SCOPE);
+            for i in 0..10 { assert_eq!(*(shrunk as *mut u8).offset(i), i as u8); }
+            assert_eq!(allocator.live_allocations(), 4);
+
+            for &block in blocks.iter().take(2).chain(&blocks[3..]) {
+                free_fn(raw.pUserData, block);
+            }
+            free_fn(raw.pUserData, shrunk);
+            free_fn(raw.pUserData, ptr::null_mut());
+        }
+        assert_eq!(allocator.live_allocations(), 0);
+        assert_eq!(allocator.allocation_count(), 6);
+        assert_eq!(allocator.allocated_bytes(), 0);
+    }
+
+    #[test]
+    fn invalid_requests_return_null() {
+        let (allocator, callbacks) = counting_callbacks();
+        let raw = &callbacks.raw;
+        let allocation_fn = raw.pfnAllocation.unwrap();
+        let reallocation_fn = raw.pfnReallocation.unwrap();
+
+        unsafe {
+            assert!(allocation_fn(raw.pUserData, 0, 8, SCOPE).is_null());
+            assert!(allocation_fn(raw.pUserData, 16, 0, SCOPE).is_null());
+            assert!(allocation_fn(raw.pUserData, 16, 24, SCOPE).is_null());
+            assert_eq!(allocator.allocation_count(), 0);
+
+            // Reallocating null allocates; reallocating to zero frees.
+            let block = reallocation_fn(raw.pUserData, ptr::null_mut(), 16, 16, SCOPE);
+            assert!(!block.is_null());
+            // A changed alignment is refused, leaving the block intact.
+            assert!(reallocation_fn(raw.pUserData, block, 32, 32, SCOPE).is_null());
+            assert_eq!(allocator.live_allocations(), 1);
+            assert!(reallocation_fn(raw.pUserData, block, 0, 16, SCOPE).is_null());
+        }
+        assert_eq!(allocator.live_allocations(), 0);
+    }
+
+    #[test]
+    fn internal_allocations_are_reported() {
+        let (allocator, callbacks) = counting_callbacks();
+        let raw = &callbacks.raw;
+        unsafe {
+            raw.pfnInternalAllocation.unwrap()(raw.pUserData, 4096,
+                vks::VK_INTERNAL_ALLOCATION_TYPE_EXECUTABLE, SCOPE);
+            assert_eq!(allocator.internal_bytes(), 4096);
+            raw.pfnInternalFree.unwrap()(raw.pUserData, 4096,
+                vks::VK_INTERNAL_ALLOCATION_TYPE_EXECUTABLE, SCOPE);
+        }
+        assert_eq!(allocator.internal_bytes(), 0);
+        assert_eq!(allocator.allocation_count(), 0);
+    }
+
+    struct PanickingAllocator;
+
+    unsafe impl HostAllocator for PanickingAllocator {
+        fn allocate(&self, _layout: Layout, _scope: SystemAllocationScope) -> *mut u8 {
+            panic!("allocation refused");
+        }
+
+        fn free(&self, _ptr: *mut u8, _layout: Layout) {
+            panic!("free refused");
+        }
+    }
+
+    #[test]
+    fn panics_do_not_cross_the_boundary() {
+        let callbacks = AllocationCallbacks::new(Arc::new(PanickingAllocator));
+        let raw = &callbacks.raw;
+        unsafe {
+            assert!(raw.pfnAllocation.unwrap()(raw.pUserData, 16, 8, SCOPE).is_null());
+            assert!(raw.pfnReallocation.unwrap()(raw.pUserData, ptr::null_mut(), 16, 8, SCOPE)
+                .is_null());
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Convenience constructors for pipeline barrier structures.
+
+use ::{MemoryBarrier, ImageSubresourceRange, ImageAspectFlags, AccessFlags, Format,
+    REMAINING_MIP_LEVELS, REMAINING_ARRAY_LAYERS};
+
+
+impl<'s> MemoryBarrier<'s> {
+    /// Returns a new global memory barrier making writes of the
+    /// `src_access_mask` types available and visible to accesses of the
+    /// `dst_access_mask` types.
+    pub fn new(src_access_mask: AccessFlags, dst_access_mask: AccessFlags) -> MemoryBarrier<'s> {
+        MemoryBarrier::builder()
+            .src_access_mask(src_access_mask)
+            .dst_access_mask(dst_access_mask)
+            .build()
+    }
+}
+
+
+impl ImageSubresourceRange {
+    /// Returns a range covering the color aspect of every mip level and array
+    /// layer of an image.
+    pub fn color_full() -> ImageSubresourceRange {
+        ImageSubresourceRange::full(ImageAspectFlags::COLOR)
+    }
+
+    /// Returns a range covering the depth aspect of every mip level and array
+    /// layer of an image.
+    ///
+    /// Images with a combined depth/stencil format must have both aspects
+    /// transitioned together; use `format_full` for those.
+    pub fn depth_full() -> ImageSubresourceRange {
+        ImageSubresourceRange::full(ImageAspectFlags::DEPTH)
+    }
+
+    /// Returns a range covering every aspect, mip level, and array layer of
+    /// an image of `format`, as given by `Format::aspect_flags`.
+    pub fn format_full(format: Format) -> ImageSubresourceRange {
+        ImageSubresourceRange::full(format.aspect_flags())
+    }
+
+    fn full(aspect_mask: ImageAspectFlags) -> ImageSubresourceRange {
+        ImageSubresourceRange::builder()
+            .aspect_mask(aspect_mask)
+            .base_mip_level(0)
+            .level_count(REMAINING_MIP_LEVELS)
+            .base_array_layer(0)
+            .layer_count(REMAINING_ARRAY_LAYERS)
+            .build()
+    }
+}
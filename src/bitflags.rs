@@ -3,6 +3,7 @@
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct InstanceCreateFlags: u32 {
         const CREATE_FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -12,6 +13,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct FormatFeatureFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const SAMPLED_IMAGE = 0x00000001;
@@ -38,6 +40,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ImageUsageFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const TRANSFER_SRC = 0x00000001;
@@ -55,6 +58,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ImageCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const SPARSE_BINDING = 0x00000001;
@@ -73,6 +77,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SampleCountFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const COUNT_1 = 0x00000001;
@@ -89,6 +94,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct QueueFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const GRAPHICS = 0x00000001;
@@ -102,6 +108,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct MemoryPropertyFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const DEVICE_LOCAL = 0x00000001;
@@ -116,6 +123,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct MemoryHeapFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const DEVICE_LOCAL = 0x00000001;
@@ -129,6 +137,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DeviceCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -138,6 +147,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DeviceQueueCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -147,6 +157,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineStageFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const TOP_OF_PIPE = 0x00000001;
@@ -176,6 +187,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct MemoryMapFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -185,6 +197,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ImageAspectFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const COLOR = 0x00000001;
@@ -198,6 +211,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SparseImageFormatFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const SINGLE_MIPTAIL = 0x00000001;
@@ -210,6 +224,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SparseMemoryBindFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const METADATA = 0x00000001;
@@ -220,6 +235,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct FenceCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const SIGNALED = 0x00000001;
@@ -230,6 +246,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SemaphoreCreateFlags: u32 {
         const SEMAPHORE_CREATE_FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -239,6 +256,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct EventCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -248,6 +266,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct QueryPoolCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -257,6 +276,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct QueryPipelineStatisticFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const INPUT_ASSEMBLY_VERTICES = 0x00000001;
@@ -277,6 +297,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct QueryResultFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const RESULT_64 = 0x00000001;
@@ -290,6 +311,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct BufferCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const SPARSE_BINDING = 0x00000001;
@@ -302,6 +324,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct BufferUsageFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const TRANSFER_SRC = 0x00000001;
@@ -320,6 +343,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct BufferViewCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -329,6 +353,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ImageViewCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -338,6 +363,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ShaderModuleCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -347,6 +373,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineCacheCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -356,6 +383,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const DISABLE_OPTIMIZATION = 0x00000001;
@@ -373,6 +401,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineShaderStageCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -382,6 +411,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ShaderStageFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const VERTEX = 0x00000001;
@@ -399,6 +429,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineVertexInputStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -408,6 +439,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineInputAssemblyStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -417,6 +449,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineTessellationStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -426,6 +459,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineViewportStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -435,6 +469,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineRasterizationStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -444,6 +479,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct CullModeFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const NONE = 0;
@@ -457,6 +493,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineMultisampleStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -466,6 +503,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineDepthStencilStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -475,6 +513,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineColorBlendStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -484,6 +523,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ColorComponentFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const R = 0x00000001;
@@ -497,6 +537,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineDynamicStateCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -506,6 +547,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineLayoutCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -515,6 +557,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SamplerCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -524,9 +567,11 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DescriptorSetLayoutCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const PUSH_DESCRIPTOR_KHR = 0x00000001;
+        const UPDATE_AFTER_BIND_POOL_EXT = 0x00000002;
     }
 }
 
@@ -534,9 +579,11 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DescriptorPoolCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const FREE_DESCRIPTOR_SET = 0x00000001;
+        const UPDATE_AFTER_BIND_EXT = 0x00000002;
     }
 }
 
@@ -544,6 +591,21 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct DescriptorBindingFlagsExt: u32 {
+        const FLAG_BITS_MAX_ENUM_EXT = 0x7fffffff;
+        const UPDATE_AFTER_BIND_EXT = 0x00000001;
+        const UPDATE_UNUSED_WHILE_PENDING_EXT = 0x00000002;
+        const PARTIALLY_BOUND_EXT = 0x00000004;
+        const VARIABLE_DESCRIPTOR_COUNT_EXT = 0x00000008;
+    }
+}
+
+
+bitflags! {
+    #[repr(C)]
+    #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DescriptorPoolResetFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -553,6 +615,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct FramebufferCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -562,6 +625,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct RenderPassCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
     }
@@ -571,6 +635,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct AttachmentDescriptionFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const MAY_ALIAS = 0x00000001;
@@ -581,6 +646,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SubpassDescriptionFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
 
@@ -595,6 +661,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct AccessFlags: u32 {
         const NONE = 0x00000001;
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
@@ -628,12 +695,12 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DependencyFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const BY_REGION = 0x00000001;
-
-        #[cfg(feature = "experimental")]
         const VIEW_LOCAL = 0x00000002;
+
         #[cfg(feature = "experimental")]
         const DEVICE_GROUP = 0x00000004;
     }
@@ -643,6 +710,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct CommandPoolCreateFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const TRANSIENT = 0x00000001;
@@ -654,6 +722,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct CommandPoolResetFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const RELEASE_RESOURCES = 0x00000001;
@@ -664,6 +733,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct CommandBufferUsageFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const ONE_TIME_SUBMIT = 0x00000001;
@@ -676,6 +746,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct QueryControlFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const PRECISE = 0x00000001;
@@ -686,6 +757,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct CommandBufferResetFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const RELEASE_RESOURCES = 0x00000001;
@@ -696,6 +768,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct StencilFaceFlags: u32 {
         const FLAG_BITS_MAX_ENUM = 0x7fffffff;
         const FRONT = 0x00000001;
@@ -708,6 +781,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SurfaceTransformFlagsKhr: u32 {
         const IDENTITY = 1;
         const ROTATE_90 = 2;
@@ -727,6 +801,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct CompositeAlphaFlagsKhr: u32 {
         const OPAQUE = 1;
         const PRE_MULTIPLIED = 2;
@@ -741,6 +816,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SwapchainCreateFlagsKhr: u32 {
         const BIND_SFR = 1;
         const FLAG_BITS_MAX_ENUM_KHR = 2147483647;
@@ -750,6 +826,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DisplayPlaneAlphaFlagsKhr: u32 {
         const OPAQUE = 1;
         const GLOBAL = 2;
@@ -763,6 +840,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalMemoryHandleTypeFlagsKhr: u32 {
         const OPAQUE_FD = 1;
         const OPAQUE_WIN32 = 2;
@@ -771,6 +849,8 @@ bitflags! {
         const D3D11_TEXTURE_KMT = 16;
         const D3D12_HEAP = 32;
         const D3D12_RESOURCE = 64;
+        const HOST_ALLOCATION_EXT = 128;
+        const HOST_MAPPED_FOREIGN_MEMORY_EXT = 256;
         const FLAG_BITS_MAX_ENUM_KHR = 2147483647;
     }
 }
@@ -778,6 +858,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalMemoryFeatureFlagsKhr: u32 {
         const DEDICATED_ONLY = 1;
         const EXPORTABLE = 2;
@@ -789,6 +870,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalSemaphoreHandleTypeFlagsKhr: u32 {
         const OPAQUE_FD = 1;
         const OPAQUE_WIN32 = 2;
@@ -802,6 +884,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalSemaphoreFeatureFlagsKhr: u32 {
         const EXPORTABLE = 1;
         const IMPORTABLE = 2;
@@ -812,6 +895,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SemaphoreImportFlagsKhr: u32 {
         const TEMPORARY = 1;
         const FLAG_BITS_MAX_ENUM_KHR = 2147483647;
@@ -821,6 +905,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalFenceHandleTypeFlagsKhr: u32 {
         const OPAQUE_FD = 1;
         const OPAQUE_WIN32 = 2;
@@ -833,6 +918,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalFenceFeatureFlagsKhr: u32 {
         const EXPORTABLE = 1;
         const IMPORTABLE = 2;
@@ -843,6 +929,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct FenceImportFlagsKhr: u32 {
         const TEMPORARY = 1;
         const FLAG_BITS_MAX_ENUM_KHR = 2147483647;
@@ -852,6 +939,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DebugReportFlagsExt:  u32 {
         const INFORMATION = 1;
         const WARNING = 2;
@@ -865,6 +953,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalMemoryHandleTypeFlagsNv:  u32 {
         const OPAQUE_WIN32 = 1;
         const OPAQUE_WIN32_KMT = 2;
@@ -877,6 +966,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ExternalMemoryFeatureFlagsNv:  u32 {
         const DEDICATED_ONLY = 1;
         const EXPORTABLE = 2;
@@ -888,6 +978,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PeerMemoryFeatureFlagsKhx:  u32 {
         const COPY_SRC = 1;
         const COPY_DST = 2;
@@ -900,6 +991,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct MemoryAllocateFlagsKhx:  u32 {
         const DEVICE_MASK = 1;
         const FLAG_BITS_MAX_ENUM_KHX = 2147483647;
@@ -909,6 +1001,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DeviceGroupPresentModeFlagsKhx:  u32 {
         const LOCAL = 1;
         const REMOTE = 2;
@@ -921,6 +1014,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct IndirectCommandsLayoutUsageFlagsNvx:  u32 {
         const UNORDERED_SEQUENCES = 1;
         const SPARSE_SEQUENCES = 2;
@@ -933,6 +1027,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ObjectEntryUsageFlagsNvx:  u32 {
         const GRAPHICS = 1;
         const COMPUTE = 2;
@@ -943,6 +1038,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct SurfaceCounterFlagsExt:  u32 {
         const VBLANK_EXT = 1;
         const FLAG_BITS_MAX_ENUM_EXT = 2147483647;
@@ -953,6 +1049,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DisplaySurfaceCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -962,6 +1059,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DisplayModeCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -971,6 +1069,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct AndroidSurfaceCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -980,6 +1079,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct MirSurfaceCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -989,6 +1089,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ViSurfaceCreateFlagsNN: u32 {
         const FLAG_BITS_MAX_ENUM_NN = 0x7fffffff;
     }
@@ -998,6 +1099,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct WaylandSurfaceCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -1007,6 +1109,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct Win32SurfaceCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -1016,6 +1119,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct XlibSurfaceCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -1025,6 +1129,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct XcbSurfaceCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -1034,6 +1139,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct ViSurfaceCreateFlagsNn: u32 {
         const FLAG_BITS_MAX_ENUM_NN = 0x7fffffff;
     }
@@ -1043,6 +1149,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct DescriptorUpdateTemplateCreateFlagsKhr: u32 {
         const FLAG_BITS_MAX_ENUM_KHR = 0x7fffffff;
     }
@@ -1052,6 +1159,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct IosSurfaceCreateFlagsMvk: u32 {
         const FLAG_BITS_MAX_ENUM_MVK = 0x7fffffff;
     }
@@ -1061,6 +1169,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct MacOsSurfaceCreateFlagsMvk: u32 {
         const FLAG_BITS_MAX_ENUM_MVK = 0x7fffffff;
     }
@@ -1070,6 +1179,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineViewportSwizzleStateCreateFlagsNv: u32 {
         const FLAG_BITS_MAX_ENUM_NV = 0x7fffffff;
     }
@@ -1079,6 +1189,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineDiscardRectangleStateCreateFlagsExt: u32 {
         const FLAG_BITS_MAX_ENUM_EXT = 0x7fffffff;
     }
@@ -1088,6 +1199,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineCoverageToColorStateCreateFlagsNv: u32 {
         const FLAG_BITS_MAX_ENUM_NV = 0x7fffffff;
     }
@@ -1097,6 +1209,7 @@ bitflags! {
 bitflags! {
     #[repr(C)]
     #[derive(Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct PipelineCoverageModulationStateCreateFlagsNv: u32 {
         const FLAG_BITS_MAX_ENUM_NV = 0x7fffffff;
     }
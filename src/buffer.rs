@@ -1,5 +1,6 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::marker::PhantomData;
+use libc::c_void;
 use vks;
 use ::{VdResult, Device, DeviceMemory, Handle};
 
@@ -9,6 +10,13 @@ use ::{VdResult, Device, DeviceMemory, Handle};
 pub struct BufferHandle(pub(crate) vks::VkBuffer);
 
 impl BufferHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkBuffer) -> BufferHandle {
+        BufferHandle(raw)
+    }
+
+    #[inline(always)]
     pub fn to_raw(&self) -> vks::VkBuffer {
         self.0
     }
@@ -29,6 +37,7 @@ struct Inner {
     handle: BufferHandle,
     memory_requirements: ::MemoryRequirements,
     device: Device,
+    memory: Mutex<Option<DeviceMemory>>,
 }
 
 impl Drop for Inner {
@@ -74,14 +83,24 @@ impl Buffer {
     /// the VkMemoryRequirements::size member in memory, starting from
     /// memoryOffset bytes, will be bound to the specified buffer.
     ///
+    /// A clone of `memory` is retained until this buffer is dropped, keeping
+    /// the allocation alive for at least as long as the buffer.
+    ///
     /// ## Safety
     ///
-    /// The caller must ensure that the bound memory is not in use when it is
-    /// dropped.
+    /// The caller must ensure that `memory` is compatible with this buffer's
+    /// memory requirements and that this buffer is not already bound.
     ///
     pub unsafe fn bind_memory(&self, memory: &DeviceMemory, offset: ::DeviceSize)
             -> VdResult<()> {
-        self.inner.device.bind_buffer_memory(self.inner.handle, memory.handle(), offset)
+        self.inner.device.bind_buffer_memory(self.inner.handle, memory.handle(), offset)?;
+        *self.inner.memory.lock().unwrap() = Some(memory.clone());
+        Ok(())
+    }
+
+    /// Returns the memory bound to this buffer using `bind_memory`, if any.
+    pub fn memory(&self) -> Option<DeviceMemory> {
+        self.inner.memory.lock().unwrap().clone()
     }
 
     /// Returns a reference to the associated device.
@@ -154,6 +173,19 @@ impl<'b> BufferBuilder<'b> {
         self
     }
 
+    /// Chains an `ExternalMemoryBufferCreateInfoKhr`, specifying the external
+    /// handle types which may be used with memory bound to this buffer.
+    pub fn external_memory<'s, 'p>(&'s mut self,
+            external_info: &'p mut ::ExternalMemoryBufferCreateInfoKhr)
+            -> &'s mut BufferBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            external_info.set_next(self.create_info.next());
+            self.create_info.set_next(external_info.as_raw() as *const _ as *const c_void);
+        }
+        self
+    }
+
     /// Creates and returns a new `Buffer`
     pub fn build(&self, device: Device) -> VdResult<Buffer> {
         let handle = unsafe { device.create_buffer(&self.create_info, None)? };
@@ -164,7 +196,41 @@ impl<'b> BufferBuilder<'b> {
                 handle,
                 device,
                 memory_requirements,
+                memory: Mutex::new(None),
             })
         })
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use ::{ExternalMemoryBufferCreateInfoKhr, ExternalMemoryHandleTypeFlagsKhr, BufferUsageFlags};
+    use super::BufferBuilder;
+
+    #[test]
+    fn external_memory_info_is_chained() {
+        let mut external_info = ExternalMemoryBufferCreateInfoKhr::builder()
+            .handle_types(ExternalMemoryHandleTypeFlagsKhr::OPAQUE_FD)
+            .build();
+        let external_ptr = external_info.as_raw() as *const vks::VkExternalMemoryBufferCreateInfoKHR;
+
+        let mut builder = BufferBuilder::new();
+        builder.size(256)
+            .usage(BufferUsageFlags::TRANSFER_SRC)
+            .external_memory(&mut external_info);
+
+        let create_info = builder.create_info.as_raw();
+        assert_eq!(create_info.size, 256);
+        assert_eq!(create_info.pNext as *const vks::VkExternalMemoryBufferCreateInfoKHR,
+            external_ptr);
+        unsafe {
+            let external = &*external_ptr;
+            assert_eq!(external.sType,
+                vks::VK_STRUCTURE_TYPE_EXTERNAL_MEMORY_BUFFER_CREATE_INFO_KHR);
+            assert_eq!(external.handleTypes, ExternalMemoryHandleTypeFlagsKhr::OPAQUE_FD.bits());
+            assert!(external.pNext.is_null());
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Structure chaining through `pNext`.
+//!
+//! Extension structures are attached to the structure they extend with
+//! `push_next`. Each call appends `next`, along with any structures already
+//! chained onto it, to the end of the chain so that structures appear in the
+//! order they were pushed.
+//!
+//! `Extends<B>` is implemented for each structure which may appear in the
+//! `pNext` chain of `B`, as listed in the specification's 'Valid Usage'
+//! sections, so that chaining an unrelated structure fails to compile.
+
+use vks;
+use ::{DeviceCreateInfo, InstanceCreateInfo, ImageCreateInfo, SamplerCreateInfo,
+    MemoryAllocateInfo, SwapchainCreateInfoKhr, PhysicalDeviceFeatures2Khr,
+    PhysicalDevice16BitStorageFeaturesKhr, PhysicalDeviceVariablePointerFeaturesKhr,
+    PhysicalDeviceDescriptorIndexingFeaturesExt, PhysicalDeviceMultiviewFeaturesKhr,
+    DebugReportCallbackCreateInfoExt, ValidationFlagsExt, ExternalMemoryImageCreateInfoKhr,
+    ExternalMemoryImageCreateInfoNv, DedicatedAllocationImageCreateInfoNv,
+    SamplerReductionModeCreateInfoExt, ExportMemoryAllocateInfoKhr, ImportMemoryFdInfoKhr,
+    ImportMemoryWin32HandleInfoKhr, ExportMemoryWin32HandleInfoKhr, MemoryDedicatedAllocateInfoKhr,
+    DedicatedAllocationMemoryAllocateInfoNv, ExportMemoryAllocateInfoNv,
+    ImportMemoryWin32HandleInfoNv, ExportMemoryWin32HandleInfoNv, SwapchainCounterCreateInfoExt,
+    ImportMemoryHostPointerInfoExt, PhysicalDeviceMemoryProperties2Khr,
+    PhysicalDeviceMemoryBudgetPropertiesExt};
+
+
+/// The `sType` and `pNext` members common to all extensible structures.
+///
+/// Corresponds to `VkBaseOutStructure`.
+#[derive(Debug)]
+#[repr(C)]
+pub struct BaseOutStructure {
+    pub s_type: vks::VkStructureType,
+    pub p_next: *mut BaseOutStructure,
+}
+
+
+/// Marks a structure which may be chained onto `B` using `push_next`.
+///
+/// Implementors must be `#[repr(C)]` and begin with `sType` and `pNext`
+/// members, as described by `BaseOutStructure`.
+pub unsafe trait Extends<B> {}
+
+
+/// Returns the last structure in the chain beginning at `base`.
+unsafe fn chain_end(mut base: *mut BaseOutStructure) -> *mut BaseOutStructure {
+    while !(*base).p_next.is_null() {
+        base = (*base).p_next;
+    }
+    base
+}
+
+/// Returns true if a structure with an `sType` of `s_type` appears in the
+/// chain beginning at `next`.
+unsafe fn chain_contains(mut next: *mut BaseOutStructure, s_type: vks::VkStructureType) -> bool {
+    while !next.is_null() {
+        if (*next).s_type == s_type { return true; }
+        next = (*next).p_next;
+    }
+    false
+}
+
+
+macro_rules! push_next {
+    ($($base:ident),+) => ($(
+        impl<'s> $base<'s> {
+            /// Appends `next`, and any structures already chained onto it,
+            /// to the end of this structure's `pNext` chain.
+            ///
+            /// `next` remains borrowed for as long as this structure.
+            pub fn push_next<'a, T>(&mut self, next: &'a mut T)
+                    where T: Extends<$base<'s>>, 'a: 's {
+                unsafe {
+                    let next_ptr = next as *mut T as *mut BaseOutStructure;
+                    debug_assert!(!chain_contains(self.next() as *mut BaseOutStructure,
+                        (*next_ptr).s_type), "{}::push_next: a structure of type {} is already \
+                        chained", stringify!($base), (*next_ptr).s_type);
+                    if self.next().is_null() {
+                        self.set_next(next_ptr as _);
+                    } else {
+                        (*chain_end(self.next() as *mut BaseOutStructure)).p_next = next_ptr;
+                    }
+                }
+            }
+        }
+    )+)
+}
+
+push_next!(DeviceCreateInfo, InstanceCreateInfo, ImageCreateInfo, SamplerCreateInfo,
+    MemoryAllocateInfo, SwapchainCreateInfoKhr, PhysicalDeviceMemoryProperties2Khr);
+
+
+macro_rules! extends {
+    ($base:ident: $($ext:ident),+) => ($(
+        unsafe impl<'a, 'b> Extends<$base<'a>> for $ext<'b> {}
+    )+)
+}
+
+extends!(DeviceCreateInfo: PhysicalDeviceFeatures2Khr, PhysicalDevice16BitStorageFeaturesKhr,
+    PhysicalDeviceVariablePointerFeaturesKhr, PhysicalDeviceDescriptorIndexingFeaturesExt,
+    PhysicalDeviceMultiviewFeaturesKhr);
+extends!(InstanceCreateInfo: DebugReportCallbackCreateInfoExt, ValidationFlagsExt);
+extends!(ImageCreateInfo: ExternalMemoryImageCreateInfoKhr, ExternalMemoryImageCreateInfoNv,
+    DedicatedAllocationImageCreateInfoNv);
+extends!(SamplerCreateInfo: SamplerReductionModeCreateInfoExt);
+extends!(MemoryAllocateInfo: ExportMemoryAllocateInfoKhr, ImportMemoryFdInfoKhr,
+    ImportMemoryWin32HandleInfoKhr, ExportMemoryWin32HandleInfoKhr, MemoryDedicatedAllocateInfoKhr,
+    DedicatedAllocationMemoryAllocateInfoNv, ExportMemoryAllocateInfoNv,
+    ImportMemoryWin32HandleInfoNv, ExportMemoryWin32HandleInfoNv, ImportMemoryHostPointerInfoExt);
+extends!(SwapchainCreateInfoKhr: SwapchainCounterCreateInfoExt);
+extends!(PhysicalDeviceMemoryProperties2Khr: PhysicalDeviceMemoryBudgetPropertiesExt);
+
+
+#[cfg(test)]
+mod tests {
+    use libc::c_void;
+    use vks;
+    use ::{MemoryAllocateInfo, ExportMemoryAllocateInfoKhr, ImportMemoryFdInfoKhr,
+        MemoryDedicatedAllocateInfoKhr};
+    use super::BaseOutStructure;
+
+    /// Returns the `sType` of each structure chained onto `info`, in order.
+    fn chain_types(info: &MemoryAllocateInfo) -> Vec<vks::VkStructureType> {
+        let mut s_types = Vec::new();
+        let mut next = info.next() as *const BaseOutStructure;
+        while !next.is_null() {
+            unsafe {
+                s_types.push((*next).s_type);
+                next = (*next).p_next;
+            }
+        }
+        s_types
+    }
+
+    #[test]
+    fn push_next_preserves_order() {
+        let mut export_info = ExportMemoryAllocateInfoKhr::default();
+        let mut import_info = ImportMemoryFdInfoKhr::default();
+        let mut dedicated_info = MemoryDedicatedAllocateInfoKhr::default();
+        let mut info = MemoryAllocateInfo::default();
+        assert!(chain_types(&info).is_empty());
+        info.push_next(&mut export_info);
+        info.push_next(&mut import_info);
+        info.push_next(&mut dedicated_info);
+        assert_eq!(chain_types(&info), [vks::VK_STRUCTURE_TYPE_EXPORT_MEMORY_ALLOCATE_INFO_KHR,
+            vks::VK_STRUCTURE_TYPE_IMPORT_MEMORY_FD_INFO_KHR,
+            vks::VK_STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO_KHR]);
+    }
+
+    #[test]
+    fn push_next_appends_an_existing_chain() {
+        let import_info = ImportMemoryFdInfoKhr::default();
+        let mut export_info = ExportMemoryAllocateInfoKhr::default();
+        unsafe { export_info.set_next(import_info.as_raw() as *const _ as *const c_void); }
+        let mut dedicated_info = MemoryDedicatedAllocateInfoKhr::default();
+        let mut info = MemoryAllocateInfo::default();
+        info.push_next(&mut export_info);
+        info.push_next(&mut dedicated_info);
+        assert_eq!(chain_types(&info), [vks::VK_STRUCTURE_TYPE_EXPORT_MEMORY_ALLOCATE_INFO_KHR,
+            vks::VK_STRUCTURE_TYPE_IMPORT_MEMORY_FD_INFO_KHR,
+            vks::VK_STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO_KHR]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "already chained")]
+    fn push_next_rejects_duplicate_structure_types() {
+        let mut export_info = ExportMemoryAllocateInfoKhr::default();
+        let mut other_export_info = ExportMemoryAllocateInfoKhr::default();
+        let mut info = MemoryAllocateInfo::default();
+        info.push_next(&mut export_info);
+        info.push_next(&mut other_export_info);
+    }
+}
@@ -1,4 +1,6 @@
 use std::sync::Arc;
+use std::{mem, slice};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use smallvec::SmallVec;
 use vks;
 use ::{VdResult, Device, Handle, CommandPool, CommandBufferUsageFlags, CommandBufferBeginInfo,
@@ -9,7 +11,8 @@ use ::{VdResult, Device, Handle, CommandPool, CommandBufferUsageFlags, CommandBu
     ImageCopy, IndexType, ImageBlit, Filter, ClearColorValue, ImageSubresourceRange,
     ClearDepthStencilValue, ClearAttachment, ImageResolve, QueryControlFlags, ClearRect,
     BufferHandle, EventHandle,Buffer, Image, Event, QueryPool, PipelineLayout, DescriptorSet,
-    PipelineHandle};
+    PipelineHandle, DeviceCommands, QueryScope, DebugMarkerScope, QueryType, CommandBufferLevel,
+    CommandBufferInheritanceInfo};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -17,6 +20,13 @@ use ::{VdResult, Device, Handle, CommandPool, CommandBufferUsageFlags, CommandBu
 pub struct CommandBufferHandle(pub(crate) vks::VkCommandBuffer);
 
 impl CommandBufferHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkCommandBuffer) -> CommandBufferHandle {
+        CommandBufferHandle(raw)
+    }
+
+    #[inline(always)]
     pub fn to_raw(&self) -> vks::VkCommandBuffer {
         self.0
     }
@@ -31,10 +41,50 @@ unsafe impl Handle for CommandBufferHandle {
     }
 }
 
+fn query_type_bit(query_type: QueryType) -> usize {
+    1 << i32::from(query_type)
+}
+
+
+// Values of `RecordingState::state`.
+#[cfg(feature = "validation")]
+const STATE_INITIAL: usize = 0;
+#[cfg(feature = "validation")]
+const STATE_RECORDING: usize = 1;
+#[cfg(feature = "validation")]
+const STATE_EXECUTABLE: usize = 2;
+
+// Values of `RecordingState::render_pass`.
+#[cfg(feature = "validation")]
+const RENDER_PASS_NONE: usize = 0;
+#[cfg(feature = "validation")]
+const RENDER_PASS_BEGUN: usize = 1;
+#[cfg(feature = "validation")]
+const RENDER_PASS_CONTINUED: usize = 2;
+
+
+/// The recording state of a command buffer, tracked by its methods when the
+/// `validation` feature is enabled.
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+struct RecordingState {
+    // Initial, recording, or executable.
+    state: AtomicUsize,
+    // Whether a render pass instance is active, and whether it was begun in
+    // this command buffer or inherited by a secondary command buffer.
+    render_pass: AtomicUsize,
+}
+
+
 #[derive(Debug)]
 struct Inner {
     handle: CommandBufferHandle,
     command_pool: CommandPool,
+    level: CommandBufferLevel,
+    // One bit per `QueryType` with a query currently active.
+    active_queries: AtomicUsize,
+    #[cfg(feature = "validation")]
+    recording: RecordingState,
 }
 
 impl Drop for Inner {
@@ -55,19 +105,42 @@ impl Drop for Inner {
 /// Dropping this `CommandBuffer` will cause `Device::free_command_buffers` to be called, 
 /// automatically releasing any resources associated with it.
 ///
+///
+/// ### Validation
+///
+/// With the `validation` feature enabled, the state of the command buffer
+/// is tracked by its methods, which panic with the offending command and
+/// the command buffer's handle when a command is recorded while not in the
+/// recording state, a draw is recorded outside of a render pass or a
+/// dispatch inside of one, commands are executed from a secondary command
+/// buffer, or recording is ended with a render pass or query still active.
+///
+/// Only commands recorded using the methods of `CommandBuffer` are tracked,
+/// and resetting the command pool is not observed. Without the feature, no
+/// state is tracked.
+///
 #[derive(Debug, Clone)]
 pub struct CommandBuffer {
     inner: Arc<Inner>,
+    commands: DeviceCommands,
 }
 
 impl CommandBuffer {
     // FIXME: MAKE pub(crate)
-    pub(crate) fn from_parts(command_pool: CommandPool, handle: CommandBufferHandle)
-            -> VdResult<CommandBuffer> {
+    pub(crate) fn from_parts(command_pool: CommandPool, handle: CommandBufferHandle,
+            level: CommandBufferLevel) -> VdResult<CommandBuffer> {
         Ok(CommandBuffer {
+            commands: command_pool.device().commands(),
             inner: Arc::new(Inner {
                 command_pool,
                 handle,
+                level,
+                active_queries: AtomicUsize::new(0),
+                #[cfg(feature = "validation")]
+                recording: RecordingState {
+                    state: AtomicUsize::new(STATE_INITIAL),
+                    render_pass: AtomicUsize::new(RENDER_PASS_NONE),
+                },
             })
         })
     }
@@ -84,6 +157,147 @@ impl CommandBuffer {
         self.inner.command_pool.device()
     }
 
+    /// Returns the level (primary or secondary) of this command buffer.
+    #[inline]
+    pub fn level(&self) -> CommandBufferLevel {
+        self.inner.level
+    }
+
+    /// Returns a reference to the command pool this command buffer was
+    /// allocated from.
+    #[inline]
+    pub fn command_pool(&self) -> &CommandPool {
+        &self.inner.command_pool
+    }
+
+    /// Returns true if neither this command buffer nor the pool it was
+    /// allocated from is referenced by any clone.
+    fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.inner) == 1 && Arc::weak_count(&self.inner) == 0 &&
+            self.inner.command_pool.is_unique()
+    }
+
+    /// Panics if this command buffer is not in the recording state.
+    #[cfg_attr(not(feature = "validation"), allow(unused_variables))]
+    #[inline(always)]
+    fn validate_recording(&self, command: &str) {
+        #[cfg(feature = "validation")]
+        {
+            if self.inner.recording.state.load(Ordering::SeqCst) != STATE_RECORDING {
+                panic!("{}: command buffer ({:?}) is not in the recording state", command,
+                    self.handle());
+            }
+        }
+    }
+
+    /// Panics if this command buffer is not in the recording state or if a
+    /// render pass instance is not active when `inside` is `true` (or is
+    /// active when `inside` is `false`).
+    #[cfg_attr(not(feature = "validation"), allow(unused_variables))]
+    #[inline(always)]
+    fn validate_render_pass(&self, command: &str, inside: bool) {
+        #[cfg(feature = "validation")]
+        {
+            self.validate_recording(command);
+            let active = self.inner.recording.render_pass.load(Ordering::SeqCst) !=
+                RENDER_PASS_NONE;
+            if inside && !active {
+                panic!("{}: must be recorded inside a render pass (command buffer: {:?})",
+                    command, self.handle());
+            } else if !inside && active {
+                panic!("{}: must be recorded outside of a render pass (command buffer: {:?})",
+                    command, self.handle());
+            }
+        }
+    }
+
+    /// Panics if this command buffer is already in the recording state.
+    #[cfg_attr(not(feature = "validation"), allow(unused_variables))]
+    #[inline(always)]
+    fn validate_begin(&self, command: &str) {
+        #[cfg(feature = "validation")]
+        {
+            if self.inner.recording.state.load(Ordering::SeqCst) == STATE_RECORDING {
+                panic!("{}: command buffer ({:?}) is already in the recording state", command,
+                    self.handle());
+            }
+        }
+    }
+
+    /// Records the transition to the recording state. Only call once
+    /// `vkBeginCommandBuffer` has succeeded.
+    #[cfg_attr(not(feature = "validation"), allow(unused_variables))]
+    #[inline(always)]
+    fn track_begin(&self, render_pass_continue: bool) {
+        #[cfg(feature = "validation")]
+        {
+            self.inner.recording.state.store(STATE_RECORDING, Ordering::SeqCst);
+            self.inner.recording.render_pass.store(if render_pass_continue {
+                RENDER_PASS_CONTINUED } else { RENDER_PASS_NONE }, Ordering::SeqCst);
+        }
+    }
+
+    /// Panics if not recording or if a render pass or query begun in this
+    /// command buffer is still active.
+    #[inline(always)]
+    fn validate_end(&self) {
+        #[cfg(feature = "validation")]
+        {
+            self.validate_recording("CommandBuffer::end");
+            if self.inner.recording.render_pass.load(Ordering::SeqCst) == RENDER_PASS_BEGUN {
+                panic!("CommandBuffer::end: a render pass is still active (command buffer: \
+                    {:?})", self.handle());
+            }
+            if self.inner.active_queries.load(Ordering::SeqCst) != 0 {
+                panic!("CommandBuffer::end: a query is still active (command buffer: {:?})",
+                    self.handle());
+            }
+        }
+    }
+
+    /// Records the transition to the executable state. Only call once
+    /// `vkEndCommandBuffer` has succeeded.
+    #[inline(always)]
+    fn track_end(&self) {
+        #[cfg(feature = "validation")]
+        {
+            self.inner.recording.state.store(STATE_EXECUTABLE, Ordering::SeqCst);
+        }
+    }
+
+    /// Records the transition to the initial state. Only call once
+    /// `vkResetCommandBuffer` has succeeded.
+    #[inline(always)]
+    fn track_reset(&self) {
+        #[cfg(feature = "validation")]
+        {
+            self.inner.recording.state.store(STATE_INITIAL, Ordering::SeqCst);
+            self.inner.recording.render_pass.store(RENDER_PASS_NONE, Ordering::SeqCst);
+        }
+    }
+
+    /// Records the beginning (`begin == true`) or end of a render pass
+    /// instance, panicking if one is already active or if none begun in this
+    /// command buffer is, respectively.
+    #[cfg_attr(not(feature = "validation"), allow(unused_variables))]
+    #[inline(always)]
+    fn track_render_pass(&self, command: &str, begin: bool) {
+        #[cfg(feature = "validation")]
+        {
+            if begin {
+                self.validate_render_pass(command, false);
+                self.inner.recording.render_pass.store(RENDER_PASS_BEGUN, Ordering::SeqCst);
+            } else {
+                self.validate_recording(command);
+                if self.inner.recording.render_pass.load(Ordering::SeqCst) != RENDER_PASS_BEGUN {
+                    panic!("{}: no render pass begun in this command buffer is active \
+                        (command buffer: {:?})", command, self.handle());
+                }
+                self.inner.recording.render_pass.store(RENDER_PASS_NONE, Ordering::SeqCst);
+            }
+        }
+    }
+
     /// Starts recording this command buffer.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkBeginCommandBuffer.html
@@ -94,9 +308,44 @@ impl CommandBuffer {
             .flags(flags)
             .build();
 
+        self.validate_begin("CommandBuffer::begin");
+        unsafe {
+            self.inner.command_pool.device().begin_command_buffer(self.inner.handle, &begin_info)?;
+        }
+        self.track_begin(false);
+        self.inner.active_queries.store(0, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Starts recording this secondary command buffer, inheriting state
+    /// from `inheritance_info`.
+    ///
+    /// To be executed within a render pass instance, `flags` must include
+    /// `CommandBufferUsageFlags::RENDER_PASS_CONTINUE` and
+    /// `inheritance_info` must specify the render pass and subpass (and
+    /// ideally the framebuffer).
+    ///
+    /// Returns an error if this is a primary command buffer.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkBeginCommandBuffer.html
+    //
+    pub fn begin_secondary(&self, flags: CommandBufferUsageFlags,
+            inheritance_info: &CommandBufferInheritanceInfo) -> VdResult<()> {
+        if self.inner.level != CommandBufferLevel::Secondary {
+            return Err("CommandBuffer::begin_secondary: not a secondary command buffer".into());
+        }
+        let begin_info = CommandBufferBeginInfo::builder()
+            .flags(flags)
+            .inheritance_info(inheritance_info)
+            .build();
+
+        self.validate_begin("CommandBuffer::begin_secondary");
         unsafe {
-            self.inner.command_pool.device().begin_command_buffer(self.inner.handle, &begin_info)
+            self.inner.command_pool.device().begin_command_buffer(self.inner.handle, &begin_info)?;
         }
+        self.track_begin(flags.contains(CommandBufferUsageFlags::RENDER_PASS_CONTINUE));
+        self.inner.active_queries.store(0, Ordering::SeqCst);
+        Ok(())
     }
 
     /// Finishes recording this command buffer.
@@ -105,9 +354,12 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn end(&self) -> VdResult<()> {
+        self.validate_end();
         unsafe {
-            self.inner.command_pool.device().end_command_buffer(self.inner.handle)
+            self.inner.command_pool.device().end_command_buffer(self.inner.handle)?;
         }
+        self.track_end();
+        Ok(())
     }
 
     /// Resets this command buffer to the initial state.
@@ -116,7 +368,10 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn reset(&self, flags: CommandBufferResetFlags) -> VdResult<()> {
-        unsafe { self.device().cmd_reset_command_buffer(self.handle(), flags) }
+        unsafe { self.device().reset_command_buffer(self.handle(), flags)?; }
+        self.track_reset();
+        self.inner.active_queries.store(0, Ordering::SeqCst);
+        Ok(())
     }
 
     /// Binds a pipeline object to this command buffer.
@@ -126,7 +381,8 @@ impl CommandBuffer {
     #[inline]
     pub fn bind_pipeline<P>(&self, pipeline_bind_point: PipelineBindPoint,
             pipeline: &P) where P: Handle<Target=PipelineHandle> {
-        unsafe { self.device().cmd_bind_pipeline(self.handle(), pipeline_bind_point,
+        self.validate_recording("CommandBuffer::bind_pipeline");
+        unsafe { self.commands.cmd_bind_pipeline(self.handle(), pipeline_bind_point,
             pipeline.handle()); }
     }
 
@@ -136,7 +392,8 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_viewport(&self, first_viewport: u32, viewports: &[Viewport]) {
-        unsafe { self.device().cmd_set_viewport(self.handle(), first_viewport, viewports); }
+        self.validate_recording("CommandBuffer::set_viewport");
+        unsafe { self.commands.cmd_set_viewport(self.handle(), first_viewport, viewports); }
     }
 
     /// Sets the dynamic scissor rectangles on this command buffer.
@@ -145,7 +402,8 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_scissor(&self, first_scissor: u32, scissors: &[Rect2d]) {
-        unsafe { self.device().cmd_set_scissor(self.handle(), first_scissor, scissors); }
+        self.validate_recording("CommandBuffer::set_scissor");
+        unsafe { self.commands.cmd_set_scissor(self.handle(), first_scissor, scissors); }
     }
 
     /// Sets the dynamic line width state.
@@ -154,6 +412,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_line_width(&self, line_width: f32) {
+        self.validate_recording("CommandBuffer::set_line_width");
         unsafe { self.device().cmd_set_line_width(self.handle(), line_width); }
     }
 
@@ -164,6 +423,7 @@ impl CommandBuffer {
     #[inline]
     pub fn set_depth_bias(&self, depth_bias_constant_factor: f32, depth_bias_clamp: f32,
             depth_bias_slope_factor: f32) {
+        self.validate_recording("CommandBuffer::set_depth_bias");
         unsafe { self.device().cmd_set_depth_bias(self.handle(),
             depth_bias_constant_factor, depth_bias_clamp, depth_bias_slope_factor); }
     }
@@ -174,6 +434,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_blend_constants(&self, blend_constants: [f32; 4]) {
+        self.validate_recording("CommandBuffer::set_blend_constants");
         unsafe { self.device().cmd_set_blend_constants(self.handle(), blend_constants); }
     }
 
@@ -182,6 +443,7 @@ impl CommandBuffer {
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdSetDepthBounds.html
     //
     pub fn set_depth_bounds(&self, min_depth_bounds: f32, max_depth_bounds: f32) {
+        self.validate_recording("CommandBuffer::set_depth_bounds");
         unsafe { self.device().cmd_set_depth_bounds(self.handle(), min_depth_bounds, max_depth_bounds); }
     }
 
@@ -191,6 +453,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_stencil_compare_mask(&self, face_mask: StencilFaceFlags, compare_mask: u32) {
+        self.validate_recording("CommandBuffer::set_stencil_compare_mask");
         unsafe { self.device().cmd_set_stencil_compare_mask(self.handle(), face_mask, compare_mask); }
     }
 
@@ -200,6 +463,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_stencil_write_mask(&self, face_mask: StencilFaceFlags, write_mask: u32) {
+        self.validate_recording("CommandBuffer::set_stencil_write_mask");
         unsafe { self.device().cmd_set_stencil_write_mask(self.handle(), face_mask, write_mask); }
     }
 
@@ -209,21 +473,41 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_stencil_reference(&self, face_mask: StencilFaceFlags, reference: u32) {
+        self.validate_recording("CommandBuffer::set_stencil_reference");
         unsafe { self.device().cmd_set_stencil_reference(self.handle(), face_mask, reference); }
     }
 
     /// Binds descriptor sets to this command buffer.
     ///
+    /// In debug builds, or with the `validation` feature enabled, panics if
+    /// the layouts of all `descriptor_sets` are known and the length of
+    /// `dynamic_offsets` differs from the number of dynamic descriptors they
+    /// contain (see `DescriptorSetLayout::dynamic_descriptor_count`).
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdBindDescriptorSets.html
     //
     #[inline]
     pub fn bind_descriptor_sets(&self, pipeline_bind_point: PipelineBindPoint,
         layout: &PipelineLayout, first_set: u32, descriptor_sets: &[&DescriptorSet],
             dynamic_offsets: &[u32]) {
+        self.validate_recording("CommandBuffer::bind_descriptor_sets");
+        #[cfg(any(debug_assertions, feature = "validation"))]
+        {
+            let dynamic_count = descriptor_sets.iter().fold(Some(0u32), |count, ds| {
+                count.and_then(|n| ds.layout().map(|l| n + l.dynamic_descriptor_count()))
+            });
+            if let Some(dynamic_count) = dynamic_count {
+                if dynamic_count as usize != dynamic_offsets.len() {
+                    panic!("CommandBuffer::bind_descriptor_sets: {} dynamic offsets were \
+                        given but the descriptor sets contain {} dynamic descriptors",
+                        dynamic_offsets.len(), dynamic_count);
+                }
+            }
+        }
         let ds_handles: SmallVec<[DescriptorSetHandle; 16]> = descriptor_sets.iter()
             .map(|ds| ds.handle()).collect();
         unsafe {
-            self.device().cmd_bind_descriptor_sets(self.handle(), pipeline_bind_point,
+            self.commands.cmd_bind_descriptor_sets(self.handle(), pipeline_bind_point,
                 layout.handle(), first_set, &ds_handles, dynamic_offsets);
         }
     }
@@ -234,20 +518,22 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn bind_index_buffer(&self, buffer: &Buffer, offset: u64, index_type: IndexType) {
-        unsafe { self.device().cmd_bind_index_buffer(self.handle(), buffer.handle(),
+        self.validate_recording("CommandBuffer::bind_index_buffer");
+        unsafe { self.commands.cmd_bind_index_buffer(self.handle(), buffer.handle(),
             offset, index_type); }
     }
 
-    /// Binds vertex buffers to this command buffer.
+    /// Binds vertex buffers, each paired with its offset, to this command
+    /// buffer.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdBindVertexBuffers.html
     //
     #[inline]
-    pub fn bind_vertex_buffers(&self, first_binding: u32, buffers: &[&Buffer], offsets: &[u64]) {
-        let buffer_handles: SmallVec<[BufferHandle; 16]> = buffers.iter()
-            .map(|b| b.handle()).collect();
-        unsafe { self.device().cmd_bind_vertex_buffers(self.handle(),
-            first_binding, &buffer_handles, offsets); }
+    pub fn bind_vertex_buffers(&self, first_binding: u32, buffers: &[(&Buffer, DeviceSize)]) {
+        self.validate_recording("CommandBuffer::bind_vertex_buffers");
+        let buffers: SmallVec<[(BufferHandle, DeviceSize); 16]> = buffers.iter()
+            .map(|&(b, offset)| (b.handle(), offset)).collect();
+        unsafe { self.commands.cmd_bind_vertex_buffers(self.handle(), first_binding, &buffers); }
     }
 
     /// Draws primitives.
@@ -257,7 +543,8 @@ impl CommandBuffer {
     #[inline]
     pub fn draw(&self, vertex_count: u32, instance_count: u32, first_vertex: u32,
             first_instance: u32) {
-        unsafe { self.device().cmd_draw(self.handle(), vertex_count, instance_count,
+        self.validate_render_pass("CommandBuffer::draw", true);
+        unsafe { self.commands.cmd_draw(self.handle(), vertex_count, instance_count,
             first_vertex, first_instance); }
     }
 
@@ -268,7 +555,8 @@ impl CommandBuffer {
     #[inline]
     pub fn draw_indexed(&self, index_count: u32, instance_count: u32, first_index: u32,
             vertex_offset: i32, first_instance: u32) {
-        unsafe { self.device().cmd_draw_indexed(self.handle(), index_count,
+        self.validate_render_pass("CommandBuffer::draw_indexed", true);
+        unsafe { self.commands.cmd_draw_indexed(self.handle(), index_count,
             instance_count, first_index, vertex_offset, first_instance); }
     }
 
@@ -279,6 +567,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn draw_indirect(&self, buffer: &Buffer, offset: u64, draw_count: u32,
             stride: u32) {
+        self.validate_render_pass("CommandBuffer::draw_indirect", true);
         self.device().cmd_draw_indirect(self.handle(),
             buffer.handle(), offset, draw_count, stride);
     }
@@ -290,6 +579,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn draw_indexed_indirect(&self, buffer: &Buffer, offset: u64, draw_count: u32,
             stride: u32) {
+        self.validate_render_pass("CommandBuffer::draw_indexed_indirect", true);
         self.device().cmd_draw_indexed_indirect(self.handle(),
             buffer.handle(), offset, draw_count, stride);
     }
@@ -299,8 +589,9 @@ impl CommandBuffer {
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdDispatch.html
     //
     pub fn dispatch(&self, group_count_x: u32, group_count_y: u32, group_count_z: u32) {
+        self.validate_render_pass("CommandBuffer::dispatch", false);
         unsafe {
-            self.device().cmd_dispatch(self.handle(), group_count_x, group_count_y, group_count_z);
+            self.commands.cmd_dispatch(self.handle(), group_count_x, group_count_y, group_count_z);
         }
     }
 
@@ -310,6 +601,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub unsafe fn dispatch_indirect(&self, buffer: &Buffer, offset: u64) {
+        self.validate_render_pass("CommandBuffer::dispatch_indirect", false);
             self.device().cmd_dispatch_indirect(self.handle(), buffer.handle(), offset);
     }
 
@@ -320,6 +612,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn copy_buffer(&self, src_buffer: &Buffer, dst_buffer: &Buffer,
             regions: &[BufferCopy]) {
+        self.validate_recording("CommandBuffer::copy_buffer");
         self.device().cmd_copy_buffer(self.handle(), src_buffer.handle(),
             dst_buffer.handle(), regions);
     }
@@ -331,6 +624,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn copy_image(&self, src_image: &Image, src_image_layout: ImageLayout,
             dst_image: &Image, dst_image_layout: ImageLayout, regions: &[ImageCopy]) {
+        self.validate_recording("CommandBuffer::copy_image");
         self.device().cmd_copy_image(self.handle(),
             src_image.handle(), src_image_layout, dst_image.handle(), dst_image_layout, regions);
     }
@@ -343,6 +637,7 @@ impl CommandBuffer {
     pub unsafe fn blit_image(&self, src_image: &Image, src_image_layout: ImageLayout,
             dst_image: &Image, dst_image_layout: ImageLayout, regions: &[ImageBlit],
             filter: Filter) {
+        self.validate_recording("CommandBuffer::blit_image");
         self.device().cmd_blit_image(self.handle(), src_image.handle(), src_image_layout,
             dst_image.handle(), dst_image_layout, regions, filter);
     }
@@ -354,6 +649,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn copy_buffer_to_image(&self, src_buffer: &Buffer, dst_image: &Image,
             dst_image_layout: ImageLayout, regions: &[BufferImageCopy]) {
+        self.validate_recording("CommandBuffer::copy_buffer_to_image");
         self.device().cmd_copy_buffer_to_image(self.handle(), src_buffer.handle(),
             dst_image.handle(), dst_image_layout, regions, );
     }
@@ -365,6 +661,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn copy_image_to_buffer(&self, src_image: &Image, src_image_layout: ImageLayout,
             dst_buffer: &Buffer, regions: &[BufferImageCopy]) {
+        self.validate_recording("CommandBuffer::copy_image_to_buffer");
         self.device().cmd_copy_image_to_buffer(self.handle(),
             src_image.handle(), src_image_layout, dst_buffer.handle(), regions);
     }
@@ -375,17 +672,39 @@ impl CommandBuffer {
     //
     #[inline]
     pub unsafe fn update_buffer(&self, dst_buffer: &Buffer, dst_offset: u64, data: &[u8]) {
+        self.validate_recording("CommandBuffer::update_buffer");
         self.device().cmd_update_buffer(self.handle(),
             dst_buffer.handle(), dst_offset, data);
     }
 
+    /// Updates a buffer's contents from a slice of plain data, such as
+    /// `DrawIndirectCommand`s.
+    ///
+    /// The size of `data` in bytes must be a multiple of four and no greater
+    /// than 65536. `T` must not contain references or padding.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdUpdateBuffer.html
+    //
+    #[inline]
+    pub unsafe fn update_buffer_typed<T>(&self, dst_buffer: &Buffer, dst_offset: u64, data: &[T]) {
+        self.validate_recording("CommandBuffer::update_buffer_typed");
+        let bytes = slice::from_raw_parts(data.as_ptr() as *const u8,
+            data.len() * mem::size_of::<T>());
+        debug_assert!(bytes.len() % 4 == 0 && bytes.len() <= 65536,
+            "CommandBuffer::update_buffer_typed: invalid data size: {}", bytes.len());
+        self.device().cmd_update_buffer(self.handle(), dst_buffer.handle(), dst_offset, bytes);
+    }
+
     /// Fills a region of a buffer with a fixed value.
     ///
+    /// A `size` of `None` fills to the end of the buffer (`WHOLE_SIZE`).
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdFillBuffer.html
     //
     #[inline]
     pub unsafe fn fill_buffer(&self, dst_buffer: &Buffer, dst_offset: u64,
             size: Option<DeviceSize>, data: u32) {
+        self.validate_recording("CommandBuffer::fill_buffer");
         self.device().cmd_fill_buffer(self.handle(),
             dst_buffer.handle(), dst_offset, size, data);
     }
@@ -397,6 +716,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn clear_color_image(&self, image: &Image, image_layout: ImageLayout,
             color: &ClearColorValue, ranges: &[ImageSubresourceRange]) {
+        self.validate_recording("CommandBuffer::clear_color_image");
         self.device().cmd_clear_color_image(self.handle(),
             image.handle(), image_layout, color, ranges);
     }
@@ -408,6 +728,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn clear_depth_stencil_image(&self, image: &Image, image_layout: ImageLayout,
             depth_stencil: &ClearDepthStencilValue, ranges: &[ImageSubresourceRange]) {
+        self.validate_recording("CommandBuffer::clear_depth_stencil_image");
         self.device().cmd_clear_depth_stencil_image(self.handle(),
             image.handle(), image_layout, depth_stencil, ranges);
     }
@@ -418,6 +739,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn clear_attachments(&self, attachments: &[ClearAttachment], rects: &[ClearRect]) {
+        self.validate_render_pass("CommandBuffer::clear_attachments", true);
         unsafe { self.device().cmd_clear_attachments(self.handle(), attachments, rects); }
     }
 
@@ -428,6 +750,7 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn resolve_image(&self, src_image: &Image, src_image_layout: ImageLayout, dst_image: &Image,
             dst_image_layout: ImageLayout, regions: &[ImageResolve]) {
+        self.validate_recording("CommandBuffer::resolve_image");
         self.device().cmd_resolve_image(self.handle(), src_image.handle(), src_image_layout,
             dst_image.handle(), dst_image_layout, regions);
     }
@@ -438,6 +761,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn set_event(&self, event: &Event, stage_mask: PipelineStageFlags) {
+        self.validate_recording("CommandBuffer::set_event");
         unsafe { self.device().cmd_set_event(self.handle(),
             event.handle(), stage_mask); }
     }
@@ -448,6 +772,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn reset_event(&self, event: &Event, stage_mask: PipelineStageFlags) {
+        self.validate_recording("CommandBuffer::reset_event");
         unsafe { self.device().cmd_reset_event(self.handle(), event.handle(), stage_mask); }
     }
 
@@ -461,6 +786,7 @@ impl CommandBuffer {
             memory_barriers: &[MemoryBarrier],
             buffer_memory_barriers: &[BufferMemoryBarrier],
             image_memory_barriers: &[ImageMemoryBarrier]) {
+        self.validate_recording("CommandBuffer::wait_events");
         let event_handles: SmallVec<[EventHandle; 16]> = events.iter()
             .map(|e| e.handle()).collect();
         unsafe { self.device().cmd_wait_events(self.handle(), &event_handles, src_stage_mask,
@@ -476,6 +802,7 @@ impl CommandBuffer {
             dependency_flags: DependencyFlags, memory_barriers: &[MemoryBarrier],
             buffer_memory_barriers: &[BufferMemoryBarrier],
             image_memory_barriers: &[ImageMemoryBarrier]) {
+        self.validate_recording("CommandBuffer::pipeline_barrier");
         unsafe {
             self.device().cmd_pipeline_barrier(self.handle(), src_stage_mask,
             dst_stage_mask, dependency_flags, memory_barriers, buffer_memory_barriers,
@@ -483,21 +810,74 @@ impl CommandBuffer {
         }
     }
 
-    /// Begins a query.
+    /// Inserts an everything-to-everything barrier. For debugging only.
+    ///
+    /// See `Device::cmd_full_pipeline_barrier`.
+    #[cfg(any(debug_assertions, not(feature = "deny-full-barrier")))]
+    #[inline]
+    pub fn full_pipeline_barrier(&self) {
+        self.validate_recording("CommandBuffer::full_pipeline_barrier");
+        unsafe { self.device().cmd_full_pipeline_barrier(self.handle()); }
+    }
+
+    /// Begins a query, returning a scope which ends it when dropped or when
+    /// `QueryScope::end` is called.
+    ///
+    /// The query must have been reset since it was last used. For occlusion
+    /// queries, `QueryControlFlags::PRECISE` is added automatically if the
+    /// pool was created precise and is an error otherwise.
+    ///
+    /// Returns an error if `query` is out of range, if `query_pool` is a
+    /// timestamp pool, or if a query of the same type is already active in
+    /// this command buffer.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdBeginQuery.html
     //
-    #[inline]
-    pub fn begin_query(&self, query_pool: &QueryPool, query: u32, flags: QueryControlFlags) {
+    pub fn begin_query<'q>(&'q self, query_pool: &'q QueryPool, query: u32,
+            mut flags: QueryControlFlags) -> VdResult<QueryScope<'q>> {
+        self.validate_recording("CommandBuffer::begin_query");
+        if query >= query_pool.query_count() {
+            return Err(format!("CommandBuffer::begin_query: query index ({}) out of range \
+                (query count: {})", query, query_pool.query_count()).into());
+        }
+        match query_pool.query_type() {
+            QueryType::Timestamp => return Err("CommandBuffer::begin_query: timestamp queries \
+                cannot be begun, use `write_timestamp` instead".into()),
+            QueryType::Occlusion => {
+                if flags.contains(QueryControlFlags::PRECISE) && !query_pool.is_precise() {
+                    return Err("CommandBuffer::begin_query: `QueryControlFlags::PRECISE` \
+                        specified for a pool created imprecise".into());
+                }
+                if query_pool.is_precise() { flags |= QueryControlFlags::PRECISE; }
+            },
+            QueryType::PipelineStatistics => {
+                if flags.contains(QueryControlFlags::PRECISE) {
+                    return Err("CommandBuffer::begin_query: `QueryControlFlags::PRECISE` \
+                        is only valid for occlusion queries".into());
+                }
+            },
+        }
+        let bit = query_type_bit(query_pool.query_type());
+        if self.inner.active_queries.fetch_or(bit, Ordering::SeqCst) & bit != 0 {
+            return Err(format!("CommandBuffer::begin_query: a query of type {:?} is already \
+                active", query_pool.query_type()).into());
+        }
         unsafe { self.device().cmd_begin_query(self.handle(), query_pool.handle(), query, flags); }
+        Ok(QueryScope::new(self, query_pool, query))
     }
 
     /// Ends a query.
     ///
+    /// Queries begun with `begin_query` are ended by their `QueryScope` and
+    /// must not also be ended using this method.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdEndQuery.html
     //
     #[inline]
     pub fn end_query(&self, query_pool: &QueryPool, query: u32) {
+        self.validate_recording("CommandBuffer::end_query");
+        self.inner.active_queries.fetch_and(!query_type_bit(query_pool.query_type()),
+            Ordering::SeqCst);
         unsafe { self.device().cmd_end_query(self.handle(), query_pool.handle(), query); }
     }
 
@@ -507,6 +887,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn reset_query_pool(&self, query_pool: &QueryPool, first_query: u32, query_count: u32) {
+        self.validate_recording("CommandBuffer::reset_query_pool");
         unsafe { self.device().cmd_reset_query_pool(self.handle(),
             query_pool.handle(), first_query, query_count); }
     }
@@ -517,6 +898,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn write_timestamp(&self, pipeline_stage: PipelineStageFlags, query_pool: &QueryPool, query: u32) {
+        self.validate_recording("CommandBuffer::write_timestamp");
         unsafe { self.device().cmd_write_timestamp(self.handle(),
             pipeline_stage, query_pool.handle(), query); }
     }
@@ -528,27 +910,55 @@ impl CommandBuffer {
     #[inline]
     pub unsafe fn copy_query_pool_results(&self, query_pool: &QueryPool, first_query: u32, query_count: u32,
             dst_buffer: &Buffer, dst_offset: u64, stride: u64, flags: QueryResultFlags) {
+        self.validate_recording("CommandBuffer::copy_query_pool_results");
         self.device().cmd_copy_query_pool_results(self.handle(), query_pool.handle(),
             first_query, query_count, dst_buffer.handle(), dst_offset, stride, flags);
     }
 
     /// Updates the values of push constants.
     ///
+    /// In debug builds, or with the `validation` feature enabled, panics if
+    /// the update is not permitted by the push constant ranges of `layout`
+    /// (see `PipelineLayout::validate_push_constants`).
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdPushConstants.html
     //
     #[inline]
     pub fn push_constants(&self, layout: &PipelineLayout, stage_flags: ShaderStageFlags, offset: u32,
             values: &[u8]) {
-        unsafe { self.device().cmd_push_constants(self.handle(), layout.handle(),
+        self.validate_recording("CommandBuffer::push_constants");
+        #[cfg(any(debug_assertions, feature = "validation"))]
+        {
+            if let Err(err) = layout.validate_push_constants(stage_flags, offset,
+                    values.len() as u32) {
+                panic!("CommandBuffer::push_constants: {}", err);
+            }
+        }
+        unsafe { self.commands.cmd_push_constants(self.handle(), layout.handle(),
             stage_flags, offset, values); }
     }
 
+    /// Updates the values of push constants from a value of plain data.
+    ///
+    /// The size of `T` must be a multiple of four. `T` must not contain
+    /// references or padding. Validated as with `push_constants`.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdPushConstants.html
+    //
+    #[inline]
+    pub unsafe fn push_constants_typed<T>(&self, layout: &PipelineLayout,
+            stage_flags: ShaderStageFlags, offset: u32, value: &T) {
+        let bytes = slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>());
+        self.push_constants(layout, stage_flags, offset, bytes);
+    }
+
     /// Begins a new render pass.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdBeginRenderPass.html
     //
     #[inline]
     pub fn begin_render_pass(&self, render_pass_begin: &RenderPassBeginInfo, contents: SubpassContents) {
+        self.track_render_pass("CommandBuffer::begin_render_pass", true);
         unsafe { self.device().cmd_begin_render_pass(self.handle(),
             render_pass_begin, contents); }
     }
@@ -559,6 +969,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn next_subpass(&self, contents: SubpassContents) {
+        self.validate_render_pass("CommandBuffer::next_subpass", true);
         unsafe { self.device().cmd_next_subpass(self.handle(), contents); }
     }
 
@@ -568,6 +979,7 @@ impl CommandBuffer {
     //
     #[inline]
     pub fn end_render_pass(&self) {
+        self.track_render_pass("CommandBuffer::end_render_pass", false);
         unsafe { self.device().cmd_end_render_pass(self.handle()); }
     }
 
@@ -576,30 +988,52 @@ impl CommandBuffer {
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdExecuteCommands.html
     //
     #[inline]
-    pub fn execute_commands(&self, command_buffers: &[&CommandBuffer]) {
-        let command_buffer_handles: SmallVec<[CommandBufferHandle; 16]> = command_buffers.iter()
-            .map(|cb| cb.handle()).collect();
-        unsafe { self.device().cmd_execute_commands(self.handle(), &command_buffer_handles); }
+    pub fn execute_commands<Cb>(&self, command_buffers: &[Cb])
+            where Cb: Handle<Target=CommandBufferHandle> {
+        self.validate_recording("CommandBuffer::execute_commands");
+        #[cfg(feature = "validation")]
+        {
+            if self.inner.level != CommandBufferLevel::Primary {
+                panic!("CommandBuffer::execute_commands: must be recorded into a primary \
+                    command buffer (command buffer: {:?})", self.handle());
+            }
+        }
+        unsafe { self.device().cmd_execute_commands(self.handle(), command_buffers); }
     }
 
     /// Begins a debug marker.
     #[inline]
     pub fn debug_marker_begin_ext(&self, marker_info: &DebugMarkerMarkerInfoExt) {
+        self.validate_recording("CommandBuffer::debug_marker_begin_ext");
         unsafe { self.device().cmd_debug_marker_begin_ext(self.handle(), marker_info); }
     }
 
     /// Ends a debug marker.
     #[inline]
     pub fn debug_marker_end_ext(&self) {
+        self.validate_recording("CommandBuffer::debug_marker_end_ext");
         unsafe { self.device().cmd_debug_marker_end_ext(self.handle()); }
     }
 
     /// Inserts a debug marker.
     #[inline]
     pub fn debug_marker_insert_ext(&self, marker_info: &DebugMarkerMarkerInfoExt) {
+        self.validate_recording("CommandBuffer::debug_marker_insert_ext");
         unsafe { self.device().cmd_debug_marker_insert_ext(self.handle(), marker_info); }
     }
 
+    /// Begins a debug region, returning a scope which ends it when dropped
+    /// or when `DebugMarkerScope::end` is called.
+    ///
+    /// Does nothing unless `VK_EXT_debug_utils` is enabled on the instance
+    /// or `VK_EXT_debug_marker` on the device. Returns an error if `name`
+    /// contains a nul byte.
+    pub fn debug_scope<'c>(&'c self, name: &str, color: [f32; 4])
+            -> VdResult<DebugMarkerScope<'c>> {
+        self.validate_recording("CommandBuffer::debug_scope");
+        DebugMarkerScope::begin(self, name, color)
+    }
+
 }
 
 unsafe impl<'h> Handle for &'h CommandBuffer {
@@ -609,4 +1043,68 @@ unsafe impl<'h> Handle for &'h CommandBuffer {
     fn handle(&self) -> Self::Target {
         self.inner.handle
     }
-}
\ No newline at end of file
+}
+
+
+/// A command buffer which, along with the pool it was allocated from, is
+/// referenced from nowhere else and may therefore be sent to another thread
+/// to be recorded.
+///
+/// `CommandBuffer` itself is neither `Send` nor `Sync`: its clones share the
+/// underlying command buffer and pool, which must be externally
+/// synchronized. A recorder is not `Clone` and only lends out its command
+/// buffer for the duration of `record`, after which it verifies that no
+/// clone of the command buffer or its pool was retained.
+///
+/// ```text
+/// let command_buffer = command_pool.allocate_secondary_command_buffer()?;
+/// drop(command_pool);
+/// let mut recorder = CommandBufferRecorder::new(command_buffer).unwrap();
+/// thread::spawn(move || {
+///     recorder.record(|cb| record_commands(cb))?;
+///     Ok(recorder)
+/// });
+/// ```
+#[derive(Debug)]
+pub struct CommandBufferRecorder {
+    // `None` only if a call to `record` panicked.
+    command_buffer: Option<CommandBuffer>,
+}
+
+impl CommandBufferRecorder {
+    /// Returns a new recorder owning `command_buffer`, or returns
+    /// `command_buffer` if it or its command pool has any other clone.
+    pub fn new(command_buffer: CommandBuffer) -> Result<CommandBufferRecorder, CommandBuffer> {
+        if command_buffer.is_unique() {
+            Ok(CommandBufferRecorder { command_buffer: Some(command_buffer) })
+        } else {
+            Err(command_buffer)
+        }
+    }
+
+    /// Calls `f` with the command buffer, returning its result.
+    ///
+    /// Panics if `f` retains a clone of the command buffer or its pool, or
+    /// if a previous call to `record` panicked.
+    pub fn record<F, R>(&mut self, f: F) -> R where F: FnOnce(&CommandBuffer) -> R {
+        let command_buffer = self.command_buffer.take()
+            .expect("CommandBufferRecorder::record: a previous recording panicked");
+        let result = f(&command_buffer);
+        assert!(command_buffer.is_unique(), "CommandBufferRecorder::record: a clone of the \
+            command buffer or its pool was retained");
+        self.command_buffer = Some(command_buffer);
+        result
+    }
+
+    /// Returns the command buffer.
+    ///
+    /// Panics if a call to `record` panicked.
+    pub fn into_inner(self) -> CommandBuffer {
+        self.command_buffer
+            .expect("CommandBufferRecorder::into_inner: a previous recording panicked")
+    }
+}
+
+// The command buffer and its pool are referenced only by this recorder (see
+// `record`), so no other thread can access either.
+unsafe impl Send for CommandBufferRecorder {}
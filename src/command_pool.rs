@@ -11,6 +11,12 @@ use ::{ VdResult, Device, Handle, CommandPoolCreateInfo, CommandPoolCreateFlags,
 pub struct CommandPoolHandle(pub(crate) vks::VkCommandPool);
 
 impl CommandPoolHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkCommandPool) -> CommandPoolHandle {
+        CommandPoolHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkCommandPool {
         self.0
@@ -61,6 +67,12 @@ impl CommandPool {
         CommandPoolBuilder::new()
     }
 
+    /// Returns true if no clone of this `CommandPool` exists (including
+    /// those held by command buffers allocated from it).
+    pub(crate) fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.inner) == 1 && Arc::weak_count(&self.inner) == 0
+    }
+
     /// Allocates command buffers from an existing command pool.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkAllocateCommandBuffers.html
@@ -92,7 +104,7 @@ impl CommandPool {
     pub fn allocate_command_buffers(&self, level: CommandBufferLevel, count: u32)
             -> VdResult<SmallVec<[CommandBuffer; 16]>> {
         self.allocate_command_buffer_handles(level, count)?.iter().map(|&hndl| {
-            CommandBuffer::from_parts(self.clone(), hndl)
+            CommandBuffer::from_parts(self.clone(), hndl, level)
         }).collect::<Result<SmallVec<_>, _>>()
     }
 
@@ -104,6 +116,23 @@ impl CommandPool {
         self.allocate_command_buffers(level, 1).map(|mut cbs| cbs.remove(0))
     }
 
+    /// Allocates secondary command buffers from an existing command pool.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkAllocateCommandBuffers.html
+    //
+    pub fn allocate_secondary_command_buffers(&self, count: u32)
+            -> VdResult<SmallVec<[CommandBuffer; 16]>> {
+        self.allocate_command_buffers(CommandBufferLevel::Secondary, count)
+    }
+
+    /// Allocates a secondary command buffer from an existing command pool.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkAllocateCommandBuffers.html
+    //
+    pub fn allocate_secondary_command_buffer(&self) -> VdResult<CommandBuffer> {
+        self.allocate_command_buffer(CommandBufferLevel::Secondary)
+    }
+
     /// Returns this object's handle.
     pub fn handle(&self) -> CommandPoolHandle {
         self.inner.handle
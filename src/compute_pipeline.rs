@@ -0,0 +1,166 @@
+use std::sync::Arc;
+use ::{VdResult, Device, PipelineLayoutHandle, PipelineHandle, PipelineCacheHandle, Handle,
+    ComputePipelineCreateInfo, PipelineShaderStageCreateInfo, ShaderStage, ShaderStageFlags,
+    PipelineCreateFlags, PipelineBindPoint, CommandBuffer};
+
+
+#[derive(Debug)]
+struct Inner {
+    handle: PipelineHandle,
+    device: Device,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_pipeline(self.handle, None);
+        }
+    }
+}
+
+
+/// A compute pipeline.
+///
+///
+/// ### Destruction
+///
+/// Dropping this `ComputePipeline` will cause `Device::destroy_pipeline` to be called,
+/// automatically releasing any resources associated with it.
+///
+#[derive(Debug, Clone)]
+pub struct ComputePipeline {
+    inner: Arc<Inner>,
+}
+
+impl ComputePipeline {
+    /// Returns a new `ComputePipelineBuilder`.
+    pub fn builder<'b>() -> ComputePipelineBuilder<'b> {
+        ComputePipelineBuilder::new()
+    }
+
+    /// Binds this pipeline to the compute bind point of `command_buffer`.
+    pub fn bind(&self, command_buffer: &CommandBuffer) {
+        command_buffer.bind_pipeline(PipelineBindPoint::Compute, &self.inner.handle);
+    }
+
+    /// Returns this object's handle.
+    pub fn handle(&self) -> PipelineHandle {
+        self.inner.handle
+    }
+
+    /// Returns a reference to the associated device.
+    pub fn device(&self) -> &Device {
+        &self.inner.device
+    }
+}
+
+unsafe impl<'g> Handle for &'g ComputePipeline {
+    type Target = PipelineHandle;
+
+    fn handle(&self) -> Self::Target {
+        self.inner.handle
+    }
+}
+
+
+/// A builder for `ComputePipeline`.
+#[derive(Debug, Clone)]
+pub struct ComputePipelineBuilder<'b> {
+    create_info: ComputePipelineCreateInfo<'b>,
+    stage: Option<PipelineShaderStageCreateInfo<'b>>,
+    pipeline_cache: Option<PipelineCacheHandle>,
+}
+
+impl<'b> ComputePipelineBuilder<'b> {
+    /// Returns a new compute pipeline builder.
+    pub fn new() -> ComputePipelineBuilder<'b> {
+        ComputePipelineBuilder {
+            create_info: ComputePipelineCreateInfo::default(),
+            stage: None,
+            pipeline_cache: None,
+        }
+    }
+
+    /// Specifies how the pipeline will be generated.
+    pub fn flags<'s>(&'s mut self, flags: PipelineCreateFlags)
+            -> &'s mut ComputePipelineBuilder<'b> {
+        self.create_info.set_flags(flags);
+        self
+    }
+
+    /// Specifies the compute shader stage. The stage must be
+    /// `ShaderStageFlags::COMPUTE`.
+    pub fn stage<'s, 'p>(&'s mut self, stage: &'p ShaderStage<'p>)
+            -> &'s mut ComputePipelineBuilder<'b>
+            where 'p: 'b {
+        self.stage = Some(stage.create_info());
+        self
+    }
+
+    /// Specifies the binding locations used by both the pipeline and
+    /// descriptor sets used with the pipeline.
+    pub fn layout<'s, H>(&'s mut self, layout: H) -> &'s mut ComputePipelineBuilder<'b>
+            where H: Handle<Target=PipelineLayoutHandle> {
+        self.create_info.set_layout(layout);
+        self
+    }
+
+    /// Specifies a pipeline cache to use instead of the device's default
+    /// pipeline cache.
+    pub fn pipeline_cache<'s>(&'s mut self, pipeline_cache: PipelineCacheHandle)
+            -> &'s mut ComputePipelineBuilder<'b> {
+        self.pipeline_cache = Some(pipeline_cache);
+        self
+    }
+
+    /// Specifies that pipelines may be derived from this one.
+    ///
+    /// Adds `PipelineCreateFlags::ALLOW_DERIVATIVES` to the flags specified
+    /// so far.
+    pub fn allow_derivatives<'s>(&'s mut self) -> &'s mut ComputePipelineBuilder<'b> {
+        let flags = self.create_info.flags() | PipelineCreateFlags::ALLOW_DERIVATIVES;
+        self.create_info.set_flags(flags);
+        self
+    }
+
+    /// Specifies an existing pipeline to derive from. The base pipeline must
+    /// have been created with `allow_derivatives`.
+    ///
+    /// Adds `PipelineCreateFlags::DERIVATIVE` to the flags specified so far.
+    pub fn base_pipeline<'s, H>(&'s mut self, base_pipeline: H)
+            -> &'s mut ComputePipelineBuilder<'b>
+            where H: Handle<Target=PipelineHandle> {
+        let flags = self.create_info.flags() | PipelineCreateFlags::DERIVATIVE;
+        self.create_info.set_flags(flags);
+        self.create_info.set_base_pipeline_handle(base_pipeline);
+        self.create_info.set_base_pipeline_index(-1);
+        self
+    }
+
+    /// Creates and returns a new `ComputePipeline`.
+    ///
+    /// Returns an error if no compute stage or layout has been specified.
+    pub fn build(&self, device: Device) -> VdResult<ComputePipeline> {
+        let stage = self.stage.as_ref()
+            .ok_or("ComputePipelineBuilder::build: no stage specified")?;
+        if stage.stage() != ShaderStageFlags::COMPUTE {
+            return Err("ComputePipelineBuilder::build: the stage must be \
+                `ShaderStageFlags::COMPUTE`".into());
+        }
+        if self.create_info.layout() == 0 {
+            return Err("ComputePipelineBuilder::build: no layout specified".into());
+        }
+        let mut create_info = self.create_info.clone();
+        create_info.set_stage(stage.clone());
+        let handle = unsafe {
+            device.create_compute_pipeline(self.pipeline_cache, &create_info, None)?
+        };
+
+        Ok(ComputePipeline {
+            inner: Arc::new(Inner {
+                handle,
+                device,
+            })
+        })
+    }
+}
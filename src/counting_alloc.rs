@@ -0,0 +1,84 @@
+//! A global allocator for unit tests which counts the heap allocations made
+//! by each thread.
+//!
+//! Allocation-free paths are verified by wrapping them in
+//! `count_allocations`. Stand-in implementations which record their
+//! arguments (see `loader::null`) do so within `uncounted` so that their own
+//! bookkeeping is not attributed to the code under test.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+    static SUSPENDED: Cell<bool> = Cell::new(false);
+}
+
+fn record_allocation() {
+    // `try_with` fails only while the thread is being torn down.
+    let suspended = SUSPENDED.try_with(|s| s.get()).unwrap_or(true);
+    if !suspended {
+        let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+
+/// Calls `f`, returning its result along with the number of allocations and
+/// reallocations it made on this thread.
+pub fn count_allocations<R, F: FnOnce() -> R>(f: F) -> (R, usize) {
+    let start = ALLOCATIONS.with(|a| a.get());
+    let result = f();
+    let end = ALLOCATIONS.with(|a| a.get());
+    (result, end - start)
+}
+
+/// Calls `f` without counting the allocations it makes.
+pub fn uncounted<R, F: FnOnce() -> R>(f: F) -> R {
+    let suspended = SUSPENDED.with(|s| s.replace(true));
+    let result = f();
+    SUSPENDED.with(|s| s.set(suspended));
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{count_allocations, uncounted};
+
+    #[test]
+    fn allocations_are_counted_unless_suspended() {
+        let (v, count) = count_allocations(|| vec![1u8; 16]);
+        assert_eq!(count, 1);
+        let ((), count) = count_allocations(|| { uncounted(|| drop(vec![1u8; 16])); });
+        assert_eq!(count, 0);
+        let ((), count) = count_allocations(|| drop(v));
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,135 @@
+//! Debug marker regions for profilers and debuggers.
+
+use std::ffi::{CString, CStr};
+use ::{VdResult, CommandBuffer, DebugMarkerMarkerInfoExt};
+
+
+/// The mechanism used to record command buffer debug regions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum DebugRegionBackend {
+    /// `VK_EXT_debug_utils` labels.
+    DebugUtils,
+    /// `VK_EXT_debug_marker` markers.
+    DebugMarker,
+    /// Neither extension is enabled.
+    None,
+}
+
+
+/// Converts `name` to a `CString`, naming `fn_name` in any error.
+fn marker_name(name: &str, fn_name: &str) -> VdResult<CString> {
+    CString::new(name).map_err(|_| format!("{}: debug marker names must not contain nul \
+        bytes", fn_name).into())
+}
+
+
+/// A debug region in a command buffer, ended when this scope is dropped or
+/// `end` is called.
+///
+/// Recorded using `VK_EXT_debug_utils` labels if that extension is enabled
+/// on the instance, otherwise using `VK_EXT_debug_marker` markers if that
+/// extension is enabled on the device. If neither is, this scope does
+/// nothing.
+///
+/// Scopes may be nested, and must be ended in the reverse order they were
+/// begun. Returned by `CommandBuffer::debug_scope`.
+#[must_use = "the debug region ends as soon as its scope is dropped"]
+#[derive(Debug)]
+pub struct DebugMarkerScope<'c> {
+    command_buffer: &'c CommandBuffer,
+    backend: DebugRegionBackend,
+    name: Option<CString>,
+    ended: bool,
+}
+
+impl<'c> DebugMarkerScope<'c> {
+    pub(crate) fn begin(command_buffer: &'c CommandBuffer, name: &str, color: [f32; 4])
+            -> VdResult<DebugMarkerScope<'c>> {
+        let backend = command_buffer.device().debug_region_backend();
+        let name_c = match backend {
+            DebugRegionBackend::None => None,
+            _ => Some(marker_name(name, "CommandBuffer::debug_scope")?),
+        };
+        unsafe {
+            match backend {
+                DebugRegionBackend::DebugUtils => {
+                    command_buffer.device().cmd_begin_debug_label(command_buffer.handle(),
+                        name, color)?;
+                },
+                DebugRegionBackend::DebugMarker => if let Some(ref n) = name_c {
+                    let marker_info = DebugMarkerMarkerInfoExt::builder()
+                        .marker_name(n)
+                        .color(color)
+                        .build();
+                    command_buffer.device().cmd_debug_marker_begin_ext(command_buffer.handle(),
+                        &marker_info);
+                },
+                DebugRegionBackend::None => (),
+            }
+        }
+        Ok(DebugMarkerScope { command_buffer, backend, name: name_c, ended: false })
+    }
+
+    /// Ends the debug region.
+    pub fn end(mut self) {
+        self.end_region();
+    }
+
+    fn end_region(&mut self) {
+        if self.ended { return; }
+        unsafe {
+            match self.backend {
+                DebugRegionBackend::DebugUtils => {
+                    // Only fails on name conversion, which cannot happen here:
+                    let _ = self.command_buffer.device()
+                        .cmd_end_debug_label(self.command_buffer.handle());
+                },
+                DebugRegionBackend::DebugMarker => {
+                    self.command_buffer.device()
+                        .cmd_debug_marker_end_ext(self.command_buffer.handle());
+                },
+                DebugRegionBackend::None => (),
+            }
+        }
+        self.ended = true;
+    }
+
+    /// Returns the name of the region, or `None` if no debug region
+    /// extension is enabled.
+    pub fn name(&self) -> Option<&CStr> {
+        self.name.as_ref().map(|n| n.as_c_str())
+    }
+}
+
+impl<'c> Drop for DebugMarkerScope<'c> {
+    fn drop(&mut self) {
+        self.end_region();
+    }
+}
+
+
+/// Inserts a single debug marker into a command buffer.
+///
+/// Uses the same extension as `DebugMarkerScope` and does nothing if neither
+/// is enabled.
+pub fn insert_debug_marker(command_buffer: &CommandBuffer, name: &str, color: [f32; 4])
+        -> VdResult<()> {
+    match command_buffer.device().debug_region_backend() {
+        DebugRegionBackend::DebugUtils => unsafe {
+            command_buffer.device().cmd_insert_debug_label(command_buffer.handle(), name, color)
+        },
+        DebugRegionBackend::DebugMarker => {
+            let name = marker_name(name, "insert_debug_marker")?;
+            let marker_info = DebugMarkerMarkerInfoExt::builder()
+                .marker_name(&name)
+                .color(color)
+                .build();
+            unsafe {
+                command_buffer.device().cmd_debug_marker_insert_ext(command_buffer.handle(),
+                    &marker_info);
+            }
+            Ok(())
+        },
+        DebugRegionBackend::None => Ok(()),
+    }
+}
@@ -0,0 +1,97 @@
+//! `VK_EXT_debug_utils` labels.
+//!
+//! `vks` predates `VK_EXT_debug_utils` so the few types needed for queue and
+//! command buffer labels are declared here and the functions are loaded by
+//! name.
+
+use std::mem;
+use std::ptr;
+use libc::{c_void, c_char};
+use smallvec::SmallVec;
+use vks;
+use ::{VdResult, Instance, names};
+
+
+const VK_STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT: vks::VkStructureType = 1000128002;
+
+
+#[repr(C)]
+pub(crate) struct VkDebugUtilsLabelEXT {
+    s_type: vks::VkStructureType,
+    p_next: *const c_void,
+    p_label_name: *const c_char,
+    color: [f32; 4],
+}
+
+type PfnQueueLabel = unsafe extern "system" fn(vks::VkQueue, *const VkDebugUtilsLabelEXT);
+type PfnQueueEndLabel = unsafe extern "system" fn(vks::VkQueue);
+type PfnCmdLabel = unsafe extern "system" fn(vks::VkCommandBuffer, *const VkDebugUtilsLabelEXT);
+type PfnCmdEndLabel = unsafe extern "system" fn(vks::VkCommandBuffer);
+
+
+/// A null-terminated copy of a label name and the label referencing it.
+pub(crate) struct Label {
+    name: SmallVec<[u8; 64]>,
+    color: [f32; 4],
+}
+
+impl Label {
+    pub(crate) fn new(name: &str, color: [f32; 4]) -> VdResult<Label> {
+        if name.as_bytes().contains(&0) {
+            return Err("debug label names must not contain nul bytes".into());
+        }
+        let mut bytes = SmallVec::new();
+        bytes.extend(name.bytes());
+        bytes.push(0);
+        Ok(Label { name: bytes, color })
+    }
+
+    pub(crate) fn raw(&self) -> VkDebugUtilsLabelEXT {
+        VkDebugUtilsLabelEXT {
+            s_type: VK_STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT,
+            p_next: ptr::null(),
+            p_label_name: self.name.as_ptr() as *const c_char,
+            color: self.color,
+        }
+    }
+}
+
+
+/// Queue and command buffer label functions.
+///
+/// Each is `None` unless `VK_EXT_debug_utils` was enabled on the instance.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DebugUtilsLabelFns {
+    pub queue_begin: Option<PfnQueueLabel>,
+    pub queue_end: Option<PfnQueueEndLabel>,
+    pub queue_insert: Option<PfnQueueLabel>,
+    pub cmd_begin: Option<PfnCmdLabel>,
+    pub cmd_end: Option<PfnCmdEndLabel>,
+    pub cmd_insert: Option<PfnCmdLabel>,
+}
+
+impl DebugUtilsLabelFns {
+    pub(crate) fn load(instance: &Instance) -> DebugUtilsLabelFns {
+        // Loaders may return functions for extensions which were not enabled:
+        if !instance.is_extension_enabled(names::EXT_DEBUG_UTILS) {
+            return DebugUtilsLabelFns::default();
+        }
+        let get_proc_addr = match instance.loader().get_instance_proc_addr() {
+            Some(gpa) => gpa,
+            None => return DebugUtilsLabelFns::default(),
+        };
+        let load = |name: &[u8]| unsafe {
+            get_proc_addr(instance.handle().to_raw(), name.as_ptr() as *const _)
+        };
+        unsafe {
+            DebugUtilsLabelFns {
+                queue_begin: load(b"vkQueueBeginDebugUtilsLabelEXT\0").map(|f| mem::transmute(f)),
+                queue_end: load(b"vkQueueEndDebugUtilsLabelEXT\0").map(|f| mem::transmute(f)),
+                queue_insert: load(b"vkQueueInsertDebugUtilsLabelEXT\0").map(|f| mem::transmute(f)),
+                cmd_begin: load(b"vkCmdBeginDebugUtilsLabelEXT\0").map(|f| mem::transmute(f)),
+                cmd_end: load(b"vkCmdEndDebugUtilsLabelEXT\0").map(|f| mem::transmute(f)),
+                cmd_insert: load(b"vkCmdInsertDebugUtilsLabelEXT\0").map(|f| mem::transmute(f)),
+            }
+        }
+    }
+}
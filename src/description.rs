@@ -0,0 +1,790 @@
+//! Plain-data descriptions of render passes and graphics pipelines.
+//!
+//! The types in this module contain no handles or raw pointers and can be
+//! freely stored, cloned, and compared. With the `serde` feature enabled
+//! they (along with all enums and flags) implement `Serialize` and
+//! `Deserialize`, allowing render passes and pipelines to be described in
+//! data files.
+//!
+//! Use `RenderPassDescription::build` (or `with_builder`) and
+//! `GraphicsPipelineDescription::with_builder` to convert a description into
+//! the corresponding builder. Handles (shader modules, layouts, render
+//! passes) are supplied at that point.
+//!
+
+use smallvec::SmallVec;
+use ::{VdResult, Device, RenderPass, RenderPassBuilder, GraphicsPipelineBuilder, Format,
+    SampleCountFlags, AttachmentLoadOp, AttachmentStoreOp, ImageLayout,
+    AttachmentDescription, AttachmentReference, PipelineBindPoint, SubpassDescription,
+    SubpassDependency, PipelineStageFlags, AccessFlags, DependencyFlags, VertexInputRate,
+    VertexInputBindingDescription, VertexInputAttributeDescription, PrimitiveTopology,
+    PolygonMode, CullModeFlags, FrontFace, StencilOp, CompareOp, StencilOpState, BlendFactor,
+    BlendOp, ColorComponentFlags, LogicOp, PipelineColorBlendAttachmentState, DynamicState,
+    ShaderStageFlags, PushConstantRange, Viewport, Rect2d, Offset2d, Extent2d,
+    PipelineShaderStageCreateInfo, PipelineVertexInputStateCreateInfo,
+    PipelineInputAssemblyStateCreateInfo, PipelineViewportStateCreateInfo,
+    PipelineRasterizationStateCreateInfo, PipelineMultisampleStateCreateInfo,
+    PipelineDepthStencilStateCreateInfo, PipelineColorBlendStateCreateInfo,
+    PipelineDynamicStateCreateInfo};
+
+
+/// A render pass attachment.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AttachmentDesc {
+    pub flags: ::bitflags::AttachmentDescriptionFlags,
+    pub format: Format,
+    pub samples: SampleCountFlags,
+    pub load_op: AttachmentLoadOp,
+    pub store_op: AttachmentStoreOp,
+    pub stencil_load_op: AttachmentLoadOp,
+    pub stencil_store_op: AttachmentStoreOp,
+    pub initial_layout: ImageLayout,
+    pub final_layout: ImageLayout,
+}
+
+impl AttachmentDesc {
+    /// Returns the equivalent `AttachmentDescription`.
+    pub fn to_attachment_description(&self) -> AttachmentDescription {
+        // `structs` defines a same-named flags type; the raw bits are identical.
+        AttachmentDescription::builder()
+            .flags(::structs::AttachmentDescriptionFlags::from_bits_truncate(self.flags.bits()))
+            .format(self.format)
+            .samples(self.samples)
+            .load_op(self.load_op)
+            .store_op(self.store_op)
+            .stencil_load_op(self.stencil_load_op)
+            .stencil_store_op(self.stencil_store_op)
+            .initial_layout(self.initial_layout)
+            .final_layout(self.final_layout)
+            .build()
+    }
+}
+
+
+/// A reference to a render pass attachment, by index.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AttachmentRef {
+    pub attachment: u32,
+    pub layout: ImageLayout,
+}
+
+impl AttachmentRef {
+    /// Returns the equivalent `AttachmentReference`.
+    pub fn to_attachment_reference(&self) -> AttachmentReference {
+        AttachmentReference::builder()
+            .attachment(self.attachment)
+            .layout(self.layout)
+            .build()
+    }
+}
+
+
+/// A subpass, referring to attachments by index.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SubpassDesc {
+    pub pipeline_bind_point: PipelineBindPoint,
+    pub input_attachments: Vec<AttachmentRef>,
+    pub color_attachments: Vec<AttachmentRef>,
+    /// Must be empty or the same length as `color_attachments`.
+    pub resolve_attachments: Vec<AttachmentRef>,
+    pub depth_stencil_attachment: Option<AttachmentRef>,
+    pub preserve_attachments: Vec<u32>,
+}
+
+
+/// A dependency between two subpasses.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SubpassDependencyDesc {
+    pub src_subpass: u32,
+    pub dst_subpass: u32,
+    pub src_stage_mask: PipelineStageFlags,
+    pub dst_stage_mask: PipelineStageFlags,
+    pub src_access_mask: AccessFlags,
+    pub dst_access_mask: AccessFlags,
+    pub dependency_flags: DependencyFlags,
+}
+
+impl SubpassDependencyDesc {
+    /// Returns the equivalent `SubpassDependency`.
+    pub fn to_subpass_dependency(&self) -> SubpassDependency {
+        SubpassDependency::builder()
+            .src_subpass(self.src_subpass)
+            .dst_subpass(self.dst_subpass)
+            .src_stage_mask(self.src_stage_mask)
+            .dst_stage_mask(self.dst_stage_mask)
+            .src_access_mask(self.src_access_mask)
+            .dst_access_mask(self.dst_access_mask)
+            .dependency_flags(self.dependency_flags)
+            .build()
+    }
+}
+
+
+/// An owned, handle-free description of a render pass.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RenderPassDescription {
+    pub attachments: Vec<AttachmentDesc>,
+    pub subpasses: Vec<SubpassDesc>,
+    pub dependencies: Vec<SubpassDependencyDesc>,
+}
+
+impl RenderPassDescription {
+    /// Calls `f` with a `RenderPassBuilder` populated from this description.
+    pub fn with_builder<F, R>(&self, f: F) -> R
+            where F: FnOnce(&mut RenderPassBuilder) -> R {
+        let attachments: SmallVec<[AttachmentDescription; 8]> = self.attachments.iter()
+            .map(|a| a.to_attachment_description()).collect();
+        let dependencies: SmallVec<[SubpassDependency; 8]> = self.dependencies.iter()
+            .map(|d| d.to_subpass_dependency()).collect();
+
+        // Attachment references for every subpass must be kept alive (and in
+        // place) until the builder is consumed.
+        let refs: SmallVec<[SubpassRefs; 4]> = self.subpasses.iter()
+            .map(SubpassRefs::new).collect();
+        let subpasses: SmallVec<[SubpassDescription; 4]> = self.subpasses.iter().zip(refs.iter())
+            .map(|(sp, refs)| refs.subpass_description(sp)).collect();
+
+        let mut builder = RenderPass::builder();
+        builder.attachments(&attachments)
+            .subpasses(&subpasses)
+            .dependencies(&dependencies);
+        f(&mut builder)
+    }
+
+    /// Builds and returns a new `RenderPass`.
+    pub fn build(&self, device: Device) -> VdResult<RenderPass> {
+        self.with_builder(|builder| builder.build(device))
+    }
+}
+
+
+/// Converted attachment references for a single subpass.
+struct SubpassRefs {
+    input: SmallVec<[AttachmentReference; 4]>,
+    color: SmallVec<[AttachmentReference; 4]>,
+    resolve: SmallVec<[AttachmentReference; 4]>,
+    depth_stencil: Option<AttachmentReference>,
+}
+
+impl SubpassRefs {
+    fn new(subpass: &SubpassDesc) -> SubpassRefs {
+        SubpassRefs {
+            input: subpass.input_attachments.iter().map(|r| r.to_attachment_reference()).collect(),
+            color: subpass.color_attachments.iter().map(|r| r.to_attachment_reference()).collect(),
+            resolve: subpass.resolve_attachments.iter().map(|r| r.to_attachment_reference()).collect(),
+            depth_stencil: subpass.depth_stencil_attachment.as_ref()
+                .map(|r| r.to_attachment_reference()),
+        }
+    }
+
+    fn subpass_description<'s>(&'s self, subpass: &'s SubpassDesc) -> SubpassDescription<'s> {
+        let mut builder = SubpassDescription::builder()
+            .pipeline_bind_point(subpass.pipeline_bind_point);
+        if !self.input.is_empty() { builder = builder.input_attachments(&self.input); }
+        if !self.color.is_empty() { builder = builder.color_attachments(&self.color); }
+        if !self.resolve.is_empty() { builder = builder.resolve_attachments(&self.resolve); }
+        if let Some(ref ds) = self.depth_stencil { builder = builder.depth_stencil_attachment(ds); }
+        if !subpass.preserve_attachments.is_empty() {
+            builder = builder.preserve_attachments(&subpass.preserve_attachments);
+        }
+        builder.build()
+    }
+}
+
+
+/// A vertex input binding.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VertexBindingDesc {
+    pub binding: u32,
+    pub stride: u32,
+    pub input_rate: VertexInputRate,
+}
+
+impl VertexBindingDesc {
+    /// Returns the equivalent `VertexInputBindingDescription`.
+    pub fn to_vertex_input_binding_description(&self) -> VertexInputBindingDescription {
+        VertexInputBindingDescription::builder()
+            .binding(self.binding)
+            .stride(self.stride)
+            .input_rate(self.input_rate)
+            .build()
+    }
+}
+
+
+/// A vertex input attribute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VertexAttributeDesc {
+    pub location: u32,
+    pub binding: u32,
+    pub format: Format,
+    pub offset: u32,
+}
+
+impl VertexAttributeDesc {
+    /// Returns the equivalent `VertexInputAttributeDescription`.
+    pub fn to_vertex_input_attribute_description(&self) -> VertexInputAttributeDescription {
+        VertexInputAttributeDescription::builder()
+            .location(self.location)
+            .binding(self.binding)
+            .format(self.format)
+            .offset(self.offset)
+            .build()
+    }
+}
+
+
+/// Input assembly state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InputAssemblyDesc {
+    pub topology: PrimitiveTopology,
+    pub primitive_restart_enable: bool,
+}
+
+
+/// A viewport.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ViewportDesc {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub min_depth: f32,
+    pub max_depth: f32,
+}
+
+impl ViewportDesc {
+    /// Returns the equivalent `Viewport`.
+    pub fn to_viewport(&self) -> Viewport {
+        Viewport::builder()
+            .x(self.x)
+            .y(self.y)
+            .width(self.width)
+            .height(self.height)
+            .min_depth(self.min_depth)
+            .max_depth(self.max_depth)
+            .build()
+    }
+}
+
+
+/// A scissor rectangle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScissorDesc {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScissorDesc {
+    /// Returns the equivalent `Rect2d`.
+    pub fn to_rect_2d(&self) -> Rect2d {
+        Rect2d::builder()
+            .offset(Offset2d::builder().x(self.x).y(self.y).build())
+            .extent(Extent2d::builder().width(self.width).height(self.height).build())
+            .build()
+    }
+}
+
+
+/// Rasterization state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RasterizationDesc {
+    pub depth_clamp_enable: bool,
+    pub rasterizer_discard_enable: bool,
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullModeFlags,
+    pub front_face: FrontFace,
+    pub depth_bias_enable: bool,
+    pub depth_bias_constant_factor: f32,
+    pub depth_bias_clamp: f32,
+    pub depth_bias_slope_factor: f32,
+    pub line_width: f32,
+}
+
+
+/// Multisample state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MultisampleDesc {
+    pub rasterization_samples: SampleCountFlags,
+    pub sample_shading_enable: bool,
+    pub min_sample_shading: f32,
+    pub alpha_to_coverage_enable: bool,
+    pub alpha_to_one_enable: bool,
+}
+
+
+/// Stencil operations for one face.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StencilOpDesc {
+    pub fail_op: StencilOp,
+    pub pass_op: StencilOp,
+    pub depth_fail_op: StencilOp,
+    pub compare_op: CompareOp,
+    pub compare_mask: u32,
+    pub write_mask: u32,
+    pub reference: u32,
+}
+
+impl StencilOpDesc {
+    /// Returns the equivalent `StencilOpState`.
+    pub fn to_stencil_op_state(&self) -> StencilOpState {
+        StencilOpState::builder()
+            .fail_op(self.fail_op)
+            .pass_op(self.pass_op)
+            .depth_fail_op(self.depth_fail_op)
+            .compare_op(self.compare_op)
+            .compare_mask(self.compare_mask)
+            .write_mask(self.write_mask)
+            .reference(self.reference)
+            .build()
+    }
+}
+
+
+/// Depth and stencil state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DepthStencilDesc {
+    pub depth_test_enable: bool,
+    pub depth_write_enable: bool,
+    pub depth_compare_op: CompareOp,
+    pub depth_bounds_test_enable: bool,
+    pub stencil_test_enable: bool,
+    pub front: StencilOpDesc,
+    pub back: StencilOpDesc,
+    pub min_depth_bounds: f32,
+    pub max_depth_bounds: f32,
+}
+
+
+/// Blend state for a single color attachment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ColorBlendAttachmentDesc {
+    pub blend_enable: bool,
+    pub src_color_blend_factor: BlendFactor,
+    pub dst_color_blend_factor: BlendFactor,
+    pub color_blend_op: BlendOp,
+    pub src_alpha_blend_factor: BlendFactor,
+    pub dst_alpha_blend_factor: BlendFactor,
+    pub alpha_blend_op: BlendOp,
+    pub color_write_mask: ColorComponentFlags,
+}
+
+impl ColorBlendAttachmentDesc {
+    /// Returns the equivalent `PipelineColorBlendAttachmentState`.
+    pub fn to_color_blend_attachment_state(&self) -> PipelineColorBlendAttachmentState {
+        PipelineColorBlendAttachmentState::builder()
+            .blend_enable(self.blend_enable)
+            .src_color_blend_factor(self.src_color_blend_factor)
+            .dst_color_blend_factor(self.dst_color_blend_factor)
+            .color_blend_op(self.color_blend_op)
+            .src_alpha_blend_factor(self.src_alpha_blend_factor)
+            .dst_alpha_blend_factor(self.dst_alpha_blend_factor)
+            .alpha_blend_op(self.alpha_blend_op)
+            .color_write_mask(self.color_write_mask)
+            .build()
+    }
+}
+
+
+/// Color blend state.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ColorBlendDesc {
+    pub logic_op_enable: bool,
+    pub logic_op: LogicOp,
+    pub attachments: Vec<ColorBlendAttachmentDesc>,
+    pub blend_constants: [f32; 4],
+}
+
+
+/// A push constant range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PushConstantRangeDesc {
+    pub stage_flags: ShaderStageFlags,
+    pub offset: u32,
+    pub size: u32,
+}
+
+impl PushConstantRangeDesc {
+    /// Returns the equivalent `PushConstantRange`.
+    pub fn to_push_constant_range(&self) -> PushConstantRange {
+        PushConstantRange::builder()
+            .stage_flags(self.stage_flags)
+            .offset(self.offset)
+            .size(self.size)
+            .build()
+    }
+}
+
+
+/// An owned, handle-free description of the fixed-function state of a
+/// graphics pipeline.
+///
+/// Shader stages, the pipeline layout, and the render pass are not part of
+/// the description and must be supplied when building.
+///
+/// When `viewports` or `scissors` is empty a single placeholder is used,
+/// which is only valid if the corresponding state is listed in
+/// `dynamic_states`.
+///
+/// `push_constant_ranges` is not used by the pipeline itself and is provided
+/// for convenience when creating the pipeline layout (see
+/// `push_constant_ranges`).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphicsPipelineDescription {
+    pub vertex_bindings: Vec<VertexBindingDesc>,
+    pub vertex_attributes: Vec<VertexAttributeDesc>,
+    pub input_assembly: InputAssemblyDesc,
+    pub viewports: Vec<ViewportDesc>,
+    pub scissors: Vec<ScissorDesc>,
+    pub rasterization: RasterizationDesc,
+    pub multisample: MultisampleDesc,
+    pub depth_stencil: Option<DepthStencilDesc>,
+    pub color_blend: ColorBlendDesc,
+    pub dynamic_states: Vec<DynamicState>,
+    pub push_constant_ranges: Vec<PushConstantRangeDesc>,
+    pub subpass: u32,
+}
+
+impl GraphicsPipelineDescription {
+    /// Returns the push constant ranges, for use with
+    /// `PipelineLayoutBuilder::push_constant_ranges`.
+    pub fn push_constant_ranges(&self) -> SmallVec<[PushConstantRange; 4]> {
+        self.push_constant_ranges.iter().map(|r| r.to_push_constant_range()).collect()
+    }
+
+    /// Calls `f` with a `GraphicsPipelineBuilder` populated from this
+    /// description and `stages`.
+    ///
+    /// The layout and render pass (and any derivative pipeline settings)
+    /// must be set by `f` before building.
+    pub fn with_builder<F, R>(&self, stages: &[PipelineShaderStageCreateInfo], f: F) -> R
+            where F: FnOnce(&mut GraphicsPipelineBuilder) -> R {
+        let vertex_bindings: SmallVec<[VertexInputBindingDescription; 4]> = self.vertex_bindings
+            .iter().map(|b| b.to_vertex_input_binding_description()).collect();
+        let vertex_attributes: SmallVec<[VertexInputAttributeDescription; 8]> = self
+            .vertex_attributes.iter().map(|a| a.to_vertex_input_attribute_description()).collect();
+        let mut vertex_input = PipelineVertexInputStateCreateInfo::builder();
+        if !vertex_bindings.is_empty() {
+            vertex_input = vertex_input.vertex_binding_descriptions(&vertex_bindings);
+        }
+        if !vertex_attributes.is_empty() {
+            vertex_input = vertex_input.vertex_attribute_descriptions(&vertex_attributes);
+        }
+        let vertex_input = vertex_input.build();
+
+        let input_assembly = PipelineInputAssemblyStateCreateInfo::builder()
+            .topology(self.input_assembly.topology)
+            .primitive_restart_enable(self.input_assembly.primitive_restart_enable)
+            .build();
+
+        let mut viewports: SmallVec<[Viewport; 1]> = self.viewports.iter()
+            .map(|v| v.to_viewport()).collect();
+        if viewports.is_empty() { viewports.push(Viewport::default()); }
+        let mut scissors: SmallVec<[Rect2d; 1]> = self.scissors.iter()
+            .map(|s| s.to_rect_2d()).collect();
+        if scissors.is_empty() { scissors.push(Rect2d::default()); }
+        let viewport = PipelineViewportStateCreateInfo::builder()
+            .viewports(&viewports)
+            .scissors(&scissors)
+            .build();
+
+        let r = &self.rasterization;
+        let rasterization = PipelineRasterizationStateCreateInfo::builder()
+            .depth_clamp_enable(r.depth_clamp_enable)
+            .rasterizer_discard_enable(r.rasterizer_discard_enable)
+            .polygon_mode(r.polygon_mode)
+            .cull_mode(r.cull_mode)
+            .front_face(r.front_face)
+            .depth_bias_enable(r.depth_bias_enable)
+            .depth_bias_constant_factor(r.depth_bias_constant_factor)
+            .depth_bias_clamp(r.depth_bias_clamp)
+            .depth_bias_slope_factor(r.depth_bias_slope_factor)
+            .line_width(r.line_width)
+            .build();
+
+        let m = &self.multisample;
+        let multisample = PipelineMultisampleStateCreateInfo::builder()
+            .rasterization_samples(m.rasterization_samples)
+            .sample_shading_enable(m.sample_shading_enable)
+            .min_sample_shading(m.min_sample_shading)
+            .alpha_to_coverage_enable(m.alpha_to_coverage_enable)
+            .alpha_to_one_enable(m.alpha_to_one_enable)
+            .build();
+
+        let depth_stencil = self.depth_stencil.as_ref().map(|ds| {
+            PipelineDepthStencilStateCreateInfo::builder()
+                .depth_test_enable(ds.depth_test_enable)
+                .depth_write_enable(ds.depth_write_enable)
+                .depth_compare_op(ds.depth_compare_op)
+                .depth_bounds_test_enable(ds.depth_bounds_test_enable)
+                .stencil_test_enable(ds.stencil_test_enable)
+                .front(ds.front.to_stencil_op_state())
+                .back(ds.back.to_stencil_op_state())
+                .min_depth_bounds(ds.min_depth_bounds)
+                .max_depth_bounds(ds.max_depth_bounds)
+                .build()
+        });
+
+        let blend_attachments: SmallVec<[PipelineColorBlendAttachmentState; 4]> = self.color_blend
+            .attachments.iter().map(|a| a.to_color_blend_attachment_state()).collect();
+        let mut color_blend = PipelineColorBlendStateCreateInfo::builder()
+            .logic_op_enable(self.color_blend.logic_op_enable)
+            .logic_op(self.color_blend.logic_op)
+            .blend_constants(self.color_blend.blend_constants);
+        if !blend_attachments.is_empty() {
+            color_blend = color_blend.attachments(&blend_attachments);
+        }
+        let color_blend = color_blend.build();
+
+        let dynamic = if self.dynamic_states.is_empty() {
+            None
+        } else {
+            Some(PipelineDynamicStateCreateInfo::builder()
+                .dynamic_states(&self.dynamic_states)
+                .build())
+        };
+
+        let mut builder = GraphicsPipelineBuilder::new();
+        builder.stages(stages)
+            .vertex_input_state(&vertex_input)
+            .input_assembly_state(&input_assembly)
+            .viewport_state(&viewport)
+            .rasterization_state(&rasterization)
+            .multisample_state(&multisample)
+            .color_blend_state(&color_blend)
+            .subpass(self.subpass);
+        if let Some(ref depth_stencil) = depth_stencil {
+            builder.depth_stencil_state(depth_stencil);
+        }
+        if let Some(ref dynamic) = dynamic {
+            builder.dynamic_state(dynamic);
+        }
+        f(&mut builder)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+    use ::{Format, SampleCountFlags, AttachmentLoadOp, AttachmentStoreOp, ImageLayout,
+        PipelineBindPoint, PipelineStageFlags, AccessFlags, DependencyFlags, VertexInputRate,
+        PrimitiveTopology, PolygonMode, CullModeFlags, FrontFace, StencilOp, CompareOp,
+        BlendFactor, BlendOp, ColorComponentFlags, LogicOp, DynamicState, ShaderStageFlags};
+    use super::*;
+
+    fn render_pass() -> RenderPassDescription {
+        RenderPassDescription {
+            attachments: vec![
+                AttachmentDesc {
+                    flags: ::bitflags::AttachmentDescriptionFlags::empty(),
+                    format: Format::B8G8R8A8Unorm,
+                    samples: SampleCountFlags::COUNT_1,
+                    load_op: AttachmentLoadOp::Clear,
+                    store_op: AttachmentStoreOp::Store,
+                    stencil_load_op: AttachmentLoadOp::DontCare,
+                    stencil_store_op: AttachmentStoreOp::DontCare,
+                    initial_layout: ImageLayout::Undefined,
+                    final_layout: ImageLayout::PresentSrcKhr,
+                },
+                AttachmentDesc {
+                    flags: ::bitflags::AttachmentDescriptionFlags::MAY_ALIAS,
+                    format: Format::D32Sfloat,
+                    samples: SampleCountFlags::COUNT_1,
+                    load_op: AttachmentLoadOp::Clear,
+                    store_op: AttachmentStoreOp::DontCare,
+                    stencil_load_op: AttachmentLoadOp::DontCare,
+                    stencil_store_op: AttachmentStoreOp::DontCare,
+                    initial_layout: ImageLayout::Undefined,
+                    final_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                },
+            ],
+            subpasses: vec![SubpassDesc {
+                pipeline_bind_point: PipelineBindPoint::Graphics,
+                input_attachments: Vec::new(),
+                color_attachments: vec![AttachmentRef {
+                    attachment: 0,
+                    layout: ImageLayout::ColorAttachmentOptimal,
+                }],
+                resolve_attachments: Vec::new(),
+                depth_stencil_attachment: Some(AttachmentRef {
+                    attachment: 1,
+                    layout: ImageLayout::DepthStencilAttachmentOptimal,
+                }),
+                preserve_attachments: Vec::new(),
+            }],
+            dependencies: vec![SubpassDependencyDesc {
+                src_subpass: !0,
+                dst_subpass: 0,
+                src_stage_mask: PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                dst_stage_mask: PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                src_access_mask: AccessFlags::empty(),
+                dst_access_mask: AccessFlags::COLOR_ATTACHMENT_READ |
+                    AccessFlags::COLOR_ATTACHMENT_WRITE,
+                dependency_flags: DependencyFlags::empty(),
+            }],
+        }
+    }
+
+    fn graphics_pipeline() -> GraphicsPipelineDescription {
+        let stencil = StencilOpDesc {
+            fail_op: StencilOp::Keep,
+            pass_op: StencilOp::Keep,
+            depth_fail_op: StencilOp::Keep,
+            compare_op: CompareOp::Always,
+            compare_mask: 0xff,
+            write_mask: 0,
+            reference: 0,
+        };
+        GraphicsPipelineDescription {
+            vertex_bindings: vec![VertexBindingDesc {
+                binding: 0,
+                stride: 20,
+                input_rate: VertexInputRate::Vertex,
+            }],
+            vertex_attributes: vec![
+                VertexAttributeDesc { location: 0, binding: 0, format: Format::R32G32B32Sfloat,
+                    offset: 0 },
+                VertexAttributeDesc { location: 1, binding: 0, format: Format::R32G32Sfloat,
+                    offset: 12 },
+            ],
+            input_assembly: InputAssemblyDesc {
+                topology: PrimitiveTopology::TriangleList,
+                primitive_restart_enable: false,
+            },
+            viewports: Vec::new(),
+            scissors: vec![ScissorDesc { x: 0, y: 0, width: 1024, height: 768 }],
+            rasterization: RasterizationDesc {
+                depth_clamp_enable: false,
+                rasterizer_discard_enable: false,
+                polygon_mode: PolygonMode::Fill,
+                cull_mode: CullModeFlags::BACK,
+                front_face: FrontFace::CounterClockwise,
+                depth_bias_enable: false,
+                depth_bias_constant_factor: 0.0,
+                depth_bias_clamp: 0.0,
+                depth_bias_slope_factor: 0.0,
+                line_width: 1.0,
+            },
+            multisample: MultisampleDesc {
+                rasterization_samples: SampleCountFlags::COUNT_1,
+                sample_shading_enable: false,
+                min_sample_shading: 1.0,
+                alpha_to_coverage_enable: false,
+                alpha_to_one_enable: false,
+            },
+            depth_stencil: Some(DepthStencilDesc {
+                depth_test_enable: true,
+                depth_write_enable: true,
+                depth_compare_op: CompareOp::Less,
+                depth_bounds_test_enable: false,
+                stencil_test_enable: false,
+                front: stencil,
+                back: stencil,
+                min_depth_bounds: 0.0,
+                max_depth_bounds: 1.0,
+            }),
+            color_blend: ColorBlendDesc {
+                logic_op_enable: false,
+                logic_op: LogicOp::Copy,
+                attachments: vec![ColorBlendAttachmentDesc {
+                    blend_enable: true,
+                    src_color_blend_factor: BlendFactor::SrcAlpha,
+                    dst_color_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                    color_blend_op: BlendOp::Add,
+                    src_alpha_blend_factor: BlendFactor::One,
+                    dst_alpha_blend_factor: BlendFactor::Zero,
+                    alpha_blend_op: BlendOp::Add,
+                    color_write_mask: ColorComponentFlags::R | ColorComponentFlags::G |
+                        ColorComponentFlags::B | ColorComponentFlags::A,
+                }],
+                blend_constants: [0.0, 0.0, 0.0, 0.0],
+            },
+            dynamic_states: vec![DynamicState::Viewport],
+            push_constant_ranges: vec![PushConstantRangeDesc {
+                stage_flags: ShaderStageFlags::VERTEX,
+                offset: 0,
+                size: 64,
+            }],
+            subpass: 0,
+        }
+    }
+
+    #[test]
+    fn subpass_refs_convert_by_index() {
+        let subpass = &render_pass().subpasses[0];
+        let refs = SubpassRefs::new(subpass);
+        let description = refs.subpass_description(subpass);
+        assert_eq!(description.pipeline_bind_point(), PipelineBindPoint::Graphics);
+        assert_eq!(description.color_attachments().len(), 1);
+        assert_eq!(description.color_attachments()[0].attachment(), 0);
+        assert_eq!(description.color_attachments()[0].layout(), ImageLayout::ColorAttachmentOptimal);
+        assert_eq!(description.depth_stencil_attachment().attachment(), 1);
+
+        // Empty lists must not be passed as dangling pointers.
+        let raw = description.as_raw();
+        assert_eq!(raw.inputAttachmentCount, 0);
+        assert_eq!(raw.pInputAttachments, ptr::null());
+        assert_eq!(raw.pResolveAttachments, ptr::null());
+        assert_eq!(raw.preserveAttachmentCount, 0);
+        assert_eq!(raw.pPreserveAttachments, ptr::null());
+    }
+
+    #[test]
+    fn descriptions_convert_field_for_field() {
+        let render_pass = render_pass();
+        let attachment = render_pass.attachments[1].to_attachment_description();
+        assert_eq!(attachment.flags().bits(), ::bitflags::AttachmentDescriptionFlags::MAY_ALIAS.bits());
+        assert_eq!(attachment.format(), Format::D32Sfloat);
+        assert_eq!(attachment.final_layout(), ImageLayout::DepthStencilAttachmentOptimal);
+        let dependency = render_pass.dependencies[0].to_subpass_dependency();
+        assert_eq!(dependency.src_subpass(), !0);
+        assert_eq!(dependency.dst_access_mask(),
+            AccessFlags::COLOR_ATTACHMENT_READ | AccessFlags::COLOR_ATTACHMENT_WRITE);
+
+        let pipeline = graphics_pipeline();
+        let scissor = pipeline.scissors[0].to_rect_2d();
+        assert_eq!((scissor.extent().width(), scissor.extent().height()), (1024, 768));
+        let attribute = pipeline.vertex_attributes[1].to_vertex_input_attribute_description();
+        assert_eq!((attribute.location(), attribute.offset()), (1, 12));
+        assert_eq!(attribute.format(), Format::R32G32Sfloat);
+        let ranges = pipeline.push_constant_ranges();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].stage_flags(), ShaderStageFlags::VERTEX);
+        assert_eq!(ranges[0].size(), 64);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn descriptions_round_trip_through_json() {
+        let render_pass = render_pass();
+        let json = ::serde_json::to_string(&render_pass).unwrap();
+        let parsed: RenderPassDescription = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, render_pass);
+
+        let pipeline = graphics_pipeline();
+        let json = ::serde_json::to_string_pretty(&pipeline).unwrap();
+        let parsed: GraphicsPipelineDescription = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, pipeline);
+    }
+}
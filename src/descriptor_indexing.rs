@@ -0,0 +1,608 @@
+//! `VK_EXT_descriptor_indexing` structures.
+//!
+//! `vks` predates `VK_EXT_descriptor_indexing` so the raw structures are
+//! declared here. The wrappers mirror those in `structs` and are chained onto
+//! the structures they extend by `DescriptorSetLayoutBuilder::binding_flags`,
+//! `DescriptorPool::allocate_descriptor_sets_variable`, and (through a
+//! `PhysicalDeviceFeatures2Khr`) `DeviceBuilder::enabled_features_2`.
+
+use std::ptr;
+use std::slice;
+use std::marker::PhantomData;
+use libc::c_void;
+use vks;
+use ::DescriptorBindingFlagsExt;
+
+
+const VK_STRUCTURE_TYPE_DESCRIPTOR_SET_LAYOUT_BINDING_FLAGS_CREATE_INFO_EXT: vks::VkStructureType = 1000161000;
+const VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_DESCRIPTOR_INDEXING_FEATURES_EXT: vks::VkStructureType = 1000161001;
+const VK_STRUCTURE_TYPE_DESCRIPTOR_SET_VARIABLE_DESCRIPTOR_COUNT_ALLOCATE_INFO_EXT: vks::VkStructureType = 1000161003;
+
+
+/// A raw `VkDescriptorSetLayoutBindingFlagsCreateInfoEXT`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkDescriptorSetLayoutBindingFlagsCreateInfoEXT {
+    pub sType: vks::VkStructureType,
+    pub pNext: *const c_void,
+    pub bindingCount: u32,
+    pub pBindingFlags: *const u32,
+}
+
+impl Default for VkDescriptorSetLayoutBindingFlagsCreateInfoEXT {
+    fn default() -> Self {
+        VkDescriptorSetLayoutBindingFlagsCreateInfoEXT {
+            sType: VK_STRUCTURE_TYPE_DESCRIPTOR_SET_LAYOUT_BINDING_FLAGS_CREATE_INFO_EXT,
+            pNext: ptr::null(),
+            bindingCount: 0,
+            pBindingFlags: ptr::null(),
+        }
+    }
+}
+
+
+/// A raw `VkDescriptorSetVariableDescriptorCountAllocateInfoEXT`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkDescriptorSetVariableDescriptorCountAllocateInfoEXT {
+    pub sType: vks::VkStructureType,
+    pub pNext: *const c_void,
+    pub descriptorSetCount: u32,
+    pub pDescriptorCounts: *const u32,
+}
+
+impl Default for VkDescriptorSetVariableDescriptorCountAllocateInfoEXT {
+    fn default() -> Self {
+        VkDescriptorSetVariableDescriptorCountAllocateInfoEXT {
+            sType: VK_STRUCTURE_TYPE_DESCRIPTOR_SET_VARIABLE_DESCRIPTOR_COUNT_ALLOCATE_INFO_EXT,
+            pNext: ptr::null(),
+            descriptorSetCount: 0,
+            pDescriptorCounts: ptr::null(),
+        }
+    }
+}
+
+
+/// A raw `VkPhysicalDeviceDescriptorIndexingFeaturesEXT`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkPhysicalDeviceDescriptorIndexingFeaturesEXT {
+    pub sType: vks::VkStructureType,
+    pub pNext: *mut c_void,
+    pub shaderInputAttachmentArrayDynamicIndexing: vks::VkBool32,
+    pub shaderUniformTexelBufferArrayDynamicIndexing: vks::VkBool32,
+    pub shaderStorageTexelBufferArrayDynamicIndexing: vks::VkBool32,
+    pub shaderUniformBufferArrayNonUniformIndexing: vks::VkBool32,
+    pub shaderSampledImageArrayNonUniformIndexing: vks::VkBool32,
+    pub shaderStorageBufferArrayNonUniformIndexing: vks::VkBool32,
+    pub shaderStorageImageArrayNonUniformIndexing: vks::VkBool32,
+    pub shaderInputAttachmentArrayNonUniformIndexing: vks::VkBool32,
+    pub shaderUniformTexelBufferArrayNonUniformIndexing: vks::VkBool32,
+    pub shaderStorageTexelBufferArrayNonUniformIndexing: vks::VkBool32,
+    pub descriptorBindingUniformBufferUpdateAfterBind: vks::VkBool32,
+    pub descriptorBindingSampledImageUpdateAfterBind: vks::VkBool32,
+    pub descriptorBindingStorageImageUpdateAfterBind: vks::VkBool32,
+    pub descriptorBindingStorageBufferUpdateAfterBind: vks::VkBool32,
+    pub descriptorBindingUniformTexelBufferUpdateAfterBind: vks::VkBool32,
+    pub descriptorBindingStorageTexelBufferUpdateAfterBind: vks::VkBool32,
+    pub descriptorBindingUpdateUnusedWhilePending: vks::VkBool32,
+    pub descriptorBindingPartiallyBound: vks::VkBool32,
+    pub descriptorBindingVariableDescriptorCount: vks::VkBool32,
+    pub runtimeDescriptorArray: vks::VkBool32,
+}
+
+impl Default for VkPhysicalDeviceDescriptorIndexingFeaturesEXT {
+    fn default() -> Self {
+        VkPhysicalDeviceDescriptorIndexingFeaturesEXT {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_DESCRIPTOR_INDEXING_FEATURES_EXT,
+            pNext: ptr::null_mut(),
+            shaderInputAttachmentArrayDynamicIndexing: vks::VK_FALSE,
+            shaderUniformTexelBufferArrayDynamicIndexing: vks::VK_FALSE,
+            shaderStorageTexelBufferArrayDynamicIndexing: vks::VK_FALSE,
+            shaderUniformBufferArrayNonUniformIndexing: vks::VK_FALSE,
+            shaderSampledImageArrayNonUniformIndexing: vks::VK_FALSE,
+            shaderStorageBufferArrayNonUniformIndexing: vks::VK_FALSE,
+            shaderStorageImageArrayNonUniformIndexing: vks::VK_FALSE,
+            shaderInputAttachmentArrayNonUniformIndexing: vks::VK_FALSE,
+            shaderUniformTexelBufferArrayNonUniformIndexing: vks::VK_FALSE,
+            shaderStorageTexelBufferArrayNonUniformIndexing: vks::VK_FALSE,
+            descriptorBindingUniformBufferUpdateAfterBind: vks::VK_FALSE,
+            descriptorBindingSampledImageUpdateAfterBind: vks::VK_FALSE,
+            descriptorBindingStorageImageUpdateAfterBind: vks::VK_FALSE,
+            descriptorBindingStorageBufferUpdateAfterBind: vks::VK_FALSE,
+            descriptorBindingUniformTexelBufferUpdateAfterBind: vks::VK_FALSE,
+            descriptorBindingStorageTexelBufferUpdateAfterBind: vks::VK_FALSE,
+            descriptorBindingUpdateUnusedWhilePending: vks::VK_FALSE,
+            descriptorBindingPartiallyBound: vks::VK_FALSE,
+            descriptorBindingVariableDescriptorCount: vks::VK_FALSE,
+            runtimeDescriptorArray: vks::VK_FALSE,
+        }
+    }
+}
+
+
+/// A `VkDescriptorSetLayoutBindingFlagsCreateInfoEXT`.
+///
+/// Specifies per-binding flags for a descriptor set layout. Each element of
+/// `binding_flags` applies to the binding at the same index in the array
+/// passed to `DescriptorSetLayoutBuilder::bindings`.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct DescriptorSetLayoutBindingFlagsCreateInfoExt<'s> {
+    raw: VkDescriptorSetLayoutBindingFlagsCreateInfoEXT,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> DescriptorSetLayoutBindingFlagsCreateInfoExt<'s> {
+    pub fn builder<'b>() -> DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder<'b> {
+        DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkDescriptorSetLayoutBindingFlagsCreateInfoEXT) -> DescriptorSetLayoutBindingFlagsCreateInfoExt<'s> {
+        DescriptorSetLayoutBindingFlagsCreateInfoExt { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *const c_void {
+        self.raw.pNext
+    }
+
+    pub fn binding_flags<'a>(&'a self) -> &'a [DescriptorBindingFlagsExt] {
+        unsafe { slice::from_raw_parts(self.raw.pBindingFlags as *const _, self.raw.bindingCount as usize) }
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *const c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn set_binding_flags<'m, 'a>(&mut self, binding_flags: &'a [DescriptorBindingFlagsExt])
+            where 'a: 's {
+        self.raw.bindingCount = binding_flags.len() as _;
+        self.raw.pBindingFlags = binding_flags.as_ptr() as *const u32;
+    }
+
+    pub fn as_raw(&self) -> &VkDescriptorSetLayoutBindingFlagsCreateInfoEXT {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkDescriptorSetLayoutBindingFlagsCreateInfoEXT`.
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder<'b> {
+    raw: VkDescriptorSetLayoutBindingFlagsCreateInfoEXT,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder<'b> {
+    pub fn new() -> DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder<'b> {
+        DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder {
+            raw: VkDescriptorSetLayoutBindingFlagsCreateInfoEXT::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *const c_void) -> DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn binding_flags<'m, 'a>(mut self, binding_flags: &'a [DescriptorBindingFlagsExt]) -> DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder<'b>
+            where 'a: 'b {
+        self.raw.bindingCount = binding_flags.len() as _;
+        self.raw.pBindingFlags = binding_flags.as_ptr() as *const u32;
+        self
+    }
+
+    pub fn build(self) -> DescriptorSetLayoutBindingFlagsCreateInfoExt<'b> {
+        DescriptorSetLayoutBindingFlagsCreateInfoExt {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
+
+
+/// A `VkDescriptorSetVariableDescriptorCountAllocateInfoEXT`.
+///
+/// Specifies, for each set being allocated, the descriptor count of its
+/// layout's variable-sized binding.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct DescriptorSetVariableDescriptorCountAllocateInfoExt<'s> {
+    raw: VkDescriptorSetVariableDescriptorCountAllocateInfoEXT,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> DescriptorSetVariableDescriptorCountAllocateInfoExt<'s> {
+    pub fn builder<'b>() -> DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder<'b> {
+        DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkDescriptorSetVariableDescriptorCountAllocateInfoEXT) -> DescriptorSetVariableDescriptorCountAllocateInfoExt<'s> {
+        DescriptorSetVariableDescriptorCountAllocateInfoExt { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *const c_void {
+        self.raw.pNext
+    }
+
+    pub fn descriptor_counts<'a>(&'a self) -> &'a [u32] {
+        unsafe { slice::from_raw_parts(self.raw.pDescriptorCounts, self.raw.descriptorSetCount as usize) }
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *const c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn set_descriptor_counts<'m, 'a>(&mut self, descriptor_counts: &'a [u32])
+            where 'a: 's {
+        self.raw.descriptorSetCount = descriptor_counts.len() as _;
+        self.raw.pDescriptorCounts = descriptor_counts.as_ptr();
+    }
+
+    pub fn as_raw(&self) -> &VkDescriptorSetVariableDescriptorCountAllocateInfoEXT {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkDescriptorSetVariableDescriptorCountAllocateInfoEXT`.
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder<'b> {
+    raw: VkDescriptorSetVariableDescriptorCountAllocateInfoEXT,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder<'b> {
+    pub fn new() -> DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder<'b> {
+        DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder {
+            raw: VkDescriptorSetVariableDescriptorCountAllocateInfoEXT::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *const c_void) -> DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn descriptor_counts<'m, 'a>(mut self, descriptor_counts: &'a [u32]) -> DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder<'b>
+            where 'a: 'b {
+        self.raw.descriptorSetCount = descriptor_counts.len() as _;
+        self.raw.pDescriptorCounts = descriptor_counts.as_ptr();
+        self
+    }
+
+    pub fn build(self) -> DescriptorSetVariableDescriptorCountAllocateInfoExt<'b> {
+        DescriptorSetVariableDescriptorCountAllocateInfoExt {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
+
+
+/// A `VkPhysicalDeviceDescriptorIndexingFeaturesEXT`.
+///
+/// Chain onto a `PhysicalDeviceFeatures2Khr` to query support with
+/// `Instance::get_physical_device_features_2_khr_into` or to enable features
+/// with `DeviceBuilder::enabled_features_2`.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct PhysicalDeviceDescriptorIndexingFeaturesExt<'s> {
+    raw: VkPhysicalDeviceDescriptorIndexingFeaturesEXT,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> PhysicalDeviceDescriptorIndexingFeaturesExt<'s> {
+    pub fn builder<'b>() -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        PhysicalDeviceDescriptorIndexingFeaturesExtBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkPhysicalDeviceDescriptorIndexingFeaturesEXT) -> PhysicalDeviceDescriptorIndexingFeaturesExt<'s> {
+        PhysicalDeviceDescriptorIndexingFeaturesExt { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *mut c_void {
+        self.raw.pNext
+    }
+
+    pub fn shader_input_attachment_array_dynamic_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderInputAttachmentArrayDynamicIndexing != 0
+    }
+
+    pub fn shader_uniform_texel_buffer_array_dynamic_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderUniformTexelBufferArrayDynamicIndexing != 0
+    }
+
+    pub fn shader_storage_texel_buffer_array_dynamic_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderStorageTexelBufferArrayDynamicIndexing != 0
+    }
+
+    pub fn shader_uniform_buffer_array_non_uniform_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderUniformBufferArrayNonUniformIndexing != 0
+    }
+
+    pub fn shader_sampled_image_array_non_uniform_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderSampledImageArrayNonUniformIndexing != 0
+    }
+
+    pub fn shader_storage_buffer_array_non_uniform_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderStorageBufferArrayNonUniformIndexing != 0
+    }
+
+    pub fn shader_storage_image_array_non_uniform_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderStorageImageArrayNonUniformIndexing != 0
+    }
+
+    pub fn shader_input_attachment_array_non_uniform_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderInputAttachmentArrayNonUniformIndexing != 0
+    }
+
+    pub fn shader_uniform_texel_buffer_array_non_uniform_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderUniformTexelBufferArrayNonUniformIndexing != 0
+    }
+
+    pub fn shader_storage_texel_buffer_array_non_uniform_indexing<'a>(&'a self) -> bool {
+        self.raw.shaderStorageTexelBufferArrayNonUniformIndexing != 0
+    }
+
+    pub fn descriptor_binding_uniform_buffer_update_after_bind<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingUniformBufferUpdateAfterBind != 0
+    }
+
+    pub fn descriptor_binding_sampled_image_update_after_bind<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingSampledImageUpdateAfterBind != 0
+    }
+
+    pub fn descriptor_binding_storage_image_update_after_bind<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingStorageImageUpdateAfterBind != 0
+    }
+
+    pub fn descriptor_binding_storage_buffer_update_after_bind<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingStorageBufferUpdateAfterBind != 0
+    }
+
+    pub fn descriptor_binding_uniform_texel_buffer_update_after_bind<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingUniformTexelBufferUpdateAfterBind != 0
+    }
+
+    pub fn descriptor_binding_storage_texel_buffer_update_after_bind<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingStorageTexelBufferUpdateAfterBind != 0
+    }
+
+    pub fn descriptor_binding_update_unused_while_pending<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingUpdateUnusedWhilePending != 0
+    }
+
+    pub fn descriptor_binding_partially_bound<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingPartiallyBound != 0
+    }
+
+    pub fn descriptor_binding_variable_descriptor_count<'a>(&'a self) -> bool {
+        self.raw.descriptorBindingVariableDescriptorCount != 0
+    }
+
+    pub fn runtime_descriptor_array<'a>(&'a self) -> bool {
+        self.raw.runtimeDescriptorArray != 0
+    }
+    pub unsafe fn set_next<'m>(&mut self, next: *mut c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn set_shader_input_attachment_array_dynamic_indexing<'m>(&mut self, shader_input_attachment_array_dynamic_indexing: bool) {
+        self.raw.shaderInputAttachmentArrayDynamicIndexing = shader_input_attachment_array_dynamic_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_uniform_texel_buffer_array_dynamic_indexing<'m>(&mut self, shader_uniform_texel_buffer_array_dynamic_indexing: bool) {
+        self.raw.shaderUniformTexelBufferArrayDynamicIndexing = shader_uniform_texel_buffer_array_dynamic_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_storage_texel_buffer_array_dynamic_indexing<'m>(&mut self, shader_storage_texel_buffer_array_dynamic_indexing: bool) {
+        self.raw.shaderStorageTexelBufferArrayDynamicIndexing = shader_storage_texel_buffer_array_dynamic_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_uniform_buffer_array_non_uniform_indexing<'m>(&mut self, shader_uniform_buffer_array_non_uniform_indexing: bool) {
+        self.raw.shaderUniformBufferArrayNonUniformIndexing = shader_uniform_buffer_array_non_uniform_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_sampled_image_array_non_uniform_indexing<'m>(&mut self, shader_sampled_image_array_non_uniform_indexing: bool) {
+        self.raw.shaderSampledImageArrayNonUniformIndexing = shader_sampled_image_array_non_uniform_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_storage_buffer_array_non_uniform_indexing<'m>(&mut self, shader_storage_buffer_array_non_uniform_indexing: bool) {
+        self.raw.shaderStorageBufferArrayNonUniformIndexing = shader_storage_buffer_array_non_uniform_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_storage_image_array_non_uniform_indexing<'m>(&mut self, shader_storage_image_array_non_uniform_indexing: bool) {
+        self.raw.shaderStorageImageArrayNonUniformIndexing = shader_storage_image_array_non_uniform_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_input_attachment_array_non_uniform_indexing<'m>(&mut self, shader_input_attachment_array_non_uniform_indexing: bool) {
+        self.raw.shaderInputAttachmentArrayNonUniformIndexing = shader_input_attachment_array_non_uniform_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_uniform_texel_buffer_array_non_uniform_indexing<'m>(&mut self, shader_uniform_texel_buffer_array_non_uniform_indexing: bool) {
+        self.raw.shaderUniformTexelBufferArrayNonUniformIndexing = shader_uniform_texel_buffer_array_non_uniform_indexing as vks::VkBool32;
+    }
+
+    pub fn set_shader_storage_texel_buffer_array_non_uniform_indexing<'m>(&mut self, shader_storage_texel_buffer_array_non_uniform_indexing: bool) {
+        self.raw.shaderStorageTexelBufferArrayNonUniformIndexing = shader_storage_texel_buffer_array_non_uniform_indexing as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_uniform_buffer_update_after_bind<'m>(&mut self, descriptor_binding_uniform_buffer_update_after_bind: bool) {
+        self.raw.descriptorBindingUniformBufferUpdateAfterBind = descriptor_binding_uniform_buffer_update_after_bind as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_sampled_image_update_after_bind<'m>(&mut self, descriptor_binding_sampled_image_update_after_bind: bool) {
+        self.raw.descriptorBindingSampledImageUpdateAfterBind = descriptor_binding_sampled_image_update_after_bind as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_storage_image_update_after_bind<'m>(&mut self, descriptor_binding_storage_image_update_after_bind: bool) {
+        self.raw.descriptorBindingStorageImageUpdateAfterBind = descriptor_binding_storage_image_update_after_bind as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_storage_buffer_update_after_bind<'m>(&mut self, descriptor_binding_storage_buffer_update_after_bind: bool) {
+        self.raw.descriptorBindingStorageBufferUpdateAfterBind = descriptor_binding_storage_buffer_update_after_bind as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_uniform_texel_buffer_update_after_bind<'m>(&mut self, descriptor_binding_uniform_texel_buffer_update_after_bind: bool) {
+        self.raw.descriptorBindingUniformTexelBufferUpdateAfterBind = descriptor_binding_uniform_texel_buffer_update_after_bind as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_storage_texel_buffer_update_after_bind<'m>(&mut self, descriptor_binding_storage_texel_buffer_update_after_bind: bool) {
+        self.raw.descriptorBindingStorageTexelBufferUpdateAfterBind = descriptor_binding_storage_texel_buffer_update_after_bind as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_update_unused_while_pending<'m>(&mut self, descriptor_binding_update_unused_while_pending: bool) {
+        self.raw.descriptorBindingUpdateUnusedWhilePending = descriptor_binding_update_unused_while_pending as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_partially_bound<'m>(&mut self, descriptor_binding_partially_bound: bool) {
+        self.raw.descriptorBindingPartiallyBound = descriptor_binding_partially_bound as vks::VkBool32;
+    }
+
+    pub fn set_descriptor_binding_variable_descriptor_count<'m>(&mut self, descriptor_binding_variable_descriptor_count: bool) {
+        self.raw.descriptorBindingVariableDescriptorCount = descriptor_binding_variable_descriptor_count as vks::VkBool32;
+    }
+
+    pub fn set_runtime_descriptor_array<'m>(&mut self, runtime_descriptor_array: bool) {
+        self.raw.runtimeDescriptorArray = runtime_descriptor_array as vks::VkBool32;
+    }
+
+    pub fn as_raw(&self) -> &VkPhysicalDeviceDescriptorIndexingFeaturesEXT {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkPhysicalDeviceDescriptorIndexingFeaturesEXT`.
+#[derive(Debug, Clone, Default)]
+pub struct PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+    raw: VkPhysicalDeviceDescriptorIndexingFeaturesEXT,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+    pub fn new() -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        PhysicalDeviceDescriptorIndexingFeaturesExtBuilder {
+            raw: VkPhysicalDeviceDescriptorIndexingFeaturesEXT::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *mut c_void) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn shader_input_attachment_array_dynamic_indexing<'m>(mut self, shader_input_attachment_array_dynamic_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderInputAttachmentArrayDynamicIndexing = shader_input_attachment_array_dynamic_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_uniform_texel_buffer_array_dynamic_indexing<'m>(mut self, shader_uniform_texel_buffer_array_dynamic_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderUniformTexelBufferArrayDynamicIndexing = shader_uniform_texel_buffer_array_dynamic_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_storage_texel_buffer_array_dynamic_indexing<'m>(mut self, shader_storage_texel_buffer_array_dynamic_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderStorageTexelBufferArrayDynamicIndexing = shader_storage_texel_buffer_array_dynamic_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_uniform_buffer_array_non_uniform_indexing<'m>(mut self, shader_uniform_buffer_array_non_uniform_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderUniformBufferArrayNonUniformIndexing = shader_uniform_buffer_array_non_uniform_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_sampled_image_array_non_uniform_indexing<'m>(mut self, shader_sampled_image_array_non_uniform_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderSampledImageArrayNonUniformIndexing = shader_sampled_image_array_non_uniform_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_storage_buffer_array_non_uniform_indexing<'m>(mut self, shader_storage_buffer_array_non_uniform_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderStorageBufferArrayNonUniformIndexing = shader_storage_buffer_array_non_uniform_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_storage_image_array_non_uniform_indexing<'m>(mut self, shader_storage_image_array_non_uniform_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderStorageImageArrayNonUniformIndexing = shader_storage_image_array_non_uniform_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_input_attachment_array_non_uniform_indexing<'m>(mut self, shader_input_attachment_array_non_uniform_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderInputAttachmentArrayNonUniformIndexing = shader_input_attachment_array_non_uniform_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_uniform_texel_buffer_array_non_uniform_indexing<'m>(mut self, shader_uniform_texel_buffer_array_non_uniform_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderUniformTexelBufferArrayNonUniformIndexing = shader_uniform_texel_buffer_array_non_uniform_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn shader_storage_texel_buffer_array_non_uniform_indexing<'m>(mut self, shader_storage_texel_buffer_array_non_uniform_indexing: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.shaderStorageTexelBufferArrayNonUniformIndexing = shader_storage_texel_buffer_array_non_uniform_indexing as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_uniform_buffer_update_after_bind<'m>(mut self, descriptor_binding_uniform_buffer_update_after_bind: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingUniformBufferUpdateAfterBind = descriptor_binding_uniform_buffer_update_after_bind as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_sampled_image_update_after_bind<'m>(mut self, descriptor_binding_sampled_image_update_after_bind: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingSampledImageUpdateAfterBind = descriptor_binding_sampled_image_update_after_bind as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_storage_image_update_after_bind<'m>(mut self, descriptor_binding_storage_image_update_after_bind: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingStorageImageUpdateAfterBind = descriptor_binding_storage_image_update_after_bind as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_storage_buffer_update_after_bind<'m>(mut self, descriptor_binding_storage_buffer_update_after_bind: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingStorageBufferUpdateAfterBind = descriptor_binding_storage_buffer_update_after_bind as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_uniform_texel_buffer_update_after_bind<'m>(mut self, descriptor_binding_uniform_texel_buffer_update_after_bind: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingUniformTexelBufferUpdateAfterBind = descriptor_binding_uniform_texel_buffer_update_after_bind as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_storage_texel_buffer_update_after_bind<'m>(mut self, descriptor_binding_storage_texel_buffer_update_after_bind: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingStorageTexelBufferUpdateAfterBind = descriptor_binding_storage_texel_buffer_update_after_bind as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_update_unused_while_pending<'m>(mut self, descriptor_binding_update_unused_while_pending: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingUpdateUnusedWhilePending = descriptor_binding_update_unused_while_pending as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_partially_bound<'m>(mut self, descriptor_binding_partially_bound: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingPartiallyBound = descriptor_binding_partially_bound as vks::VkBool32;
+        self
+    }
+
+    pub fn descriptor_binding_variable_descriptor_count<'m>(mut self, descriptor_binding_variable_descriptor_count: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.descriptorBindingVariableDescriptorCount = descriptor_binding_variable_descriptor_count as vks::VkBool32;
+        self
+    }
+
+    pub fn runtime_descriptor_array<'m>(mut self, runtime_descriptor_array: bool) -> PhysicalDeviceDescriptorIndexingFeaturesExtBuilder<'b> {
+        self.raw.runtimeDescriptorArray = runtime_descriptor_array as vks::VkBool32;
+        self
+    }
+
+    pub fn build(self) -> PhysicalDeviceDescriptorIndexingFeaturesExt<'b> {
+        PhysicalDeviceDescriptorIndexingFeaturesExt {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
@@ -2,10 +2,13 @@
 use std::sync::Arc;
 use std::marker::PhantomData;
 use smallvec::SmallVec;
+use libc::c_void;
 use vks;
-use ::{VdResult, Device, DescriptorSetLayoutHandle, Handle,
+use ::{VdResult, Device, DescriptorSetLayout, DescriptorSetLayoutHandle, Handle, DescriptorType,
+    DescriptorPoolSize,
     WriteDescriptorSet, CopyDescriptorSet, DescriptorSet,
-    DescriptorSetAllocateInfo, DescriptorSetHandle};
+    DescriptorSetAllocateInfo, DescriptorSetHandle,
+    DescriptorSetVariableDescriptorCountAllocateInfoExt};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -13,6 +16,12 @@ use ::{VdResult, Device, DescriptorSetLayoutHandle, Handle,
 pub struct DescriptorPoolHandle(pub(crate) vks::VkDescriptorPool);
 
 impl DescriptorPoolHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDescriptorPool) -> DescriptorPoolHandle {
+        DescriptorPoolHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDescriptorPool {
         self.0
@@ -72,7 +81,28 @@ impl DescriptorPool {
         &self.inner.device
     }
 
-    /// Updates descriptor sets.
+    /// Allocates a single descriptor set from this pool.
+    ///
+    /// Unlike those allocated with `allocate_descriptor_sets`, the returned
+    /// set retains `layout`, allowing writes to it to be checked against the
+    /// layout's bindings in debug builds.
+    pub fn allocate_descriptor_set(&self, layout: &DescriptorSetLayout)
+            -> VdResult<DescriptorSet> {
+        let layouts = [layout.handle()];
+        let alloc_info = DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(self.handle())
+            .set_layouts(&layouts)
+            .build();
+
+        let descriptor_set_handles: SmallVec<[DescriptorSetHandle; 8]> = unsafe {
+            self.inner.device.allocate_descriptor_sets(&alloc_info)?
+        };
+
+        Ok(DescriptorSet::from_parts(descriptor_set_handles[0], self.inner.device.clone(),
+            Some(layout.clone())))
+    }
+
+    /// Allocates descriptor sets from this pool, one for each of `layouts`.
     pub fn allocate_descriptor_sets<Ds>(&self, layouts: &[Ds])
             -> VdResult<SmallVec<[DescriptorSet; 8]>>
             where Ds: Handle<Target=DescriptorSetLayoutHandle> {
@@ -89,7 +119,46 @@ impl DescriptorPool {
             self.inner.device.allocate_descriptor_sets(&alloc_info)?
         };
 
-        Ok(descriptor_set_handles.iter().map(|&dsh| DescriptorSet(dsh)).collect())
+        Ok(descriptor_set_handles.iter().map(|&dsh| {
+            DescriptorSet::from_parts(dsh, self.inner.device.clone(), None)
+        }).collect())
+    }
+
+    /// Allocates descriptor sets from this pool, specifying the descriptor
+    /// count of the variable-sized binding of each set's layout.
+    ///
+    /// `descriptor_counts` must contain one count per layout. Requires the
+    /// `VK_EXT_descriptor_indexing` device extension.
+    pub fn allocate_descriptor_sets_variable<Ds>(&self, layouts: &[Ds], descriptor_counts: &[u32])
+            -> VdResult<SmallVec<[DescriptorSet; 8]>>
+            where Ds: Handle<Target=DescriptorSetLayoutHandle> {
+        if descriptor_counts.len() != layouts.len() {
+            return Err(format!("DescriptorPool::allocate_descriptor_sets_variable: \
+                the number of descriptor counts ({}) must equal the number of layouts ({})",
+                descriptor_counts.len(), layouts.len()).into());
+        }
+        let layouts: SmallVec<[DescriptorSetLayoutHandle; 8]> = layouts.iter().map(|ds|
+            ds.handle()).collect();
+
+        let variable_counts = DescriptorSetVariableDescriptorCountAllocateInfoExt::builder()
+            .descriptor_counts(descriptor_counts)
+            .build();
+
+        let alloc_info = unsafe {
+            DescriptorSetAllocateInfo::builder()
+                .next(variable_counts.as_raw() as *const _ as *const c_void)
+                .descriptor_pool(self.handle())
+                .set_layouts(&layouts)
+                .build()
+        };
+
+        let descriptor_set_handles: SmallVec<[DescriptorSetHandle; 8]> = unsafe {
+            self.inner.device.allocate_descriptor_sets(&alloc_info)?
+        };
+
+        Ok(descriptor_set_handles.iter().map(|&dsh| {
+            DescriptorSet::from_parts(dsh, self.inner.device.clone(), None)
+        }).collect())
     }
 
     /// Updates the contents of a descriptor set object.
@@ -117,6 +186,8 @@ unsafe impl<'d> Handle for &'d DescriptorPool {
 #[derive(Debug, Clone)]
 pub struct DescriptorPoolBuilder<'b> {
     create_info: ::DescriptorPoolCreateInfo<'b>,
+    layout_sizes: Option<(u32, SmallVec<[(DescriptorType, u32); 11]>)>,
+    overallocate: f32,
     _p: PhantomData<&'b ()>,
 }
 
@@ -125,12 +196,17 @@ impl<'b> DescriptorPoolBuilder<'b> {
     pub fn new() -> DescriptorPoolBuilder<'b> {
         DescriptorPoolBuilder {
             create_info: ::DescriptorPoolCreateInfo::default(),
+            layout_sizes: None,
+            overallocate: 1.0,
             _p: PhantomData,
         }
     }
 
     /// flags is a bitmask of VkDescriptorPoolCreateFlagBits specifying
     /// certain supported operations on the pool.
+    ///
+    /// Sets with layouts created using `UPDATE_AFTER_BIND_POOL_EXT` must be
+    /// allocated from a pool created with `UPDATE_AFTER_BIND_EXT`.
     pub fn flags<'s>(&'s mut self, flags: ::DescriptorPoolCreateFlags)
             -> &'s mut DescriptorPoolBuilder<'b> {
         self.create_info.set_flags(flags);
@@ -157,9 +233,66 @@ impl<'b> DescriptorPoolBuilder<'b> {
         self
     }
 
+    /// Determines `max_sets` and the pool sizes from the bindings of a list
+    /// of layouts, each paired with the number of sets of that layout the
+    /// pool must be able to hold at once.
+    ///
+    /// Descriptor counts are summed by type across all requested sets. Any
+    /// values specified with `max_sets` or `pool_sizes` are ignored.
+    pub fn for_layouts<'s>(&'s mut self, layouts: &[(&DescriptorSetLayout, u32)])
+            -> &'s mut DescriptorPoolBuilder<'b> {
+        let mut max_sets = 0u32;
+        let mut sizes: SmallVec<[(DescriptorType, u32); 11]> = SmallVec::new();
+        for &(layout, set_count) in layouts {
+            max_sets += set_count;
+            for binding in layout.bindings() {
+                let count = binding.descriptor_count() * set_count;
+                if count == 0 { continue; }
+                match sizes.iter().position(|&(ty, _)| ty == binding.descriptor_type()) {
+                    Some(idx) => sizes[idx].1 += count,
+                    None => sizes.push((binding.descriptor_type(), count)),
+                }
+            }
+        }
+        self.layout_sizes = Some((max_sets, sizes));
+        self
+    }
+
+    /// Scales `max_sets` and each descriptor count determined by
+    /// `for_layouts` by `factor`, rounding up.
+    ///
+    /// Useful when the number of sets allocated varies at runtime. Has no
+    /// effect unless `for_layouts` is also used.
+    pub fn overallocate<'s>(&'s mut self, factor: f32)
+            -> &'s mut DescriptorPoolBuilder<'b> {
+        assert!(factor >= 1.0, "DescriptorPoolBuilder::overallocate: factor must be at least 1.0");
+        self.overallocate = factor;
+        self
+    }
+
     /// Creates and returns a new `DescriptorPool`
     pub fn build(&self, device: Device) -> VdResult<DescriptorPool> {
-        let handle = unsafe { device.create_descriptor_pool(&self.create_info, None)? };
+        let handle = match self.layout_sizes {
+            Some((max_sets, ref sizes)) => {
+                let scale = |n: u32| (n as f32 * self.overallocate).ceil() as u32;
+                let pool_sizes: SmallVec<[DescriptorPoolSize; 11]> = sizes.iter()
+                    .map(|&(ty, count)| DescriptorPoolSize::builder()
+                        .type_of(ty)
+                        .descriptor_count(scale(count))
+                        .build())
+                    .collect();
+                let mut create_info = ::DescriptorPoolCreateInfo::builder()
+                    .flags(self.create_info.flags())
+                    .max_sets(scale(max_sets))
+                    .pool_sizes(&pool_sizes)
+                    .build();
+                unsafe {
+                    create_info.set_next(self.create_info.next());
+                    device.create_descriptor_pool(&create_info, None)?
+                }
+            },
+            None => unsafe { device.create_descriptor_pool(&self.create_info, None)? },
+        };
 
         Ok(DescriptorPool {
             inner: Arc::new(Inner {
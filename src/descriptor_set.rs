@@ -0,0 +1,157 @@
+use ::{Device, Handle, DescriptorSetHandle, DescriptorSetLayout, DescriptorType, BufferHandle,
+    SamplerHandle, ImageViewHandle, ImageLayout, DescriptorBufferInfo, DescriptorImageInfo,
+    DescriptorUpdateBatch, DeviceSize, WHOLE_SIZE};
+
+
+/// A descriptor set.
+///
+/// Descriptor sets are owned by the pool they were allocated from and are
+/// freed along with it.
+///
+/// When allocated with `DescriptorPool::allocate_descriptor_set`, the layout
+/// is retained and, in debug builds, writes are checked against the
+/// descriptor types it declares.
+///
+#[derive(Debug, Clone)]
+pub struct DescriptorSet {
+    handle: DescriptorSetHandle,
+    device: Device,
+    layout: Option<DescriptorSetLayout>,
+}
+
+impl DescriptorSet {
+    pub(crate) fn from_parts(handle: DescriptorSetHandle, device: Device,
+            layout: Option<DescriptorSetLayout>) -> DescriptorSet {
+        DescriptorSet { handle, device, layout }
+    }
+
+    /// Returns this object's handle.
+    pub fn handle(&self) -> DescriptorSetHandle {
+        self.handle
+    }
+
+    /// Returns a reference to the associated device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Returns the layout this set was allocated with, if known.
+    pub fn layout(&self) -> Option<&DescriptorSetLayout> {
+        self.layout.as_ref()
+    }
+
+    /// Writes a single buffer descriptor to `binding`, updating the set
+    /// immediately.
+    ///
+    /// A `range` of `None` uses the remainder of the buffer after `offset`
+    /// (`WHOLE_SIZE`).
+    pub fn write_buffer<B>(&self, binding: u32, buffer: B, offset: DeviceSize,
+            range: Option<DeviceSize>, descriptor_type: DescriptorType)
+            where B: Handle<Target=BufferHandle> {
+        self.writes().buffer(binding, buffer, offset, range, descriptor_type).update();
+    }
+
+    /// Writes a single image, sampler, or combined image sampler descriptor
+    /// to `binding`, updating the set immediately.
+    pub fn write_image(&self, binding: u32, sampler: Option<SamplerHandle>,
+            image_view: Option<ImageViewHandle>, image_layout: ImageLayout,
+            descriptor_type: DescriptorType) {
+        self.writes().image(binding, sampler, image_view, image_layout, descriptor_type).update();
+    }
+
+    /// Returns a builder which accumulates writes to this set and submits
+    /// them together with a single call to `update`.
+    pub fn writes<'s>(&'s self) -> DescriptorSetWrites<'s> {
+        DescriptorSetWrites { set: self, batch: DescriptorUpdateBatch::new() }
+    }
+
+    /// Checks that the layout, if known, declares `binding` with
+    /// `descriptor_type`.
+    fn debug_check_binding(&self, binding: u32, descriptor_type: DescriptorType) {
+        if let Some(ref layout) = self.layout {
+            let declared = layout.bindings().iter().find(|b| b.binding() == binding)
+                .map(|b| b.descriptor_type());
+            debug_assert!(declared == Some(descriptor_type), "descriptor write to binding {} \
+                of type {:?} does not match the layout (declared: {:?})", binding,
+                descriptor_type, declared);
+        }
+    }
+}
+
+unsafe impl Handle for DescriptorSet {
+    type Target = DescriptorSetHandle;
+
+    fn handle(&self) -> Self::Target {
+        self.handle
+    }
+}
+
+unsafe impl<'h> Handle for &'h DescriptorSet {
+    type Target = DescriptorSetHandle;
+
+    fn handle(&self) -> Self::Target {
+        self.handle
+    }
+}
+
+
+/// Pending writes to a single descriptor set.
+///
+/// Returned by `DescriptorSet::writes`.
+#[derive(Debug)]
+pub struct DescriptorSetWrites<'s> {
+    set: &'s DescriptorSet,
+    batch: DescriptorUpdateBatch,
+}
+
+impl<'s> DescriptorSetWrites<'s> {
+    /// Adds a write of a single buffer descriptor. A `range` of `None` maps
+    /// to `WHOLE_SIZE`.
+    pub fn buffer<B>(&mut self, binding: u32, buffer: B, offset: DeviceSize,
+            range: Option<DeviceSize>, descriptor_type: DescriptorType)
+            -> &mut DescriptorSetWrites<'s>
+            where B: Handle<Target=BufferHandle> {
+        self.set.debug_check_binding(binding, descriptor_type);
+        self.batch.write_buffer(self.set, binding, 0, descriptor_type, buffer, offset,
+            range.unwrap_or(WHOLE_SIZE));
+        self
+    }
+
+    /// Adds a write of a single image, sampler, or combined image sampler
+    /// descriptor.
+    pub fn image(&mut self, binding: u32, sampler: Option<SamplerHandle>,
+            image_view: Option<ImageViewHandle>, image_layout: ImageLayout,
+            descriptor_type: DescriptorType) -> &mut DescriptorSetWrites<'s> {
+        self.set.debug_check_binding(binding, descriptor_type);
+        self.batch.write_image(self.set, binding, 0, descriptor_type, sampler, image_view,
+            image_layout);
+        self
+    }
+
+    /// Adds a write of consecutive buffer descriptors starting at
+    /// `first_array_element`.
+    pub fn buffer_array(&mut self, binding: u32, first_array_element: u32,
+            descriptor_type: DescriptorType, infos: &[DescriptorBufferInfo])
+            -> &mut DescriptorSetWrites<'s> {
+        self.set.debug_check_binding(binding, descriptor_type);
+        self.batch.write_buffer_array(self.set, binding, first_array_element, descriptor_type,
+            infos);
+        self
+    }
+
+    /// Adds a write of consecutive image descriptors starting at
+    /// `first_array_element`.
+    pub fn image_array(&mut self, binding: u32, first_array_element: u32,
+            descriptor_type: DescriptorType, infos: &[DescriptorImageInfo])
+            -> &mut DescriptorSetWrites<'s> {
+        self.set.debug_check_binding(binding, descriptor_type);
+        self.batch.write_image_array(self.set, binding, first_array_element, descriptor_type,
+            infos);
+        self
+    }
+
+    /// Submits all pending writes.
+    pub fn update(&mut self) {
+        self.batch.flush(&self.set.device);
+    }
+}
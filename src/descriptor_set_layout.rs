@@ -1,7 +1,10 @@
 use std::sync::Arc;
 use std::marker::PhantomData;
+use libc::c_void;
+use smallvec::SmallVec;
 use vks;
-use ::{VdResult, Device, Handle};
+use ::{VdResult, Device, Handle, DescriptorSetLayoutBindingFlagsCreateInfoExt, DescriptorType,
+    ShaderStageFlags};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -9,6 +12,12 @@ use ::{VdResult, Device, Handle};
 pub struct DescriptorSetLayoutHandle(pub(crate) vks::VkDescriptorSetLayout);
 
 impl DescriptorSetLayoutHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDescriptorSetLayout) -> DescriptorSetLayoutHandle {
+        DescriptorSetLayoutHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDescriptorSetLayout {
         self.0
@@ -25,10 +34,53 @@ unsafe impl Handle for DescriptorSetLayoutHandle {
 }
 
 
+/// The type, count, and stages of a single binding within a descriptor set
+/// layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DescriptorBindingSummary {
+    binding: u32,
+    descriptor_type: DescriptorType,
+    descriptor_count: u32,
+    stage_flags: ShaderStageFlags,
+}
+
+impl DescriptorBindingSummary {
+    fn from_binding(binding: &::DescriptorSetLayoutBinding) -> DescriptorBindingSummary {
+        DescriptorBindingSummary {
+            binding: binding.binding(),
+            descriptor_type: binding.descriptor_type(),
+            descriptor_count: binding.descriptor_count(),
+            stage_flags: binding.stage_flags(),
+        }
+    }
+
+    /// Returns the binding number.
+    pub fn binding(&self) -> u32 {
+        self.binding
+    }
+
+    /// Returns the type of descriptor used by this binding.
+    pub fn descriptor_type(&self) -> DescriptorType {
+        self.descriptor_type
+    }
+
+    /// Returns the number of descriptors (array elements) in this binding.
+    pub fn descriptor_count(&self) -> u32 {
+        self.descriptor_count
+    }
+
+    /// Returns the shader stages which may access this binding.
+    pub fn stage_flags(&self) -> ShaderStageFlags {
+        self.stage_flags
+    }
+}
+
+
 #[derive(Debug)]
 struct Inner {
     handle: DescriptorSetLayoutHandle,
     device: Device,
+    bindings: SmallVec<[DescriptorBindingSummary; 8]>,
 }
 
 impl Drop for Inner {
@@ -68,6 +120,23 @@ impl DescriptorSetLayout {
     pub fn device(&self) -> &Device {
         &self.inner.device
     }
+
+    /// Returns a summary of each binding specified when this layout was
+    /// created.
+    pub fn bindings(&self) -> &[DescriptorBindingSummary] {
+        &self.inner.bindings
+    }
+
+    /// Returns the number of dynamic uniform and storage buffer descriptors
+    /// in this layout, each of which requires a dynamic offset when a set
+    /// with this layout is bound.
+    pub fn dynamic_descriptor_count(&self) -> u32 {
+        self.inner.bindings.iter()
+            .filter(|b| b.descriptor_type == DescriptorType::UniformBufferDynamic ||
+                b.descriptor_type == DescriptorType::StorageBufferDynamic)
+            .map(|b| b.descriptor_count)
+            .sum()
+    }
 }
 
 unsafe impl<'h> Handle for &'h DescriptorSetLayout {
@@ -84,6 +153,7 @@ unsafe impl<'h> Handle for &'h DescriptorSetLayout {
 #[derive(Debug, Clone)]
 pub struct DescriptorSetLayoutBuilder<'b> {
     create_info: ::DescriptorSetLayoutCreateInfo<'b>,
+    bindings: SmallVec<[DescriptorBindingSummary; 8]>,
     _p: PhantomData<&'b ()>,
 }
 
@@ -92,6 +162,7 @@ impl<'b> DescriptorSetLayoutBuilder<'b> {
     pub fn new() -> DescriptorSetLayoutBuilder<'b> {
         DescriptorSetLayoutBuilder {
             create_info: ::DescriptorSetLayoutCreateInfo::default(),
+            bindings: SmallVec::new(),
             _p: PhantomData,
         }
     }
@@ -109,6 +180,34 @@ impl<'b> DescriptorSetLayoutBuilder<'b> {
             -> &'s mut DescriptorSetLayoutBuilder<'b>
             where 'p: 'b {
         self.create_info.set_bindings(bindings);
+        self.bindings = bindings.iter().map(DescriptorBindingSummary::from_binding).collect();
+        self
+    }
+
+    /// Specifies the bindings of descriptor set `set` as reflected from
+    /// shader code.
+    ///
+    /// Equivalent to `bindings(reflection.bindings(set))`.
+    #[cfg(feature = "spirv-reflect")]
+    pub fn reflected_set<'s, 'p>(&'s mut self, reflection: &'p ::reflect::ShaderReflection,
+            set: u32) -> &'s mut DescriptorSetLayoutBuilder<'b>
+            where 'p: 'b {
+        self.bindings(reflection.bindings(set))
+    }
+
+    /// Specifies per-binding descriptor indexing flags.
+    ///
+    /// Requires the `VK_EXT_descriptor_indexing` device extension. Bindings
+    /// using `UPDATE_AFTER_BIND_EXT` also require the layout to be created
+    /// with `DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL_EXT`.
+    pub fn binding_flags<'s, 'p>(&'s mut self,
+            binding_flags: &'p mut DescriptorSetLayoutBindingFlagsCreateInfoExt<'p>)
+            -> &'s mut DescriptorSetLayoutBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            binding_flags.set_next(self.create_info.next());
+            self.create_info.set_next(binding_flags.as_raw() as *const _ as *const c_void);
+        }
         self
     }
 
@@ -120,6 +219,7 @@ impl<'b> DescriptorSetLayoutBuilder<'b> {
             inner: Arc::new(Inner {
                 handle,
                 device,
+                bindings: self.bindings.clone(),
             })
         })
     }
@@ -0,0 +1,325 @@
+use smallvec::SmallVec;
+use vks;
+use ::{Device, Handle, DescriptorSetHandle, DescriptorType, BufferHandle, SamplerHandle,
+    ImageViewHandle, ImageLayout, DescriptorBufferInfo, DescriptorImageInfo};
+
+
+/// Identifies which info array a pending write points into and the index of
+/// its first entry.
+#[derive(Clone, Copy, Debug)]
+enum WriteInfo {
+    Buffer(usize),
+    Image(usize),
+}
+
+
+/// A reusable batch of descriptor writes and copies.
+///
+/// Writes and copies are accumulated into internal storage and submitted
+/// with a single call to `vkUpdateDescriptorSets` by `flush`. Storage is
+/// cleared but not released after each flush, allowing the same batch to be
+/// used every frame without allocating.
+///
+/// Each write describes either a single descriptor or, when added with
+/// `write_buffer_array` or `write_image_array`, a run of consecutive array
+/// elements within one binding. Info structures are copied into the batch
+/// and pointers to them are resolved only when flushing, so adding to the
+/// batch never invalidates earlier writes.
+///
+#[derive(Debug, Default)]
+pub struct DescriptorUpdateBatch {
+    writes: SmallVec<[vks::VkWriteDescriptorSet; 16]>,
+    write_infos: SmallVec<[WriteInfo; 16]>,
+    buffer_infos: SmallVec<[vks::VkDescriptorBufferInfo; 16]>,
+    image_infos: SmallVec<[vks::VkDescriptorImageInfo; 16]>,
+    copies: SmallVec<[vks::VkCopyDescriptorSet; 8]>,
+}
+
+impl DescriptorUpdateBatch {
+    /// Returns a new, empty batch.
+    pub fn new() -> DescriptorUpdateBatch {
+        DescriptorUpdateBatch::default()
+    }
+
+    /// Adds a write of a single buffer descriptor.
+    pub fn write_buffer<S, B>(&mut self, set: S, binding: u32, array_element: u32,
+            descriptor_type: DescriptorType, buffer: B, offset: u64, range: u64)
+            -> &mut DescriptorUpdateBatch
+            where S: Handle<Target=DescriptorSetHandle>, B: Handle<Target=BufferHandle> {
+        self.write_infos.push(WriteInfo::Buffer(self.buffer_infos.len()));
+        self.buffer_infos.push(vks::VkDescriptorBufferInfo {
+            buffer: buffer.handle().to_raw(),
+            offset,
+            range,
+        });
+        self.push_write(set.handle(), binding, array_element, 1, descriptor_type);
+        self
+    }
+
+    /// Adds a write of consecutive buffer descriptors, one for each of
+    /// `infos`, starting at `first_array_element` of `binding`.
+    ///
+    /// Elements of the binding outside of the written range are left
+    /// untouched. Does nothing if `infos` is empty.
+    pub fn write_buffer_array<S>(&mut self, set: S, binding: u32, first_array_element: u32,
+            descriptor_type: DescriptorType, infos: &[DescriptorBufferInfo])
+            -> &mut DescriptorUpdateBatch
+            where S: Handle<Target=DescriptorSetHandle> {
+        if infos.is_empty() { return self; }
+        self.write_infos.push(WriteInfo::Buffer(self.buffer_infos.len()));
+        self.buffer_infos.extend(infos.iter().map(|info| *info.as_raw()));
+        self.push_write(set.handle(), binding, first_array_element, infos.len() as u32,
+            descriptor_type);
+        self
+    }
+
+    /// Adds a write of a single image, sampler, or combined image sampler
+    /// descriptor.
+    ///
+    /// `sampler` and `image_view` are ignored by the implementation when not
+    /// applicable to `descriptor_type`.
+    pub fn write_image<S>(&mut self, set: S, binding: u32, array_element: u32,
+            descriptor_type: DescriptorType, sampler: Option<SamplerHandle>,
+            image_view: Option<ImageViewHandle>, image_layout: ImageLayout)
+            -> &mut DescriptorUpdateBatch
+            where S: Handle<Target=DescriptorSetHandle> {
+        self.write_infos.push(WriteInfo::Image(self.image_infos.len()));
+        self.image_infos.push(vks::VkDescriptorImageInfo {
+            sampler: sampler.map(|s| s.to_raw()).unwrap_or(0),
+            imageView: image_view.map(|iv| iv.to_raw()).unwrap_or(0),
+            imageLayout: image_layout.into(),
+        });
+        self.push_write(set.handle(), binding, array_element, 1, descriptor_type);
+        self
+    }
+
+    /// Adds a write of consecutive image, sampler, or combined image sampler
+    /// descriptors, one for each of `infos`, starting at
+    /// `first_array_element` of `binding`.
+    ///
+    /// This is the usual way to fill part of a texture array: writing
+    /// elements 5 through 12 of a 64 element binding, for example, leaves
+    /// the remaining elements untouched. Does nothing if `infos` is empty.
+    pub fn write_image_array<S>(&mut self, set: S, binding: u32, first_array_element: u32,
+            descriptor_type: DescriptorType, infos: &[DescriptorImageInfo])
+            -> &mut DescriptorUpdateBatch
+            where S: Handle<Target=DescriptorSetHandle> {
+        if infos.is_empty() { return self; }
+        self.write_infos.push(WriteInfo::Image(self.image_infos.len()));
+        self.image_infos.extend(infos.iter().map(|info| *info.as_raw()));
+        self.push_write(set.handle(), binding, first_array_element, infos.len() as u32,
+            descriptor_type);
+        self
+    }
+
+    /// Adds a copy of `descriptor_count` descriptors from one set to another.
+    pub fn copy<Ss, Ds>(&mut self, src_set: Ss, src_binding: u32, src_array_element: u32,
+            dst_set: Ds, dst_binding: u32, dst_array_element: u32, descriptor_count: u32)
+            -> &mut DescriptorUpdateBatch
+            where Ss: Handle<Target=DescriptorSetHandle>, Ds: Handle<Target=DescriptorSetHandle> {
+        let mut copy = vks::VkCopyDescriptorSet::default();
+        copy.srcSet = src_set.handle().to_raw();
+        copy.srcBinding = src_binding;
+        copy.srcArrayElement = src_array_element;
+        copy.dstSet = dst_set.handle().to_raw();
+        copy.dstBinding = dst_binding;
+        copy.dstArrayElement = dst_array_element;
+        copy.descriptorCount = descriptor_count;
+        self.copies.push(copy);
+        self
+    }
+
+    /// Returns the number of pending writes.
+    #[inline]
+    pub fn write_count(&self) -> usize {
+        self.writes.len()
+    }
+
+    /// Returns the number of pending copies.
+    #[inline]
+    pub fn copy_count(&self) -> usize {
+        self.copies.len()
+    }
+
+    /// Returns true if there are no pending writes or copies.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty() && self.copies.is_empty()
+    }
+
+    /// Discards all pending writes and copies, retaining storage.
+    pub fn clear(&mut self) {
+        self.writes.clear();
+        self.write_infos.clear();
+        self.buffer_infos.clear();
+        self.image_infos.clear();
+        self.copies.clear();
+    }
+
+    /// Submits all pending writes and copies with a single call to
+    /// `vkUpdateDescriptorSets` then clears the batch.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkUpdateDescriptorSets.html
+    //
+    pub fn flush(&mut self, device: &Device) {
+        if self.is_empty() { return; }
+        self.resolve_info_pointers();
+        unsafe {
+            device.proc_addr_loader().vk.vkUpdateDescriptorSets(device.handle().to_raw(),
+                self.writes.len() as u32, self.writes.as_ptr(),
+                self.copies.len() as u32, self.copies.as_ptr());
+        }
+        self.clear();
+    }
+
+    fn push_write(&mut self, set: DescriptorSetHandle, binding: u32, array_element: u32,
+            descriptor_count: u32, descriptor_type: DescriptorType) {
+        let mut write = vks::VkWriteDescriptorSet::default();
+        write.dstSet = set.to_raw();
+        write.dstBinding = binding;
+        write.dstArrayElement = array_element;
+        write.descriptorCount = descriptor_count;
+        write.descriptorType = descriptor_type.into();
+        self.writes.push(write);
+    }
+
+    /// Points each write at its entry within the (now final) info arrays.
+    fn resolve_info_pointers(&mut self) {
+        debug_assert_eq!(self.writes.len(), self.write_infos.len());
+        for (write, info) in self.writes.iter_mut().zip(self.write_infos.iter()) {
+            match *info {
+                WriteInfo::Buffer(idx) => {
+                    write.pBufferInfo = &self.buffer_infos[idx];
+                    write.pImageInfo = ::std::ptr::null();
+                },
+                WriteInfo::Image(idx) => {
+                    write.pImageInfo = &self.image_infos[idx];
+                    write.pBufferInfo = ::std::ptr::null();
+                },
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use ::{DescriptorSetHandle, DescriptorType, BufferHandle, SamplerHandle, ImageViewHandle,
+        ImageLayout, DescriptorBufferInfo, DescriptorImageInfo};
+    use super::DescriptorUpdateBatch;
+
+    #[test]
+    fn writes_resolve_to_their_own_infos() {
+        let mut batch = DescriptorUpdateBatch::new();
+        assert!(batch.is_empty());
+        // Enough writes to spill every inline array, moving earlier infos:
+        for i in 0..20u64 {
+            batch.write_buffer(DescriptorSetHandle(1), i as u32, 0, DescriptorType::UniformBuffer,
+                BufferHandle(100 + i), i * 256, 256);
+            batch.write_image(DescriptorSetHandle(2), i as u32, 0,
+                DescriptorType::CombinedImageSampler, Some(SamplerHandle(200 + i)),
+                Some(ImageViewHandle(300 + i)), ImageLayout::ShaderReadOnlyOptimal);
+        }
+        assert_eq!(batch.write_count(), 40);
+        assert!(!batch.is_empty());
+
+        batch.resolve_info_pointers();
+        for (n, write) in batch.writes.iter().enumerate() {
+            let i = (n / 2) as u64;
+            assert_eq!(write.dstBinding, i as u32);
+            assert_eq!(write.descriptorCount, 1);
+            if n % 2 == 0 {
+                assert_eq!(write.dstSet, 1);
+                assert!(write.pImageInfo.is_null());
+                let info = unsafe { &*write.pBufferInfo };
+                assert_eq!((info.buffer, info.offset, info.range), (100 + i, i * 256, 256));
+            } else {
+                assert_eq!(write.dstSet, 2);
+                assert!(write.pBufferInfo.is_null());
+                let info = unsafe { &*write.pImageInfo };
+                assert_eq!((info.sampler, info.imageView), (200 + i, 300 + i));
+                let layout: vks::VkImageLayout = ImageLayout::ShaderReadOnlyOptimal.into();
+                assert_eq!(info.imageLayout, layout);
+            }
+        }
+    }
+
+    #[test]
+    fn image_write_without_sampler_or_view() {
+        let mut batch = DescriptorUpdateBatch::new();
+        batch.write_image(DescriptorSetHandle(1), 0, 3, DescriptorType::StorageImage, None,
+            Some(ImageViewHandle(7)), ImageLayout::General);
+        batch.resolve_info_pointers();
+        assert_eq!(batch.writes[0].dstArrayElement, 3);
+        let info = unsafe { &*batch.writes[0].pImageInfo };
+        assert_eq!((info.sampler, info.imageView), (0, 7));
+    }
+
+    #[test]
+    fn copies_and_clear() {
+        let mut batch = DescriptorUpdateBatch::new();
+        batch.copy(DescriptorSetHandle(1), 2, 3, DescriptorSetHandle(4), 5, 6, 7);
+        assert_eq!(batch.copy_count(), 1);
+        assert_eq!(batch.write_count(), 0);
+        assert!(!batch.is_empty());
+        let copy = &batch.copies[0];
+        assert_eq!((copy.srcSet, copy.srcBinding, copy.srcArrayElement), (1, 2, 3));
+        assert_eq!((copy.dstSet, copy.dstBinding, copy.dstArrayElement), (4, 5, 6));
+        assert_eq!(copy.descriptorCount, 7);
+
+        batch.write_buffer(DescriptorSetHandle(1), 0, 0, DescriptorType::StorageBuffer,
+            BufferHandle(1), 0, 64);
+        batch.clear();
+        assert!(batch.is_empty());
+        assert!(batch.write_infos.is_empty());
+        assert!(batch.buffer_infos.is_empty());
+    }
+
+    #[test]
+    fn array_writes_cover_consecutive_elements() {
+        let buffer_infos: Vec<_> = (0..3u64).map(|i| DescriptorBufferInfo::builder()
+            .buffer(BufferHandle(10 + i))
+            .offset(i * 64)
+            .range(64)
+            .build()).collect();
+        let image_infos: Vec<_> = (0..8u64).map(|i| DescriptorImageInfo::builder()
+            .sampler(SamplerHandle(20))
+            .image_view(ImageViewHandle(30 + i))
+            .image_layout(ImageLayout::ShaderReadOnlyOptimal)
+            .build()).collect();
+
+        let mut batch = DescriptorUpdateBatch::new();
+        batch.write_buffer(DescriptorSetHandle(1), 0, 0, DescriptorType::UniformBuffer,
+            BufferHandle(1), 0, 16);
+        batch.write_buffer_array(DescriptorSetHandle(1), 1, 2, DescriptorType::StorageBuffer,
+            &buffer_infos);
+        batch.write_image_array(DescriptorSetHandle(1), 2, 5, DescriptorType::CombinedImageSampler,
+            &image_infos);
+        assert_eq!(batch.write_count(), 3);
+        batch.resolve_info_pointers();
+
+        let write = &batch.writes[1];
+        assert_eq!((write.dstBinding, write.dstArrayElement, write.descriptorCount), (1, 2, 3));
+        let infos = unsafe { ::std::slice::from_raw_parts(write.pBufferInfo, 3) };
+        for (i, info) in infos.iter().enumerate() {
+            assert_eq!((info.buffer, info.offset), (10 + i as u64, i as u64 * 64));
+        }
+
+        let write = &batch.writes[2];
+        assert_eq!((write.dstBinding, write.dstArrayElement, write.descriptorCount), (2, 5, 8));
+        let infos = unsafe { ::std::slice::from_raw_parts(write.pImageInfo, 8) };
+        for (i, info) in infos.iter().enumerate() {
+            assert_eq!((info.sampler, info.imageView), (20, 30 + i as u64));
+        }
+    }
+
+    #[test]
+    fn empty_array_writes_are_ignored() {
+        let mut batch = DescriptorUpdateBatch::new();
+        batch.write_buffer_array(DescriptorSetHandle(1), 0, 0, DescriptorType::StorageBuffer, &[]);
+        batch.write_image_array(DescriptorSetHandle(1), 1, 0, DescriptorType::SampledImage, &[]);
+        assert!(batch.is_empty());
+        assert!(batch.write_infos.is_empty());
+    }
+}
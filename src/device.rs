@@ -1,13 +1,21 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::HashMap;
 use std::mem;
 use std::ptr;
 use std::marker::PhantomData;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use libc::{c_void};
-use smallvec::SmallVec;
+use smallvec::{SmallVec, Array};
 use vks;
-use ::{error, VdResult, Instance, PhysicalDevice, DeviceQueueCreateInfo, CharStrs,
-    PhysicalDeviceFeatures, PRINT, Handle, SubmitInfo, QueueHandle, MemoryAllocateInfo,
+use debug_utils::{DebugUtilsLabelFns, Label};
+use external_memory_host::{ExternalMemoryHostFns, VkMemoryHostPointerPropertiesEXT};
+use debug_marker::DebugRegionBackend;
+use pipeline_cache;
+use util;
+use allocation_callbacks::AllocationCallbacks;
+use ::{error, VdResult, WHOLE_SIZE, Instance, PhysicalDevice, DeviceQueueCreateInfo, CharStrs,
+    QueueFamilySelection, PhysicalDeviceFeatures, PhysicalDeviceFeatures2Khr, Handle, SubmitInfo, QueueHandle, MemoryAllocateInfo,
     DeviceMemoryHandle, MemoryMapFlags, SwapchainKhrHandle, SwapchainCreateInfoKhr,
     ShaderModuleCreateInfo, ShaderModuleHandle, SemaphoreCreateInfo, SemaphoreHandle,
     SamplerCreateInfo, SamplerHandle, RenderPassCreateInfo, RenderPassHandle, BufferCreateInfo,
@@ -19,26 +27,28 @@ use ::{error, VdResult, Instance, PhysicalDevice, DeviceQueueCreateInfo, CharStr
     FenceHandle, EventCreateInfo, EventHandle, PipelineCacheCreateInfo, PipelineCacheHandle,
     MemoryRequirements, DeviceSize, CommandBufferBeginInfo, GraphicsPipelineCreateInfo,
     PipelineHandle, ComputePipelineCreateInfo, PipelineStageFlags, DependencyFlags, MemoryBarrier,
-    BufferMemoryBarrier, ImageMemoryBarrier, WriteDescriptorSet, CopyDescriptorSet,
+    BufferMemoryBarrier, ImageMemoryBarrier, WriteDescriptorSet, CopyDescriptorSet, AccessFlags,
     BufferImageCopy, ImageLayout, BufferCopy, CommandBufferResetFlags, PipelineBindPoint, Viewport,
     Rect2d, StencilFaceFlags, DebugMarkerMarkerInfoExt, DescriptorSetHandle, QueryPoolHandle,
     QueryResultFlags, ShaderStageFlags, RenderPassBeginInfo, SubpassContents, ImageCopy, IndexType,
     ImageBlit, Filter, ClearColorValue, ImageSubresourceRange, ClearDepthStencilValue,
     ClearAttachment, ImageResolve, QueryControlFlags, ClearRect, PresentInfoKhr, MappedMemoryRange,
-    SparseImageMemoryRequirements, BindSparseInfo, CallResult, QueryPoolCreateInfo,
+    DeviceMemory,
+    SparseImageMemoryRequirements, BindSparseInfo, CallResult, AcquireResult, PresentResult,
+    WaitResult, FenceStatus, EventStatus, QueryPoolCreateInfo,
     ImageSubresource, SubresourceLayout, DescriptorSetAllocateInfo, DescriptorPoolResetFlags,
     Extent2d, CommandPoolResetFlags, CommandPoolTrimFlagsKhr, MemoryGetWin32HandleInfoKhr,
-    ExternalMemoryHandleTypeFlagsKhr, HANDLE, MemoryGetFdInfoKhr,
+    ExternalMemoryHandleTypeFlagsKhr, HANDLE, MemoryGetFdInfoKhr, MemoryFdPropertiesKhr,
     ImportSemaphoreWin32HandleInfoKhr, SemaphoreGetWin32HandleInfoKhr, ImportSemaphoreFdInfoKhr,
     SemaphoreGetFdInfoKhr, PipelineLayout, BufferMemoryRequirementsInfo2Khr,
     ImportFenceWin32HandleInfoKhr, FenceGetWin32HandleInfoKhr, ImportFenceFdInfoKhr,
     FenceGetFdInfoKhr, ImageMemoryRequirementsInfo2Khr, ImageSparseMemoryRequirementsInfo2Khr,
     DebugMarkerObjectTagInfoExt, DebugMarkerObjectNameInfoExt, DisplayPowerInfoExt,
     DisplayKhrHandle, DeviceEventInfoExt, DisplayEventInfoExt, HdrMetadataExt,
-    SurfaceCounterFlagsExt, Queue};
+    SurfaceCounterFlagsExt, Queue, MemoryRequirements2, names};
 
-// #[cfg(feature = "experimental")]
-// use ::{};
+#[cfg(feature = "experimental")]
+use ::DeviceGroupDeviceCreateInfoKhx;
 
 #[cfg(feature = "unimplemented")]
 use ::{SamplerYcbcrConversionCreateInfoKhr, IndirectCommandsLayoutNvxCreateInfo,
@@ -54,6 +64,12 @@ use ::{SamplerYcbcrConversionCreateInfoKhr, IndirectCommandsLayoutNvxCreateInfo,
 pub struct DeviceHandle(pub(crate) vks::VkDevice);
 
 impl DeviceHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDevice) -> DeviceHandle {
+        DeviceHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDevice {
         self.0
@@ -70,16 +86,320 @@ unsafe impl Handle for DeviceHandle {
 }
 
 fn get_device_queue(proc_addr_loader: &vks::DeviceProcAddrLoader, device: DeviceHandle,
-        queue_family_index: u32, queue_index: u32) -> Option<QueueHandle> {
+        queue_family_index: u32, queue_index: u32) -> VdResult<QueueHandle> {
     let mut handle = ptr::null_mut();
     unsafe {
         proc_addr_loader.vk.vkGetDeviceQueue(device.to_raw(),
             queue_family_index, queue_index, &mut handle);
     }
     if !handle.is_null() {
-        Some(QueueHandle(handle))
+        Ok(QueueHandle(handle))
     } else {
-        None
+        Err(format!("vkGetDeviceQueue: unable to get device queue (family index: {}, \
+            queue index: {})", queue_family_index, queue_index).into())
+    }
+}
+
+/// Splits paired vertex buffers and offsets into the parallel arrays expected
+/// by `vkCmdBindVertexBuffers`.
+fn split_vertex_buffers(buffers: &[(BufferHandle, DeviceSize)])
+        -> (SmallVec<[BufferHandle; 16]>, SmallVec<[DeviceSize; 16]>) {
+    let mut buffer_handles = SmallVec::new();
+    let mut offsets = SmallVec::new();
+    for &(buffer, offset) in buffers {
+        buffer_handles.push(buffer);
+        offsets.push(offset);
+    }
+    (buffer_handles, offsets)
+}
+
+/// Returns the error for a failed call creating `pipelines`, which were
+/// null-initialized, identifying the first pipeline which was not created.
+fn pipeline_creation_error(result: i32, pipelines: &[PipelineHandle], fn_name: &'static str)
+        -> ::Error {
+    error::Error {
+        kind: error::ErrorKind::PipelineCreation {
+            index: pipelines.iter().position(|p| p.to_raw() == 0),
+            code: result,
+            fn_name,
+        },
+        cause: None,
+    }
+}
+
+/// Appends `count` handles to `handles`, leaving it unchanged unless `write`
+/// returns success.
+///
+/// `write` is passed a pointer to the reserved space following the existing
+/// elements and must fill `count` handles there if it succeeds.
+unsafe fn append_handles<A, F>(handles: &mut SmallVec<A>, count: usize, fn_name: &'static str,
+        write: F) -> VdResult<()>
+        where A: Array, F: FnOnce(*mut A::Item) -> i32 {
+    let start = handles.len();
+    handles.reserve(count);
+    let result = write(handles.as_mut_ptr().offset(start as isize));
+    error::check_named(result, fn_name)?;
+    handles.set_len(start + count);
+    Ok(())
+}
+
+/// Returns the raw `size` passed to `vkCmdFillBuffer`, which uses
+/// `WHOLE_SIZE` to fill to the end of the buffer.
+fn fill_size(size: Option<DeviceSize>) -> DeviceSize {
+    size.unwrap_or(WHOLE_SIZE)
+}
+
+/// A small, copyable table of the core command recording functions.
+///
+/// Calling through `Device::proc_addr_loader` requires a trip through the
+/// device's shared state for every command recorded. A `DeviceCommands`
+/// copies the function pointers most commonly used while recording so that
+/// they can be stored inline (as `CommandBuffer` does) and called directly.
+///
+/// Obtain one with `Device::commands`.
+//
+#[derive(Clone, Copy, Debug)]
+pub struct DeviceCommands {
+    bind_pipeline: vks::PFN_vkCmdBindPipeline,
+    set_viewport: vks::PFN_vkCmdSetViewport,
+    set_scissor: vks::PFN_vkCmdSetScissor,
+    bind_descriptor_sets: vks::PFN_vkCmdBindDescriptorSets,
+    bind_index_buffer: vks::PFN_vkCmdBindIndexBuffer,
+    bind_vertex_buffers: vks::PFN_vkCmdBindVertexBuffers,
+    draw: vks::PFN_vkCmdDraw,
+    draw_indexed: vks::PFN_vkCmdDrawIndexed,
+    dispatch: vks::PFN_vkCmdDispatch,
+    push_constants: vks::PFN_vkCmdPushConstants,
+}
+
+impl DeviceCommands {
+    fn new(loader: &vks::DeviceProcAddrLoader) -> DeviceCommands {
+        DeviceCommands {
+            bind_pipeline: loader.vk.pfn_vkCmdBindPipeline,
+            set_viewport: loader.vk.pfn_vkCmdSetViewport,
+            set_scissor: loader.vk.pfn_vkCmdSetScissor,
+            bind_descriptor_sets: loader.vk.pfn_vkCmdBindDescriptorSets,
+            bind_index_buffer: loader.vk.pfn_vkCmdBindIndexBuffer,
+            bind_vertex_buffers: loader.vk.pfn_vkCmdBindVertexBuffers,
+            draw: loader.vk.pfn_vkCmdDraw,
+            draw_indexed: loader.vk.pfn_vkCmdDrawIndexed,
+            dispatch: loader.vk.pfn_vkCmdDispatch,
+            push_constants: loader.vk.pfn_vkCmdPushConstants,
+        }
+    }
+
+    /// Binds a pipeline object to a command buffer.
+    ///
+    /// See `Device::cmd_bind_pipeline`.
+    #[inline]
+    pub unsafe fn cmd_bind_pipeline(&self, command_buffer: CommandBufferHandle,
+            pipeline_bind_point: PipelineBindPoint, pipeline: PipelineHandle) {
+        (self.bind_pipeline.expect("vkCmdBindPipeline not loaded"))(command_buffer.to_raw(),
+            pipeline_bind_point.into(), pipeline.to_raw());
+    }
+
+    /// Sets the viewport on a command buffer.
+    ///
+    /// See `Device::cmd_set_viewport`.
+    #[inline]
+    pub unsafe fn cmd_set_viewport(&self, command_buffer: CommandBufferHandle,
+            first_viewport: u32, viewports: &[Viewport]) {
+        (self.set_viewport.expect("vkCmdSetViewport not loaded"))(command_buffer.to_raw(),
+            first_viewport, viewports.len() as u32, viewports.as_ptr() as *const vks::VkViewport);
+    }
+
+    /// Sets the dynamic scissor rectangles on a command buffer.
+    ///
+    /// See `Device::cmd_set_scissor`.
+    #[inline]
+    pub unsafe fn cmd_set_scissor(&self, command_buffer: CommandBufferHandle, first_scissor: u32,
+            scissors: &[Rect2d]) {
+        (self.set_scissor.expect("vkCmdSetScissor not loaded"))(command_buffer.to_raw(),
+            first_scissor, scissors.len() as u32, scissors.as_ptr() as *const vks::VkRect2D);
+    }
+
+    /// Binds descriptor sets to a command buffer.
+    ///
+    /// See `Device::cmd_bind_descriptor_sets`.
+    #[inline]
+    pub unsafe fn cmd_bind_descriptor_sets(&self, command_buffer: CommandBufferHandle,
+            pipeline_bind_point: PipelineBindPoint, layout: PipelineLayoutHandle,
+            first_set: u32, descriptor_sets: &[DescriptorSetHandle],
+            dynamic_offsets: &[u32]) {
+        (self.bind_descriptor_sets.expect("vkCmdBindDescriptorSets not loaded"))(
+            command_buffer.to_raw(), pipeline_bind_point.into(), layout.to_raw(), first_set,
+            descriptor_sets.len() as u32, descriptor_sets.as_ptr() as *const vks::VkDescriptorSet,
+            dynamic_offsets.len() as u32, dynamic_offsets.as_ptr());
+    }
+
+    /// Binds an index buffer to a command buffer.
+    ///
+    /// See `Device::cmd_bind_index_buffer`.
+    #[inline]
+    pub unsafe fn cmd_bind_index_buffer(&self, command_buffer: CommandBufferHandle,
+            buffer: BufferHandle, offset: u64, index_type: IndexType) {
+        (self.bind_index_buffer.expect("vkCmdBindIndexBuffer not loaded"))(
+            command_buffer.to_raw(), buffer.to_raw(), offset, index_type.into());
+    }
+
+    /// Binds vertex buffers, each paired with its offset, to a command
+    /// buffer.
+    ///
+    /// See `Device::cmd_bind_vertex_buffers`.
+    #[inline]
+    pub unsafe fn cmd_bind_vertex_buffers(&self, command_buffer: CommandBufferHandle,
+            first_binding: u32, buffers: &[(BufferHandle, DeviceSize)]) {
+        let (buffer_handles, offsets) = split_vertex_buffers(buffers);
+        self.cmd_bind_vertex_buffers_slices(command_buffer, first_binding, &buffer_handles,
+            &offsets);
+    }
+
+    /// Binds vertex buffers to a command buffer.
+    ///
+    /// See `Device::cmd_bind_vertex_buffers_slices`.
+    #[inline]
+    pub unsafe fn cmd_bind_vertex_buffers_slices(&self, command_buffer: CommandBufferHandle,
+            first_binding: u32, buffers: &[BufferHandle], offsets: &[DeviceSize]) {
+        assert_eq!(buffers.len(), offsets.len(), "DeviceCommands::cmd_bind_vertex_buffers_slices: \
+            `buffers` and `offsets` must have the same length");
+        (self.bind_vertex_buffers.expect("vkCmdBindVertexBuffers not loaded"))(
+            command_buffer.to_raw(), first_binding, buffers.len() as u32,
+            buffers.as_ptr() as *const vks::VkBuffer, offsets.as_ptr());
+    }
+
+    /// Binds a single vertex buffer to a command buffer.
+    ///
+    /// See `Device::cmd_bind_vertex_buffer`.
+    #[inline]
+    pub unsafe fn cmd_bind_vertex_buffer(&self, command_buffer: CommandBufferHandle,
+            binding: u32, buffer: BufferHandle, offset: DeviceSize) {
+        self.cmd_bind_vertex_buffers_slices(command_buffer, binding, &[buffer], &[offset]);
+    }
+
+    /// Draws primitives.
+    ///
+    /// See `Device::cmd_draw`.
+    #[inline]
+    pub unsafe fn cmd_draw(&self, command_buffer: CommandBufferHandle, vertex_count: u32,
+            instance_count: u32, first_vertex: u32, first_instance: u32) {
+        (self.draw.expect("vkCmdDraw not loaded"))(command_buffer.to_raw(), vertex_count,
+            instance_count, first_vertex, first_instance);
+    }
+
+    /// Issues an indexed draw into a command buffer.
+    ///
+    /// See `Device::cmd_draw_indexed`.
+    #[inline]
+    pub unsafe fn cmd_draw_indexed(&self, command_buffer: CommandBufferHandle, index_count: u32,
+            instance_count: u32, first_index: u32, vertex_offset: i32, first_instance: u32) {
+        (self.draw_indexed.expect("vkCmdDrawIndexed not loaded"))(command_buffer.to_raw(),
+            index_count, instance_count, first_index, vertex_offset, first_instance);
+    }
+
+    /// Dispatches compute work items.
+    ///
+    /// See `Device::cmd_dispatch`.
+    #[inline]
+    pub unsafe fn cmd_dispatch(&self, command_buffer: CommandBufferHandle, group_count_x: u32,
+            group_count_y: u32, group_count_z: u32) {
+        (self.dispatch.expect("vkCmdDispatch not loaded"))(command_buffer.to_raw(),
+            group_count_x, group_count_y, group_count_z);
+    }
+
+    /// Updates the values of push constants.
+    ///
+    /// Not validated against the push constant ranges of `layout`. See
+    /// `Device::cmd_push_constants`.
+    #[inline]
+    pub unsafe fn cmd_push_constants(&self, command_buffer: CommandBufferHandle,
+            layout: PipelineLayoutHandle, stage_flags: ShaderStageFlags, offset: u32,
+            values: &[u8]) {
+        (self.push_constants.expect("vkCmdPushConstants not loaded"))(command_buffer.to_raw(),
+            layout.to_raw(), stage_flags.bits(), offset, values.len() as u32,
+            values.as_ptr() as *const c_void);
+    }
+}
+
+
+/// A snapshot of memory usage for a single memory heap.
+///
+/// Returned by `Device::memory_usage`.
+#[derive(Debug, Clone)]
+pub struct HeapUsage {
+    heap_index: u32,
+    allocated_bytes: Option<u64>,
+    heap_size: u64,
+    flags: ::bitflags::MemoryHeapFlags,
+}
+
+impl HeapUsage {
+    /// Returns the index of the heap within the physical device's memory
+    /// properties.
+    pub fn heap_index(&self) -> u32 {
+        self.heap_index
+    }
+
+    /// Returns the number of bytes currently allocated from this heap
+    /// through this device, or `None` if allocation tracking is disabled
+    /// (see `DeviceBuilder::track_allocations`).
+    pub fn allocated_bytes(&self) -> Option<u64> {
+        self.allocated_bytes
+    }
+
+    /// Returns the total size of this heap in bytes.
+    pub fn heap_size(&self) -> u64 {
+        self.heap_size
+    }
+
+    /// Returns this heap's flags.
+    pub fn flags(&self) -> ::bitflags::MemoryHeapFlags {
+        self.flags
+    }
+}
+
+
+/// Per-heap allocation counters maintained by `Device::allocate_memory` and
+/// `Device::free_memory`.
+///
+/// Counts are kept as `u64` behind a mutex rather than in `AtomicUsize`s,
+/// which would truncate heap sizes on 32-bit targets.
+#[derive(Debug)]
+struct AllocationTracker {
+    memory_type_heaps: Vec<u32>,
+    heap_allocated: Mutex<Vec<u64>>,
+    allocations: Mutex<HashMap<vks::VkDeviceMemory, (u32, u64)>>,
+}
+
+impl AllocationTracker {
+    fn new(physical_device: &PhysicalDevice) -> AllocationTracker {
+        let mem_props = physical_device.memory_properties();
+        AllocationTracker::with_heaps((0..mem_props.memory_type_count() as usize)
+            .map(|i| mem_props.memory_types()[i].heap_index())
+            .collect(), mem_props.memory_heap_count())
+    }
+
+    fn with_heaps(memory_type_heaps: Vec<u32>, heap_count: u32) -> AllocationTracker {
+        AllocationTracker {
+            memory_type_heaps,
+            heap_allocated: Mutex::new(vec![0; heap_count as usize]),
+            allocations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn allocated(&self, memory: DeviceMemoryHandle, memory_type_index: u32, size: u64) {
+        let heap_index = self.memory_type_heaps[memory_type_index as usize];
+        self.heap_allocated.lock().unwrap()[heap_index as usize] += size;
+        self.allocations.lock().unwrap().insert(memory.to_raw(), (heap_index, size));
+    }
+
+    fn freed(&self, memory: DeviceMemoryHandle) {
+        if let Some((heap_index, size)) = self.allocations.lock().unwrap().remove(&memory.to_raw()) {
+            self.heap_allocated.lock().unwrap()[heap_index as usize] -= size;
+        }
+    }
+
+    fn heap_allocated(&self, heap_index: u32) -> u64 {
+        self.heap_allocated.lock().unwrap()[heap_index as usize]
     }
 }
 
@@ -88,17 +408,71 @@ fn get_device_queue(proc_addr_loader: &vks::DeviceProcAddrLoader, device: Device
 struct Inner {
     handle: DeviceHandle,
     physical_device: PhysicalDevice,
-    // features: vks::VkPhysicalDeviceFeatures,
+    properties: ::PhysicalDeviceProperties,
+    enabled_features: PhysicalDeviceFeatures,
     queues: SmallVec<[Queue; 16]>,
     instance: Instance,
     loader: vks::DeviceProcAddrLoader,
+    commands: DeviceCommands,
+    pipeline_cache: Option<PipelineCacheHandle>,
+    enabled_extensions: Vec<CString>,
+    debug_utils: DebugUtilsLabelFns,
+    external_memory_host: ExternalMemoryHostFns,
+    allocation_tracker: Option<AllocationTracker>,
+    allocation_callbacks: Option<AllocationCallbacks>,
+    // Number of `cmd_full_pipeline_barrier` calls since the last present.
+    #[cfg(debug_assertions)]
+    full_barrier_count: AtomicUsize,
+    // Number of objects created through this device and not yet destroyed.
+    // Only leaked raw handles can be counted here: the owning wrapper types
+    // hold a `Device` clone and so always destroy their handles first.
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    live_children: AtomicUsize,
+    wait_idle_on_drop: bool,
+    owned: bool,
+}
+
+#[cfg(all(test, any(debug_assertions, feature = "validation")))]
+thread_local! {
+    // Live child diagnostics reported by devices dropped on this thread.
+    static DROP_DIAGNOSTICS: ::std::cell::RefCell<Vec<String>> =
+        ::std::cell::RefCell::new(Vec::new());
+}
+
+/// Returns the diagnostic reported when a device is dropped with
+/// `live_children` child objects still alive, if any.
+#[cfg(any(debug_assertions, feature = "validation"))]
+fn live_children_diagnostic(live_children: usize) -> Option<String> {
+    if live_children == 0 { return None; }
+    Some(format!("Device dropped with {} child object(s) still alive. All objects created \
+        with a device must be destroyed before the device itself.", live_children))
 }
 
 impl Drop for Inner {
     fn drop(&mut self) {
-        if PRINT { println!("Destroying device..."); }
+        if !self.owned { return; }
+        debug!("Destroying device...");
+        #[cfg(any(debug_assertions, feature = "validation"))]
+        {
+            if let Some(msg) = live_children_diagnostic(self.live_children.load(Ordering::SeqCst)) {
+                warn!("{}", msg);
+                #[cfg(test)]
+                DROP_DIAGNOSTICS.with(|d| d.borrow_mut().push(msg));
+            }
+        }
         unsafe {
-            self.instance.destroy_device(self.handle, None);
+            if self.wait_idle_on_drop {
+                let result = self.loader.vk.vkDeviceWaitIdle(self.handle.to_raw());
+                if let Err(err) = error::check_named(result, "vkDeviceWaitIdle") {
+                    warn!("Error waiting for device to become idle before destruction: {}", err);
+                }
+            }
+            let allocator = self.allocation_callbacks.as_ref().map(|ac| ac.as_raw());
+            if let Some(pipeline_cache) = self.pipeline_cache {
+                self.loader.vk.vkDestroyPipelineCache(self.handle.to_raw(),
+                    pipeline_cache.to_raw(), allocator.unwrap_or(ptr::null()));
+            }
+            self.instance.destroy_device(self.handle, allocator);
         }
     }
 }
@@ -123,13 +497,108 @@ impl Device {
         DeviceBuilder::new()
     }
 
-    /// Returns one of this device's associated queue.
+    /// Creates a `Device` from a raw handle to a logical device created
+    /// elsewhere (by another library, for example).
+    ///
+    /// Only the core device function table is loaded and no queues are
+    /// associated with the returned device (`Device::queues` will be
+    /// empty) and no features are reported as enabled by
+    /// `Device::enabled_features`. Use `Device::get_device_queue` to retrieve
+    /// queue handles. If
+    /// `owned` is `false`, the device will not be destroyed when this
+    /// `Device` (and all of its clones) are dropped.
+    ///
+    /// ### Safety
+    ///
+    /// `raw_device` must be a valid device created from `physical_device`.
+    /// If `owned` is `true`, ownership is transferred and the device must
+    /// not be destroyed by anything else.
+    //
+    pub unsafe fn from_raw_parts(instance: Instance, physical_device: PhysicalDevice,
+            raw_device: vks::VkDevice, owned: bool) -> Device {
+        let handle = DeviceHandle(raw_device);
+        let mut loader = vks::DeviceProcAddrLoader::from_get_device_proc_addr(
+            instance.proc_addr_loader().vk.pfn_vkGetDeviceProcAddr);
+        loader.load_vk(handle.to_raw());
+
+        Device {
+            inner: Arc::new(Inner {
+                handle,
+                properties: physical_device.properties(),
+                physical_device,
+                queues: SmallVec::new(),
+                debug_utils: DebugUtilsLabelFns::load(&instance),
+                external_memory_host: ExternalMemoryHostFns::default(),
+                instance,
+                commands: DeviceCommands::new(&loader),
+                loader,
+                pipeline_cache: None,
+                enabled_features: PhysicalDeviceFeatures::default(),
+                enabled_extensions: Vec::new(),
+                allocation_tracker: None,
+                allocation_callbacks: None,
+                #[cfg(debug_assertions)]
+                full_barrier_count: AtomicUsize::new(0),
+                #[cfg(any(debug_assertions, feature = "validation"))]
+                live_children: AtomicUsize::new(0),
+                wait_idle_on_drop: true,
+                owned,
+            }),
+        }
+    }
+
+    /// Returns queue `queue_index` of the first queue family requested when
+    /// this device was created.
     ///
-    /// `device_queue_index` does not correspond to the queue family index or
-    /// any other index used when creating this device.
+    /// Note: this predates multi-family support and is equivalent to
+    /// `family_queue(first_family_index, queue_index).ok()`. Prefer
+    /// `family_queue` or `queue_by_family` when more than one family was
+    /// requested.
+    #[inline]
+    pub fn queue(&self, queue_index: usize) -> Option<&Queue> {
+        let family_index = self.inner.queues.first()?.family_index();
+        self.family_queue(family_index, queue_index as u32).ok()
+    }
+
+    /// Returns queue `queue_index` of the queue family `family_index`.
+    ///
+    /// Returns a `QueueNotRequested` error listing the families and queue
+    /// counts requested when this device was created if the family was not
+    /// requested or if `queue_index` is not less than the number of queues
+    /// requested from it.
+    pub fn family_queue(&self, family_index: u32, queue_index: u32) -> VdResult<&Queue> {
+        self.inner.queues.iter()
+            .find(|q| q.family_index() == family_index && q.index() == queue_index)
+            .ok_or_else(|| error::Error {
+                kind: error::ErrorKind::QueueNotRequested {
+                    family_index,
+                    queue_index,
+                    requested: self.requested_queue_counts(),
+                },
+                cause: None,
+            })
+    }
+
+    /// Returns the handle of queue `queue_index` of the queue family
+    /// `family_index`.
+    ///
+    /// See `family_queue` for the errors returned.
     #[inline]
-    pub fn queue(&self, device_queue_index: usize) -> Option<&Queue> {
-        self.inner.queues.get(device_queue_index)
+    pub fn queue_by_family(&self, family_index: u32, queue_index: u32) -> VdResult<QueueHandle> {
+        self.family_queue(family_index, queue_index).map(|q| q.handle())
+    }
+
+    /// Returns the `(family index, queue count)` pairs requested when this
+    /// device was created, in creation order.
+    fn requested_queue_counts(&self) -> Vec<(u32, u32)> {
+        let mut requested: Vec<(u32, u32)> = Vec::new();
+        for queue in self.inner.queues.iter() {
+            match requested.iter_mut().find(|r| r.0 == queue.family_index()) {
+                Some(r) => r.1 += 1,
+                None => requested.push((queue.family_index(), 1)),
+            }
+        }
+        requested
     }
 
     /// Returns a list of all queues associated with this device.
@@ -144,6 +613,143 @@ impl Device {
         &self.inner.loader
     }
 
+    /// Returns a pointer to the device-level command named `name`, or
+    /// `None` if it is unknown or belongs to an extension which was not
+    /// enabled.
+    ///
+    /// Intended for calling commands this crate does not yet wrap. The
+    /// returned pointer must be transmuted to the command's actual type
+    /// before being called; see the `proc_addr!` macro.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetDeviceProcAddr.html
+    //
+    pub fn get_proc_addr(&self, name: &CStr) -> vks::PFN_vkVoidFunction {
+        let get_device_proc_addr = self.inner.instance.proc_addr_loader().vk
+            .pfn_vkGetDeviceProcAddr?;
+        unsafe { get_device_proc_addr(self.inner.handle.to_raw(), name.as_ptr()) }
+    }
+
+    /// Returns a copy of the core command recording function table.
+    ///
+    /// Prefer this over `proc_addr_loader` when recording large numbers of
+    /// commands.
+    #[inline]
+    pub fn commands(&self) -> DeviceCommands {
+        self.inner.commands
+    }
+
+    /// Returns true if the extension named `name` was enabled when building
+    /// this device.
+    ///
+    /// Always returns false for devices created with `from_raw_parts`.
+    pub fn is_extension_enabled(&self, name: &CStr) -> bool {
+        self.inner.enabled_extensions.iter().any(|ext| ext.as_c_str() == name)
+    }
+
+    /// Returns the names of the extensions enabled when building this
+    /// device.
+    ///
+    /// Always empty for devices created with `from_raw_parts`.
+    pub fn enabled_extensions<'a>(&'a self) -> impl Iterator<Item=&'a CStr> + 'a {
+        self.inner.enabled_extensions.iter().map(|ext| ext.as_c_str())
+    }
+
+    /// Returns the allocation callbacks owned by this device, if any.
+    ///
+    /// See `DeviceBuilder::allocation_callbacks`.
+    #[inline]
+    pub fn allocation_callbacks(&self) -> Option<&AllocationCallbacks> {
+        self.inner.allocation_callbacks.as_ref()
+    }
+
+    /// Returns `allocator` or, if `None`, the raw allocation callbacks owned
+    /// by this device (or null if there are none).
+    #[inline]
+    fn allocator_or_default(&self, allocator: Option<*const vks::VkAllocationCallbacks>)
+            -> *const vks::VkAllocationCallbacks {
+        allocator.or_else(|| self.inner.allocation_callbacks.as_ref().map(|ac| ac.as_raw()))
+            .unwrap_or(ptr::null())
+    }
+
+    /// Records the creation of `count` child objects.
+    #[inline]
+    fn child_created(&self, count: usize) {
+        #[cfg(any(debug_assertions, feature = "validation"))]
+        self.inner.live_children.fetch_add(count, Ordering::SeqCst);
+        #[cfg(not(any(debug_assertions, feature = "validation")))]
+        let _ = count;
+    }
+
+    /// Records the destruction of a child object. Null handles are ignored.
+    #[inline]
+    fn child_destroyed(&self, raw_handle: u64) {
+        #[cfg(any(debug_assertions, feature = "validation"))]
+        { if raw_handle != 0 { self.inner.live_children.fetch_sub(1, Ordering::SeqCst); } }
+        #[cfg(not(any(debug_assertions, feature = "validation")))]
+        let _ = raw_handle;
+    }
+
+    /// Passes `result` through, recording the creation of a child object if
+    /// it is `Ok`.
+    #[inline]
+    fn track_created<T>(&self, result: VdResult<T>) -> VdResult<T> {
+        if result.is_ok() { self.child_created(1); }
+        result
+    }
+
+    /// Checks the result of a call creating `pipelines`, which must have been
+    /// null-initialized, recording their creation.
+    ///
+    /// If the call failed, any pipelines which were created anyway are
+    /// destroyed and a `PipelineCreation` error is returned with the index of
+    /// the first create info which failed.
+    unsafe fn check_pipelines(&self, result: i32, pipelines: SmallVec<[PipelineHandle; 4]>,
+            allocator: *const vks::VkAllocationCallbacks, fn_name: &'static str)
+            -> VdResult<SmallVec<[PipelineHandle; 4]>> {
+        let created = pipelines.iter().filter(|p| p.to_raw() != 0).count();
+        self.child_created(created);
+        if error::check_named(result, fn_name).is_ok() {
+            debug_assert!(created == pipelines.len(), "Device::check_pipelines: {} returned \
+                success but not all pipelines were created", fn_name);
+            return Ok(pipelines);
+        }
+        for &pipeline in pipelines.iter().filter(|p| p.to_raw() != 0) {
+            self.destroy_pipeline(pipeline, Some(allocator));
+        }
+        Err(pipeline_creation_error(result, &pipelines, fn_name))
+    }
+
+    /// Returns an `ExtensionNotEnabled` error if `name` is not enabled.
+    fn require_extension(&self, name: &'static CStr, fn_name: &'static str) -> VdResult<()> {
+        if self.is_extension_enabled(name) {
+            Ok(())
+        } else {
+            Err(error::Error { kind: error::ErrorKind::ExtensionNotEnabled { name, fn_name },
+                cause: None })
+        }
+    }
+
+    /// Returns the handle of the default pipeline cache, if one was
+    /// requested when building this device.
+    ///
+    /// See `DeviceBuilder::default_pipeline_cache`.
+    #[inline]
+    pub fn default_pipeline_cache(&self) -> Option<PipelineCacheHandle> {
+        self.inner.pipeline_cache
+    }
+
+    /// Returns the serialized contents of the default pipeline cache,
+    /// suitable for persisting and passing to
+    /// `DeviceBuilder::pipeline_cache_initial_data` in a later run.
+    ///
+    /// Returns an error if this device was built without a default pipeline
+    /// cache.
+    pub fn pipeline_cache_data(&self) -> VdResult<Vec<u8>> {
+        let pipeline_cache = self.inner.pipeline_cache
+            .ok_or("no default pipeline cache was created for this device")?;
+        unsafe { pipeline_cache::pipeline_cache_data(self, pipeline_cache) }
+    }
+
     /// Returns the handle for this device.
     #[inline]
     pub fn handle(&self) -> DeviceHandle {
@@ -156,6 +762,66 @@ impl Device {
         &self.inner.physical_device
     }
 
+    /// Returns the features enabled when this device was created.
+    ///
+    /// For devices built using `DeviceBuilder::enabled_features_2`, these
+    /// are the core features embedded in the `PhysicalDeviceFeatures2Khr`.
+    #[inline]
+    pub fn enabled_features(&self) -> &PhysicalDeviceFeatures {
+        &self.inner.enabled_features
+    }
+
+    /// Returns true if the `samplerAnisotropy` feature was enabled.
+    #[inline]
+    pub fn sampler_anisotropy_enabled(&self) -> bool {
+        self.inner.enabled_features.sampler_anisotropy()
+    }
+
+    /// Returns true if the `fillModeNonSolid` feature was enabled.
+    #[inline]
+    pub fn fill_mode_non_solid_enabled(&self) -> bool {
+        self.inner.enabled_features.fill_mode_non_solid()
+    }
+
+    /// Returns true if the `wideLines` feature was enabled.
+    #[inline]
+    pub fn wide_lines_enabled(&self) -> bool {
+        self.inner.enabled_features.wide_lines()
+    }
+
+    /// Returns true if the `depthClamp` feature was enabled.
+    #[inline]
+    pub fn depth_clamp_enabled(&self) -> bool {
+        self.inner.enabled_features.depth_clamp()
+    }
+
+    /// Returns a `FeatureNotEnabled` error if the feature selected by
+    /// `feature` was not enabled.
+    pub(crate) fn require_feature<F>(&self, feature: F, name: &'static str,
+            fn_name: &'static str) -> VdResult<()>
+            where F: FnOnce(&PhysicalDeviceFeatures) -> bool {
+        if feature(&self.inner.enabled_features) {
+            Ok(())
+        } else {
+            Err(error::Error { kind: error::ErrorKind::FeatureNotEnabled { name, fn_name },
+                cause: None })
+        }
+    }
+
+    /// Returns the properties of the associated physical device, queried
+    /// once when this device was created.
+    #[inline]
+    pub fn properties(&self) -> &::PhysicalDeviceProperties {
+        &self.inner.properties
+    }
+
+    /// Returns the limits of the associated physical device, queried once
+    /// when this device was created.
+    #[inline]
+    pub fn limits(&self) -> &::PhysicalDeviceLimits {
+        self.inner.properties.limits()
+    }
+
     /// Returns a reference to the associated instance.
     #[inline]
     pub fn instance(&self) -> &Instance {
@@ -189,14 +855,168 @@ impl Device {
             type_filter, properties);
     }
 
+    /// Returns the index of the memory type on this device best suited to
+    /// `usage` among those permitted by `type_filter`.
+    ///
+    /// The property combinations suited to `usage` (see `MemoryUsage`) are
+    /// tried in order of preference and the first memory type having all of
+    /// the properties of one is returned.
+    pub fn memory_type_index_for_usage(&self, type_filter: u32, usage: ::MemoryUsage)
+            -> VdResult<u32> {
+        let mem_props = self.physical_device().memory_properties();
+        for &properties in usage.preferred_properties().iter() {
+            for i in 0..mem_props.memory_type_count() {
+                if (type_filter & (1 << i)) != 0 &&
+                    mem_props.memory_types()[i as usize].property_flags().contains(properties)
+                {
+                    return Ok(i);
+                }
+            }
+        }
+        Err(format!("Device::memory_type_index_for_usage: no memory type permitted by the type \
+            filter ({:#b}) is suitable for {:?}", type_filter, usage).into())
+    }
+
+    /// Creates a single-level 2D image for use as a transient attachment,
+    /// such as a depth or multisampled color buffer which is never stored,
+    /// and binds it to memory of its own.
+    ///
+    /// `TRANSIENT_ATTACHMENT` is added to `usage`, which should include
+    /// `COLOR_ATTACHMENT` or `DEPTH_STENCIL_ATTACHMENT`. The memory is
+    /// allocated from a `LAZILY_ALLOCATED` memory type if the image permits
+    /// one, so that tile-based implementations need not back it with
+    /// physical memory at all (see `DeviceMemory::commitment`), otherwise
+    /// from a `DEVICE_LOCAL` memory type.
+    ///
+    /// The returned image keeps its memory alive (see `Image::memory`).
+    pub fn create_transient_attachment_image(&self, format: ::Format, extent: Extent2d,
+            samples: ::SampleCountFlags, usage: ::ImageUsageFlags) -> VdResult<::Image> {
+        let image = ::Image::builder()
+            .image_type(::ImageType::Type2d)
+            .format(format)
+            .extent(::Extent3d::builder()
+                .width(extent.width())
+                .height(extent.height())
+                .depth(1)
+                .build())
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(samples)
+            .tiling(::ImageTiling::Optimal)
+            .usage(usage | ::ImageUsageFlags::TRANSIENT_ATTACHMENT)
+            .sharing_mode(::SharingMode::Exclusive)
+            .initial_layout(ImageLayout::Undefined)
+            .build(self.clone())?;
+
+        let reqs = image.memory_requirements().clone();
+        let mem_props = self.physical_device().memory_properties();
+        let find_type = |properties: ::MemoryPropertyFlags| {
+            (0..mem_props.memory_type_count()).find(|&i| {
+                (reqs.memory_type_bits() & (1 << i)) != 0 &&
+                    mem_props.memory_types()[i as usize].property_flags().contains(properties)
+            })
+        };
+        let memory_type_index = find_type(::MemoryPropertyFlags::LAZILY_ALLOCATED)
+            .or_else(|| find_type(::MemoryPropertyFlags::DEVICE_LOCAL))
+            .ok_or("Device::create_transient_attachment_image: no lazily allocated or device \
+                local memory type is permitted by the image")?;
+
+        let memory = DeviceMemory::new(self.clone(), reqs.size(), memory_type_index)?;
+        unsafe { image.bind_memory(&memory, 0)?; }
+        Ok(image)
+    }
+
+    /// Expands the range `offset..offset + size` of a non-coherent memory
+    /// object to multiples of this device's `nonCoherentAtomSize`, returning
+    /// the `(offset, size)` to use in a `MappedMemoryRange`.
+    ///
+    /// `WHOLE_SIZE` is passed through. Ranges ending at the end of an
+    /// allocation whose size is not a multiple of the atom size should use
+    /// `WHOLE_SIZE` (or `DeviceMemory::non_coherent_round`, which does so
+    /// automatically).
+    pub fn non_coherent_round(&self, offset: ::DeviceSize, size: ::DeviceSize)
+            -> (::DeviceSize, ::DeviceSize) {
+        let atom_size = self.limits().non_coherent_atom_size();
+        ::mem_util::non_coherent_range(offset, size, atom_size, None)
+    }
+
+    /// Returns a snapshot of memory usage for each of this device's memory
+    /// heaps.
+    ///
+    /// Allocated byte counts are only available when the device was built
+    /// with `DeviceBuilder::track_allocations` enabled and only include
+    /// memory allocated through this device.
+    pub fn memory_usage(&self) -> Vec<HeapUsage> {
+        let mem_props = self.physical_device().memory_properties();
+        (0..mem_props.memory_heap_count()).map(|heap_index| {
+            let heap = &mem_props.memory_heaps()[heap_index as usize];
+            HeapUsage {
+                heap_index,
+                allocated_bytes: self.inner.allocation_tracker.as_ref()
+                    .map(|tracker| tracker.heap_allocated(heap_index)),
+                heap_size: heap.size(),
+                // `MemoryHeap::flags` returns the builder mask type; read the raw bits.
+                flags: ::bitflags::MemoryHeapFlags::from_bits_truncate(heap.as_raw().flags),
+            }
+        }).collect()
+    }
+
+    /// Allocates a single memory object with `properties` large enough to
+    /// hold each of `buffers`, then binds each buffer to it in order at a
+    /// suitably aligned offset. Returns the memory and the offsets.
+    ///
+    /// Returns an `ErrorKind::IncompatibleMemoryTypes` error if no memory
+    /// type is permitted by every buffer.
+    ///
+    /// ## Safety
+    ///
+    /// The caller is responsible for freeing the returned memory once none
+    /// of the buffers are in use. See `DeviceMemory::allocate_and_bind_buffers`
+    /// for an owning equivalent.
+    pub unsafe fn allocate_and_bind_buffers(&self, buffers: &[BufferHandle],
+            properties: ::MemoryPropertyFlags) -> VdResult<(DeviceMemoryHandle, Vec<DeviceSize>)> {
+        if buffers.is_empty() {
+            return Err("Device::allocate_and_bind_buffers: no buffers specified".into());
+        }
+        let reqs: SmallVec<[MemoryRequirements; 8]> = buffers.iter()
+            .map(|&buffer| self.get_buffer_memory_requirements(buffer))
+            .collect();
+        let memory_type_bits = ::mem_util::common_memory_type_bits(&reqs)?;
+        let (offsets, size) = ::mem_util::packed_offsets(&reqs)
+            .ok_or("Device::allocate_and_bind_buffers: combined size overflow")?;
+        let memory_type_index = self.memory_type_index(memory_type_bits, properties)?;
+
+        let allocate_info = MemoryAllocateInfo::builder()
+            .allocation_size(size)
+            .memory_type_index(memory_type_index)
+            .build();
+        let memory = self.allocate_memory(&allocate_info, None)?;
+        for (&buffer, &offset) in buffers.iter().zip(offsets.iter()) {
+            if let Err(err) = self.bind_buffer_memory(buffer, memory, offset) {
+                self.free_memory(memory, None);
+                return Err(err);
+            }
+        }
+        Ok((memory, offsets))
+    }
+
 
     /// Get a queue handle from a device.
     ///
+    /// Returns a `QueueNotRequested` error if the queue was not requested
+    /// when this device was created (not checked for devices created using
+    /// `from_raw`, for which no queues are recorded), or an error if the
+    /// driver returns a null handle.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetDeviceQueue.html
     //
     // *PFN_vkGetDeviceQueue)(VkDevice device, uint32_t queueFamilyIndex,
     // uint32_t queueIndex, VkQueue* pQueue);
-    pub fn get_device_queue(&self, queue_family_index: u32, queue_index: u32) -> Option<QueueHandle> {
+    pub fn get_device_queue(&self, queue_family_index: u32, queue_index: u32)
+            -> VdResult<QueueHandle> {
+        if !self.inner.queues.is_empty() {
+            self.family_queue(queue_family_index, queue_index)?;
+        }
         get_device_queue(self.proc_addr_loader(), self.inner.handle, queue_family_index, queue_index)
     }
 
@@ -221,10 +1041,46 @@ impl Device {
             fence: Option<FenceHandle>) -> VdResult<()>
             where Q: Handle<Target=QueueHandle> {
         let fence_handle_raw = fence.map(|f| f.to_raw()).unwrap_or(0);
-        let result = self.proc_addr_loader().vk.vkQueueSubmit(queue.handle().to_raw(),
-            submit_info.len() as u32, submit_info.as_ptr() as *const vks::VkSubmitInfo,
-            fence_handle_raw);
-        error::check(result, "vkQueueSubmit", ())
+        let result = self.proc_addr_loader().vk.vkQueueSubmit(queue.handle().to_raw(),
+            submit_info.len() as u32, submit_info.as_ptr() as *const vks::VkSubmitInfo,
+            fence_handle_raw);
+        error::check_named(result, "vkQueueSubmit")
+    }
+
+    /// Submits a single command buffer to a queue.
+    ///
+    /// Equivalent to calling `queue_submit` with one `SubmitInfo` containing
+    /// one command buffer but assembles the submission entirely on the stack.
+    /// No heap allocation occurs unless more than eight wait semaphores are
+    /// specified.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkQueueSubmit.html
+    //
+    pub unsafe fn queue_submit_one<Q>(&self, queue: Q,
+            wait: &[(SemaphoreHandle, PipelineStageFlags)], command_buffer: CommandBufferHandle,
+            signal: &[SemaphoreHandle], fence: Option<FenceHandle>) -> VdResult<()>
+            where Q: Handle<Target=QueueHandle> {
+        let mut wait_semaphores = SmallVec::<[vks::VkSemaphore; 8]>::new();
+        let mut wait_dst_stage_masks = SmallVec::<[vks::VkPipelineStageFlags; 8]>::new();
+        for &(semaphore, stage_mask) in wait {
+            wait_semaphores.push(semaphore.to_raw());
+            wait_dst_stage_masks.push(stage_mask.bits());
+        }
+        let command_buffer = command_buffer.to_raw();
+
+        let mut submit_info = vks::VkSubmitInfo::default();
+        submit_info.waitSemaphoreCount = wait_semaphores.len() as u32;
+        submit_info.pWaitSemaphores = wait_semaphores.as_ptr();
+        submit_info.pWaitDstStageMask = wait_dst_stage_masks.as_ptr();
+        submit_info.commandBufferCount = 1;
+        submit_info.pCommandBuffers = &command_buffer;
+        submit_info.signalSemaphoreCount = signal.len() as u32;
+        submit_info.pSignalSemaphores = signal.as_ptr() as *const vks::VkSemaphore;
+
+        let fence_handle_raw = fence.map(|f| f.to_raw()).unwrap_or(0);
+        let result = self.proc_addr_loader().vk.vkQueueSubmit(queue.handle().to_raw(), 1,
+            &submit_info, fence_handle_raw);
+        error::check_named(result, "vkQueueSubmit")
     }
 
     /// Waits for a queue to become idle.
@@ -259,11 +1115,17 @@ impl Device {
     // pMemory);
     pub unsafe fn allocate_memory(&self, allocate_info: &MemoryAllocateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<DeviceMemoryHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkAllocateMemory(self.handle().0,
             allocate_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkAllocateMemory", DeviceMemoryHandle(handle))
+        let handle = error::check(result, "vkAllocateMemory", DeviceMemoryHandle(handle))?;
+        self.child_created(1);
+        if let Some(ref tracker) = self.inner.allocation_tracker {
+            tracker.allocated(handle, allocate_info.memory_type_index(),
+                allocate_info.allocation_size());
+        }
+        Ok(handle)
     }
 
     /// Frees GPU memory.
@@ -274,9 +1136,13 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn free_memory(&self, memory: DeviceMemoryHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkFreeMemory(self.handle().0,
             memory.handle().to_raw(), allocator);
+        self.child_destroyed(memory.handle().to_raw());
+        if let Some(ref tracker) = self.inner.allocation_tracker {
+            tracker.freed(memory);
+        }
     }
 
     /// Maps a memory object into application address space.
@@ -312,7 +1178,7 @@ impl Device {
             -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkFlushMappedMemoryRanges(self.handle().to_raw(),
             memory_ranges.len() as u32, memory_ranges.as_ptr() as *const vks::VkMappedMemoryRange);
-        error::check(result, "vkFlushMappedMemoryRanges", ())
+        error::check_named(result, "vkFlushMappedMemoryRanges")
     }
 
     /// Invalidates ranges of mapped memory objects.
@@ -325,7 +1191,40 @@ impl Device {
             -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkInvalidateMappedMemoryRanges(self.handle().to_raw(),
             memory_ranges.len() as u32, memory_ranges.as_ptr() as *const vks::VkMappedMemoryRange);
-        error::check(result, "vkInvalidateMappedMemoryRanges", ())
+        error::check_named(result, "vkInvalidateMappedMemoryRanges")
+    }
+
+    /// Flushes the range `offset..offset + size` of the mapped, non-coherent
+    /// `memory`, first expanding it to `nonCoherentAtomSize` boundaries (see
+    /// `DeviceMemory::non_coherent_round`).
+    ///
+    /// Host writes to the range become available to the device.
+    pub unsafe fn flush_mapped_range(&self, memory: &DeviceMemory, offset: DeviceSize,
+            size: DeviceSize) -> VdResult<()> {
+        let (offset, size) = memory.non_coherent_round(offset, size);
+        let range = MappedMemoryRange::builder()
+            .memory(memory)
+            .offset(offset)
+            .size(size)
+            .build();
+        self.flush_mapped_memory_ranges(&[range])
+    }
+
+    /// Invalidates the range `offset..offset + size` of the mapped,
+    /// non-coherent `memory`, first expanding it to `nonCoherentAtomSize`
+    /// boundaries (see `DeviceMemory::non_coherent_round`).
+    ///
+    /// Device writes to the range, once made available, become visible to
+    /// the host.
+    pub unsafe fn invalidate_mapped_range(&self, memory: &DeviceMemory, offset: DeviceSize,
+            size: DeviceSize) -> VdResult<()> {
+        let (offset, size) = memory.non_coherent_round(offset, size);
+        let range = MappedMemoryRange::builder()
+            .memory(memory)
+            .offset(offset)
+            .size(size)
+            .build();
+        self.invalidate_mapped_memory_ranges(&[range])
     }
 
     /// Queries the current commitment for a VkDeviceMemory.
@@ -353,7 +1252,7 @@ impl Device {
             memory_offset: DeviceSize) -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkBindBufferMemory(
             self.handle().to_raw(), buffer.to_raw(), memory.to_raw(), memory_offset);
-        error::check(result, "vkBindBufferMemory", ())
+        error::check_named(result, "vkBindBufferMemory")
     }
 
     /// Binds device memory to an image object.
@@ -366,7 +1265,7 @@ impl Device {
             memory_offset: DeviceSize) -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkBindImageMemory(
             self.handle().to_raw(), image.to_raw(), memory.to_raw(), memory_offset);
-        error::check(result, "vkBindImageMemory", ())
+        error::check_named(result, "vkBindImageMemory")
     }
 
     /// Returns the memory requirements for specified Vulkan object.
@@ -377,7 +1276,7 @@ impl Device {
     // VkMemoryRequirements* pMemoryRequirements);
     pub unsafe fn get_buffer_memory_requirements(&self, buffer: BufferHandle) -> MemoryRequirements {
         let mut memory_requirements: vks::VkMemoryRequirements;
-        memory_requirements = mem::uninitialized();
+        memory_requirements = Default::default();
         self.proc_addr_loader().vk.vkGetBufferMemoryRequirements(self.handle().to_raw(),
             buffer.to_raw(), &mut memory_requirements);
         MemoryRequirements::from_raw(memory_requirements)
@@ -392,7 +1291,7 @@ impl Device {
     pub unsafe fn get_image_memory_requirements<I>(&self, image: I) -> MemoryRequirements
             where I: Handle<Target=ImageHandle> {
         let mut memory_requirements: vks::VkMemoryRequirements;
-        memory_requirements = mem::uninitialized();
+        memory_requirements = Default::default();
         self.proc_addr_loader().vk.vkGetImageMemoryRequirements(self.handle().to_raw(),
             image.handle().to_raw(), &mut memory_requirements);
         MemoryRequirements::from_raw(memory_requirements)
@@ -405,34 +1304,39 @@ impl Device {
     // *PFN_vkGetImageSparseMemoryRequirements)(VkDevice device, VkImage
     // image, uint32_t* pSparseMemoryRequirementCount,
     // VkSparseImageMemoryRequirements* pSparseMemoryRequirements);
+    //
+    // At most one set of requirements is returned per image aspect (color,
+    // depth, stencil, and metadata), so four are stored inline.
     pub unsafe fn get_image_sparse_memory_requirements<I>(&self, image: I)
-            -> SmallVec<[SparseImageMemoryRequirements; 32]>
+            -> SmallVec<[SparseImageMemoryRequirements; 4]>
             where I: Handle<Target=ImageHandle> {
-        let mut sparse_memory_requirement_count = 0u32;
-        let mut sparse_memory_requirements: SmallVec<[SparseImageMemoryRequirements; 32]> = SmallVec::new();
-        self.proc_addr_loader().vk.vkGetImageSparseMemoryRequirements(self.handle().to_raw(),
-            image.handle().to_raw(), &mut sparse_memory_requirement_count, ptr::null_mut());
-        sparse_memory_requirements.reserve_exact(sparse_memory_requirement_count as usize);
-        sparse_memory_requirements.set_len(sparse_memory_requirement_count as usize);
-        self.proc_addr_loader().vk.vkGetImageSparseMemoryRequirements(self.handle().to_raw(),
-            image.handle().to_raw(), &mut sparse_memory_requirement_count,
-            sparse_memory_requirements.as_mut_ptr() as *mut vks::VkSparseImageMemoryRequirements);
-        sparse_memory_requirements
+        util::enumerate_void::<[SparseImageMemoryRequirements; 4], _>(|count, p| {
+            self.proc_addr_loader().vk.vkGetImageSparseMemoryRequirements(self.handle().to_raw(),
+                image.handle().to_raw(), count, p as *mut vks::VkSparseImageMemoryRequirements)
+        })
     }
 
     /// Binds device memory to a sparse resource object.
     ///
+    /// The device must have been created with the `sparseBinding` feature
+    /// enabled (checked in debug builds). `fence`, if specified, is signaled
+    /// once all binds have completed. See `SparseBindBatch` for assembling
+    /// bind infos without managing the lifetimes of their arrays.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkQueueBindSparse.html
     //
     // *PFN_vkQueueBindSparse)(VkQueue queue, uint32_t bindInfoCount, const
     // VkBindSparseInfo* pBindInfo, VkFence fence);
-    pub unsafe fn queue_bind_sparse<Q, F>(&self, queue: Q, bind_info: &[BindSparseInfo], fence: F)
-            -> VdResult<()>
-            where Q: Handle<Target=QueueHandle>, F: Handle<Target=FenceHandle> {
+    pub unsafe fn queue_bind_sparse<Q>(&self, queue: Q, bind_info: &[BindSparseInfo],
+            fence: Option<FenceHandle>) -> VdResult<()>
+            where Q: Handle<Target=QueueHandle> {
+        debug_assert!(self.inner.enabled_features.sparse_binding(),
+            "Device::queue_bind_sparse: the `sparseBinding` feature is not enabled");
+        let fence_handle_raw = fence.map(|f| f.to_raw()).unwrap_or(0);
         let result = self.proc_addr_loader().vk.vkQueueBindSparse(queue.handle().to_raw(),
             bind_info.len() as u32, bind_info.as_ptr() as *const _ as *const vks::VkBindSparseInfo,
-            fence.handle().to_raw());
-        error::check(result, "vkQueueBindSparse", ())
+            fence_handle_raw);
+        error::check_named(result, "vkQueueBindSparse")
     }
 
     /// Creates a new fence object
@@ -443,11 +1347,11 @@ impl Device {
     // pCreateInfo, const VkAllocationCallbacks* pAllocator, VkFence* pFence);
     pub unsafe fn create_fence(&self, create_info: &FenceCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<FenceHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateFence(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateFence", FenceHandle(handle))
+        self.track_created(error::check(result, "vkCreateFence", FenceHandle(handle)))
     }
 
     /// Destroys a fence object.
@@ -458,9 +1362,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_fence(&self, fence: FenceHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyFence(self.handle().to_raw(),
             fence.to_raw(), allocator);
+        self.child_destroyed(fence.to_raw());
     }
 
     /// Resets one or more fence objects.
@@ -472,7 +1377,7 @@ impl Device {
     pub unsafe fn reset_fences(&self, fences: &[FenceHandle]) -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkResetFences(self.handle().to_raw(),
             fences.len() as u32, fences.as_ptr() as *const vks::VkFence);
-        error::check(result, "vkResetFences", ())
+        error::check_named(result, "vkResetFences")
     }
 
     /// Returns the status of a fence.
@@ -480,24 +1385,33 @@ impl Device {
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetFenceStatus.html
     //
     // *PFN_vkGetFenceStatus)(VkDevice device, VkFence fence);
-    pub unsafe fn get_fence_status<F>(&self, fence: F) -> VdResult<CallResult>
+    pub unsafe fn get_fence_status<F>(&self, fence: F) -> VdResult<FenceStatus>
             where F: Handle<Target=FenceHandle> {
         let result = self.proc_addr_loader().vk.vkGetFenceStatus(self.handle().to_raw(), fence.handle().to_raw());
-        error::check(result, "vkGetFenceStatus", CallResult::from(result))
+        match error::classify(result, "vkGetFenceStatus")? {
+            CallResult::Success => Ok(FenceStatus::Signaled),
+            CallResult::NotReady => Ok(FenceStatus::Unsignaled),
+            res => Err(error::Error::api_call(res, "vkGetFenceStatus")),
+        }
     }
 
     /// Waits for one or more fences to become signaled.
     ///
+    /// Returns `WaitResult::TimedOut` if `timeout` nanoseconds elapse first.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkWaitForFences.html
     //
     // *PFN_vkWaitForFences)(VkDevice device, uint32_t fenceCount, const
     // VkFence* pFences, VkBool32 waitAll, uint64_t timeout);
     pub unsafe fn wait_for_fences(&self, fences: &[FenceHandle], wait_all: bool, timeout: u64)
-            -> VdResult<()> {
+            -> VdResult<WaitResult> {
         let result = self.proc_addr_loader().vk.vkWaitForFences(self.handle().to_raw(),
             fences.len() as u32, fences.as_ptr() as *const vks::VkFence,
             wait_all as vks::VkBool32, timeout);
-        error::check(result, "vkWaitForFences", ())
+        match error::classify(result, "vkWaitForFences")? {
+            CallResult::Timeout => Ok(WaitResult::TimedOut),
+            _ => Ok(WaitResult::Signaled),
+        }
     }
 
     /// Creates a new queue semaphore object.
@@ -509,11 +1423,11 @@ impl Device {
     // pSemaphore);
     pub unsafe fn create_semaphore(&self, create_info: &SemaphoreCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<SemaphoreHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateSemaphore(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateSemaphore", SemaphoreHandle(handle))
+        self.track_created(error::check(result, "vkCreateSemaphore", SemaphoreHandle(handle)))
     }
 
     /// Destroys a semaphore object.
@@ -524,9 +1438,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_semaphore(&self, shader_module: SemaphoreHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroySemaphore(self.handle().to_raw(),
             shader_module.to_raw(), allocator);
+        self.child_destroyed(shader_module.to_raw());
     }
 
     /// Creates a new event object.
@@ -537,11 +1452,11 @@ impl Device {
     // pCreateInfo, const VkAllocationCallbacks* pAllocator, VkEvent* pEvent);
     pub unsafe fn create_event(&self, create_info: &EventCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<EventHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateEvent(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateEvent", EventHandle(handle))
+        self.track_created(error::check(result, "vkCreateEvent", EventHandle(handle)))
     }
 
     /// Destroys an event object.
@@ -552,9 +1467,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_event(&self, event: EventHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyEvent(self.handle().to_raw(),
             event.to_raw(), allocator);
+        self.child_destroyed(event.to_raw());
     }
 
     /// Retrieves the status of an event object.
@@ -562,11 +1478,15 @@ impl Device {
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetEventStatus.html
     //
     // *PFN_vkGetEventStatus)(VkDevice device, VkEvent event);
-    pub unsafe fn get_event_status<E>(&self, event: E) -> VdResult<CallResult>
+    pub unsafe fn get_event_status<E>(&self, event: E) -> VdResult<EventStatus>
             where E: Handle<Target=EventHandle> {
         let result = self.proc_addr_loader().vk.vkGetEventStatus(self.handle().to_raw(),
             event.handle().to_raw());
-        error::check(result, "vkGetEventStatus", CallResult::from(result))
+        match error::classify(result, "vkGetEventStatus")? {
+            CallResult::EventSet => Ok(EventStatus::Set),
+            CallResult::EventReset => Ok(EventStatus::Reset),
+            res => Err(error::Error::api_call(res, "vkGetEventStatus")),
+        }
     }
 
     /// Sets an event to signaled state.
@@ -578,7 +1498,7 @@ impl Device {
             where E: Handle<Target=EventHandle> {
         let result = self.proc_addr_loader().vk.vkSetEvent(self.handle().to_raw(),
             event.handle().to_raw());
-        error::check(result, "vkSetEvent", ())
+        error::check_named(result, "vkSetEvent")
     }
 
     /// Resets an event to non-signaled state.
@@ -590,7 +1510,7 @@ impl Device {
             where E: Handle<Target=EventHandle> {
         let result = self.proc_addr_loader().vk.vkResetEvent(self.handle().to_raw(),
             event.handle().to_raw());
-        error::check(result, "vkResetEvent", ())
+        error::check_named(result, "vkResetEvent")
     }
 
     /// Creates a new query pool object.
@@ -602,11 +1522,11 @@ impl Device {
     // pQueryPool);
     pub unsafe fn create_query_pool(&self, create_info: &QueryPoolCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<QueryPoolHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateQueryPool(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateQueryPool", QueryPoolHandle(handle))
+        self.track_created(error::check(result, "vkCreateQueryPool", QueryPoolHandle(handle)))
     }
 
     /// Destroys a query pool object
@@ -617,13 +1537,18 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_query_pool(&self, query_pool: QueryPoolHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyQueryPool(self.handle().to_raw(),
             query_pool.to_raw(), allocator);
+        self.child_destroyed(query_pool.to_raw());
     }
 
     /// Copies results of queries in a query pool to a host memory region
     ///
+    /// Returns `CallResult::NotReady` rather than `CallResult::Success` if
+    /// the results of any of the queries were not yet available (only
+    /// possible without `QueryResultFlags::WAIT`).
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetQueryPoolResults.html
     //
     // *PFN_vkGetQueryPoolResults)(VkDevice device, VkQueryPool queryPool,
@@ -631,12 +1556,12 @@ impl Device {
     // VkDeviceSize stride, VkQueryResultFlags flags);
     pub unsafe fn get_query_pool_results<Q>(&self, query_pool: Q, first_query: u32, query_count: u32,
             data_size: usize, data: *mut c_void, stride: DeviceSize, flags: QueryResultFlags)
-            -> VdResult<()>
+            -> VdResult<CallResult>
             where Q: Handle<Target=QueryPoolHandle> {
         let result = self.proc_addr_loader().vk.vkGetQueryPoolResults(self.handle().to_raw(),
             query_pool.handle().to_raw(), first_query, query_count, data_size, data, stride,
             flags.bits());
-        error::check(result, "vkGetQueryPoolResults", ())
+        error::classify(result, "vkGetQueryPoolResults")
     }
 
     /// Creates a new buffer object
@@ -648,11 +1573,11 @@ impl Device {
     // pBuffer);
     pub unsafe fn create_buffer(&self, create_info: &BufferCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<BufferHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateBuffer(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateBuffer", BufferHandle(handle))
+        self.track_created(error::check(result, "vkCreateBuffer", BufferHandle(handle)))
     }
 
     /// Destroys a buffer object.
@@ -663,9 +1588,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_buffer(&self, buffer: BufferHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyBuffer(self.handle().to_raw(),
             buffer.to_raw(), allocator);
+        self.child_destroyed(buffer.to_raw());
     }
 
     /// Creates a new buffer view object.
@@ -677,11 +1603,11 @@ impl Device {
     // pView);
     pub unsafe fn create_buffer_view(&self, create_info: &BufferViewCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<BufferViewHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateBufferView(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateBufferView", BufferViewHandle(handle))
+        self.track_created(error::check(result, "vkCreateBufferView", BufferViewHandle(handle)))
     }
 
     /// Destroys a buffer view object.
@@ -692,9 +1618,10 @@ impl Device {
     // const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_buffer_view(&self, buffer_view: BufferViewHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyBufferView(self.handle().to_raw(),
             buffer_view.to_raw(), allocator);
+        self.child_destroyed(buffer_view.to_raw());
     }
 
     /// Creates a new image object
@@ -705,11 +1632,11 @@ impl Device {
     // pCreateInfo, const VkAllocationCallbacks* pAllocator, VkImage* pImage);
     pub unsafe fn create_image(&self, create_info: &ImageCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<ImageHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateImage(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateImage", ImageHandle(handle))
+        self.track_created(error::check(result, "vkCreateImage", ImageHandle(handle)))
     }
 
     /// Destroys an image object.
@@ -720,9 +1647,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_image(&self, image: ImageHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyImage(self.handle().to_raw(),
             image.to_raw(), allocator);
+        self.child_destroyed(image.to_raw());
     }
 
     /// Retrieves information about an image subresource.
@@ -734,7 +1662,7 @@ impl Device {
     pub unsafe fn get_image_subresource_layout<I>(&self, image: I, subresource: &ImageSubresource)
             -> SubresourceLayout
             where I: Handle<Target=ImageHandle> {
-        let mut layout = mem::uninitialized();
+        let mut layout = Default::default();
         self.proc_addr_loader().vk.vkGetImageSubresourceLayout(self.handle().to_raw(),
             image.handle().to_raw(), subresource.as_raw(),
             &mut layout as *mut _ as *mut vks::VkSubresourceLayout);
@@ -750,11 +1678,11 @@ impl Device {
     // pView);
     pub unsafe fn create_image_view(&self, create_info: &ImageViewCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<ImageViewHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateImageView(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateImageView", ImageViewHandle(handle))
+        self.track_created(error::check(result, "vkCreateImageView", ImageViewHandle(handle)))
     }
 
     /// Destroys an image view object.
@@ -765,9 +1693,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_image_view(&self, image_view: ImageViewHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyImageView(self.handle().to_raw(),
             image_view.to_raw(), allocator);
+        self.child_destroyed(image_view.to_raw());
     }
 
     /// Creates a new shader module object.
@@ -779,11 +1708,11 @@ impl Device {
     // pAllocator, VkShaderModule* pShaderModule);
     pub unsafe fn create_shader_module(&self, create_info: &ShaderModuleCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<ShaderModuleHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateShaderModule(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateShaderModule", ShaderModuleHandle(handle))
+        self.track_created(error::check(result, "vkCreateShaderModule", ShaderModuleHandle(handle)))
     }
 
     /// Destroys a shader module module.
@@ -794,9 +1723,10 @@ impl Device {
     // shaderModule, const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_shader_module(&self, shader_module: ShaderModuleHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyShaderModule(self.handle().to_raw(),
             shader_module.to_raw(), allocator);
+        self.child_destroyed(shader_module.to_raw());
     }
 
     /// Creates a new pipeline cache
@@ -808,11 +1738,11 @@ impl Device {
     // pAllocator, VkPipelineCache* pPipelineCache);
     pub unsafe fn create_pipeline_cache(&self, create_info: &PipelineCacheCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<PipelineCacheHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreatePipelineCache(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreatePipelineCache", PipelineCacheHandle(handle))
+        self.track_created(error::check(result, "vkCreatePipelineCache", PipelineCacheHandle(handle)))
     }
 
     /// Destroys a pipeline cache object.
@@ -823,9 +1753,10 @@ impl Device {
     // pipelineCache, const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_pipeline_cache(&self, pipeline_cache: PipelineCacheHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyPipelineCache(self.handle().to_raw(),
             pipeline_cache.to_raw(), allocator);
+        self.child_destroyed(pipeline_cache.to_raw());
     }
 
     /// Gets the data store from a pipeline cache.
@@ -839,7 +1770,7 @@ impl Device {
             where Pc: Handle<Target=PipelineCacheHandle> {
         let result = self.proc_addr_loader().vk.vkGetPipelineCacheData(self.handle().to_raw(),
             pipeline_cache.handle().to_raw(), data_size, data);
-        error::check(result, "vkGetPipelineCacheData", ())
+        error::check_named(result, "vkGetPipelineCacheData")
     }
 
     /// Combines the data stores of pipeline caches.
@@ -854,11 +1785,18 @@ impl Device {
         let result = self.proc_addr_loader().vk.vkMergePipelineCaches(self.handle().to_raw(),
             dst_cache.handle().to_raw(), src_caches.len() as u32,
             src_caches.as_ptr() as *const vks::VkPipelineCache);
-        error::check(result, "vkMergePipelineCaches", ())
+        error::check_named(result, "vkMergePipelineCaches")
     }
 
     /// Creates graphics pipelines.
     ///
+    /// If `pipeline_cache` is `None`, the device's default pipeline cache is
+    /// used if one exists.
+    ///
+    /// If creation of any pipeline fails, those which were created are
+    /// destroyed and a `PipelineCreation` error is returned identifying the
+    /// first create info which failed.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCreateGraphicsPipelines.html
     //
     // *PFN_vkCreateGraphicsPipelines)(VkDevice device, VkPipelineCache
@@ -869,21 +1807,28 @@ impl Device {
             create_infos: &[GraphicsPipelineCreateInfo],
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SmallVec<[PipelineHandle; 4]>> {
-        let allocator = allocator.unwrap_or(ptr::null());
-        let pipeline_cache = pipeline_cache.map(|pc| pc.to_raw()).unwrap_or(0);
-        let mut pipelines = SmallVec::<[PipelineHandle; 4]>::new();
-        pipelines.reserve_exact(create_infos.len());
-        pipelines.set_len(create_infos.len());
+        let allocator = self.allocator_or_default(allocator);
+        let pipeline_cache = pipeline_cache.or(self.inner.pipeline_cache)
+            .map(|pc| pc.to_raw()).unwrap_or(0);
+        let mut pipelines: SmallVec<[PipelineHandle; 4]> = create_infos.iter()
+            .map(|_| PipelineHandle(0)).collect();
         let result = self.proc_addr_loader().vk.vkCreateGraphicsPipelines(self.handle().to_raw(),
             pipeline_cache, create_infos.len() as u32,
             create_infos.as_ptr() as *const vks::VkGraphicsPipelineCreateInfo,
             allocator,
             pipelines.as_mut_ptr() as *mut vks::VkPipeline);
-        error::check(result, "vkCreateGraphicsPipelines", pipelines)
+        self.check_pipelines(result, pipelines, allocator, "vkCreateGraphicsPipelines")
     }
 
     /// Creates a new compute pipeline object.
     ///
+    /// If `pipeline_cache` is `None`, the device's default pipeline cache is
+    /// used if one exists.
+    ///
+    /// If creation of any pipeline fails, those which were created are
+    /// destroyed and a `PipelineCreation` error is returned identifying the
+    /// first create info which failed.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCreateComputePipelines.html
     //
     // *PFN_vkCreateComputePipelines)(VkDevice device, VkPipelineCache
@@ -894,17 +1839,30 @@ impl Device {
             create_infos: &[ComputePipelineCreateInfo],
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SmallVec<[PipelineHandle; 4]>> {
-        let allocator = allocator.unwrap_or(ptr::null());
-        let pipeline_cache = pipeline_cache.map(|pc| pc.to_raw()).unwrap_or(0);
-        let mut pipelines = SmallVec::<[PipelineHandle; 4]>::new();
-        pipelines.reserve_exact(create_infos.len());
-        pipelines.set_len(create_infos.len());
+        let allocator = self.allocator_or_default(allocator);
+        let pipeline_cache = pipeline_cache.or(self.inner.pipeline_cache)
+            .map(|pc| pc.to_raw()).unwrap_or(0);
+        let mut pipelines: SmallVec<[PipelineHandle; 4]> = create_infos.iter()
+            .map(|_| PipelineHandle(0)).collect();
         let result = self.proc_addr_loader().vk.vkCreateComputePipelines(self.handle().to_raw(),
             pipeline_cache, create_infos.len() as u32,
             create_infos.as_ptr() as *const vks::VkComputePipelineCreateInfo,
             allocator,
             pipelines.as_mut_ptr() as *mut vks::VkPipeline);
-        error::check(result, "vkCreateComputePipelines", pipelines)
+        self.check_pipelines(result, pipelines, allocator, "vkCreateComputePipelines")
+    }
+
+    /// Creates a single compute pipeline object.
+    ///
+    /// Equivalent to calling `create_compute_pipelines` with one create info
+    /// and extracting the resulting handle.
+    pub unsafe fn create_compute_pipeline(&self, pipeline_cache: Option<PipelineCacheHandle>,
+            create_info: &ComputePipelineCreateInfo,
+            allocator: Option<*const vks::VkAllocationCallbacks>)
+            -> VdResult<PipelineHandle> {
+        let pipelines = self.create_compute_pipelines(pipeline_cache,
+            ::std::slice::from_ref(create_info), allocator)?;
+        Ok(*pipelines.get_unchecked(0))
     }
 
     /// Destroys a pipeline object.
@@ -915,9 +1873,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_pipeline(&self, pipeline: PipelineHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyPipeline(self.handle().to_raw(),
             pipeline.to_raw(), allocator);
+        self.child_destroyed(pipeline.to_raw());
     }
 
     /// Creates a new pipeline layout object.
@@ -929,11 +1888,11 @@ impl Device {
     // pAllocator, VkPipelineLayout* pPipelineLayout);
     pub unsafe fn create_pipeline_layout(&self, create_info: &PipelineLayoutCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<PipelineLayoutHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreatePipelineLayout(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreatePipelineLayout", PipelineLayoutHandle(handle))
+        self.track_created(error::check(result, "vkCreatePipelineLayout", PipelineLayoutHandle(handle)))
     }
 
     /// Destroys a pipeline layout object.
@@ -944,9 +1903,10 @@ impl Device {
     // pipelineLayout, const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_pipeline_layout(&self, pipeline_layout: PipelineLayoutHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyPipelineLayout(self.handle().to_raw(),
             pipeline_layout.to_raw(), allocator);
+        self.child_destroyed(pipeline_layout.to_raw());
     }
 
     /// Creates a new sampler object.
@@ -958,11 +1918,11 @@ impl Device {
     // pSampler);
     pub unsafe fn create_sampler(&self, create_info: &SamplerCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<SamplerHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateSampler(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateSampler", SamplerHandle(handle))
+        self.track_created(error::check(result, "vkCreateSampler", SamplerHandle(handle)))
     }
 
     /// Destroys a sampler object.
@@ -973,9 +1933,10 @@ impl Device {
     // VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_sampler(&self, sampler: SamplerHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroySampler(self.handle().to_raw(),
             sampler.to_raw(), allocator);
+        self.child_destroyed(sampler.to_raw());
     }
 
     /// Creates a new descriptor set layout.
@@ -987,11 +1948,11 @@ impl Device {
     // VkAllocationCallbacks* pAllocator, VkDescriptorSetLayout* pSetLayout);
     pub unsafe fn create_descriptor_set_layout(&self, create_info: &DescriptorSetLayoutCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<DescriptorSetLayoutHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateDescriptorSetLayout(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateDescriptorSetLayout", DescriptorSetLayoutHandle(handle))
+        self.track_created(error::check(result, "vkCreateDescriptorSetLayout", DescriptorSetLayoutHandle(handle)))
     }
 
     /// Destroys a descriptor set layout object.
@@ -1003,9 +1964,10 @@ impl Device {
     // pAllocator);
     pub unsafe fn destroy_descriptor_set_layout(&self, descriptor_set_layout: DescriptorSetLayoutHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyDescriptorSetLayout(self.handle().to_raw(),
             descriptor_set_layout.to_raw(), allocator);
+        self.child_destroyed(descriptor_set_layout.to_raw());
     }
 
     /// Creates a descriptor pool object.
@@ -1017,11 +1979,11 @@ impl Device {
     // pAllocator, VkDescriptorPool* pDescriptorPool);
     pub unsafe fn create_descriptor_pool(&self, create_info: &DescriptorPoolCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<DescriptorPoolHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateDescriptorPool(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateDescriptorPool", DescriptorPoolHandle(handle))
+        self.track_created(error::check(result, "vkCreateDescriptorPool", DescriptorPoolHandle(handle)))
     }
 
     /// Destroys a descriptor pool object.
@@ -1032,9 +1994,10 @@ impl Device {
     // descriptorPool, const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_descriptor_pool(&self, descriptor_pool: DescriptorPoolHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyDescriptorPool(self.handle().to_raw(),
             descriptor_pool.to_raw(), allocator);
+        self.child_destroyed(descriptor_pool.to_raw());
     }
 
     /// Resets a descriptor pool object.
@@ -1048,7 +2011,7 @@ impl Device {
             where Dp: Handle<Target=DescriptorPoolHandle> {
         let result = self.proc_addr_loader().vk.vkResetDescriptorPool(self.handle().to_raw(),
             descriptor_pool.handle().to_raw(), flags.bits());
-        error::check(result, "vkResetDescriptorPool", ())
+        error::check_named(result, "vkResetDescriptorPool")
     }
 
     /// Allocates one or more descriptor sets.
@@ -1061,13 +2024,26 @@ impl Device {
     pub unsafe fn allocate_descriptor_sets(&self, allocate_info: &DescriptorSetAllocateInfo)
             -> VdResult<SmallVec<[DescriptorSetHandle; 8]>> {
         let mut descriptor_sets = SmallVec::<[DescriptorSetHandle; 8]>::new();
-        let count = allocate_info.set_layouts().len();
-        descriptor_sets.reserve_exact(count);
-        descriptor_sets.set_len(count);
-        let result = self.proc_addr_loader().vk.vkAllocateDescriptorSets(
-            self.handle().to_raw(), allocate_info.as_raw(),
-            descriptor_sets.as_mut_ptr() as *mut vks::VkDescriptorSet);
-        error::check(result, "vkAllocateDescriptorSets", descriptor_sets)
+        self.allocate_descriptor_sets_into(allocate_info, &mut descriptor_sets)?;
+        Ok(descriptor_sets)
+    }
+
+    /// Allocates one or more descriptor sets, appending their handles to
+    /// `descriptor_sets`.
+    ///
+    /// Allows caller-provided storage to be reused across allocations. The
+    /// contents of `descriptor_sets` are unchanged if allocation fails.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkAllocateDescriptorSets.html
+    //
+    pub unsafe fn allocate_descriptor_sets_into<A>(&self, allocate_info: &DescriptorSetAllocateInfo,
+            descriptor_sets: &mut SmallVec<A>) -> VdResult<()>
+            where A: Array<Item=DescriptorSetHandle> {
+        append_handles(descriptor_sets, allocate_info.set_layouts().len(),
+                "vkAllocateDescriptorSets", |ptr| {
+            self.proc_addr_loader().vk.vkAllocateDescriptorSets(self.handle().to_raw(),
+                allocate_info.as_raw(), ptr as *mut vks::VkDescriptorSet)
+        })
     }
 
     /// Frees one or more descriptor sets.
@@ -1083,7 +2059,7 @@ impl Device {
         let result = self.proc_addr_loader().vk.vkFreeDescriptorSets(self.handle().to_raw(),
             descriptor_pool.handle().to_raw(), descriptor_sets.len() as u32,
             descriptor_sets.as_ptr() as *const vks::VkDescriptorSet);
-        error::check(result, "vkFreeDescriptorSets", ())
+        error::check_named(result, "vkFreeDescriptorSets")
     }
 
     /// Updates the contents of a descriptor set object.
@@ -1114,11 +2090,11 @@ impl Device {
     // pAllocator, VkFramebuffer* pFramebuffer);
     pub unsafe fn create_framebuffer(&self, create_info: &FramebufferCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<FramebufferHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateFramebuffer(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateFramebuffer", FramebufferHandle(handle))
+        self.track_created(error::check(result, "vkCreateFramebuffer", FramebufferHandle(handle)))
     }
 
     /// Destroys a framebuffer object.
@@ -1129,9 +2105,10 @@ impl Device {
     // const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_framebuffer(&self, framebuffer: FramebufferHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyFramebuffer(self.handle().to_raw(),
             framebuffer.to_raw(), allocator);
+        self.child_destroyed(framebuffer.to_raw());
     }
 
     /// Creates a new render pass object.
@@ -1143,11 +2120,11 @@ impl Device {
     // pRenderPass);
     pub unsafe fn create_render_pass(&self, create_info: &RenderPassCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<RenderPassHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateRenderPass(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateRenderPass", RenderPassHandle(handle))
+        self.track_created(error::check(result, "vkCreateRenderPass", RenderPassHandle(handle)))
     }
 
     /// Destroys a render pass object.
@@ -1158,9 +2135,10 @@ impl Device {
     // const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_render_pass(&self, render_pass: RenderPassHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyRenderPass(self.handle().to_raw(),
             render_pass.to_raw(), allocator);
+        self.child_destroyed(render_pass.to_raw());
     }
 
     /// Returns the granularity for optimal render area.
@@ -1172,7 +2150,7 @@ impl Device {
     pub unsafe fn get_render_area_granularity<Rp>(&self, render_pass: Rp)
             -> Extent2d
             where Rp: Handle<Target=RenderPassHandle> {
-        let mut granularity = mem::uninitialized();
+        let mut granularity = Default::default();
         self.proc_addr_loader().vk.vkGetRenderAreaGranularity(self.handle().to_raw(),
             render_pass.handle().to_raw(), &mut granularity as *mut _ as *mut vks::VkExtent2D);
         granularity
@@ -1187,11 +2165,11 @@ impl Device {
     // pAllocator, VkCommandPool* pCommandPool);
     pub unsafe fn create_command_pool(&self, create_info: &CommandPoolCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<CommandPoolHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateCommandPool(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateCommandPool", CommandPoolHandle(handle))
+        self.track_created(error::check(result, "vkCreateCommandPool", CommandPoolHandle(handle)))
     }
 
     /// Destroys a command pool object.
@@ -1202,9 +2180,10 @@ impl Device {
     // const VkAllocationCallbacks* pAllocator);
     pub unsafe fn destroy_command_pool(&self, command_pool: CommandPoolHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyCommandPool(self.handle().to_raw(),
             command_pool.to_raw(), allocator);
+        self.child_destroyed(command_pool.to_raw());
     }
 
     /// Resets a command pool.
@@ -1218,7 +2197,7 @@ impl Device {
             where Cp: Handle<Target=CommandPoolHandle> {
         let result = self.proc_addr_loader().vk.vkResetCommandPool(self.handle().to_raw(),
             command_pool.handle().to_raw(), flags.bits());
-        error::check(result, "vkResetCommandPool", ())
+        error::check_named(result, "vkResetCommandPool")
     }
 
     /// Allocates command buffers from an existing command pool.
@@ -1231,12 +2210,26 @@ impl Device {
     pub unsafe fn allocate_command_buffers(&self, allocate_info: &CommandBufferAllocateInfo)
             -> VdResult<SmallVec<[CommandBufferHandle; 16]>> {
         let mut command_buffers: SmallVec<[CommandBufferHandle; 16]> = SmallVec::new();
-        command_buffers.reserve_exact(allocate_info.command_buffer_count() as usize);
-        command_buffers.set_len(allocate_info.command_buffer_count() as usize);
-        let result = self.proc_addr_loader().vk.vkAllocateCommandBuffers(
-            self.handle().to_raw(), allocate_info.as_raw(),
-            command_buffers.as_mut_ptr() as *mut vks::VkCommandBuffer);
-        error::check(result, "vkAllocateCommandBuffers", command_buffers)
+        self.allocate_command_buffers_into(allocate_info, &mut command_buffers)?;
+        Ok(command_buffers)
+    }
+
+    /// Allocates command buffers from an existing command pool, appending
+    /// their handles to `command_buffers`.
+    ///
+    /// Allows caller-provided storage to be reused across allocations. The
+    /// contents of `command_buffers` are unchanged if allocation fails.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkAllocateCommandBuffers.html
+    //
+    pub unsafe fn allocate_command_buffers_into<A>(&self, allocate_info: &CommandBufferAllocateInfo,
+            command_buffers: &mut SmallVec<A>) -> VdResult<()>
+            where A: Array<Item=CommandBufferHandle> {
+        append_handles(command_buffers, allocate_info.command_buffer_count() as usize,
+                "vkAllocateCommandBuffers", |ptr| {
+            self.proc_addr_loader().vk.vkAllocateCommandBuffers(self.handle().to_raw(),
+                allocate_info.as_raw(), ptr as *mut vks::VkCommandBuffer)
+        })
     }
 
     /// Frees command buffers.
@@ -1261,7 +2254,7 @@ impl Device {
     pub unsafe fn begin_command_buffer(&self, command_buffer: CommandBufferHandle,
             begin_info: &CommandBufferBeginInfo) -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkBeginCommandBuffer(command_buffer.to_raw(), begin_info.as_raw());
-        error::check(result, "vkBeginCommandBuffer", ())
+        error::check_named(result, "vkBeginCommandBuffer")
     }
 
     /// Finishes recording a command buffer.
@@ -1271,7 +2264,7 @@ impl Device {
     // *PFN_vkEndCommandBuffer)(VkCommandBuffer commandBuffer);
     pub unsafe fn end_command_buffer(&self, command_buffer: CommandBufferHandle) -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkEndCommandBuffer(command_buffer.to_raw());
-        error::check(result, "vkEndCommandBuffer", ())
+        error::check_named(result, "vkEndCommandBuffer")
     }
 
     /// Resets a command buffer to the initial state.
@@ -1280,10 +2273,20 @@ impl Device {
     //
     // *PFN_vkResetCommandBuffer)(VkCommandBuffer commandBuffer,
     // VkCommandBufferResetFlags flags);
-    pub unsafe fn cmd_reset_command_buffer(&self, command_buffer: CommandBufferHandle,
+    pub unsafe fn reset_command_buffer(&self, command_buffer: CommandBufferHandle,
             flags: CommandBufferResetFlags) -> VdResult<()> {
         let result = self.proc_addr_loader().vk.vkResetCommandBuffer(command_buffer.to_raw(), flags.bits());
-        error::check(result, "vkResetCommandBuffer", ())
+        error::check_named(result, "vkResetCommandBuffer")
+    }
+
+    /// Resets a command buffer to the initial state.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkResetCommandBuffer.html
+    //
+    #[deprecated(note = "not a recorded command, use `Device::reset_command_buffer`")]
+    pub unsafe fn cmd_reset_command_buffer(&self, command_buffer: CommandBufferHandle,
+            flags: CommandBufferResetFlags) -> VdResult<()> {
+        self.reset_command_buffer(command_buffer, flags)
     }
 
     /// Binds a pipeline object to a command buffer.
@@ -1445,12 +2448,37 @@ impl Device {
     // firstBinding, uint32_t bindingCount, const VkBuffer* pBuffers, const
     // VkDeviceSize* pOffsets);
     pub unsafe fn cmd_bind_vertex_buffers(&self, command_buffer: CommandBufferHandle, first_binding: u32,
-            buffers: &[BufferHandle], offsets: &[u64]) {
+            buffers: &[(BufferHandle, DeviceSize)]) {
+        let (buffer_handles, offsets) = split_vertex_buffers(buffers);
+        self.cmd_bind_vertex_buffers_slices(command_buffer, first_binding, &buffer_handles,
+            &offsets);
+    }
+
+    /// Binds vertex buffers to a command buffer, taking buffers and offsets
+    /// as separate slices.
+    ///
+    /// Panics if `buffers` and `offsets` differ in length. Prefer
+    /// `cmd_bind_vertex_buffers`, which cannot be called with mismatched
+    /// lengths.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdBindVertexBuffers.html
+    pub unsafe fn cmd_bind_vertex_buffers_slices(&self, command_buffer: CommandBufferHandle,
+            first_binding: u32, buffers: &[BufferHandle], offsets: &[DeviceSize]) {
+        assert_eq!(buffers.len(), offsets.len(), "Device::cmd_bind_vertex_buffers_slices: \
+            `buffers` and `offsets` must have the same length");
         self.proc_addr_loader().vk.vkCmdBindVertexBuffers(command_buffer.to_raw(),
             first_binding, buffers.len() as u32, buffers.as_ptr() as *const vks::VkBuffer,
             offsets.as_ptr());
     }
 
+    /// Binds a single vertex buffer to a command buffer.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdBindVertexBuffers.html
+    pub unsafe fn cmd_bind_vertex_buffer(&self, command_buffer: CommandBufferHandle, binding: u32,
+            buffer: BufferHandle, offset: DeviceSize) {
+        self.cmd_bind_vertex_buffers_slices(command_buffer, binding, &[buffer], &[offset]);
+    }
+
     /// Draws primitives.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdDraw.html
@@ -1588,7 +2616,7 @@ impl Device {
             command_buffer.to_raw(),
             src_buffer.to_raw(),
             dst_image.to_raw(),
-            dst_image_layout as u32,
+            dst_image_layout.into(),
             regions.len() as u32,
             regions.as_ptr() as *const vks::VkBufferImageCopy,
         );
@@ -1624,6 +2652,8 @@ impl Device {
 
     /// Fills a region of a buffer with a fixed value.
     ///
+    /// A `size` of `None` fills to the end of the buffer (`WHOLE_SIZE`).
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdFillBuffer.html
     //
     // *PFN_vkCmdFillBuffer)(VkCommandBuffer commandBuffer, VkBuffer
@@ -1631,7 +2661,7 @@ impl Device {
     pub unsafe fn cmd_fill_buffer(&self,command_buffer: CommandBufferHandle,  dst_buffer: BufferHandle,
             dst_offset: u64, size: Option<DeviceSize>, data: u32) {
         self.proc_addr_loader().vk.vkCmdFillBuffer(command_buffer.to_raw(),
-            dst_buffer.to_raw(), dst_offset, size.unwrap_or(0), data);
+            dst_buffer.to_raw(), dst_offset, fill_size(size), data);
     }
 
     /// Clears regions of a color image.
@@ -1772,6 +2802,29 @@ impl Device {
         );
     }
 
+    /// Inserts an everything-to-everything execution and memory dependency:
+    /// a single `MemoryBarrier` making all `MEMORY_WRITE`s available and
+    /// visible to all `MEMORY_READ | MEMORY_WRITE` accesses, between
+    /// `ALL_COMMANDS` and `ALL_COMMANDS`.
+    ///
+    /// This is a debugging hammer for tracking down synchronization bugs (if
+    /// an artifact disappears when this is inserted, a barrier is missing
+    /// nearby) and should never ship: it serializes all work on the queue.
+    ///
+    /// In debug builds each use is counted and the count is logged as a
+    /// warning on the next `queue_present_khr`. Enabling the
+    /// `deny-full-barrier` feature removes this method from release builds
+    /// so that any remaining calls fail to compile.
+    #[cfg(any(debug_assertions, not(feature = "deny-full-barrier")))]
+    pub unsafe fn cmd_full_pipeline_barrier(&self, command_buffer: CommandBufferHandle) {
+        #[cfg(debug_assertions)]
+        self.inner.full_barrier_count.fetch_add(1, Ordering::SeqCst);
+        let access = AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE;
+        self.cmd_pipeline_barrier(command_buffer, PipelineStageFlags::ALL_COMMANDS,
+            PipelineStageFlags::ALL_COMMANDS, DependencyFlags::empty(),
+            &[MemoryBarrier::new(access, access)], &[], &[]);
+    }
+
     /// Begins a query.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdBeginQuery.html
@@ -1839,6 +2892,11 @@ impl Device {
 
     /// Updates the values of push constants.
     ///
+    /// Only a raw handle is available here, so the update cannot be checked
+    /// against the push constant ranges of `layout`. Use
+    /// `CommandBuffer::push_constants`, which validates in debug builds, or
+    /// `PipelineLayout::validate_push_constants` to check explicitly.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkCmdPushConstants.html
     //
     // *PFN_vkCmdPushConstants)(VkCommandBuffer commandBuffer,
@@ -1890,8 +2948,11 @@ impl Device {
     //
     // *PFN_vkCmdExecuteCommands)(VkCommandBuffer commandBuffer, uint32_t
     // commandBufferCount, const VkCommandBuffer* pCommandBuffers);
-    pub unsafe fn cmd_execute_commands(&self, command_buffer: CommandBufferHandle,
-            command_buffers: &[CommandBufferHandle]) {
+    pub unsafe fn cmd_execute_commands<Cb>(&self, command_buffer: CommandBufferHandle,
+            command_buffers: &[Cb])
+            where Cb: Handle<Target=CommandBufferHandle> {
+        let command_buffers: SmallVec<[CommandBufferHandle; 16]> = command_buffers.iter()
+            .map(|cb| cb.handle()).collect();
         self.proc_addr_loader().vk.vkCmdExecuteCommands(command_buffer.to_raw(),
             command_buffers.len() as u32, command_buffers.as_ptr() as *const vks::VkCommandBuffer);
     }
@@ -1905,11 +2966,12 @@ impl Device {
     // pAllocator, VkSwapchainKHR* pSwapchain);
     pub unsafe fn create_swapchain_khr(&self, create_info: &SwapchainCreateInfoKhr,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<SwapchainKhrHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        self.require_extension(names::KHR_SWAPCHAIN, "vkCreateSwapchainKHR")?;
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().khr_swapchain.vkCreateSwapchainKHR(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
-        error::check(result, "vkCreateSwapchainKHR", SwapchainKhrHandle(handle))
+        self.track_created(error::check(result, "vkCreateSwapchainKHR", SwapchainKhrHandle(handle)))
     }
 
     /// Destroys a swapchain object.
@@ -1918,11 +2980,12 @@ impl Device {
     //
     // *PFN_vkDestroySwapchainKHR)(VkDevice device, VkSwapchainKHR swapchain,
     // const VkAllocationCallbacks* pAllocator);
-    pub unsafe fn destroy_swapchain_khr(&mut self, swapchain: SwapchainKhrHandle,
+    pub unsafe fn destroy_swapchain_khr(&self, swapchain: SwapchainKhrHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let _allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().khr_swapchain.vkDestroySwapchainKHR(self.handle().to_raw(),
-            swapchain.to_raw(), ptr::null());
+            swapchain.to_raw(), allocator);
+        self.child_destroyed(swapchain.to_raw());
     }
 
     /// Obtains the array of presentable images associated with a swapchain.
@@ -1931,52 +2994,78 @@ impl Device {
     //
     // *PFN_vkGetSwapchainImagesKHR)(VkDevice device, VkSwapchainKHR
     // swapchain, uint32_t* pSwapchainImageCount, VkImage* pSwapchainImages);
+    //
+    // Drivers commonly return up to four or five images for a
+    // triple-buffered swapchain; eight avoids spilling in practice.
     pub unsafe fn get_swapchain_images_khr(&self, swapchain: SwapchainKhrHandle)
-            -> VdResult<SmallVec<[ImageHandle; 4]>> {
-        let mut image_count = 0;
-        let mut image_handles = SmallVec::<[ImageHandle; 4]>::new();
-        let result = self.proc_addr_loader().khr_swapchain.vkGetSwapchainImagesKHR(self.handle().to_raw(),
-            swapchain.to_raw(), &mut image_count, ptr::null_mut());
-        error::check(result, "vkGetSwapchainImagesKHR", ())?;
-        image_handles.reserve_exact(image_count as usize);
-        image_handles.set_len(image_count as usize);
-        loop {
-            let result = self.proc_addr_loader().khr_swapchain.vkGetSwapchainImagesKHR(self.handle().to_raw(),
-                swapchain.to_raw(), &mut image_count, image_handles.as_mut_ptr() as *mut vks::VkImage);
-            if result != CallResult::Incomplete as i32 {
-                return error::check(result, "vkGetSwapchainImagesKHR", image_handles);
-            }
-        }
+            -> VdResult<SmallVec<[ImageHandle; 8]>> {
+        self.require_extension(names::KHR_SWAPCHAIN, "vkGetSwapchainImagesKHR")?;
+        util::enumerate("vkGetSwapchainImagesKHR", |count, p| {
+            self.proc_addr_loader().khr_swapchain.vkGetSwapchainImagesKHR(self.handle().to_raw(),
+                swapchain.to_raw(), count, p as *mut vks::VkImage)
+        })
     }
 
     /// Retrieves the index of the next available presentable image.
     ///
+    /// Returns `AcquireResult::Suboptimal` if an image was acquired but the
+    /// swapchain no longer matches the surface exactly. Any other result,
+    /// including `CallResult::ErrorOutOfDateKhr`, `CallResult::Timeout`, and
+    /// `CallResult::NotReady`, is returned as an `ErrorKind::ApiCall` error,
+    /// or as an `ErrorKind::UnknownApiResult` error if unrecognized.
+    ///
     /// https://manned.org/vkAcquireNextImageKHR.3
     //
     // *PFN_vkAcquireNextImageKHR)(VkDevice device, VkSwapchainKHR swapchain,
     // uint64_t timeout, VkSemaphore semaphore, VkFence fence, uint32_t*
     // pImageIndex);
     pub unsafe fn acquire_next_image_khr(&self, swapchain: SwapchainKhrHandle, timeout: u64,
-            semaphore: Option<SemaphoreHandle>, fence: Option<FenceHandle>) -> VdResult<u32> {
+            semaphore: Option<SemaphoreHandle>, fence: Option<FenceHandle>)
+            -> VdResult<AcquireResult> {
+        self.require_extension(names::KHR_SWAPCHAIN, "vkAcquireNextImageKHR")?;
         let mut image_index = 0;
         let result = self.proc_addr_loader().khr_swapchain.vkAcquireNextImageKHR(
                 self.handle().to_raw(), swapchain.to_raw(), timeout,
                 semaphore.map(|s| s.to_raw()).unwrap_or(0),
                 fence.map(|f| f.to_raw()).unwrap_or(0), &mut image_index);
-        error::check(result, "vkAcquireNextImageKHR", image_index)
+        match error::classify(result, "vkAcquireNextImageKHR")? {
+            CallResult::Success => Ok(AcquireResult::Normal(image_index)),
+            CallResult::SuboptimalKhr => Ok(AcquireResult::Suboptimal(image_index)),
+            res => Err(error::Error::api_call(res, "vkAcquireNextImageKHR")),
+        }
     }
 
     /// Queues an image for presentation.
     ///
+    /// Returns `PresentResult::Suboptimal` if the image was queued but the
+    /// swapchain no longer matches the surface exactly. Any other result,
+    /// including `CallResult::ErrorOutOfDateKhr`, is returned as an
+    /// `ErrorKind::ApiCall` error, or as an `ErrorKind::UnknownApiResult`
+    /// error if unrecognized.
+    ///
     /// https://manned.org/vkQueuePresentKHR.3
     //
     // *PFN_vkQueuePresentKHR)(VkQueue queue, const VkPresentInfoKHR* pPresentInfo);
     pub unsafe fn queue_present_khr<Q>(&self, queue: Q, present_info: &PresentInfoKhr)
-            -> VdResult<()>
+            -> VdResult<PresentResult>
             where Q: Handle<Target=QueueHandle> {
+        self.require_extension(names::KHR_SWAPCHAIN, "vkQueuePresentKHR")?;
+        #[cfg(debug_assertions)]
+        {
+            let full_barrier_count = self.inner.full_barrier_count.swap(0, Ordering::SeqCst);
+            if full_barrier_count > 0 {
+                warn!("Device::cmd_full_pipeline_barrier was used {} time(s) this frame. \
+                    Full pipeline barriers are a debugging aid and should be replaced with \
+                    targeted barriers before shipping.", full_barrier_count);
+            }
+        }
         let result = self.proc_addr_loader().khr_swapchain.vkQueuePresentKHR(
             queue.handle().to_raw(), present_info.as_raw());
-        error::check(result, "vkQueuePresentKHR", ())
+        match error::classify(result, "vkQueuePresentKHR")? {
+            CallResult::Success => Ok(PresentResult::Normal),
+            CallResult::SuboptimalKhr => Ok(PresentResult::Suboptimal),
+            res => Err(error::Error::api_call(res, "vkQueuePresentKHR")),
+        }
     }
 
     /// Creates multiple swapchains that share presentable images.
@@ -1989,14 +3078,16 @@ impl Device {
     pub unsafe fn create_shared_swapchains_khr(&self, create_infos: &[SwapchainCreateInfoKhr],
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SmallVec<[SwapchainKhrHandle; 4]>> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        self.require_extension(names::KHR_DISPLAY_SWAPCHAIN, "vkCreateSharedSwapchainsKHR")?;
+        let allocator = self.allocator_or_default(allocator);
         let mut swapchains = SmallVec::<[SwapchainKhrHandle; 4]>::new();
-        swapchains.reserve_exact(create_infos.len());
-        swapchains.set_len(create_infos.len());
-        let result = self.proc_addr_loader().khr_display_swapchain.vkCreateSharedSwapchainsKHR(self.handle().to_raw(),
-            create_infos.len() as u32, create_infos as *const _ as *const vks::VkSwapchainCreateInfoKHR,
-            allocator, swapchains.as_mut_ptr() as *mut vks::VkSwapchainKHR);
-        error::check(result, "vkCreateSharedSwapchainsKHR", swapchains)
+        append_handles(&mut swapchains, create_infos.len(), "vkCreateSharedSwapchainsKHR", |ptr| {
+            self.proc_addr_loader().khr_display_swapchain.vkCreateSharedSwapchainsKHR(self.handle().to_raw(),
+                create_infos.len() as u32, create_infos as *const _ as *const vks::VkSwapchainCreateInfoKHR,
+                allocator, ptr as *mut vks::VkSwapchainKHR)
+        })?;
+        self.child_created(swapchains.len());
+        Ok(swapchains)
     }
 
     ///
@@ -2041,31 +3132,66 @@ impl Device {
         unimplemented!();
     }
 
+    /// Returns a POSIX file descriptor referencing the payload of a device
+    /// memory object. Ownership of the file descriptor is transferred to the
+    /// caller.
     ///
+    /// The memory must have been allocated with an
+    /// `ExportMemoryAllocateInfoKhr` chained to its `MemoryAllocateInfo`.
     ///
-    ///
+    /// https://manned.org/vkGetMemoryFdKHR.3
     //
     // *PFN_vkGetMemoryFdKHR)(VkDevice device, const VkMemoryGetFdInfoKHR*
     // pGetFdInfo, int* pFd);
-    pub unsafe fn get_memory_fd_khr(&self, _get_fd_info: &MemoryGetFdInfoKhr, _fd: &mut i32)
-            -> VdResult<()> {
-        // self.proc_addr_loader().
-        //     vkGetMemoryFdKHR)(VkDevice device, const VkMemoryGetFdInfoKHR* pGetFdInfo, int* pFd);
-        unimplemented!();
+    pub unsafe fn get_memory_fd_khr(&self, get_fd_info: &MemoryGetFdInfoKhr) -> VdResult<i32> {
+        self.require_extension(names::KHR_EXTERNAL_MEMORY_FD, "vkGetMemoryFdKHR")?;
+        let mut fd = -1;
+        let result = self.proc_addr_loader().khr_external_memory_fd.vkGetMemoryFdKHR(
+            self.handle().to_raw(), get_fd_info.as_raw(), &mut fd);
+        error::check(result, "vkGetMemoryFdKHR", fd)
     }
 
+    /// Returns the properties of a POSIX file descriptor to be imported
+    /// with `ImportMemoryFdInfoKhr`.
     ///
-    ///
-    ///
+    /// https://manned.org/vkGetMemoryFdPropertiesKHR.3
     //
     // *PFN_vkGetMemoryFdPropertiesKHR)(VkDevice device,
     // VkExternalMemoryHandleTypeFlagBitsKHR handleType, int fd,
     // VkMemoryFdPropertiesKHR* pMemoryFdProperties);
-    pub unsafe fn get_memory_fd_properties_khr(&self, _handle_type: ExternalMemoryHandleTypeFlagsKhr,
-            _fd: i32) -> VdResult<()> {
-        // self.proc_addr_loader().
-        //     vkGetMemoryFdPropertiesKHR)(VkDevice device, VkExternalMemoryHandleTypeFlagBitsKHR handleType, int fd, VkMemoryFdPropertiesKHR* pMemoryFdProperties);
-        unimplemented!();
+    pub unsafe fn get_memory_fd_properties_khr(&self, handle_type: ExternalMemoryHandleTypeFlagsKhr,
+            fd: i32) -> VdResult<MemoryFdPropertiesKhr<'static>> {
+        self.require_extension(names::KHR_EXTERNAL_MEMORY_FD, "vkGetMemoryFdPropertiesKHR")?;
+        let mut properties = vks::VkMemoryFdPropertiesKHR::default();
+        let result = self.proc_addr_loader().khr_external_memory_fd.vkGetMemoryFdPropertiesKHR(
+            self.handle().to_raw(), handle_type.bits(), fd, &mut properties);
+        error::check(result, "vkGetMemoryFdPropertiesKHR", MemoryFdPropertiesKhr::from_raw(properties))
+    }
+
+    /// Returns the memory type bits which may be used to import the host
+    /// allocation at `host_pointer` with an `ImportMemoryHostPointerInfoExt`.
+    ///
+    /// `host_pointer` must be aligned to
+    /// `PhysicalDeviceExternalMemoryHostPropertiesExt::min_imported_host_pointer_alignment`.
+    ///
+    /// Requires `VK_EXT_external_memory_host`.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/vkGetMemoryHostPointerPropertiesEXT.html
+    //
+    // *PFN_vkGetMemoryHostPointerPropertiesEXT)(VkDevice device,
+    // VkExternalMemoryHandleTypeFlagBits handleType, const void* pHostPointer,
+    // VkMemoryHostPointerPropertiesEXT* pMemoryHostPointerProperties);
+    pub unsafe fn get_memory_host_pointer_properties_ext(&self,
+            handle_type: ExternalMemoryHandleTypeFlagsKhr, host_pointer: *const c_void)
+            -> VdResult<u32> {
+        self.require_extension(names::EXT_EXTERNAL_MEMORY_HOST,
+            "vkGetMemoryHostPointerPropertiesEXT")?;
+        let get_properties = self.inner.external_memory_host.get_memory_host_pointer_properties
+            .ok_or("vkGetMemoryHostPointerPropertiesEXT: function not loaded")?;
+        let mut properties = VkMemoryHostPointerPropertiesEXT::default();
+        let result = get_properties(self.handle().to_raw(), handle_type.bits(), host_pointer,
+            &mut properties);
+        error::check(result, "vkGetMemoryHostPointerPropertiesEXT", properties.memoryTypeBits)
     }
 
     ///
@@ -2151,7 +3277,7 @@ impl Device {
             create_info: &DescriptorUpdateTemplateKhrCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<DescriptorUpdateTemplateKhrHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateDescriptorUpdateTemplateKhr(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
@@ -2170,7 +3296,7 @@ impl Device {
     pub unsafe fn destroy_descriptor_update_template_khr(&self,
             descriptor_update_template_khr: DescriptorUpdateTemplateKhrHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyDescriptorUpdateTemplateKhr(self.handle().to_raw(),
             descriptor_update_template_khr.to_raw(), allocator);
     }
@@ -2272,28 +3398,52 @@ impl Device {
         unimplemented!();
     }
 
+    /// Returns the memory requirements of an image.
     ///
+    /// Requires `VK_KHR_get_memory_requirements2`. If
+    /// `VK_KHR_dedicated_allocation` is also enabled, the dedicated
+    /// allocation preferences of the image are queried as well.
     ///
-    ///
+    /// https://manned.org/vkGetImageMemoryRequirements2KHR.3
     //
     // *PFN_vkGetImageMemoryRequirements2KHR)(VkDevice device, const
     // VkImageMemoryRequirementsInfo2KHR* pInfo, VkMemoryRequirements2KHR*
     // pMemoryRequirements);
     pub unsafe fn get_image_memory_requirements_2_khr(&self,
-            _info: &ImageMemoryRequirementsInfo2Khr) -> VdResult<()> {
-        unimplemented!();
+            info: &ImageMemoryRequirementsInfo2Khr) -> MemoryRequirements2 {
+        let mut dedicated = vks::VkMemoryDedicatedRequirementsKHR::default();
+        let mut requirements = vks::VkMemoryRequirements2KHR::default();
+        if self.is_extension_enabled(names::KHR_DEDICATED_ALLOCATION) {
+            requirements.pNext = &mut dedicated as *mut _ as *mut c_void;
+        }
+        self.proc_addr_loader().khr_get_memory_requirements2.vkGetImageMemoryRequirements2KHR(
+            self.handle().to_raw(), info.as_raw(), &mut requirements);
+        MemoryRequirements2::from_raw(requirements.memoryRequirements, &dedicated)
     }
 
+    /// Returns the memory requirements of a buffer.
     ///
+    /// Requires `VK_KHR_get_memory_requirements2`. If
+    /// `VK_KHR_dedicated_allocation` is also enabled, the dedicated
+    /// allocation preferences of the buffer are queried as well.
     ///
-    ///
+    /// https://manned.org/vkGetBufferMemoryRequirements2KHR.3
     //
     // *PFN_vkGetBufferMemoryRequirements2KHR)(VkDevice device, const
     // VkBufferMemoryRequirementsInfo2KHR* pInfo, VkMemoryRequirements2KHR*
     // pMemoryRequirements);
-    pub fn get_buffer_memory_requirements_2_khr(&self, _info: &BufferMemoryRequirementsInfo2Khr)
-            -> VdResult<()> {
-        unimplemented!();
+    pub fn get_buffer_memory_requirements_2_khr(&self, info: &BufferMemoryRequirementsInfo2Khr)
+            -> MemoryRequirements2 {
+        let mut dedicated = vks::VkMemoryDedicatedRequirementsKHR::default();
+        let mut requirements = vks::VkMemoryRequirements2KHR::default();
+        if self.is_extension_enabled(names::KHR_DEDICATED_ALLOCATION) {
+            requirements.pNext = &mut dedicated as *mut _ as *mut c_void;
+        }
+        unsafe {
+            self.proc_addr_loader().khr_get_memory_requirements2.vkGetBufferMemoryRequirements2KHR(
+                self.handle().to_raw(), info.as_raw(), &mut requirements);
+            MemoryRequirements2::from_raw(requirements.memoryRequirements, &dedicated)
+        }
     }
 
     ///
@@ -2322,7 +3472,7 @@ impl Device {
             create_info: &SamplerYcbcrConversionKhrCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SamplerYcbcrConversionKhrHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateSamplerYcbcrConversionKhr(
             self.handle().to_raw(), create_info.as_raw(), allocator, &mut handle);
@@ -2341,7 +3491,7 @@ impl Device {
     pub unsafe fn destroy_sampler_ycbcr_conversion_khr(&self,
             sampler_ycbcr_conversion_khr: SamplerYcbcrConversionKhrHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroySamplerYcbcrConversionKhr(self.handle().to_raw(),
             sampler_ycbcr_conversion_khr.to_raw(), allocator);
     }
@@ -2421,6 +3571,119 @@ impl Device {
             marker_info.as_raw());
     }
 
+    /// Returns the extension used to record command buffer debug regions,
+    /// preferring `VK_EXT_debug_utils`.
+    pub(crate) fn debug_region_backend(&self) -> DebugRegionBackend {
+        if self.instance().is_extension_enabled(names::EXT_DEBUG_UTILS) &&
+                self.inner.debug_utils.cmd_begin.is_some() {
+            DebugRegionBackend::DebugUtils
+        } else if self.is_extension_enabled(names::EXT_DEBUG_MARKER) {
+            DebugRegionBackend::DebugMarker
+        } else {
+            DebugRegionBackend::None
+        }
+    }
+
+    /// Opens a debug label region on a queue.
+    ///
+    /// Does nothing unless `VK_EXT_debug_utils` is enabled on the instance.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/vkQueueBeginDebugUtilsLabelEXT.html
+    //
+    // *PFN_vkQueueBeginDebugUtilsLabelEXT)(VkQueue queue, const
+    // VkDebugUtilsLabelEXT* pLabelInfo);
+    pub unsafe fn queue_begin_debug_label<Q>(&self, queue: Q, name: &str, color: [f32; 4])
+            -> VdResult<()>
+            where Q: Handle<Target=QueueHandle> {
+        if let Some(queue_begin) = self.inner.debug_utils.queue_begin {
+            let label = Label::new(name, color)?;
+            queue_begin(queue.handle().to_raw(), &label.raw());
+        }
+        Ok(())
+    }
+
+    /// Closes the most recently opened debug label region on a queue.
+    ///
+    /// Does nothing unless `VK_EXT_debug_utils` is enabled on the instance.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/vkQueueEndDebugUtilsLabelEXT.html
+    //
+    // *PFN_vkQueueEndDebugUtilsLabelEXT)(VkQueue queue);
+    pub unsafe fn queue_end_debug_label<Q>(&self, queue: Q) -> VdResult<()>
+            where Q: Handle<Target=QueueHandle> {
+        if let Some(queue_end) = self.inner.debug_utils.queue_end {
+            queue_end(queue.handle().to_raw());
+        }
+        Ok(())
+    }
+
+    /// Inserts a single debug label into a queue.
+    ///
+    /// Does nothing unless `VK_EXT_debug_utils` is enabled on the instance.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/vkQueueInsertDebugUtilsLabelEXT.html
+    //
+    // *PFN_vkQueueInsertDebugUtilsLabelEXT)(VkQueue queue, const
+    // VkDebugUtilsLabelEXT* pLabelInfo);
+    pub unsafe fn queue_insert_debug_label<Q>(&self, queue: Q, name: &str, color: [f32; 4])
+            -> VdResult<()>
+            where Q: Handle<Target=QueueHandle> {
+        if let Some(queue_insert) = self.inner.debug_utils.queue_insert {
+            let label = Label::new(name, color)?;
+            queue_insert(queue.handle().to_raw(), &label.raw());
+        }
+        Ok(())
+    }
+
+    /// Opens a debug label region in a command buffer.
+    ///
+    /// Does nothing unless `VK_EXT_debug_utils` is enabled on the instance.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/vkCmdBeginDebugUtilsLabelEXT.html
+    //
+    // *PFN_vkCmdBeginDebugUtilsLabelEXT)(VkCommandBuffer commandBuffer,
+    // const VkDebugUtilsLabelEXT* pLabelInfo);
+    pub unsafe fn cmd_begin_debug_label(&self, command_buffer: CommandBufferHandle, name: &str,
+            color: [f32; 4]) -> VdResult<()> {
+        if let Some(cmd_begin) = self.inner.debug_utils.cmd_begin {
+            let label = Label::new(name, color)?;
+            cmd_begin(command_buffer.to_raw(), &label.raw());
+        }
+        Ok(())
+    }
+
+    /// Closes the most recently opened debug label region in a command
+    /// buffer.
+    ///
+    /// Does nothing unless `VK_EXT_debug_utils` is enabled on the instance.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/vkCmdEndDebugUtilsLabelEXT.html
+    //
+    // *PFN_vkCmdEndDebugUtilsLabelEXT)(VkCommandBuffer commandBuffer);
+    pub unsafe fn cmd_end_debug_label(&self, command_buffer: CommandBufferHandle) -> VdResult<()> {
+        if let Some(cmd_end) = self.inner.debug_utils.cmd_end {
+            cmd_end(command_buffer.to_raw());
+        }
+        Ok(())
+    }
+
+    /// Inserts a single debug label into a command buffer.
+    ///
+    /// Does nothing unless `VK_EXT_debug_utils` is enabled on the instance.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/vkCmdInsertDebugUtilsLabelEXT.html
+    //
+    // *PFN_vkCmdInsertDebugUtilsLabelEXT)(VkCommandBuffer commandBuffer,
+    // const VkDebugUtilsLabelEXT* pLabelInfo);
+    pub unsafe fn cmd_insert_debug_label(&self, command_buffer: CommandBufferHandle, name: &str,
+            color: [f32; 4]) -> VdResult<()> {
+        if let Some(cmd_insert) = self.inner.debug_utils.cmd_insert {
+            let label = Label::new(name, color)?;
+            cmd_insert(command_buffer.to_raw(), &label.raw());
+        }
+        Ok(())
+    }
+
     ///
     ///
     ///
@@ -2549,7 +3812,7 @@ impl Device {
             create_info: &IndirectCommandsLayoutNvxCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<IndirectCommandsLayoutNvxHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateIndirectCommandsLayoutNvx(
             self.handle().to_raw(), create_info.as_raw(), allocator, &mut handle);
@@ -2568,7 +3831,7 @@ impl Device {
     pub unsafe fn destroy_indirect_commands_layout_nvx(&self,
             indirect_commands_layout_nvx: IndirectCommandsLayoutNvxHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyIndirectCommandsLayoutNvx(self.handle().to_raw(),
             indirect_commands_layout_nvx.to_raw(), allocator);
     }
@@ -2584,7 +3847,7 @@ impl Device {
     pub unsafe fn create_object_table_nvx(&self, create_info: &ObjectTableNvxCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<ObjectTableNvxHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateObjectTableNvx(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
@@ -2600,7 +3863,7 @@ impl Device {
     #[cfg(feature = "unimplemented")]
     pub unsafe fn destroy_object_table_nvx(&self, object_table_nvx: ObjectTableNvxHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyObjectTableNvx(self.handle().to_raw(),
             object_table_nvx.to_raw(), allocator);
     }
@@ -2763,7 +4026,7 @@ impl Device {
             create_info: &ValidationCacheExtCreateInfo,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<ValidationCacheExtHandle> {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         let mut handle = 0;
         let result = self.proc_addr_loader().vk.vkCreateValidationCacheExt(self.handle().to_raw(),
             create_info.as_raw(), allocator, &mut handle);
@@ -2781,7 +4044,7 @@ impl Device {
     pub unsafe fn destroy_validation_cache_ext(&self,
             validation_cache_ext: ValidationCacheExtHandle,
             allocator: Option<*const vks::VkAllocationCallbacks>) {
-        let allocator = allocator.unwrap_or(ptr::null());
+        let allocator = self.allocator_or_default(allocator);
         self.proc_addr_loader().vk.vkDestroyValidationCacheExt(self.handle().to_raw(),
             validation_cache_ext.to_raw(), allocator);
     }
@@ -2825,12 +4088,23 @@ unsafe impl Send for Device {}
 unsafe impl Sync for Device {}
 
 
+/// Queue priorities used by `DeviceBuilder::queue_families`.
+static DEFAULT_QUEUE_PRIORITIES: [f32; 1] = [1.0];
+
+
 /// A builder for `Device`.
 #[derive(Debug, Clone)]
 pub struct DeviceBuilder<'db> {
     create_info: ::DeviceCreateInfo<'db>,
     enabled_layer_names: Option<CharStrs<'db>>,
     enabled_extension_names: Option<CharStrs<'db>>,
+    default_pipeline_cache: bool,
+    pipeline_cache_initial_data: Option<&'db [u8]>,
+    track_allocations: bool,
+    enabled_features: Option<PhysicalDeviceFeatures>,
+    owned_queue_create_infos: SmallVec<[DeviceQueueCreateInfo<'static>; 4]>,
+    allocation_callbacks: Option<AllocationCallbacks>,
+    wait_idle_on_drop: bool,
     _p: PhantomData<&'db ()>,
 }
 
@@ -2841,6 +4115,13 @@ impl<'db> DeviceBuilder<'db> {
             create_info: ::DeviceCreateInfo::default(),
             enabled_layer_names: None,
             enabled_extension_names: None,
+            default_pipeline_cache: false,
+            pipeline_cache_initial_data: None,
+            track_allocations: false,
+            enabled_features: None,
+            owned_queue_create_infos: SmallVec::new(),
+            allocation_callbacks: None,
+            wait_idle_on_drop: true,
             _p: PhantomData,
         }
     }
@@ -2860,6 +4141,22 @@ impl<'db> DeviceBuilder<'db> {
         self
     }
 
+    /// Requests a single queue, with a priority of `1.0`, from each of the
+    /// distinct families in `selection`.
+    ///
+    /// Takes precedence over `queue_create_infos`. Retrieve the queues after
+    /// creation using `Device::family_queue(family_index, 0)`.
+    pub fn queue_families<'s>(&'s mut self, selection: &QueueFamilySelection)
+            -> &'s mut DeviceBuilder<'db> {
+        self.owned_queue_create_infos = selection.unique_families().iter().map(|&family_index| {
+            DeviceQueueCreateInfo::builder()
+                .queue_family_index(family_index)
+                .queue_priorities(&DEFAULT_QUEUE_PRIORITIES)
+                .build()
+        }).collect();
+        self
+    }
+
     /// Specifies the layer names to enable.
     ///
     /// Ignored.
@@ -2868,9 +4165,6 @@ impl<'db> DeviceBuilder<'db> {
             -> &'s mut DeviceBuilder<'db>
             where 'cs: 'db, Cs: 'cs + Into<CharStrs<'cs>> {
         self.enabled_layer_names = Some(enabled_layer_names.into());
-        if let Some(ref elns) = self.enabled_layer_names {
-            self.create_info.set_enabled_layer_names(elns.as_ptr_slice());
-        }
         self
     }
 
@@ -2880,9 +4174,6 @@ impl<'db> DeviceBuilder<'db> {
             -> &'s mut DeviceBuilder<'db>
             where 'cs: 'db, Cs: 'cs + Into<CharStrs<'cs>> {
         self.enabled_extension_names = Some(enabled_extension_names.into());
-        if let Some(ref eens) = self.enabled_extension_names {
-            self.create_info.set_enabled_extension_names(eens.as_ptr_slice());
-        }
         self
     }
 
@@ -2892,13 +4183,128 @@ impl<'db> DeviceBuilder<'db> {
             -> &'s mut DeviceBuilder<'db>
             where 'f: 'db {
         self.create_info.set_enabled_features(enabled_features);
+        self.enabled_features = Some(enabled_features.clone());
+        self
+    }
+
+    /// Specifies features to enable using a `PhysicalDeviceFeatures2Khr`
+    /// and any extension feature structures chained onto it, such as
+    /// `PhysicalDeviceDescriptorIndexingFeaturesExt`.
+    ///
+    /// Must not be used along with `enabled_features`. Requires the
+    /// `VK_KHR_get_physical_device_properties2` instance extension.
+    pub fn enabled_features_2<'s, 'f>(&'s mut self,
+            enabled_features: &'f mut PhysicalDeviceFeatures2Khr<'f>)
+            -> &'s mut DeviceBuilder<'db>
+            where 'f: 'db {
+        unsafe {
+            enabled_features.set_next(self.create_info.next() as *mut c_void);
+            self.create_info.set_next(enabled_features.as_raw() as *const _ as *const c_void);
+        }
+        self.enabled_features = Some(enabled_features.features().clone());
+        self
+    }
+
+    /// Specifies a group of physical devices, all belonging to the same
+    /// group as returned by `Instance::enumerate_physical_device_groups_khx`,
+    /// for the created logical device to span.
+    ///
+    /// The physical device passed to `build` must be a member of the group.
+    /// Requires the `VK_KHX_device_group_creation` instance extension.
+    #[cfg(feature = "experimental")]
+    pub fn device_group_khx<'s, 'g>(&'s mut self,
+            device_group: &'g mut DeviceGroupDeviceCreateInfoKhx<'g>)
+            -> &'s mut DeviceBuilder<'db>
+            where 'g: 'db {
+        unsafe {
+            device_group.set_next(self.create_info.next());
+            self.create_info.set_next(device_group.as_raw() as *const _ as *const c_void);
+        }
+        self
+    }
+
+    /// Specifies whether or not to create a pipeline cache along with the
+    /// device.
+    ///
+    /// The default pipeline cache is used by `Device::create_graphics_pipelines`
+    /// and `Device::create_compute_pipelines` whenever no cache is passed and
+    /// is destroyed along with the device. Its contents can be retrieved
+    /// using `Device::pipeline_cache_data`.
+    ///
+    /// Pipeline caches are internally synchronized, so pipelines may be
+    /// created concurrently from multiple threads using the default cache.
+    pub fn default_pipeline_cache<'s>(&'s mut self, enable: bool) -> &'s mut DeviceBuilder<'db> {
+        self.default_pipeline_cache = enable;
+        self
+    }
+
+    /// Specifies previously retrieved pipeline cache data used to initialize
+    /// the default pipeline cache.
+    ///
+    /// Implies `default_pipeline_cache(true)`.
+    pub fn pipeline_cache_initial_data<'s, 'd>(&'s mut self, initial_data: &'d [u8])
+            -> &'s mut DeviceBuilder<'db>
+            where 'd: 'db {
+        self.default_pipeline_cache = true;
+        self.pipeline_cache_initial_data = Some(initial_data);
+        self
+    }
+
+    /// Specifies whether or not to keep count of the number of bytes
+    /// allocated from each memory heap by `Device::allocate_memory` (and
+    /// everything built upon it), for reporting by `Device::memory_usage`.
+    ///
+    /// Disabled by default.
+    pub fn track_allocations<'s>(&'s mut self, enable: bool) -> &'s mut DeviceBuilder<'db> {
+        self.track_allocations = enable;
+        self
+    }
+
+    /// Specifies host allocation callbacks to be owned by the device.
+    ///
+    /// They are used to create and destroy the device itself and for every
+    /// object created or destroyed through it (including by all wrapper
+    /// types) for which no allocator is passed explicitly. See the
+    /// `AllocationCallbacks` documentation for lifetime requirements.
+    pub fn allocation_callbacks<'s>(&'s mut self, allocation_callbacks: AllocationCallbacks)
+            -> &'s mut DeviceBuilder<'db> {
+        self.allocation_callbacks = Some(allocation_callbacks);
+        self
+    }
+
+    /// Specifies whether or not to wait for the device to become idle
+    /// before destroying it once the last reference to it is dropped.
+    ///
+    /// Destroying a device while any of its queues are still executing
+    /// command buffers is undefined behavior. Errors from the wait, such as
+    /// `ErrorDeviceLost`, are logged and otherwise ignored.
+    ///
+    /// Enabled by default.
+    pub fn wait_idle_on_drop<'s>(&'s mut self, enable: bool) -> &'s mut DeviceBuilder<'db> {
+        self.wait_idle_on_drop = enable;
         self
     }
 
     /// Builds and returns a new `Device`.
     pub fn build(&self, physical_device: PhysicalDevice) -> VdResult<Device> {
+        // Name pointers are resolved here rather than in the setters so that
+        // inline pointer storage remains valid if the builder is moved.
+        let mut create_info: ::DeviceCreateInfo = self.create_info.clone();
+        if !self.owned_queue_create_infos.is_empty() {
+            create_info.set_queue_create_infos(&self.owned_queue_create_infos);
+        }
+        if let Some(ref elns) = self.enabled_layer_names {
+            create_info.set_enabled_layer_names(elns.as_ptr_slice());
+        }
+        if let Some(ref eens) = self.enabled_extension_names {
+            create_info.set_enabled_extension_names(eens.as_ptr_slice());
+        }
+
+        let allocator = self.allocation_callbacks.as_ref().map(|ac| ac.as_raw());
+        debug!("Creating device...");
         let handle = unsafe {
-            physical_device.instance().create_device(physical_device.handle(), &self.create_info, None)?
+            physical_device.instance().create_device(physical_device.handle(), &create_info,
+                allocator)?
         };
 
         let mut loader = vks::DeviceProcAddrLoader::from_get_device_proc_addr(
@@ -2908,18 +4314,24 @@ impl<'db> DeviceBuilder<'db> {
             loader.load_vk(handle.to_raw());
         }
 
+        let mut enabled_extensions = Vec::new();
+        let mut external_memory_host = ExternalMemoryHostFns::default();
         unsafe {
             if let Some(extension_name_char_strs) = self.enabled_extension_names.as_ref() {
                 let extension_names = extension_name_char_strs.as_ptr_slice();
                 for &extension_name in extension_names {
-                    match CStr::from_ptr(extension_name).to_str().expect("invalid extension name") {
+                    let extension_name = CStr::from_ptr(extension_name);
+                    enabled_extensions.push(extension_name.to_owned());
+                    let extension_name = extension_name.to_str()
+                        .expect("invalid extension name");
+                    trace!("Loading device extension functions: '{}'", extension_name);
+                    match extension_name {
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_16bit_storage" => loader.load_khr_16bit_storage(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_android_surface" => loader.load_khr_android_surface(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_bind_memory2" => loader.load_khr_bind_memory2(handle.to_raw()),
-                        #[cfg(feature = "unimplemented")]
                         "VK_KHR_dedicated_allocation" => loader.load_khr_dedicated_allocation(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_descriptor_update_template" => loader.load_khr_descriptor_update_template(handle.to_raw()),
@@ -2998,6 +4410,8 @@ impl<'db> DeviceBuilder<'db> {
                         "VK_EXT_direct_mode_display" => loader.load_ext_direct_mode_display(handle.to_raw()),
                         "VK_EXT_discard_rectangles" => loader.load_ext_discard_rectangles(handle.to_raw()),
                         "VK_EXT_display_control" => loader.load_ext_display_control(handle.to_raw()),
+                        "VK_EXT_external_memory_host" => external_memory_host =
+                            ExternalMemoryHostFns::load(physical_device.instance(), handle),
                         #[cfg(feature = "unimplemented")]
                         "VK_EXT_display_surface_counter" => loader.load_ext_display_surface_counter(handle.to_raw()),
                         "VK_EXT_hdr_metadata" => loader.load_ext_hdr_metadata(handle.to_raw()),
@@ -3099,32 +4513,67 @@ impl<'db> DeviceBuilder<'db> {
             }
         }
 
+        let pipeline_cache = if self.default_pipeline_cache {
+            let mut create_info = PipelineCacheCreateInfo::default();
+            if let Some(initial_data) = self.pipeline_cache_initial_data {
+                create_info.set_initial_data_size(initial_data.len());
+                unsafe { create_info.set_initial_data(initial_data.as_ptr() as *const c_void); }
+            }
+            let mut pipeline_cache = 0;
+            let result = unsafe {
+                loader.vk.vkCreatePipelineCache(handle.to_raw(), create_info.as_raw(),
+                    allocator.unwrap_or(ptr::null()), &mut pipeline_cache)
+            };
+            if let Err(err) = error::check_named(result, "vkCreatePipelineCache") {
+                unsafe { physical_device.instance().destroy_device(handle, allocator); }
+                return Err(err);
+            }
+            Some(PipelineCacheHandle(pipeline_cache))
+        } else {
+            None
+        };
+
         let instance = physical_device.instance().clone();
+        let allocation_tracker = if self.track_allocations {
+            Some(AllocationTracker::new(&physical_device))
+        } else {
+            None
+        };
 
         let device = Device {
             inner: Arc::new(Inner {
                 handle,
+                properties: physical_device.properties(),
                 physical_device,
                 queues: SmallVec::new(),
+                debug_utils: DebugUtilsLabelFns::load(&instance),
+                external_memory_host,
                 instance,
+                commands: DeviceCommands::new(&loader),
                 loader,
+                pipeline_cache,
+                enabled_features: self.enabled_features.clone().unwrap_or_default(),
+                enabled_extensions,
+                allocation_tracker,
+                allocation_callbacks: self.allocation_callbacks.clone(),
+                #[cfg(debug_assertions)]
+                full_barrier_count: AtomicUsize::new(0),
+                #[cfg(any(debug_assertions, feature = "validation"))]
+                live_children: AtomicUsize::new(0),
+                wait_idle_on_drop: self.wait_idle_on_drop,
+                owned: true,
             }),
         };
 
         let mut queues: SmallVec<[Queue; 16]> = SmallVec::new();
 
-        for qci in self.create_info.queue_create_infos() {
+        for qci in create_info.queue_create_infos() {
             for q_idx in 0..qci.queue_priorities().len() as u32 {
-                match get_device_queue(&device.inner.loader, device.inner.handle,
-                        qci.queue_family_index(), q_idx) {
-                    Some(q_handle) => unsafe {
-                        queues.push(Queue::from_parts(q_handle, device.clone(),
-                            qci.queue_family_index(), q_idx))
-                    },
-                    None => {
-                        panic!("unable to get device queue (family_index: {}, index: {})",
-                            qci.queue_family_index(), q_idx);
-                    },
+                let q_handle = get_device_queue(&device.inner.loader, device.inner.handle,
+                    qci.queue_family_index(), q_idx)?;
+                unsafe {
+                    queues.push(Queue::from_parts(q_handle, device.clone(),
+                        qci.queue_family_index(), q_idx))
                 }
             }
         }
@@ -3137,3 +4586,526 @@ impl<'db> DeviceBuilder<'db> {
         Ok(device)
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+    use std::sync::Arc;
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    use std::sync::atomic::Ordering;
+    use smallvec::SmallVec;
+    use vks;
+    use loader::null;
+    use counting_alloc::count_allocations;
+    use ::{Device, PhysicalDevice, DeviceMemoryHandle, DescriptorSetHandle, PipelineHandle, CallResult, ErrorKind,
+        BufferHandle, ImageHandle, RenderPassHandle, ImageSubresource, ImageAspectFlags,
+        QueueHandle, SemaphoreHandle, CommandBufferHandle, FenceHandle, PipelineStageFlags,
+        CommandPoolHandle, CommandBufferLevel, CommandBufferAllocateInfo, DescriptorPoolHandle,
+        DescriptorSetLayoutHandle, DescriptorSetAllocateInfo, DeviceQueueCreateInfo,
+        SwapchainKhrHandle, MemoryAllocateInfo, SparseImageMemoryRequirements, BufferCreateInfo,
+        AllocationCallbacks, CountingHostAllocator};
+    use names;
+    use super::{AllocationTracker, fill_size, append_handles, pipeline_creation_error,
+        split_vertex_buffers};
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    use super::{live_children_diagnostic, DROP_DIAGNOSTICS};
+
+    #[test]
+    fn allocation_tracker_counts_sizes_beyond_32_bits() {
+        let tracker = AllocationTracker::with_heaps(vec![0, 1, 1], 2);
+        let large = 6 << 30;
+        tracker.allocated(DeviceMemoryHandle(1), 1, large);
+        tracker.allocated(DeviceMemoryHandle(2), 2, large);
+        tracker.allocated(DeviceMemoryHandle(3), 0, 256);
+        assert_eq!(tracker.heap_allocated(0), 256);
+        assert_eq!(tracker.heap_allocated(1), 2 * large);
+
+        tracker.freed(DeviceMemoryHandle(1));
+        assert_eq!(tracker.heap_allocated(1), large);
+        // Memory not allocated through the tracker is ignored.
+        tracker.freed(DeviceMemoryHandle(4));
+        assert_eq!(tracker.heap_allocated(1), large);
+    }
+
+    #[test]
+    fn fill_size_defaults_to_whole_size() {
+        assert_eq!(fill_size(None), u64::max_value());
+        assert_eq!(fill_size(Some(0)), 0);
+        assert_eq!(fill_size(Some(256)), 256);
+    }
+
+    #[test]
+    fn append_handles_on_success() {
+        let mut handles: SmallVec<[DescriptorSetHandle; 2]> = SmallVec::new();
+        handles.push(DescriptorSetHandle(1));
+        // Spills out of inline storage, so existing elements must be moved:
+        unsafe {
+            append_handles(&mut handles, 3, "vkAllocateDescriptorSets", |ptr| {
+                for i in 0..3 { *ptr.offset(i) = DescriptorSetHandle(10 + i as u64); }
+                vks::VK_SUCCESS
+            }).unwrap();
+        }
+        assert_eq!(&handles[..], &[DescriptorSetHandle(1), DescriptorSetHandle(10),
+            DescriptorSetHandle(11), DescriptorSetHandle(12)]);
+    }
+
+    #[test]
+    fn append_handles_on_failure_leaves_handles_unchanged() {
+        let mut handles: SmallVec<[DescriptorSetHandle; 2]> = SmallVec::new();
+        handles.push(DescriptorSetHandle(1));
+        let err = unsafe {
+            append_handles(&mut handles, 4, "vkAllocateDescriptorSets", |ptr| {
+                // A failing call may still have written to the output array.
+                *ptr = DescriptorSetHandle(99);
+                vks::VK_ERROR_OUT_OF_POOL_MEMORY_KHR
+            }).unwrap_err()
+        };
+        match *err.kind() {
+            ErrorKind::ApiCall(CallResult::ErrorOutOfPoolMemoryKhr, "vkAllocateDescriptorSets") => (),
+            _ => panic!("unexpected error: {}", err),
+        }
+        assert_eq!(&handles[..], &[DescriptorSetHandle(1)]);
+    }
+
+    #[test]
+    fn pipeline_creation_error_identifies_first_failure() {
+        let pipelines = [PipelineHandle(5), PipelineHandle(0), PipelineHandle(0)];
+        let err = pipeline_creation_error(vks::VK_ERROR_OUT_OF_DEVICE_MEMORY, &pipelines,
+            "vkCreateGraphicsPipelines");
+        match *err.kind() {
+            ErrorKind::PipelineCreation { index, code, fn_name } => {
+                assert_eq!(index, Some(1));
+                assert_eq!(code, vks::VK_ERROR_OUT_OF_DEVICE_MEMORY);
+                assert_eq!(fn_name, "vkCreateGraphicsPipelines");
+            },
+            _ => panic!("unexpected error: {}", err),
+        }
+        assert_eq!(err.call_result(), Some(CallResult::ErrorOutOfDeviceMemory));
+
+        let err = pipeline_creation_error(vks::VK_ERROR_OUT_OF_HOST_MEMORY,
+            &[PipelineHandle(0)], "vkCreateComputePipelines");
+        match *err.kind() {
+            ErrorKind::PipelineCreation { index: Some(0), .. } => (),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn queries_convert_driver_output() {
+        let device = null::device();
+        assert_eq!(device.properties().limits().max_image_dimension_2d(),
+            null::MAX_IMAGE_DIMENSION_2D);
+
+        let requirements = unsafe { device.get_buffer_memory_requirements(BufferHandle(1)) };
+        assert_eq!(requirements.size(), null::MEMORY_SIZE);
+        assert_eq!(requirements.alignment(), null::MEMORY_ALIGNMENT);
+        assert_eq!(requirements.memory_type_bits(), 1);
+
+        let requirements = unsafe { device.get_image_memory_requirements(ImageHandle(2)) };
+        assert_eq!(requirements.size(), null::MEMORY_SIZE * 2);
+        assert_eq!(requirements.alignment(), null::MEMORY_ALIGNMENT * 2);
+
+        let subresource = ImageSubresource::builder()
+            .aspect_mask(ImageAspectFlags::COLOR)
+            .mip_level(3)
+            .array_layer(0)
+            .build();
+        let layout = unsafe { device.get_image_subresource_layout(ImageHandle(2), &subresource) };
+        assert_eq!(layout.offset(), 3 * null::MIP_LEVEL_SIZE);
+        assert_eq!(layout.size(), null::MIP_LEVEL_SIZE);
+        assert_eq!(layout.row_pitch(), 256);
+        assert_eq!(layout.array_pitch(), 0);
+
+        let granularity = unsafe { device.get_render_area_granularity(RenderPassHandle(4)) };
+        assert_eq!((granularity.width(), granularity.height()), null::GRANULARITY);
+    }
+
+    #[test]
+    fn queue_submit_one_submits_a_single_batch() {
+        let device = null::device();
+        let queue = QueueHandle(0x4000 as vks::VkQueue);
+        let command_buffer = CommandBufferHandle(0x5000 as vks::VkCommandBuffer);
+        let wait = [(SemaphoreHandle(1), PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT),
+            (SemaphoreHandle(2), PipelineStageFlags::TRANSFER)];
+        unsafe {
+            device.queue_submit_one(queue, &wait, command_buffer, &[SemaphoreHandle(3)],
+                Some(FenceHandle(4))).unwrap();
+        }
+        assert_eq!(null::take_submission().unwrap(), null::Submission {
+            wait_semaphores: vec![1, 2],
+            wait_dst_stage_masks: vec![PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT.bits(),
+                PipelineStageFlags::TRANSFER.bits()],
+            command_buffers: vec![command_buffer.to_raw()],
+            signal_semaphores: vec![3],
+            fence: 4,
+        });
+
+        unsafe { device.queue_submit_one(queue, &[], command_buffer, &[], None).unwrap(); }
+        let submission = null::take_submission().unwrap();
+        assert!(submission.wait_semaphores.is_empty());
+        assert!(submission.signal_semaphores.is_empty());
+        assert_eq!(submission.fence, 0);
+
+        let err = unsafe {
+            device.queue_submit_one(queue, &[], command_buffer, &[],
+                Some(FenceHandle(null::LOST_FENCE))).unwrap_err()
+        };
+        assert_eq!(err.call_result(), Some(CallResult::ErrorDeviceLost));
+        assert_eq!(err.fn_name(), Some("vkQueueSubmit"));
+    }
+
+    #[test]
+    fn queue_submit_one_does_not_allocate_for_up_to_eight_waits() {
+        let device = null::device();
+        let queue = QueueHandle(0x4000 as vks::VkQueue);
+        let command_buffer = CommandBufferHandle(0x5000 as vks::VkCommandBuffer);
+        let wait: Vec<_> = (1..10)
+            .map(|i| (SemaphoreHandle(i), PipelineStageFlags::TRANSFER))
+            .collect();
+        let signal = [SemaphoreHandle(10), SemaphoreHandle(11)];
+
+        let (result, allocations) = count_allocations(|| unsafe {
+            device.queue_submit_one(queue, &wait[..8], command_buffer, &signal,
+                Some(FenceHandle(4)))
+        });
+        result.unwrap();
+        assert_eq!(allocations, 0);
+        assert_eq!(null::take_submission().unwrap().wait_semaphores.len(), 8);
+
+        // A ninth wait spills the inline arrays.
+        let (result, allocations) = count_allocations(|| unsafe {
+            device.queue_submit_one(queue, &wait, command_buffer, &signal, None)
+        });
+        result.unwrap();
+        assert!(allocations > 0);
+        assert_eq!(null::take_submission().unwrap().wait_semaphores.len(), 9);
+    }
+
+    #[test]
+    fn allocate_into_appends_to_existing_handles() {
+        let device = null::device();
+        let mut command_buffers: SmallVec<[CommandBufferHandle; 4]> = SmallVec::new();
+        for &(pool, count) in [(1, 2), (2, 3)].iter() {
+            let allocate_info = CommandBufferAllocateInfo::builder()
+                .command_pool(CommandPoolHandle(pool))
+                .level(CommandBufferLevel::Primary)
+                .command_buffer_count(count)
+                .build();
+            unsafe {
+                device.allocate_command_buffers_into(&allocate_info, &mut command_buffers).unwrap();
+            }
+        }
+        let raw: Vec<_> = command_buffers.iter().map(|cb| cb.to_raw() as u64).collect();
+        assert_eq!(raw, [null::allocated_handle(1, 0), null::allocated_handle(1, 1),
+            null::allocated_handle(2, 0), null::allocated_handle(2, 1),
+            null::allocated_handle(2, 2)]);
+
+        let set_layouts = [DescriptorSetLayoutHandle(1), DescriptorSetLayoutHandle(2)];
+        let allocate_info = DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(DescriptorPoolHandle(3))
+            .set_layouts(&set_layouts)
+            .build();
+        let mut descriptor_sets: SmallVec<[DescriptorSetHandle; 8]> = SmallVec::new();
+        descriptor_sets.push(DescriptorSetHandle(7));
+        unsafe {
+            device.allocate_descriptor_sets_into(&allocate_info, &mut descriptor_sets).unwrap();
+        }
+        assert_eq!(&descriptor_sets[..], &[DescriptorSetHandle(7),
+            DescriptorSetHandle(null::allocated_handle(3, 0)),
+            DescriptorSetHandle(null::allocated_handle(3, 1))]);
+    }
+
+    #[test]
+    fn from_raw_parts_destroys_only_owned_devices() {
+        let device = null::device();
+        assert_eq!(device.handle().to_raw() as usize, 0x3000);
+        assert!(device.queues().is_empty());
+        assert!(!device.enabled_features().geometry_shader());
+        assert_eq!(device.enabled_extensions().count(), 0);
+        assert_eq!(device.properties().limits().max_image_dimension_2d(),
+            null::MAX_IMAGE_DIMENSION_2D);
+        null::take_lifetime_calls();
+        drop(device.clone());
+        drop(device);
+        assert!(null::take_lifetime_calls().is_empty());
+
+        let instance = null::instance();
+        let physical_device = PhysicalDevice::from_parts(instance.clone(),
+            null::physical_device_handle());
+        let device = unsafe {
+            Device::from_raw_parts(instance, physical_device, 0x3001 as vks::VkDevice, true)
+        };
+        let clone = device.clone();
+        drop(device);
+        assert!(null::take_lifetime_calls().is_empty());
+        drop(clone);
+        assert_eq!(null::take_lifetime_calls(),
+            [("vkDeviceWaitIdle", 0x3001), ("vkDestroyDevice", 0x3001)]);
+    }
+
+    #[test]
+    fn limits_are_cached_when_the_device_is_created() {
+        null::take_properties_queries();
+        let device = null::device();
+        let queries = null::take_properties_queries();
+        assert!(queries > 0);
+
+        let limits = device.limits();
+        assert_eq!(limits.max_compute_work_group_size(), null::MAX_COMPUTE_WORK_GROUP_SIZE);
+        assert_eq!(limits.max_viewport_dimensions(), null::MAX_VIEWPORT_DIMENSIONS);
+        assert_eq!(limits.point_size_range(), null::POINT_SIZE_RANGE);
+        assert_eq!(limits.buffer_image_granularity(), null::BUFFER_IMAGE_GRANULARITY);
+        assert_eq!(device.properties().device_name().to_bytes(), null::DEVICE_NAME);
+        assert_eq!(null::take_properties_queries(), 0);
+    }
+
+    #[test]
+    fn family_queue_validates_against_requested_queues() {
+        let priorities = [1.0, 0.5];
+        let queue_create_infos = [
+            DeviceQueueCreateInfo::builder()
+                .queue_family_index(2)
+                .queue_priorities(&priorities)
+                .build(),
+            DeviceQueueCreateInfo::builder()
+                .queue_family_index(0)
+                .queue_priorities(&priorities[..1])
+                .build(),
+        ];
+        let device = Device::builder()
+            .queue_create_infos(&queue_create_infos)
+            .build(null::physical_device())
+            .unwrap();
+        assert_eq!(device.handle().to_raw() as usize, null::CREATED_DEVICE);
+        assert_eq!(device.queues().len(), 3);
+
+        let queue = device.family_queue(2, 1).unwrap();
+        assert_eq!((queue.family_index(), queue.index()), (2, 1));
+        assert_eq!(queue.handle().to_raw(), null::queue_handle(2, 1));
+        assert_eq!(device.queue_by_family(0, 0).unwrap().to_raw(), null::queue_handle(0, 0));
+
+        // `queue` indexes the first family requested.
+        assert_eq!(device.queue(1).unwrap().handle().to_raw(), null::queue_handle(2, 1));
+        assert!(device.queue(2).is_none());
+
+        for &(family_index, queue_index) in [(1, 0), (0, 1), (2, 2)].iter() {
+            let err = device.queue_by_family(family_index, queue_index).unwrap_err();
+            match *err.kind() {
+                ErrorKind::QueueNotRequested { family_index: f, queue_index: q, ref requested } => {
+                    assert_eq!((f, q), (family_index, queue_index));
+                    assert_eq!(requested, &[(2, 2), (0, 1)]);
+                },
+                _ => panic!("unexpected error: {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn queue_families_requests_one_queue_per_selected_family() {
+        let physical_device = null::physical_device();
+        let selection = physical_device.find_queue_families(None).unwrap();
+        let device = Device::builder()
+            .queue_families(&selection)
+            .build(physical_device)
+            .unwrap();
+        let families: Vec<_> = device.queues().iter()
+            .map(|q| (q.family_index(), q.index()))
+            .collect();
+        assert_eq!(families, [(1, 0), (2, 0), (3, 0)]);
+        assert_eq!(device.queue_by_family(3, 0).unwrap().to_raw(), null::queue_handle(3, 0));
+    }
+
+    #[test]
+    fn enabled_extensions_reflect_the_builder() {
+        let device = Device::builder()
+            .build(null::physical_device())
+            .unwrap();
+        assert!(!device.is_extension_enabled(names::KHR_SWAPCHAIN));
+        assert_eq!(device.enabled_extensions().count(), 0);
+        let err = unsafe { device.get_swapchain_images_khr(SwapchainKhrHandle(1)) }.unwrap_err();
+        match *err.kind() {
+            ErrorKind::ExtensionNotEnabled { name, fn_name } => {
+                assert_eq!(name, names::KHR_SWAPCHAIN);
+                assert_eq!(fn_name, "vkGetSwapchainImagesKHR");
+            },
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        let extension_names = [names::KHR_SWAPCHAIN, names::KHR_DEDICATED_ALLOCATION];
+        let device = Device::builder()
+            .enabled_extension_names(&extension_names[..])
+            .build(null::physical_device())
+            .unwrap();
+        assert!(device.is_extension_enabled(names::KHR_SWAPCHAIN));
+        assert!(!device.is_extension_enabled(names::EXT_EXTERNAL_MEMORY_HOST));
+        let enabled: Vec<_> = device.enabled_extensions().collect();
+        assert_eq!(enabled, extension_names);
+    }
+
+    #[test]
+    fn dropping_waits_for_idle_unless_disabled() {
+        let device = Device::builder().build(null::physical_device()).unwrap();
+        let allocate_info = MemoryAllocateInfo::builder()
+            .allocation_size(null::MEMORY_SIZE)
+            .memory_type_index(0)
+            .build();
+        unsafe {
+            let memory = device.allocate_memory(&allocate_info, None).unwrap();
+            device.free_memory(memory, None);
+        }
+        #[cfg(any(debug_assertions, feature = "validation"))]
+        assert_eq!(device.inner.live_children.load(Ordering::SeqCst), 0);
+        null::take_lifetime_calls();
+        drop(device);
+        assert_eq!(null::take_lifetime_calls(), [("vkDeviceWaitIdle", null::CREATED_DEVICE),
+            ("vkDestroyDevice", null::CREATED_DEVICE)]);
+
+        let device = Device::builder()
+            .wait_idle_on_drop(false)
+            .build(null::physical_device())
+            .unwrap();
+        null::take_lifetime_calls();
+        drop(device);
+        assert_eq!(null::take_lifetime_calls(), [("vkDestroyDevice", null::CREATED_DEVICE)]);
+    }
+
+    #[test]
+    fn device_allocation_callbacks_are_used_by_default() {
+        let host_allocator = Arc::new(CountingHostAllocator::new());
+        let user_data = &*host_allocator as *const CountingHostAllocator as usize;
+        let device = Device::builder()
+            .allocation_callbacks(AllocationCallbacks::new(host_allocator.clone()))
+            .build(null::physical_device())
+            .unwrap();
+        unsafe {
+            let buffer = device.create_buffer(&BufferCreateInfo::default(), None).unwrap();
+            device.destroy_buffer(buffer, None);
+        }
+        drop(device);
+        assert_eq!(null::take_allocators(), [("vkCreateDevice", user_data),
+            ("vkCreateBuffer", user_data), ("vkDestroyBuffer", user_data),
+            ("vkDestroyDevice", user_data)]);
+
+        // Without device callbacks, nothing is passed.
+        let device = Device::builder().build(null::physical_device()).unwrap();
+        unsafe {
+            let buffer = device.create_buffer(&BufferCreateInfo::default(), None).unwrap();
+            device.destroy_buffer(buffer, None);
+        }
+        drop(device);
+        assert_eq!(null::take_allocators(), [("vkCreateDevice", 0), ("vkCreateBuffer", 0),
+            ("vkDestroyBuffer", 0), ("vkDestroyDevice", 0)]);
+    }
+
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    #[test]
+    fn live_children_are_reported_on_drop() {
+        let device = Device::builder().build(null::physical_device()).unwrap();
+        let allocate_info = MemoryAllocateInfo::builder()
+            .allocation_size(null::MEMORY_SIZE)
+            .memory_type_index(0)
+            .build();
+        let memories: Vec<_> = (0..2).map(|_| unsafe {
+            device.allocate_memory(&allocate_info, None).unwrap()
+        }).collect();
+        unsafe { device.free_memory(memories[0], None); }
+        // Null handles are not counted.
+        unsafe { device.free_memory(DeviceMemoryHandle(0), None); }
+        let live_children = device.inner.live_children.load(Ordering::SeqCst);
+        assert_eq!(live_children, 1);
+
+        assert!(live_children_diagnostic(0).is_none());
+        let msg = live_children_diagnostic(live_children).unwrap();
+        assert!(msg.contains("with 1 child object(s) still alive"), "{}", msg);
+    }
+
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    #[test]
+    fn leaked_raw_handles_are_reported_when_dropped() {
+        let take_diagnostics = || DROP_DIAGNOSTICS.with(|d| d.borrow_mut().split_off(0));
+        let device = Device::builder().build(null::physical_device()).unwrap();
+        let buffers: Vec<_> = (0..2).map(|_| unsafe {
+            device.create_buffer(&BufferCreateInfo::default(), None).unwrap()
+        }).collect();
+        unsafe { device.destroy_buffer(buffers[0], None); }
+        take_diagnostics();
+        drop(device);
+        let diagnostics = take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].contains("with 1 child object(s) still alive"), "{}",
+            diagnostics[0]);
+
+        // Nothing is reported once every handle has been destroyed.
+        let device = Device::builder().build(null::physical_device()).unwrap();
+        unsafe {
+            let buffer = device.create_buffer(&BufferCreateInfo::default(), None).unwrap();
+            device.destroy_buffer(buffer, None);
+        }
+        drop(device);
+        assert!(take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn split_vertex_buffers_keeps_pairs_aligned() {
+        let (buffers, offsets) = split_vertex_buffers(&[]);
+        assert!(buffers.is_empty() && offsets.is_empty());
+
+        let pairs: Vec<_> = (0..20).map(|i| (BufferHandle(i + 1), i * 256)).collect();
+        let (buffers, offsets) = split_vertex_buffers(&pairs);
+        assert_eq!(buffers.len(), 20);
+        for (i, (&buffer, &offset)) in buffers.iter().zip(offsets.iter()).enumerate() {
+            assert_eq!((buffer, offset), pairs[i]);
+        }
+    }
+
+    #[test]
+    fn bind_vertex_buffers_records_paired_offsets() {
+        let device = null::device();
+        let command_buffer = CommandBufferHandle(0x5000 as vks::VkCommandBuffer);
+        let buffers = [(BufferHandle(1), 0), (BufferHandle(2), 64), (BufferHandle(3), 4096)];
+        unsafe {
+            device.cmd_bind_vertex_buffers(command_buffer, 2, &buffers);
+            device.commands().cmd_bind_vertex_buffers(command_buffer, 0, &buffers[1..]);
+            device.cmd_bind_vertex_buffer(command_buffer, 5, BufferHandle(4), 16);
+        }
+        assert_eq!(null::take_vertex_buffer_bindings(), [
+            null::VertexBufferBinding { first_binding: 2, buffers: vec![1, 2, 3],
+                offsets: vec![0, 64, 4096] },
+            null::VertexBufferBinding { first_binding: 0, buffers: vec![2, 3],
+                offsets: vec![64, 4096] },
+            null::VertexBufferBinding { first_binding: 5, buffers: vec![4], offsets: vec![16] },
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must have the same length")]
+    fn bind_vertex_buffers_slices_rejects_short_offsets() {
+        let device = null::device();
+        let command_buffer = CommandBufferHandle(0x5000 as vks::VkCommandBuffer);
+        unsafe {
+            device.cmd_bind_vertex_buffers_slices(command_buffer, 0,
+                &[BufferHandle(1), BufferHandle(2)], &[0]);
+        }
+    }
+
+    #[test]
+    fn sparse_memory_requirements_are_stored_inline() {
+        let device = null::device();
+        let requirements = unsafe {
+            device.get_image_sparse_memory_requirements(ImageHandle(1))
+        };
+        let aspects: Vec<_> = requirements.iter()
+            .map(|r| r.format_properties().aspect_mask())
+            .collect();
+        assert_eq!(aspects, null::sparse_aspects());
+        assert!(!requirements.spilled());
+
+        // One set of requirements per aspect is stored inline. Growing the
+        // inline capacity grows the stack frame of every caller.
+        assert_eq!(requirements.inline_size(), 4);
+        assert_eq!(mem::size_of::<SparseImageMemoryRequirements>(), 48);
+        assert!(mem::size_of_val(&requirements) <= 4 * 48 + 3 * mem::size_of::<usize>(),
+            "size: {}", mem::size_of_val(&requirements));
+    }
+}
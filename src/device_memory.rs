@@ -1,11 +1,17 @@
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::mem;
 use std::ops::{Deref, DerefMut};
 use std::slice;
 use std::marker::PhantomData;
+use libc::c_void;
 use vks;
-use ::{VdResult, Device, Handle, MemoryAllocateInfo, MemoryMapFlags};
+use smallvec::SmallVec;
+use ::{VdResult, Device, Handle, MemoryAllocateInfo, MemoryMapFlags, ExportMemoryAllocateInfoKhr,
+    ImportMemoryFdInfoKhr, MemoryDedicatedAllocateInfoKhr, MemoryRequirements, Buffer,
+    MemoryPropertyFlags, DeviceSize, WHOLE_SIZE, ExternalMemoryHandleTypeFlagsKhr,
+    ImportMemoryHostPointerInfoExt};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -13,6 +19,13 @@ use ::{VdResult, Device, Handle, MemoryAllocateInfo, MemoryMapFlags};
 pub struct DeviceMemoryHandle(pub(crate) vks::VkDeviceMemory);
 
 impl DeviceMemoryHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDeviceMemory) -> DeviceMemoryHandle {
+        DeviceMemoryHandle(raw)
+    }
+
+    #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDeviceMemory {
         self.0
     }
@@ -58,12 +71,101 @@ impl<'m, T> DerefMut for MemoryMapping<'m, T> {
 }
 
 
+/// A mapped region of a `DeviceMemory`, unmapped when dropped.
+///
+/// Dereferences to a slice of `T` covering the mapped range. If the memory
+/// is not host-coherent, the range is flushed when `flush` is called and,
+/// unless disabled with `set_flush_on_drop`, when this guard is dropped.
+///
+/// Returned by `DeviceMemory::map_guard`.
+pub struct MappedMemory<'m, T> {
+    memory: &'m DeviceMemory,
+    ptr: *mut T,
+    len: usize,
+    offset: u64,
+    size: u64,
+    coherent: bool,
+    flush_on_drop: bool,
+}
+
+impl<'m, T> MappedMemory<'m, T> {
+    /// Flushes the mapped range, rounded outward to `nonCoherentAtomSize`
+    /// boundaries, making host writes available to the device.
+    ///
+    /// Does nothing if the memory is host-coherent.
+    pub fn flush(&self) -> VdResult<()> {
+        if self.coherent { return Ok(()); }
+        unsafe { self.memory.flush(self.offset, self.size) }
+    }
+
+    /// Invalidates the mapped range, rounded outward to `nonCoherentAtomSize`
+    /// boundaries, making device writes (once made available) visible to the
+    /// host.
+    ///
+    /// Does nothing if the memory is host-coherent.
+    pub fn invalidate(&self) -> VdResult<()> {
+        if self.coherent { return Ok(()); }
+        unsafe { self.memory.invalidate(self.offset, self.size) }
+    }
+
+    /// Specifies whether the mapped range is flushed when this guard is
+    /// dropped. Defaults to `true`.
+    ///
+    /// Errors from a flush on drop are ignored. Call `flush` explicitly to
+    /// handle them.
+    pub fn set_flush_on_drop(&mut self, flush_on_drop: bool) {
+        self.flush_on_drop = flush_on_drop;
+    }
+
+    /// Returns the offset of the mapped range in bytes.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns `true` if the mapped memory is host-coherent.
+    pub fn is_coherent(&self) -> bool {
+        self.coherent
+    }
+
+    /// Returns the mapped memory object.
+    pub fn memory(&self) -> &'m DeviceMemory {
+        self.memory
+    }
+}
+
+impl<'m, T> Deref for MappedMemory<'m, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl<'m, T> DerefMut for MappedMemory<'m, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl<'m, T> Drop for MappedMemory<'m, T> {
+    fn drop(&mut self) {
+        if self.flush_on_drop {
+            // Only fails when out of memory, which cannot be reported here:
+            let _ = self.flush();
+        }
+        unsafe { self.memory.unmap_ptr(); }
+        self.memory.inner.guard_mapped.store(false, Ordering::SeqCst);
+    }
+}
+
+
 #[derive(Debug)]
 struct Inner {
     handle: DeviceMemoryHandle,
     device: Device,
     allocation_size: u64,
     memory_type_index: u32,
+    guard_mapped: AtomicBool,
 }
 
 impl Drop for Inner {
@@ -101,6 +203,32 @@ impl DeviceMemory {
             .build(device)
     }
 
+    /// Allocates a single `DeviceMemory` with `properties` large enough to
+    /// hold each of `buffers`, then binds each buffer to it in order at a
+    /// suitably aligned offset. Returns the memory and the offsets.
+    ///
+    /// Returns an `ErrorKind::IncompatibleMemoryTypes` error if no memory
+    /// type is permitted by every buffer.
+    pub fn allocate_and_bind_buffers(device: Device, buffers: &[&Buffer],
+            properties: MemoryPropertyFlags) -> VdResult<(DeviceMemory, Vec<DeviceSize>)> {
+        if buffers.is_empty() {
+            return Err("DeviceMemory::allocate_and_bind_buffers: no buffers specified".into());
+        }
+        let reqs: SmallVec<[MemoryRequirements; 8]> = buffers.iter()
+            .map(|buffer| buffer.memory_requirements().clone())
+            .collect();
+        let memory_type_bits = ::mem_util::common_memory_type_bits(&reqs)?;
+        let (offsets, size) = ::mem_util::packed_offsets(&reqs)
+            .ok_or("DeviceMemory::allocate_and_bind_buffers: combined size overflow")?;
+        let memory_type_index = device.memory_type_index(memory_type_bits, properties)?;
+
+        let memory = DeviceMemory::new(device, size, memory_type_index)?;
+        for (buffer, &offset) in buffers.iter().zip(offsets.iter()) {
+            unsafe { buffer.bind_memory(&memory, offset)?; }
+        }
+        Ok((memory, offsets))
+    }
+
     /// Maps a region of this memory object to a pointer.
     ///
     /// Use `::unmap_ptr` to unmap this memory.
@@ -171,6 +299,131 @@ impl DeviceMemory {
         unsafe { self.unmap_ptr() }
     }
 
+    /// Maps `size_bytes` bytes of this memory, starting at `offset_bytes`,
+    /// and returns a guard which dereferences to a slice of `T` and unmaps
+    /// the memory when dropped.
+    ///
+    /// A `size_bytes` of `WHOLE_SIZE` maps the remainder of the allocation,
+    /// as many whole `T`s as it holds. Returns an error if `T` is zero-sized,
+    /// if the range does not lie within this allocation, if `size_bytes` is
+    /// not a multiple of the size of `T`, if `offset_bytes` is not a multiple
+    /// of the alignment of `T`, or if this memory is already mapped by
+    /// another `MappedMemory`.
+    ///
+    /// ## Safety
+    ///
+    /// This memory must have been created with the
+    /// `MemoryPropertyFlags::HOST_VISIBLE` flag and must not be mapped using
+    /// `map` or `map_to_ptr` while the guard is live. The mapped contents
+    /// must be valid values of `T` before they are read.
+    pub unsafe fn map_guard<'m, T>(&'m self, offset_bytes: u64, size_bytes: u64)
+            -> VdResult<MappedMemory<'m, T>> {
+        let elem_size = mem::size_of::<T>() as u64;
+        if elem_size == 0 {
+            return Err("DeviceMemory::map_guard: cannot map memory as zero-sized values".into());
+        }
+        let allocation_size = self.inner.allocation_size;
+        let size = if size_bytes == WHOLE_SIZE {
+            allocation_size.saturating_sub(offset_bytes)
+        } else {
+            size_bytes
+        };
+        if offset_bytes >= allocation_size || size == 0 || size > allocation_size - offset_bytes {
+            return Err(format!("DeviceMemory::map_guard: the range of {} bytes at offset {} \
+                does not lie within the allocation of {} bytes", size, offset_bytes,
+                allocation_size).into());
+        }
+        if size_bytes != WHOLE_SIZE && size % elem_size != 0 {
+            return Err(format!("DeviceMemory::map_guard: the size ({} bytes) must be a \
+                multiple of the size of the mapped type ({} bytes)", size, elem_size).into());
+        }
+        if offset_bytes % mem::align_of::<T>() as u64 != 0 {
+            return Err(format!("DeviceMemory::map_guard: the offset ({} bytes) must be a \
+                multiple of the alignment of the mapped type ({} bytes)", offset_bytes,
+                mem::align_of::<T>()).into());
+        }
+        if self.inner.guard_mapped.swap(true, Ordering::SeqCst) {
+            return Err("DeviceMemory::map_guard: the memory is already mapped".into());
+        }
+        let ptr = match self.map_to_ptr(offset_bytes, size_bytes, MemoryMapFlags::empty()) {
+            Ok(ptr) => ptr,
+            Err(err) => {
+                self.inner.guard_mapped.store(false, Ordering::SeqCst);
+                return Err(err);
+            },
+        };
+        Ok(MappedMemory {
+            memory: self,
+            ptr,
+            len: (size / elem_size) as usize,
+            offset: offset_bytes,
+            size,
+            coherent: self.is_host_coherent(),
+            flush_on_drop: true,
+        })
+    }
+
+    /// Returns `true` if the memory type of this allocation is
+    /// host-coherent.
+    fn is_host_coherent(&self) -> bool {
+        let mem_props = self.inner.device.physical_device().memory_properties();
+        mem_props.memory_types()[self.inner.memory_type_index as usize].property_flags()
+            .contains(MemoryPropertyFlags::HOST_COHERENT)
+    }
+
+    /// Expands the range `offset..offset + size` to multiples of the
+    /// device's `nonCoherentAtomSize`, returning the `(offset, size)` to use
+    /// when flushing or invalidating it. Ranges reaching the end of this
+    /// allocation become `WHOLE_SIZE`.
+    pub fn non_coherent_round(&self, offset: u64, size: u64) -> (u64, u64) {
+        let atom_size = self.inner.device.limits().non_coherent_atom_size();
+        ::mem_util::non_coherent_range(offset, size, atom_size, Some(self.inner.allocation_size))
+    }
+
+    /// Flushes the range `offset..offset + size` of this mapped memory,
+    /// rounded outward to `nonCoherentAtomSize` boundaries.
+    ///
+    /// Has no effect (other than the call itself) on host-coherent memory.
+    pub unsafe fn flush(&self, offset: u64, size: u64) -> VdResult<()> {
+        self.inner.device.flush_mapped_range(self, offset, size)
+    }
+
+    /// Invalidates the range `offset..offset + size` of this mapped memory,
+    /// rounded outward to `nonCoherentAtomSize` boundaries.
+    pub unsafe fn invalidate(&self, offset: u64, size: u64) -> VdResult<()> {
+        self.inner.device.invalidate_mapped_range(self, offset, size)
+    }
+
+    /// Returns the size of this allocation in bytes.
+    pub fn allocation_size(&self) -> u64 {
+        self.inner.allocation_size
+    }
+
+    /// Returns the index of the memory type this allocation was made from.
+    pub fn memory_type_index(&self) -> u32 {
+        self.inner.memory_type_index
+    }
+
+    /// Returns the number of bytes of this allocation currently committed
+    /// by the implementation.
+    ///
+    /// Only meaningful for memory allocated from a type with the
+    /// `LAZILY_ALLOCATED` property, such as that backing images created by
+    /// `Device::create_transient_attachment_image`. Returns the allocation
+    /// size for any other memory type.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetDeviceMemoryCommitment.html
+    //
+    pub fn commitment(&self) -> u64 {
+        if self.inner.device.physical_device().memory_properties()
+                .memory_types()[self.inner.memory_type_index as usize].property_flags()
+                .contains(MemoryPropertyFlags::LAZILY_ALLOCATED) {
+            unsafe { self.inner.device.get_device_memory_commitment(self) }
+        } else {
+            self.inner.allocation_size
+        }
+    }
+
     /// Returns this object's handle.
     pub fn handle(&self) -> DeviceMemoryHandle {
         self.inner.handle
@@ -191,10 +444,53 @@ unsafe impl<'h> Handle for &'h DeviceMemory {
 }
 
 
+/// Memory requirements of a buffer or image, including dedicated allocation
+/// preferences.
+///
+/// Returned by `Device::get_buffer_memory_requirements_2_khr` and
+/// `Device::get_image_memory_requirements_2_khr`. The dedicated allocation
+/// preferences are always false unless `VK_KHR_dedicated_allocation` is
+/// enabled.
+#[derive(Debug, Clone)]
+pub struct MemoryRequirements2 {
+    memory_requirements: MemoryRequirements,
+    prefers_dedicated: bool,
+    requires_dedicated: bool,
+}
+
+impl MemoryRequirements2 {
+    pub(crate) unsafe fn from_raw(memory_requirements: vks::VkMemoryRequirements,
+            dedicated: &vks::VkMemoryDedicatedRequirementsKHR) -> MemoryRequirements2 {
+        MemoryRequirements2 {
+            memory_requirements: MemoryRequirements::from_raw(memory_requirements),
+            prefers_dedicated: dedicated.prefersDedicatedAllocation != vks::VK_FALSE,
+            requires_dedicated: dedicated.requiresDedicatedAllocation != vks::VK_FALSE,
+        }
+    }
+
+    /// Returns the core memory requirements.
+    pub fn memory_requirements(&self) -> &MemoryRequirements {
+        &self.memory_requirements
+    }
+
+    /// Returns true if the implementation would prefer a dedicated
+    /// allocation for the resource.
+    pub fn prefers_dedicated(&self) -> bool {
+        self.prefers_dedicated
+    }
+
+    /// Returns true if the resource must be bound to a dedicated allocation.
+    pub fn requires_dedicated(&self) -> bool {
+        self.requires_dedicated
+    }
+}
+
+
 /// A builder for `DeviceMemory`.
 #[derive(Debug, Clone)]
 pub struct DeviceMemoryBuilder<'b> {
     allocate_info: MemoryAllocateInfo<'b>,
+    export_handle_types: Option<ExternalMemoryHandleTypeFlagsKhr>,
 }
 
 impl<'b> DeviceMemoryBuilder<'b> {
@@ -202,6 +498,7 @@ impl<'b> DeviceMemoryBuilder<'b> {
     pub fn new() -> DeviceMemoryBuilder<'b> {
         DeviceMemoryBuilder {
             allocate_info: MemoryAllocateInfo::default(),
+            export_handle_types: None,
         }
     }
 
@@ -220,9 +517,92 @@ impl<'b> DeviceMemoryBuilder<'b> {
         self
     }
 
+    /// Chains an `ExportMemoryAllocateInfoKhr`, allowing the memory to be
+    /// exported as any of the handle types it specifies (see
+    /// `Device::get_memory_fd_khr`).
+    pub fn export_memory<'s, 'p>(&'s mut self, export_info: &'p mut ExportMemoryAllocateInfoKhr)
+            -> &'s mut DeviceMemoryBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            export_info.set_next(self.allocate_info.next());
+            self.allocate_info.set_next(export_info.as_raw() as *const _ as *const c_void);
+        }
+        self
+    }
+
+    /// Allows the memory to be exported as any of `handle_types` (see
+    /// `Device::get_memory_fd_khr`).
+    ///
+    /// Unlike `export_memory`, the `ExportMemoryAllocateInfoKhr` is owned by
+    /// this builder and chained when `build` is called.
+    pub fn export_handle_types<'s>(&'s mut self, handle_types: ExternalMemoryHandleTypeFlagsKhr)
+            -> &'s mut DeviceMemoryBuilder<'b> {
+        self.export_handle_types = Some(handle_types);
+        self
+    }
+
+    /// Chains an `ImportMemoryFdInfoKhr`, importing the memory payload from
+    /// a POSIX file descriptor instead of allocating new memory.
+    ///
+    /// The allocation size and memory type index must be compatible with
+    /// the imported payload (see `Device::get_memory_fd_properties_khr`).
+    /// Ownership of the file descriptor is transferred to the implementation
+    /// upon a successful build.
+    pub fn import_memory_fd<'s, 'p>(&'s mut self, import_info: &'p mut ImportMemoryFdInfoKhr)
+            -> &'s mut DeviceMemoryBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            import_info.set_next(self.allocate_info.next());
+            self.allocate_info.set_next(import_info.as_raw() as *const _ as *const c_void);
+        }
+        self
+    }
+
+    /// Chains an `ImportMemoryHostPointerInfoExt`, importing an existing
+    /// host allocation instead of allocating new memory.
+    ///
+    /// Requires `VK_EXT_external_memory_host`. The memory type index must be
+    /// one of those returned by `Device::get_memory_host_pointer_properties_ext`
+    /// and the host allocation must outlive the returned `DeviceMemory`.
+    pub fn import_memory_host_pointer<'s, 'p>(&'s mut self,
+            import_info: &'p mut ImportMemoryHostPointerInfoExt)
+            -> &'s mut DeviceMemoryBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            import_info.set_next(self.allocate_info.next());
+            self.allocate_info.set_next(import_info.as_raw() as *const _ as *const c_void);
+        }
+        self
+    }
+
+    /// Chains a `MemoryDedicatedAllocateInfoKhr`, dedicating the allocation
+    /// to the single image or buffer it specifies.
+    ///
+    /// Requires `VK_KHR_dedicated_allocation`. See
+    /// `MemoryRequirements2::prefers_dedicated`.
+    pub fn dedicated_allocation<'s, 'p>(&'s mut self,
+            dedicated_info: &'p mut MemoryDedicatedAllocateInfoKhr)
+            -> &'s mut DeviceMemoryBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            dedicated_info.set_next(self.allocate_info.next());
+            self.allocate_info.set_next(dedicated_info.as_raw() as *const _ as *const c_void);
+        }
+        self
+    }
+
     /// Creates and returns a new `DeviceMemory`
     pub fn build(&self, device: Device) -> VdResult<DeviceMemory> {
-        let handle = unsafe { device.allocate_memory(&self.allocate_info, None)? };
+        let mut allocate_info = self.allocate_info.clone();
+        let mut export_info = ExportMemoryAllocateInfoKhr::default();
+        if let Some(handle_types) = self.export_handle_types {
+            export_info.set_handle_types(handle_types);
+            unsafe {
+                export_info.set_next(allocate_info.next());
+                allocate_info.set_next(export_info.as_raw() as *const _ as *const c_void);
+            }
+        }
+        let handle = unsafe { device.allocate_memory(&allocate_info, None)? };
 
         Ok(DeviceMemory {
             inner: Arc::new(Inner {
@@ -230,7 +610,193 @@ impl<'b> DeviceMemoryBuilder<'b> {
                 device,
                 allocation_size: self.allocate_info.allocation_size(),
                 memory_type_index: self.allocate_info.memory_type_index(),
+                guard_mapped: AtomicBool::new(false),
             })
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use loader::null::{self, MemoryCall, MemoryRange};
+    use ::{ExportMemoryAllocateInfoKhr, ImportMemoryFdInfoKhr, ExternalMemoryHandleTypeFlagsKhr,
+        MemoryMapFlags, WHOLE_SIZE, CallResult};
+    use super::{DeviceMemory, DeviceMemoryBuilder};
+
+    fn range(memory: &DeviceMemory, offset: u64, size: u64) -> MemoryRange {
+        MemoryRange { memory: memory.handle().to_raw(), offset, size }
+    }
+
+    #[test]
+    fn export_and_import_info_are_chained() {
+        let mut export_info = ExportMemoryAllocateInfoKhr::builder()
+            .handle_types(ExternalMemoryHandleTypeFlagsKhr::OPAQUE_FD)
+            .build();
+        let mut import_info = ImportMemoryFdInfoKhr::builder()
+            .handle_type(ExternalMemoryHandleTypeFlagsKhr::OPAQUE_FD)
+            .fd(7)
+            .build();
+        let export_ptr = export_info.as_raw() as *const vks::VkExportMemoryAllocateInfoKHR;
+        let import_ptr = import_info.as_raw() as *const vks::VkImportMemoryFdInfoKHR;
+
+        let mut builder = DeviceMemoryBuilder::new();
+        builder.allocation_size(1024)
+            .export_memory(&mut export_info)
+            .import_memory_fd(&mut import_info);
+
+        // Each chained structure is inserted at the front of the chain.
+        let allocate_info = builder.allocate_info.as_raw();
+        assert_eq!(allocate_info.allocationSize, 1024);
+        assert_eq!(allocate_info.pNext as *const vks::VkImportMemoryFdInfoKHR, import_ptr);
+        unsafe {
+            let import = &*import_ptr;
+            assert_eq!(import.sType, vks::VK_STRUCTURE_TYPE_IMPORT_MEMORY_FD_INFO_KHR);
+            assert_eq!(import.fd, 7);
+            assert_eq!(import.pNext as *const vks::VkExportMemoryAllocateInfoKHR, export_ptr);
+            let export = &*export_ptr;
+            assert_eq!(export.sType, vks::VK_STRUCTURE_TYPE_EXPORT_MEMORY_ALLOCATE_INFO_KHR);
+            assert_eq!(export.handleTypes, ExternalMemoryHandleTypeFlagsKhr::OPAQUE_FD.bits());
+            assert!(export.pNext.is_null());
+        }
+    }
+
+    #[test]
+    fn flush_and_invalidate_pass_atom_rounded_ranges() {
+        assert_eq!(null::NON_COHERENT_ATOM_SIZE, 64);
+        let memory = DeviceMemory::new(null::device(), 1000, null::NON_COHERENT_MEMORY_TYPE)
+            .unwrap();
+        unsafe {
+            let ptr = memory.map_to_ptr::<u8>(0, WHOLE_SIZE, MemoryMapFlags::empty()).unwrap();
+            // A host write straddling the first atom boundary:
+            for i in 60..72 { *ptr.offset(i) = 0xff; }
+            memory.flush(60, 12).unwrap();
+            // A host write covering exactly one atom:
+            memory.flush(64, 64).unwrap();
+            // A device write to the tail of the allocation:
+            memory.invalidate(900, 100).unwrap();
+            memory.unmap_ptr();
+        }
+        assert_eq!(null::take_memory_calls(), [
+            MemoryCall::Map(range(&memory, 0, WHOLE_SIZE)),
+            MemoryCall::Flush(vec![range(&memory, 0, 128)]),
+            MemoryCall::Flush(vec![range(&memory, 64, 64)]),
+            MemoryCall::Invalidate(vec![range(&memory, 896, WHOLE_SIZE)]),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+        ]);
+    }
+
+    fn memory(allocation_size: u64, memory_type_index: u32) -> DeviceMemory {
+        DeviceMemory::new(null::device(), allocation_size, memory_type_index).unwrap()
+    }
+
+    #[test]
+    fn map_guard_whole_size_maps_whole_values() {
+        // Neither remainder is a multiple of the size of `u64`:
+        let memory = memory(1004, null::COHERENT_MEMORY_TYPE);
+        unsafe {
+            let mut mapped = memory.map_guard::<u64>(0, WHOLE_SIZE).unwrap();
+            assert_eq!(mapped.len(), 125);
+            mapped[124] = 7;
+            drop(mapped);
+            let mapped = memory.map_guard::<u64>(16, WHOLE_SIZE).unwrap();
+            assert_eq!(mapped.len(), 123);
+            assert_eq!(mapped[122], 7);
+        }
+        assert_eq!(null::take_memory_calls(), [
+            MemoryCall::Map(range(&memory, 0, WHOLE_SIZE)),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+            MemoryCall::Map(range(&memory, 16, WHOLE_SIZE)),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+        ]);
+    }
+
+    #[test]
+    fn map_guard_rejects_invalid_ranges() {
+        let memory = memory(1000, null::COHERENT_MEMORY_TYPE);
+        let expect_err = |result: ::VdResult<_>, msg: &str| match result {
+            Ok(_) => panic!("expected an error containing '{}'", msg),
+            Err(err) => assert!(err.to_string().contains(msg), "{}", err),
+        };
+        unsafe {
+            expect_err(memory.map_guard::<()>(0, 8).map(|_| ()), "zero-sized");
+            expect_err(memory.map_guard::<u32>(0, 6).map(|_| ()), "multiple of the size");
+            expect_err(memory.map_guard::<u32>(2, 4).map(|_| ()), "multiple of the alignment");
+            expect_err(memory.map_guard::<u8>(1000, WHOLE_SIZE).map(|_| ()),
+                "does not lie within");
+            expect_err(memory.map_guard::<u8>(992, 16).map(|_| ()), "does not lie within");
+            expect_err(memory.map_guard::<u8>(0, 0).map(|_| ()), "does not lie within");
+        }
+        // Nothing was mapped and the memory can still be mapped:
+        assert!(null::take_memory_calls().is_empty());
+        unsafe { memory.map_guard::<u8>(0, WHOLE_SIZE).unwrap(); }
+    }
+
+    #[test]
+    fn failed_map_guard_can_be_retried() {
+        // Mapping past the first `MEMORY_SIZE` bytes fails:
+        let memory = memory(2 * null::MEMORY_SIZE, null::COHERENT_MEMORY_TYPE);
+        unsafe {
+            let err = memory.map_guard::<u8>(null::MEMORY_SIZE, 16).err().unwrap();
+            assert_eq!(err.call_result(), Some(CallResult::ErrorMemoryMapFailed));
+            memory.map_guard::<u8>(0, 16).unwrap();
+        }
+        assert_eq!(null::take_memory_calls(), [
+            MemoryCall::Map(range(&memory, null::MEMORY_SIZE, 16)),
+            MemoryCall::Map(range(&memory, 0, 16)),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+        ]);
+    }
+
+    #[test]
+    fn only_one_map_guard_at_a_time() {
+        let memory = memory(1000, null::COHERENT_MEMORY_TYPE);
+        unsafe {
+            let mapped = memory.map_guard::<u8>(0, 16).unwrap();
+            let err = memory.map_guard::<u8>(16, 16).err().unwrap();
+            assert!(err.to_string().contains("already mapped"), "{}", err);
+            drop(mapped);
+            memory.map_guard::<u8>(16, 16).unwrap();
+        }
+        assert_eq!(null::take_memory_calls(), [
+            MemoryCall::Map(range(&memory, 0, 16)),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+            MemoryCall::Map(range(&memory, 16, 16)),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+        ]);
+    }
+
+    #[test]
+    fn dropping_map_guard_flushes_non_coherent_memory() {
+        let memory = memory(1000, null::NON_COHERENT_MEMORY_TYPE);
+        unsafe {
+            let mapped = memory.map_guard::<u32>(64, 16).unwrap();
+            assert!(!mapped.is_coherent());
+        }
+        assert_eq!(null::take_memory_calls(), [
+            MemoryCall::Map(range(&memory, 64, 16)),
+            MemoryCall::Flush(vec![range(&memory, 64, 64)]),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+        ]);
+
+        unsafe {
+            let mut mapped = memory.map_guard::<u32>(64, 16).unwrap();
+            mapped.set_flush_on_drop(false);
+        }
+        assert_eq!(null::take_memory_calls(), [
+            MemoryCall::Map(range(&memory, 64, 16)),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+        ]);
+
+        let memory = self::memory(1000, null::COHERENT_MEMORY_TYPE);
+        unsafe {
+            let mapped = memory.map_guard::<u32>(64, 16).unwrap();
+            assert!(mapped.is_coherent());
+            mapped.flush().unwrap();
+        }
+        assert_eq!(null::take_memory_calls(), [
+            MemoryCall::Map(range(&memory, 64, 16)),
+            MemoryCall::Unmap(memory.handle().to_raw()),
+        ]);
+    }
+}
@@ -3,12 +3,14 @@ use num_traits::FromPrimitive;
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PipelineCacheHeaderVersion {
     PipelineCacheHeaderVersionOne = vks::VK_PIPELINE_CACHE_HEADER_VERSION_ONE as i32,
 }
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CallResult {
     Success = vks::VK_SUCCESS as i32,
     NotReady = vks::VK_NOT_READY as i32,
@@ -65,6 +67,7 @@ impl From<i32> for CallResult {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum StructureType {
     ApplicationInfo = vks::VK_STRUCTURE_TYPE_APPLICATION_INFO as i32,
     InstanceCreateInfo = vks::VK_STRUCTURE_TYPE_INSTANCE_CREATE_INFO as i32,
@@ -295,6 +298,7 @@ impl From<u32> for StructureType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SystemAllocationScope {
     Command = vks::VK_SYSTEM_ALLOCATION_SCOPE_COMMAND as i32,
     Object = vks::VK_SYSTEM_ALLOCATION_SCOPE_OBJECT as i32,
@@ -323,6 +327,7 @@ impl From<u32> for SystemAllocationScope {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum InternalAllocationType {
     Executable = vks::VK_INTERNAL_ALLOCATION_TYPE_EXECUTABLE as i32,
 }
@@ -347,6 +352,7 @@ impl From<u32> for InternalAllocationType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Format {
     Undefined = vks::VK_FORMAT_UNDEFINED as i32,
     R4G4UnormPack8 = vks::VK_FORMAT_R4G4_UNORM_PACK8 as i32,
@@ -563,6 +569,7 @@ impl From<u32> for Format {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ImageType {
     Type1d = vks::VK_IMAGE_TYPE_1D as i32,
     Type2d = vks::VK_IMAGE_TYPE_2D as i32,
@@ -589,6 +596,7 @@ impl From<u32> for ImageType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ImageTiling {
     Optimal = vks::VK_IMAGE_TILING_OPTIMAL as i32,
     Linear = vks::VK_IMAGE_TILING_LINEAR as i32,
@@ -614,6 +622,7 @@ impl From<u32> for ImageTiling {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PhysicalDeviceType {
     Other = vks::VK_PHYSICAL_DEVICE_TYPE_OTHER as i32,
     IntegratedGpu = vks::VK_PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU as i32,
@@ -642,6 +651,7 @@ impl From<u32> for PhysicalDeviceType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum QueryType {
     Occlusion = vks::VK_QUERY_TYPE_OCCLUSION as i32,
     PipelineStatistics = vks::VK_QUERY_TYPE_PIPELINE_STATISTICS as i32,
@@ -668,6 +678,7 @@ impl From<u32> for QueryType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SharingMode {
     Exclusive = vks::VK_SHARING_MODE_EXCLUSIVE as i32,
     Concurrent = vks::VK_SHARING_MODE_CONCURRENT as i32,
@@ -693,6 +704,7 @@ impl From<u32> for SharingMode {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ImageLayout {
     Undefined = vks::VK_IMAGE_LAYOUT_UNDEFINED as i32,
     General = vks::VK_IMAGE_LAYOUT_GENERAL as i32,
@@ -727,6 +739,7 @@ impl From<u32> for ImageLayout {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ImageViewType {
     Type1d = vks::VK_IMAGE_VIEW_TYPE_1D as i32,
     Type2d = vks::VK_IMAGE_VIEW_TYPE_2D as i32,
@@ -757,6 +770,7 @@ impl From<u32> for ImageViewType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ComponentSwizzle {
     Identity = vks::VK_COMPONENT_SWIZZLE_IDENTITY as i32,
     Zero = vks::VK_COMPONENT_SWIZZLE_ZERO as i32,
@@ -788,6 +802,7 @@ impl From<u32> for ComponentSwizzle {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum VertexInputRate {
     Vertex = vks::VK_VERTEX_INPUT_RATE_VERTEX as i32,
     Instance = vks::VK_VERTEX_INPUT_RATE_INSTANCE as i32,
@@ -813,6 +828,7 @@ impl From<u32> for VertexInputRate {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PrimitiveTopology {
     PointList = vks::VK_PRIMITIVE_TOPOLOGY_POINT_LIST as i32,
     LineList = vks::VK_PRIMITIVE_TOPOLOGY_LINE_LIST as i32,
@@ -847,6 +863,7 @@ impl From<u32> for PrimitiveTopology {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PolygonMode {
     Fill = vks::VK_POLYGON_MODE_FILL as i32,
     Line = vks::VK_POLYGON_MODE_LINE as i32,
@@ -874,6 +891,7 @@ impl From<u32> for PolygonMode {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FrontFace {
     CounterClockwise = vks::VK_FRONT_FACE_COUNTER_CLOCKWISE as i32,
     Clockwise = vks::VK_FRONT_FACE_CLOCKWISE as i32,
@@ -899,6 +917,7 @@ impl From<u32> for FrontFace {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CompareOp {
     Never = vks::VK_COMPARE_OP_NEVER as i32,
     Less = vks::VK_COMPARE_OP_LESS as i32,
@@ -930,6 +949,7 @@ impl From<u32> for CompareOp {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum StencilOp {
     Keep = vks::VK_STENCIL_OP_KEEP as i32,
     Zero = vks::VK_STENCIL_OP_ZERO as i32,
@@ -961,6 +981,7 @@ impl From<u32> for StencilOp {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum LogicOp {
     Clear = vks::VK_LOGIC_OP_CLEAR as i32,
     And = vks::VK_LOGIC_OP_AND as i32,
@@ -1000,6 +1021,7 @@ impl From<u32> for LogicOp {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BlendFactor {
     Zero = vks::VK_BLEND_FACTOR_ZERO as i32,
     One = vks::VK_BLEND_FACTOR_ONE as i32,
@@ -1042,6 +1064,7 @@ impl From<u32> for BlendFactor {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BlendOp {
     Add = vks::VK_BLEND_OP_ADD as i32,
     Subtract = vks::VK_BLEND_OP_SUBTRACT as i32,
@@ -1116,6 +1139,7 @@ impl From<u32> for BlendOp {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DynamicState {
     Viewport = vks::VK_DYNAMIC_STATE_VIEWPORT as i32,
     Scissor = vks::VK_DYNAMIC_STATE_SCISSOR as i32,
@@ -1150,6 +1174,7 @@ impl From<u32> for DynamicState {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Filter {
     Nearest = vks::VK_FILTER_NEAREST as i32,
     Linear = vks::VK_FILTER_LINEAR as i32,
@@ -1176,6 +1201,7 @@ impl From<u32> for Filter {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SamplerMipmapMode {
     Nearest = vks::VK_SAMPLER_MIPMAP_MODE_NEAREST as i32,
     Linear = vks::VK_SAMPLER_MIPMAP_MODE_LINEAR as i32,
@@ -1201,6 +1227,7 @@ impl From<u32> for SamplerMipmapMode {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SamplerAddressMode {
     Repeat = vks::VK_SAMPLER_ADDRESS_MODE_REPEAT as i32,
     MirroredRepeat = vks::VK_SAMPLER_ADDRESS_MODE_MIRRORED_REPEAT as i32,
@@ -1230,6 +1257,7 @@ impl From<u32> for SamplerAddressMode {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BorderColor {
     FloatTransparentBlack = vks::VK_BORDER_COLOR_FLOAT_TRANSPARENT_BLACK as i32,
     IntTransparentBlack = vks::VK_BORDER_COLOR_INT_TRANSPARENT_BLACK as i32,
@@ -1260,6 +1288,7 @@ impl From<u32> for BorderColor {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DescriptorType {
     Sampler = vks::VK_DESCRIPTOR_TYPE_SAMPLER as i32,
     CombinedImageSampler = vks::VK_DESCRIPTOR_TYPE_COMBINED_IMAGE_SAMPLER as i32,
@@ -1295,6 +1324,7 @@ impl From<u32> for DescriptorType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AttachmentLoadOp {
     Load = vks::VK_ATTACHMENT_LOAD_OP_LOAD as i32,
     Clear = vks::VK_ATTACHMENT_LOAD_OP_CLEAR as i32,
@@ -1322,6 +1352,7 @@ impl From<u32> for AttachmentLoadOp {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AttachmentStoreOp {
     Store = vks::VK_ATTACHMENT_STORE_OP_STORE as i32,
     DontCare = vks::VK_ATTACHMENT_STORE_OP_DONT_CARE as i32,
@@ -1348,6 +1379,7 @@ impl From<u32> for AttachmentStoreOp {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PipelineBindPoint {
     Graphics = vks::VK_PIPELINE_BIND_POINT_GRAPHICS as i32,
     Compute = vks::VK_PIPELINE_BIND_POINT_COMPUTE as i32,
@@ -1374,6 +1406,7 @@ impl From<u32> for PipelineBindPoint {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CommandBufferLevel {
     Primary = vks::VK_COMMAND_BUFFER_LEVEL_PRIMARY as i32,
     Secondary = vks::VK_COMMAND_BUFFER_LEVEL_SECONDARY as i32,
@@ -1400,6 +1433,7 @@ impl From<u32> for CommandBufferLevel {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IndexType {
     Uint16 = vks::VK_INDEX_TYPE_UINT16 as i32,
     Uint32 = vks::VK_INDEX_TYPE_UINT32 as i32,
@@ -1426,6 +1460,7 @@ impl From<u32> for IndexType {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SubpassContents {
     Inline = vks::VK_SUBPASS_CONTENTS_INLINE as i32,
     SecondaryCommandBuffers = vks::VK_SUBPASS_CONTENTS_SECONDARY_COMMAND_BUFFERS as i32,
@@ -1452,6 +1487,7 @@ impl From<u32> for SubpassContents {
 
 #[repr(i32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ObjectType {
     Unknown = vks::VK_OBJECT_TYPE_UNKNOWN as i32,
     Instance = vks::VK_OBJECT_TYPE_INSTANCE as i32,
@@ -1510,51 +1546,111 @@ impl From<u32> for ObjectType {
 }
 
 
-#[repr(i32)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+/// A color space.
+///
+/// Values other than those known to this crate, such as those reported by
+/// newer drivers, are preserved as `Unknown` rather than causing a panic.
+///
+/// Color spaces other than `SrgbNonlinearKhr` require the
+/// `VK_EXT_swapchain_colorspace` instance extension
+/// (`names::EXT_SWAPCHAIN_COLORSPACE`).
+///
+/// ## Example
+///
+/// Selecting an HDR10 surface format when available, falling back to sRGB:
+///
+/// ```rust,ignore
+/// let formats = physical_device.surface_formats_khr(&surface)?;
+/// let format = formats.iter()
+///     .find(|f| f.color_space() == ColorSpaceKhr::Hdr10St2084Ext &&
+///         f.format() == Format::A2B10G10R10UnormPack32)
+///     .or_else(|| formats.iter().find(|f| f.color_space() == ColorSpaceKhr::SrgbNonlinearKhr))
+///     .cloned();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ColorSpaceKhr {
-    SrgbNonlinearKhr = vks::VK_COLOR_SPACE_SRGB_NONLINEAR_KHR as i32,
-    DisplayP3NonlinearExt = vks::VK_COLOR_SPACE_DISPLAY_P3_NONLINEAR_EXT as i32,
-    ExtendedSrgbLinearExt = vks::VK_COLOR_SPACE_EXTENDED_SRGB_LINEAR_EXT as i32,
-    DciP3LinearExt = vks::VK_COLOR_SPACE_DCI_P3_LINEAR_EXT as i32,
-    DciP3NonlinearExt = vks::VK_COLOR_SPACE_DCI_P3_NONLINEAR_EXT as i32,
-    Bt709LinearExt = vks::VK_COLOR_SPACE_BT709_LINEAR_EXT as i32,
-    Bt709NonlinearExt = vks::VK_COLOR_SPACE_BT709_NONLINEAR_EXT as i32,
-    Bt2020LinearExt = vks::VK_COLOR_SPACE_BT2020_LINEAR_EXT as i32,
-    Hdr10St2084Ext = vks::VK_COLOR_SPACE_HDR10_ST2084_EXT as i32,
-    DolbyvisionExt = vks::VK_COLOR_SPACE_DOLBYVISION_EXT as i32,
-    Hdr10HlgExt = vks::VK_COLOR_SPACE_HDR10_HLG_EXT as i32,
-    AdobergbLinearExt = vks::VK_COLOR_SPACE_ADOBERGB_LINEAR_EXT as i32,
-    AdobergbNonlinearExt = vks::VK_COLOR_SPACE_ADOBERGB_NONLINEAR_EXT as i32,
-    PassThroughExt = vks::VK_COLOR_SPACE_PASS_THROUGH_EXT as i32,
-    ExtendedSrgbNonlinearExt = vks::VK_COLOR_SPACE_EXTENDED_SRGB_NONLINEAR_EXT as i32,
-    #[cfg(feature = "unimplemented")]
-    RangeSizeKhr = vks::VK_COLOR_SPACE_RANGE_SIZE_KHR as i32,
-    #[cfg(feature = "unimplemented")]
-    MaxEnumKhr = vks::VK_COLOR_SPACE_MAX_ENUM_KHR as i32,
+    SrgbNonlinearKhr,
+    DisplayP3NonlinearExt,
+    ExtendedSrgbLinearExt,
+    DciP3LinearExt,
+    DciP3NonlinearExt,
+    Bt709LinearExt,
+    Bt709NonlinearExt,
+    Bt2020LinearExt,
+    Hdr10St2084Ext,
+    DolbyvisionExt,
+    Hdr10HlgExt,
+    AdobergbLinearExt,
+    AdobergbNonlinearExt,
+    PassThroughExt,
+    ExtendedSrgbNonlinearExt,
+    /// A value not known to this crate.
+    Unknown(i32),
 }
 
 impl From<ColorSpaceKhr> for i32 {
     fn from(f: ColorSpaceKhr) -> i32 {
-        f as i32
+        match f {
+            ColorSpaceKhr::SrgbNonlinearKhr => vks::VK_COLOR_SPACE_SRGB_NONLINEAR_KHR as i32,
+            ColorSpaceKhr::DisplayP3NonlinearExt => vks::VK_COLOR_SPACE_DISPLAY_P3_NONLINEAR_EXT as i32,
+            ColorSpaceKhr::ExtendedSrgbLinearExt => vks::VK_COLOR_SPACE_EXTENDED_SRGB_LINEAR_EXT as i32,
+            ColorSpaceKhr::DciP3LinearExt => vks::VK_COLOR_SPACE_DCI_P3_LINEAR_EXT as i32,
+            ColorSpaceKhr::DciP3NonlinearExt => vks::VK_COLOR_SPACE_DCI_P3_NONLINEAR_EXT as i32,
+            ColorSpaceKhr::Bt709LinearExt => vks::VK_COLOR_SPACE_BT709_LINEAR_EXT as i32,
+            ColorSpaceKhr::Bt709NonlinearExt => vks::VK_COLOR_SPACE_BT709_NONLINEAR_EXT as i32,
+            ColorSpaceKhr::Bt2020LinearExt => vks::VK_COLOR_SPACE_BT2020_LINEAR_EXT as i32,
+            ColorSpaceKhr::Hdr10St2084Ext => vks::VK_COLOR_SPACE_HDR10_ST2084_EXT as i32,
+            ColorSpaceKhr::DolbyvisionExt => vks::VK_COLOR_SPACE_DOLBYVISION_EXT as i32,
+            ColorSpaceKhr::Hdr10HlgExt => vks::VK_COLOR_SPACE_HDR10_HLG_EXT as i32,
+            ColorSpaceKhr::AdobergbLinearExt => vks::VK_COLOR_SPACE_ADOBERGB_LINEAR_EXT as i32,
+            ColorSpaceKhr::AdobergbNonlinearExt => vks::VK_COLOR_SPACE_ADOBERGB_NONLINEAR_EXT as i32,
+            ColorSpaceKhr::PassThroughExt => vks::VK_COLOR_SPACE_PASS_THROUGH_EXT as i32,
+            ColorSpaceKhr::ExtendedSrgbNonlinearExt => vks::VK_COLOR_SPACE_EXTENDED_SRGB_NONLINEAR_EXT as i32,
+            ColorSpaceKhr::Unknown(v) => v,
+        }
     }
 }
 
 impl From<ColorSpaceKhr> for u32 {
     fn from(f: ColorSpaceKhr) -> u32 {
-        f as u32
+        i32::from(f) as u32
+    }
+}
+
+impl From<i32> for ColorSpaceKhr {
+    fn from(f: i32) -> ColorSpaceKhr {
+        match f as u32 {
+            vks::VK_COLOR_SPACE_SRGB_NONLINEAR_KHR => ColorSpaceKhr::SrgbNonlinearKhr,
+            vks::VK_COLOR_SPACE_DISPLAY_P3_NONLINEAR_EXT => ColorSpaceKhr::DisplayP3NonlinearExt,
+            vks::VK_COLOR_SPACE_EXTENDED_SRGB_LINEAR_EXT => ColorSpaceKhr::ExtendedSrgbLinearExt,
+            vks::VK_COLOR_SPACE_DCI_P3_LINEAR_EXT => ColorSpaceKhr::DciP3LinearExt,
+            vks::VK_COLOR_SPACE_DCI_P3_NONLINEAR_EXT => ColorSpaceKhr::DciP3NonlinearExt,
+            vks::VK_COLOR_SPACE_BT709_LINEAR_EXT => ColorSpaceKhr::Bt709LinearExt,
+            vks::VK_COLOR_SPACE_BT709_NONLINEAR_EXT => ColorSpaceKhr::Bt709NonlinearExt,
+            vks::VK_COLOR_SPACE_BT2020_LINEAR_EXT => ColorSpaceKhr::Bt2020LinearExt,
+            vks::VK_COLOR_SPACE_HDR10_ST2084_EXT => ColorSpaceKhr::Hdr10St2084Ext,
+            vks::VK_COLOR_SPACE_DOLBYVISION_EXT => ColorSpaceKhr::DolbyvisionExt,
+            vks::VK_COLOR_SPACE_HDR10_HLG_EXT => ColorSpaceKhr::Hdr10HlgExt,
+            vks::VK_COLOR_SPACE_ADOBERGB_LINEAR_EXT => ColorSpaceKhr::AdobergbLinearExt,
+            vks::VK_COLOR_SPACE_ADOBERGB_NONLINEAR_EXT => ColorSpaceKhr::AdobergbNonlinearExt,
+            vks::VK_COLOR_SPACE_PASS_THROUGH_EXT => ColorSpaceKhr::PassThroughExt,
+            vks::VK_COLOR_SPACE_EXTENDED_SRGB_NONLINEAR_EXT => ColorSpaceKhr::ExtendedSrgbNonlinearExt,
+            _ => ColorSpaceKhr::Unknown(f),
+        }
     }
 }
 
 impl From<u32> for ColorSpaceKhr {
     fn from(f: u32) -> ColorSpaceKhr {
-        ColorSpaceKhr::from_u32(f).unwrap()
+        ColorSpaceKhr::from(f as i32)
     }
 }
 
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PresentModeKhr {
     ImmediateKhr = vks::VK_PRESENT_MODE_IMMEDIATE_KHR as i32,
     MailboxKhr = vks::VK_PRESENT_MODE_MAILBOX_KHR as i32,
@@ -1589,6 +1685,7 @@ impl From<u32> for PresentModeKhr {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DescriptorUpdateTemplateTypeKhr {
     DescriptorSetKhr = vks::VK_DESCRIPTOR_UPDATE_TEMPLATE_TYPE_DESCRIPTOR_SET_KHR as i32,
     PushDescriptorsKhr = vks::VK_DESCRIPTOR_UPDATE_TEMPLATE_TYPE_PUSH_DESCRIPTORS_KHR as i32,
@@ -1621,6 +1718,7 @@ pub type DescriptorUpdateTemplateCreateFlagsKHR = i32;
 #[cfg(feature = "unimplemented")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PointClippingBehaviorKhr {
     AllClipPlanesKhr = vks::VK_POINT_CLIPPING_BEHAVIOR_ALL_CLIP_PLANES_KHR as i32,
     UserClipPlanesOnlyKhr = vks::VK_POINT_CLIPPING_BEHAVIOR_USER_CLIP_PLANES_ONLY_KHR as i32,
@@ -1653,6 +1751,7 @@ impl From<u32> for PointClippingBehaviorKhr {
 #[cfg(feature = "unimplemented")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TessellationDomainOriginKhr {
     UpperLeftKhr = vks::VK_TESSELLATION_DOMAIN_ORIGIN_UPPER_LEFT_KHR as i32,
     LowerLeftKhr = vks::VK_TESSELLATION_DOMAIN_ORIGIN_LOWER_LEFT_KHR as i32,
@@ -1685,6 +1784,7 @@ impl From<u32> for TessellationDomainOriginKhr {
 #[cfg(feature = "unimplemented")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SamplerYcbcrModelConversionKhr {
     RgbIdentityKhr = vks::VK_SAMPLER_YCBCR_MODEL_CONVERSION_RGB_IDENTITY_KHR as i32,
     YcbcrIdentityKhr = vks::VK_SAMPLER_YCBCR_MODEL_CONVERSION_YCBCR_IDENTITY_KHR as i32,
@@ -1720,6 +1820,7 @@ impl From<u32> for SamplerYcbcrModelConversionKhr {
 #[cfg(feature = "unimplemented")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SamplerYcbcrRangeKhr {
     ItuFullKhr = vks::VK_SAMPLER_YCBCR_RANGE_ITU_FULL_KHR as i32,
     ItuNarrowKhr = vks::VK_SAMPLER_YCBCR_RANGE_ITU_NARROW_KHR as i32,
@@ -1752,6 +1853,7 @@ impl From<u32> for SamplerYcbcrRangeKhr {
 #[cfg(feature = "unimplemented")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ChromaLocationKhr {
     CositedEvenKhr = vks::VK_CHROMA_LOCATION_COSITED_EVEN_KHR as i32,
     MidpointKhr = vks::VK_CHROMA_LOCATION_MIDPOINT_KHR as i32,
@@ -1783,6 +1885,7 @@ impl From<u32> for ChromaLocationKhr {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DebugReportObjectTypeExt {
     UnknownExt = vks::VK_DEBUG_REPORT_OBJECT_TYPE_UNKNOWN_EXT as i32,
     InstanceExt = vks::VK_DEBUG_REPORT_OBJECT_TYPE_INSTANCE_EXT as i32,
@@ -1852,6 +1955,7 @@ impl From<u32> for DebugReportObjectTypeExt {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum RasterizationOrderAmd {
     StrictAmd = vks::VK_RASTERIZATION_ORDER_STRICT_AMD as i32,
     RelaxedAmd = vks::VK_RASTERIZATION_ORDER_RELAXED_AMD as i32,
@@ -1882,6 +1986,7 @@ impl From<u32> for RasterizationOrderAmd {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ValidationCheckExt {
     AllExt = vks::VK_VALIDATION_CHECK_ALL_EXT as i32,
     ShadersExt = vks::VK_VALIDATION_CHECK_SHADERS_EXT as i32,
@@ -1913,6 +2018,7 @@ impl From<u32> for ValidationCheckExt {
 #[cfg(feature = "unimplemented")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IndirectCommandsTokenTypeNvx {
     PipelineNvx = vks::VK_INDIRECT_COMMANDS_TOKEN_TYPE_PIPELINE_NVX as i32,
     DescriptorSetNvx = vks::VK_INDIRECT_COMMANDS_TOKEN_TYPE_DESCRIPTOR_SET_NVX as i32,
@@ -1951,6 +2057,7 @@ impl From<u32> for IndirectCommandsTokenTypeNvx {
 #[cfg(feature = "experimental")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ObjectEntryTypeNvx {
     DescriptorSetNvx = vks::VK_OBJECT_ENTRY_TYPE_DESCRIPTOR_SET_NVX as i32,
     PipelineNvx = vks::VK_OBJECT_ENTRY_TYPE_PIPELINE_NVX as i32,
@@ -1987,6 +2094,7 @@ impl From<u32> for ObjectEntryTypeNvx {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DisplayPowerStateExt {
     OffExt = vks::VK_DISPLAY_POWER_STATE_OFF_EXT as i32,
     SuspendExt = vks::VK_DISPLAY_POWER_STATE_SUSPEND_EXT as i32,
@@ -2018,6 +2126,7 @@ impl From<u32> for DisplayPowerStateExt {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DeviceEventTypeExt {
     DisplayHotplugExt = vks::VK_DEVICE_EVENT_TYPE_DISPLAY_HOTPLUG_EXT as i32,
     #[cfg(feature = "unimplemented")]
@@ -2047,6 +2156,7 @@ impl From<u32> for DeviceEventTypeExt {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DisplayEventTypeExt {
     FirstPixelOutExt = vks::VK_DISPLAY_EVENT_TYPE_FIRST_PIXEL_OUT_EXT as i32,
     #[cfg(feature = "unimplemented")]
@@ -2076,6 +2186,7 @@ impl From<u32> for DisplayEventTypeExt {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ViewportCoordinateSwizzleNv {
     PositiveXNv = vks::VK_VIEWPORT_COORDINATE_SWIZZLE_POSITIVE_X_NV as i32,
     NegativeXNv = vks::VK_VIEWPORT_COORDINATE_SWIZZLE_NEGATIVE_X_NV as i32,
@@ -2112,6 +2223,7 @@ impl From<u32> for ViewportCoordinateSwizzleNv {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DiscardRectangleModeExt {
     InclusiveExt = vks::VK_DISCARD_RECTANGLE_MODE_INCLUSIVE_EXT as i32,
     ExclusiveExt = vks::VK_DISCARD_RECTANGLE_MODE_EXCLUSIVE_EXT as i32,
@@ -2142,6 +2254,7 @@ impl From<u32> for DiscardRectangleModeExt {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SamplerReductionModeExt {
     WeightedAverageExt = vks::VK_SAMPLER_REDUCTION_MODE_WEIGHTED_AVERAGE_EXT as i32,
     MinExt = vks::VK_SAMPLER_REDUCTION_MODE_MIN_EXT as i32,
@@ -2173,6 +2286,7 @@ impl From<u32> for SamplerReductionModeExt {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BlendOverlapExt {
     UncorrelatedExt = vks::VK_BLEND_OVERLAP_UNCORRELATED_EXT as i32,
     DisjointExt = vks::VK_BLEND_OVERLAP_DISJOINT_EXT as i32,
@@ -2204,6 +2318,7 @@ impl From<u32> for BlendOverlapExt {
 
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CoverageModulationModeNv {
     NoneNv = vks::VK_COVERAGE_MODULATION_MODE_NONE_NV as i32,
     RgbNv = vks::VK_COVERAGE_MODULATION_MODE_RGB_NV as i32,
@@ -2237,6 +2352,7 @@ impl From<u32> for CoverageModulationModeNv {
 #[cfg(feature = "unimplemented")]
 #[repr(i32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Primitive, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ValidationCacheHeaderVersionExt {
     OneExt = vks::VK_VALIDATION_CACHE_HEADER_VERSION_ONE_EXT as i32,
     MaxEnumExt = vks::VK_VALIDATION_CACHE_HEADER_VERSION_MAX_ENUM_EXT as i32,
@@ -2262,3 +2378,83 @@ impl From<u32> for ValidationCacheHeaderVersionExt {
         ValidationCacheHeaderVersionExt::from_u32(f).unwrap()
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use ::SurfaceFormatKhr;
+    use super::{ImageLayout, ColorSpaceKhr};
+
+    /// Returns the raw value `layout` must convert to. Exhaustive, so that a
+    /// new variant fails to compile until it is covered here.
+    fn expected_raw(layout: ImageLayout) -> vks::VkImageLayout {
+        match layout {
+            ImageLayout::Undefined => vks::VK_IMAGE_LAYOUT_UNDEFINED,
+            ImageLayout::General => vks::VK_IMAGE_LAYOUT_GENERAL,
+            ImageLayout::ColorAttachmentOptimal => vks::VK_IMAGE_LAYOUT_COLOR_ATTACHMENT_OPTIMAL,
+            ImageLayout::DepthStencilAttachmentOptimal =>
+                vks::VK_IMAGE_LAYOUT_DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+            ImageLayout::DepthStencilReadOnlyOptimal =>
+                vks::VK_IMAGE_LAYOUT_DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+            ImageLayout::ShaderReadOnlyOptimal => vks::VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL,
+            ImageLayout::TransferSrcOptimal => vks::VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL,
+            ImageLayout::TransferDstOptimal => vks::VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
+            ImageLayout::Preinitialized => vks::VK_IMAGE_LAYOUT_PREINITIALIZED,
+            ImageLayout::PresentSrcKhr => vks::VK_IMAGE_LAYOUT_PRESENT_SRC_KHR,
+            ImageLayout::SharedPresentKhr => vks::VK_IMAGE_LAYOUT_SHARED_PRESENT_KHR,
+        }
+    }
+
+    #[test]
+    fn image_layout_round_trips_every_variant() {
+        let layouts = [
+            ImageLayout::Undefined,
+            ImageLayout::General,
+            ImageLayout::ColorAttachmentOptimal,
+            ImageLayout::DepthStencilAttachmentOptimal,
+            ImageLayout::DepthStencilReadOnlyOptimal,
+            ImageLayout::ShaderReadOnlyOptimal,
+            ImageLayout::TransferSrcOptimal,
+            ImageLayout::TransferDstOptimal,
+            ImageLayout::Preinitialized,
+            ImageLayout::PresentSrcKhr,
+            ImageLayout::SharedPresentKhr,
+        ];
+        for &layout in layouts.iter() {
+            let raw: vks::VkImageLayout = layout.into();
+            assert_eq!(raw, expected_raw(layout), "{:?}", layout);
+            assert_eq!(ImageLayout::from(raw), layout);
+        }
+        // The extension layouts use large values well outside the core range.
+        assert_eq!(u32::from(ImageLayout::PresentSrcKhr), 1000001002);
+        assert_eq!(u32::from(ImageLayout::SharedPresentKhr), 1000111000);
+    }
+
+    #[test]
+    fn color_space_round_trips_known_and_unknown_values() {
+        let known = [
+            (ColorSpaceKhr::SrgbNonlinearKhr, vks::VK_COLOR_SPACE_SRGB_NONLINEAR_KHR),
+            (ColorSpaceKhr::Hdr10St2084Ext, vks::VK_COLOR_SPACE_HDR10_ST2084_EXT),
+            (ColorSpaceKhr::ExtendedSrgbNonlinearExt,
+                vks::VK_COLOR_SPACE_EXTENDED_SRGB_NONLINEAR_EXT),
+        ];
+        for &(color_space, raw) in known.iter() {
+            assert_eq!(u32::from(color_space), raw as u32);
+            assert_eq!(ColorSpaceKhr::from(raw as u32), color_space);
+        }
+
+        // Values from newer drivers are preserved rather than rejected.
+        for &raw in [1000104015i32, 7, -1].iter() {
+            let color_space = ColorSpaceKhr::from(raw);
+            assert_eq!(color_space, ColorSpaceKhr::Unknown(raw));
+            assert_eq!(i32::from(color_space), raw);
+            assert_eq!(ColorSpaceKhr::from(u32::from(color_space)), color_space);
+        }
+
+        let mut raw = vks::VkSurfaceFormatKHR::default();
+        raw.colorSpace = 1000104015i32 as _;
+        let format = unsafe { SurfaceFormatKhr::from_raw(raw) };
+        assert_eq!(format.color_space(), ColorSpaceKhr::Unknown(1000104015));
+    }
+}
@@ -1,18 +1,29 @@
 use std::error::Error as StdError;
 use std::result::Result as StdResult;
-use ::CallResult;
+use num_traits::FromPrimitive;
+use ::{CallResult, PipelineCacheError};
 
 
 pub enum ErrorKind {
     Void,
     ApiCall(CallResult, &'static str),
+    UnknownApiResult { code: i32, fn_name: &'static str },
+    PipelineCreation { index: Option<usize>, code: i32, fn_name: &'static str },
     String(String),
     Nul(::std::ffi::NulError),
     Io(::std::io::Error),
     FromUtf8Error(::std::string::FromUtf8Error),
     UnspecifiedDimensions,
     IntoStringError(::std::ffi::IntoStringError),
-    FromBytesWithNulError(::std::ffi::FromBytesWithNulError)
+    FromBytesWithNulError(::std::ffi::FromBytesWithNulError),
+    PipelineCache(PipelineCacheError),
+    ShaderCompilation { error_count: u32, message: String },
+    ExtensionNotEnabled { name: &'static ::std::ffi::CStr, fn_name: &'static str },
+    FeatureNotEnabled { name: &'static str, fn_name: &'static str },
+    SwapchainImage { fn_name: &'static str },
+    IncompatibleMemoryTypes { memory_type_bits: Vec<u32> },
+    QueueNotRequested { family_index: u32, queue_index: u32, requested: Vec<(u32, u32)> },
+    NoPhysicalDevices,
 }
 
 
@@ -28,6 +39,45 @@ impl self::Error {
         Error { kind: ErrorKind::UnspecifiedDimensions, cause: None }
     }
 
+    /// Returns an `Error` with the `ApiCall` kind variant, reporting that the
+    /// API function `fn_name` returned `result`.
+    pub fn api_call(result: CallResult, fn_name: &'static str) -> Error {
+        Error { kind: ErrorKind::ApiCall(result, fn_name), cause: None }
+    }
+
+    /// Returns the result returned by the failing API function, if this
+    /// error was caused by one and the result is recognized.
+    pub fn call_result(&self) -> Option<CallResult> {
+        match self.kind {
+            ErrorKind::ApiCall(res, _) => Some(res),
+            ErrorKind::PipelineCreation { code, .. } => CallResult::from_i32(code),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw result code returned by the failing API function, if
+    /// this error was caused by one.
+    pub fn result_code(&self) -> Option<i32> {
+        match self.kind {
+            ErrorKind::ApiCall(res, _) => Some(res as i32),
+            ErrorKind::UnknownApiResult { code, .. } => Some(code),
+            ErrorKind::PipelineCreation { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the failing API function (such as
+    /// `vkCreateImage`), if this error was caused by one and it is known.
+    pub fn fn_name(&self) -> Option<&'static str> {
+        match self.kind {
+            ErrorKind::ApiCall(_, "") => None,
+            ErrorKind::ApiCall(_, fn_name) => Some(fn_name),
+            ErrorKind::UnknownApiResult { fn_name, .. } => Some(fn_name),
+            ErrorKind::PipelineCreation { fn_name, .. } => Some(fn_name),
+            _ => None,
+        }
+    }
+
     /// Creates a new error with this error as its cause.
     pub fn chain<E: Into<Error>>(self, err: E) -> Self {
         // let desc = format!("{}: {}", pre, self.description());
@@ -55,12 +105,43 @@ impl self::Error {
     fn write_msg(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         match self.kind {
             ErrorKind::Void => write!(f, "Error"),
-            ErrorKind::ApiCall(ref res, ref fn_name) => write!(f, "Vulkan API ({}) call result: {:?}", fn_name, res),
+            ErrorKind::ApiCall(ref res, "") => write!(f, "Vulkan API call returned {}", res),
+            ErrorKind::ApiCall(ref res, ref fn_name) => write!(f, "{} returned {}", fn_name, res),
+            ErrorKind::UnknownApiResult { code, fn_name } => write!(f, "{} returned an \
+                unrecognized result code: {}", fn_name, code),
+            ErrorKind::PipelineCreation { index, code, fn_name } => {
+                match CallResult::from_i32(code) {
+                    Some(res) => write!(f, "{} returned {}", fn_name, res)?,
+                    None => write!(f, "{} returned an unrecognized result code: {}", fn_name,
+                        code)?,
+                }
+                match index {
+                    Some(idx) => write!(f, " (first failed create info: {})", idx),
+                    None => Ok(()),
+                }
+            },
             ErrorKind::Nul(ref err) => write!(f, "{}", err.description()),
             ErrorKind::Io(ref err) => write!(f, "{}", err.description()),
             ErrorKind::FromUtf8Error(ref err) => write!(f, "{}", err.description()),
             ErrorKind::IntoStringError(ref err) => write!(f, "{}", err.description()),
             ErrorKind::FromBytesWithNulError(ref err) => write!(f, "{}", err.description()),
+            ErrorKind::PipelineCache(ref err) => write!(f, "{}", err),
+            ErrorKind::ShaderCompilation { error_count, ref message } => write!(f, "Shader \
+                compilation failed with {} error(s): {}", error_count, message),
+            ErrorKind::ExtensionNotEnabled { name, fn_name } => write!(f, "Unable to call {}: \
+                the '{}' extension is not enabled", fn_name, name.to_string_lossy()),
+            ErrorKind::FeatureNotEnabled { name, fn_name } => write!(f, "Unable to call {}: \
+                the '{}' device feature is not enabled", fn_name, name),
+            ErrorKind::SwapchainImage { fn_name } => write!(f, "Unable to call {}: the image \
+                is owned by a swapchain", fn_name),
+            ErrorKind::IncompatibleMemoryTypes { ref memory_type_bits } => write!(f, "No memory \
+                type is compatible with all resources (memory type bits: {:?})",
+                memory_type_bits),
+            ErrorKind::QueueNotRequested { family_index, queue_index, ref requested } => write!(f,
+                "Queue {} of family {} was not requested when the device was created \
+                (requested (family index, queue count): {:?})", queue_index, family_index,
+                requested),
+            ErrorKind::NoPhysicalDevices => write!(f, "No physical devices are available"),
             ErrorKind::String(ref desc) => write!(f, "{}", desc),
             ErrorKind::UnspecifiedDimensions => write!(f, "Cannot convert to a valid set of \
                 dimensions. Please specify some dimensions."),
@@ -97,11 +178,21 @@ impl StdError for self::Error {
         match self.kind {
             ErrorKind::Void => "Vulkan error",
             ErrorKind::ApiCall(ref _res, ..) => "Vulkan API call error",
+            ErrorKind::UnknownApiResult { .. } => "Unrecognized Vulkan API result code",
+            ErrorKind::PipelineCreation { .. } => "Pipeline creation error",
             ErrorKind::Nul(ref err) => err.description(),
             ErrorKind::Io(ref err) => err.description(),
             ErrorKind::FromUtf8Error(ref err) => err.description(),
             ErrorKind::IntoStringError(ref err) => err.description(),
             ErrorKind::FromBytesWithNulError(ref err) => err.description(),
+            ErrorKind::PipelineCache(..) => "Invalid pipeline cache data",
+            ErrorKind::ShaderCompilation { .. } => "Shader compilation error",
+            ErrorKind::ExtensionNotEnabled { .. } => "Extension not enabled",
+            ErrorKind::FeatureNotEnabled { .. } => "Device feature not enabled",
+            ErrorKind::SwapchainImage { .. } => "Invalid operation on a swapchain image",
+            ErrorKind::IncompatibleMemoryTypes { .. } => "Incompatible memory types",
+            ErrorKind::QueueNotRequested { .. } => "Queue not requested at device creation",
+            ErrorKind::NoPhysicalDevices => "No physical devices",
             ErrorKind::String(ref desc) => desc.as_str(),
             ErrorKind::UnspecifiedDimensions => "Cannot convert to a valid set of dimensions. \
                 Please specify some dimensions.",
@@ -171,6 +262,12 @@ impl From<::std::ffi::FromBytesWithNulError> for self::Error {
     }
 }
 
+impl From<PipelineCacheError> for self::Error {
+    fn from(err: PipelineCacheError) -> Self {
+        Error { kind: self::ErrorKind::PipelineCache(err), cause: None }
+    }
+}
+
 unsafe impl ::std::marker::Send for self::Error {}
 
 
@@ -202,13 +299,212 @@ impl<T> ChainErr<T, Error> for self::Result<T> {
 }
 
 
-/// Returns an error if `result` is less than zero, otherwise returns the `ok_val`.
+impl CallResult {
+    /// Returns the canonical Vulkan name of this result, such as
+    /// `VK_ERROR_DEVICE_LOST`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            CallResult::Success => "VK_SUCCESS",
+            CallResult::NotReady => "VK_NOT_READY",
+            CallResult::Timeout => "VK_TIMEOUT",
+            CallResult::EventSet => "VK_EVENT_SET",
+            CallResult::EventReset => "VK_EVENT_RESET",
+            CallResult::Incomplete => "VK_INCOMPLETE",
+            CallResult::ErrorOutOfHostMemory => "VK_ERROR_OUT_OF_HOST_MEMORY",
+            CallResult::ErrorOutOfDeviceMemory => "VK_ERROR_OUT_OF_DEVICE_MEMORY",
+            CallResult::ErrorInitializationFailed => "VK_ERROR_INITIALIZATION_FAILED",
+            CallResult::ErrorDeviceLost => "VK_ERROR_DEVICE_LOST",
+            CallResult::ErrorMemoryMapFailed => "VK_ERROR_MEMORY_MAP_FAILED",
+            CallResult::ErrorLayerNotPresent => "VK_ERROR_LAYER_NOT_PRESENT",
+            CallResult::ErrorExtensionNotPresent => "VK_ERROR_EXTENSION_NOT_PRESENT",
+            CallResult::ErrorFeatureNotPresent => "VK_ERROR_FEATURE_NOT_PRESENT",
+            CallResult::ErrorIncompatibleDriver => "VK_ERROR_INCOMPATIBLE_DRIVER",
+            CallResult::ErrorTooManyObjects => "VK_ERROR_TOO_MANY_OBJECTS",
+            CallResult::ErrorFormatNotSupported => "VK_ERROR_FORMAT_NOT_SUPPORTED",
+            CallResult::ErrorFragmentedPool => "VK_ERROR_FRAGMENTED_POOL",
+            CallResult::ErrorSurfaceLostKhr => "VK_ERROR_SURFACE_LOST_KHR",
+            CallResult::ErrorNativeWindowInUseKhr => "VK_ERROR_NATIVE_WINDOW_IN_USE_KHR",
+            CallResult::SuboptimalKhr => "VK_SUBOPTIMAL_KHR",
+            CallResult::ErrorOutOfDateKhr => "VK_ERROR_OUT_OF_DATE_KHR",
+            CallResult::ErrorIncompatibleDisplayKhr => "VK_ERROR_INCOMPATIBLE_DISPLAY_KHR",
+            CallResult::ErrorValidationFailedExt => "VK_ERROR_VALIDATION_FAILED_EXT",
+            CallResult::ErrorInvalidShaderNv => "VK_ERROR_INVALID_SHADER_NV",
+            CallResult::ErrorOutOfPoolMemoryKhr => "VK_ERROR_OUT_OF_POOL_MEMORY_KHR",
+            CallResult::ErrorInvalidExternalHandleKhr => "VK_ERROR_INVALID_EXTERNAL_HANDLE_KHR",
+        }
+    }
+
+    /// Returns `true` if this is a success code, including non-zero success
+    /// codes such as `VK_SUBOPTIMAL_KHR`, `VK_NOT_READY`, `VK_TIMEOUT`, and
+    /// `VK_INCOMPLETE`.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        *self as i32 >= 0
+    }
+
+    /// Returns `true` if this is an error code.
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        !self.is_success()
+    }
+
+    /// Returns this result unchanged if it is a success code, otherwise an
+    /// `ErrorKind::ApiCall` error containing it.
+    #[inline]
+    pub fn into_result(self) -> self::Result<CallResult> {
+        self.into_call_result("")
+    }
+
+    /// Returns this result unchanged if it is a success code, otherwise an
+    /// `ErrorKind::ApiCall` error containing it and `fn_name`.
+    #[inline]
+    pub(crate) fn into_call_result(self, fn_name: &'static str) -> self::Result<CallResult> {
+        if self.is_success() {
+            Ok(self)
+        } else {
+            Err(Error::api_call(self, fn_name))
+        }
+    }
+}
+
+impl ::std::fmt::Display for CallResult {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl StdError for CallResult {
+    fn description(&self) -> &str {
+        self.name()
+    }
+}
+
+
+/// Returns an error if `result` is an error code, otherwise returns the
+/// `ok_val`.
+///
+/// Unrecognized success codes are treated as success and unrecognized error
+/// codes produce an `UnknownApiResult` error.
 pub fn check<T>(result: i32, fn_name: &'static str, ok_val: T) -> self::Result<T> {
-    if result >= 0 {
-        Ok(ok_val)
-    } else {
-        Err(Error { kind: self::ErrorKind::ApiCall(result.into(), fn_name), cause: None })
+    match CallResult::from_i32(result) {
+        Some(res) => res.into_call_result(fn_name).map(|_| ok_val),
+        None if result >= 0 => Ok(ok_val),
+        None => Err(Error { kind: ErrorKind::UnknownApiResult { code: result, fn_name },
+            cause: None }),
     }
 }
 
+/// Returns an error naming `fn_name` if `result` is an error code.
+#[inline]
+pub fn check_named(result: i32, fn_name: &'static str) -> self::Result<()> {
+    check(result, fn_name, ())
+}
+
+/// Returns `result` as a `CallResult` if it is a success code, for callers
+/// which distinguish between success codes.
+///
+/// Error codes produce an `ApiCall` error and codes unknown to this crate,
+/// whose meaning cannot be distinguished, an `UnknownApiResult` error.
+#[inline]
+pub(crate) fn classify(result: i32, fn_name: &'static str) -> self::Result<CallResult> {
+    match CallResult::from_i32(result) {
+        Some(res) => res.into_call_result(fn_name),
+        None => Err(Error { kind: ErrorKind::UnknownApiResult { code: result, fn_name },
+            cause: None }),
+    }
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use num_traits::FromPrimitive;
+    use ::CallResult;
+    use super::{check, Error, ErrorKind};
 
+    #[test]
+    fn success_codes_display_their_names() {
+        let res = CallResult::SuboptimalKhr;
+        assert_eq!(res.name(), "VK_SUBOPTIMAL_KHR");
+        assert_eq!(res.to_string(), "VK_SUBOPTIMAL_KHR");
+        assert!(res.is_success() && !res.is_error());
+        assert_eq!(res.into_result().unwrap(), CallResult::SuboptimalKhr);
+    }
+
+    #[test]
+    fn error_codes_display_their_names() {
+        let res = CallResult::ErrorDeviceLost;
+        assert_eq!(res.name(), "VK_ERROR_DEVICE_LOST");
+        assert_eq!(res.to_string(), "VK_ERROR_DEVICE_LOST");
+        assert!(res.is_error() && !res.is_success());
+        let err = res.into_result().unwrap_err();
+        assert_eq!(err.call_result(), Some(CallResult::ErrorDeviceLost));
+        assert_eq!(err.fn_name(), None);
+    }
+
+    #[test]
+    fn unknown_codes_are_classified_by_sign() {
+        assert!(CallResult::from_i32(-12345).is_none());
+        assert!(CallResult::from_i32(12345).is_none());
+        assert_eq!(check(12345, "vkX", 7).unwrap(), 7);
+        let err = check(-12345, "vkX", ()).unwrap_err();
+        match *err.kind() {
+            ErrorKind::UnknownApiResult { code, fn_name } => {
+                assert_eq!(code, -12345);
+                assert_eq!(fn_name, "vkX");
+            },
+            _ => panic!("unexpected error: {}", err),
+        }
+        assert_eq!(err.call_result(), None);
+    }
+
+    #[test]
+    fn api_call_errors_name_the_call_and_result() {
+        let err = check(CallResult::ErrorOutOfDeviceMemory as i32, "vkCreateImage", ())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "vkCreateImage returned VK_ERROR_OUT_OF_DEVICE_MEMORY");
+        assert_eq!(err.result_code(), Some(CallResult::ErrorOutOfDeviceMemory as i32));
+        assert_eq!(err.fn_name(), Some("vkCreateImage"));
+
+        let err = Error::api_call(CallResult::ErrorDeviceLost, "");
+        assert_eq!(err.to_string(), "Vulkan API call returned VK_ERROR_DEVICE_LOST");
+        assert_eq!(err.result_code(), Some(CallResult::ErrorDeviceLost as i32));
+        assert_eq!(err.fn_name(), None);
+    }
+
+    #[test]
+    fn unknown_api_result_errors_keep_the_raw_code() {
+        let err = check(-12345, "vkAllocateMemory", ()).unwrap_err();
+        assert_eq!(err.to_string(), "vkAllocateMemory returned an unrecognized result code: \
+            -12345");
+        assert_eq!(err.result_code(), Some(-12345));
+        assert_eq!(err.fn_name(), Some("vkAllocateMemory"));
+    }
+
+    #[test]
+    fn pipeline_creation_errors_report_the_failed_index() {
+        let err = Error { kind: ErrorKind::PipelineCreation { index: Some(2),
+            code: CallResult::ErrorOutOfHostMemory as i32, fn_name: "vkCreateGraphicsPipelines" },
+            cause: None };
+        assert_eq!(err.to_string(), "vkCreateGraphicsPipelines returned \
+            VK_ERROR_OUT_OF_HOST_MEMORY (first failed create info: 2)");
+        assert_eq!(err.call_result(), Some(CallResult::ErrorOutOfHostMemory));
+        assert_eq!(err.result_code(), Some(CallResult::ErrorOutOfHostMemory as i32));
+        assert_eq!(err.fn_name(), Some("vkCreateGraphicsPipelines"));
+
+        let err = Error { kind: ErrorKind::PipelineCreation { index: None, code: -12345,
+            fn_name: "vkCreateComputePipelines" }, cause: None };
+        assert_eq!(err.to_string(), "vkCreateComputePipelines returned an unrecognized result \
+            code: -12345");
+        assert_eq!(err.call_result(), None);
+        assert_eq!(err.result_code(), Some(-12345));
+    }
+
+    #[test]
+    fn errors_without_a_call_have_no_code_or_name() {
+        let err = Error::from("oops");
+        assert_eq!(err.result_code(), None);
+        assert_eq!(err.fn_name(), None);
+    }
+}
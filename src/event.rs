@@ -1,27 +1,21 @@
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 use vks;
-use ::{VdResult, CallResult, Handle, Device, EventCreateFlags, EventCreateInfo};
+use ::{VdResult, Handle, Device, EventCreateFlags, EventCreateInfo, CommandBuffer,
+    PipelineStageFlags, MemoryBarrier, BufferMemoryBarrier, ImageMemoryBarrier};
 
-pub enum EventStatus {
-    Signaled,
-    Unsignaled,
-    Error(CallResult),
-}
-
-impl From<CallResult> for EventStatus {
-    fn from(res: CallResult) -> EventStatus {
-        match res {
-            CallResult::EventSet => EventStatus::Signaled,
-            CallResult::EventReset => EventStatus::Unsignaled,
-            _ => EventStatus::Error(res),
-        }
-    }
-}
 
-impl From<i32> for EventStatus {
-    fn from(res: i32) -> EventStatus {
-        CallResult::from(res).into()
-    }
+/// The status of an event.
+///
+/// Errors, such as `CallResult::ErrorDeviceLost`, are returned as
+/// `ErrorKind::ApiCall` errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EventStatus {
+    /// The event is set (`VK_EVENT_SET`).
+    Set,
+    /// The event is reset (`VK_EVENT_RESET`).
+    Reset,
 }
 
 
@@ -31,6 +25,12 @@ impl From<i32> for EventStatus {
 pub struct EventHandle(pub(crate) vks::VkEvent);
 
 impl EventHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkEvent) -> EventHandle {
+        EventHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkEvent {
         self.0
@@ -61,9 +61,56 @@ impl Drop for Inner {
 }
 
 
+/// The longest interval `Event::wait_signaled` will back off to, as a
+/// multiple of the requested poll interval.
+const MAX_POLL_BACKOFF: u32 = 16;
+
+/// The shortest interval `Event::wait_signaled` will poll at, so that a zero
+/// poll interval does not busy-spin.
+const MIN_POLL_INTERVAL_NANOS: u32 = 50_000;
+
+/// Returns the clamped initial poll interval and the longest interval to
+/// back off to for a requested poll interval.
+fn poll_interval_bounds(poll_interval: Duration) -> (Duration, Duration) {
+    let min_interval = Duration::new(0, MIN_POLL_INTERVAL_NANOS);
+    let interval = if poll_interval < min_interval { min_interval } else { poll_interval };
+    let max_interval = interval.checked_mul(MAX_POLL_BACKOFF)
+        .unwrap_or(Duration::new(u64::max_value(), 999_999_999));
+    (interval, max_interval)
+}
+
+/// Returns the poll interval following `interval`, doubled but no longer
+/// than `max_interval`.
+fn next_poll_interval(interval: Duration, max_interval: Duration) -> Duration {
+    match interval.checked_mul(2) {
+        Some(next) if next < max_interval => next,
+        _ => max_interval,
+    }
+}
+
+
 /// An event.
 ///
 ///
+/// ### Host to device signaling
+///
+/// `signal_from_host` and `cmd_wait_from_host` together let the host
+/// release work which has already been submitted. Events are poorly suited
+/// to this on some platforms:
+///
+/// * Implementations may limit how long a command buffer may wait on an
+///   event. If the host does not signal the event within that limit the
+///   results are undefined and may include device loss, so keep the window
+///   between submission and signaling short.
+/// * Some implementations (notably those layered on Metal, as reported by
+///   the `VK_KHR_portability_subset` `events` feature) do not support
+///   events at all, and others implement device-side waits by stalling the
+///   whole queue.
+///
+/// Prefer semaphores or fences where the dependency can be expressed at
+/// submission time.
+///
+///
 /// ### Destruction
 /// 
 /// Dropping this `Event` will cause `Device::destroy_event` to be called, 
@@ -121,7 +168,57 @@ impl Event {
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetEventStatus.html
     ///
     pub fn status(&self) -> VdResult<EventStatus> {
-        unsafe { Ok(self.inner.device.get_event_status(self.handle())?.into()) }
+        unsafe { self.inner.device.get_event_status(self.handle()) }
+    }
+
+    /// Sets this event from the host, releasing any device-side waits
+    /// recorded with `cmd_wait_from_host`.
+    ///
+    /// See the type-level documentation for portability caveats.
+    pub fn signal_from_host(&self) -> VdResult<()> {
+        self.set()
+    }
+
+    /// Records a wait on `command_buffer` for this event to be signaled by
+    /// the host using `signal_from_host`.
+    ///
+    /// The source stage is `PipelineStageFlags::HOST`, so host writes made
+    /// before signaling are made available to the `dst_stage_mask` stages
+    /// according to the barriers provided (use an access mask including
+    /// `AccessFlags::HOST_WRITE` as the source of each).
+    ///
+    /// See the type-level documentation for portability caveats.
+    pub fn cmd_wait_from_host(&self, command_buffer: &CommandBuffer,
+            dst_stage_mask: PipelineStageFlags, memory_barriers: &[MemoryBarrier],
+            buffer_memory_barriers: &[BufferMemoryBarrier],
+            image_memory_barriers: &[ImageMemoryBarrier]) {
+        command_buffer.wait_events(&[self], PipelineStageFlags::HOST, dst_stage_mask,
+            memory_barriers, buffer_memory_barriers, image_memory_barriers);
+    }
+
+    /// Blocks until this event is signaled or until `timeout` has elapsed,
+    /// returning `false` if the timeout elapsed first.
+    ///
+    /// Events cannot be waited on by the host natively, so this polls
+    /// `status`, sleeping for `poll_interval` between the first polls and
+    /// doubling the interval (up to sixteen times `poll_interval`) while
+    /// the event remains unsignaled. Poll intervals shorter than 50
+    /// microseconds are lengthened to that.
+    pub fn wait_signaled(&self, timeout: Duration, poll_interval: Duration) -> VdResult<bool> {
+        let start = Instant::now();
+        let (mut interval, max_interval) = poll_interval_bounds(poll_interval);
+        loop {
+            match self.status()? {
+                EventStatus::Set => return Ok(true),
+                EventStatus::Reset => (),
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= timeout { return Ok(false); }
+            let remaining = timeout - elapsed;
+            thread::sleep(if interval < remaining { interval } else { remaining });
+            interval = next_poll_interval(interval, max_interval);
+        }
     }
 }
 
@@ -133,3 +230,39 @@ unsafe impl<'h> Handle for &'h Event {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{poll_interval_bounds, next_poll_interval, MIN_POLL_INTERVAL_NANOS};
+
+    #[test]
+    fn zero_poll_interval_is_clamped() {
+        let min_interval = Duration::new(0, MIN_POLL_INTERVAL_NANOS);
+        let (interval, max_interval) = poll_interval_bounds(Duration::new(0, 0));
+        assert_eq!(interval, min_interval);
+        assert_eq!(max_interval, min_interval * 16);
+    }
+
+    #[test]
+    fn huge_poll_interval_does_not_overflow() {
+        let huge = Duration::new(u64::max_value() / 4 * 3, 0);
+        let (interval, max_interval) = poll_interval_bounds(huge);
+        assert_eq!(interval, huge);
+        assert_eq!(max_interval, Duration::new(u64::max_value(), 999_999_999));
+        assert_eq!(next_poll_interval(interval, max_interval), max_interval);
+        assert_eq!(next_poll_interval(max_interval, max_interval), max_interval);
+    }
+
+    #[test]
+    fn poll_interval_backs_off_to_max() {
+        let (mut interval, max_interval) = poll_interval_bounds(Duration::from_millis(1));
+        let mut intervals = Vec::new();
+        for _ in 0..6 {
+            intervals.push(interval);
+            interval = next_poll_interval(interval, max_interval);
+        }
+        assert_eq!(intervals, [1, 2, 4, 8, 16, 16].iter()
+            .map(|&ms| Duration::from_millis(ms)).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,274 @@
+//! `VK_EXT_external_memory_host` structures.
+//!
+//! `vks` predates `VK_EXT_external_memory_host` so the raw structures are
+//! declared here and `vkGetMemoryHostPointerPropertiesEXT` is loaded by name.
+//!
+//! `ImportMemoryHostPointerInfoExt` is chained onto memory allocation by
+//! `DeviceMemoryBuilder::import_memory_host_pointer`. The property structure
+//! is chained onto a `PhysicalDeviceProperties2Khr`.
+
+use std::mem;
+use std::ptr;
+use std::marker::PhantomData;
+use libc::c_void;
+use vks;
+use ::{Instance, DeviceHandle, ExternalMemoryHandleTypeFlagsKhr};
+
+
+const VK_STRUCTURE_TYPE_IMPORT_MEMORY_HOST_POINTER_INFO_EXT: vks::VkStructureType = 1000178000;
+const VK_STRUCTURE_TYPE_MEMORY_HOST_POINTER_PROPERTIES_EXT: vks::VkStructureType = 1000178001;
+const VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTERNAL_MEMORY_HOST_PROPERTIES_EXT: vks::VkStructureType = 1000178002;
+
+
+/// A raw `VkImportMemoryHostPointerInfoEXT`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkImportMemoryHostPointerInfoEXT {
+    pub sType: vks::VkStructureType,
+    pub pNext: *const c_void,
+    pub handleType: u32,
+    pub pHostPointer: *mut c_void,
+}
+
+impl Default for VkImportMemoryHostPointerInfoEXT {
+    fn default() -> Self {
+        VkImportMemoryHostPointerInfoEXT {
+            sType: VK_STRUCTURE_TYPE_IMPORT_MEMORY_HOST_POINTER_INFO_EXT,
+            pNext: ptr::null(),
+            handleType: 0,
+            pHostPointer: ptr::null_mut(),
+        }
+    }
+}
+
+
+/// A raw `VkMemoryHostPointerPropertiesEXT`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkMemoryHostPointerPropertiesEXT {
+    pub sType: vks::VkStructureType,
+    pub pNext: *mut c_void,
+    pub memoryTypeBits: u32,
+}
+
+impl Default for VkMemoryHostPointerPropertiesEXT {
+    fn default() -> Self {
+        VkMemoryHostPointerPropertiesEXT {
+            sType: VK_STRUCTURE_TYPE_MEMORY_HOST_POINTER_PROPERTIES_EXT,
+            pNext: ptr::null_mut(),
+            memoryTypeBits: 0,
+        }
+    }
+}
+
+
+/// A raw `VkPhysicalDeviceExternalMemoryHostPropertiesEXT`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkPhysicalDeviceExternalMemoryHostPropertiesEXT {
+    pub sType: vks::VkStructureType,
+    pub pNext: *mut c_void,
+    pub minImportedHostPointerAlignment: vks::VkDeviceSize,
+}
+
+impl Default for VkPhysicalDeviceExternalMemoryHostPropertiesEXT {
+    fn default() -> Self {
+        VkPhysicalDeviceExternalMemoryHostPropertiesEXT {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTERNAL_MEMORY_HOST_PROPERTIES_EXT,
+            pNext: ptr::null_mut(),
+            minImportedHostPointerAlignment: 0,
+        }
+    }
+}
+
+
+type PfnGetMemoryHostPointerProperties = unsafe extern "system" fn(vks::VkDevice, u32,
+    *const c_void, *mut VkMemoryHostPointerPropertiesEXT) -> vks::VkResult;
+
+
+/// `VK_EXT_external_memory_host` functions.
+///
+/// Each is `None` unless the extension was enabled on the device.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ExternalMemoryHostFns {
+    pub get_memory_host_pointer_properties: Option<PfnGetMemoryHostPointerProperties>,
+}
+
+impl ExternalMemoryHostFns {
+    pub(crate) fn load(instance: &Instance, device: DeviceHandle) -> ExternalMemoryHostFns {
+        let get_proc_addr = match instance.proc_addr_loader().vk.pfn_vkGetDeviceProcAddr {
+            Some(gpa) => gpa,
+            None => return ExternalMemoryHostFns::default(),
+        };
+        unsafe {
+            ExternalMemoryHostFns {
+                get_memory_host_pointer_properties: get_proc_addr(device.to_raw(),
+                    b"vkGetMemoryHostPointerPropertiesEXT\0".as_ptr() as *const _)
+                    .map(|f| mem::transmute(f)),
+            }
+        }
+    }
+}
+
+
+/// A `VkImportMemoryHostPointerInfoEXT`.
+///
+/// Imports a host allocation, such as a mapped file, as device memory. The
+/// host pointer and the allocation size must both be multiples of
+/// `PhysicalDeviceExternalMemoryHostPropertiesExt::min_imported_host_pointer_alignment`
+/// and the memory type must be one of those reported by
+/// `Device::get_memory_host_pointer_properties_ext`.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct ImportMemoryHostPointerInfoExt<'s> {
+    raw: VkImportMemoryHostPointerInfoEXT,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> ImportMemoryHostPointerInfoExt<'s> {
+    pub fn builder<'b>() -> ImportMemoryHostPointerInfoExtBuilder<'b> {
+        ImportMemoryHostPointerInfoExtBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkImportMemoryHostPointerInfoEXT) -> ImportMemoryHostPointerInfoExt<'s> {
+        ImportMemoryHostPointerInfoExt { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *const c_void {
+        self.raw.pNext
+    }
+
+    pub fn handle_type<'a>(&'a self) -> ExternalMemoryHandleTypeFlagsKhr {
+        ExternalMemoryHandleTypeFlagsKhr::from_bits(self.raw.handleType)
+            .expect("ImportMemoryHostPointerInfoExt::handle_type: error converting flags")
+    }
+
+    pub fn host_pointer<'a>(&'a self) -> *mut c_void {
+        self.raw.pHostPointer
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *const c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn set_handle_type<'m>(&mut self, handle_type: ExternalMemoryHandleTypeFlagsKhr) {
+        self.raw.handleType = handle_type.bits();
+    }
+
+    pub unsafe fn set_host_pointer<'m>(&mut self, host_pointer: *mut c_void) {
+        self.raw.pHostPointer = host_pointer;
+    }
+
+    pub fn as_raw(&self) -> &VkImportMemoryHostPointerInfoEXT {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkImportMemoryHostPointerInfoEXT`.
+#[derive(Debug, Clone, Default)]
+pub struct ImportMemoryHostPointerInfoExtBuilder<'b> {
+    raw: VkImportMemoryHostPointerInfoEXT,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> ImportMemoryHostPointerInfoExtBuilder<'b> {
+    pub fn new() -> ImportMemoryHostPointerInfoExtBuilder<'b> {
+        ImportMemoryHostPointerInfoExtBuilder {
+            raw: VkImportMemoryHostPointerInfoEXT::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *const c_void) -> ImportMemoryHostPointerInfoExtBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn handle_type<'m>(mut self, handle_type: ExternalMemoryHandleTypeFlagsKhr) -> ImportMemoryHostPointerInfoExtBuilder<'b> {
+        self.raw.handleType = handle_type.bits();
+        self
+    }
+
+    pub unsafe fn host_pointer<'m>(mut self, host_pointer: *mut c_void) -> ImportMemoryHostPointerInfoExtBuilder<'b> {
+        self.raw.pHostPointer = host_pointer;
+        self
+    }
+
+    pub fn build(self) -> ImportMemoryHostPointerInfoExt<'b> {
+        ImportMemoryHostPointerInfoExt {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
+
+
+/// A `VkPhysicalDeviceExternalMemoryHostPropertiesEXT`.
+///
+/// Chain onto a `PhysicalDeviceProperties2Khr` and query with
+/// `Instance::get_physical_device_properties_2_khr_into`.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct PhysicalDeviceExternalMemoryHostPropertiesExt<'s> {
+    raw: VkPhysicalDeviceExternalMemoryHostPropertiesEXT,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> PhysicalDeviceExternalMemoryHostPropertiesExt<'s> {
+    pub fn builder<'b>() -> PhysicalDeviceExternalMemoryHostPropertiesExtBuilder<'b> {
+        PhysicalDeviceExternalMemoryHostPropertiesExtBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkPhysicalDeviceExternalMemoryHostPropertiesEXT) -> PhysicalDeviceExternalMemoryHostPropertiesExt<'s> {
+        PhysicalDeviceExternalMemoryHostPropertiesExt { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *mut c_void {
+        self.raw.pNext
+    }
+
+    pub fn min_imported_host_pointer_alignment<'a>(&'a self) -> u64 {
+        self.raw.minImportedHostPointerAlignment
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *mut c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn as_raw(&self) -> &VkPhysicalDeviceExternalMemoryHostPropertiesEXT {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkPhysicalDeviceExternalMemoryHostPropertiesEXT`.
+#[derive(Debug, Clone, Default)]
+pub struct PhysicalDeviceExternalMemoryHostPropertiesExtBuilder<'b> {
+    raw: VkPhysicalDeviceExternalMemoryHostPropertiesEXT,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> PhysicalDeviceExternalMemoryHostPropertiesExtBuilder<'b> {
+    pub fn new() -> PhysicalDeviceExternalMemoryHostPropertiesExtBuilder<'b> {
+        PhysicalDeviceExternalMemoryHostPropertiesExtBuilder {
+            raw: VkPhysicalDeviceExternalMemoryHostPropertiesEXT::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *mut c_void) -> PhysicalDeviceExternalMemoryHostPropertiesExtBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn build(self) -> PhysicalDeviceExternalMemoryHostPropertiesExt<'b> {
+        PhysicalDeviceExternalMemoryHostPropertiesExt {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
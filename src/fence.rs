@@ -1,27 +1,31 @@
 use std::sync::Arc;
 use vks;
-use ::{VdResult, CallResult, Handle, Device, FenceCreateFlags, FenceCreateInfo};
+use ::{VdResult, Handle, Device, FenceCreateFlags, FenceCreateInfo};
 
+
+/// The status of a fence.
+///
+/// Errors, such as `CallResult::ErrorDeviceLost`, are returned as
+/// `ErrorKind::ApiCall` errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum FenceStatus {
+    /// The fence is signaled (`VK_SUCCESS`).
     Signaled,
+    /// The fence is unsignaled (`VK_NOT_READY`).
     Unsignaled,
-    Error(CallResult),
 }
 
-impl From<CallResult> for FenceStatus {
-    fn from(res: CallResult) -> FenceStatus {
-        match res {
-            CallResult::Success => FenceStatus::Signaled,
-            CallResult::NotReady => FenceStatus::Unsignaled,
-            _ => FenceStatus::Error(res),
-        }
-    }
-}
 
-impl From<i32> for FenceStatus {
-    fn from(res: i32) -> FenceStatus {
-        CallResult::from(res).into()
-    }
+/// The outcome of successfully waiting on fences.
+///
+/// Errors, such as `CallResult::ErrorDeviceLost`, are returned as
+/// `ErrorKind::ApiCall` errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum WaitResult {
+    /// The wait condition was satisfied.
+    Signaled,
+    /// The timeout expired before the wait condition was satisfied.
+    TimedOut,
 }
 
 
@@ -30,6 +34,12 @@ impl From<i32> for FenceStatus {
 pub struct FenceHandle(pub(crate) vks::VkFence);
 
 impl FenceHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkFence) -> FenceHandle {
+        FenceHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkFence {
         self.0
@@ -106,7 +116,15 @@ impl Fence {
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetFenceStatus.html
     //
     pub fn status(&self) -> VdResult<FenceStatus> {
-        unsafe { Ok(FenceStatus::from(self.inner.device.get_fence_status(self.handle())?)) }
+        unsafe { self.inner.device.get_fence_status(self.handle()) }
+    }
+
+    /// Waits up to `timeout` nanoseconds for this fence to become signaled.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkWaitForFences.html
+    //
+    pub fn wait(&self, timeout: u64) -> VdResult<WaitResult> {
+        unsafe { self.inner.device.wait_for_fences(&[self.handle()], true, timeout) }
     }
 }
 
@@ -0,0 +1,222 @@
+//! Format introspection.
+//!
+//! Sizes and block dimensions are transcribed from the "Compatible Formats"
+//! table of the Vulkan specification.
+
+use ::{Format, ImageAspectFlags};
+
+
+impl Format {
+    /// Returns the size in bytes of a single texel or, for block-compressed
+    /// formats, of a single compressed block. Returns `None` for
+    /// `Format::Undefined`.
+    ///
+    /// Depth/stencil sizes are those listed by the specification for the
+    /// combined format; copies to and from buffers address each aspect
+    /// separately and use different sizes (for example, the depth aspect of
+    /// `D24UnormS8Uint` is copied as 4 bytes and its stencil aspect as 1).
+    pub fn block_size_bytes(&self) -> Option<u32> {
+        use Format::*;
+        let size = match *self {
+            Undefined => return None,
+
+            R4G4UnormPack8 | R8Unorm | R8Snorm | R8Uscaled | R8Sscaled | R8Uint | R8Sint |
+            R8Srgb | S8Uint => 1,
+
+            R4G4B4B4UnormPack16 | B4G4R4A4UnormPack16 | R5G6B5UnormPack16 | B5G6R5UnormPack16 |
+            R5G5B5A1UnormPack16 | B5G5R5A1UnormPack16 | A1R5G5B5UnormPack16 | R8G8Unorm |
+            R8G8Snorm | R8G8Uscaled | R8G8Sscaled | R8G8Uint | R8G8Sint | R8G8Srgb | R16Unorm |
+            R16Snorm | R16Uscaled | R16Sscaled | R16Uint | R16Sint | R16Sfloat | D16Unorm => 2,
+
+            R8G8B8Unorm | R8G8B8Snorm | R8G8B8Uscaled | R8G8B8Sscaled | R8G8B8Uint | R8G8B8Sint |
+            R8G8B8Srgb | B8G8R8Unorm | B8G8R8Snorm | B8G8R8Uscaled | B8G8R8Sscaled | B8G8R8Uint |
+            B8G8R8Sint | B8G8R8Srgb | D16UnormS8Uint => 3,
+
+            R8G8B8A8Unorm | R8G8B8A8Snorm | R8G8B8A8Uscaled | R8G8B8A8Sscaled | R8G8B8A8Uint |
+            R8G8B8A8Sint | R8G8B8A8Srgb | B8G8R8A8Unorm | B8G8R8A8Snorm | B8G8R8A8Uscaled |
+            B8G8R8A8Sscaled | B8G8R8A8Uint | B8G8R8A8Sint | B8G8R8A8Srgb | A8B8G8R8UnormPack32 |
+            A8B8G8R8SnormPack32 | A8B8G8R8UscaledPack32 | A8B8G8R8SscaledPack32 |
+            A8B8G8R8UintPack32 | A8B8G8R8SintPack32 | A8B8G8R8SrgbPack32 | A2R10G10B10UnormPack32 |
+            A2R10G10B10SnormPack32 | A2R10G10B10UscaledPack32 | A2R10G10B10SscaledPack32 |
+            A2R10G10B10UintPack32 | A2R10G10B10SintPack32 | A2B10G10R10UnormPack32 |
+            A2B10G10R10SnormPack32 | A2B10G10R10UscaledPack32 | A2B10G10R10SscaledPack32 |
+            A2B10G10R10UintPack32 | A2B10G10R10SintPack32 | R16G16Unorm | R16G16Snorm |
+            R16G16Uscaled | R16G16Sscaled | R16G16Uint | R16G16Sint | R16G16Sfloat | R32Uint |
+            R32Sint | R32Sfloat | B10G11R11UfloatPack32 | E5B9G9R9UfloatPack32 |
+            X8D24UnormPack32 | D32Sfloat | D24UnormS8Uint => 4,
+
+            D32SfloatS8Uint => 5,
+
+            R16G16B16Unorm | R16G16B16Snorm | R16G16B16Uscaled | R16G16B16Sscaled | R16G16B16Uint |
+            R16G16B16Sint | R16G16B16Sfloat => 6,
+
+            R16G16B16A16Unorm | R16G16B16A16Snorm | R16G16B16A16Uscaled | R16G16B16A16Sscaled |
+            R16G16B16A16Uint | R16G16B16A16Sint | R16G16B16A16Sfloat | R32G32Uint | R32G32Sint |
+            R32G32Sfloat | R64Uint | R64Sint | R64Sfloat => 8,
+
+            R32G32B32Uint | R32G32B32Sint | R32G32B32Sfloat => 12,
+
+            R32G32B32A32Uint | R32G32B32A32Sint | R32G32B32A32Sfloat | R64G64Uint | R64G64Sint |
+            R64G64Sfloat => 16,
+
+            R64G64B64Uint | R64G64B64Sint | R64G64B64Sfloat => 24,
+
+            R64G64B64A64Uint | R64G64B64A64Sint | R64G64B64A64Sfloat => 32,
+
+            Bc1RgbUnormBlock | Bc1RgbSrgbBlock | Bc1RgbaUnormBlock | Bc1RgbaSrgbBlock |
+            Bc4UnormBlock | Bc4SnormBlock | Etc2R8G8B8UnormBlock | Etc2R8G8B8SrgbBlock |
+            Etc2R8G8B8A1UnormBlock | Etc2R8G8B8A1SrgbBlock | EacR11UnormBlock |
+            EacR11SnormBlock | Pvrtc12bppUnormBlockImg | Pvrtc14bppUnormBlockImg |
+            Pvrtc22bppUnormBlockImg | Pvrtc24bppUnormBlockImg | Pvrtc12bppSrgbBlockImg |
+            Pvrtc14bppSrgbBlockImg | Pvrtc22bppSrgbBlockImg | Pvrtc24bppSrgbBlockImg => 8,
+
+            Bc2UnormBlock | Bc2SrgbBlock | Bc3UnormBlock | Bc3SrgbBlock | Bc5UnormBlock |
+            Bc5SnormBlock | Bc6hUfloatBlock | Bc6hSfloatBlock | Bc7UnormBlock | Bc7SrgbBlock |
+            Etc2R8G8B8A8UnormBlock | Etc2R8G8B8A8SrgbBlock | EacR11G11UnormBlock |
+            EacR11G11SnormBlock | Astc4x4UnormBlock | Astc4x4SrgbBlock | Astc5x4UnormBlock |
+            Astc5x4SrgbBlock | Astc5x5UnormBlock | Astc5x5SrgbBlock | Astc6x5UnormBlock |
+            Astc6x5SrgbBlock | Astc6x6UnormBlock | Astc6x6SrgbBlock | Astc8x5UnormBlock |
+            Astc8x5SrgbBlock | Astc8x6UnormBlock | Astc8x6SrgbBlock | Astc8x8UnormBlock |
+            Astc8x8SrgbBlock | Astc10x5UnormBlock | Astc10x5SrgbBlock | Astc10x6UnormBlock |
+            Astc10x6SrgbBlock | Astc10x8UnormBlock | Astc10x8SrgbBlock | Astc10x10UnormBlock |
+            Astc10x10SrgbBlock | Astc12x10UnormBlock | Astc12x10SrgbBlock | Astc12x12UnormBlock |
+            Astc12x12SrgbBlock => 16,
+        };
+        Some(size)
+    }
+
+    /// Returns the width and height in texels of a compressed block, or
+    /// `(1, 1)` for uncompressed formats.
+    pub fn block_extent(&self) -> (u32, u32) {
+        use Format::*;
+        match *self {
+            Bc1RgbUnormBlock | Bc1RgbSrgbBlock | Bc1RgbaUnormBlock | Bc1RgbaSrgbBlock |
+            Bc2UnormBlock | Bc2SrgbBlock | Bc3UnormBlock | Bc3SrgbBlock | Bc4UnormBlock |
+            Bc4SnormBlock | Bc5UnormBlock | Bc5SnormBlock | Bc6hUfloatBlock | Bc6hSfloatBlock |
+            Bc7UnormBlock | Bc7SrgbBlock | Etc2R8G8B8UnormBlock | Etc2R8G8B8SrgbBlock |
+            Etc2R8G8B8A1UnormBlock | Etc2R8G8B8A1SrgbBlock | Etc2R8G8B8A8UnormBlock |
+            Etc2R8G8B8A8SrgbBlock | EacR11UnormBlock | EacR11SnormBlock | EacR11G11UnormBlock |
+            EacR11G11SnormBlock | Astc4x4UnormBlock | Astc4x4SrgbBlock |
+            Pvrtc14bppUnormBlockImg | Pvrtc24bppUnormBlockImg | Pvrtc14bppSrgbBlockImg |
+            Pvrtc24bppSrgbBlockImg => (4, 4),
+            Astc5x4UnormBlock | Astc5x4SrgbBlock => (5, 4),
+            Astc5x5UnormBlock | Astc5x5SrgbBlock => (5, 5),
+            Astc6x5UnormBlock | Astc6x5SrgbBlock => (6, 5),
+            Astc6x6UnormBlock | Astc6x6SrgbBlock => (6, 6),
+            Astc8x5UnormBlock | Astc8x5SrgbBlock => (8, 5),
+            Astc8x6UnormBlock | Astc8x6SrgbBlock => (8, 6),
+            Astc8x8UnormBlock | Astc8x8SrgbBlock => (8, 8),
+            Astc10x5UnormBlock | Astc10x5SrgbBlock => (10, 5),
+            Astc10x6UnormBlock | Astc10x6SrgbBlock => (10, 6),
+            Astc10x8UnormBlock | Astc10x8SrgbBlock => (10, 8),
+            Astc10x10UnormBlock | Astc10x10SrgbBlock => (10, 10),
+            Astc12x10UnormBlock | Astc12x10SrgbBlock => (12, 10),
+            Astc12x12UnormBlock | Astc12x12SrgbBlock => (12, 12),
+            Pvrtc12bppUnormBlockImg | Pvrtc22bppUnormBlockImg | Pvrtc12bppSrgbBlockImg |
+            Pvrtc22bppSrgbBlockImg => (8, 4),
+            _ => (1, 1),
+        }
+    }
+
+    /// Returns `true` for block-compressed formats.
+    pub fn is_compressed(&self) -> bool {
+        self.block_extent() != (1, 1)
+    }
+
+    /// Returns `true` if this format has a depth aspect.
+    pub fn has_depth(&self) -> bool {
+        match *self {
+            Format::D16Unorm | Format::X8D24UnormPack32 | Format::D32Sfloat |
+            Format::D16UnormS8Uint | Format::D24UnormS8Uint | Format::D32SfloatS8Uint => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if this format has a stencil aspect.
+    pub fn has_stencil(&self) -> bool {
+        match *self {
+            Format::S8Uint | Format::D16UnormS8Uint | Format::D24UnormS8Uint |
+            Format::D32SfloatS8Uint => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the aspects of an image of this format: depth and/or stencil
+    /// for depth/stencil formats, color for all others, and none for
+    /// `Format::Undefined`.
+    pub fn aspect_flags(&self) -> ImageAspectFlags {
+        let mut aspect_flags = ImageAspectFlags::empty();
+        if self.has_depth() { aspect_flags |= ImageAspectFlags::DEPTH; }
+        if self.has_stencil() { aspect_flags |= ImageAspectFlags::STENCIL; }
+        if aspect_flags.is_empty() && *self != Format::Undefined {
+            aspect_flags = ImageAspectFlags::COLOR;
+        }
+        aspect_flags
+    }
+
+    /// Returns `true` for formats with sRGB-encoded color components.
+    pub fn is_srgb(&self) -> bool {
+        use Format::*;
+        match *self {
+            R8Srgb | R8G8Srgb | R8G8B8Srgb | B8G8R8Srgb | R8G8B8A8Srgb | B8G8R8A8Srgb |
+            A8B8G8R8SrgbPack32 | Bc1RgbSrgbBlock | Bc1RgbaSrgbBlock | Bc2SrgbBlock |
+            Bc3SrgbBlock | Bc7SrgbBlock | Etc2R8G8B8SrgbBlock | Etc2R8G8B8A1SrgbBlock |
+            Etc2R8G8B8A8SrgbBlock | Astc4x4SrgbBlock | Astc5x4SrgbBlock | Astc5x5SrgbBlock |
+            Astc6x5SrgbBlock | Astc6x6SrgbBlock | Astc8x5SrgbBlock | Astc8x6SrgbBlock |
+            Astc8x8SrgbBlock | Astc10x5SrgbBlock | Astc10x6SrgbBlock | Astc10x8SrgbBlock |
+            Astc10x10SrgbBlock | Astc12x10SrgbBlock | Astc12x12SrgbBlock |
+            Pvrtc12bppSrgbBlockImg | Pvrtc14bppSrgbBlockImg | Pvrtc22bppSrgbBlockImg |
+            Pvrtc24bppSrgbBlockImg => true,
+            _ => false,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::{Format, ImageAspectFlags};
+
+    #[test]
+    fn depth_stencil() {
+        let format = Format::D24UnormS8Uint;
+        assert_eq!(format.block_size_bytes(), Some(4));
+        assert_eq!(format.block_extent(), (1, 1));
+        assert_eq!(format.aspect_flags(), ImageAspectFlags::DEPTH | ImageAspectFlags::STENCIL);
+        assert!(!format.is_srgb());
+        assert!(!format.is_compressed());
+        assert_eq!(Format::D32Sfloat.aspect_flags(), ImageAspectFlags::DEPTH);
+        assert_eq!(Format::S8Uint.aspect_flags(), ImageAspectFlags::STENCIL);
+    }
+
+    #[test]
+    fn bc1() {
+        for &(format, srgb) in &[(Format::Bc1RgbUnormBlock, false),
+                (Format::Bc1RgbSrgbBlock, true), (Format::Bc1RgbaUnormBlock, false),
+                (Format::Bc1RgbaSrgbBlock, true)] {
+            assert_eq!(format.block_size_bytes(), Some(8));
+            assert_eq!(format.block_extent(), (4, 4));
+            assert_eq!(format.aspect_flags(), ImageAspectFlags::COLOR);
+            assert_eq!(format.is_srgb(), srgb);
+            assert!(format.is_compressed());
+        }
+    }
+
+    #[test]
+    fn r64() {
+        for &(format, size) in &[(Format::R64Uint, 8), (Format::R64Sfloat, 8),
+                (Format::R64G64Sint, 16), (Format::R64G64B64Sfloat, 24),
+                (Format::R64G64B64A64Uint, 32)] {
+            assert_eq!(format.block_size_bytes(), Some(size));
+            assert_eq!(format.block_extent(), (1, 1));
+            assert_eq!(format.aspect_flags(), ImageAspectFlags::COLOR);
+            assert!(!format.is_srgb());
+        }
+    }
+
+    #[test]
+    fn undefined() {
+        assert_eq!(Format::Undefined.block_size_bytes(), None);
+        assert_eq!(Format::Undefined.aspect_flags(), ImageAspectFlags::empty());
+    }
+}
@@ -11,6 +11,13 @@ use ::{VdResult, Device, RenderPass, ImageView, Handle, FramebufferCreateInfo,
 pub struct FramebufferHandle(pub(crate) vks::VkFramebuffer);
 
 impl FramebufferHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkFramebuffer) -> FramebufferHandle {
+        FramebufferHandle(raw)
+    }
+
+    #[inline(always)]
     pub fn to_raw(&self) -> vks::VkFramebuffer {
         self.0
     }
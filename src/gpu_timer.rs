@@ -0,0 +1,113 @@
+use std::mem;
+use std::time::Duration;
+use libc::c_void;
+use ::{VdResult, Device, CommandBuffer, QueryPool, QueryResultFlags,
+    PipelineStageFlags};
+
+
+/// Measures the time elapsed on a queue between two points in a command
+/// buffer.
+///
+/// Timestamps are written into a two-query pool owned by the timer. Raw
+/// timestamp values are only meaningful in their low `timestampValidBits`
+/// bits, as reported for the queue family executing the command buffer, and
+/// advance once every `timestampPeriod` nanoseconds, as reported in the
+/// device limits. Both are looked up once, when the timer is created.
+///
+/// A timer may be reused by recording `begin` and `end` again once the
+/// previous results have been read (or abandoned).
+#[derive(Debug, Clone)]
+pub struct GpuTimer {
+    query_pool: QueryPool,
+    queue_family_index: u32,
+    valid_bits_mask: u64,
+    timestamp_period: f32,
+}
+
+impl GpuTimer {
+    /// Creates and returns a new `GpuTimer` for use in command buffers which
+    /// will be submitted to queues of the family `queue_family_index`.
+    ///
+    /// Returns an error if that queue family does not support timestamps.
+    pub fn new(device: Device, queue_family_index: u32) -> VdResult<GpuTimer> {
+        let valid_bits = match device.physical_device().queue_family_properties()?
+                .get(queue_family_index as usize) {
+            Some(props) => props.timestamp_valid_bits(),
+            None => return Err(format!("GpuTimer::new: invalid queue family index: {}",
+                queue_family_index).into()),
+        };
+        if valid_bits == 0 {
+            return Err(format!("GpuTimer::new: queue family {} does not support timestamps \
+                (timestampValidBits == 0)", queue_family_index).into());
+        }
+        let valid_bits_mask = if valid_bits >= 64 { !0 } else { (1u64 << valid_bits) - 1 };
+        let timestamp_period = device.limits().timestamp_period();
+
+        let query_pool = QueryPool::timestamps(device, 2)?;
+
+        Ok(GpuTimer {
+            query_pool,
+            queue_family_index,
+            valid_bits_mask,
+            timestamp_period,
+        })
+    }
+
+    /// Records a reset of this timer's queries followed by the starting
+    /// timestamp, written once all previous commands reach `stage`.
+    ///
+    /// Must be recorded outside of a render pass.
+    pub fn begin(&self, command_buffer: &CommandBuffer, stage: PipelineStageFlags) {
+        command_buffer.reset_query_pool(&self.query_pool, 0, 2);
+        command_buffer.write_timestamp(stage, &self.query_pool, 0);
+    }
+
+    /// Records the ending timestamp, written once all previous commands
+    /// reach `stage`.
+    pub fn end(&self, command_buffer: &CommandBuffer, stage: PipelineStageFlags) {
+        command_buffer.write_timestamp(stage, &self.query_pool, 1);
+    }
+
+    /// Returns the time elapsed between the `begin` and `end` timestamps, or
+    /// `None` if either has not yet been written.
+    ///
+    /// Does not block.
+    pub fn elapsed(&self, device: &Device) -> VdResult<Option<Duration>> {
+        debug_assert!(device.handle() == self.query_pool.device().handle(),
+            "GpuTimer::elapsed: device mismatch");
+        // Each query yields its value followed by its availability.
+        let mut results = [0u64; 4];
+        unsafe {
+            device.get_query_pool_results(&self.query_pool, 0, 2, mem::size_of_val(&results),
+                results.as_mut_ptr() as *mut c_void, 2 * mem::size_of::<u64>() as u64,
+                QueryResultFlags::RESULT_64 | QueryResultFlags::WITH_AVAILABILITY)?;
+        }
+        if results[1] == 0 || results[3] == 0 { return Ok(None); }
+
+        let start = results[0] & self.valid_bits_mask;
+        let end = results[2] & self.valid_bits_mask;
+        let ticks = end.wrapping_sub(start) & self.valid_bits_mask;
+        let nanos = (ticks as f64 * self.timestamp_period as f64) as u64;
+        Ok(Some(Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)))
+    }
+
+    /// Returns the queue family this timer was created for.
+    pub fn queue_family_index(&self) -> u32 {
+        self.queue_family_index
+    }
+
+    /// Returns the mask applied to raw timestamp values.
+    pub fn valid_bits_mask(&self) -> u64 {
+        self.valid_bits_mask
+    }
+
+    /// Returns the number of nanoseconds per timestamp tick.
+    pub fn timestamp_period(&self) -> f32 {
+        self.timestamp_period
+    }
+
+    /// Returns a reference to the underlying query pool.
+    pub fn query_pool(&self) -> &QueryPool {
+        &self.query_pool
+    }
+}
@@ -2,7 +2,62 @@ use std::sync::Arc;
 use std::marker::PhantomData;
 use smallvec::SmallVec;
 use ::{VdResult, Device, PipelineLayoutHandle, PipelineHandle, RenderPassHandle,
-    Handle, GraphicsPipelineCreateInfo};
+    Handle, GraphicsPipelineCreateInfo, PipelineShaderStageCreateInfo, ShaderStage,
+    VertexInputState, VertexLayout, PipelineCreateFlags, PolygonMode, DynamicState,
+    PipelineVertexInputStateCreateInfo};
+
+
+/// Checks that the `index`th of `create_infos`, if it is a derivative,
+/// specifies exactly one of a base pipeline handle or a base pipeline index,
+/// and that any index refers to an earlier element which allows
+/// derivatives.
+fn check_derivative(create_infos: &[GraphicsPipelineCreateInfo], index: usize) -> VdResult<()> {
+    let create_info = &create_infos[index];
+    if !create_info.flags().contains(PipelineCreateFlags::DERIVATIVE) { return Ok(()); }
+    let has_handle = create_info.base_pipeline_handle() != 0;
+    match (has_handle, create_info.base_pipeline_index()) {
+        (true, -1) => Ok(()),
+        (false, base_index) if base_index >= 0 => {
+            let base_index = base_index as usize;
+            if base_index >= index {
+                Err(format!("graphics pipeline {}: the base pipeline index ({}) must refer to \
+                    an earlier pipeline in the same batch", index, base_index).into())
+            } else if !create_infos[base_index].flags()
+                    .contains(PipelineCreateFlags::ALLOW_DERIVATIVES) {
+                Err(format!("graphics pipeline {}: the base pipeline (index {}) was not \
+                    created with `ALLOW_DERIVATIVES`", index, base_index).into())
+            } else {
+                Ok(())
+            }
+        },
+        _ => Err(format!("graphics pipeline {}: a derivative pipeline must specify exactly \
+            one of a base pipeline handle or a base pipeline index (of -1 otherwise)",
+            index).into()),
+    }
+}
+
+
+/// Returns a `FeatureNotEnabled` error if the rasterization state of
+/// `create_info` requires a device feature which was not enabled.
+fn check_features(device: &Device, create_info: &GraphicsPipelineCreateInfo,
+        fn_name: &'static str) -> VdResult<()> {
+    if create_info.as_raw().pRasterizationState.is_null() { return Ok(()); }
+    let rasterization_state = create_info.rasterization_state();
+    match rasterization_state.polygon_mode() {
+        PolygonMode::Line | PolygonMode::Point => device.require_feature(
+            |f| f.fill_mode_non_solid(), "fillModeNonSolid", fn_name)?,
+        _ => (),
+    }
+    if rasterization_state.depth_clamp_enable() {
+        device.require_feature(|f| f.depth_clamp(), "depthClamp", fn_name)?;
+    }
+    let dynamic_line_width = !create_info.as_raw().pDynamicState.is_null() &&
+        create_info.dynamic_state().dynamic_states().contains(&DynamicState::LineWidth);
+    if !dynamic_line_width && rasterization_state.line_width() != 1.0 {
+        device.require_feature(|f| f.wide_lines(), "wideLines", fn_name)?;
+    }
+    Ok(())
+}
 
 
 #[derive(Debug)]
@@ -40,17 +95,30 @@ impl GraphicsPipeline {
     }
 
     /// Creates several graphics pipelines at once.
+    ///
+    /// Shader stages and vertex input specified with
+    /// `GraphicsPipelineBuilder::shader_stages` and
+    /// `GraphicsPipelineBuilder::vertex_input` are used just as by
+    /// `GraphicsPipelineBuilder::build`.
     #[deprecated(note = "use `Device::create_graphics_pipelines`")]
     pub fn create<'b, Gpb>(device: &Device, builders: &[Gpb])
             -> VdResult<SmallVec<[GraphicsPipeline; 8]>>
-            where Gpb: AsRef<::GraphicsPipelineCreateInfo<'b>> {
+            where Gpb: AsRef<GraphicsPipelineBuilder<'b>> {
+        let vertex_inputs: SmallVec<[Option<PipelineVertexInputStateCreateInfo>; 8]> = builders
+            .iter()
+            .map(|builder| builder.as_ref().vertex_input.as_ref().map(|vi| vi.create_info()))
+            .collect();
         let mut create_infos = SmallVec::<[GraphicsPipelineCreateInfo; 8]>::new();
         let mut pipelines = SmallVec::<[GraphicsPipeline; 8]>::new();
         create_infos.reserve_exact(builders.len());
         pipelines.reserve_exact(builders.len());
 
-        for builder in builders {
-            create_infos.push(builder.as_ref().clone());
+        for (builder, vertex_input) in builders.iter().zip(vertex_inputs.iter()) {
+            create_infos.push(builder.as_ref().assemble(vertex_input.as_ref()));
+        }
+        for idx in 0..create_infos.len() {
+            check_derivative(&create_infos, idx)?;
+            check_features(device, &create_infos[idx], "GraphicsPipeline::create")?;
         }
 
         let pipeline_handles = unsafe { device.create_graphics_pipelines(None, &create_infos, None)? };
@@ -94,6 +162,8 @@ unsafe impl<'g> Handle for &'g GraphicsPipeline {
 #[repr(C)]
 pub struct GraphicsPipelineBuilder<'b> {
     create_info: GraphicsPipelineCreateInfo<'b>,
+    shader_stages: SmallVec<[PipelineShaderStageCreateInfo<'b>; 5]>,
+    vertex_input: Option<VertexInputState>,
     _p: PhantomData<&'b ()>,
 }
 
@@ -102,6 +172,8 @@ impl<'b> GraphicsPipelineBuilder<'b> {
     pub fn new() -> GraphicsPipelineBuilder<'b> {
         GraphicsPipelineBuilder {
             create_info: GraphicsPipelineCreateInfo::default(),
+            shader_stages: SmallVec::new(),
+            vertex_input: None,
             _p: PhantomData,
         }
     }
@@ -125,6 +197,16 @@ impl<'b> GraphicsPipelineBuilder<'b> {
         self
     }
 
+    /// Specifies the shader stages to be included in the graphics pipeline.
+    ///
+    /// An alternative to `stages`, which takes precedence if both are used.
+    pub fn shader_stages<'s, 'p>(&'s mut self, stages: &'p [ShaderStage<'p>])
+            -> &'s mut GraphicsPipelineBuilder<'b>
+            where 'p: 'b {
+        self.shader_stages = stages.iter().map(|stage| stage.create_info()).collect();
+        self
+    }
+
     /// Specifies the vertex input state details.
     pub fn vertex_input_state<'s, 'p>(&'s mut self,
             vertex_input_state: &'p ::PipelineVertexInputStateCreateInfo)
@@ -134,6 +216,26 @@ impl<'b> GraphicsPipelineBuilder<'b> {
         self
     }
 
+    /// Specifies the vertex input bindings and attributes.
+    ///
+    /// An alternative to `vertex_input_state`, over which it takes
+    /// precedence.
+    pub fn vertex_input<'s>(&'s mut self, vertex_input: &VertexInputState)
+            -> &'s mut GraphicsPipelineBuilder<'b> {
+        self.vertex_input = Some(vertex_input.clone());
+        self
+    }
+
+    /// Specifies the vertex input bindings and attributes to be those of the
+    /// vertex type `V`.
+    ///
+    /// Equivalent to `vertex_input(&VertexInputState::for_layout::<V>())`.
+    pub fn vertex_layout<'s, V: VertexLayout>(&'s mut self)
+            -> &'s mut GraphicsPipelineBuilder<'b> {
+        self.vertex_input = Some(VertexInputState::for_layout::<V>());
+        self
+    }
+
     /// Specifies the input assembly behavior, as described in Drawing
     /// Commands.
     pub fn input_assembly_state<'s, 'p>(&'s mut self, input_assembly_state:
@@ -176,6 +278,15 @@ impl<'b> GraphicsPipelineBuilder<'b> {
 
     /// Specifies the multisample state and is ignored if the pipeline has
     /// rasterization disabled.
+    ///
+    /// The rasterization sample count must match that of the attachments
+    /// used, for example:
+    ///
+    /// ```text
+    /// let multisample_state = PipelineMultisampleStateCreateInfo::builder()
+    ///     .rasterization_samples(device.physical_device().max_usable_sample_count())
+    ///     .build();
+    /// ```
     pub fn multisample_state<'s, 'p>(&'s mut self,
             multisample_state: &'p ::PipelineMultisampleStateCreateInfo)
             -> &'s mut GraphicsPipelineBuilder<'b>
@@ -243,28 +354,70 @@ impl<'b> GraphicsPipelineBuilder<'b> {
         self
     }
 
-    /// Specifies the pipeline to derive from.
+    /// Specifies that pipelines may be derived from this one.
+    ///
+    /// Adds `PipelineCreateFlags::ALLOW_DERIVATIVES` to the flags specified
+    /// so far.
+    pub fn allow_derivatives<'s>(&'s mut self) -> &'s mut GraphicsPipelineBuilder<'b> {
+        let flags = self.create_info.flags() | PipelineCreateFlags::ALLOW_DERIVATIVES;
+        self.create_info.set_flags(flags);
+        self
+    }
+
+    /// Specifies an existing pipeline to derive from. The base pipeline must
+    /// have been created with `allow_derivatives`.
+    ///
+    /// Adds `PipelineCreateFlags::DERIVATIVE` to the flags specified so far
+    /// and clears any base pipeline index.
     pub fn base_pipeline<'s, H>(&'s mut self, base_pipeline: H)
             -> &'s mut GraphicsPipelineBuilder<'b>
             where H: Handle<Target=PipelineHandle> {
+        let flags = self.create_info.flags() | PipelineCreateFlags::DERIVATIVE;
+        self.create_info.set_flags(flags);
         self.create_info.set_base_pipeline_handle(base_pipeline);
+        self.create_info.set_base_pipeline_index(-1);
         self
     }
 
-    /// Specifies the index into the pCreateInfos parameter to use as a
-    /// pipeline to derive from.
+    /// Specifies the index, within a batch of pipelines being created
+    /// together, of an earlier pipeline to derive from. The base pipeline
+    /// must have been specified with `allow_derivatives`.
+    ///
+    /// Adds `PipelineCreateFlags::DERIVATIVE` to the flags specified so far
+    /// and clears any base pipeline handle.
     pub fn base_pipeline_index<'s>(&'s mut self, base_pipeline_index: i32)
             -> &'s mut GraphicsPipelineBuilder<'b> {
+        let flags = self.create_info.flags() | PipelineCreateFlags::DERIVATIVE;
+        self.create_info.set_flags(flags);
+        self.create_info.set_base_pipeline_handle(PipelineHandle(0));
         self.create_info.set_base_pipeline_index(base_pipeline_index);
         self
     }
 
+    /// Returns the create info specified so far with any stages specified
+    /// using `shader_stages` and with `vertex_input`, the create info of the
+    /// state specified using `vertex_input`, filled in.
+    fn assemble<'s>(&'s self, vertex_input: Option<&'s PipelineVertexInputStateCreateInfo<'s>>)
+            -> GraphicsPipelineCreateInfo<'s> {
+        let mut create_info = self.create_info.clone();
+        if create_info.stages().is_empty() && !self.shader_stages.is_empty() {
+            create_info.set_stages(&self.shader_stages);
+        }
+        if let Some(vertex_input) = vertex_input {
+            create_info.set_vertex_input_state(vertex_input);
+        }
+        create_info
+    }
+
     /// Creates and returns a new `GraphicsPipeline`. Use
     /// `GraphicsPipeline::create` to create multiple pipelines in one call.
     pub fn build(&self, device: Device) -> VdResult<GraphicsPipeline> {
+        let vertex_input = self.vertex_input.as_ref().map(|vi| vi.create_info());
+        let create_info = self.assemble(vertex_input.as_ref());
+        check_derivative(::std::slice::from_ref(&create_info), 0)?;
+        check_features(&device, &create_info, "GraphicsPipelineBuilder::build")?;
         let handle = unsafe {
-            let create_infos = ::std::slice::from_raw_parts(&self.create_info, 1);
-            *device.create_graphics_pipelines(None, create_infos, None)?.get_unchecked(0)
+            *device.create_graphics_pipelines(None, &[create_info], None)?.get_unchecked(0)
         };
 
         Ok(GraphicsPipeline {
@@ -282,8 +435,105 @@ impl<'b> AsRef<GraphicsPipelineBuilder<'b>> for GraphicsPipelineBuilder<'b> {
     }
 }
 
-impl<'b> AsRef<::GraphicsPipelineCreateInfo<'b>> for GraphicsPipelineBuilder<'b> {
-    fn as_ref(&self) -> &::GraphicsPipelineCreateInfo<'b> {
-        &self.create_info
+
+#[cfg(test)]
+mod tests {
+    use ::{ShaderStage, ShaderStageFlags, ShaderModuleHandle, PipelineShaderStageCreateInfo,
+        VertexInputState, VertexInputRate, Format};
+    use super::*;
+
+    fn shader_stages() -> Vec<ShaderStage<'static>> {
+        let module = unsafe { ShaderModuleHandle::from_raw(1) };
+        vec![
+            ShaderStage::builder().stage(ShaderStageFlags::VERTEX).module(module).build().unwrap(),
+            ShaderStage::builder().stage(ShaderStageFlags::FRAGMENT).module(module).build().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn batch_create_info_includes_owned_stages() {
+        let stages = shader_stages();
+        let mut builder = GraphicsPipelineBuilder::new();
+        builder.shader_stages(&stages);
+
+        // As in `GraphicsPipeline::create`:
+        let builders = [builder];
+        let create_infos: Vec<_> = builders.iter().map(|b| b.assemble(None)).collect();
+
+        let create_info = &create_infos[0];
+        assert_eq!(create_info.stages().len(), 2);
+        assert_eq!(create_info.stages()[0].stage(), ShaderStageFlags::VERTEX);
+        assert_eq!(create_info.stages()[1].stage(), ShaderStageFlags::FRAGMENT);
+        assert_eq!(create_info.stages()[1].name().to_str().unwrap(), "main");
+    }
+
+    #[test]
+    fn batch_create_info_includes_owned_vertex_input() {
+        let mut vertex_input = VertexInputState::new();
+        vertex_input.binding(0, 24, VertexInputRate::Vertex)
+            .attribute(0, 0, Format::R32G32B32Sfloat, 0)
+            .attribute(1, 0, Format::R32G32B32Sfloat, 12);
+        let mut builder = GraphicsPipelineBuilder::new();
+        builder.vertex_input(&vertex_input);
+
+        // As in `GraphicsPipeline::create`:
+        let builders = [builder];
+        let vertex_inputs: Vec<_> = builders.iter()
+            .map(|b| b.vertex_input.as_ref().map(|vi| vi.create_info()))
+            .collect();
+        let create_infos: Vec<_> = builders.iter().zip(vertex_inputs.iter())
+            .map(|(b, vi)| b.assemble(vi.as_ref()))
+            .collect();
+
+        let vertex_input_state = create_infos[0].vertex_input_state();
+        assert_eq!(vertex_input_state.vertex_binding_descriptions().len(), 1);
+        assert_eq!(vertex_input_state.vertex_binding_descriptions()[0].stride(), 24);
+        assert_eq!(vertex_input_state.vertex_attribute_descriptions().len(), 2);
+        assert_eq!(vertex_input_state.vertex_attribute_descriptions()[1].offset(), 12);
+    }
+
+    fn derivative_batch(base_allows_derivatives: bool, base_index: i32)
+            -> [GraphicsPipelineBuilder<'static>; 2] {
+        let mut base = GraphicsPipelineBuilder::new();
+        if base_allows_derivatives { base.allow_derivatives(); }
+        let mut derivative = GraphicsPipelineBuilder::new();
+        derivative.base_pipeline_index(base_index);
+        [base, derivative]
+    }
+
+    #[test]
+    fn batch_derivative_of_earlier_pipeline() {
+        let builders = derivative_batch(true, 0);
+        let create_infos: Vec<_> = builders.iter().map(|b| b.assemble(None)).collect();
+        assert!(check_derivative(&create_infos, 0).is_ok());
+        assert!(check_derivative(&create_infos, 1).is_ok());
+    }
+
+    #[test]
+    fn batch_derivative_errors() {
+        let cases = [
+            (derivative_batch(true, 1), "must refer to an earlier pipeline in the same batch"),
+            (derivative_batch(false, 0), "was not created with `ALLOW_DERIVATIVES`"),
+            (derivative_batch(true, -1), "must specify exactly one of a base pipeline handle"),
+        ];
+        for &(ref builders, expected) in cases.iter() {
+            let create_infos: Vec<_> = builders.iter().map(|b| b.assemble(None)).collect();
+            let message = check_derivative(&create_infos, 1).unwrap_err().to_string();
+            assert!(message.contains(expected), "unexpected message: {}", message);
+            assert!(!message.contains("  "), "message contains indentation: {}", message);
+        }
+    }
+
+    #[test]
+    fn explicit_stages_take_precedence() {
+        let stages = shader_stages();
+        let explicit = [PipelineShaderStageCreateInfo::builder()
+            .stage(ShaderStageFlags::COMPUTE)
+            .build()];
+        let mut builder = GraphicsPipelineBuilder::new();
+        builder.stages(&explicit).shader_stages(&stages);
+        let create_info = builder.assemble(None);
+        assert_eq!(create_info.stages().len(), 1);
+        assert_eq!(create_info.stages()[0].stage(), ShaderStageFlags::COMPUTE);
     }
 }
@@ -1,7 +1,8 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::marker::PhantomData;
+use libc::c_void;
 use vks;
-use ::{VdResult, Device, DeviceMemory, Handle};
+use ::{VdResult, Device, DeviceMemory, Handle, SwapchainKhr, Error, ErrorKind};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -9,6 +10,12 @@ use ::{VdResult, Device, DeviceMemory, Handle};
 pub struct ImageHandle(pub(crate) vks::VkImage);
 
 impl ImageHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkImage) -> ImageHandle {
+        ImageHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkImage {
         self.0
@@ -31,13 +38,20 @@ struct Inner {
     handle: ImageHandle,
     memory_requirements: ::MemoryRequirements,
     device: Device,
-    is_swapchain_image: bool,
+    format: ::Format,
+    extent: ::Extent3d,
+    image_type: ::ImageType,
+    mip_levels: u32,
+    array_layers: u32,
+    flags: ::ImageCreateFlags,
+    swapchain: Option<SwapchainKhr>,
+    memory: Mutex<Option<DeviceMemory>>,
 }
 
 impl Drop for Inner {
     fn drop(&mut self) {
         unsafe {
-            if !self.is_swapchain_image {
+            if self.swapchain.is_none() {
                 self.device.destroy_image(self.handle, None);
             }
         }
@@ -53,6 +67,10 @@ impl Drop for Inner {
 /// Dropping this `Image` will cause `Device::destroy_image` to be called, 
 /// automatically releasing any resources associated with it.
 ///
+/// Images returned by `SwapchainKhr::images` are owned by their swapchain
+/// and are never destroyed individually. Each keeps the swapchain alive
+/// until it, and every clone of it, has been dropped.
+///
 #[derive(Debug, Clone)]
 pub struct Image {
     inner: Arc<Inner>,
@@ -64,7 +82,8 @@ impl Image {
         ImageBuilder::new()
     }
 
-    pub(crate) unsafe fn from_handle(device: Device, handle: ImageHandle, is_swapchain_image: bool) -> Image {
+    pub(crate) unsafe fn from_handle(device: Device, handle: ImageHandle,
+            create_info: &::ImageCreateInfo) -> Image {
         let memory_requirements = device.get_image_memory_requirements(handle);
 
         Image {
@@ -72,7 +91,40 @@ impl Image {
                 handle,
                 memory_requirements: memory_requirements.into(),
                 device,
-                is_swapchain_image,
+                format: create_info.format(),
+                extent: create_info.extent().clone(),
+                image_type: create_info.image_type(),
+                mip_levels: create_info.mip_levels(),
+                array_layers: create_info.array_layers(),
+                flags: create_info.flags(),
+                swapchain: None,
+                memory: Mutex::new(None),
+            })
+        }
+    }
+
+    /// Returns a non-owning `Image` for a presentable image belonging to
+    /// `swapchain`.
+    pub(crate) unsafe fn from_swapchain(swapchain: SwapchainKhr, handle: ImageHandle) -> Image {
+        let extent = ::Extent3d::builder()
+            .width(swapchain.extent().width())
+            .height(swapchain.extent().height())
+            .depth(1)
+            .build();
+
+        Image {
+            inner: Arc::new(Inner {
+                handle,
+                memory_requirements: ::MemoryRequirements::default(),
+                device: swapchain.device().clone(),
+                format: swapchain.image_format(),
+                extent,
+                image_type: ::ImageType::Type2d,
+                mip_levels: 1,
+                array_layers: swapchain.image_array_layers(),
+                flags: ::ImageCreateFlags::empty(),
+                swapchain: Some(swapchain),
+                memory: Mutex::new(None),
             })
         }
     }
@@ -83,23 +135,82 @@ impl Image {
     }
 
     /// Returns this image's memory requirements.
+    ///
+    /// Swapchain images are bound to memory by the implementation and
+    /// report zeroed requirements.
     pub fn memory_requirements(&self) -> &::MemoryRequirements {
         &self.inner.memory_requirements
     }
 
+    /// Returns this image's format.
+    pub fn format(&self) -> ::Format {
+        self.inner.format
+    }
+
+    /// Returns this image's extent.
+    pub fn extent(&self) -> &::Extent3d {
+        &self.inner.extent
+    }
+
+    /// Returns this image's dimensionality.
+    pub fn image_type(&self) -> ::ImageType {
+        self.inner.image_type
+    }
+
+    /// Returns the number of mip levels in this image.
+    pub fn mip_levels(&self) -> u32 {
+        self.inner.mip_levels
+    }
+
+    /// Returns the number of array layers in this image.
+    pub fn array_layers(&self) -> u32 {
+        self.inner.array_layers
+    }
+
+    /// Returns the flags this image was created with.
+    pub fn flags(&self) -> ::ImageCreateFlags {
+        self.inner.flags
+    }
+
+    /// Returns the swapchain which owns this image, if any.
+    pub fn swapchain(&self) -> Option<&SwapchainKhr> {
+        self.inner.swapchain.as_ref()
+    }
+
+    /// Returns true if this image belongs to a swapchain.
+    pub fn is_swapchain_image(&self) -> bool {
+        self.inner.swapchain.is_some()
+    }
+
     /// Binds this image to device memory. `offset` is the start offset of the
     /// region of memory which is to be bound. The number of bytes returned in
     /// the VkMemoryRequirements::size member in memory, starting from
     /// memoryOffset bytes, will be bound to the specified image.
     ///
+    /// A clone of `memory` is retained until this image is dropped, keeping
+    /// the allocation alive for at least as long as the image.
+    ///
     /// ## Safety
     ///
-    /// The caller must ensure that the bound memory is not in use when it is
-    /// dropped.
+    /// The caller must ensure that `memory` is compatible with this image's
+    /// memory requirements and that this image is not already bound.
+    ///
+    /// Returns an error if this is a swapchain image.
     ///
     pub unsafe fn bind_memory(&self, memory: &DeviceMemory, offset_bytes: ::DeviceSize)
             -> VdResult<()> {
-        self.inner.device.bind_image_memory(self.inner.handle, memory.handle(), offset_bytes)
+        if self.inner.swapchain.is_some() {
+            return Err(Error { kind: ErrorKind::SwapchainImage { fn_name: "vkBindImageMemory" },
+                cause: None });
+        }
+        self.inner.device.bind_image_memory(self.inner.handle, memory.handle(), offset_bytes)?;
+        *self.inner.memory.lock().unwrap() = Some(memory.clone());
+        Ok(())
+    }
+
+    /// Returns the memory bound to this image using `bind_memory`, if any.
+    pub fn memory(&self) -> Option<DeviceMemory> {
+        self.inner.memory.lock().unwrap().clone()
     }
 
     /// Returns a reference to the associated device.
@@ -184,6 +295,13 @@ impl<'b> ImageBuilder<'b> {
 
     /// samples is the number of sub-data element samples in the image as
     /// defined in VkSampleCountFlagBits. See Multisampling.
+    ///
+    /// For a multisampled color or depth attachment, the highest count
+    /// supported by the device can be used directly:
+    ///
+    /// ```text
+    /// builder.samples(device.physical_device().max_usable_sample_count());
+    /// ```
     pub fn samples<'s>(&'s mut self, samples: ::SampleCountFlags)
             -> &'s mut ImageBuilder<'b> {
         self.create_info.set_samples(samples);
@@ -235,11 +353,104 @@ impl<'b> ImageBuilder<'b> {
         self
     }
 
-    //// Creates and returns a new `Image`
+    /// Chains an `ExternalMemoryImageCreateInfoKhr`, specifying the external
+    /// handle types which may be used with memory bound to this image.
+    pub fn external_memory<'s, 'p>(&'s mut self,
+            external_info: &'p mut ::ExternalMemoryImageCreateInfoKhr)
+            -> &'s mut ImageBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            external_info.set_next(self.create_info.next());
+            self.create_info.set_next(external_info.as_raw() as *const _ as *const c_void);
+        }
+        self
+    }
+
+    /// Checks the image parameters specified so far against the limits
+    /// reported by `PhysicalDevice::image_format_properties`.
+    ///
+    /// Skipped when the create info has extension structures chained to it,
+    /// as those may extend the supported set of parameters.
+    #[cfg(debug_assertions)]
+    fn check_format_support(&self, device: &Device) -> VdResult<()> {
+        if !self.create_info.next().is_null() { return Ok(()); }
+        let ci = &self.create_info;
+        let props = match device.physical_device().image_format_properties(ci.format(),
+                ci.image_type(), ci.tiling(), ci.usage(), ci.flags())? {
+            Some(props) => props,
+            None => return Err(format!("ImageBuilder::build: the format `{:?}` is not \
+                supported for {:?} images with {:?} tiling, usage: {:?} and flags: {:?}.",
+                ci.format(), ci.image_type(), ci.tiling(), ci.usage(), ci.flags()).into()),
+        };
+        let (extent, max_extent) = (ci.extent(), props.max_extent());
+        if extent.width() > max_extent.width() || extent.height() > max_extent.height() ||
+                extent.depth() > max_extent.depth() {
+            return Err(format!("ImageBuilder::build: the extent ({:?}) exceeds the maximum \
+                supported for format `{:?}` ({:?}).", extent, ci.format(), max_extent).into());
+        }
+        if ci.mip_levels() > props.max_mip_levels() {
+            return Err(format!("ImageBuilder::build: the mip level count ({}) exceeds the \
+                maximum supported for format `{:?}` ({}).", ci.mip_levels(), ci.format(),
+                props.max_mip_levels()).into());
+        }
+        if ci.array_layers() > props.max_array_layers() {
+            return Err(format!("ImageBuilder::build: the array layer count ({}) exceeds the \
+                maximum supported for format `{:?}` ({}).", ci.array_layers(), ci.format(),
+                props.max_array_layers()).into());
+        }
+        if !props.sample_counts().contains(ci.samples()) {
+            return Err(format!("ImageBuilder::build: the sample count ({:?}) is not supported \
+                for format `{:?}` (supported: {:?}).", ci.samples(), ci.format(),
+                props.sample_counts()).into());
+        }
+        Ok(())
+    }
+
+    /// Creates and returns a new `Image`.
+    ///
+    /// In debug builds the image parameters are first checked against
+    /// `PhysicalDevice::image_format_properties` so that unsupported
+    /// combinations produce a descriptive error rather than undefined
+    /// behavior.
     pub fn build(&self, device: Device) -> VdResult<Image> {
+        #[cfg(debug_assertions)]
+        self.check_format_support(&device)?;
         unsafe {
             let handle = device.create_image(&self.create_info, None)?;
-            Ok(Image::from_handle(device, handle, false))
+            Ok(Image::from_handle(device, handle, &self.create_info))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use ::{ExternalMemoryImageCreateInfoKhr, ExternalMemoryHandleTypeFlagsKhr, ImageType, Format,
+        Extent3d};
+    use super::ImageBuilder;
+
+    #[test]
+    fn external_memory_info_is_chained() {
+        let mut external_info = ExternalMemoryImageCreateInfoKhr::builder()
+            .handle_types(ExternalMemoryHandleTypeFlagsKhr::OPAQUE_FD)
+            .build();
+        let external_ptr = external_info.as_raw() as *const vks::VkExternalMemoryImageCreateInfoKHR;
+
+        let mut builder = ImageBuilder::new();
+        builder.image_type(ImageType::Type2d)
+            .format(Format::R8G8B8A8Unorm)
+            .extent(Extent3d::builder().width(64).height(64).depth(1).build())
+            .external_memory(&mut external_info);
+
+        let create_info = builder.create_info.as_raw();
+        assert_eq!(create_info.extent.width, 64);
+        assert_eq!(create_info.pNext as *const vks::VkExternalMemoryImageCreateInfoKHR,
+            external_ptr);
+        unsafe {
+            let external = &*external_ptr;
+            assert_eq!(external.sType, vks::VK_STRUCTURE_TYPE_EXTERNAL_MEMORY_IMAGE_CREATE_INFO_KHR);
+            assert_eq!(external.handleTypes, ExternalMemoryHandleTypeFlagsKhr::OPAQUE_FD.bits());
+            assert!(external.pNext.is_null());
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use vks;
-use ::{VdResult, SwapchainKhr, Device, ImageHandle, Handle};
+use ::{VdResult, SwapchainKhr, Device, ImageHandle, Handle, Image, ImageType, ImageViewType,
+    ImageCreateFlags, ComponentMapping, ComponentSwizzle, ImageSubresourceRange, Format};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -8,6 +9,12 @@ use ::{VdResult, SwapchainKhr, Device, ImageHandle, Handle};
 pub struct ImageViewHandle(pub(crate) vks::VkImageView);
 
 impl ImageViewHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkImageView) -> ImageViewHandle {
+        ImageViewHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkImageView {
         self.0
@@ -25,11 +32,32 @@ unsafe impl Handle for ImageViewHandle {
 }
 
 
+/// Returns the type of a view of every array layer of an image.
+///
+/// Cube compatible 2D images with six layers are viewed as cubes and, if
+/// `image_cube_array` (the `imageCubeArray` feature) is enabled, those with
+/// another multiple of six layers as cube arrays.
+fn default_view_type(image_type: ImageType, flags: ImageCreateFlags, layers: u32,
+        image_cube_array: bool) -> ImageViewType {
+    let cube_compatible = flags.contains(ImageCreateFlags::CUBE_COMPATIBLE) && layers % 6 == 0;
+    match image_type {
+        ImageType::Type1d if layers > 1 => ImageViewType::Type1dArray,
+        ImageType::Type1d => ImageViewType::Type1d,
+        ImageType::Type2d if cube_compatible && layers == 6 => ImageViewType::Cube,
+        ImageType::Type2d if cube_compatible && image_cube_array => ImageViewType::CubeArray,
+        ImageType::Type2d if layers > 1 => ImageViewType::Type2dArray,
+        ImageType::Type2d => ImageViewType::Type2d,
+        ImageType::Type3d => ImageViewType::Type3d,
+    }
+}
+
+
 #[derive(Debug)]
 pub struct Inner {
     handle: ImageViewHandle,
     device: Device,
     swapchain: Option<SwapchainKhr>,
+    image: Option<Image>,
 }
 
 impl Drop for Inner {
@@ -60,11 +88,24 @@ impl ImageView {
         ImageViewBuilder::new()
     }
 
+    /// Returns a new `ImageViewBuilder` for a view of `image`.
+    ///
+    /// See `ImageViewBuilder::for_image`.
+    pub fn builder_for<'b>(image: &Image) -> ImageViewBuilder<'b> {
+        ImageViewBuilder::for_image(image)
+    }
+
     /// Returns this object's handle.
     pub fn handle(&self) -> ImageViewHandle {
         self.inner.handle
     }
 
+    /// Returns the image this view was created for using
+    /// `ImageViewBuilder::for_image`, if any.
+    pub fn image(&self) -> Option<&Image> {
+        self.inner.image.as_ref()
+    }
+
     /// Returns a reference to the associated device.
     pub fn device(&self) -> &Device {
         &self.inner.device
@@ -85,12 +126,60 @@ unsafe impl<'i> Handle for &'i ImageView {
 #[derive(Debug, Clone)]
 pub struct ImageViewBuilder<'b> {
     create_info: ::ImageViewCreateInfo<'b>,
+    image: Option<Image>,
 }
 
 impl<'b> ImageViewBuilder<'b> {
     /// Returns a new `ImageViewBuilder`.
     pub fn new() -> ImageViewBuilder<'b> {
-        ImageViewBuilder { create_info: ::ImageViewCreateInfo::default() }
+        ImageViewBuilder { create_info: ::ImageViewCreateInfo::default(), image: None }
+    }
+
+    /// Returns a new `ImageViewBuilder` for a view of `image`, with every
+    /// other field derived from it:
+    ///
+    /// * the format of `image`,
+    /// * a view type matching its image type and array layer count (cube
+    ///   views for cube compatible 2D images with six layers, and cube array
+    ///   views for those with another multiple of six layers if the device
+    ///   has the `imageCubeArray` feature enabled),
+    /// * identity component mapping, and
+    /// * a subresource range covering every mip level, array layer, and
+    ///   aspect of the image's format (see `ImageSubresourceRange::format_full`).
+    ///
+    /// Each may be overridden. Views of combined depth/stencil images which
+    /// are to be sampled must select a single aspect.
+    ///
+    /// The view keeps `image` (and, for swapchain images, its swapchain)
+    /// alive until it is dropped.
+    pub fn for_image(image: &Image) -> ImageViewBuilder<'b> {
+        let view_type = default_view_type(image.image_type(), image.flags(),
+            image.array_layers(), image.device().enabled_features().image_cube_array());
+        let mut builder = ImageViewBuilder::for_image_handle(image.handle(), view_type,
+            image.format());
+        builder.image = Some(image.clone());
+        builder
+    }
+
+    /// Returns a new `ImageViewBuilder` for a view of type `view_type` of
+    /// every subresource of the image `image` of format `format`, as
+    /// described by `for_image`.
+    fn for_image_handle(image: ImageHandle, view_type: ImageViewType, format: Format)
+            -> ImageViewBuilder<'b> {
+        let components = ComponentMapping::builder()
+            .r(ComponentSwizzle::Identity)
+            .g(ComponentSwizzle::Identity)
+            .b(ComponentSwizzle::Identity)
+            .a(ComponentSwizzle::Identity)
+            .build();
+
+        let mut builder = ImageViewBuilder::new();
+        builder.image(image)
+            .view_type(view_type)
+            .format(format)
+            .components(components)
+            .subresource_range(ImageSubresourceRange::format_full(format));
+        builder
     }
 
     /// Specifies the image on which the view will be created.
@@ -129,7 +218,17 @@ impl<'b> ImageViewBuilder<'b> {
         self
     }
 
+    /// Creates and returns a new `ImageView`.
+    ///
+    /// `swapchain` is kept alive by the view. It need not be specified for
+    /// views of swapchain images created using `for_image`, which keep their
+    /// image's swapchain alive.
     pub fn build(&self, device: Device, swapchain: Option<SwapchainKhr>) -> VdResult<ImageView> {
+        // Ignore the source image if `image` was later set to another:
+        let image = match self.image {
+            Some(ref i) if i.handle().to_raw() == self.create_info.image() => Some(i.clone()),
+            _ => None,
+        };
         let handle = unsafe { device.create_image_view(&self.create_info, None)? };
 
         Ok(ImageView {
@@ -137,7 +236,73 @@ impl<'b> ImageViewBuilder<'b> {
                 handle,
                 device,
                 swapchain,
+                image,
             })
         })
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::{ImageHandle, ImageType, ImageViewType, ImageCreateFlags, ImageAspectFlags,
+        ImageSubresourceRange, Format, REMAINING_MIP_LEVELS, REMAINING_ARRAY_LAYERS};
+    use super::{default_view_type, ImageViewBuilder};
+
+    #[test]
+    fn cube_arrays_require_feature() {
+        let cube = ImageCreateFlags::CUBE_COMPATIBLE;
+        assert_eq!(default_view_type(ImageType::Type2d, cube, 6, false), ImageViewType::Cube);
+        assert_eq!(default_view_type(ImageType::Type2d, cube, 12, false),
+            ImageViewType::Type2dArray);
+        assert_eq!(default_view_type(ImageType::Type2d, cube, 12, true),
+            ImageViewType::CubeArray);
+        assert_eq!(default_view_type(ImageType::Type2d, cube, 8, true),
+            ImageViewType::Type2dArray);
+        assert_eq!(default_view_type(ImageType::Type2d, ImageCreateFlags::empty(), 6, true),
+            ImageViewType::Type2dArray);
+    }
+
+    #[test]
+    fn non_cube_view_types() {
+        let none = ImageCreateFlags::empty();
+        assert_eq!(default_view_type(ImageType::Type1d, none, 1, false), ImageViewType::Type1d);
+        assert_eq!(default_view_type(ImageType::Type1d, none, 4, false),
+            ImageViewType::Type1dArray);
+        assert_eq!(default_view_type(ImageType::Type2d, none, 1, false), ImageViewType::Type2d);
+        assert_eq!(default_view_type(ImageType::Type3d, none, 1, true), ImageViewType::Type3d);
+    }
+
+    #[test]
+    fn depth_view() {
+        let builder = ImageViewBuilder::for_image_handle(ImageHandle(1), ImageViewType::Type2d,
+            Format::D24UnormS8Uint);
+        let create_info = &builder.create_info;
+        assert_eq!(create_info.image(), 1);
+        assert_eq!(create_info.format(), Format::D24UnormS8Uint);
+        let range = create_info.subresource_range();
+        assert_eq!(range.aspect_mask(), ImageAspectFlags::DEPTH | ImageAspectFlags::STENCIL);
+        assert_eq!(range.level_count(), REMAINING_MIP_LEVELS);
+        assert_eq!(range.layer_count(), REMAINING_ARRAY_LAYERS);
+    }
+
+    #[test]
+    fn single_mip_override() {
+        let mut builder = ImageViewBuilder::for_image_handle(ImageHandle(1),
+            ImageViewType::Type2d, Format::D32Sfloat);
+        builder.subresource_range(ImageSubresourceRange::builder()
+            .aspect_mask(ImageAspectFlags::DEPTH)
+            .base_mip_level(2)
+            .level_count(1)
+            .base_array_layer(0)
+            .layer_count(1)
+            .build());
+        let create_info = &builder.create_info;
+        assert_eq!(create_info.view_type(), ImageViewType::Type2d);
+        assert_eq!(create_info.format(), Format::D32Sfloat);
+        let range = create_info.subresource_range();
+        assert_eq!(range.aspect_mask(), ImageAspectFlags::DEPTH);
+        assert_eq!((range.base_mip_level(), range.level_count()), (2, 1));
+        assert_eq!(range.layer_count(), 1);
+    }
+}
@@ -0,0 +1,100 @@
+//! Indirect command parameters.
+//!
+//! `DrawIndirectCommand`, `DrawIndexedIndirectCommand`, and
+//! `DispatchIndirectCommand` each wrap the corresponding `vks` structure
+//! with `#[repr(C)]` and no other fields, so slices of them may be written
+//! directly into buffers consumed by `CommandBuffer::draw_indirect`,
+//! `CommandBuffer::draw_indexed_indirect`, and
+//! `CommandBuffer::dispatch_indirect`.
+//!
+//! ```rust,ignore
+//! use voodoo::{DrawIndirectCommand, DRAW_INDIRECT_COMMAND_STRIDE};
+//!
+//! let draws = [
+//!     DrawIndirectCommand::new(3, 1, 0, 0),
+//!     DrawIndirectCommand::new(6, 4, 3, 0),
+//! ];
+//!
+//! // `indirect_buffer` was created with `TRANSFER_DST | INDIRECT_BUFFER`.
+//! unsafe {
+//!     command_buffer.update_buffer_typed(&indirect_buffer, 0, &draws);
+//! }
+//! command_buffer.pipeline_barrier(PipelineStageFlags::TRANSFER,
+//!     PipelineStageFlags::DRAW_INDIRECT, DependencyFlags::empty(),
+//!     &[MemoryBarrier::new(AccessFlags::TRANSFER_WRITE, AccessFlags::INDIRECT_COMMAND_READ)],
+//!     &[], &[]);
+//!
+//! // ... begin render pass, bind pipeline and vertex buffers ...
+//!
+//! unsafe {
+//!     command_buffer.draw_indirect(&indirect_buffer, 0, draws.len() as u32,
+//!         DRAW_INDIRECT_COMMAND_STRIDE);
+//! }
+//! ```
+
+use std::mem;
+use ::{DrawIndirectCommand, DrawIndexedIndirectCommand, DispatchIndirectCommand};
+
+
+/// The size in bytes of a `DrawIndirectCommand` and the minimum stride for
+/// `draw_indirect`.
+pub const DRAW_INDIRECT_COMMAND_STRIDE: u32 = 16;
+
+/// The size in bytes of a `DrawIndexedIndirectCommand` and the minimum
+/// stride for `draw_indexed_indirect`.
+pub const DRAW_INDEXED_INDIRECT_COMMAND_STRIDE: u32 = 20;
+
+/// The size in bytes of a `DispatchIndirectCommand`.
+pub const DISPATCH_INDIRECT_COMMAND_SIZE: u32 = 12;
+
+
+// Fail to compile if any of the wrappers differ in size from the
+// specification (a zero-length array cannot be assigned to a non-zero one).
+#[allow(dead_code)]
+const ASSERT_DRAW_INDIRECT_COMMAND_SIZE: [(); DRAW_INDIRECT_COMMAND_STRIDE as usize] =
+    [(); mem::size_of::<DrawIndirectCommand>()];
+#[allow(dead_code)]
+const ASSERT_DRAW_INDEXED_INDIRECT_COMMAND_SIZE: [(); DRAW_INDEXED_INDIRECT_COMMAND_STRIDE as usize] =
+    [(); mem::size_of::<DrawIndexedIndirectCommand>()];
+#[allow(dead_code)]
+const ASSERT_DISPATCH_INDIRECT_COMMAND_SIZE: [(); DISPATCH_INDIRECT_COMMAND_SIZE as usize] =
+    [(); mem::size_of::<DispatchIndirectCommand>()];
+
+
+impl DrawIndirectCommand {
+    /// Returns a new `DrawIndirectCommand`.
+    pub fn new(vertex_count: u32, instance_count: u32, first_vertex: u32, first_instance: u32)
+            -> DrawIndirectCommand {
+        let mut cmd = DrawIndirectCommand::default();
+        cmd.set_vertex_count(vertex_count);
+        cmd.set_instance_count(instance_count);
+        cmd.set_first_vertex(first_vertex);
+        cmd.set_first_instance(first_instance);
+        cmd
+    }
+}
+
+impl DrawIndexedIndirectCommand {
+    /// Returns a new `DrawIndexedIndirectCommand`.
+    pub fn new(index_count: u32, instance_count: u32, first_index: u32, vertex_offset: i32,
+            first_instance: u32) -> DrawIndexedIndirectCommand {
+        let mut cmd = DrawIndexedIndirectCommand::default();
+        cmd.set_index_count(index_count);
+        cmd.set_instance_count(instance_count);
+        cmd.set_first_index(first_index);
+        cmd.set_vertex_offset(vertex_offset);
+        cmd.set_first_instance(first_instance);
+        cmd
+    }
+}
+
+impl DispatchIndirectCommand {
+    /// Returns a new `DispatchIndirectCommand`.
+    pub fn new(x: u32, y: u32, z: u32) -> DispatchIndirectCommand {
+        let mut cmd = DispatchIndirectCommand::default();
+        cmd.set_x(x);
+        cmd.set_y(y);
+        cmd.set_z(z);
+        cmd
+    }
+}
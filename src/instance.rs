@@ -1,12 +1,12 @@
 use std::sync::Arc;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::ptr;
-use std::mem;
 use std::marker::PhantomData;
 use smallvec::SmallVec;
 use libc::{c_char, c_void};
 use vks;
-use ::{error, PRINT, CallResult, VdResult, Loader, ApplicationInfo, PhysicalDeviceHandle,
+use util;
+use ::{error, Error, ErrorKind, VdResult, Loader, ApplicationInfo, PhysicalDeviceHandle,
     PhysicalDevice, CharStrs, FormatProperties, Format, Handle, SurfaceKhrHandle, SurfaceFormatKhr,
     PhysicalDeviceFeatures, PhysicalDeviceProperties, QueueFamilyProperties,
     PhysicalDeviceMemoryProperties, ExtensionProperties, SurfaceCapabilitiesKhr,
@@ -28,10 +28,10 @@ use ::{error, PRINT, CallResult, VdResult, Loader, ApplicationInfo, PhysicalDevi
     SurfaceCapabilities2Khr, SurfaceFormat2Khr, PhysicalDeviceSurfaceInfo2Khr,
     ViSurfaceCreateInfoNn, IosSurfaceCreateInfoMvk, MacOsSurfaceCreateInfoMvk,
     ExternalImageFormatPropertiesNv, ExternalMemoryHandleTypeFlagsNv, SurfaceCapabilities2Ext,
-    RROutput, InstanceCreateInfo};
+    RROutput, InstanceCreateInfo, names};
 
 #[cfg(feature = "experimental")]
-use ::{Rect2d, };
+use ::{CallResult, Rect2d, PhysicalDeviceGroupPropertiesKhx};
 
 #[cfg(feature = "unimplemented")]
 use ::{MultisamplePropertiesExt, };
@@ -45,6 +45,60 @@ unsafe extern "system" fn __debug_callback(_flags: vks::VkDebugReportFlagsEXT,
 }
 
 
+/// Returns true if `name` does not belong to a platform specific surface
+/// extension, or belongs to one for the current target.
+fn is_platform_extension_supported(name: &str) -> bool {
+    let unix_ws = cfg!(all(unix, not(target_os = "android"), not(target_os = "macos"),
+        not(target_os = "ios")));
+    match name {
+        "VK_KHR_mir_surface" | "VK_KHR_wayland_surface" | "VK_KHR_xcb_surface" |
+            "VK_KHR_xlib_surface" | "VK_EXT_acquire_xlib_display" => unix_ws,
+        "VK_KHR_win32_surface" => cfg!(windows),
+        "VK_MVK_ios_surface" => cfg!(target_os = "ios"),
+        "VK_MVK_macos_surface" => cfg!(target_os = "macos"),
+        _ => true,
+    }
+}
+
+/// Returns true if `names` contains `name`.
+unsafe fn contains_name(names: &[*const c_char], name: &CStr) -> bool {
+    names.iter().any(|&n| CStr::from_ptr(n) == name)
+}
+
+/// Appends the best available validation layer and the debug report
+/// extension to `layer_names` and `extension_names` unless already present.
+fn add_validation_names(loader: &Loader, layer_names: &mut SmallVec<[*const c_char; 16]>,
+        extension_names: &mut SmallVec<[*const c_char; 16]>) -> VdResult<()> {
+    let available_layers = loader.instance_layers()?;
+    let is_available = |name: &CStr| available_layers.iter()
+        .any(|props| props.name().as_bytes() == name.to_bytes());
+    let layer = [names::LAYER_KHRONOS_VALIDATION, names::LAYER_LUNARG_STANDARD_VALIDATION]
+        .iter().cloned().find(|&name| is_available(name));
+
+    match layer {
+        Some(layer) => unsafe {
+            debug!("Enabling validation layer: '{}'", layer.to_string_lossy());
+            if !contains_name(layer_names, layer) { layer_names.push(layer.as_ptr()); }
+        },
+        None => warn!("Validation requested but no validation layer is installed. Please \
+            install the Vulkan SDK to enable validation. Continuing without validation."),
+    }
+
+    let debug_report_available = loader.instance_extensions(None)?.iter()
+        .any(|props| props.name().as_bytes() == names::EXT_DEBUG_REPORT.to_bytes());
+    if debug_report_available {
+        unsafe {
+            if !contains_name(extension_names, names::EXT_DEBUG_REPORT) {
+                extension_names.push(names::EXT_DEBUG_REPORT.as_ptr());
+            }
+        }
+    } else {
+        warn!("Validation requested but the 'VK_EXT_debug_report' extension is not available.");
+    }
+    Ok(())
+}
+
+
 /// A Vulkan instance handle.
 //
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -52,6 +106,12 @@ unsafe extern "system" fn __debug_callback(_flags: vks::VkDebugReportFlagsEXT,
 pub struct InstanceHandle(pub(crate) vks::VkInstance);
 
 impl InstanceHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkInstance) -> InstanceHandle {
+        InstanceHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkInstance {
         self.0
@@ -75,19 +135,22 @@ struct Inner {
     // debug_callback: Option<vks::VkDebugReportCallbackEXT>,
     debug_callback: Option<DebugReportCallbackExtHandle>,
     // physical_devices: SmallVec<[PhysicalDevice; 16]>,
+    enabled_extensions: Vec<CString>,
+    owned: bool,
 }
 
 impl Drop for Inner {
     fn drop(&mut self) {
+        if !self.owned { return; }
         unsafe {
-            if PRINT { println!("Destroying debug callback..."); }
+            debug!("Destroying debug callback...");
             if let Some(callback) = self.debug_callback {
                 self.loader.instance_proc_addr_loader().ext_debug_report
                     .vkDestroyDebugReportCallbackEXT(self.handle.0,
                     callback.to_raw(), ptr::null());
             }
 
-            if PRINT { println!("Destroying instance..."); }
+            debug!("Destroying instance...");
             self.loader.destroy_instance(self.handle, None);
         }
     }
@@ -115,6 +178,49 @@ impl Instance {
         InstanceBuilder::new()
     }
 
+    /// Returns the instance extensions available from `loader`, optionally
+    /// limited to those provided by `layer`.
+    ///
+    /// May be called before any instance is created.
+    pub fn available_extensions(loader: &Loader, layer: Option<&CStr>)
+            -> VdResult<Vec<ExtensionProperties>> {
+        loader.instance_extensions(layer)
+    }
+
+    /// Returns the instance layers available from `loader`.
+    ///
+    /// May be called before any instance is created.
+    pub fn available_layers(loader: &Loader) -> VdResult<Vec<LayerProperties>> {
+        loader.instance_layers()
+    }
+
+    /// Creates an `Instance` from a raw handle to an instance created
+    /// elsewhere (by another library, for example).
+    ///
+    /// Only the core instance function table is loaded. If `owned` is
+    /// `false`, the instance will not be destroyed when this `Instance` (and
+    /// all of its clones) are dropped.
+    ///
+    /// ### Safety
+    ///
+    /// `raw` must be a valid instance created using the library loaded by
+    /// `loader`. If `owned` is `true`, ownership is transferred and the
+    /// instance must not be destroyed by anything else.
+    //
+    pub unsafe fn from_raw(mut loader: Loader, raw: vks::VkInstance, owned: bool) -> Instance {
+        let handle = InstanceHandle(raw);
+        loader.instance_proc_addr_loader_mut().load_vk(handle.to_raw());
+        Instance {
+            inner: Arc::new(Inner {
+                handle,
+                loader,
+                debug_callback: None,
+                enabled_extensions: Vec::new(),
+                owned,
+            }),
+        }
+    }
+
     /// Returns the handle to this instance.
     //
     #[inline(always)]
@@ -129,6 +235,20 @@ impl Instance {
         self.inner.loader.instance_proc_addr_loader()
     }
 
+    /// Returns a pointer to the instance-level command named `name`, or
+    /// `None` if it is unknown to the loader or the implementation.
+    ///
+    /// Intended for calling commands this crate does not yet wrap. The
+    /// returned pointer must be transmuted to the command's actual type
+    /// before being called; see the `proc_addr!` macro.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetInstanceProcAddr.html
+    //
+    pub fn get_proc_addr(&self, name: &CStr) -> vks::PFN_vkVoidFunction {
+        let get_instance_proc_addr = self.inner.loader.get_instance_proc_addr()?;
+        unsafe { get_instance_proc_addr(self.inner.handle.to_raw(), name.as_ptr()) }
+    }
+
     /// Returns the `Loader` associated with this instance.
     //
     #[inline]
@@ -136,14 +256,59 @@ impl Instance {
         &self.inner.loader
     }
 
+    /// Returns true if the extension named `name` was enabled, and its
+    /// functions loaded, when building this instance.
+    ///
+    /// Always returns false for instances created with `from_raw`, which
+    /// loads core functions only.
+    pub fn is_extension_enabled(&self, name: &CStr) -> bool {
+        self.inner.enabled_extensions.iter().any(|ext| ext.as_c_str() == name)
+    }
+
+    /// Returns the names of the extensions enabled when building this
+    /// instance.
+    ///
+    /// Always empty for instances created with `from_raw`.
+    pub fn enabled_extensions<'a>(&'a self) -> impl Iterator<Item=&'a CStr> + 'a {
+        self.inner.enabled_extensions.iter().map(|ext| ext.as_c_str())
+    }
+
+    /// Returns an `ExtensionNotEnabled` error if `name` is not enabled.
+    fn require_extension(&self, name: &'static CStr, fn_name: &'static str) -> VdResult<()> {
+        if self.is_extension_enabled(name) {
+            Ok(())
+        } else {
+            Err(Error { kind: ErrorKind::ExtensionNotEnabled { name, fn_name }, cause: None })
+        }
+    }
+
     /// Returns a list of all physical devices available.
-    //
-    #[inline]
-    pub fn physical_devices(&self) -> VdResult<SmallVec<[PhysicalDevice; 16]>> {
-        Ok(self.loader().enumerate_physical_devices(self.inner.handle)?
-            .iter().map(|&pdr| {
-                PhysicalDevice::from_parts(self.clone(), PhysicalDeviceHandle(pdr))
-            }).collect())
+    ///
+    /// Devices are enumerated anew on each call so that devices attached or
+    /// removed since a previous call (external GPUs, for example) are
+    /// reflected.
+    ///
+    /// Returns an `ErrorKind::NoPhysicalDevices` error if there are none.
+    pub fn physical_devices(&self) -> VdResult<Vec<PhysicalDevice>> {
+        let handles = self.loader().enumerate_physical_devices(self.inner.handle)?;
+        if handles.is_empty() {
+            return Err(Error { kind: ErrorKind::NoPhysicalDevices, cause: None });
+        }
+        Ok(handles.into_iter().map(|pdr| {
+            PhysicalDevice::from_parts(self.clone(), PhysicalDeviceHandle(pdr))
+        }).collect())
+    }
+
+    /// Returns a list of all physical devices available along with the
+    /// properties of each.
+    ///
+    /// See `::physical_devices`.
+    pub fn physical_devices_with_properties(&self)
+            -> VdResult<Vec<(PhysicalDevice, PhysicalDeviceProperties)>> {
+        Ok(self.physical_devices()?.into_iter().map(|pd| {
+            let properties = pd.properties();
+            (pd, properties)
+        }).collect())
     }
 
     /// Reports the capabilities of a physical device.
@@ -156,7 +321,7 @@ impl Instance {
             -> PhysicalDeviceFeatures
             where Pd: Handle<Target=PhysicalDeviceHandle> {
         unsafe {
-            let mut features: vks::VkPhysicalDeviceFeatures = mem::uninitialized();
+            let mut features: vks::VkPhysicalDeviceFeatures = Default::default();
             self.proc_addr_loader().vk.vkGetPhysicalDeviceFeatures(physical_device.handle().to_raw(),
                 &mut features);
             PhysicalDeviceFeatures::from_raw(features)
@@ -174,7 +339,7 @@ impl Instance {
             -> FormatProperties
             where Pd: Handle<Target=PhysicalDeviceHandle> {
         unsafe {
-            let mut props: FormatProperties = mem::uninitialized();
+            let mut props: FormatProperties = Default::default();
             self.proc_addr_loader().vk.vkGetPhysicalDeviceFormatProperties(physical_device.handle().to_raw(),
                 format.into(), &mut props as *mut _ as *mut vks::VkFormatProperties);
             props
@@ -194,7 +359,7 @@ impl Instance {
             -> VdResult<ImageFormatProperties>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
         unsafe {
-            let mut image_format_properties = mem::uninitialized();
+            let mut image_format_properties = Default::default();
             let result = self.proc_addr_loader().vk.vkGetPhysicalDeviceImageFormatProperties(
                 physical_device.handle().to_raw(), format.into(), type_.into(),
                 tiling.into(), usage.bits(), flags.bits(), &mut image_format_properties);
@@ -212,7 +377,7 @@ impl Instance {
             -> PhysicalDeviceProperties
             where Pd: Handle<Target=PhysicalDeviceHandle> {
         unsafe {
-            let mut device_properties: vks::VkPhysicalDeviceProperties = mem::uninitialized();
+            let mut device_properties: vks::VkPhysicalDeviceProperties = Default::default();
             self.proc_addr_loader().vk.vkGetPhysicalDeviceProperties(physical_device.handle().to_raw(),
                 &mut device_properties);
             PhysicalDeviceProperties::from_raw(device_properties)
@@ -229,18 +394,13 @@ impl Instance {
     pub fn get_physical_device_queue_family_properties<Pd>(&self, physical_device: Pd)
             -> VdResult<SmallVec<[QueueFamilyProperties; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut queue_family_count = 0u32;
-        let mut queue_families = SmallVec::<[::QueueFamilyProperties; 16]>::new();
-        unsafe {
-            self.proc_addr_loader().vk.vkGetPhysicalDeviceQueueFamilyProperties(
-                physical_device.handle().to_raw(), &mut queue_family_count, ptr::null_mut());
-            queue_families.reserve_exact(queue_family_count as usize);
-            queue_families.set_len(queue_family_count as usize);
-            self.proc_addr_loader().vk.vkGetPhysicalDeviceQueueFamilyProperties(
-                physical_device.handle().to_raw(), &mut queue_family_count,
-                queue_families.as_mut_ptr() as *mut vks::VkQueueFamilyProperties);
-        }
-        if PRINT {  println!("Physical device queue family count: {:?}", queue_families.len()); }
+        let queue_families = unsafe {
+            util::enumerate_void::<[QueueFamilyProperties; 16], _>(|count, p| {
+                self.proc_addr_loader().vk.vkGetPhysicalDeviceQueueFamilyProperties(
+                    physical_device.handle().to_raw(), count, p as *mut vks::VkQueueFamilyProperties)
+            })
+        };
+        trace!("Physical device queue family count: {:?}", queue_families.len());
         Ok(queue_families)
     }
 
@@ -256,7 +416,7 @@ impl Instance {
             where Pd: Handle<Target=PhysicalDeviceHandle> {
         let mut mem_props: vks::VkPhysicalDeviceMemoryProperties;
         unsafe {
-            mem_props = mem::uninitialized();
+            mem_props = Default::default();
             self.proc_addr_loader().vk.vkGetPhysicalDeviceMemoryProperties(
                 physical_device.handle().to_raw(), &mut mem_props);
             PhysicalDeviceMemoryProperties::from_raw(mem_props)
@@ -304,22 +464,12 @@ impl Instance {
             -> VdResult<SmallVec<[ExtensionProperties; 64]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
         let layer_name = layer_name.map(|ln| ln.as_ptr()).unwrap_or(ptr::null());
-        let mut property_count = 0u32;
-        let mut properties = SmallVec::<[ExtensionProperties; 64]>::new();
         unsafe {
-            let result = self.proc_addr_loader().vk.vkEnumerateDeviceExtensionProperties(
-                physical_device.handle().to_raw(), layer_name, &mut property_count, ptr::null_mut());
-            error::check(result, "vkEnumerateDeviceExtensionProperties", ())?;
-            properties.reserve_exact(property_count as usize);
-            properties.set_len(property_count as usize);
-            loop {
-                let result = self.proc_addr_loader().vk.vkEnumerateDeviceExtensionProperties(
-                    physical_device.handle().to_raw(), layer_name, &mut property_count,
-                    properties.as_mut_ptr() as *mut vks::VkExtensionProperties);
-                if result != CallResult::Incomplete as i32 {
-                    return error::check(result, "vkEnumerateDeviceExtensionProperties", properties);
-                }
-            }
+            util::enumerate("vkEnumerateDeviceExtensionProperties", |count, p| {
+                self.proc_addr_loader().vk.vkEnumerateDeviceExtensionProperties(
+                    physical_device.handle().to_raw(), layer_name, count,
+                    p as *mut vks::VkExtensionProperties)
+            })
         }
     }
 
@@ -329,25 +479,17 @@ impl Instance {
     //
     // *PFN_vkEnumerateDeviceLayerProperties)(VkPhysicalDevice physicalDevice,
     // uint32_t* pPropertyCount, VkLayerProperties* pProperties);
+    //
+    // Layer properties are over 500 bytes each and device layers are
+    // deprecated, so few (if any) are expected.
     pub fn enumerate_device_layer_properties<Pd>(&self, physical_device: Pd)
-            -> VdResult<SmallVec<[LayerProperties; 64]>>
+            -> VdResult<SmallVec<[LayerProperties; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut property_count = 0u32;
-        let mut properties = SmallVec::<[LayerProperties; 64]>::new();
         unsafe {
-            let result = self.proc_addr_loader().vk.vkEnumerateDeviceLayerProperties(
-                physical_device.handle().to_raw(), &mut property_count, ptr::null_mut());
-            error::check(result, "vkEnumerateDeviceLayerProperties", ())?;
-            properties.reserve_exact(property_count as usize);
-            properties.set_len(property_count as usize);
-            loop {
-                let result = self.proc_addr_loader().vk.vkEnumerateDeviceLayerProperties(
-                    physical_device.handle().to_raw(), &mut property_count,
-                    properties.as_mut_ptr() as *mut vks::VkLayerProperties);
-                if result != CallResult::Incomplete as i32 {
-                    return error::check(result, "vkEnumerateDeviceLayerProperties", properties);
-                }
-            }
+            util::enumerate("vkEnumerateDeviceLayerProperties", |count, p| {
+                self.proc_addr_loader().vk.vkEnumerateDeviceLayerProperties(
+                    physical_device.handle().to_raw(), count, p as *mut vks::VkLayerProperties)
+            })
         }
     }
 
@@ -361,23 +503,17 @@ impl Instance {
     // tiling, uint32_t* pPropertyCount, VkSparseImageFormatProperties*
     // pProperties);
     pub fn get_physical_device_sparse_image_format_properties<Pd>(&self, physical_device: Pd,
-            format: Format, type_: ImageType, samples: SampleCountFlags, usage: ImageCreateFlags,
+            format: Format, type_: ImageType, samples: SampleCountFlags, usage: ImageUsageFlags,
             tiling: ImageTiling) -> SmallVec<[SparseImageFormatProperties; 8]>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut property_count = 0u32;
-        let mut properties = SmallVec::<[SparseImageFormatProperties; 8]>::new();
         unsafe {
-            self.proc_addr_loader().vk.vkGetPhysicalDeviceSparseImageFormatProperties(
-                physical_device.handle().to_raw(), format.into(), type_.into(),
-                samples.bits(), tiling.into(), usage.bits(), &mut property_count, ptr::null_mut());
-            properties.reserve_exact(property_count as usize);
-            properties.set_len(property_count as usize);
-            self.proc_addr_loader().vk.vkGetPhysicalDeviceSparseImageFormatProperties(
-                physical_device.handle().to_raw(), format.into(), type_.into(),
-                samples.bits(), tiling.into(), usage.bits(), &mut property_count,
-                properties.as_mut_ptr() as *mut vks::VkSparseImageFormatProperties);
+            util::enumerate_void(|count, p| {
+                self.proc_addr_loader().vk.vkGetPhysicalDeviceSparseImageFormatProperties(
+                    physical_device.handle().to_raw(), format.into(), type_.into(),
+                    samples.bits(), usage.bits(), tiling.into(), count,
+                    p as *mut vks::VkSparseImageFormatProperties)
+            })
         }
-        properties
     }
 
     /// Destroys a VkSurfaceKHR object.
@@ -403,6 +539,7 @@ impl Instance {
     pub unsafe fn get_physical_device_surface_support_khr<Pd, Sk>(&self, physical_device: Pd,
             queue_family_index: u32, surface: Sk) -> VdResult<bool>
             where Pd: Handle<Target=PhysicalDeviceHandle>, Sk: Handle<Target=SurfaceKhrHandle> {
+        self.require_extension(names::KHR_SURFACE, "vkGetPhysicalDeviceSurfaceSupportKHR")?;
         let mut supported: vks::VkBool32 = vks::VK_FALSE;
         let result = self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfaceSupportKHR(
             physical_device.handle().to_raw(), queue_family_index, surface.handle().to_raw(), &mut supported);
@@ -419,7 +556,8 @@ impl Instance {
     pub unsafe fn get_physical_device_surface_capabilities_khr<Pd, Sk>(&self, physical_device: Pd,
             surface: Sk) -> VdResult<SurfaceCapabilitiesKhr>
             where Pd: Handle<Target=PhysicalDeviceHandle>, Sk: Handle<Target=SurfaceKhrHandle> {
-        let mut capabilities = mem::uninitialized();
+        self.require_extension(names::KHR_SURFACE, "vkGetPhysicalDeviceSurfaceCapabilitiesKHR")?;
+        let mut capabilities = Default::default();
         let result = self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfaceCapabilitiesKHR(
             physical_device.handle().to_raw(), surface.handle().to_raw(), &mut capabilities);
         error::check(result, "vkGetPhysicalDeviceSurfaceCapabilitiesKHR",
@@ -436,22 +574,14 @@ impl Instance {
     pub unsafe fn get_physical_device_surface_formats_khr<Pd, Sk>(&self, physical_device: Pd,
             surface: Sk) -> VdResult<SmallVec<[SurfaceFormatKhr; 64]>>
             where Pd: Handle<Target=PhysicalDeviceHandle>, Sk: Handle<Target=SurfaceKhrHandle> {
-        let mut format_count = 0u32;
-        let mut formats: SmallVec<[::SurfaceFormatKhr; 64]> = SmallVec::new();
-        let result = self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfaceFormatsKHR(
-            physical_device.handle().to_raw(), surface.handle().to_raw(), &mut format_count, ptr::null_mut());
-        error::check(result, "vkGetPhysicalDeviceSurfaceFormatsKHR", ())?;
-        formats.reserve_exact(format_count as usize);
-        formats.set_len(format_count as usize);
-        if format_count != 0 {
-            let result = self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfaceFormatsKHR(
-                physical_device.handle().to_raw(), surface.handle().to_raw(), &mut format_count,
-                formats.as_mut_ptr() as *mut vks::VkSurfaceFormatKHR);
-            if PRINT { println!("Physical device format count: {:?}", formats.len()); }
-            error::check(result, "vkGetPhysicalDeviceSurfaceFormatsKHR", formats)
-        } else {
-            Ok(formats)
-        }
+        self.require_extension(names::KHR_SURFACE, "vkGetPhysicalDeviceSurfaceFormatsKHR")?;
+        let formats = util::enumerate("vkGetPhysicalDeviceSurfaceFormatsKHR", |count, p| {
+            self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfaceFormatsKHR(
+                physical_device.handle().to_raw(), surface.handle().to_raw(), count,
+                p as *mut vks::VkSurfaceFormatKHR)
+        })?;
+        trace!("Physical device format count: {:?}", formats.len());
+        Ok(formats)
     }
 
     /// Queries supported presentation modes.
@@ -464,24 +594,14 @@ impl Instance {
     pub unsafe fn get_physical_device_surface_present_modes_khr<Pd, Sk>(&self, physical_device: Pd,
             surface: Sk) -> VdResult<SmallVec<[PresentModeKhr; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle>, Sk: Handle<Target=SurfaceKhrHandle> {
-        let mut present_mode_count = 0u32;
-        let mut present_modes: SmallVec<[PresentModeKhr; 16]> = SmallVec::new();
-        let result = self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfacePresentModesKHR(
-            physical_device.handle().to_raw(), surface.handle().to_raw(), &mut present_mode_count, ptr::null_mut());
-        error::check(result, "vkGetPhysicalDeviceSurfacePresentModesKHR", ())?;
-        present_modes.reserve_exact(present_mode_count as usize);
-        present_modes.set_len(present_mode_count as usize);
-        if present_mode_count != 0 {
-            loop {
-                let result = self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfacePresentModesKHR(
-                    physical_device.handle().to_raw(), surface.handle().to_raw(),
-                    &mut present_mode_count, present_modes.as_mut_ptr() as *mut _);
-                if result != CallResult::Incomplete as i32 {
-                    if PRINT { println!("Physical device present mode count: {:?}", present_modes.len()); }
-                    return error::check(result, "vkGetPhysicalDeviceSurfacePresentModesKHR", present_modes);
-                }
-            }
-        }
+        self.require_extension(names::KHR_SURFACE, "vkGetPhysicalDeviceSurfacePresentModesKHR")?;
+        let present_modes = util::enumerate("vkGetPhysicalDeviceSurfacePresentModesKHR",
+                |count, p| {
+            self.proc_addr_loader().khr_surface.vkGetPhysicalDeviceSurfacePresentModesKHR(
+                physical_device.handle().to_raw(), surface.handle().to_raw(), count,
+                p as *mut vks::VkPresentModeKHR)
+        })?;
+        trace!("Physical device present mode count: {:?}", present_modes.len());
         Ok(present_modes)
     }
 
@@ -495,21 +615,11 @@ impl Instance {
     pub unsafe fn get_physical_device_display_properties_khr<Pd>(&self, physical_device: Pd)
             -> VdResult<SmallVec<[DisplayPropertiesKhr; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut property_count = 0u32;
-        let mut properties: SmallVec<[DisplayPropertiesKhr; 16]> = SmallVec::new();
-        let result = self.proc_addr_loader().khr_display.vkGetPhysicalDeviceDisplayPropertiesKHR(
-            physical_device.handle().to_raw(), &mut property_count, ptr::null_mut());
-        error::check(result, "vkGetPhysicalDeviceDisplayPropertiesKHR", ())?;
-        properties.reserve_exact(property_count as usize);
-        properties.set_len(property_count as usize);
-        loop {
-            let result = self.proc_addr_loader().khr_display.vkGetPhysicalDeviceDisplayPropertiesKHR(
-                physical_device.handle().to_raw(), &mut property_count,
-                properties.as_mut_ptr() as *mut vks::VkDisplayPropertiesKHR);
-            if result != CallResult::Incomplete as i32 {
-                return error::check(result, "vkGetPhysicalDeviceDisplayPropertiesKHR", properties);
-            }
-        }
+        util::enumerate("vkGetPhysicalDeviceDisplayPropertiesKHR", |count, p| {
+            self.proc_addr_loader().khr_display.vkGetPhysicalDeviceDisplayPropertiesKHR(
+                physical_device.handle().to_raw(), count,
+                p as *mut vks::VkDisplayPropertiesKHR)
+        })
     }
 
     /// Queries the plane properties.
@@ -522,21 +632,11 @@ impl Instance {
     pub unsafe fn get_physical_device_display_plane_properties_khr<Pd>(&self, physical_device: Pd)
             -> VdResult<SmallVec<[DisplayPlanePropertiesKhr; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut property_count = 0u32;
-        let mut properties: SmallVec<[DisplayPlanePropertiesKhr; 16]> = SmallVec::new();
-        let result = self.proc_addr_loader().khr_display.vkGetPhysicalDeviceDisplayPlanePropertiesKHR(
-            physical_device.handle().to_raw(), &mut property_count, ptr::null_mut());
-        error::check(result, "vkGetPhysicalDeviceDisplayPlanePropertiesKHR", ())?;
-        properties.reserve_exact(property_count as usize);
-        properties.set_len(property_count as usize);
-        loop {
-            let result = self.proc_addr_loader().khr_display.vkGetPhysicalDeviceDisplayPlanePropertiesKHR(
-                physical_device.handle().to_raw(), &mut property_count,
-                properties.as_mut_ptr() as *mut vks::VkDisplayPlanePropertiesKHR);
-            if result != CallResult::Incomplete as i32 {
-                return error::check(result, "vkGetPhysicalDeviceDisplayPlanePropertiesKHR", properties);
-            }
-        }
+        util::enumerate("vkGetPhysicalDeviceDisplayPlanePropertiesKHR", |count, p| {
+            self.proc_addr_loader().khr_display.vkGetPhysicalDeviceDisplayPlanePropertiesKHR(
+                physical_device.handle().to_raw(), count,
+                p as *mut vks::VkDisplayPlanePropertiesKHR)
+        })
     }
 
     /// Queries the list of displays a plane supports.
@@ -549,21 +649,11 @@ impl Instance {
     pub unsafe fn get_display_plane_supported_displays_khr<Pd>(&self, physical_device: Pd, plane_index: u32)
             -> VdResult<SmallVec<[DisplayKhr; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut display_count = 0u32;
-        let mut displays: SmallVec<[DisplayKhr; 16]> = SmallVec::new();
-        let result = self.proc_addr_loader().khr_display.vkGetDisplayPlaneSupportedDisplaysKHR(
-            physical_device.handle().to_raw(), plane_index, &mut display_count, ptr::null_mut());
-        error::check(result, "vkGetDisplayPlaneSupportedDisplaysKHR", ())?;
-        displays.reserve_exact(display_count as usize);
-        displays.set_len(display_count as usize);
-        loop {
-            let result = self.proc_addr_loader().khr_display.vkGetDisplayPlaneSupportedDisplaysKHR(
-                physical_device.handle().to_raw(), plane_index, &mut display_count,
-                displays.as_mut_ptr() as *mut vks::VkDisplayKHR);
-            if result != CallResult::Incomplete as i32 {
-                return error::check(result, "vkGetDisplayPlaneSupportedDisplaysKHR", displays);
-            }
-        }
+        util::enumerate("vkGetDisplayPlaneSupportedDisplaysKHR", |count, p| {
+            self.proc_addr_loader().khr_display.vkGetDisplayPlaneSupportedDisplaysKHR(
+                physical_device.handle().to_raw(), plane_index, count,
+                p as *mut vks::VkDisplayKHR)
+        })
     }
 
     /// Queries the set of mode properties supported by the display.
@@ -576,21 +666,11 @@ impl Instance {
     pub unsafe fn get_display_mode_properties_khr<Pd, D>(&self, physical_device: Pd, display: D)
             -> VdResult<SmallVec<[DisplayModePropertiesKhr; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle>, D: Handle<Target=DisplayKhrHandle> {
-        let mut property_count = 0u32;
-        let mut properties: SmallVec<[DisplayModePropertiesKhr; 16]> = SmallVec::new();
-        let result = self.proc_addr_loader().khr_display.vkGetDisplayModePropertiesKHR(
-            physical_device.handle().to_raw(), display.handle().to_raw(), &mut property_count, ptr::null_mut());
-        error::check(result, "vkGetDisplayModePropertiesKHR", ())?;
-        properties.reserve_exact(property_count as usize);
-        properties.set_len(property_count as usize);
-        loop {
-            let result = self.proc_addr_loader().khr_display.vkGetDisplayModePropertiesKHR(
-                physical_device.handle().to_raw(), display.handle().to_raw(), &mut property_count,
-                properties.as_mut_ptr() as *mut vks::VkDisplayModePropertiesKHR);
-            if result != CallResult::Incomplete as i32 {
-                return error::check(result, "vkGetDisplayModePropertiesKHR", properties);
-            }
-        }
+        util::enumerate("vkGetDisplayModePropertiesKHR", |count, p| {
+            self.proc_addr_loader().khr_display.vkGetDisplayModePropertiesKHR(
+                physical_device.handle().to_raw(), display.handle().to_raw(), count,
+                p as *mut vks::VkDisplayModePropertiesKHR)
+        })
     }
 
     /// Creates a display mode.
@@ -622,7 +702,7 @@ impl Instance {
             plane_index: u32)
             -> VdResult<DisplayPlaneCapabilitiesKhr>
             where Pd: Handle<Target=PhysicalDeviceHandle>, M: Handle<Target=DisplayModeKhrHandle> {
-        let mut capabilities = mem::uninitialized();
+        let mut capabilities = Default::default();
         let result = self.proc_addr_loader().khr_display.vkGetDisplayPlaneCapabilitiesKHR(physical_device.handle().to_raw(),
             mode.handle().to_raw(), plane_index, &mut capabilities);
         error::check(result, "vkGetDisplayPlaneCapabilitiesKHR",
@@ -656,6 +736,7 @@ impl Instance {
     pub unsafe fn create_xlib_surface_khr(&self, create_info: &XlibSurfaceCreateInfoKhr,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SurfaceKhrHandle> {
+        self.require_extension(names::KHR_XLIB_SURFACE, "vkCreateXlibSurfaceKHR")?;
         let allocator = allocator.unwrap_or(ptr::null());
         let mut surface = 0;
         let result = self.proc_addr_loader().khr_xlib_surface.vkCreateXlibSurfaceKHR(self.handle().to_raw(),
@@ -671,11 +752,12 @@ impl Instance {
     // physicalDevice, uint32_t queueFamilyIndex, Display* dpy, VisualID
     // visualID);
     pub unsafe fn get_physical_device_xlib_presentation_support_khr<Pd>(&self, physical_device: Pd,
-            queue_family_index: u32, dpy: *mut Display, visual_id: VisualID) -> bool
+            queue_family_index: u32, dpy: *mut Display, visual_id: VisualID) -> VdResult<bool>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_XLIB_SURFACE, "vkGetPhysicalDeviceXlibPresentationSupportKHR")?;
         let result = self.proc_addr_loader().khr_xlib_surface.vkGetPhysicalDeviceXlibPresentationSupportKHR(
             physical_device.handle().to_raw(), queue_family_index, dpy, visual_id);
-        result != 0
+        Ok(result != 0)
     }
 
     /// Creates a `SurfaceKhrHandle` object for a X11 window, using the XCB client-side library.
@@ -688,6 +770,7 @@ impl Instance {
     pub unsafe fn create_xcb_surface_khr(&self, create_info: &XcbSurfaceCreateInfoKhr,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SurfaceKhrHandle> {
+        self.require_extension(names::KHR_XCB_SURFACE, "vkCreateXcbSurfaceKHR")?;
         let allocator = allocator.unwrap_or(ptr::null());
         let mut surface = 0;
         let result = self.proc_addr_loader().khr_xcb_surface.vkCreateXcbSurfaceKHR(self.handle().to_raw(),
@@ -704,12 +787,12 @@ impl Instance {
     // connection, xcb_visualid_t visual_id);
     pub unsafe fn get_physical_device_xcb_presentation_support_khr<Pd>(&self, physical_device: Pd,
         queue_family_index: u32, connection: *mut xcb_connection_t, visual_id: xcb_visualid_t)
-             -> bool
+             -> VdResult<bool>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_XCB_SURFACE, "vkGetPhysicalDeviceXcbPresentationSupportKHR")?;
         let result = self.proc_addr_loader().khr_xcb_surface.vkGetPhysicalDeviceXcbPresentationSupportKHR(
             physical_device.handle().to_raw(), queue_family_index, connection, visual_id);
-        result != 0
-
+        Ok(result != 0)
     }
 
     /// Creates a `SurfaceKhrHandle` object for a Wayland window.
@@ -722,6 +805,7 @@ impl Instance {
     pub unsafe fn create_wayland_surface_khr(&self, create_info: &WaylandSurfaceCreateInfoKhr,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SurfaceKhrHandle> {
+        self.require_extension(names::KHR_WAYLAND_SURFACE, "vkCreateWaylandSurfaceKHR")?;
         let allocator = allocator.unwrap_or(ptr::null());
         let mut surface = 0;
         let result = self.proc_addr_loader().khr_wayland_surface.vkCreateWaylandSurfaceKHR(self.handle().to_raw(),
@@ -736,11 +820,12 @@ impl Instance {
     // *PFN_vkGetPhysicalDeviceWaylandPresentationSupportKHR)(VkPhysicalDevice
     // physicalDevice, uint32_t queueFamilyIndex, struct wl_display* display);
     pub unsafe fn get_physical_device_wayland_presentation_support_khr<Pd>(&self,
-            physical_device: Pd, queue_family_index: u32, display: *mut wl_display) -> bool
+            physical_device: Pd, queue_family_index: u32, display: *mut wl_display) -> VdResult<bool>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_WAYLAND_SURFACE, "vkGetPhysicalDeviceWaylandPresentationSupportKHR")?;
         let result = self.proc_addr_loader().khr_wayland_surface.vkGetPhysicalDeviceWaylandPresentationSupportKHR(
             physical_device.handle().to_raw(), queue_family_index, display);
-        result != 0
+        Ok(result != 0)
     }
 
     /// Creates a `SurfaceKhrHandle` object for a Mir window.
@@ -753,6 +838,7 @@ impl Instance {
     pub unsafe fn create_mir_surface_khr(&self, create_info: &MirSurfaceCreateInfoKhr,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SurfaceKhrHandle> {
+        self.require_extension(names::KHR_MIR_SURFACE, "vkCreateMirSurfaceKHR")?;
         let allocator = allocator.unwrap_or(ptr::null());
         let mut surface = 0;
         let result = self.proc_addr_loader().khr_mir_surface.vkCreateMirSurfaceKHR(self.handle().to_raw(),
@@ -767,11 +853,12 @@ impl Instance {
     // *PFN_vkGetPhysicalDeviceMirPresentationSupportKHR)(VkPhysicalDevice
     // physicalDevice, uint32_t queueFamilyIndex, MirConnection* connection);
     pub unsafe fn get_physical_device_mir_presentation_support_khr<Pd>(&self,
-            physical_device: Pd, queue_family_index: u32, connection: *mut MirConnection) -> bool
+            physical_device: Pd, queue_family_index: u32, connection: *mut MirConnection) -> VdResult<bool>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_MIR_SURFACE, "vkGetPhysicalDeviceMirPresentationSupportKHR")?;
         let result = self.proc_addr_loader().khr_mir_surface.vkGetPhysicalDeviceMirPresentationSupportKHR(
             physical_device.handle().to_raw(), queue_family_index, connection);
-        result != 0
+        Ok(result != 0)
     }
 
     /// Create a `SurfaceKhrHandle` object for an Android native window.
@@ -784,6 +871,7 @@ impl Instance {
     pub unsafe fn create_android_surface_khr(&self, create_info: &AndroidSurfaceCreateInfoKhr,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             -> VdResult<SurfaceKhrHandle> {
+        self.require_extension(names::KHR_ANDROID_SURFACE, "vkCreateAndroidSurfaceKHR")?;
         let allocator = allocator.unwrap_or(ptr::null());
         let mut surface = 0;
         let result = self.proc_addr_loader().khr_android_surface.vkCreateAndroidSurfaceKHR(self.handle().to_raw(),
@@ -800,6 +888,7 @@ impl Instance {
     // pAllocator, VkSurfaceKHR* pSurface);
     pub unsafe fn create_win32_surface_khr(&self, create_info: &Win32SurfaceCreateInfoKhr,
             allocator: Option<*const vks::VkAllocationCallbacks>) -> VdResult<SurfaceKhrHandle> {
+        self.require_extension(names::KHR_WIN32_SURFACE, "vkCreateWin32SurfaceKHR")?;
         let allocator = allocator.unwrap_or(ptr::null());
         let mut surface = 0;
         let result = self.proc_addr_loader().khr_win32_surface.vkCreateWin32SurfaceKHR(
@@ -814,11 +903,12 @@ impl Instance {
     // *PFN_vkGetPhysicalDeviceWin32PresentationSupportKHR)(VkPhysicalDevice
     // physicalDevice, uint32_t queueFamilyIndex);
     pub unsafe fn get_physical_device_win32_presentation_support_khr<Pd>(&self, physical_device: Pd,
-            queue_family_index: u32) -> bool
+            queue_family_index: u32) -> VdResult<bool>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_WIN32_SURFACE, "vkGetPhysicalDeviceWin32PresentationSupportKHR")?;
         let result = self.proc_addr_loader().khr_win32_surface.vkGetPhysicalDeviceWin32PresentationSupportKHR(
             physical_device.handle().to_raw(), queue_family_index);
-        result != 0
+        Ok(result != 0)
     }
 
     ///
@@ -828,12 +918,28 @@ impl Instance {
     // *PFN_vkGetPhysicalDeviceFeatures2KHR)(VkPhysicalDevice physicalDevice,
     // VkPhysicalDeviceFeatures2KHR* pFeatures);
     pub unsafe fn get_physical_device_features_2_khr<Pd>(&self, physical_device: Pd)
-            -> PhysicalDeviceFeatures2Khr
+            -> VdResult<PhysicalDeviceFeatures2Khr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut features = mem::uninitialized();
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceFeatures2KHR")?;
+        let mut features = Default::default();
         self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceFeatures2KHR(
             physical_device.handle().to_raw(), &mut features);
-        PhysicalDeviceFeatures2Khr::from_raw(features)
+        Ok(PhysicalDeviceFeatures2Khr::from_raw(features))
+    }
+
+    /// Queries features into `features`, including those of any extension
+    /// feature structures chained onto it with `set_next`.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0-extensions/man/html/vkGetPhysicalDeviceFeatures2KHR.html
+    //
+    pub unsafe fn get_physical_device_features_2_khr_into<Pd>(&self, physical_device: Pd,
+            features: &mut PhysicalDeviceFeatures2Khr) -> VdResult<()>
+            where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceFeatures2KHR")?;
+        self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceFeatures2KHR(
+            physical_device.handle().to_raw(),
+            features as *mut _ as *mut vks::VkPhysicalDeviceFeatures2KHR);
+        Ok(())
     }
 
     ///
@@ -843,12 +949,28 @@ impl Instance {
     // *PFN_vkGetPhysicalDeviceProperties2KHR)(VkPhysicalDevice
     // physicalDevice, VkPhysicalDeviceProperties2KHR* pProperties);
     pub unsafe fn get_physical_device_properties_2_khr<Pd>(&self, physical_device: Pd)
-            -> PhysicalDeviceProperties2Khr
+            -> VdResult<PhysicalDeviceProperties2Khr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut properties = mem::uninitialized();
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceProperties2KHR")?;
+        let mut properties = Default::default();
         self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceProperties2KHR(
             physical_device.handle().to_raw(), &mut properties);
-        PhysicalDeviceProperties2Khr::from_raw(properties)
+        Ok(PhysicalDeviceProperties2Khr::from_raw(properties))
+    }
+
+    /// Queries properties into `properties`, including those of any extension
+    /// property structures chained onto it with `set_next`.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0-extensions/man/html/vkGetPhysicalDeviceProperties2KHR.html
+    //
+    pub unsafe fn get_physical_device_properties_2_khr_into<Pd>(&self, physical_device: Pd,
+            properties: &mut PhysicalDeviceProperties2Khr) -> VdResult<()>
+            where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceProperties2KHR")?;
+        self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceProperties2KHR(
+            physical_device.handle().to_raw(),
+            properties as *mut _ as *mut vks::VkPhysicalDeviceProperties2KHR);
+        Ok(())
     }
 
     ///
@@ -859,13 +981,14 @@ impl Instance {
     // physicalDevice, VkFormat format, VkFormatProperties2KHR*
     // pFormatProperties);
     pub unsafe fn get_physical_device_format_properties_2_khr<Pd>(&self, physical_device: Pd, format: Format)
-            -> FormatProperties2Khr
+            -> VdResult<FormatProperties2Khr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut props: FormatProperties2Khr = mem::uninitialized();
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceFormatProperties2KHR")?;
+        let mut props: FormatProperties2Khr = Default::default();
         self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceFormatProperties2KHR(
             physical_device.handle().to_raw(),
             format.into(), &mut props as *mut _ as *mut vks::VkFormatProperties2KHR);
-        props
+        Ok(props)
     }
 
     ///
@@ -879,7 +1002,8 @@ impl Instance {
             image_format_info: &PhysicalDeviceImageFormatInfo2Khr)
             -> VdResult<ImageFormatProperties2Khr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut image_format_properties = mem::uninitialized();
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceImageFormatProperties2KHR")?;
+        let mut image_format_properties = Default::default();
         let result = self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceImageFormatProperties2KHR(
             physical_device.handle().to_raw(), image_format_info.as_raw(),
             &mut image_format_properties);
@@ -897,16 +1021,12 @@ impl Instance {
     pub unsafe fn get_physical_device_queue_family_properties_2_khr<Pd>(&self, physical_device: Pd)
             -> VdResult<SmallVec<[QueueFamilyProperties2Khr; 16]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut queue_family_count = 0u32;
-        let mut queue_families = SmallVec::<[QueueFamilyProperties2Khr; 16]>::new();
-        self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceQueueFamilyProperties2KHR(
-            physical_device.handle().to_raw(), &mut queue_family_count, ptr::null_mut());
-        queue_families.reserve_exact(queue_family_count as usize);
-        queue_families.set_len(queue_family_count as usize);
-        self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceQueueFamilyProperties2KHR(
-            physical_device.handle().to_raw(), &mut queue_family_count,
-            queue_families.as_mut_ptr() as *mut vks::VkQueueFamilyProperties2KHR);
-        Ok(queue_families)
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceQueueFamilyProperties2KHR")?;
+        Ok(util::enumerate_void(|count, p| {
+            self.proc_addr_loader().khr_get_physical_device_properties2
+                .vkGetPhysicalDeviceQueueFamilyProperties2KHR(physical_device.handle().to_raw(),
+                    count, p as *mut vks::VkQueueFamilyProperties2KHR)
+        }))
     }
 
     ///
@@ -918,13 +1038,29 @@ impl Instance {
     // pFormatInfo, uint32_t* pPropertyCount,
     // VkSparseImageFormatProperties2KHR* pProperties);
     pub unsafe fn get_physical_device_memory_properties_2_khr<Pd>(&self, physical_device: Pd)
-            -> PhysicalDeviceMemoryProperties2Khr
+            -> VdResult<PhysicalDeviceMemoryProperties2Khr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceMemoryProperties2KHR")?;
         let mut mem_props: vks::VkPhysicalDeviceMemoryProperties2KHR;
-        mem_props = mem::uninitialized();
+        mem_props = Default::default();
         self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceMemoryProperties2KHR(
             physical_device.handle().to_raw(), &mut mem_props);
-        PhysicalDeviceMemoryProperties2Khr::from_raw(mem_props)
+        Ok(PhysicalDeviceMemoryProperties2Khr::from_raw(mem_props))
+    }
+
+    /// Queries memory properties into `memory_properties`, including those of
+    /// any extension property structures chained onto it.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0-extensions/man/html/vkGetPhysicalDeviceMemoryProperties2KHR.html
+    //
+    pub unsafe fn get_physical_device_memory_properties_2_khr_into<Pd>(&self, physical_device: Pd,
+            memory_properties: &mut PhysicalDeviceMemoryProperties2Khr) -> VdResult<()>
+            where Pd: Handle<Target=PhysicalDeviceHandle> {
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceMemoryProperties2KHR")?;
+        self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceMemoryProperties2KHR(
+            physical_device.handle().to_raw(),
+            memory_properties as *mut _ as *mut vks::VkPhysicalDeviceMemoryProperties2KHR);
+        Ok(())
     }
 
     ///
@@ -938,19 +1074,15 @@ impl Instance {
     // VkSparseImageFormatProperties2KHR* pProperties);
     pub unsafe fn get_physical_device_sparse_image_format_properties_2_khr<Pd>(&self, physical_device: Pd,
             format_info: &PhysicalDeviceSparseImageFormatInfo2Khr)
-            -> SmallVec<[SparseImageFormatProperties2Khr; 8]>
+            -> VdResult<SmallVec<[SparseImageFormatProperties2Khr; 8]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut property_count = 0u32;
-        let mut properties = SmallVec::<[SparseImageFormatProperties2Khr; 8]>::new();
-        self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceSparseImageFormatProperties2KHR(
-            physical_device.handle().to_raw(), format_info.as_raw(), &mut property_count,
-            ptr::null_mut());
-        properties.reserve_exact(property_count as usize);
-        properties.set_len(property_count as usize);
-        self.proc_addr_loader().khr_get_physical_device_properties2.vkGetPhysicalDeviceSparseImageFormatProperties2KHR(
-            physical_device.handle().to_raw(), format_info.as_raw(), &mut property_count,
-            properties.as_mut_ptr() as *mut vks::VkSparseImageFormatProperties2KHR);
-        properties
+        self.require_extension(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "vkGetPhysicalDeviceSparseImageFormatProperties2KHR")?;
+        Ok(util::enumerate_void(|count, p| {
+            self.proc_addr_loader().khr_get_physical_device_properties2
+                .vkGetPhysicalDeviceSparseImageFormatProperties2KHR(
+                    physical_device.handle().to_raw(), format_info.as_raw(), count,
+                    p as *mut vks::VkSparseImageFormatProperties2KHR)
+        }))
     }
 
     ///
@@ -963,13 +1095,14 @@ impl Instance {
     // pExternalBufferProperties);
     pub unsafe fn get_physical_device_external_buffer_properties_khr<Pd>(&self,
             physical_device: Pd, external_buffer_info: &PhysicalDeviceExternalBufferInfoKhr)
-            -> ExternalBufferPropertiesKhr
+            -> VdResult<ExternalBufferPropertiesKhr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut external_buffer_properties = mem::uninitialized();
+        self.require_extension(names::KHR_EXTERNAL_MEMORY_CAPABILITIES, "vkGetPhysicalDeviceExternalBufferPropertiesKHR")?;
+        let mut external_buffer_properties = Default::default();
         self.proc_addr_loader().khr_external_memory_capabilities.vkGetPhysicalDeviceExternalBufferPropertiesKHR(
             physical_device.handle().to_raw(), external_buffer_info.as_raw(),
             &mut external_buffer_properties);
-        ExternalBufferPropertiesKhr::from_raw(external_buffer_properties)
+        Ok(ExternalBufferPropertiesKhr::from_raw(external_buffer_properties))
     }
 
     ///
@@ -982,13 +1115,14 @@ impl Instance {
     // pExternalSemaphoreProperties);
     pub unsafe fn get_physical_device_external_semaphore_properties_khr<Pd>(&self,
             physical_device: Pd, external_semaphore_info: &PhysicalDeviceExternalSemaphoreInfoKhr)
-            -> ExternalSemaphorePropertiesKhr
+            -> VdResult<ExternalSemaphorePropertiesKhr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut external_semaphore_properties = mem::uninitialized();
+        self.require_extension(names::KHR_EXTERNAL_SEMAPHORE_CAPABILITIES, "vkGetPhysicalDeviceExternalSemaphorePropertiesKHR")?;
+        let mut external_semaphore_properties = Default::default();
         self.proc_addr_loader().khr_external_semaphore_capabilities.vkGetPhysicalDeviceExternalSemaphorePropertiesKHR(
             physical_device.handle().to_raw(), external_semaphore_info.as_raw(),
             &mut external_semaphore_properties);
-        ExternalSemaphorePropertiesKhr::from_raw(external_semaphore_properties)
+        Ok(ExternalSemaphorePropertiesKhr::from_raw(external_semaphore_properties))
     }
 
     ///
@@ -1001,13 +1135,14 @@ impl Instance {
     // pExternalFenceProperties);
     pub unsafe fn get_physical_device_external_fence_properties_khr<Pd>(&self,
             physical_device: Pd, external_fence_info: &PhysicalDeviceExternalFenceInfoKhr)
-            -> ExternalFencePropertiesKhr
+            -> VdResult<ExternalFencePropertiesKhr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut external_fence_properties = mem::uninitialized();
+        self.require_extension(names::KHR_EXTERNAL_FENCE_CAPABILITIES, "vkGetPhysicalDeviceExternalFencePropertiesKHR")?;
+        let mut external_fence_properties = Default::default();
         self.proc_addr_loader().khr_external_fence_capabilities.vkGetPhysicalDeviceExternalFencePropertiesKHR(
             physical_device.handle().to_raw(), external_fence_info.as_raw(),
             &mut external_fence_properties);
-        ExternalFencePropertiesKhr::from_raw(external_fence_properties)
+        Ok(ExternalFencePropertiesKhr::from_raw(external_fence_properties))
     }
 
     ///
@@ -1020,7 +1155,8 @@ impl Instance {
     pub unsafe fn get_physical_device_surface_capabilities_2_khr<Pd>(&self, physical_device: Pd,
             surface_info: &PhysicalDeviceSurfaceInfo2Khr) -> VdResult<SurfaceCapabilities2Khr>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut capabilities = mem::uninitialized();
+        self.require_extension(names::KHR_GET_SURFACE_CAPABILITIES_2, "vkGetPhysicalDeviceSurfaceCapabilities2KHR")?;
+        let mut capabilities = Default::default();
         let result = self.proc_addr_loader().khr_get_surface_capabilities2.vkGetPhysicalDeviceSurfaceCapabilities2KHR(
             physical_device.handle().to_raw(), surface_info.as_raw(), &mut capabilities);
         error::check(result, "vkGetPhysicalDeviceSurfaceCapabilities2KHR",
@@ -1037,24 +1173,12 @@ impl Instance {
     pub unsafe fn get_physical_device_surface_formats_2_khr<Pd>(&self, physical_device: Pd,
             surface_info: &PhysicalDeviceSurfaceInfo2Khr) -> VdResult<SmallVec<[SurfaceFormat2Khr; 64]>>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut format_count = 0u32;
-        let mut formats: SmallVec<[::SurfaceFormat2Khr; 64]> = SmallVec::new();
-        let result = self.proc_addr_loader().khr_get_surface_capabilities2.vkGetPhysicalDeviceSurfaceFormats2KHR(
-            physical_device.handle().to_raw(), surface_info.as_raw(), &mut format_count, ptr::null_mut());
-        error::check(result, "vkGetPhysicalDeviceSurfaceFormats2KHR", ())?;
-        formats.reserve_exact(format_count as usize);
-        formats.set_len(format_count as usize);
-        if format_count != 0 {
-            loop {
-                let result = self.proc_addr_loader().khr_get_surface_capabilities2.vkGetPhysicalDeviceSurfaceFormats2KHR(
-                    physical_device.handle().to_raw(), surface_info.as_raw(), &mut format_count,
-                    formats.as_mut_ptr() as *mut vks::VkSurfaceFormat2KHR);
-                if result != CallResult::Incomplete as i32 {
-                    return error::check(result, "vkGetPhysicalDeviceSurfaceFormats2KHR", formats);
-                }
-            }
-        }
-        Ok(formats)
+        self.require_extension(names::KHR_GET_SURFACE_CAPABILITIES_2, "vkGetPhysicalDeviceSurfaceFormats2KHR")?;
+        util::enumerate("vkGetPhysicalDeviceSurfaceFormats2KHR", |count, p| {
+            self.proc_addr_loader().khr_get_surface_capabilities2.vkGetPhysicalDeviceSurfaceFormats2KHR(
+                physical_device.handle().to_raw(), surface_info.as_raw(), count,
+                p as *mut vks::VkSurfaceFormat2KHR)
+        })
     }
 
     ///
@@ -1069,6 +1193,7 @@ impl Instance {
             create_info: &DebugReportCallbackCreateInfoExt,
             allocator: Option<*const vks::VkAllocationCallbacks>)
             ->  VdResult<DebugReportCallbackExtHandle> {
+        self.require_extension(names::EXT_DEBUG_REPORT, "vkCreateDebugReportCallbackEXT")?;
         let allocator = allocator.unwrap_or(ptr::null());
         let mut callback = 0;
         let result = self.proc_addr_loader().ext_debug_report.vkCreateDebugReportCallbackEXT(self.handle().to_raw(),
@@ -1101,10 +1226,12 @@ impl Instance {
     // pLayerPrefix, const char* pMessage);
     pub unsafe fn debug_report_message_ext(&self, flags: DebugReportFlagsExt,
             object_type: DebugReportObjectTypeExt, object: u64, location: usize, message_code: i32,
-            layer_prefix: &CStr, message: &CStr) {
+            layer_prefix: &CStr, message: &CStr) -> VdResult<()> {
+        self.require_extension(names::EXT_DEBUG_REPORT, "vkDebugReportMessageEXT")?;
         self.proc_addr_loader().ext_debug_report.vkDebugReportMessageEXT(self.handle().to_raw(), flags.bits(),
             object_type.into(), object, location, message_code, layer_prefix.as_ptr(),
             message.as_ptr());
+        Ok(())
     }
 
     ///
@@ -1122,7 +1249,7 @@ impl Instance {
             external_handle_type: ExternalMemoryHandleTypeFlagsNv)
             -> VdResult<ExternalImageFormatPropertiesNv>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut external_image_format_properties = mem::uninitialized();
+        let mut external_image_format_properties = Default::default();
         let result = self.proc_addr_loader().nv_external_memory_capabilities.vkGetPhysicalDeviceExternalImageFormatPropertiesNV(
             physical_device.handle().to_raw(), format.into(), type_.into(),
             tiling.into(), usage.bits(), flags.bits(), external_handle_type.bits(),
@@ -1143,23 +1270,11 @@ impl Instance {
             physical_device: Pd, surface: S)
             -> VdResult<SmallVec<[Rect2d; 8]>>
             where Pd: Handle<Target=PhysicalDeviceHandle>, S: Handle<Target=SurfaceKhrHandle> {
-        let mut rect_count = 0u32;
-        let mut rects: SmallVec<[Rect2d; 8]> = SmallVec::new();
-        let result = self.proc_addr_loader().vkGetPhysicalDevicePresentRectanglesKHX(
-            physical_device.handle().to_raw(), surface.handle().to_raw(), &mut rect_count,
-            ptr::null_mut());
-        error::check(result, "vkGetPhysicalDevicePresentRectanglesKHX", ())?;
-        rects.reserve_exact(rect_count as usize);
-        rects.set_len(rect_count as usize);
-        loop {
-            let result = self.proc_addr_loader().vkGetPhysicalDevicePresentRectanglesKHX(
-                physical_device.handle().to_raw(), surface.handle().to_raw(), &mut rect_count,
-                rects.as_mut_ptr() as *mut vks::VkRect2D);
-            if result != CallResult::Incomplete as i32 {
-                return error::check(result, "vkGetPhysicalDevicePresentRectanglesKHX", rects);
-            }
-        }
-        Ok(rects)
+        util::enumerate("vkGetPhysicalDevicePresentRectanglesKHX", |count, p| {
+            self.proc_addr_loader().vkGetPhysicalDevicePresentRectanglesKHX(
+                physical_device.handle().to_raw(), surface.handle().to_raw(), count,
+                p as *mut vks::VkRect2D)
+        })
     }
 
     ///
@@ -1178,16 +1293,44 @@ impl Instance {
         error::check(result, "vkCreateViSurfaceNN", SurfaceKhrHandle(surface))
     }
 
+    /// Returns the groups of physical devices which may each be used to
+    /// create a single logical device spanning all of their members.
     ///
+    /// Every physical device is a member of exactly one group. Requires the
+    /// `VK_KHX_device_group_creation` extension.
     ///
-    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0-extensions/html/vkspec.html#vkEnumeratePhysicalDeviceGroupsKHX
     //
     // *PFN_vkEnumeratePhysicalDeviceGroupsKHX)(VkInstance instance, uint32_t*
     // pPhysicalDeviceGroupCount, VkPhysicalDeviceGroupPropertiesKHX*
     // pPhysicalDeviceGroupProperties);
     #[cfg(feature = "experimental")]
-    pub unsafe fn enumerate_physical_device_groups_khx(&self) {
-        unimplemented!();
+    pub fn enumerate_physical_device_groups_khx(&self)
+            -> VdResult<Vec<PhysicalDeviceGroupPropertiesKhx>> {
+        self.require_extension(names::KHX_DEVICE_GROUP_CREATION,
+            "vkEnumeratePhysicalDeviceGroupsKHX")?;
+        let mut props = Vec::new();
+        unsafe {
+            loop {
+                let mut count = 0;
+                let result = self.proc_addr_loader().khx_device_group_creation
+                    .vkEnumeratePhysicalDeviceGroupsKHX(self.handle().to_raw(), &mut count,
+                        ptr::null_mut());
+                error::check_named(result, "vkEnumeratePhysicalDeviceGroupsKHX")?;
+                props.clear();
+                props.resize(count as usize, vks::VkPhysicalDeviceGroupPropertiesKHX::default());
+                let result = self.proc_addr_loader().khx_device_group_creation
+                    .vkEnumeratePhysicalDeviceGroupsKHX(self.handle().to_raw(), &mut count,
+                        props.as_mut_ptr());
+                // The number of groups may change between calls.
+                if result != CallResult::Incomplete as i32 {
+                    error::check_named(result, "vkEnumeratePhysicalDeviceGroupsKHX")?;
+                    props.truncate(count as usize);
+                    break;
+                }
+            }
+        }
+        Ok(props.iter().map(|raw| PhysicalDeviceGroupPropertiesKhx::from_raw(self, raw)).collect())
     }
 
     ///
@@ -1213,7 +1356,7 @@ impl Instance {
             where Pd: Handle<Target=PhysicalDeviceHandle>, D: Handle<Target=DisplayKhrHandle> {
         let result = self.proc_addr_loader().ext_direct_mode_display.vkReleaseDisplayEXT(
             physical_device.handle().to_raw(), display.handle().to_raw());
-        error::check(result, "vkReleaseDisplayEXT", ())
+        error::check_named(result, "vkReleaseDisplayEXT")
     }
 
     ///
@@ -1227,7 +1370,7 @@ impl Instance {
             where Pd: Handle<Target=PhysicalDeviceHandle>, D: Handle<Target=DisplayKhrHandle> {
         let result = self.proc_addr_loader().ext_acquire_xlib_display.vkAcquireXlibDisplayEXT(
             physical_device.handle().to_raw(), dpy, display.handle().to_raw());
-        error::check(result, "vkAcquireXlibDisplayEXT", ())
+        error::check_named(result, "vkAcquireXlibDisplayEXT")
     }
 
     ///
@@ -1255,7 +1398,7 @@ impl Instance {
     pub unsafe fn get_physical_device_surface_capabilities_2_ext<Pd, S>(&self, physical_device: Pd,
             surface: S) -> VdResult<SurfaceCapabilities2Ext>
             where Pd: Handle<Target=PhysicalDeviceHandle>, S: Handle<Target=SurfaceKhrHandle> {
-        let mut surface_capabilities = mem::uninitialized();
+        let mut surface_capabilities = Default::default();
         let result = self.proc_addr_loader().ext_display_surface_counter.vkGetPhysicalDeviceSurfaceCapabilities2EXT(
             physical_device.handle().to_raw(), surface.handle().to_raw(),
             &mut surface_capabilities as *mut _ as *mut vks::VkSurfaceCapabilities2EXT);
@@ -1306,7 +1449,7 @@ impl Instance {
             samples: SampleCountFlags)
             -> VdResult<MultisamplePropertiesExt>
             where Pd: Handle<Target=PhysicalDeviceHandle> {
-        let mut multisample_properties = mem::uninitialized();
+        let mut multisample_properties = Default::default();
         let result = self.proc_addr_loader().vkGetPhysicalDeviceMultisamplePropertiesEXT(
             physical_device.handle().to_raw(), samples.bits(),
             multisample_properties as *mut _ as *mut vks::VkMultisamplePropertiesEXT);
@@ -1335,6 +1478,7 @@ pub struct InstanceBuilder<'ib> {
     enabled_layer_names: Option<CharStrs<'ib>>,
     enabled_extension_names: Option<CharStrs<'ib>>,
     print_debug_report_enable: bool,
+    validation: bool,
     _p: PhantomData<&'ib ()>,
 }
 
@@ -1346,6 +1490,7 @@ impl<'ib> InstanceBuilder<'ib> {
             enabled_layer_names: None,
             enabled_extension_names: None,
             print_debug_report_enable: false,
+            validation: false,
             _p: PhantomData,
         }
     }
@@ -1363,9 +1508,6 @@ impl<'ib> InstanceBuilder<'ib> {
             -> &'s mut InstanceBuilder<'ib>
             where 'cs: 'ib, Cs: 'cs + Into<CharStrs<'cs>> {
         self.enabled_layer_names = Some(enabled_layer_names.into());
-        if let Some(ref elns) = self.enabled_layer_names {
-            self.create_info.set_enabled_layer_names(elns.as_ptr_slice());
-        }
         self
     }
 
@@ -1375,13 +1517,10 @@ impl<'ib> InstanceBuilder<'ib> {
     pub fn enabled_extension_names<'s, 'cs, Cs>(&'s mut self, enabled_extension_names: Cs)
             -> &'s mut InstanceBuilder<'ib>
             where 'cs: 'ib, Cs: 'cs + Into<CharStrs<'cs>> {
-        if self.create_info.enabled_extension_names().len() != 0 {
+        if self.enabled_extension_names.is_some() {
             panic!("Enabled extension names have already been set.");
         }
         self.enabled_extension_names = Some(enabled_extension_names.into());
-        if let Some(ref eens) = self.enabled_extension_names {
-            self.create_info.set_enabled_extension_names(eens.as_ptr_slice());
-        }
         self
     }
 
@@ -1391,20 +1530,17 @@ impl<'ib> InstanceBuilder<'ib> {
     pub fn enabled_extensions<'een, 's>(&'s mut self, enabled_extensions: &'een [vks::VkExtensionProperties])
             -> &'s mut InstanceBuilder<'ib>
             where 'een: 'ib {
-        if self.create_info.enabled_extension_names().len() != 0 {
+        if self.enabled_extension_names.is_some() {
             panic!("Enabled extension names have already been set.");
         }
-        let enabled_extension_name_ptrs: Vec<_> = enabled_extensions.iter().map(|eext| {
-            if PRINT { println!("Enabling instance extension: '{}' (version: {})",
+        let enabled_extension_name_ptrs: SmallVec<_> = enabled_extensions.iter().map(|eext| {
+            debug!("Enabling instance extension: '{}' (version: {})",
                 unsafe { CStr::from_ptr(&eext.extensionName as *const c_char).to_str().unwrap() },
-                    eext.specVersion); }
+                    eext.specVersion);
             eext.extensionName.as_ptr()
         }).collect();
 
         self.enabled_extension_names = Some(CharStrs::OwnedPtr { ptrs: enabled_extension_name_ptrs });
-        if let Some(ref eens) = self.enabled_extension_names {
-            self.create_info.set_enabled_extension_names(eens.as_ptr_slice());
-        }
         self
     }
 
@@ -1421,18 +1557,68 @@ impl<'ib> InstanceBuilder<'ib> {
         self
     }
 
+    /// Enables validation in one step.
+    ///
+    /// When `true`, the best available validation layer
+    /// (`VK_LAYER_KHRONOS_validation`, falling back to
+    /// `VK_LAYER_LUNARG_standard_validation`) and the `VK_EXT_debug_report`
+    /// extension are appended to any layer and extension names otherwise
+    /// specified, and debug report printing is enabled as if by
+    /// `print_debug_report(true)`. The callback lives as long as the
+    /// instance.
+    ///
+    /// If no validation layer is installed, a warning is logged and the
+    /// instance is created without one.
+    pub fn validation<'s>(&'s mut self, enable: bool) -> &'s mut InstanceBuilder<'ib> {
+        self.validation = enable;
+        self
+    }
+
     /// Builds and returns a new `Instance`.
     pub fn build(&self, mut loader: Loader) -> VdResult<Instance> {
         let mut enable_debug_callback = false;
 
-        let handle = unsafe { loader.create_instance(&self.create_info, None)? };
+        // Name pointers are resolved here rather than in the setters so that
+        // inline pointer storage remains valid if the builder is moved.
+        let mut layer_names: SmallVec<[*const c_char; 16]> = SmallVec::new();
+        if let Some(ref elns) = self.enabled_layer_names {
+            layer_names.extend(elns.as_ptr_slice().iter().cloned());
+        }
+        let mut extension_names: SmallVec<[*const c_char; 16]> = SmallVec::new();
+        if let Some(ref eens) = self.enabled_extension_names {
+            extension_names.extend(eens.as_ptr_slice().iter().cloned());
+        }
+        if self.validation {
+            add_validation_names(&loader, &mut layer_names, &mut extension_names)?;
+        }
+
+        let mut create_info: InstanceCreateInfo = self.create_info.clone();
+        if !layer_names.is_empty() {
+            create_info.set_enabled_layer_names(&layer_names);
+        }
+        if !extension_names.is_empty() {
+            create_info.set_enabled_extension_names(&extension_names);
+        }
+
+        debug!("Creating instance...");
+        let handle = unsafe { loader.create_instance(&create_info, None)? };
         unsafe { loader.instance_proc_addr_loader_mut().load_vk(handle.to_raw()); }
 
+        let mut enabled_extensions = Vec::new();
         unsafe {
-            if let Some(extension_name_char_strs) = self.enabled_extension_names.as_ref() {
-                let extension_names = extension_name_char_strs.as_ptr_slice();
-                for &extension_name in extension_names {
-                    match CStr::from_ptr(extension_name).to_str().expect("invalid extension name") {
+            {
+                for &extension_name in extension_names.iter() {
+                    let extension_name_c = CStr::from_ptr(extension_name);
+                    let extension_name = extension_name_c.to_str()
+                        .expect("invalid extension name");
+                    if !is_platform_extension_supported(extension_name) {
+                        warn!("Instance extension '{}' is not supported on this platform. \
+                            Its functions will not be loaded.", extension_name);
+                        continue;
+                    }
+                    enabled_extensions.push(extension_name_c.to_owned());
+                    trace!("Loading instance extension functions: '{}'", extension_name);
+                    match extension_name {
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_16bit_storage" => loader.instance_proc_addr_loader_mut().load_khr_16bit_storage(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
@@ -1471,6 +1657,7 @@ impl<'ib> InstanceBuilder<'ib> {
                         "VK_KHR_maintenance1" => loader.instance_proc_addr_loader_mut().load_khr_maintenance1(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_maintenance2" => loader.instance_proc_addr_loader_mut().load_khr_maintenance2(handle.to_raw()),
+                        #[cfg(all(unix, not(target_os = "android"), not(target_os = "macos"), not(target_os = "ios")))]
                         "VK_KHR_mir_surface" => loader.instance_proc_addr_loader_mut().load_khr_mir_surface(handle.to_raw()),
                         "VK_KHR_push_descriptor" => loader.instance_proc_addr_loader_mut().load_khr_push_descriptor(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
@@ -1488,12 +1675,17 @@ impl<'ib> InstanceBuilder<'ib> {
                         "VK_KHR_swapchain" => loader.instance_proc_addr_loader_mut().load_khr_swapchain(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_variable_pointers" => loader.instance_proc_addr_loader_mut().load_khr_variable_pointers(handle.to_raw()),
+                        #[cfg(all(unix, not(target_os = "android"), not(target_os = "macos"), not(target_os = "ios")))]
                         "VK_KHR_wayland_surface" => loader.instance_proc_addr_loader_mut().load_khr_wayland_surface(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHR_win32_keyed_mutex" => loader.instance_proc_addr_loader_mut().load_khr_win32_keyed_mutex(handle.to_raw()),
+                        #[cfg(windows)]
                         "VK_KHR_win32_surface" => loader.instance_proc_addr_loader_mut().load_khr_win32_surface(handle.to_raw()),
+                        #[cfg(all(unix, not(target_os = "android"), not(target_os = "macos"), not(target_os = "ios")))]
                         "VK_KHR_xcb_surface" => loader.instance_proc_addr_loader_mut().load_khr_xcb_surface(handle.to_raw()),
+                        #[cfg(all(unix, not(target_os = "android"), not(target_os = "macos"), not(target_os = "ios")))]
                         "VK_KHR_xlib_surface" => loader.instance_proc_addr_loader_mut().load_khr_xlib_surface(handle.to_raw()),
+                        #[cfg(all(unix, not(target_os = "android"), not(target_os = "macos"), not(target_os = "ios")))]
                         "VK_EXT_acquire_xlib_display" => loader.instance_proc_addr_loader_mut().load_ext_acquire_xlib_display(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_EXT_blend_operation_advanced" => loader.instance_proc_addr_loader_mut().load_ext_blend_operation_advanced(handle.to_raw()),
@@ -1562,11 +1754,13 @@ impl<'ib> InstanceBuilder<'ib> {
                         "VK_IMG_format_pvrtc" => loader.instance_proc_addr_loader_mut().load_img_format_pvrtc(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHX_device_group" => loader.instance_proc_addr_loader_mut().load_khx_device_group(handle.to_raw()),
-                        #[cfg(feature = "unimplemented")]
+                        #[cfg(feature = "experimental")]
                         "VK_KHX_device_group_creation" => loader.instance_proc_addr_loader_mut().load_khx_device_group_creation(handle.to_raw()),
                         #[cfg(feature = "unimplemented")]
                         "VK_KHX_multiview" => loader.instance_proc_addr_loader_mut().load_khx_multiview(handle.to_raw()),
+                        #[cfg(target_os = "ios")]
                         "VK_MVK_ios_surface" => loader.instance_proc_addr_loader_mut().load_mvk_ios_surface(handle.to_raw()),
+                        #[cfg(target_os = "macos")]
                         "VK_MVK_macos_surface" => loader.instance_proc_addr_loader_mut().load_mvk_macos_surface(handle.to_raw()),
                         "VK_NN_vi_surface" => loader.instance_proc_addr_loader_mut().load_nn_vi_surface(handle.to_raw()),
                         "VK_NV_clip_space_w_scaling" => loader.instance_proc_addr_loader_mut().load_nv_clip_space_w_scaling(handle.to_raw()),
@@ -1604,7 +1798,7 @@ impl<'ib> InstanceBuilder<'ib> {
             }
         }
 
-        let debug_callback = if self.print_debug_report_enable {
+        let debug_callback = if self.print_debug_report_enable || self.validation {
             if enable_debug_callback {
                 let create_info = vks::VkDebugReportCallbackCreateInfoEXT {
                     sType:  vks::VK_STRUCTURE_TYPE_DEBUG_REPORT_CALLBACK_CREATE_INFO_EXT,
@@ -1621,13 +1815,12 @@ impl<'ib> InstanceBuilder<'ib> {
                 {
                     panic!("failed to set up debug callback");
                 } else {
-                    println!("DEBUG_REPORT: Debug report printing enabled.");
+                    debug!("Debug report printing enabled.");
                 }
                 Some(DebugReportCallbackExtHandle(callback))
             } else {
-                println!("DEBUG_REPORT: WARNING: Debug report printing requested but the \
-                    'VK_EXT_debug_report' extension is not loaded. Debug report printing \
-                    is NOT enabled.");
+                warn!("Debug report printing requested but the 'VK_EXT_debug_report' \
+                    extension is not loaded. Debug report printing is NOT enabled.");
                 None
             }
         } else {
@@ -1643,7 +1836,127 @@ impl<'ib> InstanceBuilder<'ib> {
                 loader,
                 debug_callback,
                 // physical_devices,
+                enabled_extensions,
+                owned: true,
             }),
         })
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use loader::null;
+    use names;
+    use super::{Instance, is_platform_extension_supported};
+    use ::{Version, Format, PhysicalDeviceType, MemoryPropertyFlags, FormatFeatureFlags,
+        SurfaceKhrHandle, ErrorKind};
+
+    #[test]
+    fn physical_device_queries_convert_driver_output() {
+        let instance = null::instance();
+        let physical_device = null::physical_device_handle();
+
+        let features = instance.get_physical_device_features(physical_device);
+        assert!(features.geometry_shader());
+        assert!(!features.tessellation_shader());
+
+        let properties = instance.get_physical_device_properties(physical_device);
+        assert_eq!(properties.api_version(), Version::from(null::API_VERSION));
+        assert_eq!(properties.device_type(), PhysicalDeviceType::DiscreteGpu);
+        assert_eq!(properties.device_name().to_bytes(), null::DEVICE_NAME);
+        assert_eq!(properties.limits().max_image_dimension_2d(), null::MAX_IMAGE_DIMENSION_2D);
+
+        let memory_properties = instance.get_physical_device_memory_properties(physical_device);
+        assert_eq!(memory_properties.memory_type_count(), 3);
+        assert_eq!(memory_properties.memory_types()[0].property_flags(),
+            MemoryPropertyFlags::DEVICE_LOCAL);
+        assert_eq!(memory_properties.memory_types()[null::NON_COHERENT_MEMORY_TYPE as usize]
+            .property_flags(), MemoryPropertyFlags::HOST_VISIBLE);
+        assert_eq!(memory_properties.memory_types()[null::COHERENT_MEMORY_TYPE as usize]
+            .property_flags(), MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT);
+        assert!(memory_properties.memory_types()[..3].iter().all(|t| t.heap_index() == 0));
+        assert_eq!(memory_properties.memory_heap_count(), 1);
+        assert_eq!(memory_properties.memory_heaps()[0].size(), null::HEAP_SIZE);
+
+        let format_properties = instance.get_physical_device_format_properties(physical_device,
+            Format::R8G8B8A8Unorm);
+        assert_eq!(format_properties.optimal_tiling_features(), FormatFeatureFlags::SAMPLED_IMAGE);
+        assert!(format_properties.linear_tiling_features().is_empty());
+        assert!(format_properties.buffer_features().is_empty());
+    }
+
+    #[test]
+    fn from_raw_destroys_only_owned_instances() {
+        null::take_lifetime_calls();
+        drop(null::instance());
+        assert!(null::take_lifetime_calls().is_empty());
+
+        let instance = unsafe { Instance::from_raw(null::loader(), 0x1001 as vks::VkInstance, true) };
+        assert_eq!(instance.handle().to_raw() as usize, 0x1001);
+        let clone = instance.clone();
+        drop(instance);
+        assert!(null::take_lifetime_calls().is_empty());
+        drop(clone);
+        assert_eq!(null::take_lifetime_calls(), [("vkDestroyInstance", 0x1001)]);
+    }
+
+    #[test]
+    fn platform_extensions_match_the_target() {
+        let unix_ws = cfg!(all(unix, not(target_os = "android"), not(target_os = "macos"),
+            not(target_os = "ios")));
+        assert_eq!(is_platform_extension_supported("VK_KHR_xlib_surface"), unix_ws);
+        assert_eq!(is_platform_extension_supported("VK_KHR_wayland_surface"), unix_ws);
+        assert_eq!(is_platform_extension_supported("VK_KHR_win32_surface"), cfg!(windows));
+        assert_eq!(is_platform_extension_supported("VK_MVK_macos_surface"),
+            cfg!(target_os = "macos"));
+        assert!(is_platform_extension_supported("VK_KHR_surface"));
+        assert!(is_platform_extension_supported("VK_EXT_debug_report"));
+    }
+
+    #[test]
+    fn surface_calls_without_the_extension_fail() {
+        let instance = null::instance();
+        assert!(!instance.is_extension_enabled(names::KHR_SURFACE));
+        let err = unsafe {
+            instance.get_physical_device_surface_support_khr(null::physical_device_handle(), 0,
+                SurfaceKhrHandle(1)).unwrap_err()
+        };
+        match *err.kind() {
+            ErrorKind::ExtensionNotEnabled { name, fn_name } => {
+                assert_eq!(name, names::KHR_SURFACE);
+                assert_eq!(fn_name, "vkGetPhysicalDeviceSurfaceSupportKHR");
+            },
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[cfg(feature = "experimental")]
+    #[test]
+    fn device_spanning_a_single_device_group() {
+        use ::{Device, DeviceGroupDeviceCreateInfoKhx};
+
+        let instance = Instance::builder()
+            .enabled_extension_names(&[names::KHX_DEVICE_GROUP_CREATION][..])
+            .build(null::loader())
+            .unwrap();
+        let groups = instance.enumerate_physical_device_groups_khx().unwrap();
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert!(!group.subset_allocation());
+        let handles = group.physical_device_handles();
+        assert_eq!(&handles[..], &[null::physical_device_handle()]);
+
+        let mut device_group = DeviceGroupDeviceCreateInfoKhx::builder()
+            .physical_devices(&handles)
+            .build();
+        let device = Device::builder()
+            .device_group_khx(&mut device_group)
+            .build(group.physical_devices()[0].clone())
+            .unwrap();
+        assert_eq!(device.handle().to_raw() as usize, null::CREATED_DEVICE);
+        assert_eq!(null::take_device_groups(),
+            [Some(vec![null::physical_device_handle().to_raw()])]);
+    }
+}
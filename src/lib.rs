@@ -1,4 +1,34 @@
 //! Voodoo - Vulkan, but rustier.
+//!
+//! ## Interop
+//!
+//! Every object type comes in two parts: a plain `Copy` handle (such as
+//! `BufferHandle`) and, for types voodoo creates, an owning wrapper (such as
+//! `Buffer`) which destroys the object when its last clone is dropped.
+//!
+//! * Handles convert to and from the raw `vks` type with `to_raw` and
+//!   `from_raw`. Handles never own their object, so `from_raw` cannot cause
+//!   a double free, but it is `unsafe` because other functions taking a
+//!   handle assume it is valid.
+//! * Owning wrappers return their handle with `handle` (also available
+//!   generically through the `Handle` trait).
+//! * Structs wrapping a `vks` struct (create infos, properties, and the
+//!   like) return a reference to it with `as_raw` and can be built from one
+//!   with the `unsafe` `from_raw`, which requires any pointers it contains
+//!   to remain valid for the wrapper's lifetime.
+//! * `Instance::from_raw` wraps an instance created elsewhere. Pass `owned`
+//!   as `false` unless the instance should be destroyed with the wrapper.
+//!
+//! ```rust,ignore
+//! // Hand a buffer to code which calls Vulkan directly:
+//! let raw: vks::VkBuffer = buffer.handle().to_raw();
+//!
+//! // ...and a raw handle back to a voodoo command:
+//! let handle = unsafe { BufferHandle::from_raw(raw) };
+//! unsafe {
+//!     device.cmd_copy_buffer(command_buffer.handle(), handle, dst_buffer.handle(), &regions);
+//! }
+//! ```
 
 extern crate libloading as lib;
 extern crate smallvec;
@@ -9,10 +39,33 @@ extern crate bitflags as bitflags_;
 #[macro_use]
 extern crate enum_primitive_derive;
 extern crate num_traits;
+#[cfg(feature = "logging")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(feature = "winit")]
+pub extern crate winit;
+#[cfg(feature = "shaderc")]
+pub extern crate shaderc;
+
+// No-op stand-ins for the `log` macros used when the `logging` feature is
+// disabled. Arguments are type checked but never evaluated.
+#[cfg(not(feature = "logging"))]
+macro_rules! trace { ($($arg:tt)*) => ({ if false { let _ = format_args!($($arg)*); } }) }
+#[cfg(not(feature = "logging"))]
+macro_rules! debug { ($($arg:tt)*) => ({ if false { let _ = format_args!($($arg)*); } }) }
+#[cfg(not(feature = "logging"))]
+macro_rules! warn { ($($arg:tt)*) => ({ if false { let _ = format_args!($($arg)*); } }) }
 
 mod error;
 mod version;
 mod loader;
+#[cfg(test)]
+mod counting_alloc;
 mod instance;
 mod physical_device;
 mod swapchain;
@@ -21,9 +74,15 @@ mod pipeline_layout;
 mod shader_module;
 mod render_pass;
 mod graphics_pipeline;
+mod compute_pipeline;
+mod shader_stage;
+mod specialization;
+mod vertex_input;
 mod framebuffer;
 mod surface;
 mod queue;
+mod submit_batch;
+mod sparse_bind;
 mod command_pool;
 mod command_buffer;
 mod semaphore;
@@ -32,14 +91,40 @@ mod image;
 mod sampler;
 mod device_memory;
 mod descriptor_set_layout;
+mod descriptor_set;
 mod descriptor_pool;
+mod descriptor_update_batch;
+mod descriptor_indexing;
+mod external_memory_host;
+mod memory_budget;
+mod multiview;
+mod chain;
+mod debug_utils;
+mod debug_marker;
 mod structs;
 mod enums;
 mod bitflags;
 mod event;
 mod fence;
+mod timeline;
+mod query_pool;
+mod gpu_timer;
+mod indirect;
+mod upload;
+mod barrier;
+mod format_util;
+mod pipeline_cache;
+mod allocation_callbacks;
 mod device;
+pub mod description;
 pub mod util;
+pub mod names;
+pub mod alloc;
+pub mod mem_util;
+#[cfg(feature = "winit")]
+pub mod winit_support;
+#[cfg(feature = "spirv-reflect")]
+pub mod reflect;
 
 pub mod vks {
     pub use vks_::*;
@@ -159,29 +244,70 @@ pub use loader::Loader;
 pub use error::{Error, ErrorKind, Result};
 pub use version::Version;
 pub use instance::{InstanceHandle, Instance, InstanceBuilder};
-pub use physical_device::{PhysicalDeviceHandle, PhysicalDevice};
-pub use device::{DeviceHandle, Device, DeviceBuilder};
+pub use physical_device::{PhysicalDeviceHandle, PhysicalDevice, QueueFamilySelection};
+#[cfg(feature = "experimental")]
+pub use physical_device::PhysicalDeviceGroupPropertiesKhx;
+pub use device::{DeviceHandle, Device, DeviceBuilder, DeviceCommands, HeapUsage};
+pub use allocation_callbacks::{AllocationCallbacks, HostAllocator, CountingHostAllocator};
 pub use surface::{SurfaceKhrHandle, SurfaceKhr, SurfaceKhrBuilder};
 pub use queue::{QueueHandle, Queue};
-pub use swapchain::{SwapchainKhrHandle, SwapchainKhr, SwapchainKhrBuilder, SwapchainSupportDetails};
+pub use submit_batch::SubmitBatch;
+pub use sparse_bind::SparseBindBatch;
+pub use swapchain::{SwapchainKhrHandle, SwapchainKhr, SwapchainKhrBuilder, SwapchainSupportDetails,
+    AcquireResult, PresentResult, choose_present_mode, choose_surface_format};
 pub use image_view::{ImageViewHandle, ImageView, ImageViewBuilder};
 pub use shader_module::{ShaderModuleHandle, ShaderModule};
+#[cfg(feature = "shaderc")]
+pub use shader_module::GlslOptions;
 pub use pipeline_layout::{PipelineLayoutHandle, PipelineLayout, PipelineLayoutBuilder};
-pub use render_pass::{RenderPassHandle, RenderPass, RenderPassBuilder};
+pub use render_pass::{RenderPassHandle, RenderPass, RenderPassBuilder, SubpassBuilder,
+    AttachmentIndex, SubpassIndex};
 pub use graphics_pipeline::{GraphicsPipeline, GraphicsPipelineBuilder};
+pub use compute_pipeline::{ComputePipeline, ComputePipelineBuilder};
+pub use shader_stage::{ShaderStage, ShaderStageBuilder};
+pub use specialization::{SpecializationConstants, SpecializationValue};
+pub use vertex_input::{VertexLayout, VertexInputState};
 pub use framebuffer::{FramebufferHandle, Framebuffer, FramebufferBuilder};
 pub use command_pool::{CommandPoolHandle, CommandPool, CommandPoolBuilder};
-pub use command_buffer::{CommandBufferHandle, CommandBuffer};
+pub use command_buffer::{CommandBufferHandle, CommandBuffer, CommandBufferRecorder};
 pub use semaphore::{SemaphoreHandle, Semaphore};
+pub use pipeline_cache::{PipelineCache, PipelineCachePrefix, PipelineCacheError};
 pub use buffer::{BufferHandle, Buffer, BufferBuilder};
 pub use image::{ImageHandle, Image, ImageBuilder};
 pub use sampler::{SamplerHandle, Sampler, SamplerBuilder};
-pub use device_memory::{DeviceMemoryHandle, DeviceMemory, DeviceMemoryBuilder};
+pub use device_memory::{DeviceMemoryHandle, DeviceMemory, DeviceMemoryBuilder,
+    MemoryRequirements2, MappedMemory};
 pub use descriptor_set_layout::{DescriptorSetLayoutHandle, DescriptorSetLayout,
-    DescriptorSetLayoutBuilder};
+    DescriptorSetLayoutBuilder, DescriptorBindingSummary};
+pub use descriptor_set::{DescriptorSet, DescriptorSetWrites};
 pub use descriptor_pool::{DescriptorPoolHandle, DescriptorPool, DescriptorPoolBuilder};
-pub use fence::{FenceHandle, Fence, FenceStatus};
+pub use descriptor_update_batch::DescriptorUpdateBatch;
+pub use descriptor_indexing::{DescriptorSetLayoutBindingFlagsCreateInfoExt,
+    DescriptorSetLayoutBindingFlagsCreateInfoExtBuilder,
+    DescriptorSetVariableDescriptorCountAllocateInfoExt,
+    DescriptorSetVariableDescriptorCountAllocateInfoExtBuilder,
+    PhysicalDeviceDescriptorIndexingFeaturesExt, PhysicalDeviceDescriptorIndexingFeaturesExtBuilder};
+pub use external_memory_host::{ImportMemoryHostPointerInfoExt, ImportMemoryHostPointerInfoExtBuilder,
+    PhysicalDeviceExternalMemoryHostPropertiesExt,
+    PhysicalDeviceExternalMemoryHostPropertiesExtBuilder};
+pub use memory_budget::{PhysicalDeviceMemoryBudgetPropertiesExt,
+    PhysicalDeviceMemoryBudgetPropertiesExtBuilder};
+pub use chain::{BaseOutStructure, Extends};
+pub use multiview::{RenderPassMultiviewCreateInfoKhr, RenderPassMultiviewCreateInfoKhrBuilder,
+    PhysicalDeviceMultiviewFeaturesKhr, PhysicalDeviceMultiviewFeaturesKhrBuilder,
+    PhysicalDeviceMultiviewPropertiesKhr, PhysicalDeviceMultiviewPropertiesKhrBuilder};
+pub use description::{RenderPassDescription, GraphicsPipelineDescription};
+pub use fence::{FenceHandle, Fence, FenceStatus, WaitResult};
 pub use event::{EventHandle, Event, EventStatus};
+pub use timeline::{TimelineValue, FenceTimeline};
+pub use query_pool::{QueryPool, QueryPoolBuilder, QueryScope, PipelineStatistics};
+pub use debug_marker::{DebugMarkerScope, insert_debug_marker};
+pub use gpu_timer::GpuTimer;
+pub use upload::{one_time_commands, upload_image_data};
+pub use alloc::{LinearAllocator, Suballocation, ResourceKind, Allocator, AllocationStrategy,
+    AllocatorStats, MemoryUsage};
+pub use indirect::{DRAW_INDIRECT_COMMAND_STRIDE, DRAW_INDEXED_INDIRECT_COMMAND_STRIDE,
+    DISPATCH_INDIRECT_COMMAND_SIZE};
 pub use structs::*;
 pub use enums::*;
 pub use bitflags::*;
@@ -195,12 +321,36 @@ macro_rules! offset_of {
 }
 
 
+/// Looks up a command using `get_proc_addr` on a `Device` or `Instance` and
+/// transmutes it to the specified function pointer type, evaluating to an
+/// `Option` of that type.
+///
+/// Must be used within an `unsafe` block: specifying a type which does not
+/// match the command's signature is undefined behavior.
+///
+/// ```rust,ignore
+/// type QueueSubmitFn = unsafe extern "system" fn(vks::VkQueue, u32,
+///     *const vks::VkSubmitInfo, vks::VkFence) -> vks::VkResult;
+///
+/// let name = CStr::from_bytes_with_nul(b"vkQueueSubmit\0").unwrap();
+/// let queue_submit = unsafe { proc_addr!(device, name => QueueSubmitFn) }
+///     .expect("vkQueueSubmit not found");
+/// let result = unsafe { queue_submit(queue.handle().to_raw(), 0, ptr::null(), 0) };
+/// ```
+#[macro_export]
+macro_rules! proc_addr {
+    ($source:expr, $name:expr => $fn_ty:ty) => (
+        $source.get_proc_addr($name)
+            .map(|f| ::std::mem::transmute::<unsafe extern "system" fn(), $fn_ty>(f))
+    )
+}
+
+
 pub static VALIDATION_LAYER_NAMES: &[&[u8]] = &[
     b"VK_LAYER_LUNARG_standard_validation\0"
 ];
 
 
-const PRINT: bool = false;
 pub const LOD_CLAMP_NONE: f32 = 1000.0f32;
 pub const REMAINING_MIP_LEVELS: u32 = !0;
 pub const REMAINING_ARRAY_LAYERS: u32= !0;
@@ -230,6 +380,12 @@ pub unsafe trait Handle {
 pub struct QueryPoolHandle(pub(crate) vks::VkQueryPool);
 
 impl QueryPoolHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkQueryPool) -> QueryPoolHandle {
+        QueryPoolHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkQueryPool {
         self.0
@@ -250,6 +406,12 @@ unsafe impl Handle for QueryPoolHandle {
 pub struct BufferViewHandle(pub(crate) vks::VkBufferView);
 
 impl BufferViewHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkBufferView) -> BufferViewHandle {
+        BufferViewHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkBufferView {
         self.0
@@ -270,6 +432,12 @@ unsafe impl Handle for BufferViewHandle {
 pub struct PipelineCacheHandle(pub(crate) vks::VkPipelineCache);
 
 impl PipelineCacheHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkPipelineCache) -> PipelineCacheHandle {
+        PipelineCacheHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkPipelineCache {
         self.0
@@ -290,6 +458,12 @@ unsafe impl Handle for PipelineCacheHandle {
 pub struct PipelineHandle(pub(crate) vks::VkPipeline);
 
 impl PipelineHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkPipeline) -> PipelineHandle {
+        PipelineHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkPipeline {
         self.0
@@ -310,6 +484,12 @@ unsafe impl Handle for PipelineHandle {
 pub struct DescriptorSetHandle(pub(crate) vks::VkDescriptorSet);
 
 impl DescriptorSetHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDescriptorSet) -> DescriptorSetHandle {
+        DescriptorSetHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDescriptorSet {
         self.0
@@ -330,6 +510,12 @@ unsafe impl Handle for DescriptorSetHandle {
 pub struct DisplayKhrHandle(pub(crate) vks::VkDisplayKHR);
 
 impl DisplayKhrHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDisplayKHR) -> DisplayKhrHandle {
+        DisplayKhrHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDisplayKHR {
         self.0
@@ -350,6 +536,12 @@ unsafe impl Handle for DisplayKhrHandle {
 pub struct DisplayModeKhrHandle(pub(crate) vks::VkDisplayModeKHR);
 
 impl DisplayModeKhrHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDisplayModeKHR) -> DisplayModeKhrHandle {
+        DisplayModeKhrHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDisplayModeKHR {
         self.0
@@ -370,6 +562,12 @@ unsafe impl Handle for DisplayModeKhrHandle {
 pub struct DescriptorUpdateTemplateHandle(pub(crate) vks::VkDescriptorUpdateTemplateKHR);
 
 impl DescriptorUpdateTemplateHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDescriptorUpdateTemplateKHR) -> DescriptorUpdateTemplateHandle {
+        DescriptorUpdateTemplateHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDescriptorUpdateTemplateKHR {
         self.0
@@ -390,6 +588,12 @@ unsafe impl Handle for DescriptorUpdateTemplateHandle {
 pub struct DebugReportCallbackExtHandle(pub(crate) vks::VkDebugReportCallbackEXT);
 
 impl DebugReportCallbackExtHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkDebugReportCallbackEXT) -> DebugReportCallbackExtHandle {
+        DebugReportCallbackExtHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkDebugReportCallbackEXT {
         self.0
@@ -410,6 +614,12 @@ unsafe impl Handle for DebugReportCallbackExtHandle {
 pub struct SamplerYcbcrConversionKhrHandle(pub(crate) u64);
 
 impl SamplerYcbcrConversionKhrHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: u64) -> SamplerYcbcrConversionKhrHandle {
+        SamplerYcbcrConversionKhrHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> u64 {
         self.0
@@ -430,6 +640,12 @@ unsafe impl Handle for SamplerYcbcrConversionKhrHandle {
 pub struct ObjectTableNvxHandle(pub(crate) u64);
 
 impl ObjectTableNvxHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: u64) -> ObjectTableNvxHandle {
+        ObjectTableNvxHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> u64 {
         self.0
@@ -450,6 +666,12 @@ unsafe impl Handle for ObjectTableNvxHandle {
 pub struct IndirectCommandsLayoutNvxHandle(pub(crate) u64);
 
 impl IndirectCommandsLayoutNvxHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: u64) -> IndirectCommandsLayoutNvxHandle {
+        IndirectCommandsLayoutNvxHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> u64 {
         self.0
@@ -470,6 +692,12 @@ unsafe impl Handle for IndirectCommandsLayoutNvxHandle {
 pub struct ValidationCacheExtHandle(pub(crate) u64);
 
 impl ValidationCacheExtHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: u64) -> ValidationCacheExtHandle {
+        ValidationCacheExtHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> u64 {
         self.0
@@ -492,6 +720,12 @@ pub struct DescriptorUpdateTemplateKhrHandle(pub(crate) u64);
 
 #[cfg(feature = "experimental")]
 impl DescriptorUpdateTemplateKhrHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: u64) -> DescriptorUpdateTemplateKhrHandle {
+        DescriptorUpdateTemplateKhrHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> u64 {
         self.0
@@ -508,33 +742,6 @@ unsafe impl Handle for DescriptorUpdateTemplateKhrHandle {
 }
 
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[repr(C)]
-pub struct DescriptorSet(pub(crate) DescriptorSetHandle);
-
-impl DescriptorSet {
-    pub fn handle(&self) -> DescriptorSetHandle {
-        self.0
-    }
-}
-
-unsafe impl Handle for DescriptorSet {
-    type Target = DescriptorSetHandle;
-
-    fn handle(&self) -> Self::Target {
-        self.0
-    }
-}
-
-unsafe impl<'h> Handle for &'h DescriptorSet {
-    type Target = DescriptorSetHandle;
-
-    fn handle(&self) -> Self::Target {
-        self.0
-    }
-}
-
-
 #[derive(Clone, Debug)]
 pub struct BufferView(BufferViewHandle);
 
@@ -571,24 +778,6 @@ unsafe impl<'h> Handle for &'h Pipeline {
 }
 
 
-#[derive(Clone, Debug)]
-pub struct QueryPool(QueryPoolHandle);
-
-impl QueryPool {
-    pub fn handle(&self) -> QueryPoolHandle {
-        self.0
-    }
-}
-
-unsafe impl<'h> Handle for &'h QueryPool {
-    type Target = QueryPoolHandle;
-
-    fn handle(&self) -> Self::Target {
-        self.0
-    }
-}
-
-
 #[derive(Clone, Debug)]
 pub struct DisplayModeKhr(DisplayModeKhrHandle);
 
@@ -663,3 +852,81 @@ pub type DWORD = vks::DWORD;
 pub type LPCWSTR = vks::LPCWSTR;
 pub type CommandPoolTrimFlagsKhr = vks::VkCommandPoolTrimFlagsKHR;
 
+
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::ptr;
+    use loader::null;
+    use super::*;
+
+    #[test]
+    fn non_dispatchable_handles_round_trip() {
+        let raw: vks::VkBuffer = 0xdead_beef_0000_0001;
+        let handle = unsafe { BufferHandle::from_raw(raw) };
+        assert_eq!(handle.to_raw(), raw);
+        assert_eq!(handle.handle(), handle);
+
+        let raw: vks::VkPipeline = 42;
+        assert_eq!(unsafe { PipelineHandle::from_raw(raw) }.to_raw(), raw);
+        let raw: vks::VkDescriptorSet = 7;
+        assert_eq!(unsafe { DescriptorSetHandle::from_raw(raw) }.to_raw(), raw);
+    }
+
+    #[test]
+    fn dispatchable_handles_round_trip() {
+        let raw = 0x1000 as vks::VkDevice;
+        let handle = unsafe { DeviceHandle::from_raw(raw) };
+        assert_eq!(handle.to_raw(), raw);
+        assert_eq!(handle.handle(), handle);
+
+        let raw = 0x2000 as vks::VkCommandBuffer;
+        assert_eq!(unsafe { CommandBufferHandle::from_raw(raw) }.to_raw(), raw);
+    }
+
+    #[test]
+    fn wrapped_structs_round_trip() {
+        let raw = vks::VkExtent2D { width: 640, height: 480 };
+        let extent = unsafe { Extent2d::from_raw(raw) };
+        assert_eq!(extent.width(), 640);
+        assert_eq!(extent.height(), 480);
+        assert_eq!(extent.as_raw().width, raw.width);
+        assert_eq!(extent.as_raw().height, raw.height);
+    }
+
+    #[test]
+    fn proc_addr_round_trips_device_commands() {
+        type QueueSubmitFn = unsafe extern "system" fn(vks::VkQueue, u32,
+            *const vks::VkSubmitInfo, vks::VkFence) -> vks::VkResult;
+
+        let device = null::device();
+        let name = CStr::from_bytes_with_nul(b"vkQueueSubmit\0").unwrap();
+        let queue_submit = unsafe { proc_addr!(device, name => QueueSubmitFn) }
+            .expect("vkQueueSubmit not found");
+
+        let mut submit_info = vks::VkSubmitInfo::default();
+        let command_buffer = 0x10 as vks::VkCommandBuffer;
+        submit_info.commandBufferCount = 1;
+        submit_info.pCommandBuffers = &command_buffer;
+        let result = unsafe {
+            queue_submit(null::queue_handle(0, 0), 1, &submit_info, 7)
+        };
+        assert_eq!(result, vks::VK_SUCCESS);
+        let submission = null::take_submission().unwrap();
+        assert_eq!(submission.command_buffers, [command_buffer]);
+        assert_eq!(submission.fence, 7);
+        let result = unsafe { queue_submit(null::queue_handle(0, 0), 0, ptr::null(), 0) };
+        assert_eq!(result, vks::VK_SUCCESS);
+    }
+
+    #[test]
+    fn get_proc_addr_returns_none_for_unknown_commands() {
+        let name = CStr::from_bytes_with_nul(b"vkFrobnicateEXT\0").unwrap();
+        assert!(null::device().get_proc_addr(name).is_none());
+        assert!(null::instance().get_proc_addr(name).is_none());
+
+        let name = CStr::from_bytes_with_nul(b"vkCreateDevice\0").unwrap();
+        assert!(null::instance().get_proc_addr(name).is_some());
+    }
+}
@@ -5,15 +5,17 @@ use libc::{c_char};
 use lib;
 use smallvec::SmallVec;
 use vks::{self};
-use ::{error, VdResult, Handle, InstanceHandle, CallResult, InstanceCreateInfo};
-use util::CharStrs;
+use std::str;
+use ::{error, VdResult, Handle, InstanceHandle, InstanceCreateInfo,
+    ExtensionProperties, LayerProperties};
+use util::{self, CharStrs};
 
-const PRINT: bool = false;
 
 
 /// A loaded library and `InstanceProcAddrLoader`.
 pub struct Loader {
-    _vk_lib: lib::Library,
+    // `None` for loaders which resolve functions without a library (tests).
+    _vk_lib: Option<lib::Library>,
     vk_get_instance_proc_addr: vks::PFN_vkGetInstanceProcAddr,
     instance_proc_addr_loader: vks::InstanceProcAddrLoader,
 }
@@ -44,7 +46,19 @@ impl Loader {
             instance_proc_addr_loader.load_vk_global();
         }
 
-        Ok(Loader { _vk_lib: vk_lib, vk_get_instance_proc_addr, instance_proc_addr_loader })
+        Ok(Loader { _vk_lib: Some(vk_lib), vk_get_instance_proc_addr, instance_proc_addr_loader })
+    }
+
+    /// Returns a loader which resolves all functions through
+    /// `vk_get_instance_proc_addr` rather than a loaded library.
+    #[cfg(test)]
+    pub(crate) fn from_get_instance_proc_addr(vk_get_instance_proc_addr: vks::PFN_vkGetInstanceProcAddr)
+            -> Loader {
+        let mut instance_proc_addr_loader = vks::InstanceProcAddrLoader::from_get_instance_proc_addr(vk_get_instance_proc_addr);
+        unsafe {
+            instance_proc_addr_loader.load_vk_global();
+        }
+        Loader { _vk_lib: None, vk_get_instance_proc_addr, instance_proc_addr_loader }
     }
 
     /// Returns the `vkGetInstanceProcAddr` function pointer.
@@ -74,53 +88,52 @@ impl Loader {
     }
 
     /// Returns all available instance layers.
-    pub fn enumerate_instance_layer_properties(&self) -> VdResult<SmallVec<[vks::VkLayerProperties; 64]>> {
-        let mut property_count = 0u32;
-        let mut properties = SmallVec::new();
+    //
+    // Layer properties are over 500 bytes each and typical installations
+    // provide well under sixteen layers.
+    pub fn enumerate_instance_layer_properties(&self) -> VdResult<SmallVec<[vks::VkLayerProperties; 16]>> {
         unsafe {
-            let result = self.core_global().vkEnumerateInstanceLayerProperties(&mut property_count,
-                ptr::null_mut());
-            error::check(result, "vkEnumerateInstanceLayerProperties", ())?;
-            properties.reserve_exact(property_count as usize);
-            properties.set_len(property_count as usize);
-            loop {
-                let result = self.core_global().vkEnumerateInstanceLayerProperties(&mut property_count,
-                    properties.as_mut_ptr());
-                if result != CallResult::Incomplete as i32 {
-                    error::check(result, "vkEnumerateInstanceLayerProperties", ())?;
-                    break;
-                }
-            }
+            util::enumerate("vkEnumerateInstanceLayerProperties", |count, p| {
+                self.core_global().vkEnumerateInstanceLayerProperties(count, p)
+            })
         }
-        Ok(properties)
     }
 
     /// Returns all available instance extensions.
     pub fn enumerate_instance_extension_properties(&self) -> VdResult<SmallVec<[vks::VkExtensionProperties; 64]>> {
-        let mut property_count = 0u32;
-        let mut properties = SmallVec::new();
         unsafe {
-            let result = self.core_global().vkEnumerateInstanceExtensionProperties(ptr::null(),
-                &mut property_count, ptr::null_mut());
-            error::check(result, "vkEnumerateInstanceExtensionProperties", ())?;
-            properties.reserve_exact(property_count as usize);
-            properties.set_len(property_count as usize);
-            loop {
-                let result = self.core_global().vkEnumerateInstanceExtensionProperties(ptr::null(),
-                    &mut property_count, properties.as_mut_ptr());
-                if result != CallResult::Incomplete as i32 {
-                    error::check(result, "vkEnumerateInstanceExtensionProperties", ())?;
-                    break;
-                }
-            }
+            let properties = util::enumerate::<[vks::VkExtensionProperties; 64], _>(
+                    "vkEnumerateInstanceExtensionProperties", |count, p| {
+                self.core_global().vkEnumerateInstanceExtensionProperties(ptr::null(), count, p)
+            })?;
             // Print available:
             for ext in properties.iter() {
                 let name = (&ext.extensionName) as *const c_char;
-                if PRINT { println!("Available instance extension: '{}' (version: {})",
-                    CStr::from_ptr(name).to_str().unwrap(), ext.specVersion); }
+                trace!("Available instance extension: '{}' (version: {})",
+                    CStr::from_ptr(name).to_str().unwrap(), ext.specVersion);
             }
+            Ok(properties)
+        }
+    }
+
+    /// Returns the instance extensions provided by the Vulkan implementation
+    /// and implicitly enabled layers or, if `layer` is specified, by that
+    /// layer.
+    pub fn instance_extensions(&self, layer: Option<&CStr>) -> VdResult<Vec<ExtensionProperties>> {
+        let layer = layer.map(|l| l.as_ptr()).unwrap_or(ptr::null());
+        unsafe {
+            let properties = util::enumerate::<[vks::VkExtensionProperties; 64], _>(
+                    "vkEnumerateInstanceExtensionProperties", |count, p| {
+                self.core_global().vkEnumerateInstanceExtensionProperties(layer, count, p)
+            })?;
+            Ok(properties.into_iter().map(|p| ExtensionProperties::from_raw(p)).collect())
         }
-        Ok(properties)
+    }
+
+    /// Returns the available instance layers.
+    pub fn instance_layers(&self) -> VdResult<Vec<LayerProperties>> {
+        let properties = self.enumerate_instance_layer_properties()?;
+        unsafe { Ok(properties.into_iter().map(|p| LayerProperties::from_raw(p)).collect()) }
     }
 
     /// Verifies that each layer name listed is available.
@@ -130,8 +143,8 @@ impl Loader {
         // Print available layers:
         for layer_props in &available_layers {
             unsafe {
-                if PRINT { println!("Available layer: '{}'",
-                    CStr::from_ptr(layer_props.layerName.as_ptr()).to_str().unwrap()); }
+                trace!("Available layer: '{}'",
+                    CStr::from_ptr(layer_props.layerName.as_ptr()).to_str().unwrap());
             }
         }
 
@@ -141,8 +154,8 @@ impl Loader {
             for layer_props in &available_layers {
                 unsafe {
                     if CStr::from_ptr(layer_name) == CStr::from_ptr(layer_props.layerName.as_ptr()) {
-                        if PRINT { println!("Layer validated: '{}'",
-                            CStr::from_ptr(layer_name).to_str().unwrap()); }
+                        trace!("Layer validated: '{}'",
+                            CStr::from_ptr(layer_name).to_str().unwrap());
                         layer_found = true;
                         break;
                     }
@@ -160,26 +173,16 @@ impl Loader {
     // *PFN_vkEnumeratePhysicalDevices)(VkInstance instance, uint32_t*
     // pPhysicalDeviceCount, VkPhysicalDevice* pPhysicalDevices);
     pub fn enumerate_physical_devices<I>(&self, instance: I)
-            -> VdResult<SmallVec<[vks::VkPhysicalDevice; 16]>>
+            -> VdResult<Vec<vks::VkPhysicalDevice>>
             where I: Handle<Target=InstanceHandle> {
-        let mut device_count = 0;
-        let mut devices_raw = SmallVec::new();
-        unsafe {
-            error::check(self.instance_proc_addr_loader.vk.vkEnumeratePhysicalDevices(instance.handle().0,
-                &mut device_count, ptr::null_mut()), "vkEnumeratePhysicalDevices", ())?;
-            if device_count == 0 { panic!("No physical devices found."); }
-            assert!(device_count as usize <= devices_raw.inline_size());
-            devices_raw.set_len(device_count as usize);
-            loop {
-                let result = self.instance_proc_addr_loader.vk.vkEnumeratePhysicalDevices(instance.handle().0,
-                    &mut device_count, devices_raw.as_mut_ptr());
-                if result != CallResult::Incomplete as i32 {
-                        error::check(result, "vkEnumeratePhysicalDevices", ())?;
-                        break;
-                    }
-                }
-            }
-        if PRINT { println!("Available devices: {:?}", devices_raw); }
+        let instance = instance.handle().to_raw();
+        let devices_raw = unsafe {
+            util::enumerate::<[vks::VkPhysicalDevice; 4], _>("vkEnumeratePhysicalDevices",
+                    |count, p| {
+                self.instance_proc_addr_loader.vk.vkEnumeratePhysicalDevices(instance, count, p)
+            })?.into_vec()
+        };
+        trace!("Available devices: {:?}", devices_raw);
         Ok(devices_raw)
     }
 
@@ -216,4 +219,837 @@ impl fmt::Debug for Loader {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Loader")
     }
-}
\ No newline at end of file
+}
+
+
+/// Returns the contents of a fixed-size, NUL-padded character array up to
+/// the first NUL or, if there is none, the whole array.
+///
+/// Names and descriptions in property structures are required to be valid
+/// UTF-8. Should one not be, the valid prefix is returned.
+pub(crate) fn fixed_array_str(chars: &[c_char]) -> &str {
+    let bytes = unsafe { ::std::slice::from_raw_parts(chars.as_ptr() as *const u8, chars.len()) };
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    match str::from_utf8(&bytes[..len]) {
+        Ok(s) => s,
+        Err(err) => unsafe { str::from_utf8_unchecked(&bytes[..err.valid_up_to()]) },
+    }
+}
+
+impl ExtensionProperties {
+    /// Returns the name of this extension.
+    ///
+    /// Unlike `extension_name`, does not rely on the name being
+    /// NUL-terminated within its array.
+    pub fn name(&self) -> &str {
+        fixed_array_str(&self.as_raw().extensionName)
+    }
+}
+
+impl LayerProperties {
+    /// Returns the name of this layer.
+    ///
+    /// Unlike `layer_name`, does not rely on the name being NUL-terminated
+    /// within its array.
+    pub fn name(&self) -> &str {
+        fixed_array_str(&self.as_raw().layerName)
+    }
+
+    /// Returns the description of this layer.
+    pub fn description_str(&self) -> &str {
+        fixed_array_str(&self.as_raw().description)
+    }
+}
+
+
+/// A stand-in Vulkan implementation for unit tests.
+///
+/// Resolves a handful of query functions, each of which fills its output
+/// structure with fixed values, allowing the conversions performed by the
+/// `Instance` and `Device` wrappers to be checked without a driver. All
+/// other functions resolve to null.
+#[cfg(test)]
+pub(crate) mod null {
+    use std::ffi::CStr;
+    use std::mem;
+    use std::slice;
+    use std::cell::{Cell, RefCell};
+    use libc::{c_char, c_void};
+    use vks;
+    use counting_alloc;
+    use multiview::VkRenderPassMultiviewCreateInfoKHR;
+    use ::{Instance, PhysicalDevice, PhysicalDeviceHandle, Device, PhysicalDeviceType,
+        MemoryPropertyFlags, FormatFeatureFlags, QueueFlags, ImageAspectFlags, BaseOutStructure};
+    use super::Loader;
+
+    pub const API_VERSION: (u16, u16, u16) = (1, 0, 42);
+    pub const DEVICE_NAME: &[u8] = b"voodoo null device";
+    pub const VENDOR_ID: u32 = 0x10de;
+    pub const DEVICE_ID: u32 = 0x1b80;
+    pub const PIPELINE_CACHE_UUID: [u8; 16] = [0x76, 0x6f, 0x6f, 0x64, 0x6f, 0x6f, 0x20, 0x6e,
+        0x75, 0x6c, 0x6c, 0x20, 0x75, 0x75, 0x69, 0x64];
+    pub const MAX_IMAGE_DIMENSION_2D: u32 = 4096;
+    pub const HEAP_SIZE: u64 = 1 << 30;
+    /// The index of the host-visible, non-coherent memory type.
+    pub const NON_COHERENT_MEMORY_TYPE: u32 = 1;
+    /// The index of the host-visible, host-coherent memory type.
+    pub const COHERENT_MEMORY_TYPE: u32 = 2;
+    pub const NON_COHERENT_ATOM_SIZE: u64 = 64;
+    /// The size of buffer memory requirements, and the number of bytes of
+    /// any allocation which may be mapped.
+    pub const MEMORY_SIZE: u64 = 65536;
+    pub const MEMORY_ALIGNMENT: u64 = 256;
+    pub const MIP_LEVEL_SIZE: u64 = 4096;
+    pub const GRANULARITY: (u32, u32) = (32, 16);
+    pub const BUFFER_IMAGE_GRANULARITY: u64 = 1024;
+    pub const MAX_COMPUTE_WORK_GROUP_SIZE: [u32; 3] = [1024, 1024, 64];
+    pub const MAX_VIEWPORT_DIMENSIONS: [u32; 2] = [16384, 8192];
+    pub const POINT_SIZE_RANGE: [f32; 2] = [1.0, 64.0];
+    /// The handle of buffers created with `vkCreateBuffer`.
+    pub const CREATED_BUFFER: u64 = 0x8000;
+    /// The handle of render passes created with `vkCreateRenderPass`.
+    pub const CREATED_RENDER_PASS: u64 = 0x5000;
+    /// The handle of command pools created with `vkCreateCommandPool`.
+    pub const CREATED_COMMAND_POOL: u64 = 0x6000;
+    /// The handle of query pools created with `vkCreateQueryPool`.
+    pub const CREATED_QUERY_POOL: u64 = 0x7000;
+    /// The handle of instances created with `vkCreateInstance`.
+    pub const CREATED_INSTANCE: usize = 0x1100;
+    /// The handle of devices created with `vkCreateDevice`.
+    pub const CREATED_DEVICE: usize = 0x3100;
+    /// Submissions signaling this fence fail with `VK_ERROR_DEVICE_LOST`.
+    pub const LOST_FENCE: u64 = 0xdead;
+
+    /// The contents of a single batch of a `vkQueueSubmit` call.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct Submission {
+        pub wait_semaphores: Vec<vks::VkSemaphore>,
+        pub wait_dst_stage_masks: Vec<vks::VkPipelineStageFlags>,
+        pub command_buffers: Vec<vks::VkCommandBuffer>,
+        pub signal_semaphores: Vec<vks::VkSemaphore>,
+        pub fence: vks::VkFence,
+    }
+
+    /// The arguments of a single `vkCmdBindVertexBuffers` call.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct VertexBufferBinding {
+        pub first_binding: u32,
+        pub buffers: Vec<vks::VkBuffer>,
+        pub offsets: Vec<vks::VkDeviceSize>,
+    }
+
+    /// A single buffer memory bind made by a `vkQueueBindSparse` call.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct SparseBufferBind {
+        pub buffer: vks::VkBuffer,
+        pub resource_offset: vks::VkDeviceSize,
+        pub size: vks::VkDeviceSize,
+        pub memory: vks::VkDeviceMemory,
+        pub memory_offset: vks::VkDeviceSize,
+    }
+
+    /// The `VkRenderPassMultiviewCreateInfoKHR` chained onto a
+    /// `vkCreateRenderPass` call.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct RenderPassMultiview {
+        pub view_masks: Vec<u32>,
+        pub view_offsets: Vec<i32>,
+        pub correlation_masks: Vec<u32>,
+    }
+
+    /// A range of a memory object passed to `vkMapMemory`,
+    /// `vkFlushMappedMemoryRanges`, or `vkInvalidateMappedMemoryRanges`.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct MemoryRange {
+        pub memory: vks::VkDeviceMemory,
+        pub offset: vks::VkDeviceSize,
+        pub size: vks::VkDeviceSize,
+    }
+
+    /// A call mapping, unmapping, flushing, or invalidating memory.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum MemoryCall {
+        Map(MemoryRange),
+        Unmap(vks::VkDeviceMemory),
+        Flush(Vec<MemoryRange>),
+        Invalidate(Vec<MemoryRange>),
+    }
+
+    thread_local! {
+        static LAST_SUBMISSIONS: RefCell<Vec<Submission>> = RefCell::new(Vec::new());
+        static LIFETIME_CALLS: RefCell<Vec<(&'static str, usize)>> = RefCell::new(Vec::new());
+        static NEXT_MEMORY: Cell<vks::VkDeviceMemory> = Cell::new(0x10000);
+        static PROPERTIES_QUERIES: Cell<usize> = Cell::new(0);
+        static VERTEX_BUFFER_BINDINGS: RefCell<Vec<VertexBufferBinding>> = RefCell::new(Vec::new());
+        static SPARSE_BUFFER_BINDS: RefCell<Vec<SparseBufferBind>> = RefCell::new(Vec::new());
+        static MEMORY_CALLS: RefCell<Vec<MemoryCall>> = RefCell::new(Vec::new());
+        // Backs every mapping made on this thread.
+        static HOST_MEMORY: RefCell<Vec<u64>> = RefCell::new(vec![0; MEMORY_SIZE as usize / 8]);
+        #[cfg(feature = "experimental")]
+        static DEVICE_GROUPS: RefCell<Vec<Option<Vec<vks::VkPhysicalDevice>>>> =
+            RefCell::new(Vec::new());
+        static ALLOCATORS: RefCell<Vec<(&'static str, usize)>> = RefCell::new(Vec::new());
+        static COMMANDS: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+        static RENDER_PASS_MULTIVIEWS: RefCell<Vec<Option<RenderPassMultiview>>> =
+            RefCell::new(Vec::new());
+    }
+
+    /// Returns and clears the batches of the last `vkQueueSubmit` call made
+    /// on this thread.
+    pub fn take_submissions() -> Vec<Submission> {
+        LAST_SUBMISSIONS.with(|s| s.borrow_mut().split_off(0))
+    }
+
+    /// Returns and clears the last submission made on this thread if it
+    /// consisted of a single batch.
+    pub fn take_submission() -> Option<Submission> {
+        let mut submissions = take_submissions();
+        if submissions.len() == 1 { submissions.pop() } else { None }
+    }
+
+    /// Returns and clears the names of the device wait idle and destruction
+    /// functions called on this thread, along with the raw handle of the
+    /// instance or device each was called on.
+    pub fn take_lifetime_calls() -> Vec<(&'static str, usize)> {
+        LIFETIME_CALLS.with(|c| c.borrow_mut().split_off(0))
+    }
+
+    /// Returns and clears the number of `vkGetPhysicalDeviceProperties` calls
+    /// made on this thread.
+    pub fn take_properties_queries() -> usize {
+        PROPERTIES_QUERIES.with(|count| count.replace(0))
+    }
+
+    /// Returns and clears the `vkCmdBindVertexBuffers` calls made on this
+    /// thread.
+    pub fn take_vertex_buffer_bindings() -> Vec<VertexBufferBinding> {
+        VERTEX_BUFFER_BINDINGS.with(|b| b.borrow_mut().split_off(0))
+    }
+
+    /// Returns and clears the buffer memory binds made by each
+    /// `vkQueueBindSparse` call on this thread, in order.
+    pub fn take_sparse_buffer_binds() -> Vec<SparseBufferBind> {
+        SPARSE_BUFFER_BINDS.with(|b| b.borrow_mut().split_off(0))
+    }
+
+    /// Returns and clears the multiview parameters of each
+    /// `vkCreateRenderPass` call made on this thread, or `None` for calls
+    /// without them.
+    pub fn take_render_pass_multiviews() -> Vec<Option<RenderPassMultiview>> {
+        RENDER_PASS_MULTIVIEWS.with(|m| m.borrow_mut().split_off(0))
+    }
+
+    /// Returns and clears the memory mapping, unmapping, flushing, and
+    /// invalidation calls made on this thread, in order.
+    pub fn take_memory_calls() -> Vec<MemoryCall> {
+        MEMORY_CALLS.with(|c| c.borrow_mut().split_off(0))
+    }
+
+    fn record_memory_call(call: MemoryCall) {
+        MEMORY_CALLS.with(|c| c.borrow_mut().push(call));
+    }
+
+    /// Returns and clears the names of the render pass, query, and draw
+    /// commands recorded on this thread, in order.
+    pub fn take_commands() -> Vec<&'static str> {
+        COMMANDS.with(|c| c.borrow_mut().split_off(0))
+    }
+
+    fn record_command(name: &'static str) {
+        COMMANDS.with(|c| c.borrow_mut().push(name));
+    }
+
+    /// Returns and clears the physical devices of the device group chained
+    /// onto each `vkCreateDevice` call made on this thread, or `None` for
+    /// calls without one.
+    #[cfg(feature = "experimental")]
+    pub fn take_device_groups() -> Vec<Option<Vec<vks::VkPhysicalDevice>>> {
+        DEVICE_GROUPS.with(|g| g.borrow_mut().split_off(0))
+    }
+
+    /// Returns and clears the device and buffer creation and destruction
+    /// calls made on this thread, along with the `pUserData` of the
+    /// allocation callbacks passed to each (zero if none were).
+    pub fn take_allocators() -> Vec<(&'static str, usize)> {
+        ALLOCATORS.with(|a| a.borrow_mut().split_off(0))
+    }
+
+    unsafe fn record_allocator(name: &'static str, allocator: *const vks::VkAllocationCallbacks) {
+        let user_data = if allocator.is_null() { 0 } else { (*allocator).pUserData as usize };
+        ALLOCATORS.with(|a| a.borrow_mut().push((name, user_data)));
+    }
+
+    fn record_lifetime_call(name: &'static str, handle: usize) {
+        LIFETIME_CALLS.with(|c| c.borrow_mut().push((name, handle)));
+    }
+
+    unsafe extern "system" fn destroy_instance(instance: vks::VkInstance,
+            _: *const vks::VkAllocationCallbacks) {
+        record_lifetime_call("vkDestroyInstance", instance as usize);
+    }
+
+    unsafe extern "system" fn destroy_device(device: vks::VkDevice,
+            allocator: *const vks::VkAllocationCallbacks) {
+        record_allocator("vkDestroyDevice", allocator);
+        record_lifetime_call("vkDestroyDevice", device as usize);
+    }
+
+    unsafe extern "system" fn create_instance(_: *const vks::VkInstanceCreateInfo,
+            _: *const vks::VkAllocationCallbacks, instance: *mut vks::VkInstance)
+            -> vks::VkResult {
+        *instance = CREATED_INSTANCE as vks::VkInstance;
+        vks::VK_SUCCESS
+    }
+
+    #[cfg_attr(not(feature = "experimental"), allow(unused_variables))]
+    unsafe extern "system" fn create_device(_: vks::VkPhysicalDevice,
+            info: *const vks::VkDeviceCreateInfo, allocator: *const vks::VkAllocationCallbacks,
+            device: *mut vks::VkDevice) -> vks::VkResult {
+        record_allocator("vkCreateDevice", allocator);
+        #[cfg(feature = "experimental")]
+        record_device_group(info);
+        *device = CREATED_DEVICE as vks::VkDevice;
+        vks::VK_SUCCESS
+    }
+
+    #[cfg(feature = "experimental")]
+    unsafe fn record_device_group(info: *const vks::VkDeviceCreateInfo) {
+        let mut next = (*info).pNext as *const BaseOutStructure;
+        let mut physical_devices = None;
+        while !next.is_null() {
+            if (*next).s_type == vks::VK_STRUCTURE_TYPE_DEVICE_GROUP_DEVICE_CREATE_INFO_KHX {
+                let raw = &*(next as *const vks::VkDeviceGroupDeviceCreateInfoKHX);
+                physical_devices = Some(to_vec(raw.pPhysicalDevices, raw.physicalDeviceCount));
+            }
+            next = (*next).p_next;
+        }
+        DEVICE_GROUPS.with(|g| g.borrow_mut().push(physical_devices));
+    }
+
+    /// Reports a single group containing only the null physical device.
+    #[cfg(feature = "experimental")]
+    unsafe extern "system" fn enumerate_physical_device_groups_khx(_: vks::VkInstance,
+            count: *mut u32, groups: *mut vks::VkPhysicalDeviceGroupPropertiesKHX)
+            -> vks::VkResult {
+        if groups.is_null() {
+            *count = 1;
+            return vks::VK_SUCCESS;
+        }
+        if *count == 0 { return vks::VK_INCOMPLETE; }
+        *count = 1;
+        (*groups).physicalDeviceCount = 1;
+        (*groups).physicalDevices[0] = physical_device_handle().to_raw();
+        (*groups).subsetAllocation = vks::VK_FALSE;
+        vks::VK_SUCCESS
+    }
+
+    /// Returns the handle written for queue `queue_index` of the family
+    /// `family_index`.
+    pub fn queue_handle(family_index: u32, queue_index: u32) -> vks::VkQueue {
+        (0x4000 + family_index as usize * 0x10 + queue_index as usize) as vks::VkQueue
+    }
+
+    unsafe extern "system" fn get_device_queue(_: vks::VkDevice, family_index: u32,
+            queue_index: u32, queue: *mut vks::VkQueue) {
+        *queue = queue_handle(family_index, queue_index);
+    }
+
+    unsafe extern "system" fn device_wait_idle(device: vks::VkDevice) -> vks::VkResult {
+        record_lifetime_call("vkDeviceWaitIdle", device as usize);
+        vks::VK_SUCCESS
+    }
+
+    type VoidFunction = Option<unsafe extern "system" fn()>;
+
+    unsafe extern "system" fn get_physical_device_features(_: vks::VkPhysicalDevice,
+            features: *mut vks::VkPhysicalDeviceFeatures) {
+        (*features).geometryShader = vks::VK_TRUE;
+    }
+
+    unsafe extern "system" fn get_physical_device_properties(_: vks::VkPhysicalDevice,
+            properties: *mut vks::VkPhysicalDeviceProperties) {
+        PROPERTIES_QUERIES.with(|count| count.set(count.get() + 1));
+        let properties = &mut *properties;
+        properties.apiVersion = ::Version::from(API_VERSION).into();
+        properties.deviceType = PhysicalDeviceType::DiscreteGpu.into();
+        properties.vendorID = VENDOR_ID;
+        properties.deviceID = DEVICE_ID;
+        properties.pipelineCacheUUID = PIPELINE_CACHE_UUID;
+        for (dst, &src) in properties.deviceName.iter_mut().zip(DEVICE_NAME) {
+            *dst = src as c_char;
+        }
+        properties.limits.maxImageDimension2D = MAX_IMAGE_DIMENSION_2D;
+        properties.limits.bufferImageGranularity = BUFFER_IMAGE_GRANULARITY;
+        properties.limits.maxComputeWorkGroupSize = MAX_COMPUTE_WORK_GROUP_SIZE;
+        properties.limits.maxViewportDimensions = MAX_VIEWPORT_DIMENSIONS;
+        properties.limits.pointSizeRange = POINT_SIZE_RANGE;
+        properties.limits.nonCoherentAtomSize = NON_COHERENT_ATOM_SIZE;
+    }
+
+    /// Returns the flags and queue count of each queue family.
+    pub fn queue_families() -> [(QueueFlags, u32); 4] {
+        [
+            (QueueFlags::TRANSFER, 0),
+            (QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER, 1),
+            (QueueFlags::COMPUTE | QueueFlags::TRANSFER, 2),
+            (QueueFlags::TRANSFER, 1),
+        ]
+    }
+
+    unsafe extern "system" fn get_physical_device_queue_family_properties(_: vks::VkPhysicalDevice,
+            count: *mut u32, properties: *mut vks::VkQueueFamilyProperties) {
+        let families = queue_families();
+        if properties.is_null() {
+            *count = families.len() as u32;
+            return;
+        }
+        *count = (*count).min(families.len() as u32);
+        for (i, &(flags, queue_count)) in families.iter().take(*count as usize).enumerate() {
+            let properties = &mut *properties.offset(i as isize);
+            properties.queueFlags = flags.bits();
+            properties.queueCount = queue_count;
+        }
+    }
+
+    unsafe extern "system" fn get_physical_device_memory_properties(_: vks::VkPhysicalDevice,
+            properties: *mut vks::VkPhysicalDeviceMemoryProperties) {
+        let properties = &mut *properties;
+        let types = [
+            MemoryPropertyFlags::DEVICE_LOCAL,
+            MemoryPropertyFlags::HOST_VISIBLE,
+            MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT,
+        ];
+        properties.memoryTypeCount = types.len() as u32;
+        for (memory_type, flags) in properties.memoryTypes.iter_mut().zip(types.iter()) {
+            memory_type.propertyFlags = flags.bits();
+            memory_type.heapIndex = 0;
+        }
+        properties.memoryHeapCount = 1;
+        properties.memoryHeaps[0].size = HEAP_SIZE;
+    }
+
+    unsafe extern "system" fn get_physical_device_format_properties(_: vks::VkPhysicalDevice,
+            _: vks::VkFormat, properties: *mut vks::VkFormatProperties) {
+        (*properties).optimalTilingFeatures = FormatFeatureFlags::SAMPLED_IMAGE.bits();
+    }
+
+    unsafe extern "system" fn allocate_memory(_: vks::VkDevice,
+            _: *const vks::VkMemoryAllocateInfo, _: *const vks::VkAllocationCallbacks,
+            memory: *mut vks::VkDeviceMemory) -> vks::VkResult {
+        *memory = NEXT_MEMORY.with(|next| {
+            let handle = next.get();
+            next.set(handle + 1);
+            handle
+        });
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn free_memory(_: vks::VkDevice, _: vks::VkDeviceMemory,
+            _: *const vks::VkAllocationCallbacks) {}
+
+    /// Fails with `VK_ERROR_MEMORY_MAP_FAILED` for ranges extending past the
+    /// first `MEMORY_SIZE` bytes of the memory object.
+    unsafe extern "system" fn map_memory(_: vks::VkDevice, memory: vks::VkDeviceMemory,
+            offset: vks::VkDeviceSize, size: vks::VkDeviceSize, _: vks::VkMemoryMapFlags,
+            data: *mut *mut c_void) -> vks::VkResult {
+        record_memory_call(MemoryCall::Map(MemoryRange { memory, offset, size }));
+        let end = if size == ::WHOLE_SIZE { offset } else { offset.saturating_add(size) };
+        if offset >= MEMORY_SIZE || end > MEMORY_SIZE {
+            return vks::VK_ERROR_MEMORY_MAP_FAILED;
+        }
+        *data = HOST_MEMORY.with(|m| {
+            (m.borrow_mut().as_mut_ptr() as *mut u8).offset(offset as isize) as *mut c_void
+        });
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn unmap_memory(_: vks::VkDevice, memory: vks::VkDeviceMemory) {
+        record_memory_call(MemoryCall::Unmap(memory));
+    }
+
+    unsafe fn memory_ranges(count: u32, ranges: *const vks::VkMappedMemoryRange)
+            -> Vec<MemoryRange> {
+        if count == 0 { return Vec::new(); }
+        slice::from_raw_parts(ranges, count as usize).iter()
+            .map(|r| MemoryRange { memory: r.memory, offset: r.offset, size: r.size })
+            .collect()
+    }
+
+    unsafe extern "system" fn flush_mapped_memory_ranges(_: vks::VkDevice, count: u32,
+            ranges: *const vks::VkMappedMemoryRange) -> vks::VkResult {
+        record_memory_call(MemoryCall::Flush(memory_ranges(count, ranges)));
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn invalidate_mapped_memory_ranges(_: vks::VkDevice, count: u32,
+            ranges: *const vks::VkMappedMemoryRange) -> vks::VkResult {
+        record_memory_call(MemoryCall::Invalidate(memory_ranges(count, ranges)));
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn get_buffer_memory_requirements(_: vks::VkDevice, _: vks::VkBuffer,
+            requirements: *mut vks::VkMemoryRequirements) {
+        (*requirements).size = MEMORY_SIZE;
+        (*requirements).alignment = MEMORY_ALIGNMENT;
+        (*requirements).memoryTypeBits = 1;
+    }
+
+    unsafe extern "system" fn get_image_memory_requirements(_: vks::VkDevice, _: vks::VkImage,
+            requirements: *mut vks::VkMemoryRequirements) {
+        (*requirements).size = MEMORY_SIZE * 2;
+        (*requirements).alignment = MEMORY_ALIGNMENT * 2;
+        (*requirements).memoryTypeBits = 1;
+    }
+
+    unsafe extern "system" fn get_image_subresource_layout(_: vks::VkDevice, _: vks::VkImage,
+            subresource: *const vks::VkImageSubresource, layout: *mut vks::VkSubresourceLayout) {
+        (*layout).offset = (*subresource).mipLevel as u64 * MIP_LEVEL_SIZE;
+        (*layout).size = MIP_LEVEL_SIZE;
+        (*layout).rowPitch = 256;
+    }
+
+    unsafe extern "system" fn get_render_area_granularity(_: vks::VkDevice, _: vks::VkRenderPass,
+            granularity: *mut vks::VkExtent2D) {
+        (*granularity).width = GRANULARITY.0;
+        (*granularity).height = GRANULARITY.1;
+    }
+
+    /// Returns the image aspects for which sparse memory requirements are
+    /// reported.
+    pub fn sparse_aspects() -> [ImageAspectFlags; 2] {
+        [ImageAspectFlags::COLOR, ImageAspectFlags::METADATA]
+    }
+
+    unsafe extern "system" fn get_image_sparse_memory_requirements(_: vks::VkDevice,
+            _: vks::VkImage, count: *mut u32,
+            requirements: *mut vks::VkSparseImageMemoryRequirements) {
+        let aspects = sparse_aspects();
+        if requirements.is_null() {
+            *count = aspects.len() as u32;
+            return;
+        }
+        *count = (*count).min(aspects.len() as u32);
+        for (i, aspect) in aspects.iter().take(*count as usize).enumerate() {
+            let requirements = &mut *requirements.offset(i as isize);
+            requirements.formatProperties.aspectMask = aspect.bits();
+            requirements.imageMipTailSize = MIP_LEVEL_SIZE;
+        }
+    }
+
+    unsafe fn to_vec<T: Copy>(ptr: *const T, count: u32) -> Vec<T> {
+        if count == 0 { return Vec::new(); }
+        slice::from_raw_parts(ptr, count as usize).to_vec()
+    }
+
+    unsafe extern "system" fn queue_submit(_: vks::VkQueue, submit_count: u32,
+            submits: *const vks::VkSubmitInfo, fence: vks::VkFence) -> vks::VkResult {
+        let submits = if submit_count == 0 {
+            &[][..]
+        } else {
+            slice::from_raw_parts(submits, submit_count as usize)
+        };
+        // Recording is not attributed to the submitting code:
+        counting_alloc::uncounted(|| {
+            let submissions = submits.iter().map(|submit| {
+                let wait_count = submit.waitSemaphoreCount;
+                Submission {
+                    wait_semaphores: to_vec(submit.pWaitSemaphores, wait_count),
+                    wait_dst_stage_masks: to_vec(submit.pWaitDstStageMask, wait_count),
+                    command_buffers: to_vec(submit.pCommandBuffers, submit.commandBufferCount),
+                    signal_semaphores: to_vec(submit.pSignalSemaphores,
+                        submit.signalSemaphoreCount),
+                    fence,
+                }
+            }).collect();
+            LAST_SUBMISSIONS.with(|s| *s.borrow_mut() = submissions);
+        });
+        if fence == LOST_FENCE { vks::VK_ERROR_DEVICE_LOST } else { vks::VK_SUCCESS }
+    }
+
+    unsafe extern "system" fn queue_bind_sparse(_: vks::VkQueue, bind_info_count: u32,
+            bind_infos: *const vks::VkBindSparseInfo, _: vks::VkFence) -> vks::VkResult {
+        let bind_infos = if bind_info_count == 0 {
+            &[][..]
+        } else {
+            slice::from_raw_parts(bind_infos, bind_info_count as usize)
+        };
+        for bind_info in bind_infos {
+            for i in 0..bind_info.bufferBindCount as usize {
+                let buffer_binds = &*bind_info.pBufferBinds.offset(i as isize);
+                for j in 0..buffer_binds.bindCount as usize {
+                    let bind = &*buffer_binds.pBinds.offset(j as isize);
+                    SPARSE_BUFFER_BINDS.with(|b| b.borrow_mut().push(SparseBufferBind {
+                        buffer: buffer_binds.buffer,
+                        resource_offset: bind.resourceOffset,
+                        size: bind.size,
+                        memory: bind.memory,
+                        memory_offset: bind.memoryOffset,
+                    }));
+                }
+            }
+        }
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn cmd_bind_vertex_buffers(_: vks::VkCommandBuffer,
+            first_binding: u32, binding_count: u32, buffers: *const vks::VkBuffer,
+            offsets: *const vks::VkDeviceSize) {
+        let binding = VertexBufferBinding {
+            first_binding,
+            buffers: to_vec(buffers, binding_count),
+            offsets: to_vec(offsets, binding_count),
+        };
+        VERTEX_BUFFER_BINDINGS.with(|b| b.borrow_mut().push(binding));
+    }
+
+    unsafe extern "system" fn create_buffer(_: vks::VkDevice,
+            _: *const vks::VkBufferCreateInfo, allocator: *const vks::VkAllocationCallbacks,
+            buffer: *mut vks::VkBuffer) -> vks::VkResult {
+        record_allocator("vkCreateBuffer", allocator);
+        *buffer = CREATED_BUFFER;
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn destroy_buffer(_: vks::VkDevice, _: vks::VkBuffer,
+            allocator: *const vks::VkAllocationCallbacks) {
+        record_allocator("vkDestroyBuffer", allocator);
+    }
+
+    unsafe extern "system" fn create_render_pass(_: vks::VkDevice,
+            info: *const vks::VkRenderPassCreateInfo, _: *const vks::VkAllocationCallbacks,
+            render_pass: *mut vks::VkRenderPass) -> vks::VkResult {
+        let multiview_type = VkRenderPassMultiviewCreateInfoKHR::default().sType;
+        let mut next = (*info).pNext as *const BaseOutStructure;
+        let mut multiview = None;
+        while !next.is_null() {
+            if (*next).s_type == multiview_type {
+                let raw = &*(next as *const VkRenderPassMultiviewCreateInfoKHR);
+                multiview = Some(RenderPassMultiview {
+                    view_masks: to_vec(raw.pViewMasks, raw.subpassCount),
+                    view_offsets: to_vec(raw.pViewOffsets, raw.dependencyCount),
+                    correlation_masks: to_vec(raw.pCorrelationMasks, raw.correlationMaskCount),
+                });
+            }
+            next = (*next).p_next;
+        }
+        RENDER_PASS_MULTIVIEWS.with(|m| m.borrow_mut().push(multiview));
+        *render_pass = CREATED_RENDER_PASS;
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn destroy_render_pass(_: vks::VkDevice, _: vks::VkRenderPass,
+            _: *const vks::VkAllocationCallbacks) {}
+
+    unsafe extern "system" fn create_command_pool(_: vks::VkDevice,
+            _: *const vks::VkCommandPoolCreateInfo, _: *const vks::VkAllocationCallbacks,
+            command_pool: *mut vks::VkCommandPool) -> vks::VkResult {
+        *command_pool = CREATED_COMMAND_POOL;
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn destroy_command_pool(_: vks::VkDevice, _: vks::VkCommandPool,
+            _: *const vks::VkAllocationCallbacks) {}
+
+    unsafe extern "system" fn free_command_buffers(_: vks::VkDevice, _: vks::VkCommandPool,
+            _: u32, _: *const vks::VkCommandBuffer) {}
+
+    unsafe extern "system" fn begin_command_buffer(_: vks::VkCommandBuffer,
+            _: *const vks::VkCommandBufferBeginInfo) -> vks::VkResult {
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn end_command_buffer(_: vks::VkCommandBuffer) -> vks::VkResult {
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn create_query_pool(_: vks::VkDevice,
+            _: *const vks::VkQueryPoolCreateInfo, _: *const vks::VkAllocationCallbacks,
+            query_pool: *mut vks::VkQueryPool) -> vks::VkResult {
+        *query_pool = CREATED_QUERY_POOL;
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn destroy_query_pool(_: vks::VkDevice, _: vks::VkQueryPool,
+            _: *const vks::VkAllocationCallbacks) {}
+
+    unsafe extern "system" fn cmd_begin_render_pass(_: vks::VkCommandBuffer,
+            _: *const vks::VkRenderPassBeginInfo, _: vks::VkSubpassContents) {
+        record_command("vkCmdBeginRenderPass");
+    }
+
+    unsafe extern "system" fn cmd_end_render_pass(_: vks::VkCommandBuffer) {
+        record_command("vkCmdEndRenderPass");
+    }
+
+    unsafe extern "system" fn cmd_begin_query(_: vks::VkCommandBuffer, _: vks::VkQueryPool,
+            _: u32, _: vks::VkQueryControlFlags) {
+        record_command("vkCmdBeginQuery");
+    }
+
+    unsafe extern "system" fn cmd_end_query(_: vks::VkCommandBuffer, _: vks::VkQueryPool,
+            _: u32) {
+        record_command("vkCmdEndQuery");
+    }
+
+    unsafe extern "system" fn cmd_draw(_: vks::VkCommandBuffer, _: u32, _: u32, _: u32,
+            _: u32) {
+        record_command("vkCmdDraw");
+    }
+
+    /// Returns the handle written for element `index` of an allocation from
+    /// the pool with raw handle `pool`.
+    pub fn allocated_handle(pool: u64, index: usize) -> u64 {
+        pool * 0x100 + index as u64 + 1
+    }
+
+    unsafe extern "system" fn allocate_command_buffers(_: vks::VkDevice,
+            info: *const vks::VkCommandBufferAllocateInfo,
+            command_buffers: *mut vks::VkCommandBuffer) -> vks::VkResult {
+        for i in 0..(*info).commandBufferCount as usize {
+            *command_buffers.offset(i as isize) =
+                allocated_handle((*info).commandPool, i) as vks::VkCommandBuffer;
+        }
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn allocate_descriptor_sets(_: vks::VkDevice,
+            info: *const vks::VkDescriptorSetAllocateInfo,
+            descriptor_sets: *mut vks::VkDescriptorSet) -> vks::VkResult {
+        for i in 0..(*info).descriptorSetCount as usize {
+            *descriptor_sets.offset(i as isize) = allocated_handle((*info).descriptorPool, i);
+        }
+        vks::VK_SUCCESS
+    }
+
+    unsafe extern "system" fn get_device_proc_addr(_: vks::VkDevice, name: *const c_char)
+            -> VoidFunction {
+        match CStr::from_ptr(name).to_bytes() {
+            b"vkAllocateMemory" => Some(mem::transmute(allocate_memory
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkFreeMemory" => Some(mem::transmute(free_memory
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkMapMemory" => Some(mem::transmute(map_memory
+                as unsafe extern "system" fn(_, _, _, _, _, _) -> _)),
+            b"vkUnmapMemory" => Some(mem::transmute(unmap_memory
+                as unsafe extern "system" fn(_, _))),
+            b"vkFlushMappedMemoryRanges" => Some(mem::transmute(flush_mapped_memory_ranges
+                as unsafe extern "system" fn(_, _, _) -> _)),
+            b"vkInvalidateMappedMemoryRanges" => Some(mem::transmute(
+                invalidate_mapped_memory_ranges as unsafe extern "system" fn(_, _, _) -> _)),
+            b"vkGetBufferMemoryRequirements" => Some(mem::transmute(get_buffer_memory_requirements
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkGetImageMemoryRequirements" => Some(mem::transmute(get_image_memory_requirements
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkGetImageSparseMemoryRequirements" => Some(mem::transmute(
+                get_image_sparse_memory_requirements as unsafe extern "system" fn(_, _, _, _))),
+            b"vkGetImageSubresourceLayout" => Some(mem::transmute(get_image_subresource_layout
+                as unsafe extern "system" fn(_, _, _, _))),
+            b"vkGetRenderAreaGranularity" => Some(mem::transmute(get_render_area_granularity
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkGetDeviceQueue" => Some(mem::transmute(get_device_queue
+                as unsafe extern "system" fn(_, _, _, _))),
+            b"vkDeviceWaitIdle" => Some(mem::transmute(device_wait_idle
+                as unsafe extern "system" fn(_) -> _)),
+            b"vkQueueSubmit" => Some(mem::transmute(queue_submit
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkQueueBindSparse" => Some(mem::transmute(queue_bind_sparse
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkCmdBindVertexBuffers" => Some(mem::transmute(cmd_bind_vertex_buffers
+                as unsafe extern "system" fn(_, _, _, _, _))),
+            b"vkCreateBuffer" => Some(mem::transmute(create_buffer
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkDestroyBuffer" => Some(mem::transmute(destroy_buffer
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkCreateRenderPass" => Some(mem::transmute(create_render_pass
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkDestroyRenderPass" => Some(mem::transmute(destroy_render_pass
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkCreateCommandPool" => Some(mem::transmute(create_command_pool
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkDestroyCommandPool" => Some(mem::transmute(destroy_command_pool
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkFreeCommandBuffers" => Some(mem::transmute(free_command_buffers
+                as unsafe extern "system" fn(_, _, _, _))),
+            b"vkBeginCommandBuffer" => Some(mem::transmute(begin_command_buffer
+                as unsafe extern "system" fn(_, _) -> _)),
+            b"vkEndCommandBuffer" => Some(mem::transmute(end_command_buffer
+                as unsafe extern "system" fn(_) -> _)),
+            b"vkCreateQueryPool" => Some(mem::transmute(create_query_pool
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkDestroyQueryPool" => Some(mem::transmute(destroy_query_pool
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkCmdBeginRenderPass" => Some(mem::transmute(cmd_begin_render_pass
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkCmdEndRenderPass" => Some(mem::transmute(cmd_end_render_pass
+                as unsafe extern "system" fn(_))),
+            b"vkCmdBeginQuery" => Some(mem::transmute(cmd_begin_query
+                as unsafe extern "system" fn(_, _, _, _))),
+            b"vkCmdEndQuery" => Some(mem::transmute(cmd_end_query
+                as unsafe extern "system" fn(_, _, _))),
+            b"vkCmdDraw" => Some(mem::transmute(cmd_draw
+                as unsafe extern "system" fn(_, _, _, _, _))),
+            b"vkAllocateCommandBuffers" => Some(mem::transmute(allocate_command_buffers
+                as unsafe extern "system" fn(_, _, _) -> _)),
+            b"vkAllocateDescriptorSets" => Some(mem::transmute(allocate_descriptor_sets
+                as unsafe extern "system" fn(_, _, _) -> _)),
+            _ => None,
+        }
+    }
+
+    unsafe extern "system" fn get_instance_proc_addr(_: vks::VkInstance, name: *const c_char)
+            -> VoidFunction {
+        match CStr::from_ptr(name).to_bytes() {
+            b"vkGetPhysicalDeviceFeatures" => Some(mem::transmute(get_physical_device_features
+                as unsafe extern "system" fn(_, _))),
+            b"vkGetPhysicalDeviceProperties" => Some(mem::transmute(get_physical_device_properties
+                as unsafe extern "system" fn(_, _))),
+            b"vkGetPhysicalDeviceQueueFamilyProperties" => Some(mem::transmute(
+                get_physical_device_queue_family_properties as unsafe extern "system" fn(_, _, _))),
+            b"vkGetPhysicalDeviceMemoryProperties" => Some(mem::transmute(
+                get_physical_device_memory_properties as unsafe extern "system" fn(_, _))),
+            b"vkGetPhysicalDeviceFormatProperties" => Some(mem::transmute(
+                get_physical_device_format_properties as unsafe extern "system" fn(_, _, _))),
+            b"vkGetDeviceProcAddr" => Some(mem::transmute(get_device_proc_addr
+                as unsafe extern "system" fn(_, _) -> _)),
+            b"vkDestroyInstance" => Some(mem::transmute(destroy_instance
+                as unsafe extern "system" fn(_, _))),
+            b"vkCreateInstance" => Some(mem::transmute(create_instance
+                as unsafe extern "system" fn(_, _, _) -> _)),
+            #[cfg(feature = "experimental")]
+            b"vkEnumeratePhysicalDeviceGroupsKHX" => Some(mem::transmute(
+                enumerate_physical_device_groups_khx as unsafe extern "system" fn(_, _, _) -> _)),
+            b"vkCreateDevice" => Some(mem::transmute(create_device
+                as unsafe extern "system" fn(_, _, _, _) -> _)),
+            b"vkDestroyDevice" => Some(mem::transmute(destroy_device
+                as unsafe extern "system" fn(_, _))),
+            _ => None,
+        }
+    }
+
+    /// Returns a loader for the null implementation.
+    pub fn loader() -> Loader {
+        Loader::from_get_instance_proc_addr(Some(get_instance_proc_addr))
+    }
+
+    /// Returns a non-owning instance backed by the null implementation.
+    pub fn instance() -> Instance {
+        unsafe { Instance::from_raw(loader(), 0x1000 as vks::VkInstance, false) }
+    }
+
+    /// Returns a physical device handle of the null implementation.
+    pub fn physical_device_handle() -> PhysicalDeviceHandle {
+        PhysicalDeviceHandle(0x2000 as vks::VkPhysicalDevice)
+    }
+
+    /// Returns the physical device of the null implementation.
+    pub fn physical_device() -> PhysicalDevice {
+        PhysicalDevice::from_parts(instance(), physical_device_handle())
+    }
+
+    /// Returns a non-owning device backed by the null implementation.
+    pub fn device() -> Device {
+        let physical_device = physical_device();
+        let instance = physical_device.instance().clone();
+        unsafe { Device::from_raw_parts(instance, physical_device, 0x3000 as vks::VkDevice, false) }
+    }
+}
@@ -0,0 +1,210 @@
+//! Alignment and size arithmetic for binding and flushing device memory.
+//!
+//! All alignments are expected to be powers of two, as is every alignment
+//! reported by Vulkan (`MemoryRequirements::alignment`,
+//! `nonCoherentAtomSize`, `bufferImageGranularity`, etc.). An alignment of
+//! zero is treated as one.
+
+use ::{VdResult, DeviceSize, MemoryRequirements, WHOLE_SIZE, Error, ErrorKind};
+
+
+/// Rounds `value` up to the next multiple of `alignment`, returning `None`
+/// if the result would not fit in a `DeviceSize`.
+#[inline]
+pub fn checked_align_up(value: DeviceSize, alignment: DeviceSize) -> Option<DeviceSize> {
+    if alignment <= 1 { return Some(value); }
+    debug_assert!(alignment.is_power_of_two(), "alignment must be a power of two");
+    value.checked_add(alignment - 1).map(|v| v & !(alignment - 1))
+}
+
+/// Rounds `value` up to the next multiple of `alignment`.
+///
+/// ### Panics
+///
+/// Panics if the result would not fit in a `DeviceSize`.
+#[inline]
+pub fn align_up(value: DeviceSize, alignment: DeviceSize) -> DeviceSize {
+    checked_align_up(value, alignment).expect("align_up: overflow")
+}
+
+/// Rounds `value` down to the previous multiple of `alignment`.
+#[inline]
+pub fn align_down(value: DeviceSize, alignment: DeviceSize) -> DeviceSize {
+    if alignment <= 1 { return value; }
+    debug_assert!(alignment.is_power_of_two(), "alignment must be a power of two");
+    value & !(alignment - 1)
+}
+
+/// Expands the range `offset..offset + size` outward to multiples of
+/// `atom_size`, as required of ranges flushed or invalidated in non-coherent
+/// memory, returning the new `(offset, size)`.
+///
+/// If `size` is `WHOLE_SIZE`, or the expanded range would reach or pass the
+/// end of the allocation (`allocation_size`, when known), the returned size
+/// is `WHOLE_SIZE`, which is always valid for a range running to the end of
+/// an allocation even if the allocation size is not itself a multiple of the
+/// atom size.
+pub fn non_coherent_range(offset: DeviceSize, size: DeviceSize, atom_size: DeviceSize,
+        allocation_size: Option<DeviceSize>) -> (DeviceSize, DeviceSize) {
+    let start = align_down(offset, atom_size);
+    if size == WHOLE_SIZE { return (start, WHOLE_SIZE); }
+
+    let end = match offset.checked_add(size).and_then(|end| checked_align_up(end, atom_size)) {
+        Some(end) => end,
+        None => return (start, WHOLE_SIZE),
+    };
+    match allocation_size {
+        Some(alloc_size) if end >= alloc_size => (start, WHOLE_SIZE),
+        _ => (start, end - start),
+    }
+}
+
+/// Returns the offsets at which resources with the requirements `reqs`
+/// would be placed, in order and each suitably aligned, within a single
+/// allocation, along with the total size of that allocation.
+///
+/// Returns `None` if the total size would not fit in a `DeviceSize`.
+pub fn packed_offsets(reqs: &[MemoryRequirements]) -> Option<(Vec<DeviceSize>, DeviceSize)> {
+    let mut offsets = Vec::with_capacity(reqs.len());
+    let mut end: DeviceSize = 0;
+    for req in reqs {
+        let offset = checked_align_up(end, req.alignment())?;
+        end = offset.checked_add(req.size())?;
+        offsets.push(offset);
+    }
+    Some((offsets, end))
+}
+
+/// Returns the memory type bits permitted by every one of `reqs`.
+///
+/// Returns an `ErrorKind::IncompatibleMemoryTypes` error if no memory type
+/// is permitted by all of them.
+pub fn common_memory_type_bits(reqs: &[MemoryRequirements]) -> VdResult<u32> {
+    let memory_type_bits = reqs.iter().fold(!0, |bits, req| bits & req.memory_type_bits());
+    if memory_type_bits == 0 {
+        return Err(Error {
+            kind: ErrorKind::IncompatibleMemoryTypes {
+                memory_type_bits: reqs.iter().map(|req| req.memory_type_bits()).collect(),
+            },
+            cause: None,
+        });
+    }
+    Ok(memory_type_bits)
+}
+
+
+impl MemoryRequirements {
+    /// Returns this resource's size rounded up to its alignment, the amount
+    /// of memory consumed when resources with these requirements are placed
+    /// back to back.
+    pub fn aligned_size(&self) -> DeviceSize {
+        align_up(self.size(), self.alignment())
+    }
+
+    /// Returns true if a resource with these requirements, placed at the
+    /// first suitably aligned position at or after `offset`, ends at or
+    /// before `available` bytes.
+    pub fn fits_in(&self, offset: DeviceSize, available: DeviceSize) -> bool {
+        checked_align_up(offset, self.alignment())
+            .and_then(|start| start.checked_add(self.size()))
+            .map(|end| end <= available)
+            .unwrap_or(false)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::{WHOLE_SIZE, DeviceSize, MemoryRequirements, ErrorKind};
+    use super::{checked_align_up, align_up, align_down, non_coherent_range, packed_offsets,
+        common_memory_type_bits};
+
+    fn reqs(size: DeviceSize, alignment: DeviceSize) -> MemoryRequirements {
+        MemoryRequirements::builder().size(size).alignment(alignment).memory_type_bits(!0).build()
+    }
+
+    #[test]
+    fn align_rounds_to_power_of_two_multiples() {
+        assert_eq!(align_up(0, 256), 0);
+        assert_eq!(align_up(1, 256), 256);
+        assert_eq!(align_up(256, 256), 256);
+        assert_eq!(align_up(257, 256), 512);
+        assert_eq!(align_down(255, 256), 0);
+        assert_eq!(align_down(511, 256), 256);
+        assert_eq!(align_down(512, 256), 512);
+        // Alignments of zero and one leave the value unchanged.
+        assert_eq!((align_up(13, 0), align_up(13, 1)), (13, 13));
+        assert_eq!((align_down(13, 0), align_down(13, 1)), (13, 13));
+    }
+
+    #[test]
+    fn checked_align_up_detects_overflow() {
+        assert_eq!(checked_align_up(WHOLE_SIZE - 255, 256), Some(WHOLE_SIZE - 255));
+        assert_eq!(checked_align_up(WHOLE_SIZE - 254, 256), None);
+        assert_eq!(checked_align_up(WHOLE_SIZE, 1), Some(WHOLE_SIZE));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn align_up_panics_on_overflow() {
+        align_up(WHOLE_SIZE, 2);
+    }
+
+    #[test]
+    fn memory_requirements_size_and_fit() {
+        assert_eq!(reqs(1000, 256).aligned_size(), 1024);
+        assert_eq!(reqs(1024, 256).aligned_size(), 1024);
+        assert!(reqs(1000, 256).fits_in(0, 1000));
+        assert!(!reqs(1000, 256).fits_in(0, 999));
+        // Placed at 256, not 1, and so ending at 1256.
+        assert!(reqs(1000, 256).fits_in(1, 1256));
+        assert!(!reqs(1000, 256).fits_in(1, 1255));
+        assert!(!reqs(1000, 256).fits_in(WHOLE_SIZE - 100, WHOLE_SIZE));
+    }
+
+    #[test]
+    fn non_coherent_range_straddling_atom_boundary() {
+        // 60..72 crosses the boundary at 64 and expands to 0..128.
+        assert_eq!(non_coherent_range(60, 12, 64, Some(1024)), (0, 128));
+        // 64..128 is already aligned.
+        assert_eq!(non_coherent_range(64, 64, 64, Some(1024)), (64, 64));
+        // 127..129 touches two atoms.
+        assert_eq!(non_coherent_range(127, 2, 64, None), (64, 128));
+    }
+
+    #[test]
+    fn non_coherent_range_at_allocation_tail() {
+        // The allocation size (1000) is not a multiple of the atom size, so
+        // a range ending at or rounded past its end becomes `WHOLE_SIZE`.
+        assert_eq!(non_coherent_range(900, 100, 64, Some(1000)), (896, WHOLE_SIZE));
+        assert_eq!(non_coherent_range(900, 90, 64, Some(1000)), (896, WHOLE_SIZE));
+        // Without a known allocation size the range is simply rounded out.
+        assert_eq!(non_coherent_range(900, 100, 64, None), (896, 128));
+        assert_eq!(non_coherent_range(900, WHOLE_SIZE, 64, Some(1000)), (896, WHOLE_SIZE));
+        // A range whose rounded end would overflow runs to the end.
+        assert_eq!(non_coherent_range(64, WHOLE_SIZE - 64, 64, None), (64, WHOLE_SIZE));
+    }
+
+    #[test]
+    fn packed_offsets_align_each_resource() {
+        let (offsets, size) = packed_offsets(&[reqs(1000, 256), reqs(16, 16), reqs(4096, 4096)])
+            .unwrap();
+        assert_eq!(offsets, [0, 1008, 4096]);
+        assert_eq!(size, 8192);
+        assert_eq!(packed_offsets(&[]).unwrap(), (Vec::new(), 0));
+        assert!(packed_offsets(&[reqs(WHOLE_SIZE - 16, 1), reqs(32, 1)]).is_none());
+    }
+
+    #[test]
+    fn common_memory_type_bits_intersects_requirements() {
+        let bits = |bits| MemoryRequirements::builder().size(64).alignment(64)
+            .memory_type_bits(bits).build();
+        assert_eq!(common_memory_type_bits(&[bits(0b0111), bits(0b1110)]).unwrap(), 0b0110);
+        let err = common_memory_type_bits(&[bits(0b0011), bits(0b1100)]).unwrap_err();
+        match *err.kind() {
+            ErrorKind::IncompatibleMemoryTypes { ref memory_type_bits } =>
+                assert_eq!(memory_type_bits, &[0b0011, 0b1100]),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+}
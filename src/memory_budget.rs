@@ -0,0 +1,111 @@
+//! `VK_EXT_memory_budget` structures.
+//!
+//! `vks` predates `VK_EXT_memory_budget` so the raw structure is declared
+//! here. It is chained onto a `PhysicalDeviceMemoryProperties2Khr` and
+//! queried with `PhysicalDevice::memory_properties_2`, or more simply through
+//! `PhysicalDevice::memory_budget`.
+
+use std::ptr;
+use std::marker::PhantomData;
+use libc::c_void;
+use vks;
+
+
+const VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MEMORY_BUDGET_PROPERTIES_EXT: vks::VkStructureType = 1000237000;
+
+
+/// A raw `VkPhysicalDeviceMemoryBudgetPropertiesEXT`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkPhysicalDeviceMemoryBudgetPropertiesEXT {
+    pub sType: vks::VkStructureType,
+    pub pNext: *mut c_void,
+    pub heapBudget: [vks::VkDeviceSize; vks::VK_MAX_MEMORY_HEAPS],
+    pub heapUsage: [vks::VkDeviceSize; vks::VK_MAX_MEMORY_HEAPS],
+}
+
+impl Default for VkPhysicalDeviceMemoryBudgetPropertiesEXT {
+    fn default() -> Self {
+        VkPhysicalDeviceMemoryBudgetPropertiesEXT {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MEMORY_BUDGET_PROPERTIES_EXT,
+            pNext: ptr::null_mut(),
+            heapBudget: [0; vks::VK_MAX_MEMORY_HEAPS],
+            heapUsage: [0; vks::VK_MAX_MEMORY_HEAPS],
+        }
+    }
+}
+
+
+/// A `VkPhysicalDeviceMemoryBudgetPropertiesEXT`.
+///
+/// Reports, for each memory heap, an estimate of how much memory the process
+/// can allocate from it without degrading performance (its budget) and how
+/// much it currently uses. Only the first
+/// `PhysicalDeviceMemoryProperties::memory_heap_count` elements of each
+/// array are valid.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct PhysicalDeviceMemoryBudgetPropertiesExt<'s> {
+    raw: VkPhysicalDeviceMemoryBudgetPropertiesEXT,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> PhysicalDeviceMemoryBudgetPropertiesExt<'s> {
+    pub fn builder<'b>() -> PhysicalDeviceMemoryBudgetPropertiesExtBuilder<'b> {
+        PhysicalDeviceMemoryBudgetPropertiesExtBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkPhysicalDeviceMemoryBudgetPropertiesEXT) -> PhysicalDeviceMemoryBudgetPropertiesExt<'s> {
+        PhysicalDeviceMemoryBudgetPropertiesExt { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *mut c_void {
+        self.raw.pNext
+    }
+
+    pub fn heap_budget<'a>(&'a self) -> &'a [u64] {
+        &self.raw.heapBudget
+    }
+
+    pub fn heap_usage<'a>(&'a self) -> &'a [u64] {
+        &self.raw.heapUsage
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *mut c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn as_raw(&self) -> &VkPhysicalDeviceMemoryBudgetPropertiesEXT {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkPhysicalDeviceMemoryBudgetPropertiesEXT`.
+#[derive(Debug, Clone, Default)]
+pub struct PhysicalDeviceMemoryBudgetPropertiesExtBuilder<'b> {
+    raw: VkPhysicalDeviceMemoryBudgetPropertiesEXT,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> PhysicalDeviceMemoryBudgetPropertiesExtBuilder<'b> {
+    pub fn new() -> PhysicalDeviceMemoryBudgetPropertiesExtBuilder<'b> {
+        PhysicalDeviceMemoryBudgetPropertiesExtBuilder {
+            raw: VkPhysicalDeviceMemoryBudgetPropertiesEXT::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *mut c_void) -> PhysicalDeviceMemoryBudgetPropertiesExtBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn build(self) -> PhysicalDeviceMemoryBudgetPropertiesExt<'b> {
+        PhysicalDeviceMemoryBudgetPropertiesExt {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
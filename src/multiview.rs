@@ -0,0 +1,392 @@
+//! `VK_KHR_multiview` structures.
+//!
+//! `vks` only provides the experimental `VK_KHX_multiview` predecessor of
+//! this extension so the raw structures are declared here. They are
+//! layout-compatible with, and use the same structure type values as, their
+//! `KHX` counterparts.
+//!
+//! `RenderPassMultiviewCreateInfoKhr` is chained onto render pass creation by
+//! `RenderPassBuilder::multiview`. The feature and property structures are
+//! chained onto a `PhysicalDeviceFeatures2Khr` or
+//! `PhysicalDeviceProperties2Khr`.
+
+use std::ptr;
+use std::slice;
+use std::marker::PhantomData;
+use libc::c_void;
+use vks;
+
+
+const VK_STRUCTURE_TYPE_RENDER_PASS_MULTIVIEW_CREATE_INFO_KHR: vks::VkStructureType = 1000053000;
+const VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_FEATURES_KHR: vks::VkStructureType = 1000053001;
+const VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_PROPERTIES_KHR: vks::VkStructureType = 1000053002;
+
+
+/// A raw `VkRenderPassMultiviewCreateInfoKHR`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkRenderPassMultiviewCreateInfoKHR {
+    pub sType: vks::VkStructureType,
+    pub pNext: *const c_void,
+    pub subpassCount: u32,
+    pub pViewMasks: *const u32,
+    pub dependencyCount: u32,
+    pub pViewOffsets: *const i32,
+    pub correlationMaskCount: u32,
+    pub pCorrelationMasks: *const u32,
+}
+
+impl Default for VkRenderPassMultiviewCreateInfoKHR {
+    fn default() -> Self {
+        VkRenderPassMultiviewCreateInfoKHR {
+            sType: VK_STRUCTURE_TYPE_RENDER_PASS_MULTIVIEW_CREATE_INFO_KHR,
+            pNext: ptr::null(),
+            subpassCount: 0,
+            pViewMasks: ptr::null(),
+            dependencyCount: 0,
+            pViewOffsets: ptr::null(),
+            correlationMaskCount: 0,
+            pCorrelationMasks: ptr::null(),
+        }
+    }
+}
+
+
+/// A raw `VkPhysicalDeviceMultiviewFeaturesKHR`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkPhysicalDeviceMultiviewFeaturesKHR {
+    pub sType: vks::VkStructureType,
+    pub pNext: *mut c_void,
+    pub multiview: vks::VkBool32,
+    pub multiviewGeometryShader: vks::VkBool32,
+    pub multiviewTessellationShader: vks::VkBool32,
+}
+
+impl Default for VkPhysicalDeviceMultiviewFeaturesKHR {
+    fn default() -> Self {
+        VkPhysicalDeviceMultiviewFeaturesKHR {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_FEATURES_KHR,
+            pNext: ptr::null_mut(),
+            multiview: vks::VK_FALSE,
+            multiviewGeometryShader: vks::VK_FALSE,
+            multiviewTessellationShader: vks::VK_FALSE,
+        }
+    }
+}
+
+
+/// A raw `VkPhysicalDeviceMultiviewPropertiesKHR`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VkPhysicalDeviceMultiviewPropertiesKHR {
+    pub sType: vks::VkStructureType,
+    pub pNext: *mut c_void,
+    pub maxMultiviewViewCount: u32,
+    pub maxMultiviewInstanceIndex: u32,
+}
+
+impl Default for VkPhysicalDeviceMultiviewPropertiesKHR {
+    fn default() -> Self {
+        VkPhysicalDeviceMultiviewPropertiesKHR {
+            sType: VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_PROPERTIES_KHR,
+            pNext: ptr::null_mut(),
+            maxMultiviewViewCount: 0,
+            maxMultiviewInstanceIndex: 0,
+        }
+    }
+}
+
+
+/// A `VkRenderPassMultiviewCreateInfoKHR`.
+///
+/// Specifies the views rendered by each subpass of a render pass. Each view
+/// mask has one bit set per view (array layer of the framebuffer
+/// attachments) rendered by the subpass at the same index. View offsets
+/// correspond to dependencies and correlation masks indicate sets of views
+/// which may be rendered concurrently.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct RenderPassMultiviewCreateInfoKhr<'s> {
+    raw: VkRenderPassMultiviewCreateInfoKHR,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> RenderPassMultiviewCreateInfoKhr<'s> {
+    pub fn builder<'b>() -> RenderPassMultiviewCreateInfoKhrBuilder<'b> {
+        RenderPassMultiviewCreateInfoKhrBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkRenderPassMultiviewCreateInfoKHR) -> RenderPassMultiviewCreateInfoKhr<'s> {
+        RenderPassMultiviewCreateInfoKhr { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *const c_void {
+        self.raw.pNext
+    }
+
+    pub fn view_masks<'a>(&'a self) -> &'a [u32] {
+        unsafe { slice::from_raw_parts(self.raw.pViewMasks, self.raw.subpassCount as usize) }
+    }
+
+    pub fn view_offsets<'a>(&'a self) -> &'a [i32] {
+        unsafe { slice::from_raw_parts(self.raw.pViewOffsets, self.raw.dependencyCount as usize) }
+    }
+
+    pub fn correlation_masks<'a>(&'a self) -> &'a [u32] {
+        unsafe { slice::from_raw_parts(self.raw.pCorrelationMasks, self.raw.correlationMaskCount as usize) }
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *const c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn set_view_masks<'m, 'a>(&mut self, view_masks: &'a [u32])
+            where 'a: 's {
+        self.raw.subpassCount = view_masks.len() as _;
+        self.raw.pViewMasks = view_masks.as_ptr();
+    }
+
+    pub fn set_view_offsets<'m, 'a>(&mut self, view_offsets: &'a [i32])
+            where 'a: 's {
+        self.raw.dependencyCount = view_offsets.len() as _;
+        self.raw.pViewOffsets = view_offsets.as_ptr();
+    }
+
+    pub fn set_correlation_masks<'m, 'a>(&mut self, correlation_masks: &'a [u32])
+            where 'a: 's {
+        self.raw.correlationMaskCount = correlation_masks.len() as _;
+        self.raw.pCorrelationMasks = correlation_masks.as_ptr();
+    }
+
+    pub fn as_raw(&self) -> &VkRenderPassMultiviewCreateInfoKHR {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkRenderPassMultiviewCreateInfoKHR`.
+#[derive(Debug, Clone, Default)]
+pub struct RenderPassMultiviewCreateInfoKhrBuilder<'b> {
+    raw: VkRenderPassMultiviewCreateInfoKHR,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> RenderPassMultiviewCreateInfoKhrBuilder<'b> {
+    pub fn new() -> RenderPassMultiviewCreateInfoKhrBuilder<'b> {
+        RenderPassMultiviewCreateInfoKhrBuilder {
+            raw: VkRenderPassMultiviewCreateInfoKHR::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *const c_void) -> RenderPassMultiviewCreateInfoKhrBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn view_masks<'m, 'a>(mut self, view_masks: &'a [u32]) -> RenderPassMultiviewCreateInfoKhrBuilder<'b>
+            where 'a: 'b {
+        self.raw.subpassCount = view_masks.len() as _;
+        self.raw.pViewMasks = view_masks.as_ptr();
+        self
+    }
+
+    pub fn view_offsets<'m, 'a>(mut self, view_offsets: &'a [i32]) -> RenderPassMultiviewCreateInfoKhrBuilder<'b>
+            where 'a: 'b {
+        self.raw.dependencyCount = view_offsets.len() as _;
+        self.raw.pViewOffsets = view_offsets.as_ptr();
+        self
+    }
+
+    pub fn correlation_masks<'m, 'a>(mut self, correlation_masks: &'a [u32]) -> RenderPassMultiviewCreateInfoKhrBuilder<'b>
+            where 'a: 'b {
+        self.raw.correlationMaskCount = correlation_masks.len() as _;
+        self.raw.pCorrelationMasks = correlation_masks.as_ptr();
+        self
+    }
+
+    pub fn build(self) -> RenderPassMultiviewCreateInfoKhr<'b> {
+        RenderPassMultiviewCreateInfoKhr {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
+
+
+/// A `VkPhysicalDeviceMultiviewFeaturesKHR`.
+///
+/// Chain onto a `PhysicalDeviceFeatures2Khr` to query support with
+/// `Instance::get_physical_device_features_2_khr_into` or to enable features
+/// with `DeviceBuilder::enabled_features_2`.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct PhysicalDeviceMultiviewFeaturesKhr<'s> {
+    raw: VkPhysicalDeviceMultiviewFeaturesKHR,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> PhysicalDeviceMultiviewFeaturesKhr<'s> {
+    pub fn builder<'b>() -> PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+        PhysicalDeviceMultiviewFeaturesKhrBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkPhysicalDeviceMultiviewFeaturesKHR) -> PhysicalDeviceMultiviewFeaturesKhr<'s> {
+        PhysicalDeviceMultiviewFeaturesKhr { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *mut c_void {
+        self.raw.pNext
+    }
+
+    pub fn multiview<'a>(&'a self) -> bool {
+        self.raw.multiview != 0
+    }
+
+    pub fn multiview_geometry_shader<'a>(&'a self) -> bool {
+        self.raw.multiviewGeometryShader != 0
+    }
+
+    pub fn multiview_tessellation_shader<'a>(&'a self) -> bool {
+        self.raw.multiviewTessellationShader != 0
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *mut c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn set_multiview<'m>(&mut self, multiview: bool) {
+        self.raw.multiview = multiview as vks::VkBool32;
+    }
+
+    pub fn set_multiview_geometry_shader<'m>(&mut self, multiview_geometry_shader: bool) {
+        self.raw.multiviewGeometryShader = multiview_geometry_shader as vks::VkBool32;
+    }
+
+    pub fn set_multiview_tessellation_shader<'m>(&mut self, multiview_tessellation_shader: bool) {
+        self.raw.multiviewTessellationShader = multiview_tessellation_shader as vks::VkBool32;
+    }
+
+    pub fn as_raw(&self) -> &VkPhysicalDeviceMultiviewFeaturesKHR {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkPhysicalDeviceMultiviewFeaturesKHR`.
+#[derive(Debug, Clone, Default)]
+pub struct PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+    raw: VkPhysicalDeviceMultiviewFeaturesKHR,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+    pub fn new() -> PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+        PhysicalDeviceMultiviewFeaturesKhrBuilder {
+            raw: VkPhysicalDeviceMultiviewFeaturesKHR::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *mut c_void) -> PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn multiview<'m>(mut self, multiview: bool) -> PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+        self.raw.multiview = multiview as vks::VkBool32;
+        self
+    }
+
+    pub fn multiview_geometry_shader<'m>(mut self, multiview_geometry_shader: bool) -> PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+        self.raw.multiviewGeometryShader = multiview_geometry_shader as vks::VkBool32;
+        self
+    }
+
+    pub fn multiview_tessellation_shader<'m>(mut self, multiview_tessellation_shader: bool) -> PhysicalDeviceMultiviewFeaturesKhrBuilder<'b> {
+        self.raw.multiviewTessellationShader = multiview_tessellation_shader as vks::VkBool32;
+        self
+    }
+
+    pub fn build(self) -> PhysicalDeviceMultiviewFeaturesKhr<'b> {
+        PhysicalDeviceMultiviewFeaturesKhr {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
+
+
+/// A `VkPhysicalDeviceMultiviewPropertiesKHR`.
+///
+/// Chain onto a `PhysicalDeviceProperties2Khr` to query limits with
+/// `Instance::get_physical_device_properties_2_khr_into`.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct PhysicalDeviceMultiviewPropertiesKhr<'s> {
+    raw: VkPhysicalDeviceMultiviewPropertiesKHR,
+    _p: PhantomData<&'s ()>,
+}
+
+impl<'s> PhysicalDeviceMultiviewPropertiesKhr<'s> {
+    pub fn builder<'b>() -> PhysicalDeviceMultiviewPropertiesKhrBuilder<'b> {
+        PhysicalDeviceMultiviewPropertiesKhrBuilder::new()
+    }
+
+    pub unsafe fn from_raw(raw: VkPhysicalDeviceMultiviewPropertiesKHR) -> PhysicalDeviceMultiviewPropertiesKhr<'s> {
+        PhysicalDeviceMultiviewPropertiesKhr { raw, _p: PhantomData }
+    }
+
+    pub fn next<'a>(&'a self) -> *mut c_void {
+        self.raw.pNext
+    }
+
+    pub fn max_multiview_view_count<'a>(&'a self) -> u32 {
+        self.raw.maxMultiviewViewCount
+    }
+
+    pub fn max_multiview_instance_index<'a>(&'a self) -> u32 {
+        self.raw.maxMultiviewInstanceIndex
+    }
+
+    pub unsafe fn set_next<'m>(&mut self, next: *mut c_void) {
+        self.raw.pNext = next;
+    }
+
+    pub fn as_raw(&self) -> &VkPhysicalDeviceMultiviewPropertiesKHR {
+        &self.raw
+    }
+}
+
+
+/// A builder for `VkPhysicalDeviceMultiviewPropertiesKHR`.
+#[derive(Debug, Clone, Default)]
+pub struct PhysicalDeviceMultiviewPropertiesKhrBuilder<'b> {
+    raw: VkPhysicalDeviceMultiviewPropertiesKHR,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> PhysicalDeviceMultiviewPropertiesKhrBuilder<'b> {
+    pub fn new() -> PhysicalDeviceMultiviewPropertiesKhrBuilder<'b> {
+        PhysicalDeviceMultiviewPropertiesKhrBuilder {
+            raw: VkPhysicalDeviceMultiviewPropertiesKHR::default(),
+            _p: PhantomData,
+        }
+    }
+
+    pub unsafe fn next<'m>(mut self, next: *mut c_void) -> PhysicalDeviceMultiviewPropertiesKhrBuilder<'b> {
+        self.raw.pNext = next;
+        self
+    }
+
+    pub fn build(self) -> PhysicalDeviceMultiviewPropertiesKhr<'b> {
+        PhysicalDeviceMultiviewPropertiesKhr {
+            raw: self.raw,
+            _p: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Commonly used layer and extension names.
+//!
+//! These may be passed directly (as `&[&CStr]`) to
+//! `InstanceBuilder::enabled_extension_names`,
+//! `InstanceBuilder::enabled_layer_names`, and
+//! `DeviceBuilder::enabled_extension_names` without allocating a `CString`
+//! per name.
+
+use std::ffi::CStr;
+
+macro_rules! c_str {
+    ($name:ident, $s:expr) => (
+        pub const $name: &'static CStr = unsafe {
+            CStr::from_bytes_with_nul_unchecked(concat!($s, "\0").as_bytes())
+        };
+    )
+}
+
+// Layers:
+c_str!(LAYER_KHRONOS_VALIDATION, "VK_LAYER_KHRONOS_validation");
+c_str!(LAYER_LUNARG_STANDARD_VALIDATION, "VK_LAYER_LUNARG_standard_validation");
+
+// Instance extensions:
+c_str!(KHR_SURFACE, "VK_KHR_surface");
+c_str!(KHR_XLIB_SURFACE, "VK_KHR_xlib_surface");
+c_str!(KHR_XCB_SURFACE, "VK_KHR_xcb_surface");
+c_str!(KHR_WAYLAND_SURFACE, "VK_KHR_wayland_surface");
+c_str!(KHR_WIN32_SURFACE, "VK_KHR_win32_surface");
+c_str!(KHR_MIR_SURFACE, "VK_KHR_mir_surface");
+c_str!(KHR_ANDROID_SURFACE, "VK_KHR_android_surface");
+c_str!(MVK_MACOS_SURFACE, "VK_MVK_macos_surface");
+c_str!(MVK_IOS_SURFACE, "VK_MVK_ios_surface");
+c_str!(KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2, "VK_KHR_get_physical_device_properties2");
+c_str!(KHX_DEVICE_GROUP_CREATION, "VK_KHX_device_group_creation");
+c_str!(KHR_GET_SURFACE_CAPABILITIES_2, "VK_KHR_get_surface_capabilities2");
+c_str!(KHR_EXTERNAL_MEMORY_CAPABILITIES, "VK_KHR_external_memory_capabilities");
+c_str!(KHR_EXTERNAL_SEMAPHORE_CAPABILITIES, "VK_KHR_external_semaphore_capabilities");
+c_str!(KHR_EXTERNAL_FENCE_CAPABILITIES, "VK_KHR_external_fence_capabilities");
+c_str!(EXT_DEBUG_REPORT, "VK_EXT_debug_report");
+c_str!(EXT_DEBUG_UTILS, "VK_EXT_debug_utils");
+c_str!(EXT_SWAPCHAIN_COLORSPACE, "VK_EXT_swapchain_colorspace");
+
+// Device extensions:
+c_str!(KHR_SWAPCHAIN, "VK_KHR_swapchain");
+c_str!(KHR_DISPLAY_SWAPCHAIN, "VK_KHR_display_swapchain");
+c_str!(KHR_GET_MEMORY_REQUIREMENTS_2, "VK_KHR_get_memory_requirements2");
+c_str!(KHR_DEDICATED_ALLOCATION, "VK_KHR_dedicated_allocation");
+c_str!(KHR_EXTERNAL_MEMORY, "VK_KHR_external_memory");
+c_str!(KHR_EXTERNAL_MEMORY_FD, "VK_KHR_external_memory_fd");
+c_str!(KHR_EXTERNAL_MEMORY_WIN32, "VK_KHR_external_memory_win32");
+c_str!(KHR_EXTERNAL_SEMAPHORE_FD, "VK_KHR_external_semaphore_fd");
+c_str!(KHR_EXTERNAL_SEMAPHORE_WIN32, "VK_KHR_external_semaphore_win32");
+c_str!(KHR_EXTERNAL_FENCE_FD, "VK_KHR_external_fence_fd");
+c_str!(KHR_EXTERNAL_FENCE_WIN32, "VK_KHR_external_fence_win32");
+c_str!(KHR_MAINTENANCE_1, "VK_KHR_maintenance1");
+c_str!(KHR_PUSH_DESCRIPTOR, "VK_KHR_push_descriptor");
+c_str!(KHR_MAINTENANCE_3, "VK_KHR_maintenance3");
+c_str!(KHR_MULTIVIEW, "VK_KHR_multiview");
+c_str!(EXT_DESCRIPTOR_INDEXING, "VK_EXT_descriptor_indexing");
+c_str!(EXT_DEBUG_MARKER, "VK_EXT_debug_marker");
+c_str!(EXT_EXTERNAL_MEMORY_HOST, "VK_EXT_external_memory_host");
+c_str!(EXT_MEMORY_BUDGET, "VK_EXT_memory_budget");
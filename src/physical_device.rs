@@ -1,11 +1,15 @@
 use std::ffi::CStr;
 use smallvec::SmallVec;
 use vks;
-use ::{PRINT, VdResult, Instance, Handle, SurfaceFormatKhr, PhysicalDeviceFeatures,
+use loader::fixed_array_str;
+use ::{VdResult, Instance, Handle, SurfaceFormatKhr, PhysicalDeviceFeatures,
     PhysicalDeviceProperties, QueueFamilyProperties, PhysicalDeviceMemoryProperties,
     ExtensionProperties, SurfaceCapabilitiesKhr, PresentModeKhr, FormatProperties, Format,
     SurfaceKhr, CharStrs, ImageType, ImageTiling, ImageUsageFlags, ImageCreateFlags,
-    ImageFormatProperties, };
+    ImageFormatProperties, PhysicalDeviceSparseProperties, SparseImageFormatProperties,
+    SampleCountFlags, PhysicalDeviceLimits, Error, ErrorKind, CallResult, QueueFlags,
+    PhysicalDeviceMemoryProperties2Khr, PhysicalDeviceMemoryBudgetPropertiesExt};
+use names;
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -13,6 +17,12 @@ use ::{PRINT, VdResult, Instance, Handle, SurfaceFormatKhr, PhysicalDeviceFeatur
 pub struct PhysicalDeviceHandle(pub(crate) vks::VkPhysicalDevice);
 
 impl PhysicalDeviceHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkPhysicalDevice) -> PhysicalDeviceHandle {
+        PhysicalDeviceHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkPhysicalDevice {
         self.0
@@ -33,14 +43,17 @@ unsafe impl Handle for PhysicalDeviceHandle {
 #[derive(Debug, Clone)]
 pub struct PhysicalDevice {
     handle: PhysicalDeviceHandle,
-    instance: Instance
+    instance: Instance,
+    properties: PhysicalDeviceProperties,
 }
 
 impl PhysicalDevice {
     pub fn from_parts(instance: Instance, handle: PhysicalDeviceHandle) -> PhysicalDevice {
+        let properties = instance.get_physical_device_properties(handle);
         PhysicalDevice {
             handle,
             instance,
+            properties,
         }
     }
 
@@ -56,6 +69,12 @@ impl PhysicalDevice {
         &self.instance
     }
 
+    /// Returns the name of this device as reported in its properties.
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.properties.name()
+    }
+
     /// Reports the capabilities of a physical device.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetPhysicalDeviceFeatures.html
@@ -76,23 +95,85 @@ impl PhysicalDevice {
 
     /// Lists a physical device's image format capabilities.
     ///
+    /// Returns `Ok(None)` if the combination of parameters is not supported
+    /// by the implementation for use with `vkCreateImage` (the query returned
+    /// `VK_ERROR_FORMAT_NOT_SUPPORTED`). Any other failure is returned as an
+    /// error.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetPhysicalDeviceImageFormatProperties.html
     //
     #[inline]
-    pub fn image_format_properties<Pd>(&self, format: Format,
-            type_: ImageType, tiling: ImageTiling, usage: ImageUsageFlags, flags: ImageCreateFlags)
-            -> VdResult<ImageFormatProperties> {
-        self.instance().get_physical_device_image_format_properties(self, format, type_,
-            tiling, usage, flags)
+    pub fn image_format_properties(&self, format: Format, type_: ImageType,
+            tiling: ImageTiling, usage: ImageUsageFlags, flags: ImageCreateFlags)
+            -> VdResult<Option<ImageFormatProperties>> {
+        match self.instance().get_physical_device_image_format_properties(self, format, type_,
+                tiling, usage, flags) {
+            Ok(props) => Ok(Some(props)),
+            Err(Error { kind: ErrorKind::ApiCall(CallResult::ErrorFormatNotSupported, _), .. }) => {
+                Ok(None)
+            },
+            Err(err) => Err(err),
+        }
     }
 
     /// Returns the properties of a physical device.
     ///
+    /// Properties are queried once, when this `PhysicalDevice` is created.
+    /// Use `properties_ref` to avoid the copy.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetPhysicalDeviceProperties.html
     //
     #[inline]
     pub fn properties(&self) -> PhysicalDeviceProperties {
-        self.instance().get_physical_device_properties(self)
+        self.properties.clone()
+    }
+
+    /// Returns a reference to the properties of a physical device, queried
+    /// once when this `PhysicalDevice` was created.
+    #[inline]
+    pub fn properties_ref(&self) -> &PhysicalDeviceProperties {
+        &self.properties
+    }
+
+    /// Returns the sparse resource properties of this physical device.
+    #[inline]
+    pub fn sparse_properties(&self) -> PhysicalDeviceSparseProperties {
+        self.properties.sparse_properties().clone()
+    }
+
+    /// Returns the highest sample count supported by framebuffers with both
+    /// color and depth attachments.
+    ///
+    /// Suitable for passing directly to `ImageBuilder::samples` and
+    /// `PipelineMultisampleStateCreateInfoBuilder::rasterization_samples`
+    /// when setting up multisampling.
+    pub fn max_usable_sample_count(&self) -> SampleCountFlags {
+        highest_sample_count(self.supported_sample_counts_for(true, true, false))
+    }
+
+    /// Returns the sample counts supported by framebuffers with each of the
+    /// specified kinds of attachment (the intersection of the corresponding
+    /// `framebuffer_*_sample_counts` limits).
+    ///
+    /// If no kinds are specified, returns the sample counts supported by
+    /// framebuffers with no attachments.
+    pub fn supported_sample_counts_for(&self, color: bool, depth: bool, stencil: bool)
+            -> SampleCountFlags {
+        supported_sample_counts(self.properties.limits(), color, depth, stencil)
+    }
+
+    /// Lists the sparse image format properties of images created with the
+    /// specified parameters. Returns an empty list if sparse images with
+    /// these parameters are not supported.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetPhysicalDeviceSparseImageFormatProperties.html
+    //
+    #[inline]
+    pub fn sparse_image_format_properties(&self, format: Format, type_: ImageType,
+            samples: SampleCountFlags, usage: ImageUsageFlags, tiling: ImageTiling)
+            -> SmallVec<[SparseImageFormatProperties; 8]> {
+        self.instance().get_physical_device_sparse_image_format_properties(self, format, type_,
+            samples, usage, tiling)
     }
 
     /// Reports properties of the queues of the specified physical device.
@@ -105,6 +186,36 @@ impl PhysicalDevice {
         self.instance().get_physical_device_queue_family_properties(self)
     }
 
+    /// Chooses queue families for common roles.
+    ///
+    /// * `graphics` is a family supporting `GRAPHICS`. When `surface` is
+    ///   specified, a family which can also present to it is preferred.
+    /// * `present` is a family which can present to `surface` (the graphics
+    ///   family whenever it can), or `None` if no surface is specified.
+    /// * `dedicated_transfer` is a family supporting `TRANSFER` but neither
+    ///   `GRAPHICS` nor `COMPUTE`, typically backed by a DMA engine.
+    /// * `async_compute` is a family supporting `COMPUTE` but not
+    ///   `GRAPHICS`.
+    ///
+    /// Families with no queues are ignored. Where several families qualify
+    /// for a role, the lowest index is chosen.
+    pub fn find_queue_families(&self, surface: Option<&SurfaceKhr>)
+            -> VdResult<QueueFamilySelection> {
+        let mut families: SmallVec<[(QueueFlags, bool); 16]> = SmallVec::new();
+        for (idx, props) in self.queue_family_properties()?.iter().enumerate() {
+            if props.queue_count() == 0 {
+                families.push((QueueFlags::empty(), false));
+                continue;
+            }
+            let present = match surface {
+                Some(surface) => self.surface_support_khr(idx as u32, surface)?,
+                None => false,
+            };
+            families.push((props.queue_flags(), present));
+        }
+        Ok(select_queue_families(&families))
+    }
+
     /// Reports memory information for the specified physical device.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkGetPhysicalDeviceMemoryProperties.html
@@ -114,6 +225,59 @@ impl PhysicalDevice {
         self.instance().get_physical_device_memory_properties(self)
     }
 
+    /// Reports memory information into `memory_properties`, including that
+    /// of any extension structures chained onto it with `push_next` (such as
+    /// a `PhysicalDeviceMemoryBudgetPropertiesExt`).
+    ///
+    /// Requires `VK_KHR_get_physical_device_properties2` to be enabled on the
+    /// instance.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0-extensions/man/html/vkGetPhysicalDeviceMemoryProperties2KHR.html
+    //
+    #[inline]
+    pub fn memory_properties_2(&self, memory_properties: &mut PhysicalDeviceMemoryProperties2Khr)
+            -> VdResult<()> {
+        unsafe {
+            self.instance().get_physical_device_memory_properties_2_khr_into(self,
+                memory_properties)
+        }
+    }
+
+    /// Returns the current `(budget, usage)` in bytes of each memory heap,
+    /// indexed as `PhysicalDeviceMemoryProperties::memory_heaps`.
+    ///
+    /// The budget is an estimate of how much memory this process can
+    /// allocate from a heap and may change at any time, as may usage by
+    /// other processes.
+    ///
+    /// Requires `VK_KHR_get_physical_device_properties2` to be enabled on the
+    /// instance and `VK_EXT_memory_budget` to be supported by this physical
+    /// device.
+    pub fn memory_budget(&self) -> VdResult<SmallVec<[(u64, u64); 16]>> {
+        if !self.instance().is_extension_enabled(names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2) {
+            return Err(Error { kind: ErrorKind::ExtensionNotEnabled {
+                name: names::KHR_GET_PHYSICAL_DEVICE_PROPERTIES_2,
+                fn_name: "PhysicalDevice::memory_budget" }, cause: None });
+        }
+        if !self.extension_properties()?.iter()
+                .any(|ext| ext.extension_name() == names::EXT_MEMORY_BUDGET) {
+            return Err("PhysicalDevice::memory_budget: VK_EXT_memory_budget is not supported \
+                by this physical device".into());
+        }
+
+        let mut budget = PhysicalDeviceMemoryBudgetPropertiesExt::default();
+        let heap_count = {
+            let mut memory_properties = PhysicalDeviceMemoryProperties2Khr::default();
+            memory_properties.push_next(&mut budget);
+            self.memory_properties_2(&mut memory_properties)?;
+            memory_properties.memory_properties().memory_heap_count() as usize
+        };
+
+        Ok(budget.heap_budget()[..heap_count].iter().cloned()
+            .zip(budget.heap_usage()[..heap_count].iter().cloned())
+            .collect())
+    }
+
     /// Returns the properties of available physical device extensions.
     ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkEnumerateDeviceExtensionProperties.html
@@ -170,16 +334,16 @@ impl PhysicalDevice {
         unsafe {
             // Print available:
             for ext in &avail_exts {
-                    if PRINT { println!("Available device extension: '{}' (version: {})",
-                        ext.extension_name().to_str().unwrap(), ext.spec_version()); }
+                    trace!("Available device extension: '{}' (version: {})",
+                        ext.extension_name().to_str().unwrap(), ext.spec_version());
             };
 
             for &reqd_ext_name in extension_names.into().as_ptr_slice() {
                 let mut ext_avail = false;
                 for avail_ext in &avail_exts {
                     if CStr::from_ptr(reqd_ext_name) == avail_ext.extension_name() {
-                        if PRINT { println!("Required device extension available: '{}'",
-                            CStr::from_ptr(reqd_ext_name).to_str().unwrap()); }
+                        trace!("Required device extension available: '{}'",
+                            CStr::from_ptr(reqd_ext_name).to_str().unwrap());
                         ext_avail = true;
                         break;
                     }
@@ -192,6 +356,77 @@ impl PhysicalDevice {
 
 }
 
+/// Queue family indices chosen for common roles by
+/// `PhysicalDevice::find_queue_families`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueFamilySelection {
+    pub graphics: Option<u32>,
+    pub present: Option<u32>,
+    pub dedicated_transfer: Option<u32>,
+    pub async_compute: Option<u32>,
+}
+
+impl QueueFamilySelection {
+    /// Returns the distinct family indices selected, in ascending order.
+    pub fn unique_families(&self) -> SmallVec<[u32; 4]> {
+        let mut families: SmallVec<[u32; 4]> = [self.graphics, self.present,
+                self.dedicated_transfer, self.async_compute]
+            .iter()
+            .filter_map(|&f| f)
+            .collect();
+        families.sort();
+        families.dedup();
+        families
+    }
+}
+
+/// Selects queue families from a list of each family's flags and whether or
+/// not it can present to the surface in question.
+fn select_queue_families(families: &[(QueueFlags, bool)]) -> QueueFamilySelection {
+    let find = |pred: &Fn(QueueFlags, bool) -> bool| {
+        families.iter().position(|&(flags, present)| pred(flags, present)).map(|i| i as u32)
+    };
+
+    let graphics = find(&|flags, present| flags.contains(QueueFlags::GRAPHICS) && present)
+        .or_else(|| find(&|flags, _| flags.contains(QueueFlags::GRAPHICS)));
+    let present = match graphics {
+        Some(idx) if families[idx as usize].1 => Some(idx),
+        _ => find(&|_, present| present),
+    };
+    let dedicated_transfer = find(&|flags, _| flags.contains(QueueFlags::TRANSFER) &&
+        !flags.intersects(QueueFlags::GRAPHICS | QueueFlags::COMPUTE));
+    let async_compute = find(&|flags, _| flags.contains(QueueFlags::COMPUTE) &&
+        !flags.contains(QueueFlags::GRAPHICS));
+
+    QueueFamilySelection { graphics, present, dedicated_transfer, async_compute }
+}
+
+/// Returns the sample counts supported by framebuffers with each of the
+/// specified kinds of attachment.
+fn supported_sample_counts(limits: &PhysicalDeviceLimits, color: bool, depth: bool,
+        stencil: bool) -> SampleCountFlags {
+    if !(color || depth || stencil) {
+        return limits.framebuffer_no_attachments_sample_counts();
+    }
+    let mut counts = SampleCountFlags::all();
+    if color { counts &= limits.framebuffer_color_sample_counts(); }
+    if depth { counts &= limits.framebuffer_depth_sample_counts(); }
+    if stencil { counts &= limits.framebuffer_stencil_sample_counts(); }
+    counts
+}
+
+/// Returns the single highest sample count within `counts`, or `COUNT_1` if
+/// `counts` is empty.
+fn highest_sample_count(counts: SampleCountFlags) -> SampleCountFlags {
+    [SampleCountFlags::COUNT_64, SampleCountFlags::COUNT_32, SampleCountFlags::COUNT_16,
+            SampleCountFlags::COUNT_8, SampleCountFlags::COUNT_4, SampleCountFlags::COUNT_2]
+        .iter()
+        .cloned()
+        .find(|&count| counts.contains(count))
+        .unwrap_or(SampleCountFlags::COUNT_1)
+}
+
+
 unsafe impl<'p> Handle for &'p PhysicalDevice {
     type Target = PhysicalDeviceHandle;
 
@@ -201,3 +436,149 @@ unsafe impl<'p> Handle for &'p PhysicalDevice {
     }
 }
 
+
+impl PhysicalDeviceProperties {
+    /// Returns the name of the device.
+    ///
+    /// Unlike `device_name`, does not rely on the name being NUL-terminated
+    /// within its array.
+    pub fn name(&self) -> &str {
+        fixed_array_str(&self.as_raw().deviceName)
+    }
+}
+
+/// The properties of a group of physical devices which may be used together
+/// as a single logical device.
+///
+/// Returned by `Instance::enumerate_physical_device_groups_khx`.
+#[cfg(feature = "experimental")]
+#[derive(Debug, Clone)]
+pub struct PhysicalDeviceGroupPropertiesKhx {
+    physical_devices: SmallVec<[PhysicalDevice; 4]>,
+    subset_allocation: bool,
+}
+
+#[cfg(feature = "experimental")]
+impl PhysicalDeviceGroupPropertiesKhx {
+    pub(crate) fn from_raw(instance: &Instance, raw: &vks::VkPhysicalDeviceGroupPropertiesKHX)
+            -> PhysicalDeviceGroupPropertiesKhx {
+        PhysicalDeviceGroupPropertiesKhx {
+            physical_devices: raw.physicalDevices[..raw.physicalDeviceCount as usize].iter()
+                .map(|&pd| PhysicalDevice::from_parts(instance.clone(), PhysicalDeviceHandle(pd)))
+                .collect(),
+            subset_allocation: raw.subsetAllocation != vks::VK_FALSE,
+        }
+    }
+
+    /// Returns the physical devices in this group.
+    pub fn physical_devices(&self) -> &[PhysicalDevice] {
+        &self.physical_devices
+    }
+
+    /// Returns the handles of the physical devices in this group, suitable
+    /// for `DeviceGroupDeviceCreateInfoKhx::set_physical_devices`.
+    pub fn physical_device_handles(&self) -> SmallVec<[PhysicalDeviceHandle; 4]> {
+        self.physical_devices.iter().map(|pd| pd.handle()).collect()
+    }
+
+    /// Returns true if memory may be allocated on a subset of the devices
+    /// in this group (using `VK_MEMORY_ALLOCATE_DEVICE_MASK_BIT_KHX`).
+    pub fn subset_allocation(&self) -> bool {
+        self.subset_allocation
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use loader::null;
+    use ::{PhysicalDeviceLimits, SampleCountFlags, QueueFlags};
+    use super::{QueueFamilySelection, supported_sample_counts, highest_sample_count,
+        select_queue_families};
+
+    fn limits() -> PhysicalDeviceLimits {
+        PhysicalDeviceLimits::builder()
+            .framebuffer_color_sample_counts(SampleCountFlags::COUNT_1 |
+                SampleCountFlags::COUNT_2 | SampleCountFlags::COUNT_4 | SampleCountFlags::COUNT_8)
+            .framebuffer_depth_sample_counts(SampleCountFlags::COUNT_1 |
+                SampleCountFlags::COUNT_2 | SampleCountFlags::COUNT_4)
+            .framebuffer_stencil_sample_counts(SampleCountFlags::COUNT_1 |
+                SampleCountFlags::COUNT_2)
+            .framebuffer_no_attachments_sample_counts(SampleCountFlags::COUNT_1 |
+                SampleCountFlags::COUNT_16)
+            .build()
+    }
+
+    #[test]
+    fn supported_sample_counts_intersects_attachment_kinds() {
+        let limits = limits();
+        assert_eq!(supported_sample_counts(&limits, true, false, false),
+            limits.framebuffer_color_sample_counts());
+        assert_eq!(supported_sample_counts(&limits, true, true, false),
+            SampleCountFlags::COUNT_1 | SampleCountFlags::COUNT_2 | SampleCountFlags::COUNT_4);
+        assert_eq!(supported_sample_counts(&limits, true, true, true),
+            SampleCountFlags::COUNT_1 | SampleCountFlags::COUNT_2);
+        assert_eq!(supported_sample_counts(&limits, false, false, false),
+            SampleCountFlags::COUNT_1 | SampleCountFlags::COUNT_16);
+    }
+
+    #[test]
+    fn highest_sample_count_picks_single_highest() {
+        assert_eq!(highest_sample_count(SampleCountFlags::COUNT_1 | SampleCountFlags::COUNT_4 |
+            SampleCountFlags::COUNT_8), SampleCountFlags::COUNT_8);
+        assert_eq!(highest_sample_count(SampleCountFlags::all()), SampleCountFlags::COUNT_64);
+        assert_eq!(highest_sample_count(SampleCountFlags::COUNT_1), SampleCountFlags::COUNT_1);
+        assert_eq!(highest_sample_count(SampleCountFlags::empty()), SampleCountFlags::COUNT_1);
+    }
+
+    #[test]
+    fn select_queue_families_prefers_presenting_graphics_families() {
+        let families = [
+            (QueueFlags::GRAPHICS | QueueFlags::COMPUTE | QueueFlags::TRANSFER, false),
+            (QueueFlags::GRAPHICS, true),
+            (QueueFlags::COMPUTE | QueueFlags::TRANSFER, false),
+            (QueueFlags::TRANSFER, false),
+        ];
+        assert_eq!(select_queue_families(&families), QueueFamilySelection {
+            graphics: Some(1),
+            present: Some(1),
+            dedicated_transfer: Some(3),
+            async_compute: Some(2),
+        });
+
+        // No graphics family can present, so presentation gets its own.
+        let families = [(QueueFlags::GRAPHICS, false), (QueueFlags::COMPUTE, true)];
+        assert_eq!(select_queue_families(&families), QueueFamilySelection {
+            graphics: Some(0),
+            present: Some(1),
+            dedicated_transfer: None,
+            async_compute: Some(1),
+        });
+
+        assert_eq!(select_queue_families(&[]), QueueFamilySelection::default());
+    }
+
+    #[test]
+    fn unique_families_are_sorted_and_distinct() {
+        let selection = QueueFamilySelection {
+            graphics: Some(2),
+            present: Some(2),
+            dedicated_transfer: Some(0),
+            async_compute: None,
+        };
+        assert_eq!(&selection.unique_families()[..], &[0, 2]);
+        assert!(QueueFamilySelection::default().unique_families().is_empty());
+    }
+
+    #[test]
+    fn find_queue_families_skips_empty_families() {
+        let selection = null::physical_device().find_queue_families(None).unwrap();
+        // Family 0 has no queues.
+        assert_eq!(selection, QueueFamilySelection {
+            graphics: Some(1),
+            present: None,
+            dedicated_transfer: Some(3),
+            async_compute: Some(2),
+        });
+    }
+}
@@ -0,0 +1,314 @@
+use std::sync::Arc;
+use std::ptr;
+use std::fmt;
+use libc::c_void;
+use ::{VdResult, Device, Handle, PipelineCacheHandle, PipelineCacheCreateInfo,
+    PhysicalDeviceProperties, UUID_SIZE};
+
+
+/// The length in bytes of a version one pipeline cache header.
+const HEADER_LENGTH_ONE: usize = 16 + UUID_SIZE;
+
+const HEADER_VERSION_ONE: u32 = 1;
+
+
+/// A pipeline cache blob which is unusable on a particular device.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PipelineCacheError {
+    /// The blob is shorter than its header or than the length its header
+    /// specifies.
+    Truncated { len: usize },
+    /// The header version is not `VK_PIPELINE_CACHE_HEADER_VERSION_ONE`.
+    UnsupportedVersion(u32),
+    VendorMismatch { expected: u32, found: u32 },
+    DeviceMismatch { expected: u32, found: u32 },
+    UuidMismatch,
+}
+
+impl fmt::Display for PipelineCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PipelineCacheError::Truncated { len } => write!(f, "pipeline cache data is \
+                truncated ({} bytes)", len),
+            PipelineCacheError::UnsupportedVersion(v) => write!(f, "unsupported pipeline cache \
+                header version: {}", v),
+            PipelineCacheError::VendorMismatch { expected, found } => write!(f, "pipeline cache \
+                vendor ID mismatch (expected: {:#x}, found: {:#x})", expected, found),
+            PipelineCacheError::DeviceMismatch { expected, found } => write!(f, "pipeline cache \
+                device ID mismatch (expected: {:#x}, found: {:#x})", expected, found),
+            PipelineCacheError::UuidMismatch => write!(f, "pipeline cache UUID mismatch"),
+        }
+    }
+}
+
+
+/// The header at the start of all pipeline cache data.
+///
+/// https://www.khronos.org/registry/vulkan/specs/1.0/html/vkspec.html#pipelines-cache-header
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PipelineCachePrefix {
+    header_length: u32,
+    header_version: u32,
+    vendor_id: u32,
+    device_id: u32,
+    pipeline_cache_uuid: [u8; UUID_SIZE],
+}
+
+impl PipelineCachePrefix {
+    /// Parses the header at the start of `data`.
+    ///
+    /// Header fields are always stored least significant byte first.
+    pub fn parse(data: &[u8]) -> Result<PipelineCachePrefix, PipelineCacheError> {
+        let truncated = PipelineCacheError::Truncated { len: data.len() };
+        if data.len() < HEADER_LENGTH_ONE { return Err(truncated); }
+        let read_u32 = |offset: usize| {
+            (data[offset] as u32) | (data[offset + 1] as u32) << 8 |
+                (data[offset + 2] as u32) << 16 | (data[offset + 3] as u32) << 24
+        };
+
+        let header_length = read_u32(0);
+        let header_version = read_u32(4);
+        if header_version != HEADER_VERSION_ONE {
+            return Err(PipelineCacheError::UnsupportedVersion(header_version));
+        }
+        if (header_length as usize) < HEADER_LENGTH_ONE || header_length as usize > data.len() {
+            return Err(truncated);
+        }
+
+        let mut pipeline_cache_uuid = [0u8; UUID_SIZE];
+        pipeline_cache_uuid.copy_from_slice(&data[16..HEADER_LENGTH_ONE]);
+
+        Ok(PipelineCachePrefix {
+            header_length,
+            header_version,
+            vendor_id: read_u32(8),
+            device_id: read_u32(12),
+            pipeline_cache_uuid,
+        })
+    }
+
+    /// Returns an error if data with this header was not created by a device
+    /// with the specified properties.
+    pub fn validate(&self, properties: &PhysicalDeviceProperties)
+            -> Result<(), PipelineCacheError> {
+        if self.vendor_id != properties.vendor_id() {
+            return Err(PipelineCacheError::VendorMismatch { expected: properties.vendor_id(),
+                found: self.vendor_id });
+        }
+        if self.device_id != properties.device_id() {
+            return Err(PipelineCacheError::DeviceMismatch { expected: properties.device_id(),
+                found: self.device_id });
+        }
+        if &self.pipeline_cache_uuid[..] != properties.pipeline_cache_uuid() {
+            return Err(PipelineCacheError::UuidMismatch);
+        }
+        Ok(())
+    }
+
+    pub fn header_length(&self) -> u32 {
+        self.header_length
+    }
+
+    pub fn header_version(&self) -> u32 {
+        self.header_version
+    }
+
+    pub fn vendor_id(&self) -> u32 {
+        self.vendor_id
+    }
+
+    pub fn device_id(&self) -> u32 {
+        self.device_id
+    }
+
+    pub fn pipeline_cache_uuid(&self) -> &[u8; UUID_SIZE] {
+        &self.pipeline_cache_uuid
+    }
+}
+
+
+/// Retrieves the contents of a pipeline cache, verifying that the result
+/// is well-formed.
+pub(crate) unsafe fn pipeline_cache_data(device: &Device, pipeline_cache: PipelineCacheHandle)
+        -> VdResult<Vec<u8>> {
+    let mut data_size = 0usize;
+    device.get_pipeline_cache_data(pipeline_cache, &mut data_size, ptr::null_mut())?;
+    let mut data = vec![0u8; data_size];
+    device.get_pipeline_cache_data(pipeline_cache, &mut data_size,
+        data.as_mut_ptr() as *mut c_void)?;
+    data.truncate(data_size);
+    PipelineCachePrefix::parse(&data)?;
+    Ok(data)
+}
+
+
+#[derive(Debug)]
+struct Inner {
+    handle: PipelineCacheHandle,
+    device: Device,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_pipeline_cache(self.handle, None);
+        }
+    }
+}
+
+
+/// A pipeline cache.
+///
+///
+/// ### Destruction
+///
+/// Dropping this `PipelineCache` will cause `Device::destroy_pipeline_cache`
+/// to be called, automatically releasing any resources associated with it.
+///
+#[derive(Debug, Clone)]
+pub struct PipelineCache {
+    inner: Arc<Inner>,
+}
+
+impl PipelineCache {
+    /// Creates and returns a new, empty `PipelineCache`.
+    pub fn new(device: Device) -> VdResult<PipelineCache> {
+        PipelineCache::with_data(device, &[])
+    }
+
+    /// Creates and returns a new `PipelineCache` populated with previously
+    /// retrieved `data` after verifying that it was created by a device
+    /// compatible with `device`.
+    ///
+    /// Returns a `PipelineCacheError` if `data` is malformed or was created
+    /// by a different vendor, device, or driver version.
+    pub fn load_validated(device: Device, data: &[u8]) -> VdResult<PipelineCache> {
+        let prefix = PipelineCachePrefix::parse(data)?;
+        prefix.validate(device.properties())?;
+        PipelineCache::with_data(device, data)
+    }
+
+    fn with_data(device: Device, data: &[u8]) -> VdResult<PipelineCache> {
+        let mut create_info = PipelineCacheCreateInfo::default();
+        if !data.is_empty() {
+            create_info.set_initial_data_size(data.len());
+            unsafe { create_info.set_initial_data(data.as_ptr() as *const c_void); }
+        }
+        let handle = unsafe { device.create_pipeline_cache(&create_info, None)? };
+
+        Ok(PipelineCache {
+            inner: Arc::new(Inner {
+                handle,
+                device,
+            })
+        })
+    }
+
+    /// Returns the serialized contents of this cache, suitable for passing to
+    /// `load_validated` in a later run.
+    pub fn data(&self) -> VdResult<Vec<u8>> {
+        unsafe { pipeline_cache_data(&self.inner.device, self.inner.handle) }
+    }
+
+    /// Returns this object's handle.
+    pub fn handle(&self) -> PipelineCacheHandle {
+        self.inner.handle
+    }
+
+    /// Returns a reference to the associated device.
+    pub fn device(&self) -> &Device {
+        &self.inner.device
+    }
+}
+
+unsafe impl<'h> Handle for &'h PipelineCache {
+    type Target = PipelineCacheHandle;
+
+    #[inline(always)]
+    fn handle(&self) -> Self::Target {
+        self.inner.handle
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use loader::null;
+    use ::ErrorKind;
+    use super::{PipelineCache, PipelineCachePrefix, PipelineCacheError, HEADER_LENGTH_ONE};
+
+    /// Returns cache data with a version one header for the specified
+    /// device followed by `payload_len` bytes of payload.
+    fn cache_data(vendor_id: u32, device_id: u32, uuid: [u8; 16], payload_len: usize)
+            -> Vec<u8> {
+        let mut data = Vec::new();
+        for &word in [HEADER_LENGTH_ONE as u32, 1, vendor_id, device_id].iter() {
+            data.extend_from_slice(&[word as u8, (word >> 8) as u8, (word >> 16) as u8,
+                (word >> 24) as u8]);
+        }
+        data.extend_from_slice(&uuid);
+        data.extend(0..payload_len as u8);
+        data
+    }
+
+    fn null_cache_data() -> Vec<u8> {
+        cache_data(null::VENDOR_ID, null::DEVICE_ID, null::PIPELINE_CACHE_UUID, 8)
+    }
+
+    fn load_error(data: &[u8]) -> PipelineCacheError {
+        let err = PipelineCache::load_validated(null::device(), data).unwrap_err();
+        match *err.kind() {
+            ErrorKind::PipelineCache(ref err) => err.clone(),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn header_fields_are_little_endian() {
+        let prefix = PipelineCachePrefix::parse(&null_cache_data()).unwrap();
+        assert_eq!(prefix.header_length() as usize, HEADER_LENGTH_ONE);
+        assert_eq!(prefix.header_version(), 1);
+        assert_eq!(prefix.vendor_id(), null::VENDOR_ID);
+        assert_eq!(prefix.device_id(), null::DEVICE_ID);
+        assert_eq!(prefix.pipeline_cache_uuid(), &null::PIPELINE_CACHE_UUID);
+        prefix.validate(null::device().properties()).unwrap();
+    }
+
+    #[test]
+    fn truncated_headers_are_rejected() {
+        let data = null_cache_data();
+        for &len in [0, 4, HEADER_LENGTH_ONE - 1].iter() {
+            assert_eq!(PipelineCachePrefix::parse(&data[..len]),
+                Err(PipelineCacheError::Truncated { len }));
+            assert_eq!(load_error(&data[..len]), PipelineCacheError::Truncated { len });
+        }
+
+        // A header claiming to be longer than the data.
+        let mut data = null_cache_data();
+        data[0] = 0xff;
+        assert_eq!(PipelineCachePrefix::parse(&data),
+            Err(PipelineCacheError::Truncated { len: data.len() }));
+
+        let mut data = null_cache_data();
+        data[4] = 2;
+        assert_eq!(PipelineCachePrefix::parse(&data),
+            Err(PipelineCacheError::UnsupportedVersion(2)));
+    }
+
+    #[test]
+    fn data_from_other_devices_is_rejected() {
+        let data = cache_data(0x1002, null::DEVICE_ID, null::PIPELINE_CACHE_UUID, 8);
+        assert_eq!(load_error(&data),
+            PipelineCacheError::VendorMismatch { expected: null::VENDOR_ID, found: 0x1002 });
+
+        let data = cache_data(null::VENDOR_ID, 0x687f, null::PIPELINE_CACHE_UUID, 8);
+        assert_eq!(load_error(&data),
+            PipelineCacheError::DeviceMismatch { expected: null::DEVICE_ID, found: 0x687f });
+
+        // Same device, different driver build.
+        let mut uuid = null::PIPELINE_CACHE_UUID;
+        uuid[15] ^= 1;
+        let data = cache_data(null::VENDOR_ID, null::DEVICE_ID, uuid, 8);
+        assert_eq!(load_error(&data), PipelineCacheError::UuidMismatch);
+    }
+}
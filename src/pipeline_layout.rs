@@ -1,8 +1,9 @@
 use std::sync::Arc;
 use std::marker::PhantomData;
+use smallvec::SmallVec;
 use vks;
 use ::{VdResult, Device,  DescriptorSetLayoutHandle, Handle,
-    PipelineLayoutCreateInfo, PushConstantRange};
+    PipelineLayoutCreateInfo, PushConstantRange, ShaderStageFlags};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -10,6 +11,12 @@ use ::{VdResult, Device,  DescriptorSetLayoutHandle, Handle,
 pub struct PipelineLayoutHandle(pub(crate) vks::VkPipelineLayout);
 
 impl PipelineLayoutHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkPipelineLayout) -> PipelineLayoutHandle {
+        PipelineLayoutHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkPipelineLayout {
         self.0
@@ -27,9 +34,47 @@ unsafe impl Handle for PipelineLayoutHandle {
 }
 
 
+/// Checks a push constant update against `push_constant_ranges`.
+///
+/// See `PipelineLayout::validate_push_constants`.
+fn validate_push_constants(push_constant_ranges: &[PushConstantRange],
+        stage_flags: ShaderStageFlags, offset: u32, size: u32) -> VdResult<()> {
+    let end = offset as u64 + size as u64;
+    if size == 0 {
+        return Err(format!("PipelineLayout::validate_push_constants: empty push constant \
+            update at offset {}", offset).into());
+    }
+    for byte in offset as u64..end {
+        let mut covered_stages = ShaderStageFlags::empty();
+        for range in push_constant_ranges.iter() {
+            let range_start = range.offset() as u64;
+            if byte < range_start || byte >= range_start + range.size() as u64 {
+                continue;
+            }
+            if !stage_flags.contains(range.stage_flags()) {
+                return Err(format!("PipelineLayout::validate_push_constants: bytes {}..{} \
+                    overlap the push constant range at bytes {}..{} for stages {:?}, which \
+                    are not all included in the update's stages ({:?})", offset, end,
+                    range.offset(), range_start + range.size() as u64, range.stage_flags(),
+                    stage_flags).into());
+            }
+            covered_stages |= range.stage_flags();
+        }
+        if !covered_stages.contains(stage_flags) {
+            return Err(format!("PipelineLayout::validate_push_constants: bytes {}..{} for \
+                stages {:?} are not covered by the layout's push constant ranges (byte {} \
+                is missing stages {:?})", offset, end, stage_flags, byte,
+                stage_flags - covered_stages).into());
+        }
+    }
+    Ok(())
+}
+
+
 #[derive(Debug)]
 struct Inner {
     handle: PipelineLayoutHandle,
+    push_constant_ranges: SmallVec<[PushConstantRange; 4]>,
     device: Device,
 }
 
@@ -66,6 +111,24 @@ impl PipelineLayout {
         self.inner.handle
     }
 
+    /// Returns the push constant ranges this layout was created with.
+    pub fn push_constant_ranges(&self) -> &[PushConstantRange] {
+        &self.inner.push_constant_ranges
+    }
+
+    /// Checks that a push constant update of `size` bytes at `offset`, for
+    /// the stages in `stage_flags`, is permitted by this layout's push
+    /// constant ranges.
+    ///
+    /// Every byte written must be covered, for each stage in `stage_flags`,
+    /// by a range including that stage, and every range covering a written
+    /// byte must have all of its stages included in `stage_flags`. An update
+    /// straddling two ranges with differing stages therefore fails.
+    pub fn validate_push_constants(&self, stage_flags: ShaderStageFlags, offset: u32, size: u32)
+            -> VdResult<()> {
+        validate_push_constants(&self.inner.push_constant_ranges, stage_flags, offset, size)
+    }
+
     /// Returns a reference to the associated device.
     pub fn device(&self) -> &Device {
         &self.inner.device
@@ -128,11 +191,84 @@ impl<'b> PipelineLayoutBuilder<'b> {
     pub fn build(&self, device: Device) -> VdResult<PipelineLayout> {
         let handle = unsafe { device.create_pipeline_layout(&self.create_info, None)? };
 
+        let push_constant_ranges = if self.create_info.as_raw().pushConstantRangeCount == 0 {
+            SmallVec::new()
+        } else {
+            self.create_info.push_constant_ranges().iter().cloned().collect()
+        };
+
         Ok(PipelineLayout {
             inner: Arc::new(Inner {
                 handle,
+                push_constant_ranges,
                 device,
             })
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use ::{PushConstantRange, ShaderStageFlags, ErrorKind};
+    use super::validate_push_constants;
+
+    fn range(stage_flags: ShaderStageFlags, offset: u32, size: u32) -> PushConstantRange {
+        PushConstantRange::builder()
+            .stage_flags(stage_flags)
+            .offset(offset)
+            .size(size)
+            .build()
+    }
+
+    fn ranges() -> [PushConstantRange; 3] {
+        [
+            range(ShaderStageFlags::VERTEX, 0, 64),
+            range(ShaderStageFlags::FRAGMENT, 64, 16),
+            range(ShaderStageFlags::FRAGMENT, 80, 16),
+        ]
+    }
+
+    fn error_message(stage_flags: ShaderStageFlags, offset: u32, size: u32) -> String {
+        let err = validate_push_constants(&ranges(), stage_flags, offset, size).unwrap_err();
+        match *err.kind() {
+            ErrorKind::String(ref msg) => msg.clone(),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn updates_within_a_range_are_accepted() {
+        validate_push_constants(&ranges(), ShaderStageFlags::VERTEX, 0, 64).unwrap();
+        validate_push_constants(&ranges(), ShaderStageFlags::VERTEX, 16, 4).unwrap();
+        validate_push_constants(&ranges(), ShaderStageFlags::FRAGMENT, 64, 16).unwrap();
+        // Adjacent ranges for the same stages cover an update together.
+        validate_push_constants(&ranges(), ShaderStageFlags::FRAGMENT, 72, 24).unwrap();
+    }
+
+    #[test]
+    fn updates_straddling_ranges_with_differing_stages_are_rejected() {
+        let msg = error_message(ShaderStageFlags::VERTEX, 56, 16);
+        assert!(msg.contains("bytes 56..72"), "{}", msg);
+        assert!(msg.contains("range at bytes 64..80"), "{}", msg);
+
+        // Naming both stages still leaves each byte covered for only one.
+        let msg = error_message(ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT, 56, 16);
+        assert!(msg.contains("not covered"), "{}", msg);
+    }
+
+    #[test]
+    fn updates_for_other_stages_are_rejected() {
+        let msg = error_message(ShaderStageFlags::FRAGMENT, 0, 4);
+        assert!(msg.contains("bytes 0..4"), "{}", msg);
+        let msg = error_message(ShaderStageFlags::COMPUTE, 64, 4);
+        assert!(msg.contains("bytes 64..68"), "{}", msg);
+    }
+
+    #[test]
+    fn updates_outside_every_range_are_rejected() {
+        let msg = error_message(ShaderStageFlags::FRAGMENT, 88, 16);
+        assert!(msg.contains("byte 96 is missing"), "{}", msg);
+        error_message(ShaderStageFlags::VERTEX, 0, 0);
+        assert!(validate_push_constants(&[], ShaderStageFlags::VERTEX, 0, 4).is_err());
+    }
+}
@@ -0,0 +1,438 @@
+use std::sync::Arc;
+use std::marker::PhantomData;
+use std::mem;
+use smallvec::SmallVec;
+use libc::c_void;
+use ::{VdResult, Device, Handle, QueryPoolHandle, QueryPoolCreateInfo, QueryType,
+    QueryPipelineStatisticFlags, QueryResultFlags, CommandBuffer};
+
+
+/// Pipeline statistics counter values, read from a pipeline statistics
+/// query.
+///
+/// Counters not enabled in the pool's `QueryPipelineStatisticFlags` are
+/// `None`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PipelineStatistics {
+    pub input_assembly_vertices: Option<u64>,
+    pub input_assembly_primitives: Option<u64>,
+    pub vertex_shader_invocations: Option<u64>,
+    pub geometry_shader_invocations: Option<u64>,
+    pub geometry_shader_primitives: Option<u64>,
+    pub clipping_invocations: Option<u64>,
+    pub clipping_primitives: Option<u64>,
+    pub fragment_shader_invocations: Option<u64>,
+    pub tessellation_control_shader_patches: Option<u64>,
+    pub tessellation_evaluation_shader_invocations: Option<u64>,
+    pub compute_shader_invocations: Option<u64>,
+}
+
+/// Pipeline statistics flags in the order their counters are written.
+const PIPELINE_STATISTICS_ORDER: [QueryPipelineStatisticFlags; 11] = [
+    QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES,
+    QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES,
+    QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS,
+    QueryPipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS,
+    QueryPipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES,
+    QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS,
+    QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES,
+    QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS,
+    QueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES,
+    QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS,
+    QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS,
+];
+
+impl PipelineStatistics {
+    /// Interprets the packed counter values written for a query in a pool
+    /// created with `flags`.
+    ///
+    /// Values are packed in order of increasing flag bit, one for each flag
+    /// set. Returns `None` if `values` is too short.
+    pub fn from_packed(flags: QueryPipelineStatisticFlags, values: &[u64])
+            -> Option<PipelineStatistics> {
+        let mut values = values.iter();
+        let mut counters = [None; 11];
+        for (counter, &flag) in counters.iter_mut().zip(PIPELINE_STATISTICS_ORDER.iter()) {
+            if flags.contains(flag) {
+                *counter = Some(*values.next()?);
+            }
+        }
+        Some(PipelineStatistics {
+            input_assembly_vertices: counters[0],
+            input_assembly_primitives: counters[1],
+            vertex_shader_invocations: counters[2],
+            geometry_shader_invocations: counters[3],
+            geometry_shader_primitives: counters[4],
+            clipping_invocations: counters[5],
+            clipping_primitives: counters[6],
+            fragment_shader_invocations: counters[7],
+            tessellation_control_shader_patches: counters[8],
+            tessellation_evaluation_shader_invocations: counters[9],
+            compute_shader_invocations: counters[10],
+        })
+    }
+}
+
+
+/// Returns the number of values written for each query in a pool of
+/// `query_type` counting `pipeline_statistics`.
+fn values_per_query(query_type: QueryType, pipeline_statistics: QueryPipelineStatisticFlags)
+        -> usize {
+    match query_type {
+        QueryType::PipelineStatistics => PIPELINE_STATISTICS_ORDER.iter()
+            .filter(|&&flag| pipeline_statistics.contains(flag)).count(),
+        _ => 1,
+    }
+}
+
+
+#[derive(Debug)]
+struct Inner {
+    handle: QueryPoolHandle,
+    device: Device,
+    query_type: QueryType,
+    query_count: u32,
+    pipeline_statistics: QueryPipelineStatisticFlags,
+    precise: bool,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_query_pool(self.handle, None);
+        }
+    }
+}
+
+
+/// A query pool.
+///
+///
+/// ### Destruction
+///
+/// Dropping this `QueryPool` will cause `Device::destroy_query_pool` to be
+/// called, automatically releasing any resources associated with it.
+///
+#[derive(Debug, Clone)]
+pub struct QueryPool {
+    inner: Arc<Inner>,
+}
+
+impl QueryPool {
+    /// Returns a new `QueryPoolBuilder`.
+    pub fn builder<'b>() -> QueryPoolBuilder<'b> {
+        QueryPoolBuilder::new()
+    }
+
+    /// Creates and returns a new occlusion query pool.
+    ///
+    /// If `precise` is true, queries will be begun with
+    /// `QueryControlFlags::PRECISE` and return exact sample counts. This
+    /// requires the `occlusionQueryPrecise` device feature.
+    pub fn occlusion(device: Device, query_count: u32, precise: bool) -> VdResult<QueryPool> {
+        QueryPool::builder()
+            .query_type(QueryType::Occlusion)
+            .query_count(query_count)
+            .precise(precise)
+            .build(device)
+    }
+
+    /// Creates and returns a new pipeline statistics query pool counting
+    /// `pipeline_statistics`.
+    pub fn statistics(device: Device, query_count: u32,
+            pipeline_statistics: QueryPipelineStatisticFlags) -> VdResult<QueryPool> {
+        QueryPool::builder()
+            .query_type(QueryType::PipelineStatistics)
+            .query_count(query_count)
+            .pipeline_statistics(pipeline_statistics)
+            .build(device)
+    }
+
+    /// Creates and returns a new timestamp query pool.
+    pub fn timestamps(device: Device, query_count: u32) -> VdResult<QueryPool> {
+        QueryPool::builder()
+            .query_type(QueryType::Timestamp)
+            .query_count(query_count)
+            .build(device)
+    }
+
+    /// Returns the values written for `query`, or `None` if they are not yet
+    /// available.
+    fn values(&self, query: u32) -> VdResult<Option<SmallVec<[u64; 12]>>> {
+        if query >= self.inner.query_count {
+            return Err(format!("QueryPool: query index ({}) out of range (query count: {})",
+                query, self.inner.query_count).into());
+        }
+        let count = values_per_query(self.inner.query_type, self.inner.pipeline_statistics);
+        // The values are followed by the availability.
+        let mut values: SmallVec<[u64; 12]> = SmallVec::from_slice(&[0u64; 12][..count + 1]);
+        unsafe {
+            self.inner.device.get_query_pool_results(self, query, 1,
+                values.len() * mem::size_of::<u64>(), values.as_mut_ptr() as *mut c_void,
+                (values.len() * mem::size_of::<u64>()) as u64,
+                QueryResultFlags::RESULT_64 | QueryResultFlags::WITH_AVAILABILITY)?;
+        }
+        if values.pop() == Some(0) { return Ok(None); }
+        Ok(Some(values))
+    }
+
+    /// Returns the number of samples which passed depth and stencil tests
+    /// during occlusion query `query`, or `None` if the result is not yet
+    /// available.
+    ///
+    /// Imprecise pools may return any non-zero value if any samples passed.
+    pub fn occlusion_result(&self, query: u32) -> VdResult<Option<u64>> {
+        if self.inner.query_type != QueryType::Occlusion {
+            return Err("QueryPool::occlusion_result: not an occlusion query pool".into());
+        }
+        Ok(self.values(query)?.map(|values| values[0]))
+    }
+
+    /// Returns the counters written by pipeline statistics query `query`,
+    /// or `None` if the result is not yet available.
+    pub fn statistics_result(&self, query: u32) -> VdResult<Option<PipelineStatistics>> {
+        if self.inner.query_type != QueryType::PipelineStatistics {
+            return Err("QueryPool::statistics_result: not a pipeline statistics query \
+                pool".into());
+        }
+        Ok(self.values(query)?.and_then(|values| {
+            PipelineStatistics::from_packed(self.inner.pipeline_statistics, &values)
+        }))
+    }
+
+    /// Returns this object's handle.
+    pub fn handle(&self) -> QueryPoolHandle {
+        self.inner.handle
+    }
+
+    /// Returns a reference to the associated device.
+    pub fn device(&self) -> &Device {
+        &self.inner.device
+    }
+
+    /// Returns the type of queries managed by this pool.
+    pub fn query_type(&self) -> QueryType {
+        self.inner.query_type
+    }
+
+    /// Returns the number of queries managed by this pool.
+    pub fn query_count(&self) -> u32 {
+        self.inner.query_count
+    }
+
+    /// Returns the statistics counted by this pool's queries.
+    ///
+    /// Empty unless this is a pipeline statistics pool.
+    pub fn pipeline_statistics(&self) -> QueryPipelineStatisticFlags {
+        self.inner.pipeline_statistics
+    }
+
+    /// Returns true if this is an occlusion pool whose queries are begun
+    /// precise.
+    pub fn is_precise(&self) -> bool {
+        self.inner.precise
+    }
+}
+
+unsafe impl<'h> Handle for &'h QueryPool {
+    type Target = QueryPoolHandle;
+
+    #[inline(always)]
+    fn handle(&self) -> Self::Target {
+        self.inner.handle
+    }
+}
+
+
+/// A builder for `QueryPool`.
+#[derive(Debug, Clone)]
+pub struct QueryPoolBuilder<'b> {
+    create_info: QueryPoolCreateInfo<'b>,
+    precise: bool,
+    _p: PhantomData<&'b ()>,
+}
+
+impl<'b> QueryPoolBuilder<'b> {
+    /// Returns a new query pool builder.
+    pub fn new() -> QueryPoolBuilder<'b> {
+        QueryPoolBuilder {
+            create_info: QueryPoolCreateInfo::default(),
+            precise: false,
+            _p: PhantomData,
+        }
+    }
+
+    /// Specifies the type of queries managed by the pool.
+    pub fn query_type<'s>(&'s mut self, query_type: QueryType)
+            -> &'s mut QueryPoolBuilder<'b> {
+        self.create_info.set_query_type(query_type);
+        self
+    }
+
+    /// Specifies the number of queries managed by the pool.
+    pub fn query_count<'s>(&'s mut self, query_count: u32)
+            -> &'s mut QueryPoolBuilder<'b> {
+        self.create_info.set_query_count(query_count);
+        self
+    }
+
+    /// Specifies which counters will be returned by queries in a pipeline
+    /// statistics pool.
+    pub fn pipeline_statistics<'s>(&'s mut self, pipeline_statistics: QueryPipelineStatisticFlags)
+            -> &'s mut QueryPoolBuilder<'b> {
+        self.create_info.set_pipeline_statistics(pipeline_statistics);
+        self
+    }
+
+    /// Specifies whether occlusion queries in the pool are begun precise.
+    ///
+    /// Defaults to false.
+    pub fn precise<'s>(&'s mut self, precise: bool) -> &'s mut QueryPoolBuilder<'b> {
+        self.precise = precise;
+        self
+    }
+
+    /// Creates and returns a new `QueryPool`
+    pub fn build(&self, device: Device) -> VdResult<QueryPool> {
+        if self.precise && self.create_info.query_type() != QueryType::Occlusion {
+            return Err("QueryPoolBuilder::build: only occlusion pools may be precise".into());
+        }
+        let handle = unsafe { device.create_query_pool(&self.create_info, None)? };
+
+        Ok(QueryPool {
+            inner: Arc::new(Inner {
+                handle,
+                device,
+                query_type: self.create_info.query_type(),
+                query_count: self.create_info.query_count(),
+                pipeline_statistics: self.create_info.pipeline_statistics(),
+                precise: self.precise,
+            })
+        })
+    }
+}
+
+
+/// An active query, ended when this scope is dropped or `end` is called.
+///
+/// Returned by `CommandBuffer::begin_query`.
+#[must_use = "the query ends as soon as its scope is dropped"]
+#[derive(Debug)]
+pub struct QueryScope<'q> {
+    command_buffer: &'q CommandBuffer,
+    query_pool: &'q QueryPool,
+    query: u32,
+    ended: bool,
+}
+
+impl<'q> QueryScope<'q> {
+    pub(crate) fn new(command_buffer: &'q CommandBuffer, query_pool: &'q QueryPool, query: u32)
+            -> QueryScope<'q> {
+        QueryScope { command_buffer, query_pool, query, ended: false }
+    }
+
+    /// Ends the query.
+    pub fn end(mut self) {
+        self.end_query();
+    }
+
+    fn end_query(&mut self) {
+        if !self.ended {
+            self.command_buffer.end_query(self.query_pool, self.query);
+            self.ended = true;
+        }
+    }
+
+    /// Returns the index of the active query.
+    pub fn query(&self) -> u32 {
+        self.query
+    }
+
+    /// Returns a reference to the pool containing the active query.
+    pub fn query_pool(&self) -> &'q QueryPool {
+        self.query_pool
+    }
+}
+
+impl<'q> Drop for QueryScope<'q> {
+    fn drop(&mut self) {
+        self.end_query();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use loader::null;
+    use ::{CommandPool, CommandBuffer, CommandBufferLevel, CommandBufferUsageFlags, QueryPool,
+        QueryControlFlags, RenderPassBeginInfo, RenderPassHandle, FramebufferHandle,
+        SubpassContents};
+
+    /// Returns a command buffer of the null device in the recording state,
+    /// inside a render pass instance.
+    fn command_buffer() -> CommandBuffer {
+        let command_pool = CommandPool::builder()
+            .queue_family_index(0)
+            .build(null::device())
+            .unwrap();
+        let command_buffer = command_pool.allocate_command_buffer(CommandBufferLevel::Primary)
+            .unwrap();
+        command_buffer.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT).unwrap();
+        let begin_info = unsafe {
+            RenderPassBeginInfo::builder()
+                .render_pass(RenderPassHandle::from_raw(1))
+                .framebuffer(FramebufferHandle::from_raw(1))
+                .build()
+        };
+        command_buffer.begin_render_pass(&begin_info, SubpassContents::Inline);
+        command_buffer
+    }
+
+    #[test]
+    fn scope_ends_query_when_ended() {
+        let command_buffer = command_buffer();
+        let pool = QueryPool::occlusion(null::device(), 2, false).unwrap();
+        command_buffer.draw(3, 1, 0, 0);
+        let scope = command_buffer.begin_query(&pool, 0, QueryControlFlags::empty()).unwrap();
+        command_buffer.draw(3, 1, 0, 0);
+        scope.end();
+        command_buffer.draw(3, 1, 0, 0);
+        command_buffer.end_render_pass();
+        command_buffer.end().unwrap();
+        assert_eq!(null::take_commands(), ["vkCmdBeginRenderPass", "vkCmdDraw",
+            "vkCmdBeginQuery", "vkCmdDraw", "vkCmdEndQuery", "vkCmdDraw", "vkCmdEndRenderPass"]);
+    }
+
+    #[test]
+    fn scope_ends_query_when_dropped() {
+        let command_buffer = command_buffer();
+        let pool = QueryPool::occlusion(null::device(), 2, false).unwrap();
+        {
+            let _scope = command_buffer.begin_query(&pool, 0, QueryControlFlags::empty())
+                .unwrap();
+            command_buffer.draw(3, 1, 0, 0);
+        }
+        command_buffer.draw(3, 1, 0, 0);
+        // The first query is no longer active, so another may be begun.
+        let scope = command_buffer.begin_query(&pool, 1, QueryControlFlags::empty()).unwrap();
+        scope.end();
+        command_buffer.end_render_pass();
+        command_buffer.end().unwrap();
+        assert_eq!(null::take_commands(), ["vkCmdBeginRenderPass", "vkCmdBeginQuery",
+            "vkCmdDraw", "vkCmdEndQuery", "vkCmdDraw", "vkCmdBeginQuery", "vkCmdEndQuery",
+            "vkCmdEndRenderPass"]);
+    }
+
+    #[test]
+    fn nested_scopes_of_one_type_are_rejected() {
+        let command_buffer = command_buffer();
+        let pool = QueryPool::occlusion(null::device(), 2, false).unwrap();
+        let scope = command_buffer.begin_query(&pool, 0, QueryControlFlags::empty()).unwrap();
+        assert!(command_buffer.begin_query(&pool, 1, QueryControlFlags::empty()).is_err());
+        command_buffer.draw(3, 1, 0, 0);
+        scope.end();
+        command_buffer.end_render_pass();
+        assert_eq!(null::take_commands(), ["vkCmdBeginRenderPass", "vkCmdBeginQuery",
+            "vkCmdDraw", "vkCmdEndQuery", "vkCmdEndRenderPass"]);
+    }
+}
@@ -1,5 +1,6 @@
 use vks;
-use ::{VdResult, Device, Handle, SubmitInfo, FenceHandle, BindSparseInfo, PresentInfoKhr};
+use ::{VdResult, Device, Handle, SubmitInfo, FenceHandle, BindSparseInfo, PresentInfoKhr,
+    SemaphoreHandle, PipelineStageFlags, CommandBufferHandle, PresentResult};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -7,6 +8,12 @@ use ::{VdResult, Device, Handle, SubmitInfo, FenceHandle, BindSparseInfo, Presen
 pub struct QueueHandle(pub(crate) vks::VkQueue);
 
 impl QueueHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkQueue) -> QueueHandle {
+        QueueHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkQueue {
         self.0
@@ -43,6 +50,11 @@ impl Queue {
         }
     }
 
+    /// Returns this object's handle.
+    pub fn handle(&self) -> QueueHandle {
+        self.handle
+    }
+
     /// Returns a reference to this object's associated device.
     pub fn device(&self) -> &Device {
         &self.device
@@ -59,11 +71,24 @@ impl Queue {
     }
 
     /// Submits a sequence of semaphores or command buffers to this queue.
+    ///
+    /// See `SubmitBatch` for assembling several batches without managing
+    /// the lifetimes of their arrays.
     #[inline]
     pub fn submit(&self, submit_info: &[SubmitInfo], fence: Option<FenceHandle>) -> VdResult<()> {
         unsafe { self.device.queue_submit(self.handle, submit_info, fence) }
     }
 
+    /// Submits a single command buffer to this queue without allocating.
+    ///
+    /// See `Device::queue_submit_one`.
+    #[inline]
+    pub fn submit_one(&self, wait: &[(SemaphoreHandle, PipelineStageFlags)],
+            command_buffer: CommandBufferHandle, signal: &[SemaphoreHandle],
+            fence: Option<FenceHandle>) -> VdResult<()> {
+        unsafe { self.device.queue_submit_one(self.handle, wait, command_buffer, signal, fence) }
+    }
+
     /// Waits for this queue to become idle.
     pub fn wait_idle(&self) {
         self.device.queue_wait_idle(self.handle)
@@ -71,20 +96,30 @@ impl Queue {
 
     /// Binds device memory to a sparse resource object.
     ///
+    /// Returns a `FeatureNotEnabled` error if the device was not created
+    /// with the `sparseBinding` feature enabled.
+    ///
+    /// See `SparseBindBatch` for assembling bind infos without managing the
+    /// lifetimes of their arrays.
+    ///
     /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkQueueBindSparse.html
     //
     #[inline]
-    pub fn bind_sparse<Q, F>(&self, bind_info: &[BindSparseInfo], fence: F) -> VdResult<()>
-            where Q: Handle<Target=QueueHandle>, F: Handle<Target=FenceHandle> {
+    pub fn bind_sparse(&self, bind_info: &[BindSparseInfo], fence: Option<FenceHandle>)
+            -> VdResult<()> {
+        self.device.require_feature(|f| f.sparse_binding(), "sparseBinding",
+            "Queue::bind_sparse")?;
         unsafe { self.device.queue_bind_sparse(self.handle, bind_info, fence) }
     }
 
     /// Queues an image for presentation.
     ///
+    /// See `Device::queue_present_khr`.
+    ///
     /// https://manned.org/vkQueuePresentKHR.3
     //
     #[inline]
-    pub fn present_khr(&self, present_info: &PresentInfoKhr) -> VdResult<()> {
+    pub fn present_khr(&self, present_info: &PresentInfoKhr) -> VdResult<PresentResult> {
         unsafe { self.device.queue_present_khr(self.handle, present_info) }
     }
 }
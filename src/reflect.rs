@@ -0,0 +1,519 @@
+//! Descriptor set layout reflection from SPIR-V.
+//!
+//! Only the instructions needed to describe descriptor bindings and the push
+//! constant block are parsed: `OpDecorate`/`OpMemberDecorate`, `OpVariable`,
+//! `OpConstant`, and the type declarations.
+//!
+//! ```text
+//! let vert = reflect_spirv(&vert_code, ShaderStageFlags::VERTEX)?;
+//! let frag = reflect_spirv(&frag_code, ShaderStageFlags::FRAGMENT)?;
+//! let reflection = merge_stage_reflections(&[vert, frag])?;
+//! let set_layout = DescriptorSetLayout::builder()
+//!     .reflected_set(&reflection, 0)
+//!     .build(device.clone())?;
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use ::{VdResult, DescriptorSetLayoutBinding, DescriptorType, ShaderStageFlags};
+
+
+const MAGIC: u32 = 0x07230203;
+const HEADER_LEN: usize = 5;
+
+// Opcodes:
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+
+// Decorations:
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ROW_MAJOR: u32 = 4;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+// Storage classes:
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+// Image dimensionalities:
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+
+fn size_overflow(ty_id: u32) -> ::Error {
+    format!("reflect_spirv: the size of a type (id {}) overflows", ty_id).into()
+}
+
+
+/// A type declaration.
+#[derive(Debug, Clone)]
+enum Type {
+    Scalar { width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Image { dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage { image: u32 },
+    Array { element: u32, length_id: u32 },
+    RuntimeArray { element: u32 },
+    Struct { members: Vec<u32> },
+    Pointer { pointee: u32 },
+}
+
+
+/// The declarations and decorations of a module relevant to reflection.
+#[derive(Debug, Default)]
+struct Module {
+    types: HashMap<u32, Type>,
+    constants: HashMap<u32, u32>,
+    // (result type, result id, storage class)
+    variables: Vec<(u32, u32, u32)>,
+    decorations: HashMap<(u32, u32), u32>,
+    flag_decorations: HashMap<u32, Vec<u32>>,
+    member_decorations: HashMap<(u32, u32, u32), u32>,
+    member_flag_decorations: HashMap<(u32, u32), Vec<u32>>,
+}
+
+impl Module {
+    fn parse(words: &[u32]) -> VdResult<Module> {
+        if words.len() < HEADER_LEN || words[0] != MAGIC {
+            return Err("reflect_spirv: not a SPIR-V module (bad magic number)".into());
+        }
+        let mut module = Module::default();
+        let mut idx = HEADER_LEN;
+        while idx < words.len() {
+            let word_count = (words[idx] >> 16) as usize;
+            let opcode = words[idx] & 0xffff;
+            if word_count == 0 || idx + word_count > words.len() {
+                return Err(format!("reflect_spirv: malformed instruction at word {}", idx)
+                    .into());
+            }
+            module.instruction(opcode, &words[idx + 1..idx + word_count])?;
+            idx += word_count;
+        }
+        Ok(module)
+    }
+
+    fn instruction(&mut self, opcode: u32, ops: &[u32]) -> VdResult<()> {
+        let min_len = match opcode {
+            OP_TYPE_BOOL | OP_TYPE_SAMPLER | OP_TYPE_RUNTIME_ARRAY | OP_TYPE_STRUCT => 1,
+            OP_TYPE_FLOAT | OP_TYPE_SAMPLED_IMAGE => 2,
+            OP_TYPE_INT | OP_TYPE_VECTOR | OP_TYPE_MATRIX | OP_TYPE_ARRAY | OP_TYPE_POINTER |
+            OP_CONSTANT | OP_VARIABLE => 3,
+            OP_TYPE_IMAGE => 7,
+            OP_DECORATE => 2,
+            OP_MEMBER_DECORATE => 3,
+            _ => return Ok(()),
+        };
+        if ops.len() < min_len {
+            return Err(format!("reflect_spirv: truncated instruction (opcode {})", opcode).into());
+        }
+        match opcode {
+            OP_TYPE_BOOL => { self.types.insert(ops[0], Type::Scalar { width: 32 }); },
+            OP_TYPE_INT | OP_TYPE_FLOAT => {
+                self.types.insert(ops[0], Type::Scalar { width: ops[1] });
+            },
+            OP_TYPE_VECTOR => {
+                self.types.insert(ops[0], Type::Vector { component: ops[1], count: ops[2] });
+            },
+            OP_TYPE_MATRIX => {
+                self.types.insert(ops[0], Type::Matrix { column: ops[1], count: ops[2] });
+            },
+            OP_TYPE_IMAGE => {
+                self.types.insert(ops[0], Type::Image { dim: ops[2], sampled: ops[6] });
+            },
+            OP_TYPE_SAMPLER => { self.types.insert(ops[0], Type::Sampler); },
+            OP_TYPE_SAMPLED_IMAGE => {
+                self.types.insert(ops[0], Type::SampledImage { image: ops[1] });
+            },
+            OP_TYPE_ARRAY => {
+                self.types.insert(ops[0], Type::Array { element: ops[1], length_id: ops[2] });
+            },
+            OP_TYPE_RUNTIME_ARRAY => {
+                self.types.insert(ops[0], Type::RuntimeArray { element: ops[1] });
+            },
+            OP_TYPE_STRUCT => {
+                self.types.insert(ops[0], Type::Struct { members: ops[1..].to_vec() });
+            },
+            OP_TYPE_POINTER => {
+                self.types.insert(ops[0], Type::Pointer { pointee: ops[2] });
+            },
+            // Only the low word of 64-bit constants is kept; lengths fit in 32 bits.
+            OP_CONSTANT => { self.constants.insert(ops[1], ops[2]); },
+            OP_VARIABLE => { self.variables.push((ops[0], ops[1], ops[2])); },
+            OP_DECORATE => match ops.get(2) {
+                Some(&value) => { self.decorations.insert((ops[0], ops[1]), value); },
+                None => self.flag_decorations.entry(ops[0]).or_insert_with(Vec::new)
+                    .push(ops[1]),
+            },
+            OP_MEMBER_DECORATE => match ops.get(3) {
+                Some(&value) => {
+                    self.member_decorations.insert((ops[0], ops[1], ops[2]), value);
+                },
+                None => self.member_flag_decorations.entry((ops[0], ops[1]))
+                    .or_insert_with(Vec::new).push(ops[2]),
+            },
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn ty(&self, id: u32) -> VdResult<&Type> {
+        self.types.get(&id).ok_or_else(|| format!("reflect_spirv: undeclared type (id {})", id)
+            .into())
+    }
+
+    fn has_flag(&self, id: u32, decoration: u32) -> bool {
+        self.flag_decorations.get(&id).map(|d| d.contains(&decoration)).unwrap_or(false)
+    }
+
+    /// Returns the descriptor type and count of a variable of type
+    /// `pointee` in `storage_class`, or `None` if it is not a descriptor.
+    fn descriptor(&self, storage_class: u32, pointee: u32)
+            -> VdResult<Option<(DescriptorType, u32)>> {
+        // Strip (possibly nested) array types:
+        let mut count = 1u32;
+        let mut ty_id = pointee;
+        loop {
+            match *self.ty(ty_id)? {
+                Type::Array { element, length_id } => {
+                    let length = *self.constants.get(&length_id).ok_or_else(||
+                        format!("reflect_spirv: array length is not a constant (id {})",
+                            length_id))?;
+                    count = count.saturating_mul(length);
+                    ty_id = element;
+                },
+                Type::RuntimeArray { element } => {
+                    count = 0;
+                    ty_id = element;
+                },
+                _ => break,
+            }
+        }
+
+        let descriptor_type = match (storage_class, self.ty(ty_id)?) {
+            (STORAGE_CLASS_UNIFORM_CONSTANT, &Type::Sampler) => DescriptorType::Sampler,
+            (STORAGE_CLASS_UNIFORM_CONSTANT, &Type::SampledImage { image }) => {
+                match *self.ty(image)? {
+                    Type::Image { dim: DIM_BUFFER, .. } => DescriptorType::UniformTexelBuffer,
+                    _ => DescriptorType::CombinedImageSampler,
+                }
+            },
+            (STORAGE_CLASS_UNIFORM_CONSTANT, &Type::Image { dim, sampled }) => {
+                match (dim, sampled) {
+                    (DIM_SUBPASS_DATA, _) => DescriptorType::InputAttachment,
+                    (DIM_BUFFER, 2) => DescriptorType::StorageTexelBuffer,
+                    (DIM_BUFFER, _) => DescriptorType::UniformTexelBuffer,
+                    (_, 2) => DescriptorType::StorageImage,
+                    _ => DescriptorType::SampledImage,
+                }
+            },
+            (STORAGE_CLASS_UNIFORM, &Type::Struct { .. }) => {
+                if self.has_flag(ty_id, DECORATION_BUFFER_BLOCK) {
+                    DescriptorType::StorageBuffer
+                } else {
+                    DescriptorType::UniformBuffer
+                }
+            },
+            (STORAGE_CLASS_STORAGE_BUFFER, &Type::Struct { .. }) => DescriptorType::StorageBuffer,
+            _ => return Ok(None),
+        };
+        Ok(Some((descriptor_type, count)))
+    }
+
+    /// Returns the size in bytes of a type as laid out in a block, or zero
+    /// for runtime arrays.
+    ///
+    /// Returns an error if the size does not fit in 32 bits.
+    fn size_of(&self, ty_id: u32) -> VdResult<u32> {
+        let size = match *self.ty(ty_id)? {
+            Type::Scalar { width } => Some(width / 8),
+            Type::Vector { component, count } => self.size_of(component)?.checked_mul(count),
+            // Matrices within blocks are sized using their member
+            // decorations; see `member_size`.
+            Type::Matrix { column, count } => self.size_of(column)?.checked_mul(count),
+            Type::Array { element, length_id } => {
+                let length = *self.constants.get(&length_id).unwrap_or(&0);
+                let stride = match self.decorations.get(&(ty_id, DECORATION_ARRAY_STRIDE)) {
+                    Some(&stride) => stride,
+                    None => self.size_of(element)?,
+                };
+                stride.checked_mul(length)
+            },
+            Type::RuntimeArray { .. } => Some(0),
+            Type::Struct { ref members } => {
+                let mut size = 0u32;
+                for (member, &member_ty) in members.iter().enumerate() {
+                    let offset = self.member_decorations
+                        .get(&(ty_id, member as u32, DECORATION_OFFSET)).cloned().unwrap_or(size);
+                    let member_size = self.member_size(ty_id, member as u32, member_ty)?;
+                    let end = offset.checked_add(member_size).ok_or_else(|| size_overflow(ty_id))?;
+                    size = size.max(end);
+                }
+                Some(size)
+            },
+            Type::Image { .. } | Type::Sampler | Type::SampledImage { .. } |
+            Type::Pointer { .. } => Some(0),
+        };
+        size.ok_or_else(|| size_overflow(ty_id))
+    }
+
+    /// Returns the size of member `member` of struct `struct_ty`, taking its
+    /// matrix stride and majorness into account.
+    fn member_size(&self, struct_ty: u32, member: u32, member_ty: u32) -> VdResult<u32> {
+        if let Type::Matrix { column, count } = *self.ty(member_ty)? {
+            if let Some(&stride) = self.member_decorations
+                    .get(&(struct_ty, member, DECORATION_MATRIX_STRIDE)) {
+                let row_major = self.member_flag_decorations.get(&(struct_ty, member))
+                    .map(|d| d.contains(&DECORATION_ROW_MAJOR)).unwrap_or(false);
+                let rows = match *self.ty(column)? {
+                    Type::Vector { count, .. } => count,
+                    _ => 1,
+                };
+                return stride.checked_mul(if row_major { rows } else { count })
+                    .ok_or_else(|| size_overflow(member_ty));
+            }
+        }
+        self.size_of(member_ty)
+    }
+}
+
+
+/// The descriptor bindings and push constant block size used by one or more
+/// shader stages.
+///
+/// Returned by `reflect_spirv` and `merge_stage_reflections`.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderReflection {
+    sets: BTreeMap<u32, Vec<DescriptorSetLayoutBinding<'static>>>,
+    push_constant_size: u32,
+    push_constant_stages: ShaderStageFlags,
+}
+
+impl ShaderReflection {
+    /// Returns the indices of the descriptor sets used, in ascending order.
+    pub fn set_indices<'a>(&'a self) -> impl Iterator<Item=u32> + 'a {
+        self.sets.keys().cloned()
+    }
+
+    /// Returns the bindings of descriptor set `set`, ordered by binding
+    /// number, or an empty slice if the set is unused.
+    ///
+    /// Runtime-sized (unbounded) descriptor arrays have a descriptor count
+    /// of zero, which must be replaced before creating a layout.
+    pub fn bindings(&self, set: u32) -> &[DescriptorSetLayoutBinding<'static>] {
+        self.sets.get(&set).map(|b| b.as_slice()).unwrap_or(&[])
+    }
+
+    /// Returns the size in bytes of the push constant block, or zero if
+    /// there is none.
+    pub fn push_constant_size(&self) -> u32 {
+        self.push_constant_size
+    }
+
+    /// Returns the stages which use the push constant block.
+    pub fn push_constant_stages(&self) -> ShaderStageFlags {
+        self.push_constant_stages
+    }
+}
+
+
+/// Reflects the descriptor bindings and push constant block size of the
+/// SPIR-V module `code`, attributing each to `stage`.
+///
+/// Dynamic uniform and storage buffers cannot be distinguished from their
+/// non-dynamic counterparts and are reported as the latter.
+pub fn reflect_spirv(code: &[u32], stage: ShaderStageFlags) -> VdResult<ShaderReflection> {
+    let module = Module::parse(code)?;
+    let mut reflection = ShaderReflection::default();
+
+    for &(var_ty, var_id, storage_class) in module.variables.iter() {
+        let pointee = match *module.ty(var_ty)? {
+            Type::Pointer { pointee } => pointee,
+            _ => return Err(format!("reflect_spirv: variable type is not a pointer (id {})",
+                var_id).into()),
+        };
+
+        if storage_class == STORAGE_CLASS_PUSH_CONSTANT {
+            reflection.push_constant_size = reflection.push_constant_size
+                .max(module.size_of(pointee)?);
+            reflection.push_constant_stages = stage;
+            continue;
+        }
+
+        let (descriptor_type, count) = match module.descriptor(storage_class, pointee)? {
+            Some(d) => d,
+            None => continue,
+        };
+        let set = module.decorations.get(&(var_id, DECORATION_DESCRIPTOR_SET)).cloned()
+            .unwrap_or(0);
+        let binding = match module.decorations.get(&(var_id, DECORATION_BINDING)) {
+            Some(&binding) => binding,
+            None => return Err(format!("reflect_spirv: descriptor variable (id {}) has no \
+                binding decoration", var_id).into()),
+        };
+        reflection.sets.entry(set).or_insert_with(Vec::new).push(
+            DescriptorSetLayoutBinding::builder()
+                .binding(binding)
+                .descriptor_type(descriptor_type)
+                .descriptor_count(count)
+                .stage_flags(stage)
+                .build());
+    }
+
+    for bindings in reflection.sets.values_mut() {
+        bindings.sort_by_key(|b| b.binding());
+    }
+    Ok(reflection)
+}
+
+
+/// Combines the reflections of several shader stages, such as a vertex and
+/// a fragment shader, into one describing the pipeline layout they share.
+///
+/// The stage flags of bindings used by more than one stage are combined.
+/// Returns an error if two stages declare the same binding with a differing
+/// descriptor type or count.
+pub fn merge_stage_reflections(reflections: &[ShaderReflection]) -> VdResult<ShaderReflection> {
+    let mut merged = ShaderReflection::default();
+    for reflection in reflections {
+        for (&set, bindings) in reflection.sets.iter() {
+            let merged_bindings = merged.sets.entry(set).or_insert_with(Vec::new);
+            for binding in bindings {
+                match merged_bindings.iter_mut().find(|b| b.binding() == binding.binding()) {
+                    Some(existing) => {
+                        if existing.descriptor_type() != binding.descriptor_type() ||
+                                existing.descriptor_count() != binding.descriptor_count() {
+                            return Err(format!("merge_stage_reflections: set {}, binding {} \
+                                is declared as {} x {:?} and as {} x {:?}", set,
+                                binding.binding(), existing.descriptor_count(),
+                                existing.descriptor_type(), binding.descriptor_count(),
+                                binding.descriptor_type()).into());
+                        }
+                        let stage_flags = existing.stage_flags() | binding.stage_flags();
+                        existing.set_stage_flags(stage_flags);
+                    },
+                    None => merged_bindings.push(binding.clone()),
+                }
+            }
+            merged_bindings.sort_by_key(|b| b.binding());
+        }
+        merged.push_constant_size = merged.push_constant_size.max(reflection.push_constant_size);
+        merged.push_constant_stages |= reflection.push_constant_stages;
+    }
+    Ok(merged)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::ShaderStageFlags;
+    use super::*;
+
+    /// Returns a module consisting of a header followed by `instructions`,
+    /// each given as an opcode and its operands.
+    fn module(instructions: &[(u32, &[u32])]) -> Vec<u32> {
+        let mut words = vec![MAGIC, 0x00010000, 0, 100, 0];
+        for &(opcode, ops) in instructions {
+            words.push(((ops.len() as u32 + 1) << 16) | opcode);
+            words.extend_from_slice(ops);
+        }
+        words
+    }
+
+    /// Returns a module whose push constant block is a struct with the
+    /// single member type `member_ty` (id 10) at `offset`, preceded by
+    /// `types`.
+    fn push_constant_module(types: &[(u32, &[u32])], offset: u32) -> Vec<u32> {
+        let mut instructions = types.to_vec();
+        let member_offset = [20, 0, DECORATION_OFFSET, offset];
+        instructions.extend_from_slice(&[
+            (OP_MEMBER_DECORATE, &member_offset[..]),
+            (OP_TYPE_STRUCT, &[20, 10]),
+            (OP_TYPE_POINTER, &[21, STORAGE_CLASS_PUSH_CONSTANT, 20]),
+            (OP_VARIABLE, &[21, 22, STORAGE_CLASS_PUSH_CONSTANT]),
+        ]);
+        module(&instructions)
+    }
+
+    fn assert_overflows(code: &[u32]) {
+        match reflect_spirv(code, ShaderStageFlags::VERTEX) {
+            Ok(reflection) => panic!("size did not overflow: {}", reflection.push_constant_size()),
+            Err(err) => assert!(err.to_string().contains("overflows"), "{}", err),
+        }
+    }
+
+    #[test]
+    fn push_constant_size() {
+        let code = push_constant_module(&[
+            (OP_TYPE_FLOAT, &[1, 32]),
+            (OP_TYPE_VECTOR, &[10, 1, 4]),
+        ], 16);
+        let reflection = reflect_spirv(&code, ShaderStageFlags::VERTEX).unwrap();
+        assert_eq!(reflection.push_constant_size(), 32);
+        assert_eq!(reflection.push_constant_stages(), ShaderStageFlags::VERTEX);
+    }
+
+    #[test]
+    fn array_size_overflow() {
+        assert_overflows(&push_constant_module(&[
+            (OP_DECORATE, &[10, DECORATION_ARRAY_STRIDE, 16]),
+            (OP_TYPE_FLOAT, &[1, 32]),
+            (OP_TYPE_INT, &[2, 32, 0]),
+            (OP_CONSTANT, &[2, 3, 0x4000_0000]),
+            (OP_TYPE_ARRAY, &[10, 1, 3]),
+        ], 0));
+    }
+
+    #[test]
+    fn vector_size_overflow() {
+        assert_overflows(&push_constant_module(&[
+            (OP_TYPE_FLOAT, &[1, 64]),
+            (OP_TYPE_VECTOR, &[10, 1, 0x2000_0000]),
+        ], 0));
+    }
+
+    #[test]
+    fn member_offset_overflow() {
+        assert_overflows(&push_constant_module(&[
+            (OP_TYPE_FLOAT, &[1, 32]),
+            (OP_TYPE_VECTOR, &[10, 1, 4]),
+        ], u32::max_value() - 8));
+    }
+
+    #[test]
+    fn matrix_stride_overflow() {
+        let matrix_stride = [20, 0, DECORATION_MATRIX_STRIDE, 0x8000_0000];
+        assert_overflows(&push_constant_module(&[
+            (OP_MEMBER_DECORATE, &matrix_stride[..]),
+            (OP_TYPE_FLOAT, &[1, 32]),
+            (OP_TYPE_VECTOR, &[2, 1, 4]),
+            (OP_TYPE_MATRIX, &[10, 2, 4]),
+        ], 0));
+    }
+
+    #[test]
+    fn bad_magic() {
+        let mut code = module(&[]);
+        code[0] = 0;
+        assert!(reflect_spirv(&code, ShaderStageFlags::VERTEX).is_err());
+    }
+}
@@ -1,7 +1,12 @@
 use std::sync::Arc;
 use std::marker::PhantomData;
+use libc::c_void;
 use vks;
-use ::{VdResult, Device,  Handle};
+use smallvec::SmallVec;
+use ::{VdResult, Device,  Handle, RenderPassMultiviewCreateInfoKhr, names, AttachmentDescription,
+    AttachmentReference, SubpassDescription, SubpassDependency, SubpassDependencyBuilder,
+    SubpassDescriptionFlags, PipelineBindPoint, ImageLayout, RenderPassCreateInfo, PipelineStageFlags,
+    AccessFlags, DependencyFlags, ATTACHMENT_UNUSED, SUBPASS_EXTERNAL};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -9,6 +14,12 @@ use ::{VdResult, Device,  Handle};
 pub struct RenderPassHandle(pub(crate) vks::VkRenderPass);
 
 impl RenderPassHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkRenderPass) -> RenderPassHandle {
+        RenderPassHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkRenderPass {
         self.0
@@ -84,10 +95,270 @@ unsafe impl<'h> Handle for &'h RenderPass {
 }
 
 
+/// The index of an attachment added with `RenderPassBuilder::attachment`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct AttachmentIndex(u32);
+
+impl AttachmentIndex {
+    /// An attachment index which refers to no attachment
+    /// (`ATTACHMENT_UNUSED`).
+    pub const UNUSED: AttachmentIndex = AttachmentIndex(ATTACHMENT_UNUSED);
+
+    /// Returns the raw attachment index.
+    pub fn index(&self) -> u32 {
+        self.0
+    }
+}
+
+impl From<AttachmentIndex> for u32 {
+    fn from(index: AttachmentIndex) -> u32 {
+        index.0
+    }
+}
+
+
+/// The index of a subpass added with `RenderPassBuilder::subpass`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SubpassIndex(u32);
+
+impl SubpassIndex {
+    /// Refers to commands before or after the render pass in a dependency
+    /// (`SUBPASS_EXTERNAL`).
+    pub const EXTERNAL: SubpassIndex = SubpassIndex(SUBPASS_EXTERNAL);
+
+    /// Returns the raw subpass index.
+    pub fn index(&self) -> u32 {
+        self.0
+    }
+}
+
+impl From<SubpassIndex> for u32 {
+    fn from(index: SubpassIndex) -> u32 {
+        index.0
+    }
+}
+
+
+/// Presets for common subpass dependencies.
+impl SubpassDependency {
+    /// Returns a dependency which makes color attachment writes in
+    /// `dst_subpass` wait for commands submitted before the render pass.
+    ///
+    /// A swapchain image is transitioned out of its initial layout at the
+    /// start of the render pass, but the presentation engine may still be
+    /// reading from it until the image-available semaphore is signaled. A
+    /// semaphore wait at `COLOR_ATTACHMENT_OUTPUT` only blocks that stage,
+    /// so the layout transition (and the attachment's first writes) must
+    /// also be ordered after `COLOR_ATTACHMENT_OUTPUT` of earlier commands.
+    /// No source access mask is needed since the semaphore already makes
+    /// prior writes available; the destination mask covers the read and
+    /// write of the clear or load operation.
+    pub fn external_color_to(dst_subpass: SubpassIndex) -> SubpassDependency {
+        SubpassDependency::builder()
+            .src_subpass(SUBPASS_EXTERNAL)
+            .dst_subpass(dst_subpass.0)
+            .src_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .dst_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(AccessFlags::empty())
+            .dst_access_mask(AccessFlags::COLOR_ATTACHMENT_READ |
+                AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .build()
+    }
+
+    /// Returns a dependency which makes depth/stencil attachment accesses in
+    /// `dst_subpass` wait for depth/stencil writes made before the render
+    /// pass.
+    ///
+    /// When a single depth buffer is shared by frames in flight, the clear
+    /// at the start of one frame must not begin until depth writes of the
+    /// previous frame have finished. Depth tests read and write the
+    /// attachment during both the early and late fragment test stages, so
+    /// both stages are included on either side.
+    pub fn external_depth_to(dst_subpass: SubpassIndex) -> SubpassDependency {
+        let stages = PipelineStageFlags::EARLY_FRAGMENT_TESTS |
+            PipelineStageFlags::LATE_FRAGMENT_TESTS;
+        SubpassDependency::builder()
+            .src_subpass(SUBPASS_EXTERNAL)
+            .dst_subpass(dst_subpass.0)
+            .src_stage_mask(stages)
+            .dst_stage_mask(stages)
+            .src_access_mask(AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+            .dst_access_mask(AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ |
+                AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+            .build()
+    }
+
+    /// Returns a builder for a custom dependency from `src_subpass` to
+    /// `dst_subpass`, either of which may be `SubpassIndex::EXTERNAL`.
+    ///
+    /// `RenderPassBuilder::build` rejects dependencies with an external
+    /// source subpass which use `DependencyFlags::BY_REGION`.
+    pub fn between(src_subpass: SubpassIndex, dst_subpass: SubpassIndex)
+            -> SubpassDependencyBuilder {
+        SubpassDependency::builder()
+            .src_subpass(src_subpass.0)
+            .dst_subpass(dst_subpass.0)
+    }
+}
+
+
+fn attachment_reference(attachment: AttachmentIndex, layout: ImageLayout) -> AttachmentReference {
+    AttachmentReference::builder()
+        .attachment(attachment.0)
+        .layout(layout)
+        .build()
+}
+
+
+/// A builder for a single subpass, referring to attachments by the indices
+/// returned from `RenderPassBuilder::attachment`.
+///
+/// Add to a render pass with `RenderPassBuilder::subpass`.
+#[derive(Debug, Clone)]
+pub struct SubpassBuilder {
+    flags: SubpassDescriptionFlags,
+    pipeline_bind_point: PipelineBindPoint,
+    input_attachments: SmallVec<[AttachmentReference; 4]>,
+    color_attachments: SmallVec<[AttachmentReference; 4]>,
+    resolve_attachments: SmallVec<[AttachmentReference; 4]>,
+    depth_stencil_attachment: Option<AttachmentReference>,
+    preserve_attachments: SmallVec<[u32; 4]>,
+}
+
+impl SubpassBuilder {
+    /// Returns a new subpass builder for a graphics subpass.
+    pub fn new() -> SubpassBuilder {
+        SubpassBuilder {
+            flags: SubpassDescriptionFlags::empty(),
+            pipeline_bind_point: PipelineBindPoint::Graphics,
+            input_attachments: SmallVec::new(),
+            color_attachments: SmallVec::new(),
+            resolve_attachments: SmallVec::new(),
+            depth_stencil_attachment: None,
+            preserve_attachments: SmallVec::new(),
+        }
+    }
+
+    /// Specifies subpass usage flags.
+    pub fn flags<'s>(&'s mut self, flags: SubpassDescriptionFlags) -> &'s mut SubpassBuilder {
+        self.flags = flags;
+        self
+    }
+
+    /// Specifies the pipeline type supported by this subpass. Defaults to
+    /// `PipelineBindPoint::Graphics`.
+    pub fn pipeline_bind_point<'s>(&'s mut self, pipeline_bind_point: PipelineBindPoint)
+            -> &'s mut SubpassBuilder {
+        self.pipeline_bind_point = pipeline_bind_point;
+        self
+    }
+
+    /// Adds an input attachment, read by fragment shaders, used with
+    /// `layout` during this subpass.
+    pub fn input_attachment<'s>(&'s mut self, attachment: AttachmentIndex, layout: ImageLayout)
+            -> &'s mut SubpassBuilder {
+        self.input_attachments.push(attachment_reference(attachment, layout));
+        self
+    }
+
+    /// Adds a color attachment, used with `layout` during this subpass.
+    ///
+    /// Color attachments correspond, in order, to fragment shader output
+    /// locations.
+    pub fn color_attachment<'s>(&'s mut self, attachment: AttachmentIndex, layout: ImageLayout)
+            -> &'s mut SubpassBuilder {
+        self.color_attachments.push(attachment_reference(attachment, layout));
+        self
+    }
+
+    /// Adds a multisample resolve attachment, used with `layout` during this
+    /// subpass.
+    ///
+    /// If any resolve attachments are added, there must be exactly one per
+    /// color attachment (use `AttachmentIndex::UNUSED` for color attachments
+    /// which are not resolved).
+    pub fn resolve_attachment<'s>(&'s mut self, attachment: AttachmentIndex, layout: ImageLayout)
+            -> &'s mut SubpassBuilder {
+        self.resolve_attachments.push(attachment_reference(attachment, layout));
+        self
+    }
+
+    /// Specifies the depth/stencil attachment, used with `layout` during
+    /// this subpass.
+    pub fn depth_stencil_attachment<'s>(&'s mut self, attachment: AttachmentIndex,
+            layout: ImageLayout) -> &'s mut SubpassBuilder {
+        self.depth_stencil_attachment = Some(attachment_reference(attachment, layout));
+        self
+    }
+
+    /// Adds an attachment which is not used by this subpass but whose
+    /// contents must be preserved throughout it.
+    pub fn preserve_attachment<'s>(&'s mut self, attachment: AttachmentIndex)
+            -> &'s mut SubpassBuilder {
+        self.preserve_attachments.push(attachment.0);
+        self
+    }
+
+    /// Returns a description of this subpass, valid for as long as this
+    /// builder is borrowed.
+    fn description<'s>(&'s self) -> SubpassDescription<'s> {
+        let mut description = SubpassDescription::default();
+        description.set_flags(self.flags);
+        description.set_pipeline_bind_point(self.pipeline_bind_point);
+        description.set_input_attachments(&self.input_attachments);
+        description.set_color_attachments(&self.color_attachments);
+        if !self.resolve_attachments.is_empty() {
+            description.set_resolve_attachments(&self.resolve_attachments);
+        }
+        if let Some(ref depth_stencil_attachment) = self.depth_stencil_attachment {
+            description.set_depth_stencil_attachment(depth_stencil_attachment);
+        }
+        description.set_preserve_attachments(&self.preserve_attachments);
+        description
+    }
+
+    /// Checks that every attachment referred to exists and that resolve
+    /// attachments, if any, match the color attachments in number.
+    fn validate(&self, subpass: usize, attachment_count: usize) -> VdResult<()> {
+        if !self.resolve_attachments.is_empty() &&
+                self.resolve_attachments.len() != self.color_attachments.len() {
+            return Err(format!("RenderPassBuilder::build: subpass {}: the number of resolve \
+                attachments ({}) must equal the number of color attachments ({})", subpass,
+                self.resolve_attachments.len(), self.color_attachments.len()).into());
+        }
+        let refs = self.input_attachments.iter()
+            .chain(self.color_attachments.iter())
+            .chain(self.resolve_attachments.iter())
+            .chain(self.depth_stencil_attachment.iter())
+            .map(|r| r.attachment())
+            .chain(self.preserve_attachments.iter().cloned());
+        for attachment in refs {
+            if attachment != ATTACHMENT_UNUSED && attachment as usize >= attachment_count {
+                return Err(format!("RenderPassBuilder::build: subpass {}: attachment {} does \
+                    not exist (the render pass has {} attachments)", subpass, attachment,
+                    attachment_count).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+
 /// A builder for `RenderPass`.
+///
+/// Attachments, subpasses, and dependencies may either be specified as
+/// slices of raw structures with `attachments`, `subpasses`, and
+/// `dependencies`, or added one at a time with `attachment`, `subpass`, and
+/// `dependency`, in which case the builder owns them and checks the
+/// references between them when built. Items added one at a time take
+/// precedence.
 #[derive(Debug, Clone)]
 pub struct RenderPassBuilder<'b> {
     create_info: ::RenderPassCreateInfo<'b>,
+    owned_attachments: SmallVec<[AttachmentDescription; 8]>,
+    owned_subpasses: SmallVec<[SubpassBuilder; 4]>,
+    owned_dependencies: SmallVec<[SubpassDependency; 8]>,
+    multiview: bool,
     _p: PhantomData<&'b ()>,
 }
 
@@ -96,6 +367,10 @@ impl<'b> RenderPassBuilder<'b> {
     pub fn new() -> RenderPassBuilder<'b> {
         RenderPassBuilder {
             create_info: ::RenderPassCreateInfo::default(),
+            owned_attachments: SmallVec::new(),
+            owned_subpasses: SmallVec::new(),
+            owned_dependencies: SmallVec::new(),
+            multiview: false,
             _p: PhantomData,
         }
     }
@@ -132,9 +407,83 @@ impl<'b> RenderPassBuilder<'b> {
         self
     }
 
+    /// Adds an attachment and returns its index, for use with
+    /// `SubpassBuilder`.
+    pub fn attachment(&mut self, attachment: AttachmentDescription) -> AttachmentIndex {
+        self.owned_attachments.push(attachment);
+        AttachmentIndex(self.owned_attachments.len() as u32 - 1)
+    }
+
+    /// Adds a subpass and returns its index, for use in dependencies.
+    pub fn subpass(&mut self, subpass: &SubpassBuilder) -> SubpassIndex {
+        self.owned_subpasses.push(subpass.clone());
+        SubpassIndex(self.owned_subpasses.len() as u32 - 1)
+    }
+
+    /// Adds a dependency between two subpasses (by the indices returned
+    /// from `subpass`), or between a subpass and commands outside of the
+    /// render pass.
+    ///
+    /// See `SubpassDependency::external_color_to`,
+    /// `SubpassDependency::external_depth_to`, and
+    /// `SubpassDependency::between`.
+    pub fn dependency<'s>(&'s mut self, dependency: SubpassDependency)
+            -> &'s mut RenderPassBuilder<'b> {
+        self.owned_dependencies.push(dependency);
+        self
+    }
+
+    /// Specifies the view masks, view offsets, and correlation masks used
+    /// for multiview rendering.
+    ///
+    /// Requires the `VK_KHR_multiview` device extension to be enabled;
+    /// `build` returns an error otherwise. Dependencies between views of
+    /// different subpasses should use `DependencyFlags::VIEW_LOCAL` where
+    /// possible.
+    pub fn multiview<'s, 'p>(&'s mut self,
+            multiview: &'p mut RenderPassMultiviewCreateInfoKhr<'p>)
+            -> &'s mut RenderPassBuilder<'b>
+            where 'p: 'b {
+        unsafe {
+            multiview.set_next(self.create_info.next());
+            self.create_info.set_next(multiview.as_raw() as *const _ as *const c_void);
+        }
+        self.multiview = true;
+        self
+    }
+
     /// Builds and returns a new `RenderPass`
     pub fn build(&self, device: Device) -> VdResult<RenderPass> {
-        let handle = unsafe { device.create_render_pass(&self.create_info, None)? };
+        if self.multiview && !device.is_extension_enabled(names::KHR_MULTIVIEW) {
+            return Err("RenderPassBuilder::build: multiview rendering requires the \
+                'VK_KHR_multiview' device extension to be enabled".into());
+        }
+        for (idx, subpass) in self.owned_subpasses.iter().enumerate() {
+            subpass.validate(idx, self.attachment_count())?;
+        }
+        self.validate_dependencies()?;
+
+        let subpasses: SmallVec<[SubpassDescription; 4]> = self.owned_subpasses.iter()
+            .map(|sp| sp.description()).collect();
+        let attachments = match self.owned_attachments.is_empty() {
+            true => self.create_info.attachments(),
+            false => &self.owned_attachments[..],
+        };
+        let dependencies = match self.owned_dependencies.is_empty() {
+            true => self.create_info.dependencies(),
+            false => &self.owned_dependencies[..],
+        };
+        let mut create_info = RenderPassCreateInfo::default();
+        unsafe { create_info.set_next(self.create_info.next()); }
+        create_info.set_flags(self.create_info.flags());
+        create_info.set_attachments(attachments);
+        create_info.set_subpasses(match subpasses.is_empty() {
+            true => self.create_info.subpasses(),
+            false => &subpasses[..],
+        });
+        create_info.set_dependencies(dependencies);
+
+        let handle = unsafe { device.create_render_pass(&create_info, None)? };
 
         Ok(RenderPass {
             inner: Arc::new(Inner {
@@ -143,4 +492,147 @@ impl<'b> RenderPassBuilder<'b> {
             })
         })
     }
-}
\ No newline at end of file
+
+    /// Returns the number of attachments which will be used by `build`.
+    fn attachment_count(&self) -> usize {
+        if self.owned_attachments.is_empty() {
+            self.create_info.attachments().len()
+        } else {
+            self.owned_attachments.len()
+        }
+    }
+
+    /// Checks that dependencies refer to existing subpasses, that no
+    /// dependency is from a later subpass to an earlier one, and that
+    /// dependencies from outside the render pass are not by region.
+    fn validate_dependencies(&self) -> VdResult<()> {
+        let subpass_count = if self.owned_subpasses.is_empty() {
+            self.create_info.subpasses().len()
+        } else {
+            self.owned_subpasses.len()
+        };
+        let dependencies = if self.owned_dependencies.is_empty() {
+            self.create_info.dependencies()
+        } else {
+            &self.owned_dependencies[..]
+        };
+        for (idx, dependency) in dependencies.iter().enumerate() {
+            let (src, dst) = (dependency.src_subpass(), dependency.dst_subpass());
+            for &subpass in &[src, dst] {
+                if subpass != SUBPASS_EXTERNAL && subpass as usize >= subpass_count {
+                    return Err(format!("RenderPassBuilder::build: dependency {}: subpass {} \
+                        does not exist (the render pass has {} subpasses)", idx, subpass,
+                        subpass_count).into());
+                }
+            }
+            if src == SUBPASS_EXTERNAL && dst == SUBPASS_EXTERNAL {
+                return Err(format!("RenderPassBuilder::build: dependency {}: source and \
+                    destination subpasses must not both be external", idx).into());
+            }
+            if src != SUBPASS_EXTERNAL && dst != SUBPASS_EXTERNAL && src > dst {
+                return Err(format!("RenderPassBuilder::build: dependency {}: the source \
+                    subpass ({}) must not be later than the destination subpass ({})",
+                    idx, src, dst).into());
+            }
+            if src == SUBPASS_EXTERNAL &&
+                    dependency.dependency_flags().contains(DependencyFlags::BY_REGION) {
+                return Err(format!("RenderPassBuilder::build: dependency {}: a dependency \
+                    with an external source subpass must not use `DependencyFlags::BY_REGION`",
+                    idx).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use loader::null;
+    use ::{Device, RenderPass, RenderPassMultiviewCreateInfoKhr, AttachmentDescription,
+        SubpassBuilder, SubpassDependency, Format, SampleCountFlags, AttachmentLoadOp,
+        AttachmentStoreOp, ImageLayout, PipelineStageFlags, AccessFlags, DependencyFlags, names};
+    use super::RenderPassBuilder;
+
+    /// Adds a color attachment and `subpass_count` subpasses drawing to it,
+    /// each depending on the one before.
+    fn add_subpasses(builder: &mut RenderPassBuilder, subpass_count: usize) {
+        let color = builder.attachment(AttachmentDescription::builder()
+            .format(Format::R8G8B8A8Unorm)
+            .samples(SampleCountFlags::COUNT_1)
+            .load_op(AttachmentLoadOp::Clear)
+            .store_op(AttachmentStoreOp::Store)
+            .stencil_load_op(AttachmentLoadOp::DontCare)
+            .stencil_store_op(AttachmentStoreOp::DontCare)
+            .initial_layout(ImageLayout::Undefined)
+            .final_layout(ImageLayout::ColorAttachmentOptimal)
+            .build());
+        let mut prev = None;
+        for _ in 0..subpass_count {
+            let subpass = builder.subpass(SubpassBuilder::new()
+                .color_attachment(color, ImageLayout::ColorAttachmentOptimal));
+            if let Some(prev) = prev {
+                builder.dependency(SubpassDependency::between(prev, subpass)
+                    .src_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+                    .dst_stage_mask(PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+                    .src_access_mask(AccessFlags::COLOR_ATTACHMENT_WRITE)
+                    .dst_access_mask(AccessFlags::COLOR_ATTACHMENT_WRITE)
+                    .dependency_flags(DependencyFlags::VIEW_LOCAL)
+                    .build());
+            }
+            prev = Some(subpass);
+        }
+    }
+
+    fn multiview_device() -> Device {
+        Device::builder()
+            .enabled_extension_names(&[names::KHR_MULTIVIEW][..])
+            .build(null::physical_device())
+            .unwrap()
+    }
+
+    #[test]
+    fn multiview_masks_are_chained_onto_creation() {
+        let view_masks = [0b0011, 0b1100];
+        let view_offsets = [2];
+        let correlation_masks = [0b1111];
+        let mut multiview = RenderPassMultiviewCreateInfoKhr::builder()
+            .view_masks(&view_masks)
+            .view_offsets(&view_offsets)
+            .correlation_masks(&correlation_masks)
+            .build();
+        let mut builder = RenderPass::builder();
+        add_subpasses(&mut builder, 2);
+        builder.multiview(&mut multiview);
+        let render_pass = builder.build(multiview_device()).unwrap();
+        assert_eq!(render_pass.handle().to_raw(), null::CREATED_RENDER_PASS);
+
+        let recorded = null::take_render_pass_multiviews();
+        assert_eq!(recorded, [Some(null::RenderPassMultiview {
+            view_masks: view_masks.to_vec(),
+            view_offsets: view_offsets.to_vec(),
+            correlation_masks: correlation_masks.to_vec(),
+        })]);
+    }
+
+    #[test]
+    fn render_passes_without_multiview_chain_nothing() {
+        let mut builder = RenderPass::builder();
+        add_subpasses(&mut builder, 1);
+        builder.build(multiview_device()).unwrap();
+        assert_eq!(null::take_render_pass_multiviews(), [None]);
+    }
+
+    #[test]
+    fn multiview_requires_the_extension() {
+        let view_masks = [0b11];
+        let mut multiview = RenderPassMultiviewCreateInfoKhr::builder()
+            .view_masks(&view_masks)
+            .build();
+        let mut builder = RenderPass::builder();
+        add_subpasses(&mut builder, 1);
+        builder.multiview(&mut multiview);
+        assert!(builder.build(null::device()).is_err());
+        assert!(null::take_render_pass_multiviews().is_empty());
+    }
+}
@@ -9,6 +9,12 @@ use ::{VdResult, Device, Handle};
 pub struct SamplerHandle(pub(crate) vks::VkSampler);
 
 impl SamplerHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkSampler) -> SamplerHandle {
+        SamplerHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkSampler {
         self.0
@@ -253,8 +259,16 @@ impl<'b> SamplerBuilder<'b> {
         self
     }
 
-    /// Creates and returns a new `Sampler`
+    /// Creates and returns a new `Sampler`.
+    ///
+    /// Returns a `FeatureNotEnabled` error if anisotropic filtering is
+    /// enabled but the device was not created with the `samplerAnisotropy`
+    /// feature.
     pub fn build(&self, device: Device) -> VdResult<Sampler> {
+        if self.create_info.anisotropy_enable() {
+            device.require_feature(|f| f.sampler_anisotropy(), "samplerAnisotropy",
+                "SamplerBuilder::build")?;
+        }
         let handle = unsafe { device.create_sampler(&self.create_info, None)? };
 
         Ok(Sampler {
@@ -8,6 +8,12 @@ use ::{VdResult, Device, Handle, SemaphoreCreateFlags, SemaphoreCreateInfo};
 pub struct SemaphoreHandle(pub(crate) vks::VkSemaphore);
 
 impl SemaphoreHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkSemaphore) -> SemaphoreHandle {
+        SemaphoreHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkSemaphore {
         self.0
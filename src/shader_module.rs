@@ -1,6 +1,14 @@
 use std::sync::Arc;
 use vks;
 use ::{VdResult, Device, Handle, ShaderModuleCreateInfo};
+#[cfg(feature = "shaderc")]
+use std::collections::HashMap;
+#[cfg(feature = "shaderc")]
+use std::cell::RefCell;
+#[cfg(feature = "shaderc")]
+use shaderc::{self, ShaderKind};
+#[cfg(feature = "shaderc")]
+use ::{Error, ErrorKind};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -8,6 +16,12 @@ use ::{VdResult, Device, Handle, ShaderModuleCreateInfo};
 pub struct ShaderModuleHandle(pub(crate) vks::VkShaderModule);
 
 impl ShaderModuleHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkShaderModule) -> ShaderModuleHandle {
+        ShaderModuleHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkShaderModule {
         self.0
@@ -31,6 +45,64 @@ struct Inner {
     device: Device,
 }
 
+/// Options used when compiling GLSL with `ShaderModule::from_glsl`.
+///
+/// Requires the `shaderc` feature.
+#[cfg(feature = "shaderc")]
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct GlslOptions {
+    /// Preprocessor macro definitions as `(name, value)` pairs.
+    pub macro_definitions: Vec<(String, Option<String>)>,
+    /// Optimize for performance.
+    pub optimize: bool,
+    /// Emit debug information.
+    pub generate_debug_info: bool,
+}
+
+
+/// The arguments to `ShaderModule::from_glsl` which determine the compiled
+/// code: source, kind, entry point, and options.
+#[cfg(feature = "shaderc")]
+type GlslCacheKey = (String, u32, String, GlslOptions);
+
+#[cfg(feature = "shaderc")]
+thread_local! {
+    static GLSL_CACHE: RefCell<HashMap<GlslCacheKey, Vec<u32>>> = RefCell::new(HashMap::new());
+}
+
+#[cfg(feature = "shaderc")]
+fn compile_glsl(source: &str, kind: ShaderKind, entry_point: &str, options: &GlslOptions)
+        -> VdResult<Vec<u32>> {
+    let mut compiler = shaderc::Compiler::new()
+        .ok_or("unable to initialize the shaderc compiler")?;
+    let mut compile_options = shaderc::CompileOptions::new()
+        .ok_or("unable to initialize shaderc compile options")?;
+    for &(ref name, ref value) in options.macro_definitions.iter() {
+        compile_options.add_macro_definition(name, value.as_ref().map(|v| v.as_str()));
+    }
+    if options.optimize {
+        compile_options.set_optimization_level(shaderc::OptimizationLevel::Performance);
+    }
+    if options.generate_debug_info {
+        compile_options.set_generate_debug_info();
+    }
+
+    match compiler.compile_into_spirv(source, kind, "<glsl>", entry_point,
+            Some(&compile_options)) {
+        Ok(artifact) => {
+            if artifact.get_num_warnings() > 0 {
+                warn!("GLSL compilation warnings: {}", artifact.get_warning_messages());
+            }
+            Ok(artifact.as_binary().to_vec())
+        },
+        Err(shaderc::Error::CompilationError(error_count, message)) => {
+            Err(Error { kind: ErrorKind::ShaderCompilation { error_count, message }, cause: None })
+        },
+        Err(err) => Err(format!("shaderc: {:?}", err).into()),
+    }
+}
+
+
 // The following object types are consumed when they are passed into a Vulkan
 // command and not further accessed by the objects they are used to create.
 // They must not be destroyed in the duration of any API command they are
@@ -61,6 +133,32 @@ impl ShaderModule {
         })
     }
 
+    /// Compiles GLSL `source` to SPIR-V and returns a new `ShaderModule`
+    /// containing the result.
+    ///
+    /// Compiled code is cached per thread, keyed on `source`, `kind`,
+    /// `entry_point`, and `options`, so repeated calls with the same
+    /// arguments do not recompile.
+    ///
+    /// Compilation errors are returned as an `ErrorKind::ShaderCompilation`
+    /// with shaderc's message, which includes line numbers.
+    ///
+    /// Requires the `shaderc` feature.
+    #[cfg(feature = "shaderc")]
+    pub fn from_glsl(device: Device, source: &str, kind: ShaderKind, entry_point: &str,
+            options: &GlslOptions) -> VdResult<ShaderModule> {
+        let key = (source.to_owned(), kind as u32, entry_point.to_owned(), options.clone());
+
+        GLSL_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if !cache.contains_key(&key) {
+                let code = compile_glsl(source, kind, entry_point, options)?;
+                cache.insert(key.clone(), code);
+            }
+            ShaderModule::new(device, &cache[&key])
+        })
+    }
+
     /// Returns this object's handle.
     pub fn handle(&self) -> ShaderModuleHandle {
         self.inner.handle
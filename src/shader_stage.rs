@@ -0,0 +1,145 @@
+use std::ffi::CString;
+use ::{VdResult, Handle, ShaderModuleHandle, ShaderStageFlags, PipelineShaderStageCreateFlags,
+    PipelineShaderStageCreateInfo, SpecializationInfo, SpecializationConstants};
+
+
+/// A single shader stage of a pipeline, owning its entry point name.
+///
+/// Use `create_info` to obtain a `PipelineShaderStageCreateInfo` borrowing
+/// from this stage, or pass a slice of stages directly to
+/// `GraphicsPipelineBuilder::shader_stages`.
+#[derive(Debug, Clone)]
+pub struct ShaderStage<'b> {
+    flags: PipelineShaderStageCreateFlags,
+    stage: ShaderStageFlags,
+    module: ShaderModuleHandle,
+    entry_point: CString,
+    specialization_info: Option<SpecializationInfo<'b>>,
+}
+
+impl<'b> ShaderStage<'b> {
+    /// Returns a new `ShaderStageBuilder`.
+    pub fn builder() -> ShaderStageBuilder<'b> {
+        ShaderStageBuilder::new()
+    }
+
+    /// Returns the stage this shader is used for.
+    pub fn stage(&self) -> ShaderStageFlags {
+        self.stage
+    }
+
+    /// Returns the shader module handle.
+    pub fn module(&self) -> ShaderModuleHandle {
+        self.module
+    }
+
+    /// Returns the name of the entry point.
+    pub fn entry_point(&self) -> &str {
+        self.entry_point.to_str().expect("ShaderStage::entry_point: invalid entry point")
+    }
+
+    /// Returns a create info structure describing this stage, valid for as
+    /// long as this stage is borrowed.
+    pub fn create_info<'s>(&'s self) -> PipelineShaderStageCreateInfo<'s> {
+        let builder = PipelineShaderStageCreateInfo::builder()
+            .flags(self.flags)
+            .stage(self.stage)
+            .module(self.module)
+            .name(&self.entry_point);
+        match self.specialization_info {
+            Some(ref info) => builder.specialization_info(info).build(),
+            None => builder.build(),
+        }
+    }
+}
+
+
+/// A builder for `ShaderStage`.
+#[derive(Debug, Clone)]
+pub struct ShaderStageBuilder<'b> {
+    flags: PipelineShaderStageCreateFlags,
+    stage: Option<ShaderStageFlags>,
+    module: Option<ShaderModuleHandle>,
+    entry_point: String,
+    specialization_info: Option<SpecializationInfo<'b>>,
+}
+
+impl<'b> ShaderStageBuilder<'b> {
+    /// Returns a new shader stage builder with an entry point of "main".
+    pub fn new() -> ShaderStageBuilder<'b> {
+        ShaderStageBuilder {
+            flags: PipelineShaderStageCreateFlags::empty(),
+            stage: None,
+            module: None,
+            entry_point: String::from("main"),
+            specialization_info: None,
+        }
+    }
+
+    /// Specifies stage creation flags (reserved for future use).
+    pub fn flags<'s>(&'s mut self, flags: PipelineShaderStageCreateFlags)
+            -> &'s mut ShaderStageBuilder<'b> {
+        self.flags = flags;
+        self
+    }
+
+    /// Specifies the pipeline stage this shader is used for. Exactly one
+    /// stage flag must be set.
+    pub fn stage<'s>(&'s mut self, stage: ShaderStageFlags)
+            -> &'s mut ShaderStageBuilder<'b> {
+        self.stage = Some(stage);
+        self
+    }
+
+    /// Specifies the shader module containing the shader code for this
+    /// stage.
+    pub fn module<'s, H>(&'s mut self, module: H) -> &'s mut ShaderStageBuilder<'b>
+            where H: Handle<Target=ShaderModuleHandle> {
+        self.module = Some(module.handle());
+        self
+    }
+
+    /// Specifies the name of the entry point for this stage. Defaults to
+    /// "main".
+    pub fn entry_point<'s>(&'s mut self, entry_point: &str) -> &'s mut ShaderStageBuilder<'b> {
+        self.entry_point = entry_point.to_owned();
+        self
+    }
+
+    /// Specifies the values of specialization constants used by this stage.
+    pub fn specialization_info<'s, 'p>(&'s mut self,
+            specialization_info: &'p SpecializationInfo<'p>)
+            -> &'s mut ShaderStageBuilder<'b>
+            where 'p: 'b {
+        self.specialization_info = Some(specialization_info.clone());
+        self
+    }
+
+    /// Specifies the values of specialization constants used by this stage.
+    ///
+    /// An alternative to `specialization_info`.
+    pub fn specialization_constants<'s, 'p>(&'s mut self,
+            specialization_constants: &'p SpecializationConstants)
+            -> &'s mut ShaderStageBuilder<'b>
+            where 'p: 'b {
+        self.specialization_info = Some(specialization_constants.info());
+        self
+    }
+
+    /// Creates and returns a new `ShaderStage`.
+    ///
+    /// Returns an error if the stage or module have not been specified or if
+    /// the entry point contains an interior NUL byte.
+    pub fn build(&self) -> VdResult<ShaderStage<'b>> {
+        let stage = self.stage.ok_or("ShaderStageBuilder::build: no stage specified")?;
+        let module = self.module.ok_or("ShaderStageBuilder::build: no module specified")?;
+        let entry_point = CString::new(self.entry_point.as_str())?;
+        Ok(ShaderStage {
+            flags: self.flags,
+            stage,
+            module,
+            entry_point,
+            specialization_info: self.specialization_info.clone(),
+        })
+    }
+}
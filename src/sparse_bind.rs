@@ -0,0 +1,334 @@
+//! Sparse resource memory binding.
+
+use std::slice;
+use vks;
+use ::{VdResult, Handle, Queue, SemaphoreHandle, FenceHandle, BufferHandle, ImageHandle,
+    BindSparseInfo, SparseMemoryBind, SparseImageMemoryBind};
+
+
+impl SparseMemoryBind {
+    /// Returns a bind which unbinds `size` bytes of a sparse resource
+    /// starting at `resource_offset` (binds them to no memory).
+    pub fn unbind(resource_offset: u64, size: u64) -> SparseMemoryBind {
+        SparseMemoryBind::builder()
+            .resource_offset(resource_offset)
+            .size(size)
+            .build()
+    }
+}
+
+
+/// A resource and the range of its binds within one of the bind arrays of a
+/// `SparseBindBatch`.
+#[derive(Debug, Clone, Copy)]
+struct ResourceBinds<H> {
+    resource: H,
+    start: usize,
+    end: usize,
+}
+
+
+/// Offsets of a batch's first elements within each of the arrays of a
+/// `SparseBindBatch`. A batch extends to the offsets of the next one.
+#[derive(Debug, Clone, Copy)]
+struct BatchStart {
+    wait: usize,
+    buffer: usize,
+    image_opaque: usize,
+    image: usize,
+    signal: usize,
+}
+
+
+/// A reusable builder for sparse binding operations made up of several
+/// batches, each with its own semaphore dependencies.
+///
+/// Call `batch` to start each batch then append to it using `wait`,
+/// `buffer_binds`, `image_opaque_binds`, `image_binds`, and `signal`. `bind`
+/// submits all batches with a single call to `vkQueueBindSparse`.
+///
+/// The binds are copied into arrays owned by the builder, along with the
+/// per-resource bind infos and the `VkBindSparseInfo`s themselves, so that
+/// every pointer passed to the driver remains valid for the duration of the
+/// call. Call `clear` and reuse the same builder to avoid allocating once
+/// those arrays have grown to their working size.
+///
+/// ```text
+/// sparse_bind_batch.batch()
+///         .buffer_binds(&buffer, &[SparseMemoryBind::builder()
+///             .resource_offset(0)
+///             .size(page_size)
+///             .memory(&memory)
+///             .memory_offset(0)
+///             .build()])
+///         .signal(&bound);
+/// sparse_bind_batch.bind(&sparse_queue, None)?;
+/// ```
+#[derive(Debug, Default)]
+pub struct SparseBindBatch {
+    batches: Vec<BatchStart>,
+    wait_semaphores: Vec<vks::VkSemaphore>,
+    signal_semaphores: Vec<vks::VkSemaphore>,
+    memory_binds: Vec<vks::VkSparseMemoryBind>,
+    image_memory_binds: Vec<vks::VkSparseImageMemoryBind>,
+    buffer_binds: Vec<ResourceBinds<BufferHandle>>,
+    image_opaque_binds: Vec<ResourceBinds<ImageHandle>>,
+    image_binds: Vec<ResourceBinds<ImageHandle>>,
+    raw_buffer_binds: Vec<vks::VkSparseBufferMemoryBindInfo>,
+    raw_image_opaque_binds: Vec<vks::VkSparseImageOpaqueMemoryBindInfo>,
+    raw_image_binds: Vec<vks::VkSparseImageMemoryBindInfo>,
+    bind_infos: Vec<vks::VkBindSparseInfo>,
+}
+
+impl SparseBindBatch {
+    /// Returns a new, empty `SparseBindBatch`.
+    pub fn new() -> SparseBindBatch {
+        SparseBindBatch::default()
+    }
+
+    /// Starts a new batch. Subsequent calls to `wait`, `buffer_binds`,
+    /// `image_opaque_binds`, `image_binds`, and `signal` append to it.
+    pub fn batch<'s>(&'s mut self) -> &'s mut SparseBindBatch {
+        self.batches.push(BatchStart {
+            wait: self.wait_semaphores.len(),
+            buffer: self.buffer_binds.len(),
+            image_opaque: self.image_opaque_binds.len(),
+            image: self.image_binds.len(),
+            signal: self.signal_semaphores.len(),
+        });
+        self
+    }
+
+    /// Starts a batch if none has been started yet.
+    fn ensure_batch(&mut self) {
+        if self.batches.is_empty() { self.batch(); }
+    }
+
+    /// Adds a semaphore for the current batch to wait on before performing
+    /// its binds.
+    pub fn wait<'s, S>(&'s mut self, semaphore: S) -> &'s mut SparseBindBatch
+            where S: Handle<Target=SemaphoreHandle> {
+        self.ensure_batch();
+        self.wait_semaphores.push(semaphore.handle().to_raw());
+        self
+    }
+
+    /// Adds memory binds for ranges of a sparse buffer to the current batch.
+    pub fn buffer_binds<'s, B>(&'s mut self, buffer: B, binds: &[SparseMemoryBind])
+            -> &'s mut SparseBindBatch
+            where B: Handle<Target=BufferHandle> {
+        self.ensure_batch();
+        let start = self.memory_binds.len();
+        self.memory_binds.extend(binds.iter().map(|b| b.as_raw().clone()));
+        self.buffer_binds.push(ResourceBinds { resource: buffer.handle(), start,
+            end: self.memory_binds.len() });
+        self
+    }
+
+    /// Adds opaque memory binds for ranges of a sparse image (such as its
+    /// mip tail or metadata) to the current batch.
+    pub fn image_opaque_binds<'s, I>(&'s mut self, image: I, binds: &[SparseMemoryBind])
+            -> &'s mut SparseBindBatch
+            where I: Handle<Target=ImageHandle> {
+        self.ensure_batch();
+        let start = self.memory_binds.len();
+        self.memory_binds.extend(binds.iter().map(|b| b.as_raw().clone()));
+        self.image_opaque_binds.push(ResourceBinds { resource: image.handle(), start,
+            end: self.memory_binds.len() });
+        self
+    }
+
+    /// Adds memory binds for regions of a sparse image to the current batch.
+    pub fn image_binds<'s, I>(&'s mut self, image: I, binds: &[SparseImageMemoryBind])
+            -> &'s mut SparseBindBatch
+            where I: Handle<Target=ImageHandle> {
+        self.ensure_batch();
+        let start = self.image_memory_binds.len();
+        self.image_memory_binds.extend(binds.iter().map(|b| b.as_raw().clone()));
+        self.image_binds.push(ResourceBinds { resource: image.handle(), start,
+            end: self.image_memory_binds.len() });
+        self
+    }
+
+    /// Adds a semaphore to be signaled once the binds of the current batch
+    /// have completed.
+    pub fn signal<'s, S>(&'s mut self, semaphore: S) -> &'s mut SparseBindBatch
+            where S: Handle<Target=SemaphoreHandle> {
+        self.ensure_batch();
+        self.signal_semaphores.push(semaphore.handle().to_raw());
+        self
+    }
+
+    /// Returns the number of batches started.
+    pub fn len(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// Returns `true` if no batches have been started.
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// Removes all batches, retaining allocated capacity.
+    pub fn clear(&mut self) {
+        self.batches.clear();
+        self.wait_semaphores.clear();
+        self.signal_semaphores.clear();
+        self.memory_binds.clear();
+        self.image_memory_binds.clear();
+        self.buffer_binds.clear();
+        self.image_opaque_binds.clear();
+        self.image_binds.clear();
+        self.clear_raw();
+    }
+
+    /// Clears the raw arrays, which point into the others and are rebuilt
+    /// on each call to `bind`.
+    fn clear_raw(&mut self) {
+        self.raw_buffer_binds.clear();
+        self.raw_image_opaque_binds.clear();
+        self.raw_image_binds.clear();
+        self.bind_infos.clear();
+    }
+
+    /// Submits all batches to `queue` with a single call to
+    /// `vkQueueBindSparse`, signaling `fence` once all have completed.
+    ///
+    /// Returns a `FeatureNotEnabled` error if the device was not created
+    /// with the `sparseBinding` feature enabled.
+    ///
+    /// The batches are retained and may be submitted again.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkQueueBindSparse.html
+    //
+    pub fn bind(&mut self, queue: &Queue, fence: Option<FenceHandle>) -> VdResult<()> {
+        queue.device().require_feature(|f| f.sparse_binding(), "sparseBinding",
+            "SparseBindBatch::bind")?;
+
+        self.clear_raw();
+        for b in self.buffer_binds.iter() {
+            self.raw_buffer_binds.push(vks::VkSparseBufferMemoryBindInfo {
+                buffer: b.resource.to_raw(),
+                bindCount: (b.end - b.start) as u32,
+                pBinds: self.memory_binds[b.start..].as_ptr(),
+            });
+        }
+        for b in self.image_opaque_binds.iter() {
+            self.raw_image_opaque_binds.push(vks::VkSparseImageOpaqueMemoryBindInfo {
+                image: b.resource.to_raw(),
+                bindCount: (b.end - b.start) as u32,
+                pBinds: self.memory_binds[b.start..].as_ptr(),
+            });
+        }
+        for b in self.image_binds.iter() {
+            self.raw_image_binds.push(vks::VkSparseImageMemoryBindInfo {
+                image: b.resource.to_raw(),
+                bindCount: (b.end - b.start) as u32,
+                pBinds: self.image_memory_binds[b.start..].as_ptr(),
+            });
+        }
+
+        for (idx, start) in self.batches.iter().enumerate() {
+            let end = self.batches.get(idx + 1).cloned().unwrap_or(BatchStart {
+                wait: self.wait_semaphores.len(),
+                buffer: self.buffer_binds.len(),
+                image_opaque: self.image_opaque_binds.len(),
+                image: self.image_binds.len(),
+                signal: self.signal_semaphores.len(),
+            });
+
+            let mut bind_info = vks::VkBindSparseInfo::default();
+            bind_info.waitSemaphoreCount = (end.wait - start.wait) as u32;
+            bind_info.pWaitSemaphores = self.wait_semaphores[start.wait..].as_ptr();
+            bind_info.bufferBindCount = (end.buffer - start.buffer) as u32;
+            bind_info.pBufferBinds = self.raw_buffer_binds[start.buffer..].as_ptr();
+            bind_info.imageOpaqueBindCount = (end.image_opaque - start.image_opaque) as u32;
+            bind_info.pImageOpaqueBinds =
+                self.raw_image_opaque_binds[start.image_opaque..].as_ptr();
+            bind_info.imageBindCount = (end.image - start.image) as u32;
+            bind_info.pImageBinds = self.raw_image_binds[start.image..].as_ptr();
+            bind_info.signalSemaphoreCount = (end.signal - start.signal) as u32;
+            bind_info.pSignalSemaphores = self.signal_semaphores[start.signal..].as_ptr();
+            self.bind_infos.push(bind_info);
+        }
+
+        let result = unsafe {
+            // `BindSparseInfo` is a `repr(C)` wrapper around `VkBindSparseInfo`:
+            let bind_infos = slice::from_raw_parts(
+                self.bind_infos.as_ptr() as *const BindSparseInfo, self.bind_infos.len());
+            queue.device().queue_bind_sparse(queue.handle(), bind_infos, fence)
+        };
+        self.clear_raw();
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use loader::null::{self, SparseBufferBind};
+    use ::{Device, Queue, QueueHandle, BufferHandle, DeviceMemoryHandle, SparseMemoryBind,
+        PhysicalDeviceFeatures, ErrorKind};
+    use super::SparseBindBatch;
+
+    const PAGE_SIZE: u64 = 65536;
+
+    fn queue(device: Device) -> Queue {
+        unsafe { Queue::from_parts(QueueHandle(0x4000 as vks::VkQueue), device, 0, 0) }
+    }
+
+    fn buffer_bind(resource_offset: u64, memory: u64, memory_offset: u64) -> SparseBufferBind {
+        SparseBufferBind { buffer: 1, resource_offset, size: PAGE_SIZE, memory, memory_offset }
+    }
+
+    #[test]
+    fn bind_and_unbind_round_trip() {
+        let features = PhysicalDeviceFeatures::builder().sparse_binding(true).build();
+        let device = Device::builder()
+            .enabled_features(&features)
+            .build(null::physical_device())
+            .unwrap();
+        let queue = queue(device);
+        let memory = DeviceMemoryHandle(0x10);
+        let mut sparse_bind_batch = SparseBindBatch::new();
+        sparse_bind_batch.batch()
+            .buffer_binds(BufferHandle(1), &[
+                SparseMemoryBind::builder()
+                    .resource_offset(0)
+                    .size(PAGE_SIZE)
+                    .memory(memory)
+                    .memory_offset(0)
+                    .build(),
+                SparseMemoryBind::builder()
+                    .resource_offset(PAGE_SIZE)
+                    .size(PAGE_SIZE)
+                    .memory(memory)
+                    .memory_offset(2 * PAGE_SIZE)
+                    .build(),
+            ]);
+        sparse_bind_batch.bind(&queue, None).unwrap();
+        assert_eq!(null::take_sparse_buffer_binds(), [buffer_bind(0, 0x10, 0),
+            buffer_bind(PAGE_SIZE, 0x10, 2 * PAGE_SIZE)]);
+
+        sparse_bind_batch.clear();
+        sparse_bind_batch.batch()
+            .buffer_binds(BufferHandle(1), &[SparseMemoryBind::unbind(0, 2 * PAGE_SIZE)]);
+        sparse_bind_batch.bind(&queue, None).unwrap();
+        assert_eq!(null::take_sparse_buffer_binds(), [SparseBufferBind {
+            buffer: 1, resource_offset: 0, size: 2 * PAGE_SIZE, memory: 0, memory_offset: 0 }]);
+    }
+
+    #[test]
+    fn binding_requires_the_feature() {
+        let queue = queue(null::device());
+        let mut sparse_bind_batch = SparseBindBatch::new();
+        sparse_bind_batch.buffer_binds(BufferHandle(1), &[SparseMemoryBind::unbind(0, PAGE_SIZE)]);
+        let err = sparse_bind_batch.bind(&queue, None).unwrap_err();
+        match *err.kind() {
+            ErrorKind::FeatureNotEnabled { name, .. } => assert_eq!(name, "sparseBinding"),
+            _ => panic!("unexpected error: {}", err),
+        }
+        assert!(null::take_sparse_buffer_binds().is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+use libc::c_void;
+use ::{VdResult, SpecializationInfo, SpecializationMapEntry, TRUE, FALSE};
+
+
+/// A type which may be used as the value of a specialization constant.
+pub trait SpecializationValue: Copy {
+    /// Appends the in-memory representation of this value, as expected by
+    /// the implementation, to `data`.
+    fn append_to(self, data: &mut Vec<u8>);
+}
+
+impl SpecializationValue for u32 {
+    fn append_to(self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl SpecializationValue for i32 {
+    fn append_to(self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl SpecializationValue for f32 {
+    fn append_to(self, data: &mut Vec<u8>) {
+        data.extend_from_slice(&self.to_bits().to_ne_bytes());
+    }
+}
+
+/// Encoded as a `VkBool32`.
+impl SpecializationValue for bool {
+    fn append_to(self, data: &mut Vec<u8>) {
+        (if self { TRUE } else { FALSE }).append_to(data)
+    }
+}
+
+
+/// A set of specialization constant values along with the map entries
+/// describing their layout.
+///
+/// Values are packed in the order they are pushed. Pass to
+/// `ShaderStageBuilder::specialization_constants` or use `info` to obtain a
+/// `SpecializationInfo`.
+#[derive(Debug, Clone, Default)]
+pub struct SpecializationConstants {
+    entries: Vec<SpecializationMapEntry>,
+    data: Vec<u8>,
+}
+
+impl SpecializationConstants {
+    /// Returns a new, empty set of specialization constants.
+    pub fn new() -> SpecializationConstants {
+        SpecializationConstants::default()
+    }
+
+    /// Adds the value of the constant with the given `constant_id`.
+    ///
+    /// Returns an error if a value has already been added for `constant_id`.
+    pub fn push<'s, T>(&'s mut self, constant_id: u32, value: T)
+            -> VdResult<&'s mut SpecializationConstants>
+            where T: SpecializationValue {
+        if self.entries.iter().any(|e| e.constant_id() == constant_id) {
+            return Err(format!("SpecializationConstants::push: a value for constant id {} \
+                has already been specified", constant_id).into());
+        }
+        let offset = self.data.len();
+        value.append_to(&mut self.data);
+        self.entries.push(SpecializationMapEntry::builder()
+            .constant_id(constant_id)
+            .offset(offset as u32)
+            .size(self.data.len() - offset)
+            .build());
+        Ok(self)
+    }
+
+    /// Returns the map entries, one per constant, in the order pushed.
+    pub fn entries(&self) -> &[SpecializationMapEntry] {
+        &self.entries
+    }
+
+    /// Returns the packed constant values.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns true if no constants have been added.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns a `SpecializationInfo` referring to these constants.
+    pub fn info<'s>(&'s self) -> SpecializationInfo<'s> {
+        let mut builder = SpecializationInfo::builder();
+        if !self.entries.is_empty() {
+            builder = builder.map_entries(&self.entries);
+        }
+        unsafe {
+            builder
+                .data_size(self.data.len())
+                .data(self.data.as_ptr() as *const c_void)
+                .build()
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::SpecializationConstants;
+
+    #[test]
+    fn values_are_packed_in_push_order() {
+        let mut constants = SpecializationConstants::new();
+        assert!(constants.is_empty());
+        constants.push(7, 16u32).unwrap()
+            .push(2, -1i32).unwrap()
+            .push(3, 0.5f32).unwrap()
+            .push(0, true).unwrap();
+
+        let layout: Vec<_> = constants.entries().iter()
+            .map(|e| (e.constant_id(), e.offset(), e.size()))
+            .collect();
+        assert_eq!(layout, [(7, 0, 4), (2, 4, 4), (3, 8, 4), (0, 12, 4)]);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&16u32.to_ne_bytes());
+        data.extend_from_slice(&(-1i32).to_ne_bytes());
+        data.extend_from_slice(&0.5f32.to_bits().to_ne_bytes());
+        data.extend_from_slice(&1u32.to_ne_bytes());
+        assert_eq!(constants.data(), &data[..]);
+    }
+
+    #[test]
+    fn duplicate_constant_ids_are_rejected() {
+        let mut constants = SpecializationConstants::new();
+        constants.push(1, 1u32).unwrap();
+        assert!(constants.push(1, 2u32).is_err());
+        assert_eq!(constants.entries().len(), 1);
+        assert_eq!(constants.data().len(), 4);
+    }
+
+    #[test]
+    fn info_refers_to_entries_and_data() {
+        let empty = SpecializationConstants::new();
+        let info = empty.info();
+        assert_eq!(info.as_raw().mapEntryCount, 0);
+        assert_eq!(info.data_size(), 0);
+
+        let mut constants = SpecializationConstants::new();
+        constants.push(0, 3u32).unwrap().push(1, false).unwrap();
+        let info = constants.info();
+        assert_eq!(info.map_entries().len(), 2);
+        assert_eq!(info.map_entries()[1].offset(), 4);
+        assert_eq!(info.data_size(), 8);
+        assert_eq!(info.data() as *const u8, constants.data().as_ptr());
+    }
+}
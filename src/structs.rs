@@ -5940,11 +5940,21 @@ pub struct BufferMemoryBarrierBuilder<'b> {
 }
 
 impl<'b> BufferMemoryBarrierBuilder<'b> {
+    /// Returns a new builder with both queue family indices set to
+    /// `QUEUE_FAMILY_IGNORED` and the range covering the whole buffer
+    /// (offset `0`, size `WHOLE_SIZE`).
     pub fn new() -> BufferMemoryBarrierBuilder<'b> {
+        let mut raw = vks::VkBufferMemoryBarrier::default();
+        raw.srcQueueFamilyIndex = QUEUE_FAMILY_IGNORED;
+        raw.dstQueueFamilyIndex = QUEUE_FAMILY_IGNORED;
+        raw.offset = 0;
+        raw.size = WHOLE_SIZE;
         BufferMemoryBarrierBuilder {
-            raw: vks::VkBufferMemoryBarrier::default(),
+            raw,
             _p: PhantomData,
-            set_mask: BufferMemoryBarrierFlags::default(),
+            set_mask: BufferMemoryBarrierFlags::FLAG_SRCQUEUEFAMILYINDEX |
+                BufferMemoryBarrierFlags::FLAG_DSTQUEUEFAMILYINDEX |
+                BufferMemoryBarrierFlags::FLAG_OFFSET | BufferMemoryBarrierFlags::FLAG_SIZE,
         }
     }
 
@@ -6189,11 +6199,20 @@ pub struct ImageMemoryBarrierBuilder<'b> {
 }
 
 impl<'b> ImageMemoryBarrierBuilder<'b> {
+    /// Returns a new builder with both queue family indices set to
+    /// `QUEUE_FAMILY_IGNORED` and the subresource range set to
+    /// `ImageSubresourceRange::color_full()`.
     pub fn new() -> ImageMemoryBarrierBuilder<'b> {
+        let mut raw = vks::VkImageMemoryBarrier::default();
+        raw.srcQueueFamilyIndex = QUEUE_FAMILY_IGNORED;
+        raw.dstQueueFamilyIndex = QUEUE_FAMILY_IGNORED;
+        raw.subresourceRange = ImageSubresourceRange::color_full().raw;
         ImageMemoryBarrierBuilder {
-            raw: vks::VkImageMemoryBarrier::default(),
+            raw,
             _p: PhantomData,
-            set_mask: ImageMemoryBarrierFlags::default(),
+            set_mask: ImageMemoryBarrierFlags::FLAG_SRCQUEUEFAMILYINDEX |
+                ImageMemoryBarrierFlags::FLAG_DSTQUEUEFAMILYINDEX |
+                ImageMemoryBarrierFlags::FLAG_SUBRESOURCERANGE,
         }
     }
 
@@ -17951,7 +17970,14 @@ impl PhysicalDeviceSparsePropertiesBuilder {
 
 /// A `VkPhysicalDeviceLimits`.
 ///
-/// compute stage limits
+/// Implementation-dependent limits of a physical device, obtained from
+/// `PhysicalDeviceProperties::limits` (or `Device::limits`, which is
+/// cached). Getters are grouped in specification order: image and buffer
+/// sizes, allocation counts, descriptor counts (per stage, then per
+/// pipeline layout), per-shader-stage limits (vertex, tessellation,
+/// geometry, fragment, compute), precision and sampling, viewports,
+/// alignments, framebuffer and sample counts, then miscellaneous
+/// rasterization and copy limits.
 #[derive(Debug, Clone, Default)]
 #[repr(C)]
 pub struct PhysicalDeviceLimits {
@@ -17967,435 +17993,615 @@ impl PhysicalDeviceLimits {
         PhysicalDeviceLimits { raw, }
     }
 
+    /// The largest `width` of an image created with an `ImageType` of
+    /// `Type1d`.
     pub fn max_image_dimension_1d<'a>(&'a self) -> u32 {
         self.raw.maxImageDimension1D.into()
     }
 
+    /// The largest `width` or `height` of an image created with an
+    /// `ImageType` of `Type2d` (without the cube-compatible flag).
     pub fn max_image_dimension_2d<'a>(&'a self) -> u32 {
         self.raw.maxImageDimension2D.into()
     }
 
+    /// The largest `width`, `height`, or `depth` of an image created with an
+    /// `ImageType` of `Type3d`.
     pub fn max_image_dimension_3d<'a>(&'a self) -> u32 {
         self.raw.maxImageDimension3D.into()
     }
 
+    /// The largest `width` or `height` of a cube-compatible image.
     pub fn max_image_dimension_cube<'a>(&'a self) -> u32 {
         self.raw.maxImageDimensionCube.into()
     }
 
+    /// The maximum number of layers (`arrayLayers`) of an image.
     pub fn max_image_array_layers<'a>(&'a self) -> u32 {
         self.raw.maxImageArrayLayers.into()
     }
 
+    /// The maximum number of addressable texels in a buffer view of a texel
+    /// buffer.
     pub fn max_texel_buffer_elements<'a>(&'a self) -> u32 {
         self.raw.maxTexelBufferElements.into()
     }
 
+    /// The maximum `range` of a uniform buffer descriptor.
     pub fn max_uniform_buffer_range<'a>(&'a self) -> u32 {
         self.raw.maxUniformBufferRange.into()
     }
 
+    /// The maximum `range` of a storage buffer descriptor.
     pub fn max_storage_buffer_range<'a>(&'a self) -> u32 {
         self.raw.maxStorageBufferRange.into()
     }
 
+    /// The maximum size, in bytes, of the push constants pool. Push constant
+    /// ranges must end at or below this offset.
     pub fn max_push_constants_size<'a>(&'a self) -> u32 {
         self.raw.maxPushConstantsSize.into()
     }
 
+    /// The maximum number of device memory allocations which can exist
+    /// simultaneously.
     pub fn max_memory_allocation_count<'a>(&'a self) -> u32 {
         self.raw.maxMemoryAllocationCount.into()
     }
 
+    /// The maximum number of sampler objects which can exist simultaneously.
     pub fn max_sampler_allocation_count<'a>(&'a self) -> u32 {
         self.raw.maxSamplerAllocationCount.into()
     }
 
+    /// The granularity, in bytes, at which adjacent linear and non-linear
+    /// resources in the same memory object may be placed without aliasing.
     pub fn buffer_image_granularity<'a>(&'a self) -> u64 {
         self.raw.bufferImageGranularity.into()
     }
 
+    /// The total address space, in bytes, available for sparse memory
+    /// resources.
     pub fn sparse_address_space_size<'a>(&'a self) -> u64 {
         self.raw.sparseAddressSpaceSize.into()
     }
 
+    /// The maximum number of descriptor sets which can be simultaneously used
+    /// by a pipeline.
     pub fn max_bound_descriptor_sets<'a>(&'a self) -> u32 {
         self.raw.maxBoundDescriptorSets.into()
     }
 
+    /// The maximum number of samplers accessible to a single shader stage.
     pub fn max_per_stage_descriptor_samplers<'a>(&'a self) -> u32 {
         self.raw.maxPerStageDescriptorSamplers.into()
     }
 
+    /// The maximum number of uniform buffers accessible to a single shader
+    /// stage.
     pub fn max_per_stage_descriptor_uniform_buffers<'a>(&'a self) -> u32 {
         self.raw.maxPerStageDescriptorUniformBuffers.into()
     }
 
+    /// The maximum number of storage buffers accessible to a single shader
+    /// stage.
     pub fn max_per_stage_descriptor_storage_buffers<'a>(&'a self) -> u32 {
         self.raw.maxPerStageDescriptorStorageBuffers.into()
     }
 
+    /// The maximum number of sampled images accessible to a single shader
+    /// stage.
     pub fn max_per_stage_descriptor_sampled_images<'a>(&'a self) -> u32 {
         self.raw.maxPerStageDescriptorSampledImages.into()
     }
 
+    /// The maximum number of storage images accessible to a single shader
+    /// stage.
     pub fn max_per_stage_descriptor_storage_images<'a>(&'a self) -> u32 {
         self.raw.maxPerStageDescriptorStorageImages.into()
     }
 
+    /// The maximum number of input attachments accessible to a single shader
+    /// stage.
     pub fn max_per_stage_descriptor_input_attachments<'a>(&'a self) -> u32 {
         self.raw.maxPerStageDescriptorInputAttachments.into()
     }
 
+    /// The maximum number of resources (descriptors and color attachments)
+    /// accessible to a single shader stage.
     pub fn max_per_stage_resources<'a>(&'a self) -> u32 {
         self.raw.maxPerStageResources.into()
     }
 
+    /// The maximum number of samplers which can be included in a pipeline
+    /// layout.
     pub fn max_descriptor_set_samplers<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetSamplers.into()
     }
 
+    /// The maximum number of uniform buffers which can be included in a
+    /// pipeline layout.
     pub fn max_descriptor_set_uniform_buffers<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetUniformBuffers.into()
     }
 
+    /// The maximum number of dynamic uniform buffers which can be included in
+    /// a pipeline layout.
     pub fn max_descriptor_set_uniform_buffers_dynamic<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetUniformBuffersDynamic.into()
     }
 
+    /// The maximum number of storage buffers which can be included in a
+    /// pipeline layout.
     pub fn max_descriptor_set_storage_buffers<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetStorageBuffers.into()
     }
 
+    /// The maximum number of dynamic storage buffers which can be included in
+    /// a pipeline layout.
     pub fn max_descriptor_set_storage_buffers_dynamic<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetStorageBuffersDynamic.into()
     }
 
+    /// The maximum number of sampled images which can be included in a
+    /// pipeline layout.
     pub fn max_descriptor_set_sampled_images<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetSampledImages.into()
     }
 
+    /// The maximum number of storage images which can be included in a
+    /// pipeline layout.
     pub fn max_descriptor_set_storage_images<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetStorageImages.into()
     }
 
+    /// The maximum number of input attachments which can be included in a
+    /// pipeline layout.
     pub fn max_descriptor_set_input_attachments<'a>(&'a self) -> u32 {
         self.raw.maxDescriptorSetInputAttachments.into()
     }
 
+    /// The maximum number of vertex input attributes of a graphics pipeline.
     pub fn max_vertex_input_attributes<'a>(&'a self) -> u32 {
         self.raw.maxVertexInputAttributes.into()
     }
 
+    /// The maximum number of vertex buffers which can be bound at once.
     pub fn max_vertex_input_bindings<'a>(&'a self) -> u32 {
         self.raw.maxVertexInputBindings.into()
     }
 
+    /// The maximum offset of a vertex input attribute within its binding.
     pub fn max_vertex_input_attribute_offset<'a>(&'a self) -> u32 {
         self.raw.maxVertexInputAttributeOffset.into()
     }
 
+    /// The maximum stride of a vertex input binding.
     pub fn max_vertex_input_binding_stride<'a>(&'a self) -> u32 {
         self.raw.maxVertexInputBindingStride.into()
     }
 
+    /// The maximum number of output components which can be written by a
+    /// vertex shader.
     pub fn max_vertex_output_components<'a>(&'a self) -> u32 {
         self.raw.maxVertexOutputComponents.into()
     }
 
+    /// The maximum tessellation generation level supported by the fixed-
+    /// function tessellation primitive generator.
     pub fn max_tessellation_generation_level<'a>(&'a self) -> u32 {
         self.raw.maxTessellationGenerationLevel.into()
     }
 
+    /// The maximum patch size, in vertices, processed by the tessellation
+    /// control shader and primitive generator.
     pub fn max_tessellation_patch_size<'a>(&'a self) -> u32 {
         self.raw.maxTessellationPatchSize.into()
     }
 
+    /// The maximum number of per-vertex input components of a tessellation
+    /// control shader.
     pub fn max_tessellation_control_per_vertex_input_components<'a>(&'a self) -> u32 {
         self.raw.maxTessellationControlPerVertexInputComponents.into()
     }
 
+    /// The maximum number of per-vertex output components of a tessellation
+    /// control shader.
     pub fn max_tessellation_control_per_vertex_output_components<'a>(&'a self) -> u32 {
         self.raw.maxTessellationControlPerVertexOutputComponents.into()
     }
 
+    /// The maximum number of per-patch output components of a tessellation
+    /// control shader.
     pub fn max_tessellation_control_per_patch_output_components<'a>(&'a self) -> u32 {
         self.raw.maxTessellationControlPerPatchOutputComponents.into()
     }
 
+    /// The maximum total number of per-vertex and per-patch output components
+    /// of a tessellation control shader.
     pub fn max_tessellation_control_total_output_components<'a>(&'a self) -> u32 {
         self.raw.maxTessellationControlTotalOutputComponents.into()
     }
 
+    /// The maximum number of per-vertex input components of a tessellation
+    /// evaluation shader.
     pub fn max_tessellation_evaluation_input_components<'a>(&'a self) -> u32 {
         self.raw.maxTessellationEvaluationInputComponents.into()
     }
 
+    /// The maximum number of per-vertex output components of a tessellation
+    /// evaluation shader.
     pub fn max_tessellation_evaluation_output_components<'a>(&'a self) -> u32 {
         self.raw.maxTessellationEvaluationOutputComponents.into()
     }
 
+    /// The maximum invocation count of a geometry shader.
     pub fn max_geometry_shader_invocations<'a>(&'a self) -> u32 {
         self.raw.maxGeometryShaderInvocations.into()
     }
 
+    /// The maximum number of input components of a geometry shader.
     pub fn max_geometry_input_components<'a>(&'a self) -> u32 {
         self.raw.maxGeometryInputComponents.into()
     }
 
+    /// The maximum number of output components of a geometry shader.
     pub fn max_geometry_output_components<'a>(&'a self) -> u32 {
         self.raw.maxGeometryOutputComponents.into()
     }
 
+    /// The maximum number of vertices which can be emitted by a geometry
+    /// shader.
     pub fn max_geometry_output_vertices<'a>(&'a self) -> u32 {
         self.raw.maxGeometryOutputVertices.into()
     }
 
+    /// The maximum total number of components of all vertices emitted by a
+    /// geometry shader.
     pub fn max_geometry_total_output_components<'a>(&'a self) -> u32 {
         self.raw.maxGeometryTotalOutputComponents.into()
     }
 
+    /// The maximum number of input components of a fragment shader.
     pub fn max_fragment_input_components<'a>(&'a self) -> u32 {
         self.raw.maxFragmentInputComponents.into()
     }
 
+    /// The maximum number of output attachments which can be written by a
+    /// fragment shader.
     pub fn max_fragment_output_attachments<'a>(&'a self) -> u32 {
         self.raw.maxFragmentOutputAttachments.into()
     }
 
+    /// The maximum number of output attachments which can be written by a
+    /// fragment shader when dual-source blending is used.
     pub fn max_fragment_dual_src_attachments<'a>(&'a self) -> u32 {
         self.raw.maxFragmentDualSrcAttachments.into()
     }
 
+    /// The total number of storage buffers, storage images, and output
+    /// attachments which can be used by a fragment shader.
     pub fn max_fragment_combined_output_resources<'a>(&'a self) -> u32 {
         self.raw.maxFragmentCombinedOutputResources.into()
     }
 
+    /// The maximum total storage size, in bytes, of shared variables declared
+    /// by a compute shader.
     pub fn max_compute_shared_memory_size<'a>(&'a self) -> u32 {
         self.raw.maxComputeSharedMemorySize.into()
     }
 
-    pub fn max_compute_work_group_count<'a>(&'a self) -> &[u32] {
-        unsafe { slice::from_raw_parts(&self.raw.maxComputeWorkGroupCount as *const _, 3 as usize) }
+    /// The maximum number of work groups which can be dispatched in each of
+    /// the X, Y, and Z dimensions.
+    pub fn max_compute_work_group_count<'a>(&'a self) -> [u32; 3] {
+        self.raw.maxComputeWorkGroupCount
     }
 
+    /// The maximum total number of invocations in a single compute work
+    /// group.
     pub fn max_compute_work_group_invocations<'a>(&'a self) -> u32 {
         self.raw.maxComputeWorkGroupInvocations.into()
     }
 
-    pub fn max_compute_work_group_size<'a>(&'a self) -> &[u32] {
-        unsafe { slice::from_raw_parts(&self.raw.maxComputeWorkGroupSize as *const _, 3 as usize) }
+    /// The maximum size of a compute work group in each of the X, Y, and Z
+    /// dimensions.
+    pub fn max_compute_work_group_size<'a>(&'a self) -> [u32; 3] {
+        self.raw.maxComputeWorkGroupSize
     }
 
+    /// The number of bits of sub-pixel precision in framebuffer coordinates.
     pub fn sub_pixel_precision_bits<'a>(&'a self) -> u32 {
         self.raw.subPixelPrecisionBits.into()
     }
 
+    /// The number of bits of precision in the division along an axis of an
+    /// image used for minification and magnification filters.
     pub fn sub_texel_precision_bits<'a>(&'a self) -> u32 {
         self.raw.subTexelPrecisionBits.into()
     }
 
+    /// The number of bits of division that the LOD calculation for mipmap
+    /// fetching gets snapped to.
     pub fn mipmap_precision_bits<'a>(&'a self) -> u32 {
         self.raw.mipmapPrecisionBits.into()
     }
 
+    /// The maximum index value which can be used for indexed draw calls when
+    /// 32-bit indices are used.
     pub fn max_draw_indexed_index_value<'a>(&'a self) -> u32 {
         self.raw.maxDrawIndexedIndexValue.into()
     }
 
+    /// The maximum draw count supported for indirect draw calls.
     pub fn max_draw_indirect_count<'a>(&'a self) -> u32 {
         self.raw.maxDrawIndirectCount.into()
     }
 
+    /// The maximum absolute sampler LOD bias.
     pub fn max_sampler_lod_bias<'a>(&'a self) -> f32 {
         self.raw.maxSamplerLodBias.into()
     }
 
+    /// The maximum degree of sampler anisotropy.
     pub fn max_sampler_anisotropy<'a>(&'a self) -> f32 {
         self.raw.maxSamplerAnisotropy.into()
     }
 
+    /// The maximum number of active viewports.
     pub fn max_viewports<'a>(&'a self) -> u32 {
         self.raw.maxViewports.into()
     }
 
-    pub fn max_viewport_dimensions<'a>(&'a self) -> &[u32] {
-        unsafe { slice::from_raw_parts(&self.raw.maxViewportDimensions as *const _, 2 as usize) }
+    /// The maximum viewport `[width, height]`.
+    pub fn max_viewport_dimensions<'a>(&'a self) -> [u32; 2] {
+        self.raw.maxViewportDimensions
     }
 
-    pub fn viewport_bounds_range<'a>(&'a self) -> &[f32] {
-        unsafe { slice::from_raw_parts(&self.raw.viewportBoundsRange as *const _, 2 as usize) }
+    /// The `[minimum, maximum]` range that the corners of a viewport must be
+    /// contained in.
+    pub fn viewport_bounds_range<'a>(&'a self) -> [f32; 2] {
+        self.raw.viewportBoundsRange
     }
 
+    /// The number of bits of sub-pixel precision for viewport bounds.
     pub fn viewport_sub_pixel_bits<'a>(&'a self) -> u32 {
         self.raw.viewportSubPixelBits.into()
     }
 
+    /// The minimum required alignment, in bytes, of host visible memory
+    /// allocations within the host address space.
     pub fn min_memory_map_alignment<'a>(&'a self) -> usize {
         self.raw.minMemoryMapAlignment.into()
     }
 
+    /// The minimum required alignment, in bytes, of the `offset` of a texel
+    /// buffer view.
     pub fn min_texel_buffer_offset_alignment<'a>(&'a self) -> u64 {
         self.raw.minTexelBufferOffsetAlignment.into()
     }
 
+    /// The minimum required alignment, in bytes, of the offset of a uniform
+    /// buffer descriptor (including dynamic offsets).
     pub fn min_uniform_buffer_offset_alignment<'a>(&'a self) -> u64 {
         self.raw.minUniformBufferOffsetAlignment.into()
     }
 
+    /// The minimum required alignment, in bytes, of the offset of a storage
+    /// buffer descriptor (including dynamic offsets).
     pub fn min_storage_buffer_offset_alignment<'a>(&'a self) -> u64 {
         self.raw.minStorageBufferOffsetAlignment.into()
     }
 
+    /// The minimum offset value for the `ConstOffset` image operand of image
+    /// sample and fetch instructions.
     pub fn min_texel_offset<'a>(&'a self) -> i32 {
         self.raw.minTexelOffset.into()
     }
 
+    /// The maximum offset value for the `ConstOffset` image operand of image
+    /// sample and fetch instructions.
     pub fn max_texel_offset<'a>(&'a self) -> u32 {
         self.raw.maxTexelOffset.into()
     }
 
+    /// The minimum offset value for the `Offset` or `ConstOffsets` image
+    /// operands of image gather instructions.
     pub fn min_texel_gather_offset<'a>(&'a self) -> i32 {
         self.raw.minTexelGatherOffset.into()
     }
 
+    /// The maximum offset value for the `Offset` or `ConstOffsets` image
+    /// operands of image gather instructions.
     pub fn max_texel_gather_offset<'a>(&'a self) -> u32 {
         self.raw.maxTexelGatherOffset.into()
     }
 
+    /// The minimum negative offset value for the `offset` operand of the
+    /// `InterpolateAtOffset` extended instruction.
     pub fn min_interpolation_offset<'a>(&'a self) -> f32 {
         self.raw.minInterpolationOffset.into()
     }
 
+    /// The maximum positive offset value for the `offset` operand of the
+    /// `InterpolateAtOffset` extended instruction.
     pub fn max_interpolation_offset<'a>(&'a self) -> f32 {
         self.raw.maxInterpolationOffset.into()
     }
 
+    /// The number of fractional bits that the `x` and `y` offsets of
+    /// `InterpolateAtOffset` may be rounded to.
     pub fn sub_pixel_interpolation_offset_bits<'a>(&'a self) -> u32 {
         self.raw.subPixelInterpolationOffsetBits.into()
     }
 
+    /// The maximum width of a framebuffer.
     pub fn max_framebuffer_width<'a>(&'a self) -> u32 {
         self.raw.maxFramebufferWidth.into()
     }
 
+    /// The maximum height of a framebuffer.
     pub fn max_framebuffer_height<'a>(&'a self) -> u32 {
         self.raw.maxFramebufferHeight.into()
     }
 
+    /// The maximum layer count of a layered framebuffer.
     pub fn max_framebuffer_layers<'a>(&'a self) -> u32 {
         self.raw.maxFramebufferLayers.into()
     }
 
+    /// The color sample counts supported for all framebuffer color
+    /// attachments with floating- or fixed-point formats.
     pub fn framebuffer_color_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.framebufferColorSampleCounts)
             .expect("PhysicalDeviceLimits::framebuffer_color_sample_counts: error converting flags")
     }
 
+    /// The depth sample counts supported for all framebuffer depth/stencil
+    /// attachments with a depth component.
     pub fn framebuffer_depth_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.framebufferDepthSampleCounts)
             .expect("PhysicalDeviceLimits::framebuffer_depth_sample_counts: error converting flags")
     }
 
+    /// The stencil sample counts supported for all framebuffer depth/stencil
+    /// attachments with a stencil component.
     pub fn framebuffer_stencil_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.framebufferStencilSampleCounts)
             .expect("PhysicalDeviceLimits::framebuffer_stencil_sample_counts: error converting flags")
     }
 
+    /// The sample counts supported for a subpass which uses no attachments.
     pub fn framebuffer_no_attachments_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.framebufferNoAttachmentsSampleCounts)
             .expect("PhysicalDeviceLimits::framebuffer_no_attachments_sample_counts: error converting flags")
     }
 
+    /// The maximum number of color attachments which can be used by a
+    /// subpass.
     pub fn max_color_attachments<'a>(&'a self) -> u32 {
         self.raw.maxColorAttachments.into()
     }
 
+    /// The sample counts supported for all optimally tiled 2D sampled images
+    /// with a non-integer color format.
     pub fn sampled_image_color_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.sampledImageColorSampleCounts)
             .expect("PhysicalDeviceLimits::sampled_image_color_sample_counts: error converting flags")
     }
 
+    /// The sample counts supported for all optimally tiled 2D sampled images
+    /// with an integer color format.
     pub fn sampled_image_integer_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.sampledImageIntegerSampleCounts)
             .expect("PhysicalDeviceLimits::sampled_image_integer_sample_counts: error converting flags")
     }
 
+    /// The sample counts supported for all optimally tiled 2D sampled images
+    /// with a depth format.
     pub fn sampled_image_depth_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.sampledImageDepthSampleCounts)
             .expect("PhysicalDeviceLimits::sampled_image_depth_sample_counts: error converting flags")
     }
 
+    /// The sample counts supported for all optimally tiled 2D sampled images
+    /// with a stencil format.
     pub fn sampled_image_stencil_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.sampledImageStencilSampleCounts)
             .expect("PhysicalDeviceLimits::sampled_image_stencil_sample_counts: error converting flags")
     }
 
+    /// The sample counts supported for all optimally tiled 2D storage images.
     pub fn storage_image_sample_counts<'a>(&'a self) -> SampleCountFlags {
         SampleCountFlags::from_bits(self.raw.storageImageSampleCounts)
             .expect("PhysicalDeviceLimits::storage_image_sample_counts: error converting flags")
     }
 
+    /// The maximum number of array elements of a variable decorated with the
+    /// `SampleMask` built-in decoration.
     pub fn max_sample_mask_words<'a>(&'a self) -> u32 {
         self.raw.maxSampleMaskWords.into()
     }
 
+    /// Whether timestamps are supported on all graphics and compute queues.
     pub fn timestamp_compute_and_graphics<'a>(&'a self) -> bool {
         self.raw.timestampComputeAndGraphics != 0
     }
 
+    /// The number of nanoseconds required for a timestamp query to be
+    /// incremented by 1.
     pub fn timestamp_period<'a>(&'a self) -> f32 {
         self.raw.timestampPeriod.into()
     }
 
+    /// The maximum number of clip distances which can be used in a single
+    /// shader stage.
     pub fn max_clip_distances<'a>(&'a self) -> u32 {
         self.raw.maxClipDistances.into()
     }
 
+    /// The maximum number of cull distances which can be used in a single
+    /// shader stage.
     pub fn max_cull_distances<'a>(&'a self) -> u32 {
         self.raw.maxCullDistances.into()
     }
 
+    /// The maximum combined number of clip and cull distances which can be
+    /// used in a single shader stage.
     pub fn max_combined_clip_and_cull_distances<'a>(&'a self) -> u32 {
         self.raw.maxCombinedClipAndCullDistances.into()
     }
 
+    /// The number of discrete priorities which can be assigned to a queue.
     pub fn discrete_queue_priorities<'a>(&'a self) -> u32 {
         self.raw.discreteQueuePriorities.into()
     }
 
-    pub fn point_size_range<'a>(&'a self) -> &[f32] {
-        unsafe { slice::from_raw_parts(&self.raw.pointSizeRange as *const _, 2 as usize) }
+    /// The `[minimum, maximum]` range of supported point sizes.
+    pub fn point_size_range<'a>(&'a self) -> [f32; 2] {
+        self.raw.pointSizeRange
     }
 
-    pub fn line_width_range<'a>(&'a self) -> &[f32] {
-        unsafe { slice::from_raw_parts(&self.raw.lineWidthRange as *const _, 2 as usize) }
+    /// The `[minimum, maximum]` range of supported line widths.
+    pub fn line_width_range<'a>(&'a self) -> [f32; 2] {
+        self.raw.lineWidthRange
     }
 
+    /// The granularity of supported point sizes.
     pub fn point_size_granularity<'a>(&'a self) -> f32 {
         self.raw.pointSizeGranularity.into()
     }
 
+    /// The granularity of supported line widths.
     pub fn line_width_granularity<'a>(&'a self) -> f32 {
         self.raw.lineWidthGranularity.into()
     }
 
+    /// Whether lines are rasterized according to the preferred method of
+    /// rasterization.
     pub fn strict_lines<'a>(&'a self) -> bool {
         self.raw.strictLines != 0
     }
 
+    /// Whether rasterization uses the standard sample locations.
     pub fn standard_sample_locations<'a>(&'a self) -> bool {
         self.raw.standardSampleLocations != 0
     }
 
+    /// The optimal buffer offset alignment, in bytes, for image-buffer
+    /// copies.
     pub fn optimal_buffer_copy_offset_alignment<'a>(&'a self) -> u64 {
         self.raw.optimalBufferCopyOffsetAlignment.into()
     }
 
+    /// The optimal buffer row pitch alignment, in bytes, for image-buffer
+    /// copies.
     pub fn optimal_buffer_copy_row_pitch_alignment<'a>(&'a self) -> u64 {
         self.raw.optimalBufferCopyRowPitchAlignment.into()
     }
 
+    /// The size and alignment, in bytes, used to bound concurrent access to
+    /// host-mapped device memory. Ranges of non-coherent memory which are
+    /// flushed or invalidated must be aligned to this size.
     pub fn non_coherent_atom_size<'a>(&'a self) -> u64 {
         self.raw.nonCoherentAtomSize.into()
     }
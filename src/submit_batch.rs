@@ -0,0 +1,265 @@
+//! Multi-batch queue submission.
+
+use vks;
+use ::{error, VdResult, Handle, Queue, SemaphoreHandle, CommandBufferHandle, FenceHandle,
+    PipelineStageFlags};
+
+
+/// Offsets of a batch's first elements within each of the arrays of a
+/// `SubmitBatch`. A batch extends to the offsets of the next one.
+#[derive(Debug, Clone, Copy)]
+struct BatchStart {
+    wait: usize,
+    command_buffer: usize,
+    signal: usize,
+}
+
+
+/// A reusable builder for submissions made up of several batches, each with
+/// its own semaphore dependencies.
+///
+/// Call `batch` to start each batch then append to it using `wait`,
+/// `command_buffer`, and `signal`. `submit` submits all batches with a
+/// single call to `vkQueueSubmit`.
+///
+/// All semaphores, stage masks, command buffers and the `VkSubmitInfo`s
+/// themselves are stored in arrays owned by the builder. Call `clear` and
+/// reuse the same builder each frame to avoid allocating once those arrays
+/// have grown to their working size.
+///
+/// For a single command buffer, `Queue::submit_one` remains the simpler
+/// option.
+///
+/// ```text
+/// submit_batch.clear();
+/// submit_batch.batch()
+///         .wait(&image_available, PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+///         .command_buffer(&scene_commands)
+///         .signal(&scene_finished)
+///     .batch()
+///         .wait(&scene_finished, PipelineStageFlags::FRAGMENT_SHADER)
+///         .command_buffer(&post_commands)
+///         .signal(&render_finished);
+/// submit_batch.submit(&queue, Some(frame_fence.handle()))?;
+/// ```
+#[derive(Debug, Default)]
+pub struct SubmitBatch {
+    batches: Vec<BatchStart>,
+    wait_semaphores: Vec<vks::VkSemaphore>,
+    wait_dst_stage_masks: Vec<vks::VkPipelineStageFlags>,
+    command_buffers: Vec<vks::VkCommandBuffer>,
+    signal_semaphores: Vec<vks::VkSemaphore>,
+    submit_infos: Vec<vks::VkSubmitInfo>,
+}
+
+impl SubmitBatch {
+    /// Returns a new, empty `SubmitBatch`.
+    pub fn new() -> SubmitBatch {
+        SubmitBatch::default()
+    }
+
+    /// Starts a new batch. Subsequent calls to `wait`, `command_buffer`, and
+    /// `signal` append to it.
+    pub fn batch<'s>(&'s mut self) -> &'s mut SubmitBatch {
+        self.batches.push(BatchStart {
+            wait: self.wait_semaphores.len(),
+            command_buffer: self.command_buffers.len(),
+            signal: self.signal_semaphores.len(),
+        });
+        self
+    }
+
+    /// Starts a batch if none has been started yet.
+    fn ensure_batch(&mut self) {
+        if self.batches.is_empty() { self.batch(); }
+    }
+
+    /// Adds a semaphore for the current batch to wait on before the
+    /// `dst_stage_mask` stages of its command buffers execute.
+    pub fn wait<'s, S>(&'s mut self, semaphore: S, dst_stage_mask: PipelineStageFlags)
+            -> &'s mut SubmitBatch
+            where S: Handle<Target=SemaphoreHandle> {
+        self.ensure_batch();
+        self.wait_semaphores.push(semaphore.handle().to_raw());
+        self.wait_dst_stage_masks.push(dst_stage_mask.bits());
+        self
+    }
+
+    /// Adds a command buffer to the current batch.
+    pub fn command_buffer<'s, C>(&'s mut self, command_buffer: C) -> &'s mut SubmitBatch
+            where C: Handle<Target=CommandBufferHandle> {
+        self.ensure_batch();
+        self.command_buffers.push(command_buffer.handle().to_raw());
+        self
+    }
+
+    /// Adds a semaphore to be signaled once the command buffers of the
+    /// current batch have completed execution.
+    pub fn signal<'s, S>(&'s mut self, semaphore: S) -> &'s mut SubmitBatch
+            where S: Handle<Target=SemaphoreHandle> {
+        self.ensure_batch();
+        self.signal_semaphores.push(semaphore.handle().to_raw());
+        self
+    }
+
+    /// Returns the number of batches started.
+    pub fn len(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// Returns `true` if no batches have been started.
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// Removes all batches, retaining allocated capacity.
+    pub fn clear(&mut self) {
+        self.batches.clear();
+        self.wait_semaphores.clear();
+        self.wait_dst_stage_masks.clear();
+        self.command_buffers.clear();
+        self.signal_semaphores.clear();
+        self.submit_infos.clear();
+    }
+
+    /// Submits all batches to `queue` with a single call to
+    /// `vkQueueSubmit`, signaling `fence` once all have completed.
+    ///
+    /// The batches are retained and may be submitted again.
+    ///
+    /// https://www.khronos.org/registry/vulkan/specs/1.0/man/html/vkQueueSubmit.html
+    //
+    pub fn submit(&mut self, queue: &Queue, fence: Option<FenceHandle>) -> VdResult<()> {
+        self.submit_infos.clear();
+        for (idx, start) in self.batches.iter().enumerate() {
+            let end = self.batches.get(idx + 1).cloned().unwrap_or(BatchStart {
+                wait: self.wait_semaphores.len(),
+                command_buffer: self.command_buffers.len(),
+                signal: self.signal_semaphores.len(),
+            });
+
+            let mut submit_info = vks::VkSubmitInfo::default();
+            submit_info.waitSemaphoreCount = (end.wait - start.wait) as u32;
+            submit_info.pWaitSemaphores = self.wait_semaphores[start.wait..].as_ptr();
+            submit_info.pWaitDstStageMask = self.wait_dst_stage_masks[start.wait..].as_ptr();
+            submit_info.commandBufferCount = (end.command_buffer - start.command_buffer) as u32;
+            submit_info.pCommandBuffers = self.command_buffers[start.command_buffer..].as_ptr();
+            submit_info.signalSemaphoreCount = (end.signal - start.signal) as u32;
+            submit_info.pSignalSemaphores = self.signal_semaphores[start.signal..].as_ptr();
+            self.submit_infos.push(submit_info);
+        }
+
+        let fence_handle_raw = fence.map(|f| f.to_raw()).unwrap_or(0);
+        let result = unsafe {
+            queue.device().proc_addr_loader().vk.vkQueueSubmit(queue.handle().to_raw(),
+                self.submit_infos.len() as u32, self.submit_infos.as_ptr(), fence_handle_raw)
+        };
+        // The submit infos point into the other arrays and are rebuilt on
+        // each submission:
+        self.submit_infos.clear();
+        error::check_named(result, "vkQueueSubmit")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use vks;
+    use loader::null;
+    use counting_alloc::count_allocations;
+    use ::{Queue, QueueHandle, SemaphoreHandle, CommandBufferHandle, FenceHandle,
+        PipelineStageFlags, CallResult};
+    use super::SubmitBatch;
+
+    fn command_buffer(raw: usize) -> CommandBufferHandle {
+        CommandBufferHandle(raw as vks::VkCommandBuffer)
+    }
+
+    fn record(submit_batch: &mut SubmitBatch) {
+        submit_batch.batch()
+                .wait(SemaphoreHandle(1), PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+                .command_buffer(command_buffer(0x10))
+                .command_buffer(command_buffer(0x11))
+                .signal(SemaphoreHandle(2))
+            .batch()
+                .wait(SemaphoreHandle(2), PipelineStageFlags::FRAGMENT_SHADER)
+                .wait(SemaphoreHandle(3), PipelineStageFlags::TRANSFER)
+                .command_buffer(command_buffer(0x12))
+            .batch()
+                .signal(SemaphoreHandle(4));
+    }
+
+    #[test]
+    fn batches_are_submitted_in_a_single_call() {
+        let queue = unsafe {
+            Queue::from_parts(QueueHandle(0x4000 as vks::VkQueue), null::device(), 0, 0)
+        };
+        let mut submit_batch = SubmitBatch::new();
+        assert!(submit_batch.is_empty());
+        record(&mut submit_batch);
+        assert_eq!(submit_batch.len(), 3);
+        submit_batch.submit(&queue, Some(FenceHandle(5))).unwrap();
+
+        let submissions = null::take_submissions();
+        assert_eq!(submissions.len(), 3);
+        assert_eq!(submissions[0], null::Submission {
+            wait_semaphores: vec![1],
+            wait_dst_stage_masks: vec![PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT.bits()],
+            command_buffers: vec![command_buffer(0x10).to_raw(), command_buffer(0x11).to_raw()],
+            signal_semaphores: vec![2],
+            fence: 5,
+        });
+        assert_eq!(submissions[1].wait_semaphores, [2, 3]);
+        assert_eq!(submissions[1].wait_dst_stage_masks, [PipelineStageFlags::FRAGMENT_SHADER.bits(),
+            PipelineStageFlags::TRANSFER.bits()]);
+        assert_eq!(submissions[1].command_buffers, [command_buffer(0x12).to_raw()]);
+        assert!(submissions[1].signal_semaphores.is_empty());
+        assert!(submissions[2].wait_semaphores.is_empty());
+        assert!(submissions[2].command_buffers.is_empty());
+        assert_eq!(submissions[2].signal_semaphores, [4]);
+
+        // Batches are retained after submission.
+        submit_batch.submit(&queue, None).unwrap();
+        assert_eq!(null::take_submissions().len(), 3);
+    }
+
+    #[test]
+    fn submission_errors_are_named() {
+        let queue = unsafe {
+            Queue::from_parts(QueueHandle(0x4000 as vks::VkQueue), null::device(), 0, 0)
+        };
+        let mut submit_batch = SubmitBatch::new();
+        record(&mut submit_batch);
+        let err = submit_batch.submit(&queue, Some(FenceHandle(null::LOST_FENCE))).unwrap_err();
+        assert_eq!(err.call_result(), Some(CallResult::ErrorDeviceLost));
+        assert_eq!(err.fn_name(), Some("vkQueueSubmit"));
+    }
+
+    #[test]
+    fn appending_without_a_batch_starts_one() {
+        let mut submit_batch = SubmitBatch::new();
+        submit_batch.command_buffer(command_buffer(0x10)).signal(SemaphoreHandle(1));
+        assert_eq!(submit_batch.len(), 1);
+    }
+
+    #[test]
+    fn clear_retains_capacity() {
+        let queue = unsafe {
+            Queue::from_parts(QueueHandle(0x4000 as vks::VkQueue), null::device(), 0, 0)
+        };
+        let mut submit_batch = SubmitBatch::new();
+        record(&mut submit_batch);
+        submit_batch.submit(&queue, None).unwrap();
+
+        for _ in 0..3 {
+            let ((), allocations) = count_allocations(|| {
+                submit_batch.clear();
+                assert!(submit_batch.is_empty());
+                record(&mut submit_batch);
+                submit_batch.submit(&queue, None).unwrap();
+            });
+            assert_eq!(allocations, 0);
+        }
+        assert_eq!(null::take_submissions().len(), 3);
+    }
+}
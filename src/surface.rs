@@ -11,6 +11,12 @@ use ::{VdResult, Instance, Handle, XlibSurfaceCreateInfoKhr, XcbSurfaceCreateInf
 pub struct SurfaceKhrHandle(pub(crate) vks::VkSurfaceKHR);
 
 impl SurfaceKhrHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkSurfaceKHR) -> SurfaceKhrHandle {
+        SurfaceKhrHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkSurfaceKHR {
         self.0
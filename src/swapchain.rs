@@ -2,7 +2,7 @@ use std::sync::Arc;
 use std::marker::PhantomData;
 use smallvec::SmallVec;
 use vks;
-use ::{VdResult, SurfaceKhr, Device, PhysicalDevice, Image, Handle, Semaphore, Fence};
+use ::{VdResult, SurfaceKhr, Device, PhysicalDevice, Image, ImageHandle, Handle, Semaphore, Fence};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -10,6 +10,12 @@ use ::{VdResult, SurfaceKhr, Device, PhysicalDevice, Image, Handle, Semaphore, F
 pub struct SwapchainKhrHandle(pub(crate) vks::VkSwapchainKHR);
 
 impl SwapchainKhrHandle {
+    /// Returns a new handle wrapping `raw`. Handles are not owning.
+    #[inline(always)]
+    pub unsafe fn from_raw(raw: vks::VkSwapchainKHR) -> SwapchainKhrHandle {
+        SwapchainKhrHandle(raw)
+    }
+
     #[inline(always)]
     pub fn to_raw(&self) -> vks::VkSwapchainKHR {
         self.0
@@ -27,6 +33,64 @@ unsafe impl Handle for SwapchainKhrHandle {
 }
 
 
+/// The outcome of successfully acquiring a presentable image.
+///
+/// Failures, including `CallResult::ErrorOutOfDateKhr`,
+/// `CallResult::ErrorSurfaceLostKhr`, `CallResult::Timeout`, and
+/// `CallResult::NotReady`, are returned as `ErrorKind::ApiCall` errors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum AcquireResult {
+    /// The image at the contained index was acquired.
+    Normal(u32),
+    /// The image at the contained index was acquired but the swapchain no
+    /// longer matches the surface properties exactly. The image may still
+    /// be presented, after which the swapchain should be recreated.
+    Suboptimal(u32),
+}
+
+impl AcquireResult {
+    /// Returns the index of the acquired image.
+    #[inline]
+    pub fn image_index(&self) -> u32 {
+        match *self {
+            AcquireResult::Normal(idx) | AcquireResult::Suboptimal(idx) => idx,
+        }
+    }
+
+    /// Returns `true` if the swapchain is suboptimal.
+    #[inline]
+    pub fn is_suboptimal(&self) -> bool {
+        match *self {
+            AcquireResult::Suboptimal(_) => true,
+            AcquireResult::Normal(_) => false,
+        }
+    }
+}
+
+
+/// The outcome of successfully queuing an image for presentation.
+///
+/// Failures, including `CallResult::ErrorOutOfDateKhr`,
+/// `CallResult::ErrorSurfaceLostKhr`, and `CallResult::ErrorDeviceLost`, are
+/// returned as `ErrorKind::ApiCall` errors, as with `AcquireResult`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PresentResult {
+    /// The image was queued for presentation.
+    Normal,
+    /// The image was queued for presentation but the swapchain no longer
+    /// matches the surface properties exactly and should be recreated.
+    Suboptimal,
+}
+
+impl PresentResult {
+    /// Returns `true` if the swapchain is suboptimal.
+    #[inline]
+    pub fn is_suboptimal(&self) -> bool {
+        *self == PresentResult::Suboptimal
+    }
+}
+
+
 pub struct SwapchainSupportDetails {
     pub capabilities: ::SurfaceCapabilitiesKhr,
     pub formats: SmallVec<[::SurfaceFormatKhr; 64]>,
@@ -49,14 +113,61 @@ impl SwapchainSupportDetails {
 }
 
 
+/// Returns the first of `preferences` which is contained in `available`,
+/// falling back to `PresentModeKhr::FifoKhr`, the only mode every
+/// implementation is required to support.
+///
+/// ```rust,ignore
+/// let present_mode = voodoo::choose_present_mode(&details.present_modes,
+///     &[PresentModeKhr::MailboxKhr, PresentModeKhr::ImmediateKhr]);
+/// ```
+pub fn choose_present_mode(available: &[::PresentModeKhr], preferences: &[::PresentModeKhr])
+        -> ::PresentModeKhr {
+    preferences.iter().cloned()
+        .find(|pref| available.contains(pref))
+        .unwrap_or(::PresentModeKhr::FifoKhr)
+}
+
+/// Returns the first `(format, color_space)` pair of `preferences` which is
+/// contained in `available`, falling back to the first available format.
+///
+/// Some implementations report a single format of `Format::Undefined`,
+/// meaning that the surface has no preferred format and any may be used. In
+/// that case, or if `available` is empty, the first preference is returned
+/// or, if there are none, `B8G8R8A8Unorm` with `SrgbNonlinearKhr`.
+pub fn choose_surface_format(available: &[::SurfaceFormatKhr],
+        preferences: &[(::Format, ::ColorSpaceKhr)]) -> ::SurfaceFormatKhr {
+    let surface_format = |&(format, color_space): &(::Format, ::ColorSpaceKhr)| {
+        ::SurfaceFormatKhr::builder()
+            .format(format)
+            .color_space(color_space)
+            .build()
+    };
+
+    if available.is_empty() ||
+            (available.len() == 1 && available[0].format() == ::Format::Undefined) {
+        return surface_format(preferences.first()
+            .unwrap_or(&(::Format::B8G8R8A8Unorm, ::ColorSpaceKhr::SrgbNonlinearKhr)));
+    }
+
+    preferences.iter()
+        .find(|&&(format, color_space)| available.iter().any(|af| {
+            af.format() == format && af.color_space() == color_space
+        }))
+        .map(surface_format)
+        .unwrap_or_else(|| surface_format(&(available[0].format(), available[0].color_space())))
+}
+
+
 #[derive(Debug)]
 struct Inner {
     handle: SwapchainKhrHandle,
     device: Device,
     surface: SurfaceKhr,
-    images: SmallVec<[Image; 4]>,
+    image_handles: SmallVec<[ImageHandle; 8]>,
     image_format: ::Format,
     extent: ::Extent2d,
+    image_array_layers: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -71,8 +182,19 @@ impl SwapchainKhr {
     }
 
     /// Returns the images associated with this swapchain.
-    pub fn images(&self) -> &[Image] {
-        &self.inner.images
+    ///
+    /// The returned images do not own their handles and each holds a
+    /// reference to this swapchain, keeping it alive for as long as the image
+    /// (or any view created with `Some(swapchain)`) exists.
+    pub fn images(&self) -> Vec<Image> {
+        self.inner.image_handles.iter().map(|&h| unsafe {
+            Image::from_swapchain(self.clone(), h)
+        }).collect()
+    }
+
+    /// Returns the handles of the images associated with this swapchain.
+    pub fn image_handles(&self) -> &[ImageHandle] {
+        &self.inner.image_handles
     }
 
     /// Returns this swapchain's image format.
@@ -85,6 +207,12 @@ impl SwapchainKhr {
         &self.inner.extent
     }
 
+    /// Returns the number of array layers in each of this swapchain's
+    /// images.
+    pub fn image_array_layers(&self) -> u32 {
+        self.inner.image_array_layers
+    }
+
     /// Returns this swapchain's handle.
     pub fn handle(&self) -> SwapchainKhrHandle {
         self.inner.handle
@@ -97,11 +225,13 @@ impl SwapchainKhr {
 
     /// Retrieves the index of the next available presentable image.
     ///
+    /// See `Device::acquire_next_image_khr`.
+    ///
     /// https://manned.org/vkAcquireNextImageKHR.3
     //
     #[inline]
     pub fn acquire_next_image_khr(&self, timeout: u64, semaphore: Option<&Semaphore>,
-            fence: Option<&Fence>) -> VdResult<u32> {
+            fence: Option<&Fence>) -> VdResult<AcquireResult> {
         unsafe { self.inner.device.acquire_next_image_khr(self.handle(), timeout,
             semaphore.map(|s| s.handle()), fence.map(|f| f.handle())) }
     }
@@ -172,6 +302,9 @@ impl<'b> SwapchainKhrBuilder<'b> {
 
     /// Specifies the format that is valid for swapchains on the specified
     /// surface.
+    ///
+    /// See `choose_surface_format` for selecting a format and color space
+    /// from those reported by `PhysicalDevice::surface_formats_khr`.
     pub fn image_format<'s>(&'s mut self, image_format: ::Format)
             -> &'s mut SwapchainKhrBuilder<'b> {
         self.create_info.set_image_format(image_format);
@@ -251,6 +384,9 @@ impl<'b> SwapchainKhrBuilder<'b> {
     /// Specifies the presentation mode the swapchain will use. A swapchain’s
     /// present mode determines how incoming present requests will be
     /// processed and queued internally.
+    ///
+    /// See `choose_present_mode` for selecting among the modes reported by
+    /// `PhysicalDevice::surface_present_modes_khr`.
     pub fn present_mode<'s>(&'s mut self, present_mode: ::PresentModeKhr)
             -> &'s mut SwapchainKhrBuilder<'b> {
         self.create_info.set_present_mode(present_mode);
@@ -319,13 +455,13 @@ impl<'b> SwapchainKhrBuilder<'b> {
         let image_format = self.create_info.image_format().clone();
         let extent = self.create_info.image_extent().clone();
 
+        debug!("Creating swapchain: format: {:?}, color space: {:?}, extent: {:?}, \
+            min image count: {}, present mode: {:?}", image_format,
+            self.create_info.image_color_space(), extent, self.create_info.min_image_count(),
+            self.create_info.present_mode());
         let handle = unsafe { device.create_swapchain_khr(&self.create_info, None)? };
 
-        let images = unsafe {
-            device.get_swapchain_images_khr(handle)?.iter().map(|&h| {
-                Image::from_handle(device.clone(), h, true)
-            }).collect()
-        };
+        let image_handles = unsafe { device.get_swapchain_images_khr(handle)? };
 
         Ok(SwapchainKhr {
             inner: Arc::new(Inner {
@@ -333,10 +469,60 @@ impl<'b> SwapchainKhrBuilder<'b> {
                 device,
                 surface: self.surface.cloned()
                     .expect("unable to create swapchain: no surface specified"),
-                images,
+                image_handles,
                 image_format: image_format,
                 extent,
+                image_array_layers: self.create_info.image_array_layers(),
             })
         })
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::{Format, ColorSpaceKhr, SurfaceFormatKhr, PresentModeKhr};
+    use super::{choose_surface_format, choose_present_mode};
+
+    fn surface_format(format: Format, color_space: ColorSpaceKhr) -> SurfaceFormatKhr {
+        SurfaceFormatKhr::builder().format(format).color_space(color_space).build()
+    }
+
+    fn pair(surface_format: &SurfaceFormatKhr) -> (Format, ColorSpaceKhr) {
+        (surface_format.format(), surface_format.color_space())
+    }
+
+    const SRGB: ColorSpaceKhr = ColorSpaceKhr::SrgbNonlinearKhr;
+
+    #[test]
+    fn choose_surface_format_prefers_first_available_preference() {
+        let available = [surface_format(Format::B8G8R8A8Unorm, SRGB),
+            surface_format(Format::B8G8R8A8Srgb, SRGB)];
+        let chosen = choose_surface_format(&available,
+            &[(Format::R8G8B8A8Srgb, SRGB), (Format::B8G8R8A8Srgb, SRGB)]);
+        assert_eq!(pair(&chosen), (Format::B8G8R8A8Srgb, SRGB));
+        let chosen = choose_surface_format(&available, &[(Format::R8G8B8A8Srgb, SRGB)]);
+        assert_eq!(pair(&chosen), (Format::B8G8R8A8Unorm, SRGB));
+    }
+
+    #[test]
+    fn choose_surface_format_without_reported_formats() {
+        let undefined = [surface_format(Format::Undefined, SRGB)];
+        for available in &[&undefined[..], &[][..]] {
+            let chosen = choose_surface_format(available, &[(Format::R8G8B8A8Srgb, SRGB)]);
+            assert_eq!(pair(&chosen), (Format::R8G8B8A8Srgb, SRGB));
+            let chosen = choose_surface_format(available, &[]);
+            assert_eq!(pair(&chosen), (Format::B8G8R8A8Unorm, SRGB));
+        }
+    }
+
+    #[test]
+    fn choose_present_mode_falls_back_to_fifo() {
+        let available = [PresentModeKhr::FifoKhr, PresentModeKhr::ImmediateKhr];
+        assert_eq!(choose_present_mode(&available,
+            &[PresentModeKhr::MailboxKhr, PresentModeKhr::ImmediateKhr]),
+            PresentModeKhr::ImmediateKhr);
+        assert_eq!(choose_present_mode(&available, &[PresentModeKhr::MailboxKhr]),
+            PresentModeKhr::FifoKhr);
+    }
+}
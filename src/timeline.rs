@@ -0,0 +1,310 @@
+//! Host-waitable, monotonically increasing synchronization values.
+//!
+//! `TimelineValue` presents the interface of a `VK_KHR_timeline_semaphore`
+//! semaphore: a 64-bit counter which may be advanced from the host
+//! (`signal`) or by a queue once previously submitted work completes
+//! (`queue_signal`), and which may be waited on from the host for any
+//! value (`wait`).
+//!
+//! The bindings this crate is built on predate timeline semaphores, so the
+//! only implementation for now is `FenceTimeline`:
+//!
+//! * Each `queue_signal` submits an empty batch which signals a fence taken
+//!   from a ring of fences owned by the timeline. The fence is recorded
+//!   along with the value it represents.
+//! * The current value is the greatest of the last host-signaled value and
+//!   the values of all pending fences found to be signaled. Signaled fences
+//!   are returned to the ring as they are found and reset when reused.
+//! * `wait` blocks on the earliest pending fence which satisfies the
+//!   requested value or, if there is none yet, on a condition variable
+//!   notified by `signal` and `queue_signal`. Waits may therefore be issued
+//!   for values in any order, including values not yet scheduled.
+//!
+//! Timelines are standalone objects rather than `Device` methods (such as
+//! `signal_timeline` and `wait_timeline`): a timeline owned by its device
+//! would own fences which in turn keep the device alive, so neither could
+//! ever be destroyed. Share a `FenceTimeline` (it is `Sync`) wherever the
+//! device is shared instead.
+//!
+//! When timeline semaphore support lands, a semaphore-backed implementation
+//! of `TimelineValue` will map these methods directly onto
+//! `SemaphoreTypeCreateInfo`, `TimelineSemaphoreSubmitInfo`,
+//! `vkSignalSemaphore`, `vkWaitSemaphores`, and
+//! `vkGetSemaphoreCounterValue`, without changes to callers written
+//! against the trait.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, Condvar};
+use std::time::{Duration, Instant};
+use ::{VdResult, Device, Queue, Fence, FenceCreateFlags, FenceStatus};
+
+
+/// A host-waitable, monotonically increasing synchronization value.
+///
+/// See the module documentation for details.
+pub trait TimelineValue {
+    /// Returns the current value.
+    fn value(&self) -> VdResult<u64>;
+
+    /// Sets the value from the host.
+    ///
+    /// `value` must be greater than the current value and less than the
+    /// value of any pending queue signal.
+    fn signal(&self, value: u64) -> VdResult<()>;
+
+    /// Sets the value once all work previously submitted to `queue` has
+    /// completed.
+    ///
+    /// `value` must be greater than the current value and the value of any
+    /// pending queue signal.
+    fn queue_signal(&self, queue: &Queue, value: u64) -> VdResult<()>;
+
+    /// Blocks until the value is greater than or equal to `value` or until
+    /// `timeout` nanoseconds have elapsed.
+    ///
+    /// Returns `false` if the timeout elapsed first.
+    fn wait(&self, value: u64, timeout: u64) -> VdResult<bool>;
+}
+
+
+fn duration_from_nanos(nanos: u64) -> Duration {
+    Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+}
+
+fn nanos_from_duration(duration: Duration) -> u64 {
+    duration.as_secs().saturating_mul(1_000_000_000)
+        .saturating_add(duration.subsec_nanos() as u64)
+}
+
+
+#[derive(Debug)]
+struct State {
+    value: u64,
+    pending: VecDeque<(u64, Fence)>,
+    free: Vec<Fence>,
+}
+
+
+/// A `TimelineValue` implemented over a ring of fences.
+///
+/// Host waits on a pending queue signal are not interrupted by a host
+/// `signal` which satisfies them early; they return once the queue signal
+/// completes.
+#[derive(Debug)]
+pub struct FenceTimeline {
+    device: Device,
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+impl FenceTimeline {
+    /// Creates and returns a new `FenceTimeline` starting at
+    /// `initial_value`.
+    pub fn new(device: Device, initial_value: u64) -> FenceTimeline {
+        FenceTimeline {
+            device,
+            state: Mutex::new(State {
+                value: initial_value,
+                pending: VecDeque::new(),
+                free: Vec::new(),
+            }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    /// Returns a reference to the associated device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    fn lock<'s>(&'s self) -> MutexGuard<'s, State> {
+        self.state.lock().expect("FenceTimeline: state mutex poisoned")
+    }
+
+    /// Advances the value past all pending fences which have been signaled,
+    /// returning them to the ring.
+    ///
+    /// Fences are reset only when reused so that a concurrent `wait` on a
+    /// just-retired fence returns immediately.
+    fn retire(&self, state: &mut State) -> VdResult<()> {
+        let mut retired = false;
+        while let Some(fence) = state.pending.front().map(|&(_, ref f)| f.handle()) {
+            match unsafe { self.device.get_fence_status(fence)? } {
+                FenceStatus::Signaled => {
+                    let (value, fence) = state.pending.pop_front().unwrap();
+                    state.free.push(fence);
+                    if value > state.value { state.value = value; }
+                    retired = true;
+                },
+                FenceStatus::Unsignaled => break,
+            }
+        }
+        if retired { self.condvar.notify_all(); }
+        Ok(())
+    }
+}
+
+impl Drop for FenceTimeline {
+    /// Waits for any pending queue signals to complete so that their fences
+    /// are not destroyed while still in use by a queue.
+    fn drop(&mut self) {
+        let state = match self.state.get_mut() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let fences: Vec<_> = state.pending.iter().map(|&(_, ref f)| f.handle()).collect();
+        if !fences.is_empty() {
+            unsafe { self.device.wait_for_fences(&fences, true, u64::max_value()).ok(); }
+        }
+    }
+}
+
+impl TimelineValue for FenceTimeline {
+    fn value(&self) -> VdResult<u64> {
+        let mut state = self.lock();
+        self.retire(&mut state)?;
+        Ok(state.value)
+    }
+
+    fn signal(&self, value: u64) -> VdResult<()> {
+        let mut state = self.lock();
+        self.retire(&mut state)?;
+        if value <= state.value {
+            return Err(format!("FenceTimeline::signal: value ({}) must be greater than the \
+                current value ({})", value, state.value).into());
+        }
+        if let Some(&(pending, _)) = state.pending.front() {
+            if value >= pending {
+                return Err(format!("FenceTimeline::signal: value ({}) must be less than that of \
+                    any pending queue signal ({})", value, pending).into());
+            }
+        }
+        state.value = value;
+        self.condvar.notify_all();
+        Ok(())
+    }
+
+    fn queue_signal(&self, queue: &Queue, value: u64) -> VdResult<()> {
+        let mut state = self.lock();
+        self.retire(&mut state)?;
+        let last = state.pending.back().map(|&(v, _)| v).unwrap_or(state.value);
+        if value <= last {
+            return Err(format!("FenceTimeline::queue_signal: value ({}) must be greater than \
+                the current or last pending value ({})", value, last).into());
+        }
+        let fence = match state.free.pop() {
+            Some(fence) => {
+                unsafe { self.device.reset_fences(&[fence.handle()])?; }
+                fence
+            },
+            None => Fence::new(self.device.clone(), FenceCreateFlags::empty())?,
+        };
+        if let Err(err) = queue.submit(&[], Some(fence.handle())) {
+            state.free.push(fence);
+            return Err(err);
+        }
+        state.pending.push_back((value, fence));
+        self.condvar.notify_all();
+        Ok(())
+    }
+
+    fn wait(&self, value: u64, timeout: u64) -> VdResult<bool> {
+        let start = Instant::now();
+        let mut state = self.lock();
+        loop {
+            self.retire(&mut state)?;
+            if state.value >= value { return Ok(true); }
+
+            let elapsed = nanos_from_duration(start.elapsed());
+            if elapsed >= timeout { return Ok(false); }
+            let remaining = timeout - elapsed;
+
+            let fence = state.pending.iter().find(|&&(v, _)| v >= value)
+                .map(|&(_, ref f)| f.handle());
+            match fence {
+                Some(fence) => {
+                    drop(state);
+                    unsafe { self.device.wait_for_fences(&[fence], true, remaining)?; }
+                    state = self.lock();
+                },
+                None => {
+                    state = self.condvar.wait_timeout(state, duration_from_nanos(remaining))
+                        .expect("FenceTimeline: state mutex poisoned").0;
+                },
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use loader::null;
+    use super::{duration_from_nanos, nanos_from_duration, FenceTimeline, TimelineValue};
+
+    /// Far longer than any test should take; reaching it fails the test.
+    const LONG_TIMEOUT: u64 = 10_000_000_000;
+
+    #[test]
+    fn nanos_round_trip() {
+        for &nanos in &[0, 1, 999_999_999, 1_000_000_000, 1_500_000_001, u64::max_value()] {
+            assert_eq!(nanos_from_duration(duration_from_nanos(nanos)), nanos);
+        }
+    }
+
+    #[test]
+    fn nanos_from_duration_saturates() {
+        assert_eq!(nanos_from_duration(Duration::new(u64::max_value(), 999_999_999)),
+            u64::max_value());
+        assert_eq!(nanos_from_duration(Duration::new(2, 5)), 2_000_000_005);
+    }
+
+    #[test]
+    fn waits_for_values_not_yet_signaled() {
+        let timeline = Arc::new(FenceTimeline::new(null::device(), 0));
+        let waiter = {
+            let timeline = timeline.clone();
+            thread::spawn(move || timeline.wait(3, LONG_TIMEOUT).unwrap())
+        };
+        thread::sleep(Duration::from_millis(20));
+        timeline.signal(2).unwrap();
+        thread::sleep(Duration::from_millis(20));
+        timeline.signal(3).unwrap();
+        assert!(waiter.join().unwrap());
+        assert_eq!(timeline.value().unwrap(), 3);
+    }
+
+    #[test]
+    fn waits_complete_in_any_order() {
+        let timeline = Arc::new(FenceTimeline::new(null::device(), 0));
+        let waiters: Vec<_> = (1..5).rev().map(|value| {
+            let timeline = timeline.clone();
+            thread::spawn(move || timeline.wait(value, LONG_TIMEOUT).unwrap())
+        }).collect();
+        thread::sleep(Duration::from_millis(20));
+        timeline.signal(4).unwrap();
+        for waiter in waiters {
+            assert!(waiter.join().unwrap());
+        }
+        for value in (0..5).rev() {
+            assert!(timeline.wait(value, 0).unwrap());
+        }
+    }
+
+    #[test]
+    fn finite_timeouts_elapse() {
+        let timeline = FenceTimeline::new(null::device(), 1);
+        let timeout = Duration::from_millis(30);
+        let start = Instant::now();
+        assert!(!timeline.wait(2, nanos_from_duration(timeout)).unwrap());
+        assert!(start.elapsed() >= timeout);
+        assert_eq!(timeline.value().unwrap(), 1);
+
+        // An earlier signal does not satisfy a wait for a later value.
+        timeline.signal(2).unwrap();
+        assert!(!timeline.wait(3, nanos_from_duration(timeout)).unwrap());
+    }
+}
@@ -0,0 +1,185 @@
+//! One-time command submission and staged uploads.
+
+use ::{VdResult, Device, Queue, CommandPool, CommandBuffer, CommandBufferLevel,
+    CommandBufferUsageFlags, Buffer, BufferUsageFlags, SharingMode, DeviceMemory,
+    MemoryPropertyFlags, MemoryMapFlags, Image, ImageLayout, ImageMemoryBarrier,
+    ImageSubresourceLayers, ImageAspectFlags, BufferImageCopy, Offset3d,
+    Extent3d, Format, AccessFlags, PipelineStageFlags, DependencyFlags};
+
+
+/// Allocates a primary command buffer from `command_pool`, records into it
+/// using `record`, submits it to `queue`, then waits for the queue to become
+/// idle.
+///
+/// Intended for infrequent setup work such as uploads and layout
+/// transitions, not per-frame rendering.
+pub fn one_time_commands<F>(queue: &Queue, command_pool: &CommandPool, record: F)
+        -> VdResult<()>
+        where F: FnOnce(&CommandBuffer) -> VdResult<()> {
+    let command_buffer = command_pool.allocate_command_buffer(CommandBufferLevel::Primary)?;
+    command_buffer.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT)?;
+    record(&command_buffer)?;
+    command_buffer.end()?;
+
+    queue.submit_one(&[], command_buffer.handle(), &[], None)?;
+    queue.wait_idle();
+    Ok(())
+}
+
+/// Returns the size in bytes of tightly packed data covering `extent` of an
+/// image of a color `format`, or `None` for depth/stencil and undefined
+/// formats.
+fn color_data_size(format: Format, extent: &Extent3d) -> Option<u64> {
+    if format.aspect_flags() != ImageAspectFlags::COLOR { return None; }
+    let block_size = format.block_size_bytes()? as u64;
+    let (block_width, block_height) = format.block_extent();
+    let (block_width, block_height) = (block_width as u64, block_height as u64);
+    let blocks_wide = (extent.width() as u64 + block_width - 1) / block_width;
+    let blocks_high = (extent.height() as u64 + block_height - 1) / block_height;
+    Some(block_size * blocks_wide * blocks_high * extent.depth() as u64)
+}
+
+/// Returns the access mask and pipeline stages which must wait for a
+/// transfer write before an image is used in `layout`.
+fn layout_dst_access(layout: ImageLayout) -> (AccessFlags, PipelineStageFlags) {
+    match layout {
+        ImageLayout::ShaderReadOnlyOptimal => (AccessFlags::SHADER_READ,
+            PipelineStageFlags::VERTEX_SHADER | PipelineStageFlags::FRAGMENT_SHADER |
+            PipelineStageFlags::COMPUTE_SHADER),
+        ImageLayout::TransferSrcOptimal => (AccessFlags::TRANSFER_READ,
+            PipelineStageFlags::TRANSFER),
+        ImageLayout::TransferDstOptimal => (AccessFlags::TRANSFER_WRITE,
+            PipelineStageFlags::TRANSFER),
+        ImageLayout::ColorAttachmentOptimal => (AccessFlags::COLOR_ATTACHMENT_READ |
+            AccessFlags::COLOR_ATTACHMENT_WRITE, PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT),
+        _ => (AccessFlags::MEMORY_READ | AccessFlags::MEMORY_WRITE,
+            PipelineStageFlags::ALL_COMMANDS),
+    }
+}
+
+/// Uploads `data` into mip level 0, array layer 0 of `image` through a
+/// temporary host-visible staging buffer, leaving the image in
+/// `final_layout`.
+///
+/// `data` must contain exactly `extent.width * extent.height * extent.depth`
+/// tightly packed texels of `format` (no row padding) or, for
+/// block-compressed formats, the blocks covering that extent. `image` must have
+/// been created with `ImageUsageFlags::TRANSFER_DST`. Its previous contents
+/// and layout are discarded, and every mip level and array layer is left in
+/// `final_layout`.
+///
+/// The copy and both layout transitions are recorded into a one-time
+/// command buffer allocated from `command_pool` and submitted to `queue`;
+/// this function blocks until they complete.
+///
+/// Returns an error for depth/stencil formats, which are not yet supported.
+pub fn upload_image_data(device: &Device, queue: &Queue, command_pool: &CommandPool,
+        image: &Image, format: Format, extent: &Extent3d, data: &[u8], final_layout: ImageLayout)
+        -> VdResult<()> {
+    let size = match color_data_size(format, extent) {
+        Some(size) => size,
+        None => return Err(format!("upload_image_data: unsupported format: {:?} (only \
+            color formats are supported)", format).into()),
+    };
+    if data.len() as u64 != size {
+        return Err(format!("upload_image_data: expected {} bytes of data for a {}x{}x{} {:?} \
+            image, found {}", size, extent.width(), extent.height(), extent.depth(), format,
+            data.len()).into());
+    }
+
+    let staging_buffer = Buffer::builder()
+        .size(size)
+        .usage(BufferUsageFlags::TRANSFER_SRC)
+        .sharing_mode(SharingMode::Exclusive)
+        .build(device.clone())?;
+    let memory_requirements = staging_buffer.memory_requirements().clone();
+    let memory_type_index = device.memory_type_index(memory_requirements.memory_type_bits(),
+        MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT)?;
+    let staging_memory = DeviceMemory::new(device.clone(), memory_requirements.size(),
+        memory_type_index)?;
+    unsafe {
+        staging_buffer.bind_memory(&staging_memory, 0)?;
+        let mut mapping = staging_memory.map::<u8>(0, size, MemoryMapFlags::empty())?;
+        mapping.copy_from_slice(data);
+        staging_memory.unmap(mapping);
+    }
+
+    let to_transfer_dst = ImageMemoryBarrier::builder()
+        .src_access_mask(AccessFlags::empty())
+        .dst_access_mask(AccessFlags::TRANSFER_WRITE)
+        .old_layout(ImageLayout::Undefined)
+        .new_layout(ImageLayout::TransferDstOptimal)
+        .image(image)
+        .build();
+
+    let (dst_access_mask, dst_stage) = layout_dst_access(final_layout);
+    let to_final = ImageMemoryBarrier::builder()
+        .src_access_mask(AccessFlags::TRANSFER_WRITE)
+        .dst_access_mask(dst_access_mask)
+        .old_layout(ImageLayout::TransferDstOptimal)
+        .new_layout(final_layout)
+        .image(image)
+        .build();
+
+    // A row length and image height of zero mean the data is tightly
+    // packed according to the image extent.
+    let region = BufferImageCopy::builder()
+        .buffer_offset(0)
+        .buffer_row_length(0)
+        .buffer_image_height(0)
+        .image_subresource(ImageSubresourceLayers::builder()
+            .aspect_mask(ImageAspectFlags::COLOR)
+            .mip_level(0)
+            .base_array_layer(0)
+            .layer_count(1)
+            .build())
+        .image_offset(Offset3d::builder().x(0).y(0).z(0).build())
+        .image_extent(extent.clone())
+        .build();
+
+    one_time_commands(queue, command_pool, |command_buffer| {
+        command_buffer.pipeline_barrier(PipelineStageFlags::TOP_OF_PIPE,
+            PipelineStageFlags::TRANSFER, DependencyFlags::empty(), &[], &[],
+            &[to_transfer_dst]);
+        unsafe {
+            command_buffer.copy_buffer_to_image(&staging_buffer, image,
+                ImageLayout::TransferDstOptimal, &[region]);
+        }
+        command_buffer.pipeline_barrier(PipelineStageFlags::TRANSFER, dst_stage,
+            DependencyFlags::empty(), &[], &[], &[to_final]);
+        Ok(())
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::{Format, Extent3d};
+    use super::color_data_size;
+
+    fn extent(width: u32, height: u32, depth: u32) -> Extent3d {
+        Extent3d::builder().width(width).height(height).depth(depth).build()
+    }
+
+    #[test]
+    fn color_data_size_rounds_up_to_whole_blocks() {
+        assert_eq!(color_data_size(Format::R8G8B8A8Unorm, &extent(3, 5, 2)), Some(4 * 3 * 5 * 2));
+        assert_eq!(color_data_size(Format::Bc1RgbaUnormBlock, &extent(5, 4, 1)), Some(8 * 2));
+        assert_eq!(color_data_size(Format::Bc1RgbaUnormBlock, &extent(1, 1, 1)), Some(8));
+    }
+
+    #[test]
+    fn color_data_size_of_maximum_extent() {
+        let max = u32::max_value();
+        assert_eq!(color_data_size(Format::Bc1RgbaUnormBlock, &extent(max, 4, 1)),
+            Some(8 * (1 << 30)));
+        assert_eq!(color_data_size(Format::Astc12x12UnormBlock, &extent(max, max, 1)),
+            Some(16 * 357913942 * 357913942));
+    }
+
+    #[test]
+    fn color_data_size_of_non_color_formats() {
+        assert_eq!(color_data_size(Format::D24UnormS8Uint, &extent(4, 4, 1)), None);
+        assert_eq!(color_data_size(Format::Undefined, &extent(4, 4, 1)), None);
+    }
+}
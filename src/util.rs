@@ -4,9 +4,11 @@ use std::path::Path;
 use std::fs::File;
 use std::io::{Read, BufReader};
 use std::ops::Deref;
+use std::ptr;
 use std::slice;
 use libc::c_char;
-use ::{VdResult, PRINT};
+use smallvec::{Array, SmallVec};
+use ::{error, VdResult, CallResult};
 
 /// An owned or borrowed C string representable as a pointer.
 #[derive(Debug, Clone)]
@@ -73,8 +75,8 @@ impl<'cp> From<String> for CharStr<'cp> {
 pub enum CharStrs<'cs> {
     Ptr { ptr: *const *const c_char, len: usize },
     RefPtr { ptrs: &'cs [*const c_char] },
-    OwnedPtr { ptrs: Vec<*const c_char> },
-    OwnedOwned { strings: Vec<CString>, ptrs: Vec<*const c_char> },
+    OwnedPtr { ptrs: SmallVec<[*const c_char; 16]> },
+    OwnedOwned { strings: Vec<CString>, ptrs: SmallVec<[*const c_char; 16]> },
 }
 
 impl<'cs> CharStrs<'cs> {
@@ -96,7 +98,11 @@ impl<'cs> CharStrs<'cs> {
         }
     }
 
-    pub fn as_ptr_slice(&self) -> &'cs [*const c_char] {
+    /// Returns the list of pointers as a slice.
+    ///
+    /// The returned slice borrows from `self` and must not be retained
+    /// across moves of this `CharStrs` (pointer storage may be inline).
+    pub fn as_ptr_slice<'s>(&'s self) -> &'s [*const c_char] {
         unsafe { slice::from_raw_parts(self.as_ptr(), self.len()) }
     }
 }
@@ -142,6 +148,46 @@ impl <'cs, 'p, 'q> From<&'p [&'q str]> for CharStrs<'cs> where 'q: 'p, 'p: 'cs,
 }
 
 
+/// Calls an enumeration function using the two-call idiom, first with a
+/// null pointer to query the count then with storage for that many items.
+///
+/// The available items may change between the two calls, so the second is
+/// retried from the start for as long as it returns `VK_INCOMPLETE`. The
+/// returned list is truncated to the number of items actually written. Any
+/// error code is returned as an error naming `fn_name`.
+pub(crate) unsafe fn enumerate<A, F>(fn_name: &'static str, mut f: F) -> VdResult<SmallVec<A>>
+        where A: Array, F: FnMut(&mut u32, *mut A::Item) -> i32 {
+    let mut items = SmallVec::<A>::new();
+    loop {
+        let mut count = 0u32;
+        error::check_named(f(&mut count, ptr::null_mut()), fn_name)?;
+        items.reserve_exact(count as usize);
+        let result = f(&mut count, items.as_mut_ptr());
+        if result != CallResult::Incomplete as i32 {
+            error::check_named(result, fn_name)?;
+            items.set_len(count as usize);
+            return Ok(items);
+        }
+    }
+}
+
+/// Calls an enumeration function which returns no result using the
+/// two-call idiom.
+///
+/// The returned list is truncated to the number of items actually written.
+pub(crate) unsafe fn enumerate_void<A, F>(mut f: F) -> SmallVec<A>
+        where A: Array, F: FnMut(&mut u32, *mut A::Item) {
+    let mut items = SmallVec::<A>::new();
+    let mut count = 0u32;
+    f(&mut count, ptr::null_mut());
+    items.reserve_exact(count as usize);
+    f(&mut count, items.as_mut_ptr());
+    // The count is updated to the number actually written:
+    items.set_len(count as usize);
+    items
+}
+
+
 pub fn read_spir_v_file<P: AsRef<Path>>(file: P) -> VdResult<Vec<u32>> {
     let mut contents = read_file(file)?;
     assert!(contents.len() % 4 == 0);
@@ -167,7 +213,7 @@ pub fn read_file<P: AsRef<Path>>(file: P) -> VdResult<Vec<u8>> {
     match reader.read_to_end(&mut contents) {
         Ok(bytes) => {
             assert_eq!(bytes, file_bytes);
-            if PRINT { println!("Read {} bytes from {}", bytes, &file_name); }
+            trace!("Read {} bytes from {}", bytes, &file_name);
         },
         Err(e) => panic!("{}", e),
     }
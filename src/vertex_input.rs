@@ -0,0 +1,123 @@
+use std::mem;
+use smallvec::SmallVec;
+use ::{Format, VertexInputRate, VertexInputBindingDescription, VertexInputAttributeDescription,
+    PipelineVertexInputStateCreateInfo};
+
+
+/// Describes how a vertex type is laid out in vertex buffers.
+///
+/// Attribute offsets are most easily computed with the `offset_of!` macro:
+///
+/// ```text
+/// #[repr(C)]
+/// struct Vertex { pos: [f32; 3], uv: [f32; 2] }
+///
+/// impl VertexLayout for Vertex {
+///     fn attributes() -> Vec<VertexInputAttributeDescription> {
+///         vec![
+///             VertexInputAttributeDescription::builder().location(0).binding(0)
+///                 .format(Format::R32G32B32Sfloat).offset(offset_of!(Vertex, pos)).build(),
+///             VertexInputAttributeDescription::builder().location(1).binding(0)
+///                 .format(Format::R32G32Sfloat).offset(offset_of!(Vertex, uv)).build(),
+///         ]
+///     }
+/// }
+/// ```
+///
+/// Vertex types should be `#[repr(C)]` so that field offsets are stable.
+pub trait VertexLayout {
+    /// Returns the vertex buffer bindings used by this type.
+    ///
+    /// Defaults to a single per-vertex binding at index 0 with a stride of
+    /// `size_of::<Self>()`.
+    fn bindings() -> Vec<VertexInputBindingDescription> where Self: Sized {
+        vec![VertexInputBindingDescription::builder()
+            .binding(0)
+            .stride(mem::size_of::<Self>() as u32)
+            .input_rate(VertexInputRate::Vertex)
+            .build()]
+    }
+
+    /// Returns the attributes of this type, with offsets relative to the
+    /// start of each element.
+    fn attributes() -> Vec<VertexInputAttributeDescription> where Self: Sized;
+}
+
+
+/// Vertex input bindings and attributes, owning their arrays.
+///
+/// Pass to `GraphicsPipelineBuilder::vertex_input` or use `create_info` to
+/// obtain a `PipelineVertexInputStateCreateInfo`.
+#[derive(Debug, Clone, Default)]
+pub struct VertexInputState {
+    bindings: SmallVec<[VertexInputBindingDescription; 4]>,
+    attributes: SmallVec<[VertexInputAttributeDescription; 8]>,
+}
+
+impl VertexInputState {
+    /// Returns a new, empty vertex input state.
+    pub fn new() -> VertexInputState {
+        VertexInputState::default()
+    }
+
+    /// Returns a new vertex input state containing the bindings and
+    /// attributes of `V`.
+    pub fn for_layout<V: VertexLayout>() -> VertexInputState {
+        let mut state = VertexInputState::new();
+        state.layout::<V>();
+        state
+    }
+
+    /// Adds a vertex buffer binding.
+    pub fn binding<'s>(&'s mut self, binding: u32, stride: u32, input_rate: VertexInputRate)
+            -> &'s mut VertexInputState {
+        self.bindings.push(VertexInputBindingDescription::builder()
+            .binding(binding)
+            .stride(stride)
+            .input_rate(input_rate)
+            .build());
+        self
+    }
+
+    /// Adds a vertex attribute.
+    pub fn attribute<'s>(&'s mut self, location: u32, binding: u32, format: Format, offset: u32)
+            -> &'s mut VertexInputState {
+        self.attributes.push(VertexInputAttributeDescription::builder()
+            .location(location)
+            .binding(binding)
+            .format(format)
+            .offset(offset)
+            .build());
+        self
+    }
+
+    /// Adds the bindings and attributes of `V`.
+    pub fn layout<'s, V: VertexLayout>(&'s mut self) -> &'s mut VertexInputState {
+        self.bindings.extend(V::bindings());
+        self.attributes.extend(V::attributes());
+        self
+    }
+
+    /// Returns the vertex buffer bindings.
+    pub fn bindings(&self) -> &[VertexInputBindingDescription] {
+        &self.bindings
+    }
+
+    /// Returns the vertex attributes.
+    pub fn attributes(&self) -> &[VertexInputAttributeDescription] {
+        &self.attributes
+    }
+
+    /// Returns a create info structure referring to these bindings and
+    /// attributes.
+    pub fn create_info<'s>(&'s self) -> PipelineVertexInputStateCreateInfo<'s> {
+        let mut builder = PipelineVertexInputStateCreateInfo::builder();
+        if !self.bindings.is_empty() {
+            builder = builder.vertex_binding_descriptions(&self.bindings);
+        }
+        if !self.attributes.is_empty() {
+            builder = builder.vertex_attribute_descriptions(&self.attributes);
+        }
+        builder.build()
+    }
+}
@@ -0,0 +1,104 @@
+//! Surface creation for `winit` windows.
+//!
+//! Enabled by the `winit` feature.
+
+use std::ffi::CStr;
+use winit::Window;
+use ::{VdResult, Instance, SurfaceKhr, names};
+
+
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd"))]
+static REQUIRED_INSTANCE_EXTENSIONS: &[&CStr] = &[
+    names::KHR_SURFACE, names::KHR_XLIB_SURFACE, names::KHR_WAYLAND_SURFACE];
+
+#[cfg(target_os = "windows")]
+static REQUIRED_INSTANCE_EXTENSIONS: &[&CStr] = &[names::KHR_SURFACE, names::KHR_WIN32_SURFACE];
+
+#[cfg(target_os = "android")]
+static REQUIRED_INSTANCE_EXTENSIONS: &[&CStr] = &[names::KHR_SURFACE, names::KHR_ANDROID_SURFACE];
+
+#[cfg(target_os = "macos")]
+static REQUIRED_INSTANCE_EXTENSIONS: &[&CStr] = &[names::KHR_SURFACE, names::MVK_MACOS_SURFACE];
+
+
+/// Returns the instance extensions required by `create_surface` on the
+/// current platform.
+///
+/// On Linux and the BSDs, both the Xlib and Wayland surface extensions are
+/// listed as the window system is only known at runtime.
+pub fn required_instance_extensions() -> &'static [&'static CStr] {
+    REQUIRED_INSTANCE_EXTENSIONS
+}
+
+
+/// Creates and returns a new surface for `window`.
+///
+/// `instance` must have been created with the extensions returned by
+/// `required_instance_extensions` enabled.
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd"))]
+pub fn create_surface(instance: &Instance, window: &Window) -> VdResult<SurfaceKhr> {
+    use winit::os::unix::WindowExt;
+    use ::vks::{wl_display, wl_surface};
+
+    let mut sb = SurfaceKhr::builder();
+    unsafe {
+        if let (Some(display), Some(xlib_window)) = (window.get_xlib_display(),
+                window.get_xlib_window()) {
+            sb.xlib(display as _, xlib_window as _);
+        } else if let (Some(display), Some(surface)) = (window.get_wayland_display(),
+                window.get_wayland_surface()) {
+            sb.wayland(display as *mut wl_display, surface as *mut wl_surface);
+        } else {
+            return Err("winit_support::create_surface: unsupported window system".into());
+        }
+    }
+    sb.build(instance.clone())
+}
+
+/// Creates and returns a new surface for `window`.
+///
+/// `instance` must have been created with the extensions returned by
+/// `required_instance_extensions` enabled.
+#[cfg(target_os = "windows")]
+pub fn create_surface(instance: &Instance, window: &Window) -> VdResult<SurfaceKhr> {
+    use winit::os::windows::WindowExt;
+    use std::ptr;
+
+    unsafe {
+        SurfaceKhr::builder()
+            .win32(ptr::null_mut(), window.get_hwnd() as *mut _)
+            .build(instance.clone())
+    }
+}
+
+/// Creates and returns a new surface for `window`.
+///
+/// `instance` must have been created with the extensions returned by
+/// `required_instance_extensions` enabled.
+#[cfg(target_os = "android")]
+pub fn create_surface(instance: &Instance, window: &Window) -> VdResult<SurfaceKhr> {
+    use winit::os::android::WindowExt;
+    use ::AndroidSurfaceCreateFlagsKhr;
+
+    unsafe {
+        SurfaceKhr::builder()
+            .android(AndroidSurfaceCreateFlagsKhr::empty(), window.get_native_window() as *mut _)
+            .build(instance.clone())
+    }
+}
+
+/// Creates and returns a new surface for `window`.
+///
+/// `instance` must have been created with the extensions returned by
+/// `required_instance_extensions` enabled. The window's view must be backed
+/// by a `CAMetalLayer` as required by MoltenVK.
+#[cfg(target_os = "macos")]
+pub fn create_surface(instance: &Instance, window: &Window) -> VdResult<SurfaceKhr> {
+    use winit::os::macos::WindowExt;
+
+    unsafe {
+        SurfaceKhr::builder()
+            .macos(window.get_nsview() as *const _)
+            .build(instance.clone())
+    }
+}
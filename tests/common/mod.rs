@@ -0,0 +1,69 @@
+//! Setup shared by tests which require a Vulkan implementation.
+//!
+//! Each function returns `None` if no Vulkan loader, instance, or suitable
+//! physical device is available, in which case the calling test returns
+//! without checking anything.
+
+#![allow(dead_code)]
+
+use std::ffi::CString;
+use voodoo::{Instance, ApplicationInfo, Loader, PhysicalDevice, Device, DeviceQueueCreateInfo,
+    QueueFlags};
+
+
+/// Returns a new instance with `extension_names` and `layer_names` enabled.
+pub fn instance_with(extension_names: &[&str], layer_names: &[&str]) -> Option<Instance> {
+    let app_name = CString::new("voodoo tests").unwrap();
+    let app_info = ApplicationInfo::builder()
+        .application_name(&app_name)
+        .application_version((1, 0, 0))
+        .api_version((1, 0, 0))
+        .build();
+    let loader = Loader::new().ok()?;
+    Instance::builder()
+        .application_info(&app_info)
+        .enabled_extension_names(extension_names)
+        .enabled_layer_names(layer_names)
+        .build(loader)
+        .ok()
+}
+
+/// Returns a new instance with no extensions or layers enabled.
+pub fn instance() -> Option<Instance> {
+    instance_with(&[], &[])
+}
+
+/// Returns the first physical device of `instance` with a graphics queue
+/// family, along with the index of that family.
+pub fn physical_device(instance: &Instance) -> Option<(PhysicalDevice, u32)> {
+    for physical_device in instance.physical_devices().ok()? {
+        let families = physical_device.queue_family_properties().ok()?;
+        if let Some(idx) = families.iter()
+                .position(|f| f.queue_flags().contains(QueueFlags::GRAPHICS)) {
+            return Some((physical_device, idx as u32));
+        }
+    }
+    None
+}
+
+/// Returns a device created from `instance` with a single graphics queue,
+/// along with that queue's family index.
+pub fn device_from(instance: &Instance) -> Option<(Device, u32)> {
+    let (physical_device, family_index) = physical_device(instance)?;
+    let queue_priorities = [1.0];
+    let queue_create_infos = [DeviceQueueCreateInfo::builder()
+        .queue_family_index(family_index)
+        .queue_priorities(&queue_priorities)
+        .build()];
+    let device = Device::builder()
+        .queue_create_infos(&queue_create_infos)
+        .build(physical_device)
+        .ok()?;
+    Some((device, family_index))
+}
+
+/// Returns a device with a single graphics queue, along with that queue's
+/// family index.
+pub fn device() -> Option<(Device, u32)> {
+    device_from(&instance()?)
+}
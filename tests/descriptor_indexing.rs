@@ -0,0 +1,116 @@
+extern crate voodoo;
+
+mod common;
+
+use voodoo::{Device, DeviceQueueCreateInfo, PhysicalDeviceFeatures2Khr,
+    PhysicalDeviceDescriptorIndexingFeaturesExt, DescriptorSetLayout, DescriptorSetLayoutBinding,
+    DescriptorSetLayoutBindingFlagsCreateInfoExt, DescriptorBindingFlagsExt, DescriptorType,
+    ShaderStageFlags, DescriptorPool, DescriptorPoolSize, DescriptorImageInfo, ImageLayout,
+    Sampler, PipelineLayout, PipelineBindPoint, CommandPool, CommandPoolCreateFlags,
+    CommandBufferLevel, CommandBufferUsageFlags};
+
+
+const VALIDATION_LAYER: &str = "VK_LAYER_KHRONOS_validation";
+
+/// The length of the sampler array, larger than any set is likely to be
+/// fully written.
+const SAMPLER_COUNT: u32 = 4096;
+
+
+#[test]
+fn partially_bound_sampler_array() {
+    let instance = match common::instance_with(&["VK_KHR_get_physical_device_properties2"],
+            &[VALIDATION_LAYER]) {
+        Some(i) => i,
+        None => return,
+    };
+    let (physical_device, family_index) = match common::physical_device(&instance) {
+        Some(pd) => pd,
+        None => return,
+    };
+    let limits = physical_device.properties_ref().limits();
+    if limits.max_per_stage_descriptor_samplers() < SAMPLER_COUNT ||
+            limits.max_descriptor_set_samplers() < SAMPLER_COUNT {
+        return;
+    }
+
+    let mut indexing_features = PhysicalDeviceDescriptorIndexingFeaturesExt::builder().build();
+    let mut features = PhysicalDeviceFeatures2Khr::builder().build();
+    unsafe {
+        features.set_next(&mut indexing_features
+            as *mut PhysicalDeviceDescriptorIndexingFeaturesExt as *mut _);
+        instance.get_physical_device_features_2_khr_into(&physical_device, &mut features)
+            .unwrap();
+    }
+    if !indexing_features.descriptor_binding_partially_bound() { return; }
+
+    // Enable only the feature under test.
+    let indexing_features = PhysicalDeviceDescriptorIndexingFeaturesExt::builder()
+        .descriptor_binding_partially_bound(true)
+        .build();
+    let mut features = PhysicalDeviceFeatures2Khr::builder().build();
+    unsafe { features.set_next(indexing_features.as_raw() as *const _ as *mut _); }
+    let queue_priorities = [1.0];
+    let queue_create_infos = [DeviceQueueCreateInfo::builder()
+        .queue_family_index(family_index)
+        .queue_priorities(&queue_priorities)
+        .build()];
+    let device = match Device::builder()
+            .queue_create_infos(&queue_create_infos)
+            .enabled_extension_names(&["VK_KHR_maintenance3", "VK_EXT_descriptor_indexing"][..])
+            .enabled_features_2(&mut features)
+            .build(physical_device) {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+
+    let bindings = [DescriptorSetLayoutBinding::builder()
+        .binding(0)
+        .descriptor_type(DescriptorType::Sampler)
+        .descriptor_count(SAMPLER_COUNT)
+        .stage_flags(ShaderStageFlags::FRAGMENT)
+        .build()];
+    let flags = [DescriptorBindingFlagsExt::PARTIALLY_BOUND_EXT];
+    let mut binding_flags = DescriptorSetLayoutBindingFlagsCreateInfoExt::builder()
+        .binding_flags(&flags)
+        .build();
+    let layout = DescriptorSetLayout::builder()
+        .bindings(&bindings)
+        .binding_flags(&mut binding_flags)
+        .build(device.clone()).unwrap();
+
+    let pool_sizes = [DescriptorPoolSize::builder()
+        .type_of(DescriptorType::Sampler)
+        .descriptor_count(SAMPLER_COUNT)
+        .build()];
+    let pool = DescriptorPool::builder()
+        .max_sets(1)
+        .pool_sizes(&pool_sizes)
+        .build(device.clone()).unwrap();
+    let set = pool.allocate_descriptor_set(&layout).unwrap();
+
+    // Write a few elements at each end of the array, leaving the rest unbound.
+    let sampler = Sampler::builder().build(device.clone()).unwrap();
+    let infos: Vec<_> = (0..4).map(|_| DescriptorImageInfo::builder()
+        .sampler(sampler.handle())
+        .image_layout(ImageLayout::Undefined)
+        .build()).collect();
+    set.writes()
+        .image_array(0, 0, DescriptorType::Sampler, &infos)
+        .image_array(0, SAMPLER_COUNT - infos.len() as u32, DescriptorType::Sampler, &infos)
+        .update();
+
+    let set_layouts = [layout.handle()];
+    let pipeline_layout = PipelineLayout::builder()
+        .set_layouts(&set_layouts)
+        .build(device.clone()).unwrap();
+    let command_pool = CommandPool::builder()
+        .flags(CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+        .queue_family_index(family_index)
+        .build(device.clone()).unwrap();
+    let command_buffer = command_pool.allocate_command_buffer(CommandBufferLevel::Primary).unwrap();
+    command_buffer.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT).unwrap();
+    command_buffer.bind_descriptor_sets(PipelineBindPoint::Graphics, &pipeline_layout, 0,
+        &[&set], &[]);
+    command_buffer.end().unwrap();
+}
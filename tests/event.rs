@@ -0,0 +1,19 @@
+extern crate voodoo;
+
+mod common;
+
+use std::time::Duration;
+use voodoo::{Event, EventCreateFlags};
+
+
+#[test]
+fn wait_signaled_with_zero_poll_interval() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let event = Event::new(device, EventCreateFlags::empty()).unwrap();
+    let zero = Duration::new(0, 0);
+    assert!(!event.wait_signaled(Duration::from_millis(5), zero).unwrap());
+    event.set().unwrap();
+    assert!(event.wait_signaled(zero, zero).unwrap());
+    event.reset().unwrap();
+    assert!(!event.wait_signaled(zero, Duration::new(u64::max_value(), 0)).unwrap());
+}
@@ -0,0 +1,24 @@
+extern crate voodoo;
+
+mod common;
+
+use voodoo::{Fence, FenceCreateFlags, FenceStatus, WaitResult};
+
+
+#[test]
+fn wait_on_unsignaled_fence_with_zero_timeout_times_out() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let fence = Fence::new(device.clone(), FenceCreateFlags::empty()).unwrap();
+    assert_eq!(fence.status().unwrap(), FenceStatus::Unsignaled);
+    assert_eq!(fence.wait(0).unwrap(), WaitResult::TimedOut);
+    let result = unsafe { device.wait_for_fences(&[fence.handle()], false, 0).unwrap() };
+    assert_eq!(result, WaitResult::TimedOut);
+}
+
+#[test]
+fn wait_on_signaled_fence_returns_signaled() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let fence = Fence::new(device, FenceCreateFlags::SIGNALED).unwrap();
+    assert_eq!(fence.status().unwrap(), FenceStatus::Signaled);
+    assert_eq!(fence.wait(0).unwrap(), WaitResult::Signaled);
+}
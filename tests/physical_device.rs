@@ -0,0 +1,20 @@
+extern crate voodoo;
+
+mod common;
+
+
+#[test]
+fn properties_are_cached_consistently() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let physical_device = device.physical_device();
+    let owned = physical_device.properties();
+    let borrowed = physical_device.properties_ref();
+    let cached = device.properties();
+    assert_eq!(owned.name(), borrowed.name());
+    assert_eq!(owned.name(), cached.name());
+    assert_eq!(owned.device_id(), cached.device_id());
+    assert_eq!(owned.api_version(), cached.api_version());
+    assert_eq!(device.limits().max_image_dimension_2d(),
+        borrowed.limits().max_image_dimension_2d());
+    assert_eq!(physical_device.name(), owned.name());
+}
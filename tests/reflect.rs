@@ -0,0 +1,104 @@
+//! Reflection of the SPIR-V fixtures in `tests/fixtures`.
+//!
+//! `reflect_vert.spv` and `reflect_frag.spv` are the example shaders in
+//! `examples/shaders`. `reflect_compute.spv` is a minimal hand-assembled
+//! module declaring:
+//!
+//! * set 0, binding 0: a storage buffer (a `BufferBlock` struct containing a
+//!   runtime array),
+//! * set 1, binding 0: a storage image,
+//! * set 1, binding 2: an array of four combined image samplers,
+//! * a push constant block of a column-major `mat4` (matrix stride 16)
+//!   followed by a `vec4` at offset 64.
+
+#![cfg(feature = "spirv-reflect")]
+
+extern crate voodoo;
+
+use voodoo::{DescriptorType, ShaderStageFlags};
+use voodoo::reflect::{reflect_spirv, merge_stage_reflections, ShaderReflection};
+
+
+fn words(bytes: &[u8]) -> Vec<u32> {
+    bytes.chunks(4)
+        .map(|b| b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24)
+        .collect()
+}
+
+fn reflect(bytes: &[u8], stage: ShaderStageFlags) -> ShaderReflection {
+    reflect_spirv(&words(bytes), stage).unwrap()
+}
+
+fn vert() -> ShaderReflection {
+    reflect(include_bytes!("fixtures/reflect_vert.spv"), ShaderStageFlags::VERTEX)
+}
+
+fn frag() -> ShaderReflection {
+    reflect(include_bytes!("fixtures/reflect_frag.spv"), ShaderStageFlags::FRAGMENT)
+}
+
+
+#[test]
+fn vertex_uniform_buffer() {
+    let reflection = vert();
+    assert_eq!(reflection.set_indices().collect::<Vec<_>>(), [0]);
+    let bindings = reflection.bindings(0);
+    assert_eq!(bindings.len(), 1);
+    assert_eq!(bindings[0].binding(), 0);
+    assert_eq!(bindings[0].descriptor_type(), DescriptorType::UniformBuffer);
+    assert_eq!(bindings[0].descriptor_count(), 1);
+    assert_eq!(bindings[0].stage_flags(), ShaderStageFlags::VERTEX);
+    assert_eq!(reflection.push_constant_size(), 0);
+}
+
+#[test]
+fn fragment_combined_image_sampler() {
+    let reflection = frag();
+    let bindings = reflection.bindings(0);
+    assert_eq!(bindings.len(), 1);
+    assert_eq!(bindings[0].binding(), 1);
+    assert_eq!(bindings[0].descriptor_type(), DescriptorType::CombinedImageSampler);
+    assert_eq!(bindings[0].descriptor_count(), 1);
+    assert!(reflection.bindings(1).is_empty());
+}
+
+#[test]
+fn merged_vertex_and_fragment() {
+    let merged = merge_stage_reflections(&[vert(), frag()]).unwrap();
+    let bindings = merged.bindings(0);
+    assert_eq!(bindings.iter().map(|b| b.binding()).collect::<Vec<_>>(), [0, 1]);
+    assert_eq!(bindings[0].stage_flags(), ShaderStageFlags::VERTEX);
+    assert_eq!(bindings[1].stage_flags(), ShaderStageFlags::FRAGMENT);
+
+    let merged = merge_stage_reflections(&[vert(), vert()]).unwrap();
+    assert_eq!(merged.bindings(0).len(), 1);
+}
+
+#[test]
+fn merge_conflicting_bindings() {
+    let compute = reflect(include_bytes!("fixtures/reflect_compute.spv"),
+        ShaderStageFlags::COMPUTE);
+    // Set 0, binding 0 is a uniform buffer in one and a storage buffer in
+    // the other.
+    assert!(merge_stage_reflections(&[vert(), compute]).is_err());
+}
+
+#[test]
+fn compute_arrays_storage_and_push_constants() {
+    let reflection = reflect(include_bytes!("fixtures/reflect_compute.spv"),
+        ShaderStageFlags::COMPUTE);
+    assert_eq!(reflection.set_indices().collect::<Vec<_>>(), [0, 1]);
+
+    let set_0 = reflection.bindings(0);
+    assert_eq!(set_0.len(), 1);
+    assert_eq!(set_0[0].descriptor_type(), DescriptorType::StorageBuffer);
+    assert_eq!(set_0[0].descriptor_count(), 1);
+
+    let set_1 = reflection.bindings(1);
+    assert_eq!(set_1.iter().map(|b| (b.binding(), b.descriptor_type(), b.descriptor_count()))
+        .collect::<Vec<_>>(),
+        [(0, DescriptorType::StorageImage, 1), (2, DescriptorType::CombinedImageSampler, 4)]);
+
+    assert_eq!(reflection.push_constant_size(), 80);
+    assert_eq!(reflection.push_constant_stages(), ShaderStageFlags::COMPUTE);
+}
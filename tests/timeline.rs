@@ -0,0 +1,95 @@
+extern crate voodoo;
+
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use voodoo::{FenceTimeline, TimelineValue};
+
+
+/// Far longer than any test should take; reaching it fails the test.
+const LONG_TIMEOUT: u64 = 10_000_000_000;
+
+
+#[test]
+fn host_signal_and_wait() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let timeline = FenceTimeline::new(device, 1);
+    assert_eq!(timeline.value().unwrap(), 1);
+    assert!(!timeline.wait(2, 0).unwrap());
+    timeline.signal(2).unwrap();
+    assert!(timeline.wait(2, 0).unwrap());
+    assert!(timeline.signal(2).is_err());
+}
+
+#[test]
+fn queue_signal_completes() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let queue = device.queue(0).unwrap().clone();
+    let timeline = FenceTimeline::new(device, 0);
+    timeline.queue_signal(&queue, 1).unwrap();
+    timeline.queue_signal(&queue, 2).unwrap();
+    assert!(timeline.queue_signal(&queue, 2).is_err());
+    assert!(timeline.wait(2, u64::max_value()).unwrap());
+    assert_eq!(timeline.value().unwrap(), 2);
+}
+
+#[test]
+fn drop_with_pending_queue_signals() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let queue = device.queue(0).unwrap().clone();
+    for _ in 0..4 {
+        let timeline = FenceTimeline::new(device.clone(), 0);
+        for value in 1..9 {
+            timeline.queue_signal(&queue, value).unwrap();
+        }
+        // Dropping must wait for the pending fences before destroying them.
+        drop(timeline);
+    }
+    device.wait_idle();
+}
+
+#[test]
+fn wait_before_queue_signal_is_scheduled() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let queue = device.queue(0).unwrap().clone();
+    let timeline = Arc::new(FenceTimeline::new(device, 0));
+    let waiter = {
+        let timeline = timeline.clone();
+        thread::spawn(move || timeline.wait(2, LONG_TIMEOUT).unwrap())
+    };
+    thread::sleep(Duration::from_millis(20));
+    timeline.queue_signal(&queue, 1).unwrap();
+    timeline.queue_signal(&queue, 2).unwrap();
+    assert!(waiter.join().unwrap());
+    assert_eq!(timeline.value().unwrap(), 2);
+}
+
+#[test]
+fn queue_signaled_values_waited_in_descending_order() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let queue = device.queue(0).unwrap().clone();
+    let timeline = FenceTimeline::new(device, 0);
+    for value in 1..5 {
+        timeline.queue_signal(&queue, value).unwrap();
+    }
+    for value in (1..5).rev() {
+        assert!(timeline.wait(value, LONG_TIMEOUT).unwrap());
+    }
+    assert_eq!(timeline.value().unwrap(), 4);
+}
+
+#[test]
+fn finite_timeout_elapses_past_pending_queue_signals() {
+    let (device, _) = match common::device() { Some(d) => d, None => return };
+    let queue = device.queue(0).unwrap().clone();
+    let timeline = FenceTimeline::new(device, 0);
+    timeline.queue_signal(&queue, 1).unwrap();
+    let timeout = Duration::from_millis(30);
+    let start = Instant::now();
+    // No signal, pending or otherwise, will reach 5.
+    assert!(!timeline.wait(5, 30_000_000).unwrap());
+    assert!(start.elapsed() >= timeout);
+    assert!(timeline.wait(1, LONG_TIMEOUT).unwrap());
+}